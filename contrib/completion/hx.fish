@@ -15,6 +15,9 @@ complete -c hx -l vsplit -d "Splits all given files vertically"
 complete -c hx -l hsplit -d "Splits all given files horizontally"
 complete -c hx -s c -l config -r -d "Specifies a file to use for config"
 complete -c hx -l log -r -d "Specifies a file to use for logging"
+complete -c hx -l remote -d "Opens files in a running editor"
+complete -c hx -l remote-wait -d "Opens files in a running editor and waits until they are closed"
+complete -c hx -l remote-send -x -d "Runs a command in a running editor"
 complete -c hx -s w -l working-dir -d "Specify initial working directory" -xa "(__fish_complete_directories)"
 
 function __hx_langs_ops
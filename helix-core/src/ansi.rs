@@ -0,0 +1,77 @@
+//! Handling of ANSI escape sequences in the output of external programs.
+
+use std::borrow::Cow;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// Removes ANSI escape sequences (colors, cursor movement, hyperlinks, …) from `text`.
+///
+/// Control Sequences (`ESC [ … final`), Operating System Commands (`ESC ] … BEL` or
+/// `ESC ] … ESC \`), string commands such as DCS and two or three character escapes like
+/// `ESC ( B` are recognized. A truncated sequence at the end of the text is dropped.
+pub fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains(ESC) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != ESC {
+            stripped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameter and intermediate bytes followed by a single final byte.
+            Some('[') => {
+                for ch in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&ch) {
+                        break;
+                    }
+                }
+            }
+            // OSC, DCS, SOS, PM and APC: terminated by ST (`ESC \`) or, for OSC, BEL.
+            Some(']' | 'P' | 'X' | '^' | '_') => {
+                while let Some(ch) = chars.next() {
+                    if ch == BEL {
+                        break;
+                    }
+                    if ch == ESC && chars.next_if_eq(&'\\').is_some() {
+                        break;
+                    }
+                }
+            }
+            // nF escapes such as charset designations: intermediate bytes then a final byte.
+            Some(' '..='/') => {
+                while chars.next_if(|ch| (' '..='/').contains(ch)).is_some() {}
+                chars.next();
+            }
+            // Any other two character escape (`ESC =`, `ESC 7`, `ESC c`, …).
+            Some(_) | None => (),
+        }
+    }
+    Cow::Owned(stripped)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn strips_escape_sequences() {
+        assert!(matches!(strip_ansi("plain text"), Cow::Borrowed(_)));
+        assert_eq!(
+            strip_ansi("\x1b[1;31merror\x1b[0m: oops\x1b[K"),
+            "error: oops"
+        );
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://helix-editor.com\x1b\\link\x1b]8;;\x07 text"),
+            "link text"
+        );
+        assert_eq!(strip_ansi("\x1b(Bcharset\x1b=\x1b>"), "charset");
+        assert_eq!(strip_ansi("\x1bPq#0;2;0;0;0\x1b\\sixel"), "sixel");
+        assert_eq!(strip_ansi("truncated \x1b[38;5"), "truncated ");
+        assert_eq!(strip_ansi("ünïcödé \x1b[4mü\x1b[24m"), "ünïcödé ü");
+    }
+}
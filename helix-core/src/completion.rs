@@ -1,4 +1,4 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, sync::Arc};
 
 use crate::{diagnostic::LanguageServerId, Transaction};
 
@@ -9,6 +9,9 @@ pub struct CompletionItem {
     pub kind: Cow<'static, str>,
     /// Containing Markdown
     pub documentation: Option<String>,
+    /// The snippet which is rendered in place of the text of `transaction` once the item is
+    /// accepted, which is then a preview.
+    pub snippet: Option<Arc<str>>,
     pub provider: CompletionProvider,
 }
 
@@ -17,6 +20,9 @@ pub enum CompletionProvider {
     Lsp(LanguageServerId),
     Path,
     Word,
+    Snippet,
+    /// A provider which isn't built in, identified by a name unique among the providers.
+    Other(&'static str),
 }
 
 impl From<LanguageServerId> for CompletionProvider {
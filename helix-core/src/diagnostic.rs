@@ -68,6 +68,8 @@ pub enum DiagnosticProvider {
         /// not clear the pull diagnostics and vice-versa.
         identifier: Option<Arc<str>>,
     },
+    /// Diagnostics parsed from the output of the `:make` command.
+    Make,
     // Future internal features can go here...
 }
 
@@ -75,7 +77,7 @@ impl DiagnosticProvider {
     pub fn language_server_id(&self) -> Option<LanguageServerId> {
         match self {
             Self::Lsp { server_id, .. } => Some(*server_id),
-            // _ => None,
+            _ => None,
         }
     }
 }
@@ -1,7 +1,10 @@
 use std::ops::Range;
 use std::time::Instant;
 
-use imara_diff::{Algorithm, Diff, Hunk, IndentHeuristic, IndentLevel, InternedInput};
+use imara_diff::{
+    Algorithm, BasicLineDiffPrinter, Diff, Hunk, IndentHeuristic, IndentLevel, InternedInput,
+    UnifiedDiffConfig,
+};
 use ropey::RopeSlice;
 
 use crate::{ChangeSet, Rope, Tendril, Transaction};
@@ -168,6 +171,15 @@ pub fn compare_ropes(before: &Rope, after: &Rope) -> Transaction {
     res
 }
 
+/// Formats the changes from `before` to `after` as the hunks of a unified diff.
+pub fn unified_diff(before: &str, after: &str) -> String {
+    let input = InternedInput::new(before, after);
+    let diff = Diff::compute(Algorithm::Histogram, &input);
+    let printer = BasicLineDiffPrinter(&input.interner);
+    diff.unified_diff(&printer, UnifiedDiffConfig::default(), &input)
+        .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,4 +220,13 @@ fn new_file() {
     fn deleted_file() {
         test_identity("foo", "");
     }
+
+    #[test]
+    fn unified() {
+        assert_eq!(
+            unified_diff("a\nb\nc\n", "a\nd\nc\n"),
+            "@@ -1,3 +1,3 @@\n a\n-b\n+d\n c\n"
+        );
+        assert_eq!(unified_diff("a\n", "a\n"), "");
+    }
 }
@@ -0,0 +1,1257 @@
+//! Digraphs: pairs of characters which stand for a character that is hard to type, like Vim's
+//! `CTRL-K`. They follow RFC 1345, so that `a:` is `ä`, `e'` is `é`, `a*` is `α`, `->` is `→` and
+//! `Eu` is `€`.
+
+/// Looks up the character of a digraph. The characters may also be typed in the reverse order if
+/// that isn't a digraph itself.
+pub fn lookup(first: char, second: char) -> Option<char> {
+    let find = |first: char, second: char| {
+        DIGRAPHS.iter().find_map(|&(digraph, ch, _)| {
+            let mut chars = digraph.chars();
+            (chars.next() == Some(first) && chars.next() == Some(second)).then_some(ch)
+        })
+    };
+    find(first, second).or_else(|| find(second, first))
+}
+
+/// Parses the hexadecimal code point typed after `CTRL-V u`, which must be a valid character.
+pub fn code_point(digits: &str) -> Option<char> {
+    u32::from_str_radix(digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+}
+
+/// The digraphs of RFC 1345 supported by `insert_digraph`, with the names of their characters.
+#[rustfmt::skip]
+pub const DIGRAPHS: &[(&str, char, &str)] = &[
+    ("A!", 'À', "latin capital letter a with grave"),
+    ("A'", 'Á', "latin capital letter a with acute"),
+    ("A>", 'Â', "latin capital letter a with circumflex"),
+    ("A?", 'Ã', "latin capital letter a with tilde"),
+    ("A:", 'Ä', "latin capital letter a with diaeresis"),
+    ("A0", 'Å', "latin capital letter a with ring above"),
+    ("AA", 'Å', "latin capital letter a with ring above"),
+    ("AE", 'Æ', "latin capital letter ae"),
+    ("C,", 'Ç', "latin capital letter c with cedilla"),
+    ("E!", 'È', "latin capital letter e with grave"),
+    ("E'", 'É', "latin capital letter e with acute"),
+    ("E>", 'Ê', "latin capital letter e with circumflex"),
+    ("E:", 'Ë', "latin capital letter e with diaeresis"),
+    ("I!", 'Ì', "latin capital letter i with grave"),
+    ("I'", 'Í', "latin capital letter i with acute"),
+    ("I>", 'Î', "latin capital letter i with circumflex"),
+    ("I:", 'Ï', "latin capital letter i with diaeresis"),
+    ("D-", 'Ð', "latin capital letter eth"),
+    ("N?", 'Ñ', "latin capital letter n with tilde"),
+    ("O!", 'Ò', "latin capital letter o with grave"),
+    ("O'", 'Ó', "latin capital letter o with acute"),
+    ("O>", 'Ô', "latin capital letter o with circumflex"),
+    ("O?", 'Õ', "latin capital letter o with tilde"),
+    ("O:", 'Ö', "latin capital letter o with diaeresis"),
+    ("O/", 'Ø', "latin capital letter o with stroke"),
+    ("U!", 'Ù', "latin capital letter u with grave"),
+    ("U'", 'Ú', "latin capital letter u with acute"),
+    ("U>", 'Û', "latin capital letter u with circumflex"),
+    ("U:", 'Ü', "latin capital letter u with diaeresis"),
+    ("Y'", 'Ý', "latin capital letter y with acute"),
+    ("TH", 'Þ', "latin capital letter thorn"),
+    ("ss", 'ß', "latin small letter sharp s"),
+    ("a!", 'à', "latin small letter a with grave"),
+    ("a'", 'á', "latin small letter a with acute"),
+    ("a>", 'â', "latin small letter a with circumflex"),
+    ("a?", 'ã', "latin small letter a with tilde"),
+    ("a:", 'ä', "latin small letter a with diaeresis"),
+    ("a0", 'å', "latin small letter a with ring above"),
+    ("aa", 'å', "latin small letter a with ring above"),
+    ("ae", 'æ', "latin small letter ae"),
+    ("c,", 'ç', "latin small letter c with cedilla"),
+    ("e!", 'è', "latin small letter e with grave"),
+    ("e'", 'é', "latin small letter e with acute"),
+    ("e>", 'ê', "latin small letter e with circumflex"),
+    ("e:", 'ë', "latin small letter e with diaeresis"),
+    ("i!", 'ì', "latin small letter i with grave"),
+    ("i'", 'í', "latin small letter i with acute"),
+    ("i>", 'î', "latin small letter i with circumflex"),
+    ("i:", 'ï', "latin small letter i with diaeresis"),
+    ("d-", 'ð', "latin small letter eth"),
+    ("n?", 'ñ', "latin small letter n with tilde"),
+    ("o!", 'ò', "latin small letter o with grave"),
+    ("o'", 'ó', "latin small letter o with acute"),
+    ("o>", 'ô', "latin small letter o with circumflex"),
+    ("o?", 'õ', "latin small letter o with tilde"),
+    ("o:", 'ö', "latin small letter o with diaeresis"),
+    ("o/", 'ø', "latin small letter o with stroke"),
+    ("u!", 'ù', "latin small letter u with grave"),
+    ("u'", 'ú', "latin small letter u with acute"),
+    ("u>", 'û', "latin small letter u with circumflex"),
+    ("u:", 'ü', "latin small letter u with diaeresis"),
+    ("y'", 'ý', "latin small letter y with acute"),
+    ("th", 'þ', "latin small letter thorn"),
+    ("y:", 'ÿ', "latin small letter y with diaeresis"),
+    ("A-", 'Ā', "latin capital letter a with macron"),
+    ("a-", 'ā', "latin small letter a with macron"),
+    ("A(", 'Ă', "latin capital letter a with breve"),
+    ("a(", 'ă', "latin small letter a with breve"),
+    ("A;", 'Ą', "latin capital letter a with ogonek"),
+    ("a;", 'ą', "latin small letter a with ogonek"),
+    ("C'", 'Ć', "latin capital letter c with acute"),
+    ("c'", 'ć', "latin small letter c with acute"),
+    ("C>", 'Ĉ', "latin capital letter c with circumflex"),
+    ("c>", 'ĉ', "latin small letter c with circumflex"),
+    ("C.", 'Ċ', "latin capital letter c with dot above"),
+    ("c.", 'ċ', "latin small letter c with dot above"),
+    ("C<", 'Č', "latin capital letter c with caron"),
+    ("c<", 'č', "latin small letter c with caron"),
+    ("D<", 'Ď', "latin capital letter d with caron"),
+    ("d<", 'ď', "latin small letter d with caron"),
+    ("D/", 'Đ', "latin capital letter d with stroke"),
+    ("d/", 'đ', "latin small letter d with stroke"),
+    ("E-", 'Ē', "latin capital letter e with macron"),
+    ("e-", 'ē', "latin small letter e with macron"),
+    ("E(", 'Ĕ', "latin capital letter e with breve"),
+    ("e(", 'ĕ', "latin small letter e with breve"),
+    ("E.", 'Ė', "latin capital letter e with dot above"),
+    ("e.", 'ė', "latin small letter e with dot above"),
+    ("E;", 'Ę', "latin capital letter e with ogonek"),
+    ("e;", 'ę', "latin small letter e with ogonek"),
+    ("E<", 'Ě', "latin capital letter e with caron"),
+    ("e<", 'ě', "latin small letter e with caron"),
+    ("G>", 'Ĝ', "latin capital letter g with circumflex"),
+    ("g>", 'ĝ', "latin small letter g with circumflex"),
+    ("G(", 'Ğ', "latin capital letter g with breve"),
+    ("g(", 'ğ', "latin small letter g with breve"),
+    ("G.", 'Ġ', "latin capital letter g with dot above"),
+    ("g.", 'ġ', "latin small letter g with dot above"),
+    ("G,", 'Ģ', "latin capital letter g with cedilla"),
+    ("g,", 'ģ', "latin small letter g with cedilla"),
+    ("H>", 'Ĥ', "latin capital letter h with circumflex"),
+    ("h>", 'ĥ', "latin small letter h with circumflex"),
+    ("H/", 'Ħ', "latin capital letter h with stroke"),
+    ("h/", 'ħ', "latin small letter h with stroke"),
+    ("I?", 'Ĩ', "latin capital letter i with tilde"),
+    ("i?", 'ĩ', "latin small letter i with tilde"),
+    ("I-", 'Ī', "latin capital letter i with macron"),
+    ("i-", 'ī', "latin small letter i with macron"),
+    ("I(", 'Ĭ', "latin capital letter i with breve"),
+    ("i(", 'ĭ', "latin small letter i with breve"),
+    ("I;", 'Į', "latin capital letter i with ogonek"),
+    ("i;", 'į', "latin small letter i with ogonek"),
+    ("I.", 'İ', "latin capital letter i with dot above"),
+    ("i.", 'ı', "latin small letter dotless i"),
+    ("IJ", 'Ĳ', "latin capital ligature ij"),
+    ("ij", 'ĳ', "latin small ligature ij"),
+    ("J>", 'Ĵ', "latin capital letter j with circumflex"),
+    ("j>", 'ĵ', "latin small letter j with circumflex"),
+    ("K,", 'Ķ', "latin capital letter k with cedilla"),
+    ("k,", 'ķ', "latin small letter k with cedilla"),
+    ("L'", 'Ĺ', "latin capital letter l with acute"),
+    ("l'", 'ĺ', "latin small letter l with acute"),
+    ("L,", 'Ļ', "latin capital letter l with cedilla"),
+    ("l,", 'ļ', "latin small letter l with cedilla"),
+    ("L<", 'Ľ', "latin capital letter l with caron"),
+    ("l<", 'ľ', "latin small letter l with caron"),
+    ("L/", 'Ł', "latin capital letter l with stroke"),
+    ("l/", 'ł', "latin small letter l with stroke"),
+    ("N'", 'Ń', "latin capital letter n with acute"),
+    ("n'", 'ń', "latin small letter n with acute"),
+    ("N,", 'Ņ', "latin capital letter n with cedilla"),
+    ("n,", 'ņ', "latin small letter n with cedilla"),
+    ("N<", 'Ň', "latin capital letter n with caron"),
+    ("n<", 'ň', "latin small letter n with caron"),
+    ("O-", 'Ō', "latin capital letter o with macron"),
+    ("o-", 'ō', "latin small letter o with macron"),
+    ("O(", 'Ŏ', "latin capital letter o with breve"),
+    ("o(", 'ŏ', "latin small letter o with breve"),
+    ("O\"", 'Ő', "latin capital letter o with double acute"),
+    ("o\"", 'ő', "latin small letter o with double acute"),
+    ("OE", 'Œ', "latin capital ligature oe"),
+    ("oe", 'œ', "latin small ligature oe"),
+    ("R'", 'Ŕ', "latin capital letter r with acute"),
+    ("r'", 'ŕ', "latin small letter r with acute"),
+    ("R,", 'Ŗ', "latin capital letter r with cedilla"),
+    ("r,", 'ŗ', "latin small letter r with cedilla"),
+    ("R<", 'Ř', "latin capital letter r with caron"),
+    ("r<", 'ř', "latin small letter r with caron"),
+    ("S'", 'Ś', "latin capital letter s with acute"),
+    ("s'", 'ś', "latin small letter s with acute"),
+    ("S>", 'Ŝ', "latin capital letter s with circumflex"),
+    ("s>", 'ŝ', "latin small letter s with circumflex"),
+    ("S,", 'Ş', "latin capital letter s with cedilla"),
+    ("s,", 'ş', "latin small letter s with cedilla"),
+    ("S<", 'Š', "latin capital letter s with caron"),
+    ("s<", 'š', "latin small letter s with caron"),
+    ("T,", 'Ţ', "latin capital letter t with cedilla"),
+    ("t,", 'ţ', "latin small letter t with cedilla"),
+    ("T<", 'Ť', "latin capital letter t with caron"),
+    ("t<", 'ť', "latin small letter t with caron"),
+    ("U?", 'Ũ', "latin capital letter u with tilde"),
+    ("u?", 'ũ', "latin small letter u with tilde"),
+    ("U-", 'Ū', "latin capital letter u with macron"),
+    ("u-", 'ū', "latin small letter u with macron"),
+    ("U(", 'Ŭ', "latin capital letter u with breve"),
+    ("u(", 'ŭ', "latin small letter u with breve"),
+    ("U0", 'Ů', "latin capital letter u with ring above"),
+    ("u0", 'ů', "latin small letter u with ring above"),
+    ("U\"", 'Ű', "latin capital letter u with double acute"),
+    ("u\"", 'ű', "latin small letter u with double acute"),
+    ("U;", 'Ų', "latin capital letter u with ogonek"),
+    ("u;", 'ų', "latin small letter u with ogonek"),
+    ("W>", 'Ŵ', "latin capital letter w with circumflex"),
+    ("w>", 'ŵ', "latin small letter w with circumflex"),
+    ("Y>", 'Ŷ', "latin capital letter y with circumflex"),
+    ("y>", 'ŷ', "latin small letter y with circumflex"),
+    ("Y:", 'Ÿ', "latin capital letter y with diaeresis"),
+    ("Z'", 'Ź', "latin capital letter z with acute"),
+    ("z'", 'ź', "latin small letter z with acute"),
+    ("Z.", 'Ż', "latin capital letter z with dot above"),
+    ("z.", 'ż', "latin small letter z with dot above"),
+    ("Z<", 'Ž', "latin capital letter z with caron"),
+    ("z<", 'ž', "latin small letter z with caron"),
+    ("A<", 'Ǎ', "latin capital letter a with caron"),
+    ("a<", 'ǎ', "latin small letter a with caron"),
+    ("I<", 'Ǐ', "latin capital letter i with caron"),
+    ("i<", 'ǐ', "latin small letter i with caron"),
+    ("O<", 'Ǒ', "latin capital letter o with caron"),
+    ("o<", 'ǒ', "latin small letter o with caron"),
+    ("U<", 'Ǔ', "latin capital letter u with caron"),
+    ("u<", 'ǔ', "latin small letter u with caron"),
+    ("G<", 'Ǧ', "latin capital letter g with caron"),
+    ("g<", 'ǧ', "latin small letter g with caron"),
+    ("K<", 'Ǩ', "latin capital letter k with caron"),
+    ("k<", 'ǩ', "latin small letter k with caron"),
+    ("O;", 'Ǫ', "latin capital letter o with ogonek"),
+    ("o;", 'ǫ', "latin small letter o with ogonek"),
+    ("j<", 'ǰ', "latin small letter j with caron"),
+    ("G'", 'Ǵ', "latin capital letter g with acute"),
+    ("g'", 'ǵ', "latin small letter g with acute"),
+    ("N!", 'Ǹ', "latin capital letter n with grave"),
+    ("n!", 'ǹ', "latin small letter n with grave"),
+    ("H<", 'Ȟ', "latin capital letter h with caron"),
+    ("h<", 'ȟ', "latin small letter h with caron"),
+    ("A.", 'Ȧ', "latin capital letter a with dot above"),
+    ("a.", 'ȧ', "latin small letter a with dot above"),
+    ("E,", 'Ȩ', "latin capital letter e with cedilla"),
+    ("e,", 'ȩ', "latin small letter e with cedilla"),
+    ("O.", 'Ȯ', "latin capital letter o with dot above"),
+    ("o.", 'ȯ', "latin small letter o with dot above"),
+    ("Y-", 'Ȳ', "latin capital letter y with macron"),
+    ("y-", 'ȳ', "latin small letter y with macron"),
+    ("D.", 'Ḋ', "latin capital letter d with dot above"),
+    ("d.", 'ḋ', "latin small letter d with dot above"),
+    ("D,", 'Ḑ', "latin capital letter d with cedilla"),
+    ("d,", 'ḑ', "latin small letter d with cedilla"),
+    ("G-", 'Ḡ', "latin capital letter g with macron"),
+    ("g-", 'ḡ', "latin small letter g with macron"),
+    ("H.", 'Ḣ', "latin capital letter h with dot above"),
+    ("h.", 'ḣ', "latin small letter h with dot above"),
+    ("H:", 'Ḧ', "latin capital letter h with diaeresis"),
+    ("h:", 'ḧ', "latin small letter h with diaeresis"),
+    ("H,", 'Ḩ', "latin capital letter h with cedilla"),
+    ("h,", 'ḩ', "latin small letter h with cedilla"),
+    ("K'", 'Ḱ', "latin capital letter k with acute"),
+    ("k'", 'ḱ', "latin small letter k with acute"),
+    ("N.", 'Ṅ', "latin capital letter n with dot above"),
+    ("n.", 'ṅ', "latin small letter n with dot above"),
+    ("R.", 'Ṙ', "latin capital letter r with dot above"),
+    ("r.", 'ṙ', "latin small letter r with dot above"),
+    ("S.", 'Ṡ', "latin capital letter s with dot above"),
+    ("s.", 'ṡ', "latin small letter s with dot above"),
+    ("T.", 'Ṫ', "latin capital letter t with dot above"),
+    ("t.", 'ṫ', "latin small letter t with dot above"),
+    ("W!", 'Ẁ', "latin capital letter w with grave"),
+    ("w!", 'ẁ', "latin small letter w with grave"),
+    ("W'", 'Ẃ', "latin capital letter w with acute"),
+    ("w'", 'ẃ', "latin small letter w with acute"),
+    ("W:", 'Ẅ', "latin capital letter w with diaeresis"),
+    ("w:", 'ẅ', "latin small letter w with diaeresis"),
+    ("W.", 'Ẇ', "latin capital letter w with dot above"),
+    ("w.", 'ẇ', "latin small letter w with dot above"),
+    ("Y.", 'Ẏ', "latin capital letter y with dot above"),
+    ("y.", 'ẏ', "latin small letter y with dot above"),
+    ("Z>", 'Ẑ', "latin capital letter z with circumflex"),
+    ("z>", 'ẑ', "latin small letter z with circumflex"),
+    ("t:", 'ẗ', "latin small letter t with diaeresis"),
+    ("w0", 'ẘ', "latin small letter w with ring above"),
+    ("y0", 'ẙ', "latin small letter y with ring above"),
+    ("E?", 'Ẽ', "latin capital letter e with tilde"),
+    ("e?", 'ẽ', "latin small letter e with tilde"),
+    ("Y!", 'Ỳ', "latin capital letter y with grave"),
+    ("y!", 'ỳ', "latin small letter y with grave"),
+    ("Y?", 'Ỹ', "latin capital letter y with tilde"),
+    ("y?", 'ỹ', "latin small letter y with tilde"),
+    ("A*", 'Α', "greek capital letter alpha"),
+    ("B*", 'Β', "greek capital letter beta"),
+    ("G*", 'Γ', "greek capital letter gamma"),
+    ("D*", 'Δ', "greek capital letter delta"),
+    ("E*", 'Ε', "greek capital letter epsilon"),
+    ("Z*", 'Ζ', "greek capital letter zeta"),
+    ("Y*", 'Η', "greek capital letter eta"),
+    ("H*", 'Θ', "greek capital letter theta"),
+    ("I*", 'Ι', "greek capital letter iota"),
+    ("K*", 'Κ', "greek capital letter kappa"),
+    ("L*", 'Λ', "greek capital letter lamda"),
+    ("M*", 'Μ', "greek capital letter mu"),
+    ("N*", 'Ν', "greek capital letter nu"),
+    ("C*", 'Ξ', "greek capital letter xi"),
+    ("O*", 'Ο', "greek capital letter omicron"),
+    ("P*", 'Π', "greek capital letter pi"),
+    ("R*", 'Ρ', "greek capital letter rho"),
+    ("S*", 'Σ', "greek capital letter sigma"),
+    ("T*", 'Τ', "greek capital letter tau"),
+    ("U*", 'Υ', "greek capital letter upsilon"),
+    ("F*", 'Φ', "greek capital letter phi"),
+    ("X*", 'Χ', "greek capital letter chi"),
+    ("Q*", 'Ψ', "greek capital letter psi"),
+    ("W*", 'Ω', "greek capital letter omega"),
+    ("a*", 'α', "greek small letter alpha"),
+    ("b*", 'β', "greek small letter beta"),
+    ("g*", 'γ', "greek small letter gamma"),
+    ("d*", 'δ', "greek small letter delta"),
+    ("e*", 'ε', "greek small letter epsilon"),
+    ("z*", 'ζ', "greek small letter zeta"),
+    ("y*", 'η', "greek small letter eta"),
+    ("h*", 'θ', "greek small letter theta"),
+    ("i*", 'ι', "greek small letter iota"),
+    ("k*", 'κ', "greek small letter kappa"),
+    ("l*", 'λ', "greek small letter lamda"),
+    ("m*", 'μ', "greek small letter mu"),
+    ("n*", 'ν', "greek small letter nu"),
+    ("c*", 'ξ', "greek small letter xi"),
+    ("o*", 'ο', "greek small letter omicron"),
+    ("p*", 'π', "greek small letter pi"),
+    ("r*", 'ρ', "greek small letter rho"),
+    ("s*", 'σ', "greek small letter sigma"),
+    ("t*", 'τ', "greek small letter tau"),
+    ("u*", 'υ', "greek small letter upsilon"),
+    ("f*", 'φ', "greek small letter phi"),
+    ("x*", 'χ', "greek small letter chi"),
+    ("q*", 'ψ', "greek small letter psi"),
+    ("w*", 'ω', "greek small letter omega"),
+    ("*s", 'ς', "greek small letter final sigma"),
+    ("A=", 'А', "cyrillic capital letter a"),
+    ("B=", 'Б', "cyrillic capital letter be"),
+    ("V=", 'В', "cyrillic capital letter ve"),
+    ("G=", 'Г', "cyrillic capital letter ghe"),
+    ("D=", 'Д', "cyrillic capital letter de"),
+    ("E=", 'Е', "cyrillic capital letter ie"),
+    ("IO", 'Ё', "cyrillic capital letter io"),
+    ("Z%", 'Ж', "cyrillic capital letter zhe"),
+    ("Z=", 'З', "cyrillic capital letter ze"),
+    ("I=", 'И', "cyrillic capital letter i"),
+    ("J=", 'Й', "cyrillic capital letter short i"),
+    ("K=", 'К', "cyrillic capital letter ka"),
+    ("L=", 'Л', "cyrillic capital letter el"),
+    ("M=", 'М', "cyrillic capital letter em"),
+    ("N=", 'Н', "cyrillic capital letter en"),
+    ("O=", 'О', "cyrillic capital letter o"),
+    ("P=", 'П', "cyrillic capital letter pe"),
+    ("R=", 'Р', "cyrillic capital letter er"),
+    ("S=", 'С', "cyrillic capital letter es"),
+    ("T=", 'Т', "cyrillic capital letter te"),
+    ("U=", 'У', "cyrillic capital letter u"),
+    ("F=", 'Ф', "cyrillic capital letter ef"),
+    ("H=", 'Х', "cyrillic capital letter ha"),
+    ("C=", 'Ц', "cyrillic capital letter tse"),
+    ("C%", 'Ч', "cyrillic capital letter che"),
+    ("S%", 'Ш', "cyrillic capital letter sha"),
+    ("Sc", 'Щ', "cyrillic capital letter shcha"),
+    ("=\"", 'Ъ', "cyrillic capital letter hard sign"),
+    ("Y=", 'Ы', "cyrillic capital letter yeru"),
+    ("%\"", 'Ь', "cyrillic capital letter soft sign"),
+    ("JE", 'Э', "cyrillic capital letter e"),
+    ("JU", 'Ю', "cyrillic capital letter yu"),
+    ("JA", 'Я', "cyrillic capital letter ya"),
+    ("a=", 'а', "cyrillic small letter a"),
+    ("b=", 'б', "cyrillic small letter be"),
+    ("v=", 'в', "cyrillic small letter ve"),
+    ("g=", 'г', "cyrillic small letter ghe"),
+    ("d=", 'д', "cyrillic small letter de"),
+    ("e=", 'е', "cyrillic small letter ie"),
+    ("io", 'ё', "cyrillic small letter io"),
+    ("z%", 'ж', "cyrillic small letter zhe"),
+    ("z=", 'з', "cyrillic small letter ze"),
+    ("i=", 'и', "cyrillic small letter i"),
+    ("j=", 'й', "cyrillic small letter short i"),
+    ("k=", 'к', "cyrillic small letter ka"),
+    ("l=", 'л', "cyrillic small letter el"),
+    ("m=", 'м', "cyrillic small letter em"),
+    ("n=", 'н', "cyrillic small letter en"),
+    ("o=", 'о', "cyrillic small letter o"),
+    ("p=", 'п', "cyrillic small letter pe"),
+    ("r=", 'р', "cyrillic small letter er"),
+    ("s=", 'с', "cyrillic small letter es"),
+    ("t=", 'т', "cyrillic small letter te"),
+    ("u=", 'у', "cyrillic small letter u"),
+    ("f=", 'ф', "cyrillic small letter ef"),
+    ("h=", 'х', "cyrillic small letter ha"),
+    ("c=", 'ц', "cyrillic small letter tse"),
+    ("c%", 'ч', "cyrillic small letter che"),
+    ("s%", 'ш', "cyrillic small letter sha"),
+    ("sc", 'щ', "cyrillic small letter shcha"),
+    ("y=", 'ы', "cyrillic small letter yeru"),
+    ("je", 'э', "cyrillic small letter e"),
+    ("ju", 'ю', "cyrillic small letter yu"),
+    ("ja", 'я', "cyrillic small letter ya"),
+    ("='", 'ъ', "cyrillic small letter hard sign"),
+    ("%'", 'ь', "cyrillic small letter soft sign"),
+    ("NS", '\u{a0}', "no-break space"),
+    ("!I", '¡', "inverted exclamation mark"),
+    ("Ct", '¢', "cent sign"),
+    ("Pd", '£', "pound sign"),
+    ("Cu", '¤', "currency sign"),
+    ("Ye", '¥', "yen sign"),
+    ("BB", '¦', "broken bar"),
+    ("SE", '§', "section sign"),
+    ("':", '¨', "diaeresis"),
+    ("Co", '©', "copyright sign"),
+    ("-a", 'ª', "feminine ordinal indicator"),
+    ("<<", '«', "left-pointing double angle quotation mark"),
+    ("NO", '¬', "not sign"),
+    ("--", '\u{ad}', "soft hyphen"),
+    ("Rg", '®', "registered sign"),
+    ("'m", '¯', "macron"),
+    ("DG", '°', "degree sign"),
+    ("+-", '±', "plus-minus sign"),
+    ("2S", '²', "superscript two"),
+    ("3S", '³', "superscript three"),
+    ("''", '´', "acute accent"),
+    ("My", 'µ', "micro sign"),
+    ("PI", '¶', "pilcrow sign"),
+    (".M", '·', "middle dot"),
+    ("',", '¸', "cedilla"),
+    ("1S", '¹', "superscript one"),
+    ("-o", 'º', "masculine ordinal indicator"),
+    (">>", '»', "right-pointing double angle quotation mark"),
+    ("14", '¼', "vulgar fraction one quarter"),
+    ("12", '½', "vulgar fraction one half"),
+    ("34", '¾', "vulgar fraction three quarters"),
+    ("?I", '¿', "inverted question mark"),
+    ("*X", '×', "multiplication sign"),
+    ("-:", '÷', "division sign"),
+    ("-N", '–', "en dash"),
+    ("-M", '—', "em dash"),
+    ("-3", '―', "horizontal bar"),
+    ("!2", '‖', "double vertical line"),
+    ("'6", '‘', "left single quotation mark"),
+    ("'9", '’', "right single quotation mark"),
+    (".9", '‚', "single low-9 quotation mark"),
+    ("9'", '‛', "single high-reversed-9 quotation mark"),
+    ("\"6", '“', "left double quotation mark"),
+    ("\"9", '”', "right double quotation mark"),
+    (":9", '„', "double low-9 quotation mark"),
+    ("9\"", '‟', "double high-reversed-9 quotation mark"),
+    ("/-", '†', "dagger"),
+    ("/=", '‡', "double dagger"),
+    ("..", '‥', "two dot leader"),
+    (".3", '…', "horizontal ellipsis"),
+    ("%0", '‰', "per mille sign"),
+    ("1'", '′', "prime"),
+    ("2'", '″', "double prime"),
+    ("3'", '‴', "triple prime"),
+    ("<1", '‹', "single left-pointing angle quotation mark"),
+    (">1", '›', "single right-pointing angle quotation mark"),
+    ("0S", '⁰', "superscript zero"),
+    ("4S", '⁴', "superscript four"),
+    ("5S", '⁵', "superscript five"),
+    ("6S", '⁶', "superscript six"),
+    ("7S", '⁷', "superscript seven"),
+    ("8S", '⁸', "superscript eight"),
+    ("9S", '⁹', "superscript nine"),
+    ("0s", '₀', "subscript zero"),
+    ("1s", '₁', "subscript one"),
+    ("2s", '₂', "subscript two"),
+    ("3s", '₃', "subscript three"),
+    ("4s", '₄', "subscript four"),
+    ("5s", '₅', "subscript five"),
+    ("6s", '₆', "subscript six"),
+    ("7s", '₇', "subscript seven"),
+    ("8s", '₈', "subscript eight"),
+    ("9s", '₉', "subscript nine"),
+    ("Eu", '€', "euro sign"),
+    ("=R", '₽', "ruble sign"),
+    ("oC", '℃', "degree celsius"),
+    ("co", '℅', "care of"),
+    ("oF", '℉', "degree fahrenheit"),
+    ("N0", '№', "numero sign"),
+    ("PO", '℗', "sound recording copyright"),
+    ("Rx", '℞', "prescription take"),
+    ("SM", '℠', "service mark"),
+    ("TM", '™', "trade mark sign"),
+    ("13", '⅓', "vulgar fraction one third"),
+    ("23", '⅔', "vulgar fraction two thirds"),
+    ("15", '⅕', "vulgar fraction one fifth"),
+    ("18", '⅛', "vulgar fraction one eighth"),
+    ("<-", '←', "leftwards arrow"),
+    ("-!", '↑', "upwards arrow"),
+    ("->", '→', "rightwards arrow"),
+    ("-v", '↓', "downwards arrow"),
+    ("<>", '↔', "left right arrow"),
+    ("UD", '↕', "up down arrow"),
+    ("<=", '⇐', "leftwards double arrow"),
+    ("=>", '⇒', "rightwards double arrow"),
+    ("==", '⇔', "left right double arrow"),
+    ("FA", '∀', "for all"),
+    ("dP", '∂', "partial differential"),
+    ("TE", '∃', "there exists"),
+    ("/0", '∅', "empty set"),
+    ("DE", '∆', "increment"),
+    ("NB", '∇', "nabla"),
+    ("(-", '∈', "element of"),
+    ("-)", '∋', "contains as member"),
+    ("*P", '∏', "n-ary product"),
+    ("+Z", '∑', "n-ary summation"),
+    ("-2", '−', "minus sign"),
+    ("-+", '∓', "minus-or-plus sign"),
+    ("*-", '∗', "asterisk operator"),
+    ("Ob", '∘', "ring operator"),
+    ("Sb", '∙', "bullet operator"),
+    ("RT", '√', "square root"),
+    ("0(", '∝', "proportional to"),
+    ("00", '∞', "infinity"),
+    ("-L", '∟', "right angle"),
+    ("-V", '∠', "angle"),
+    ("PP", '∥', "parallel to"),
+    ("AN", '∧', "logical and"),
+    ("OR", '∨', "logical or"),
+    ("(U", '∩', "intersection"),
+    (")U", '∪', "union"),
+    ("In", '∫', "integral"),
+    ("DI", '∬', "double integral"),
+    ("Io", '∮', "contour integral"),
+    (".:", '∴', "therefore"),
+    (":.", '∵', "because"),
+    (":R", '∶', "ratio"),
+    ("::", '∷', "proportion"),
+    ("?1", '∼', "tilde operator"),
+    ("CG", '∾', "inverted lazy s"),
+    ("?-", '≃', "asymptotically equal to"),
+    ("?=", '≅', "approximately equal to"),
+    ("?2", '≈', "almost equal to"),
+    ("=?", '≌', "all equal to"),
+    ("HI", '≓', "image of or approximately equal to"),
+    ("!=", '≠', "not equal to"),
+    ("=3", '≡', "identical to"),
+    ("=<", '≤', "less-than or equal to"),
+    (">=", '≥', "greater-than or equal to"),
+    ("<*", '≪', "much less-than"),
+    ("*>", '≫', "much greater-than"),
+    ("!<", '≮', "not less-than"),
+    ("!>", '≯', "not greater-than"),
+    ("(C", '⊂', "subset of"),
+    (")C", '⊃', "superset of"),
+    ("(_", '⊆', "subset of or equal to"),
+    (")_", '⊇', "superset of or equal to"),
+    ("0.", '⊙', "circled dot operator"),
+    ("02", '⊚', "circled ring operator"),
+    ("-T", '⊥', "up tack"),
+    (".P", '⋅', "dot operator"),
+    (":3", '⋮', "vertical ellipsis"),
+    ("Eh", '⌂', "house"),
+    ("<7", '⌈', "left ceiling"),
+    (">7", '⌉', "right ceiling"),
+    ("7<", '⌊', "left floor"),
+    ("7>", '⌋', "right floor"),
+    ("NI", '⌐', "reversed not sign"),
+    ("(A", '⌒', "arc"),
+    ("TR", '⌕', "telephone recorder"),
+    ("Iu", '⌠', "top half integral"),
+    ("Il", '⌡', "bottom half integral"),
+    ("</", '〈', "left angle bracket"),
+    ("/>", '〉', "right angle bracket"),
+    ("Vs", '␣', "open box"),
+    ("hh", '─', "box drawings light horizontal"),
+    ("HH", '━', "box drawings heavy horizontal"),
+    ("vv", '│', "box drawings light vertical"),
+    ("VV", '┃', "box drawings heavy vertical"),
+    ("dr", '┌', "box drawings light down and right"),
+    ("dl", '┐', "box drawings light down and left"),
+    ("ur", '└', "box drawings light up and right"),
+    ("ul", '┘', "box drawings light up and left"),
+    ("vr", '├', "box drawings light vertical and right"),
+    ("vl", '┤', "box drawings light vertical and left"),
+    ("dh", '┬', "box drawings light down and horizontal"),
+    ("uh", '┴', "box drawings light up and horizontal"),
+    ("vh", '┼', "box drawings light vertical and horizontal"),
+    ("TB", '▀', "upper half block"),
+    ("LB", '▄', "lower half block"),
+    ("FB", '█', "full block"),
+    ("lB", '▌', "left half block"),
+    ("RB", '▐', "right half block"),
+    (".S", '░', "light shade"),
+    (":S", '▒', "medium shade"),
+    ("?S", '▓', "dark shade"),
+    ("fS", '■', "black square"),
+    ("OS", '□', "white square"),
+    ("Db", '◆', "black diamond"),
+    ("Dw", '◇', "white diamond"),
+    ("LZ", '◊', "lozenge"),
+    ("0m", '○', "white circle"),
+    ("0o", '◎', "bullseye"),
+    ("0M", '●', "black circle"),
+    ("*2", '★', "black star"),
+    ("*1", '☆', "white star"),
+    ("<H", '☜', "white left pointing index"),
+    (">H", '☞', "white right pointing index"),
+    ("0u", '☺', "white smiling face"),
+    ("0U", '☻', "black smiling face"),
+    ("SU", '☼', "white sun with rays"),
+    ("Fm", '♀', "female sign"),
+    ("Ml", '♂', "male sign"),
+    ("cS", '♠', "black spade suit"),
+    ("cH", '♡', "white heart suit"),
+    ("cD", '♢', "white diamond suit"),
+    ("cC", '♣', "black club suit"),
+    ("Md", '♩', "quarter note"),
+    ("M8", '♪', "eighth note"),
+    ("M2", '♫', "beamed eighth notes"),
+    ("Mb", '♭', "music flat sign"),
+    ("Mx", '♮', "music natural sign"),
+    ("MX", '♯', "music sharp sign"),
+    ("OK", '✓', "check mark"),
+    ("XX", '✗', "ballot x"),
+    ("-X", '✠', "maltese cross"),
+];
+
+/// Emoji and symbols without a digraph, which are only offered by the symbol picker.
+#[rustfmt::skip]
+pub const SYMBOLS: &[(char, &str)] = &[
+    ('😀', "grinning face"),
+    ('😁', "grinning face with smiling eyes"),
+    ('😂', "face with tears of joy"),
+    ('😃', "smiling face with open mouth"),
+    ('😄', "smiling face with open mouth and smiling eyes"),
+    ('😅', "smiling face with open mouth and cold sweat"),
+    ('😆', "smiling face with open mouth and tightly-closed eyes"),
+    ('😇', "smiling face with halo"),
+    ('😈', "smiling face with horns"),
+    ('😉', "winking face"),
+    ('😊', "smiling face with smiling eyes"),
+    ('😋', "face savouring delicious food"),
+    ('😌', "relieved face"),
+    ('😍', "smiling face with heart-shaped eyes"),
+    ('😎', "smiling face with sunglasses"),
+    ('😏', "smirking face"),
+    ('😐', "neutral face"),
+    ('😑', "expressionless face"),
+    ('😒', "unamused face"),
+    ('😓', "face with cold sweat"),
+    ('😔', "pensive face"),
+    ('😕', "confused face"),
+    ('😖', "confounded face"),
+    ('😗', "kissing face"),
+    ('😘', "face throwing a kiss"),
+    ('😙', "kissing face with smiling eyes"),
+    ('😚', "kissing face with closed eyes"),
+    ('😛', "face with stuck-out tongue"),
+    ('😜', "face with stuck-out tongue and winking eye"),
+    ('😝', "face with stuck-out tongue and tightly-closed eyes"),
+    ('😞', "disappointed face"),
+    ('😟', "worried face"),
+    ('😠', "angry face"),
+    ('😡', "pouting face"),
+    ('😢', "crying face"),
+    ('😣', "persevering face"),
+    ('😤', "face with look of triumph"),
+    ('😥', "disappointed but relieved face"),
+    ('😦', "frowning face with open mouth"),
+    ('😧', "anguished face"),
+    ('😨', "fearful face"),
+    ('😩', "weary face"),
+    ('😪', "sleepy face"),
+    ('😫', "tired face"),
+    ('😬', "grimacing face"),
+    ('😭', "loudly crying face"),
+    ('😮', "face with open mouth"),
+    ('😯', "hushed face"),
+    ('😰', "face with open mouth and cold sweat"),
+    ('😱', "face screaming in fear"),
+    ('😲', "astonished face"),
+    ('😳', "flushed face"),
+    ('😴', "sleeping face"),
+    ('😵', "dizzy face"),
+    ('😶', "face without mouth"),
+    ('😷', "face with medical mask"),
+    ('😸', "grinning cat face with smiling eyes"),
+    ('😹', "cat face with tears of joy"),
+    ('😺', "smiling cat face with open mouth"),
+    ('😻', "smiling cat face with heart-shaped eyes"),
+    ('😼', "cat face with wry smile"),
+    ('😽', "kissing cat face with closed eyes"),
+    ('😾', "pouting cat face"),
+    ('😿', "crying cat face"),
+    ('🙀', "weary cat face"),
+    ('🙁', "slightly frowning face"),
+    ('🙂', "slightly smiling face"),
+    ('🙃', "upside-down face"),
+    ('🙄', "face with rolling eyes"),
+    ('🙅', "face with no good gesture"),
+    ('🙆', "face with ok gesture"),
+    ('🙇', "person bowing deeply"),
+    ('🙈', "see-no-evil monkey"),
+    ('🙉', "hear-no-evil monkey"),
+    ('🙊', "speak-no-evil monkey"),
+    ('🙋', "happy person raising one hand"),
+    ('🙌', "person raising both hands in celebration"),
+    ('🙍', "person frowning"),
+    ('🙎', "person with pouting face"),
+    ('🙏', "person with folded hands"),
+    ('🤐', "zipper-mouth face"),
+    ('🤑', "money-mouth face"),
+    ('🤒', "face with thermometer"),
+    ('🤓', "nerd face"),
+    ('🤔', "thinking face"),
+    ('🤕', "face with head-bandage"),
+    ('🤖', "robot face"),
+    ('🤗', "hugging face"),
+    ('🤘', "sign of the horns"),
+    ('🤙', "call me hand"),
+    ('🤚', "raised back of hand"),
+    ('🤛', "left-facing fist"),
+    ('🤜', "right-facing fist"),
+    ('🤝', "handshake"),
+    ('🤞', "hand with index and middle fingers crossed"),
+    ('🤟', "i love you hand sign"),
+    ('🤠', "face with cowboy hat"),
+    ('🤡', "clown face"),
+    ('🤢', "nauseated face"),
+    ('🤣', "rolling on the floor laughing"),
+    ('🤤', "drooling face"),
+    ('🤥', "lying face"),
+    ('🤦', "face palm"),
+    ('🤧', "sneezing face"),
+    ('🤨', "face with one eyebrow raised"),
+    ('🤩', "grinning face with star eyes"),
+    ('🤪', "grinning face with one large and one small eye"),
+    ('🤫', "face with finger covering closed lips"),
+    ('🤬', "serious face with symbols covering mouth"),
+    ('🤭', "smiling face with smiling eyes and hand covering mouth"),
+    ('🤮', "face with open mouth vomiting"),
+    ('🤯', "shocked face with exploding head"),
+    ('👀', "eyes"),
+    ('👁', "eye"),
+    ('👂', "ear"),
+    ('👃', "nose"),
+    ('👄', "mouth"),
+    ('👅', "tongue"),
+    ('👆', "white up pointing backhand index"),
+    ('👇', "white down pointing backhand index"),
+    ('👈', "white left pointing backhand index"),
+    ('👉', "white right pointing backhand index"),
+    ('👊', "fisted hand sign"),
+    ('👋', "waving hand sign"),
+    ('👌', "ok hand sign"),
+    ('👍', "thumbs up sign"),
+    ('👎', "thumbs down sign"),
+    ('👏', "clapping hands sign"),
+    ('👐', "open hands sign"),
+    ('👑', "crown"),
+    ('👒', "womans hat"),
+    ('👓', "eyeglasses"),
+    ('👔', "necktie"),
+    ('👕', "t-shirt"),
+    ('👖', "jeans"),
+    ('👗', "dress"),
+    ('👘', "kimono"),
+    ('👙', "bikini"),
+    ('👚', "womans clothes"),
+    ('👛', "purse"),
+    ('👜', "handbag"),
+    ('👝', "pouch"),
+    ('👞', "mans shoe"),
+    ('👟', "athletic shoe"),
+    ('👠', "high-heeled shoe"),
+    ('👡', "womans sandal"),
+    ('👢', "womans boots"),
+    ('👣', "footprints"),
+    ('👤', "bust in silhouette"),
+    ('👥', "busts in silhouette"),
+    ('👦', "boy"),
+    ('👧', "girl"),
+    ('👨', "man"),
+    ('👩', "woman"),
+    ('👪', "family"),
+    ('👫', "man and woman holding hands"),
+    ('👬', "two men holding hands"),
+    ('👭', "two women holding hands"),
+    ('👮', "police officer"),
+    ('👯', "woman with bunny ears"),
+    ('👰', "bride with veil"),
+    ('👱', "person with blond hair"),
+    ('👲', "man with gua pi mao"),
+    ('👳', "man with turban"),
+    ('👴', "older man"),
+    ('👵', "older woman"),
+    ('👶', "baby"),
+    ('👷', "construction worker"),
+    ('👸', "princess"),
+    ('👹', "japanese ogre"),
+    ('👺', "japanese goblin"),
+    ('👻', "ghost"),
+    ('👼', "baby angel"),
+    ('👽', "extraterrestrial alien"),
+    ('👾', "alien monster"),
+    ('👿', "imp"),
+    ('💀', "skull"),
+    ('💁', "information desk person"),
+    ('💂', "guardsman"),
+    ('💃', "dancer"),
+    ('💄', "lipstick"),
+    ('💅', "nail polish"),
+    ('💆', "face massage"),
+    ('💇', "haircut"),
+    ('💈', "barber pole"),
+    ('💉', "syringe"),
+    ('💊', "pill"),
+    ('💋', "kiss mark"),
+    ('💌', "love letter"),
+    ('💍', "ring"),
+    ('💎', "gem stone"),
+    ('💏', "kiss"),
+    ('💐', "bouquet"),
+    ('💑', "couple with heart"),
+    ('💒', "wedding"),
+    ('💓', "beating heart"),
+    ('💔', "broken heart"),
+    ('💕', "two hearts"),
+    ('💖', "sparkling heart"),
+    ('💗', "growing heart"),
+    ('💘', "heart with arrow"),
+    ('💙', "blue heart"),
+    ('💚', "green heart"),
+    ('💛', "yellow heart"),
+    ('💜', "purple heart"),
+    ('💝', "heart with ribbon"),
+    ('💞', "revolving hearts"),
+    ('💟', "heart decoration"),
+    ('💠', "diamond shape with a dot inside"),
+    ('💡', "electric light bulb"),
+    ('💢', "anger symbol"),
+    ('💣', "bomb"),
+    ('💤', "sleeping symbol"),
+    ('💥', "collision symbol"),
+    ('💦', "splashing sweat symbol"),
+    ('💧', "droplet"),
+    ('💨', "dash symbol"),
+    ('💩', "pile of poo"),
+    ('💪', "flexed biceps"),
+    ('💫', "dizzy symbol"),
+    ('💬', "speech balloon"),
+    ('💭', "thought balloon"),
+    ('💮', "white flower"),
+    ('💯', "hundred points symbol"),
+    ('💰', "money bag"),
+    ('💱', "currency exchange"),
+    ('💲', "heavy dollar sign"),
+    ('💳', "credit card"),
+    ('💴', "banknote with yen sign"),
+    ('💵', "banknote with dollar sign"),
+    ('💶', "banknote with euro sign"),
+    ('💷', "banknote with pound sign"),
+    ('💸', "money with wings"),
+    ('💹', "chart with upwards trend and yen sign"),
+    ('💺', "seat"),
+    ('💻', "personal computer"),
+    ('💼', "briefcase"),
+    ('💽', "minidisc"),
+    ('💾', "floppy disk"),
+    ('💿', "optical disc"),
+    ('📀', "dvd"),
+    ('📁', "file folder"),
+    ('📂', "open file folder"),
+    ('📃', "page with curl"),
+    ('📄', "page facing up"),
+    ('📅', "calendar"),
+    ('📆', "tear-off calendar"),
+    ('📇', "card index"),
+    ('📈', "chart with upwards trend"),
+    ('📉', "chart with downwards trend"),
+    ('📊', "bar chart"),
+    ('📋', "clipboard"),
+    ('📌', "pushpin"),
+    ('📍', "round pushpin"),
+    ('📎', "paperclip"),
+    ('📏', "straight ruler"),
+    ('📐', "triangular ruler"),
+    ('📑', "bookmark tabs"),
+    ('📒', "ledger"),
+    ('📓', "notebook"),
+    ('📔', "notebook with decorative cover"),
+    ('📕', "closed book"),
+    ('📖', "open book"),
+    ('📗', "green book"),
+    ('📘', "blue book"),
+    ('📙', "orange book"),
+    ('📚', "books"),
+    ('📛', "name badge"),
+    ('📜', "scroll"),
+    ('📝', "memo"),
+    ('📞', "telephone receiver"),
+    ('📟', "pager"),
+    ('📠', "fax machine"),
+    ('📡', "satellite antenna"),
+    ('📢', "public address loudspeaker"),
+    ('📣', "cheering megaphone"),
+    ('📤', "outbox tray"),
+    ('📥', "inbox tray"),
+    ('📦', "package"),
+    ('📧', "e-mail symbol"),
+    ('📨', "incoming envelope"),
+    ('📩', "envelope with downwards arrow above"),
+    ('📪', "closed mailbox with lowered flag"),
+    ('📫', "closed mailbox with raised flag"),
+    ('📬', "open mailbox with raised flag"),
+    ('📭', "open mailbox with lowered flag"),
+    ('📮', "postbox"),
+    ('📯', "postal horn"),
+    ('📰', "newspaper"),
+    ('📱', "mobile phone"),
+    ('📲', "mobile phone with rightwards arrow at left"),
+    ('📳', "vibration mode"),
+    ('📴', "mobile phone off"),
+    ('📵', "no mobile phones"),
+    ('📶', "antenna with bars"),
+    ('📷', "camera"),
+    ('📸', "camera with flash"),
+    ('📹', "video camera"),
+    ('📺', "television"),
+    ('📻', "radio"),
+    ('📼', "videocassette"),
+    ('📽', "film projector"),
+    ('📾', "portable stereo"),
+    ('📿', "prayer beads"),
+    ('🚀', "rocket"),
+    ('🚁', "helicopter"),
+    ('🚂', "steam locomotive"),
+    ('🚃', "railway car"),
+    ('🚄', "high-speed train"),
+    ('🚅', "high-speed train with bullet nose"),
+    ('🚆', "train"),
+    ('🚇', "metro"),
+    ('🚈', "light rail"),
+    ('🚉', "station"),
+    ('🚊', "tram"),
+    ('🚋', "tram car"),
+    ('🚌', "bus"),
+    ('🚍', "oncoming bus"),
+    ('🚎', "trolleybus"),
+    ('🚏', "bus stop"),
+    ('🚐', "minibus"),
+    ('🚑', "ambulance"),
+    ('🚒', "fire engine"),
+    ('🚓', "police car"),
+    ('🚔', "oncoming police car"),
+    ('🚕', "taxi"),
+    ('🚖', "oncoming taxi"),
+    ('🚗', "automobile"),
+    ('🚘', "oncoming automobile"),
+    ('🚙', "recreational vehicle"),
+    ('🚚', "delivery truck"),
+    ('🚛', "articulated lorry"),
+    ('🚜', "tractor"),
+    ('🚝', "monorail"),
+    ('🚞', "mountain railway"),
+    ('🚟', "suspension railway"),
+    ('🚠', "mountain cableway"),
+    ('🚡', "aerial tramway"),
+    ('🚢', "ship"),
+    ('🚣', "rowboat"),
+    ('🚤', "speedboat"),
+    ('🚥', "horizontal traffic light"),
+    ('🚦', "vertical traffic light"),
+    ('🚧', "construction sign"),
+    ('🚨', "police cars revolving light"),
+    ('🚩', "triangular flag on post"),
+    ('🚪', "door"),
+    ('🚫', "no entry sign"),
+    ('🚬', "smoking symbol"),
+    ('🚭', "no smoking symbol"),
+    ('🚮', "put litter in its place symbol"),
+    ('🚯', "do not litter symbol"),
+    ('🚰', "potable water symbol"),
+    ('🚱', "non-potable water symbol"),
+    ('🚲', "bicycle"),
+    ('🚳', "no bicycles"),
+    ('🚴', "bicyclist"),
+    ('🚵', "mountain bicyclist"),
+    ('🚶', "pedestrian"),
+    ('🚷', "no pedestrians"),
+    ('🚸', "children crossing"),
+    ('🚹', "mens symbol"),
+    ('🚺', "womens symbol"),
+    ('🚻', "restroom"),
+    ('🚼', "baby symbol"),
+    ('🚽', "toilet"),
+    ('🚾', "water closet"),
+    ('🚿', "shower"),
+    ('🛀', "bath"),
+    ('🛁', "bathtub"),
+    ('🛂', "passport control"),
+    ('🛃', "customs"),
+    ('🛄', "baggage claim"),
+    ('🛅', "left luggage"),
+    ('☀', "black sun with rays"),
+    ('☁', "cloud"),
+    ('☂', "umbrella"),
+    ('☃', "snowman"),
+    ('☄', "comet"),
+    ('☇', "lightning"),
+    ('☈', "thunderstorm"),
+    ('☉', "sun"),
+    ('☊', "ascending node"),
+    ('☋', "descending node"),
+    ('☌', "conjunction"),
+    ('☍', "opposition"),
+    ('☎', "black telephone"),
+    ('☏', "white telephone"),
+    ('☐', "ballot box"),
+    ('☑', "ballot box with check"),
+    ('☒', "ballot box with x"),
+    ('☓', "saltire"),
+    ('☔', "umbrella with rain drops"),
+    ('☕', "hot beverage"),
+    ('☖', "white shogi piece"),
+    ('☗', "black shogi piece"),
+    ('☘', "shamrock"),
+    ('☙', "reversed rotated floral heart bullet"),
+    ('☚', "black left pointing index"),
+    ('☛', "black right pointing index"),
+    ('☝', "white up pointing index"),
+    ('☟', "white down pointing index"),
+    ('☠', "skull and crossbones"),
+    ('☡', "caution sign"),
+    ('☢', "radioactive sign"),
+    ('☣', "biohazard sign"),
+    ('☤', "caduceus"),
+    ('☥', "ankh"),
+    ('☦', "orthodox cross"),
+    ('☧', "chi rho"),
+    ('☨', "cross of lorraine"),
+    ('☩', "cross of jerusalem"),
+    ('☪', "star and crescent"),
+    ('☫', "farsi symbol"),
+    ('☬', "adi shakti"),
+    ('☭', "hammer and sickle"),
+    ('☮', "peace symbol"),
+    ('☯', "yin yang"),
+    ('☰', "trigram for heaven"),
+    ('☱', "trigram for lake"),
+    ('☲', "trigram for fire"),
+    ('☳', "trigram for thunder"),
+    ('☴', "trigram for wind"),
+    ('☵', "trigram for water"),
+    ('☶', "trigram for mountain"),
+    ('☷', "trigram for earth"),
+    ('☸', "wheel of dharma"),
+    ('☹', "white frowning face"),
+    ('☽', "first quarter moon"),
+    ('☾', "last quarter moon"),
+    ('☿', "mercury"),
+    ('♁', "earth"),
+    ('♃', "jupiter"),
+    ('♄', "saturn"),
+    ('♅', "uranus"),
+    ('♆', "neptune"),
+    ('♇', "pluto"),
+    ('♈', "aries"),
+    ('♉', "taurus"),
+    ('♊', "gemini"),
+    ('♋', "cancer"),
+    ('♌', "leo"),
+    ('♍', "virgo"),
+    ('♎', "libra"),
+    ('♏', "scorpius"),
+    ('♐', "sagittarius"),
+    ('♑', "capricorn"),
+    ('♒', "aquarius"),
+    ('♓', "pisces"),
+    ('♔', "white chess king"),
+    ('♕', "white chess queen"),
+    ('♖', "white chess rook"),
+    ('♗', "white chess bishop"),
+    ('♘', "white chess knight"),
+    ('♙', "white chess pawn"),
+    ('♚', "black chess king"),
+    ('♛', "black chess queen"),
+    ('♜', "black chess rook"),
+    ('♝', "black chess bishop"),
+    ('♞', "black chess knight"),
+    ('♟', "black chess pawn"),
+    ('♤', "white spade suit"),
+    ('♥', "black heart suit"),
+    ('♦', "black diamond suit"),
+    ('♧', "white club suit"),
+    ('♨', "hot springs"),
+    ('♬', "beamed sixteenth notes"),
+    ('♰', "west syriac cross"),
+    ('♱', "east syriac cross"),
+    ('♲', "universal recycling symbol"),
+    ('♳', "recycling symbol for type-1 plastics"),
+    ('♴', "recycling symbol for type-2 plastics"),
+    ('♵', "recycling symbol for type-3 plastics"),
+    ('♶', "recycling symbol for type-4 plastics"),
+    ('♷', "recycling symbol for type-5 plastics"),
+    ('♸', "recycling symbol for type-6 plastics"),
+    ('♹', "recycling symbol for type-7 plastics"),
+    ('♺', "recycling symbol for generic materials"),
+    ('♻', "black universal recycling symbol"),
+    ('♼', "recycled paper symbol"),
+    ('♽', "partially-recycled paper symbol"),
+    ('♾', "permanent paper sign"),
+    ('♿', "wheelchair symbol"),
+    ('⚀', "die face-1"),
+    ('⚁', "die face-2"),
+    ('⚂', "die face-3"),
+    ('⚃', "die face-4"),
+    ('⚄', "die face-5"),
+    ('⚅', "die face-6"),
+    ('⚆', "white circle with dot right"),
+    ('⚇', "white circle with two dots"),
+    ('⚈', "black circle with white dot right"),
+    ('⚉', "black circle with two white dots"),
+    ('⚊', "monogram for yang"),
+    ('⚋', "monogram for yin"),
+    ('⚌', "digram for greater yang"),
+    ('⚍', "digram for lesser yin"),
+    ('⚎', "digram for lesser yang"),
+    ('⚏', "digram for greater yin"),
+    ('⚐', "white flag"),
+    ('⚑', "black flag"),
+    ('⚒', "hammer and pick"),
+    ('⚓', "anchor"),
+    ('⚔', "crossed swords"),
+    ('⚕', "staff of aesculapius"),
+    ('⚖', "scales"),
+    ('⚗', "alembic"),
+    ('⚘', "flower"),
+    ('⚙', "gear"),
+    ('⚚', "staff of hermes"),
+    ('⚛', "atom symbol"),
+    ('⚜', "fleur-de-lis"),
+    ('⚝', "outlined white star"),
+    ('⚞', "three lines converging right"),
+    ('⚟', "three lines converging left"),
+    ('⚠', "warning sign"),
+    ('⚡', "high voltage sign"),
+    ('⚢', "doubled female sign"),
+    ('⚣', "doubled male sign"),
+    ('⚤', "interlocked female and male sign"),
+    ('⚥', "male and female sign"),
+    ('⚦', "male with stroke sign"),
+    ('⚧', "male with stroke and male and female sign"),
+    ('⚨', "vertical male with stroke sign"),
+    ('⚩', "horizontal male with stroke sign"),
+    ('⚪', "medium white circle"),
+    ('⚫', "medium black circle"),
+    ('⚬', "medium small white circle"),
+    ('⚭', "marriage symbol"),
+    ('⚮', "divorce symbol"),
+    ('⚯', "unmarried partnership symbol"),
+    ('⚰', "coffin"),
+    ('⚱', "funeral urn"),
+    ('⚲', "neuter"),
+    ('⚳', "ceres"),
+    ('⚴', "pallas"),
+    ('⚵', "juno"),
+    ('⚶', "vesta"),
+    ('⚷', "chiron"),
+    ('⚸', "black moon lilith"),
+    ('⚹', "sextile"),
+    ('⚺', "semisextile"),
+    ('⚻', "quincunx"),
+    ('⚼', "sesquiquadrate"),
+    ('⚽', "soccer ball"),
+    ('⚾', "baseball"),
+    ('⚿', "squared key"),
+    ('⛀', "white draughts man"),
+    ('⛁', "white draughts king"),
+    ('⛂', "black draughts man"),
+    ('⛃', "black draughts king"),
+    ('⛄', "snowman without snow"),
+    ('⛅', "sun behind cloud"),
+    ('⛆', "rain"),
+    ('⛇', "black snowman"),
+    ('⛈', "thunder cloud and rain"),
+    ('⛉', "turned white shogi piece"),
+    ('⛊', "turned black shogi piece"),
+    ('⛋', "white diamond in square"),
+    ('⛌', "crossing lanes"),
+    ('⛍', "disabled car"),
+    ('⛎', "ophiuchus"),
+    ('⛏', "pick"),
+    ('⛐', "car sliding"),
+    ('⛑', "helmet with white cross"),
+    ('⛒', "circled crossing lanes"),
+    ('⛓', "chains"),
+    ('⛔', "no entry"),
+    ('⛕', "alternate one-way left way traffic"),
+    ('⛖', "black two-way left way traffic"),
+    ('⛗', "white two-way left way traffic"),
+    ('⛘', "black left lane merge"),
+    ('⛙', "white left lane merge"),
+    ('⛚', "drive slow sign"),
+    ('⛛', "heavy white down-pointing triangle"),
+    ('⛜', "left closed entry"),
+    ('⛝', "squared saltire"),
+    ('⛞', "falling diagonal in white circle in black square"),
+    ('⛟', "black truck"),
+    ('⛠', "restricted left entry-1"),
+    ('⛡', "restricted left entry-2"),
+    ('⛢', "astronomical symbol for uranus"),
+    ('⛣', "heavy circle with stroke and two dots above"),
+    ('⛤', "pentagram"),
+    ('⛥', "right-handed interlaced pentagram"),
+    ('⛦', "left-handed interlaced pentagram"),
+    ('⛧', "inverted pentagram"),
+    ('⛨', "black cross on shield"),
+    ('⛩', "shinto shrine"),
+    ('⛪', "church"),
+    ('⛫', "castle"),
+    ('⛬', "historic site"),
+    ('⛭', "gear without hub"),
+    ('⛮', "gear with handles"),
+    ('⛯', "map symbol for lighthouse"),
+    ('⛰', "mountain"),
+    ('⛱', "umbrella on ground"),
+    ('⛲', "fountain"),
+    ('⛳', "flag in hole"),
+    ('⛴', "ferry"),
+    ('⛵', "sailboat"),
+    ('⛶', "square four corners"),
+    ('⛷', "skier"),
+    ('⛸', "ice skate"),
+    ('⛹', "person with ball"),
+    ('⛺', "tent"),
+    ('⛻', "japanese bank symbol"),
+    ('⛼', "headstone graveyard symbol"),
+    ('⛽', "fuel pump"),
+    ('⛾', "cup on black square"),
+    ('⛿', "white flag with horizontal middle black stripe"),
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lookup_digraphs() {
+        assert_eq!(lookup('a', ':'), Some('ä'));
+        assert_eq!(lookup('e', '\''), Some('é'));
+        assert_eq!(lookup('\'', 'e'), Some('é'));
+        assert_eq!(lookup('s', 's'), Some('ß'));
+        assert_eq!(lookup('l', '*'), Some('λ'));
+        assert_eq!(lookup('-', '>'), Some('→'));
+        // `<-` is a digraph of its own.
+        assert_eq!(lookup('<', '-'), Some('←'));
+        assert_eq!(lookup('E', 'u'), Some('€'));
+        assert_eq!(lookup('z', '%'), Some('ж'));
+        assert_eq!(lookup('q', 'q'), None);
+    }
+
+    #[test]
+    fn parse_code_points() {
+        assert_eq!(code_point("e9"), Some('é'));
+        assert_eq!(code_point("1F600"), Some('😀'));
+        assert_eq!(code_point("d800"), None);
+        assert_eq!(code_point(""), None);
+    }
+}
@@ -175,6 +175,7 @@ pub struct DocumentFormatter<'t> {
     text_fmt: &'t TextFormat,
     annotations: &'t TextAnnotations<'t>,
 
+    text: RopeSlice<'t>,
     /// The visual position at the end of the last yielded word boundary
     visual_pos: Position,
     graphemes: RopeGraphemes<'t>,
@@ -213,13 +214,18 @@ pub fn new_at_prev_checkpoint(
         char_idx: usize,
     ) -> Self {
         // TODO divide long lines into blocks to avoid bad performance for long lines
-        let block_line_idx = text.char_to_line(char_idx.min(text.len_chars()));
+        let mut block_line_idx = text.char_to_line(char_idx.min(text.len_chars()));
+        // Lines hidden by a fold are formatted as part of the line the fold starts on.
+        if let Some(fold) = annotations.fold_hiding(block_line_idx) {
+            block_line_idx = fold.start;
+        }
         let block_char_idx = text.line_to_char(block_line_idx);
         annotations.reset_pos(block_char_idx);
 
         DocumentFormatter {
             text_fmt,
             annotations,
+            text,
             visual_pos: Position { row: 0, col: 0 },
             graphemes: text.slice(block_char_idx..).graphemes(),
             char_pos: block_char_idx,
@@ -420,6 +426,21 @@ fn advance_to_next_word(&mut self) {
         }
     }
 
+    /// Continue formatting at the start of `line`, skipping the lines hidden by a fold.
+    fn skip_to_line(&mut self, line: usize) {
+        let line = line.min(self.text.len_lines());
+        self.char_pos = if line == self.text.len_lines() {
+            self.text.len_chars()
+        } else {
+            self.text.line_to_char(line)
+        };
+        self.line_pos = line;
+        self.graphemes = self.text.slice(self.char_pos..).graphemes();
+        self.peeked_grapheme = None;
+        self.inline_annotation_graphemes = None;
+        self.annotations.reset_pos(self.char_pos);
+    }
+
     /// returns the char index at the end of the last yielded grapheme
     pub fn next_char_pos(&self) -> usize {
         self.char_pos
@@ -470,7 +491,11 @@ fn next(&mut self) -> Option<Self::Item> {
             self.visual_pos.row += 1 + virtual_lines;
             self.visual_pos.col = 0;
             if !grapheme.is_virtual() {
-                self.line_pos += 1;
+                if let Some(fold_end) = self.annotations.fold_end_at(self.line_pos) {
+                    self.skip_to_line(fold_end + 1);
+                } else {
+                    self.line_pos += 1;
+                }
             }
         } else {
             self.visual_pos.col += grapheme.width();
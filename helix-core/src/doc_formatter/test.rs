@@ -1,4 +1,5 @@
 use crate::doc_formatter::{DocumentFormatter, TextFormat};
+use crate::fold::Fold;
 use crate::text_annotations::{InlineAnnotation, Overlay, TextAnnotations};
 
 impl TextFormat {
@@ -211,3 +212,28 @@ fn annotation_and_overlay() {
         "fooo  bar "
     );
 }
+
+fn fold_text(text: &str, softwrap: bool, folds: &[Fold], char_pos: usize) -> String {
+    DocumentFormatter::new_at_prev_checkpoint(
+        text.into(),
+        &TextFormat::new_test(softwrap),
+        TextAnnotations::default().add_folds(folds),
+        char_pos,
+    )
+    .collect_to_str()
+}
+
+#[test]
+fn folds() {
+    let text = "a\nb\nc\nd\n";
+    let fold = |start, end| Fold { start, end };
+    assert_eq!(fold_text(text, false, &[fold(0, 1)], 0), "a \nc \nd \n ");
+    assert_eq!(fold_text(text, true, &[fold(1, 2)], 0), "a \nb \nd \n ");
+    assert_eq!(
+        fold_text(text, false, &[fold(0, 2), fold(1, 2)], 0),
+        "a \nd \n "
+    );
+    // Formatting starting on a hidden line starts at the first line of the fold.
+    assert_eq!(fold_text(text, false, &[fold(0, 1)], 2), "a \nc \nd \n ");
+    assert_eq!(fold_text(text, false, &[fold(2, 4)], 0), "a \nb \nc \n ");
+}
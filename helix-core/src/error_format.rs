@@ -0,0 +1,332 @@
+//! Parsing of build tool output into locations using `errorformat`-style templates.
+//!
+//! An error format describes a single line of output, similar to Vim's `errorformat`
+//! option. The following specifiers are recognized:
+//!
+//! * `%f` - the file path
+//! * `%l` - the line number (1-based)
+//! * `%c` - the column number (1-based)
+//! * `%t` - a severity word such as `error`, `warning`, `note`, `info` or `help`
+//! * `%m` - the message
+//! * `%*` - any text, which is skipped
+//! * `%%` - a literal `%`
+//!
+//! Any other character is matched literally. A format must contain at least `%f` and `%l`.
+
+use std::{fmt, path::PathBuf, str::FromStr};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+
+use crate::diagnostic::Severity;
+
+/// Formats used when neither the language nor the user configured any. These cover the
+/// output of most compilers and linters (GCC, Clang, `rustc --error-format=short`, …).
+pub const DEFAULT_ERROR_FORMATS: &[&str] = &["%f:%l:%c: %t%*: %m", "%f:%l:%c: %m", "%f:%l: %m"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorFormatError {
+    UnknownSpecifier(char),
+    UnterminatedSpecifier,
+    MissingSpecifier(char),
+}
+
+impl fmt::Display for ErrorFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSpecifier(ch) => write!(f, "unknown specifier '%{ch}'"),
+            Self::UnterminatedSpecifier => write!(f, "format ends with an unterminated '%'"),
+            Self::MissingSpecifier(ch) => write!(f, "format is missing the '%{ch}' specifier"),
+        }
+    }
+}
+
+impl std::error::Error for ErrorFormatError {}
+
+/// A compiled error format template.
+#[derive(Debug, Clone)]
+pub struct ErrorFormat {
+    source: String,
+    regex: Regex,
+}
+
+/// A location extracted from a line of output by an [`ErrorFormat`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorMatch {
+    pub path: PathBuf,
+    /// The 1-based line number.
+    pub line: usize,
+    /// The 1-based column number, if the format captured one.
+    pub column: Option<usize>,
+    pub severity: Option<Severity>,
+    pub message: String,
+}
+
+impl ErrorFormat {
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the compiled [`DEFAULT_ERROR_FORMATS`].
+    pub fn defaults() -> Vec<Self> {
+        DEFAULT_ERROR_FORMATS
+            .iter()
+            .map(|format| format.parse().expect("default error formats are valid"))
+            .collect()
+    }
+
+    /// Matches a single line of output against this format.
+    pub fn parse_line(&self, line: &str) -> Option<ErrorMatch> {
+        let captures = self.regex.captures(line.trim_end_matches('\r'))?;
+        let line = captures["line"].parse().ok()?;
+        let column = captures
+            .name("column")
+            .and_then(|column| column.as_str().parse().ok());
+        let severity = captures
+            .name("severity")
+            .and_then(|severity| parse_severity(severity.as_str()));
+        let message = captures
+            .name("message")
+            .map_or("", |message| message.as_str())
+            .trim()
+            .to_string();
+
+        Some(ErrorMatch {
+            path: PathBuf::from(&captures["file"]),
+            line,
+            column,
+            severity,
+            message,
+        })
+    }
+}
+
+impl FromStr for ErrorFormat {
+    type Err = ErrorFormatError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        let mut pattern = String::from("^");
+        let mut literal = String::new();
+        let mut chars = format.chars();
+
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                literal.push(ch);
+                continue;
+            }
+            let specifier = chars
+                .next()
+                .ok_or(ErrorFormatError::UnterminatedSpecifier)?;
+            if specifier == '%' {
+                literal.push('%');
+                continue;
+            }
+            pattern.push_str(&regex::escape(&literal));
+            literal.clear();
+            let group = match specifier {
+                'f' => capture(&pattern, "file", r".+?"),
+                'l' => capture(&pattern, "line", r"\d+"),
+                'c' => capture(&pattern, "column", r"\d+"),
+                't' => capture(
+                    &pattern,
+                    "severity",
+                    r"(?i:error|warning|warn|note|info|information|hint|help)",
+                ),
+                'm' => capture(&pattern, "message", r".*"),
+                '*' => r".*?".to_string(),
+                other => return Err(ErrorFormatError::UnknownSpecifier(other)),
+            };
+            pattern.push_str(&group);
+        }
+        pattern.push_str(&regex::escape(&literal));
+        pattern.push('$');
+
+        for (specifier, name) in [('f', "file"), ('l', "line")] {
+            if !pattern.contains(&format!("(?P<{name}>")) {
+                return Err(ErrorFormatError::MissingSpecifier(specifier));
+            }
+        }
+
+        let regex = Regex::new(&pattern).expect("escaped error format is a valid regex");
+        Ok(Self {
+            source: format.to_string(),
+            regex,
+        })
+    }
+}
+
+impl PartialEq for ErrorFormat {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorFormat {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let format = String::deserialize(deserializer)?;
+        format.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Wraps `body` in a named capture group unless the pattern already has a group with
+/// that name: a repeated specifier is matched but only its first occurrence is captured.
+fn capture(pattern: &str, name: &str, body: &str) -> String {
+    if pattern.contains(&format!("(?P<{name}>")) {
+        format!("(?:{body})")
+    } else {
+        format!("(?P<{name}>{body})")
+    }
+}
+
+fn parse_severity(word: &str) -> Option<Severity> {
+    match word.to_ascii_lowercase().as_str() {
+        "error" => Some(Severity::Error),
+        "warning" | "warn" => Some(Severity::Warning),
+        "note" | "info" | "information" => Some(Severity::Info),
+        "hint" | "help" => Some(Severity::Hint),
+        _ => None,
+    }
+}
+
+/// Parses every line of `output`, using the first format in `formats` which matches the line.
+/// Lines which no format matches are skipped.
+pub fn parse_output<'a>(
+    formats: &'a [ErrorFormat],
+    output: &'a str,
+) -> impl Iterator<Item = ErrorMatch> + 'a {
+    output
+        .lines()
+        .filter_map(|line| formats.iter().find_map(|format| format.parse_line(line)))
+}
+
+/// Finds every `path:line` or `path:line:column` reference in `text`, such as those printed by
+/// `grep -n`, test runners or compiler backtraces. The message of each match is its whole line.
+pub fn find_locations(text: &str) -> impl Iterator<Item = ErrorMatch> + '_ {
+    static LOCATION: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"(?P<file>[^\s:"'`()\[\]<>]+):(?P<line>\d+)(?::(?P<column>\d+))?"#).unwrap()
+    });
+
+    text.lines().flat_map(|line| {
+        LOCATION.captures_iter(line).filter_map(move |captures| {
+            Some(ErrorMatch {
+                path: PathBuf::from(&captures["file"]),
+                line: captures["line"].parse().ok()?,
+                column: captures
+                    .name("column")
+                    .and_then(|column| column.as_str().parse().ok()),
+                severity: None,
+                message: line.trim().to_string(),
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(format: &str, line: &str) -> Option<ErrorMatch> {
+        format.parse::<ErrorFormat>().unwrap().parse_line(line)
+    }
+
+    #[test]
+    fn parses_compiler_lines() {
+        assert_eq!(
+            parse(
+                "%f:%l:%c: %t%*: %m",
+                "src/main.rs:12:5: error[E0425]: cannot find value `x` in this scope"
+            ),
+            Some(ErrorMatch {
+                path: PathBuf::from("src/main.rs"),
+                line: 12,
+                column: Some(5),
+                severity: Some(Severity::Error),
+                message: "cannot find value `x` in this scope".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("%f:%l: %m", "main.c:3: expected ';'"),
+            Some(ErrorMatch {
+                path: PathBuf::from("main.c"),
+                line: 3,
+                column: None,
+                severity: None,
+                message: "expected ';'".to_string(),
+            })
+        );
+        assert_eq!(parse("%f:%l: %m", "warning: unused variable"), None);
+    }
+
+    #[test]
+    fn escapes_literals() {
+        assert_eq!(
+            parse("%f(%l) %%%m", "lib.cs(7) %oops").map(|m| (m.line, m.message)),
+            Some((7, "oops".to_string()))
+        );
+        assert_eq!(parse("%f(%l) %%%m", "lib.cs:7: oops"), None);
+    }
+
+    #[test]
+    fn rejects_invalid_formats() {
+        assert_eq!(
+            "%f:%l:%x".parse::<ErrorFormat>().unwrap_err(),
+            ErrorFormatError::UnknownSpecifier('x')
+        );
+        assert_eq!(
+            "%f:%".parse::<ErrorFormat>().unwrap_err(),
+            ErrorFormatError::UnterminatedSpecifier
+        );
+        assert_eq!(
+            "%f: %m".parse::<ErrorFormat>().unwrap_err(),
+            ErrorFormatError::MissingSpecifier('l')
+        );
+    }
+
+    #[test]
+    fn first_matching_format_wins() {
+        let formats = ErrorFormat::defaults();
+        let output = "   Compiling foo v0.1.0\n\
+                      src/lib.rs:1:4: warning: unused import\n\
+                      src/lib.rs:9:1: some message: with colons\n\
+                      Makefile:2: *** missing separator.  Stop.\n";
+        let matches: Vec<_> = parse_output(&formats, output)
+            .map(|m| (m.line, m.column, m.severity, m.message))
+            .collect();
+        assert_eq!(
+            matches,
+            vec![
+                (
+                    1,
+                    Some(4),
+                    Some(Severity::Warning),
+                    "unused import".to_string()
+                ),
+                (9, Some(1), None, "some message: with colons".to_string()),
+                (2, None, None, "*** missing separator.  Stop.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_locations_anywhere_in_lines() {
+        let output = "  --> src/main.rs:3:5\n\
+                      thread 'main' panicked at 'boom', tests/it.rs:42:9\n\
+                      src/lib.rs:10:    let x = 1;\n\
+                      no locations here: 12\n";
+        let locations: Vec<_> = find_locations(output)
+            .map(|m| (m.path, m.line, m.column))
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                (PathBuf::from("src/main.rs"), 3, Some(5)),
+                (PathBuf::from("tests/it.rs"), 42, Some(9)),
+                (PathBuf::from("src/lib.rs"), 10, None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! Fold regions which hide the lines of a syntax node or an indented block.
+
+use ropey::RopeSlice;
+
+use crate::{indent::indent_level_for_line, Syntax};
+
+/// A closed fold. The `start` line stays visible while the lines after it up to and including
+/// the `end` line are hidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Fold {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Fold {
+    /// Whether the line is hidden by the fold.
+    pub fn hides(&self, line: usize) -> bool {
+        self.start < line && line <= self.end
+    }
+}
+
+/// Returns the region to fold for the cursor on `line`: the smallest syntax node spanning
+/// several lines which contains the line or, without a syntax tree, the block of lines indented
+/// deeper than the line or the lines around it.
+pub fn fold_at(
+    text: RopeSlice,
+    line: usize,
+    syntax: Option<&Syntax>,
+    tab_width: usize,
+    indent_width: usize,
+) -> Option<Fold> {
+    match syntax {
+        Some(syntax) => syntax_fold_at(text, line, syntax),
+        None => indent_fold_at(text, line, tab_width, indent_width),
+    }
+}
+
+/// Returns every region which can be folded, sorted by their first line.
+pub fn fold_all(
+    text: RopeSlice,
+    syntax: Option<&Syntax>,
+    tab_width: usize,
+    indent_width: usize,
+) -> Vec<Fold> {
+    let mut folds = match syntax {
+        Some(syntax) => syntax_folds(text, syntax),
+        None => indent_folds(text, tab_width, indent_width),
+    };
+    // Keep the largest region of the ones starting on the same line.
+    folds.sort_unstable_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+    folds.dedup_by_key(|fold| fold.start);
+    folds
+}
+
+fn syntax_fold_at(text: RopeSlice, line: usize, syntax: &Syntax) -> Option<Fold> {
+    let line_text = text.line(line);
+    let first_char = line_text
+        .chars()
+        .position(|ch| !ch.is_whitespace())
+        .unwrap_or(0);
+    let start = text.char_to_byte(text.line_to_char(line) + first_char) as u32;
+    let end = (text.line_to_byte(line) + line_text.len_bytes())
+        .saturating_sub(1)
+        .max(start as usize) as u32;
+
+    let root = syntax.tree().root_node();
+    let mut node = syntax.named_descendant_for_byte_range(start, end)?;
+    loop {
+        if node == root {
+            return None;
+        }
+        let fold = node_fold(text, node.start_byte(), node.end_byte());
+        if fold.end > fold.start {
+            return Some(fold);
+        }
+        node = node.parent()?;
+    }
+}
+
+fn syntax_folds(text: RopeSlice, syntax: &Syntax) -> Vec<Fold> {
+    let root = syntax.tree().root_node();
+    let mut folds = Vec::new();
+    let mut nodes: Vec<_> = root.children().collect();
+    while let Some(node) = nodes.pop() {
+        if !node.is_named() {
+            continue;
+        }
+        let fold = node_fold(text, node.start_byte(), node.end_byte());
+        if fold.end > fold.start {
+            folds.push(fold);
+            nodes.extend(node.children());
+        }
+    }
+    folds
+}
+
+fn node_fold(text: RopeSlice, start_byte: u32, end_byte: u32) -> Fold {
+    let start = text.byte_to_line(start_byte as usize);
+    // The end byte is exclusive, so a node ending with a newline doesn't include the next line.
+    let end = text.byte_to_line(
+        (end_byte as usize)
+            .saturating_sub(1)
+            .max(start_byte as usize),
+    );
+    Fold { start, end }
+}
+
+fn is_blank(text: RopeSlice, line: usize) -> bool {
+    text.line(line).chars().all(char::is_whitespace)
+}
+
+fn indent_fold_at(
+    text: RopeSlice,
+    line: usize,
+    tab_width: usize,
+    indent_width: usize,
+) -> Option<Fold> {
+    let indent = |line| indent_level_for_line(text.line(line), tab_width, indent_width);
+    let len_lines = text.len_lines();
+
+    // The line starts a block if the next line with text is indented deeper.
+    let next = (line + 1..len_lines).find(|&line| !is_blank(text, line));
+    let start = match next {
+        Some(next) if !is_blank(text, line) && indent(next) > indent(line) => line,
+        _ => {
+            let level = (line..len_lines)
+                .find(|&line| !is_blank(text, line))
+                .map_or(0, indent);
+            (0..line)
+                .rev()
+                .find(|&line| !is_blank(text, line) && indent(line) < level)?
+        }
+    };
+    let end = indent_block_end(text, start, tab_width, indent_width);
+    (end > start).then_some(Fold { start, end })
+}
+
+fn indent_folds(text: RopeSlice, tab_width: usize, indent_width: usize) -> Vec<Fold> {
+    (0..text.len_lines())
+        .filter(|&line| !is_blank(text, line))
+        .filter_map(|start| {
+            let end = indent_block_end(text, start, tab_width, indent_width);
+            (end > start).then_some(Fold { start, end })
+        })
+        .collect()
+}
+
+/// The last line with text of the block of lines after `start` which are indented deeper.
+fn indent_block_end(text: RopeSlice, start: usize, tab_width: usize, indent_width: usize) -> usize {
+    let indent = |line| indent_level_for_line(text.line(line), tab_width, indent_width);
+    let level = indent(start);
+    let mut end = start;
+    for line in start + 1..text.len_lines() {
+        if is_blank(text, line) {
+            continue;
+        }
+        if indent(line) <= level {
+            break;
+        }
+        end = line;
+    }
+    end
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Rope;
+
+    #[test]
+    fn folds_indented_blocks() {
+        let text = Rope::from_str(
+            "fn a() {\n    one();\n\n    if x {\n        two();\n    }\n}\nfn b() {}\n",
+        );
+        let text = text.slice(..);
+        let fold = |line| fold_at(text, line, None, 4, 4);
+
+        assert_eq!(fold(0), Some(Fold { start: 0, end: 5 }));
+        assert_eq!(fold(1), Some(Fold { start: 0, end: 5 }));
+        assert_eq!(fold(2), Some(Fold { start: 0, end: 5 }));
+        assert_eq!(fold(3), Some(Fold { start: 3, end: 4 }));
+        assert_eq!(fold(4), Some(Fold { start: 3, end: 4 }));
+        assert_eq!(fold(7), None);
+
+        assert_eq!(
+            fold_all(text, None, 4, 4),
+            vec![Fold { start: 0, end: 5 }, Fold { start: 3, end: 4 }]
+        );
+    }
+}
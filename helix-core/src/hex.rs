@@ -0,0 +1,183 @@
+//! Hex dumps for viewing and editing binary files as text.
+//!
+//! A dump line shows the offset of its first byte, up to [`BYTES_PER_LINE`] bytes as pairs of
+//! hex digits and the bytes as ASCII:
+//!
+//! ```text
+//! 00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 ff  |Hello, world!...|
+//! ```
+//!
+//! When a dump is parsed only the pairs of hex digits count, so bytes can be changed, inserted
+//! or deleted by editing them. The offsets and the ASCII column are ignored.
+
+use std::fmt::{self, Write as _};
+
+pub const BYTES_PER_LINE: usize = 16;
+
+/// Whether the bytes look like the start of a binary file, which is assumed when they contain
+/// a NUL byte like git does.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.contains(&0)
+}
+
+/// Formats the bytes as a hex dump.
+pub fn dump(bytes: &[u8]) -> String {
+    let mut dump = String::with_capacity(bytes.len().div_ceil(BYTES_PER_LINE) * 78);
+    for (i, line) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+        let _ = write!(dump, "{:08x} ", i * BYTES_PER_LINE);
+        for j in 0..BYTES_PER_LINE {
+            if j % 8 == 0 {
+                dump.push(' ');
+            }
+            match line.get(j) {
+                Some(byte) => {
+                    let _ = write!(dump, "{byte:02x} ");
+                }
+                None => dump.push_str("   "),
+            }
+        }
+        dump.push_str(" |");
+        dump.extend(line.iter().map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        }));
+        dump.push_str("|\n");
+    }
+    dump
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The 0-based line of the invalid text.
+    pub line: usize,
+    pub text: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid byte '{}' on line {} of the hex dump",
+            self.text,
+            self.line + 1
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses the bytes of a hex dump along with the char index of their first hex digit.
+pub fn parse_with_positions(dump: &str) -> Result<Vec<(usize, u8)>, ParseError> {
+    let mut bytes = Vec::new();
+    let mut line_start = 0;
+    for (line_idx, line) in dump.split_inclusive('\n').enumerate() {
+        // The hex column never contains a `|`, everything after it is the ASCII column.
+        let hex = line.split('|').next().unwrap_or_default();
+        let mut char_idx = line_start;
+        let mut first = true;
+        let mut chars = hex.chars().peekable();
+        while let Some(&ch) = chars.peek() {
+            if ch.is_whitespace() {
+                chars.next();
+                char_idx += 1;
+                continue;
+            }
+            let start = char_idx;
+            let mut token = String::new();
+            while let Some(ch) = chars.next_if(|ch| !ch.is_whitespace()) {
+                token.push(ch);
+                char_idx += 1;
+            }
+            let is_offset = first && token.len() >= 8;
+            first = false;
+            if is_offset && token.chars().all(|ch| ch.is_ascii_hexdigit()) {
+                continue;
+            }
+            match u8::from_str_radix(&token, 16) {
+                Ok(byte) if token.len() == 2 => bytes.push((start, byte)),
+                _ => {
+                    return Err(ParseError {
+                        line: line_idx,
+                        text: token,
+                    })
+                }
+            }
+        }
+        line_start += line.chars().count();
+    }
+    Ok(bytes)
+}
+
+/// Parses the bytes of a hex dump.
+pub fn parse(dump: &str) -> Result<Vec<u8>, ParseError> {
+    parse_with_positions(dump).map(|bytes| bytes.into_iter().map(|(_, byte)| byte).collect())
+}
+
+/// Parses a search pattern of hex digits such as `deadbeef` or `de ad be ef`.
+pub fn parse_pattern(pattern: &str) -> Option<Vec<u8>> {
+    let digits: Vec<_> = pattern.chars().filter(|ch| !ch.is_whitespace()).collect();
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return None;
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let high = pair[0].to_digit(16)?;
+            let low = pair[1].to_digit(16)?;
+            Some((high * 16 + low) as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dump_and_parse() {
+        let bytes: Vec<u8> = (0..=40).chain([0, 0xff]).collect();
+        let dump = dump(&bytes);
+        assert_eq!(
+            dump.lines().nth(2),
+            Some("00000020  20 21 22 23 24 25 26 27  28 00 ff                 | !\"#$%&'(..|")
+        );
+        assert_eq!(parse(&dump), Ok(bytes));
+
+        // Edited lines don't need to line up with the offsets or the ASCII column.
+        assert_eq!(
+            parse("00000000  41 42 |AB|\nff 43\n\n44|garbage"),
+            Ok(vec![0x41, 0x42, 0xff, 0x43, 0x44])
+        );
+        assert_eq!(
+            parse("00000000  41 4g |AB|"),
+            Err(ParseError {
+                line: 0,
+                text: "4g".to_owned()
+            })
+        );
+        assert!(parse("00000000  414").is_err());
+    }
+
+    #[test]
+    fn positions() {
+        assert_eq!(
+            parse_with_positions("00000000  41 42\n00000002  43"),
+            Ok(vec![(10, 0x41), (13, 0x42), (26, 0x43)])
+        );
+    }
+
+    #[test]
+    fn patterns() {
+        assert_eq!(
+            parse_pattern("deadBEEF"),
+            Some(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+        assert_eq!(parse_pattern("de ad"), Some(vec![0xde, 0xad]));
+        assert_eq!(parse_pattern("dea"), None);
+        assert_eq!(parse_pattern("zz"), None);
+        assert_eq!(parse_pattern(""), None);
+    }
+}
@@ -1,8 +1,9 @@
-use crate::{Assoc, ChangeSet, Range, Rope, Selection, Transaction};
+use crate::{transaction::Operation, Assoc, ChangeSet, Range, Rope, Selection, Transaction};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::num::NonZeroUsize;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 #[derive(Debug, Clone)]
 pub struct State {
@@ -119,6 +120,54 @@ pub const fn at_root(&self) -> bool {
         self.current == 0
     }
 
+    /// The number of revisions including the root revision.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.revisions.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() <= 1
+    }
+
+    pub fn parent(&self, revision: usize) -> usize {
+        self.revisions[revision].parent
+    }
+
+    pub fn timestamp(&self, revision: usize) -> Instant {
+        self.revisions[revision].timestamp
+    }
+
+    /// The transaction going from the parent of the revision to the revision.
+    pub fn transaction(&self, revision: usize) -> &Transaction {
+        &self.revisions[revision].transaction
+    }
+
+    /// Lists the revisions as a tree in depth-first order along with how deep each of them is
+    /// nested in branches. The last child of a revision continues the branch of its parent while
+    /// its older siblings start branches nested one level deeper, which are listed before it.
+    pub fn tree(&self) -> Vec<(usize, usize)> {
+        let mut children = vec![Vec::new(); self.revisions.len()];
+        for (revision, rev) in self.revisions.iter().enumerate().skip(1) {
+            children[rev.parent].push(revision);
+        }
+
+        let mut tree = Vec::with_capacity(self.revisions.len());
+        let mut stack = vec![(0, 0)];
+        while let Some((revision, level)) = stack.pop() {
+            tree.push((revision, level));
+            let children = &children[revision];
+            let Some((&last, older)) = children.split_last() else {
+                continue;
+            };
+            // Pushed first so it is visited after the branches of its siblings.
+            stack.push((last, level));
+            stack.extend(older.iter().rev().map(|&child| (child, level + 1)));
+        }
+        tree
+    }
+
     /// Returns the changes since the given revision composed into a transaction.
     /// Returns None if there are no changes between the current and given revisions.
     pub fn changes_since(&self, revision: usize) -> Option<Transaction> {
@@ -210,7 +259,7 @@ fn path_up(&self, mut n: usize, a: usize) -> Vec<usize> {
     }
 
     /// Create a [`Transaction`] that will jump to a specific revision in the history.
-    fn jump_to(&mut self, to: usize) -> Vec<Transaction> {
+    pub fn jump_to(&mut self, to: usize) -> Vec<Transaction> {
         let lca = self.lowest_common_ancestor(self.current, to);
         let up = self.path_up(self.current, lca);
         let down = self.path_up(to, lca);
@@ -302,6 +351,257 @@ pub fn later(&mut self, uk: UndoKind) -> Vec<Transaction> {
     }
 }
 
+/// A [History] in a form which can be saved to restore it in a later session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistentHistory {
+    revisions: Vec<PersistentRevision>,
+    current: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PersistentRevision {
+    parent: usize,
+    transaction: PersistentTransaction,
+    inversion: PersistentTransaction,
+    /// Milliseconds since the unix epoch.
+    timestamp: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PersistentTransaction {
+    changes: Vec<PersistentOperation>,
+    /// The ranges as anchor and head along with the index of the primary range.
+    selection: Option<(Vec<(usize, usize)>, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum PersistentOperation {
+    Retain(usize),
+    Delete(usize),
+    Insert(String),
+}
+
+impl From<&Transaction> for PersistentTransaction {
+    fn from(transaction: &Transaction) -> Self {
+        let changes = transaction
+            .changes()
+            .changes()
+            .iter()
+            .map(|operation| match operation {
+                Operation::Retain(n) => PersistentOperation::Retain(*n),
+                Operation::Delete(n) => PersistentOperation::Delete(*n),
+                Operation::Insert(text) => PersistentOperation::Insert(text.to_string()),
+            })
+            .collect();
+        let selection = transaction.selection().map(|selection| {
+            let ranges = selection
+                .ranges()
+                .iter()
+                .map(|range| (range.anchor, range.head))
+                .collect();
+            (ranges, selection.primary_index())
+        });
+        Self { changes, selection }
+    }
+}
+
+impl PersistentTransaction {
+    fn into_transaction(self) -> Option<Transaction> {
+        let mut changes = ChangeSet::with_capacity(self.changes.len());
+        for operation in self.changes {
+            match operation {
+                PersistentOperation::Retain(n) => changes.retain(n),
+                PersistentOperation::Delete(n) => changes.delete(n),
+                PersistentOperation::Insert(text) => changes.insert(text.into()),
+            }
+        }
+        let mut transaction = Transaction::from(changes);
+        if let Some((ranges, primary_index)) = self.selection {
+            if primary_index >= ranges.len() {
+                return None;
+            }
+            let ranges = ranges
+                .into_iter()
+                .map(|(anchor, head)| Range::new(anchor, head))
+                .collect();
+            transaction = transaction.with_selection(Selection::new(ranges, primary_index));
+        }
+        Some(transaction)
+    }
+}
+
+impl History {
+    /// Converts the history to be saved, keeping at most `max_revisions` revisions.
+    ///
+    /// The `current` revision and as many of its ancestors as fit are kept first, so that it can
+    /// be undone as far as possible. The oldest of them becomes the root of the saved history.
+    /// The remaining revisions descending from the root are then kept, the newest first along
+    /// with their ancestors, as long as they fit.
+    pub fn to_persistent(&self, current: usize, max_revisions: usize) -> PersistentHistory {
+        let max_revisions = max_revisions.max(1);
+        let mut kept = vec![false; self.revisions.len()];
+        kept[current] = true;
+        let mut count = 1;
+        let mut root = current;
+        while root != 0 && count < max_revisions {
+            root = self.revisions[root].parent;
+            kept[root] = true;
+            count += 1;
+        }
+        for revision in (root + 1..self.revisions.len()).rev() {
+            if count == max_revisions {
+                break;
+            }
+            // The revisions up to a kept ancestor, which is the root at the latest for the
+            // revisions descending from it.
+            let mut chain = Vec::new();
+            let mut ancestor = revision;
+            while !kept[ancestor] && ancestor > root && count + chain.len() < max_revisions {
+                chain.push(ancestor);
+                ancestor = self.revisions[ancestor].parent;
+            }
+            if kept[ancestor] {
+                count += chain.len();
+                for revision in chain {
+                    kept[revision] = true;
+                }
+            }
+        }
+
+        // Maps each kept revision to its new index.
+        let mut indices = vec![None; self.revisions.len()];
+        let now = Instant::now();
+        let now_system = SystemTime::now();
+        let mut revisions = Vec::new();
+        for (revision, rev) in self.revisions.iter().enumerate().skip(root) {
+            if !kept[revision] {
+                continue;
+            }
+            let parent = if revision == root {
+                0
+            } else {
+                indices[rev.parent].expect("the parents of kept revisions are kept")
+            };
+            indices[revision] = Some(revisions.len());
+            let (transaction, inversion) = if revision == root {
+                let empty = Transaction::from(ChangeSet::new("".into()));
+                ((&empty).into(), (&empty).into())
+            } else {
+                ((&rev.transaction).into(), (&rev.inversion).into())
+            };
+            let timestamp = now_system - now.saturating_duration_since(rev.timestamp);
+            revisions.push(PersistentRevision {
+                parent,
+                transaction,
+                inversion,
+                timestamp: timestamp
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |time| time.as_millis() as u64),
+            });
+        }
+        PersistentHistory {
+            revisions,
+            current: indices[current].expect("the current revision is kept"),
+        }
+    }
+
+    /// Restores a saved history whose current revision has `text`. Returns `None` if it is
+    /// malformed or its changes don't apply to the text of their revisions.
+    pub fn from_persistent(history: PersistentHistory, text: &Rope) -> Option<Self> {
+        let PersistentHistory { revisions, current } = history;
+        if revisions.is_empty() || current >= revisions.len() {
+            return None;
+        }
+        let now = Instant::now();
+        let now_system = SystemTime::now();
+        let mut restored: Vec<Revision> = Vec::with_capacity(revisions.len());
+        for (revision, rev) in revisions.into_iter().enumerate() {
+            if revision > 0 && rev.parent >= revision {
+                return None;
+            }
+            let time = SystemTime::UNIX_EPOCH + Duration::from_millis(rev.timestamp);
+            let age = now_system.duration_since(time).unwrap_or_default();
+            // Timestamps must not decrease for jumping to a moment in time.
+            let previous = restored.last().map(|rev| rev.timestamp);
+            let timestamp = match (now.checked_sub(age), previous) {
+                (Some(timestamp), Some(previous)) => timestamp.max(previous),
+                (Some(timestamp), None) => timestamp,
+                (None, previous) => previous.unwrap_or(now),
+            };
+            if revision > 0 {
+                restored[rev.parent].last_child = NonZeroUsize::new(revision);
+            }
+            restored.push(Revision {
+                parent: if revision == 0 { 0 } else { rev.parent },
+                last_child: None,
+                transaction: rev.transaction.into_transaction()?,
+                inversion: rev.inversion.into_transaction()?,
+                timestamp,
+            });
+        }
+        let history = Self {
+            revisions: restored,
+            current,
+        };
+        history.applies_to(text).then_some(history)
+    }
+
+    /// Checks that the changes of each revision apply to the text of its parent and the inversions
+    /// back, given the `text` of the current revision.
+    fn applies_to(&self, text: &Rope) -> bool {
+        fn apply(transaction: &Transaction, text: &Rope) -> Option<Rope> {
+            let len: usize = (transaction.changes().changes().iter())
+                .map(|operation| match operation {
+                    Operation::Retain(n) | Operation::Delete(n) => *n,
+                    Operation::Insert(_) => 0,
+                })
+                .sum();
+            let mut text = text.clone();
+            // Empty changes apply to any text.
+            let applies = (transaction.changes().changes().is_empty() || len == text.len_chars())
+                && transaction.apply(&mut text);
+            let selection_fits = transaction.selection().map_or(true, |selection| {
+                selection
+                    .ranges()
+                    .iter()
+                    .all(|range| range.to() <= text.len_chars())
+            });
+            (applies && selection_fits).then_some(text)
+        }
+
+        let mut texts = vec![None; self.revisions.len()];
+        texts[self.current] = Some(text.clone());
+        let mut revision = self.current;
+        while revision != 0 {
+            let parent = self.revisions[revision].parent;
+            let Some(text) = texts[revision]
+                .as_ref()
+                .and_then(|text| apply(&self.revisions[revision].inversion, text))
+            else {
+                return false;
+            };
+            texts[parent] = Some(text);
+            revision = parent;
+        }
+        // Parents come before their children.
+        for revision in 1..self.revisions.len() {
+            let rev = &self.revisions[revision];
+            let Some(parent) = texts[rev.parent].clone() else {
+                return false;
+            };
+            let Some(text) = apply(&rev.transaction, &parent) else {
+                return false;
+            };
+            if apply(&rev.inversion, &text).is_none() {
+                return false;
+            }
+            texts[revision].get_or_insert(text);
+        }
+        true
+    }
+}
+
 /// Whether to undo by a number of edits or a duration of time.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum UndoKind {
@@ -546,6 +846,117 @@ fn commit_change(
         assert_eq!("a\n", state.doc);
     }
 
+    #[test]
+    fn test_tree() {
+        let mut history = History::default();
+        let mut state = State {
+            doc: Rope::from("a\n"),
+            selection: Selection::point(0),
+        };
+        let mut commit = |history: &mut History, text: &str| {
+            let txn = Transaction::change(&state.doc, [(0, 0, Some(text.into()))].into_iter());
+            history.commit_revision(&txn, &state);
+            txn.apply(&mut state.doc);
+        };
+
+        // 0 - 1 - 5
+        //      \
+        //       2 - 4
+        //        \
+        //         3
+        commit(&mut history, "b");
+        commit(&mut history, "c");
+        commit(&mut history, "d");
+        history.jump_to(2);
+        commit(&mut history, "e");
+        history.jump_to(1);
+        commit(&mut history, "f");
+
+        assert_eq!(history.len(), 6);
+        assert_eq!(
+            history.tree(),
+            vec![(0, 0), (1, 0), (2, 1), (3, 2), (4, 1), (5, 0)]
+        );
+        assert_eq!(history.jump_to(3).len(), 3);
+        assert_eq!(history.current_revision(), 3);
+    }
+
+    #[test]
+    fn test_persistent_history() {
+        let mut history = History::default();
+        let mut state = State {
+            doc: Rope::from("a\n"),
+            selection: Selection::point(0),
+        };
+        let commit = |history: &mut History, state: &mut State, text: &str| {
+            let txn = Transaction::change(&state.doc, [(0, 0, Some(text.into()))].into_iter())
+                .with_selection(Selection::point(1));
+            history.commit_revision(&txn, state);
+            txn.apply(&mut state.doc);
+        };
+
+        // 0 - 1 - 2 - 3
+        //      \
+        //       4
+        commit(&mut history, &mut state, "b");
+        commit(&mut history, &mut state, "c");
+        commit(&mut history, &mut state, "d");
+        history.jump_to(1);
+        state.doc = Rope::from("ba\n");
+        commit(&mut history, &mut state, "e");
+
+        let text = Rope::from("dcba\n");
+        let persistent = history.to_persistent(3, usize::MAX);
+        assert_eq!(persistent.current, 3);
+        // The changes must apply to the text.
+        assert!(History::from_persistent(persistent.clone(), &Rope::from("a\n")).is_none());
+        let restored = History::from_persistent(persistent, &text).unwrap();
+        assert_eq!(restored.len(), 5);
+        assert_eq!(restored.current_revision(), 3);
+        assert_eq!(restored.tree(), history.tree());
+        assert_eq!(restored.transaction(2), history.transaction(2));
+        assert_eq!(restored.revisions[1].last_child, NonZeroUsize::new(4));
+
+        // Keeping three revisions roots the history at revision 1, dropping the branch.
+        let restored = History::from_persistent(history.to_persistent(3, 3), &text).unwrap();
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored.current_revision(), 2);
+        assert_eq!(restored.transaction(2), history.transaction(3));
+
+        // The ancestors of revision 4 are kept before the newest of the other revisions.
+        let text = Rope::from("eba\n");
+        let restored = History::from_persistent(history.to_persistent(4, 4), &text).unwrap();
+        assert_eq!(restored.len(), 4);
+        assert_eq!(restored.current_revision(), 3);
+        assert_eq!(restored.transaction(2), history.transaction(2));
+        assert_eq!(restored.transaction(3), history.transaction(4));
+    }
+
+    #[test]
+    fn test_persistent_history_undone() {
+        let mut history = History::default();
+        let mut state = State {
+            doc: Rope::from("a\n"),
+            selection: Selection::point(0),
+        };
+        for text in ["b", "c", "d", "e", "f"] {
+            let txn = Transaction::change(&state.doc, [(0, 0, Some(text.into()))].into_iter())
+                .with_selection(Selection::point(1));
+            history.commit_revision(&txn, &state);
+            txn.apply(&mut state.doc);
+        }
+        history.jump_to(0);
+
+        // Only the oldest revisions can be redone from the current one.
+        let persistent = history.to_persistent(0, 3);
+        assert_eq!(persistent.current, 0);
+        let restored = History::from_persistent(persistent, &Rope::from("a\n")).unwrap();
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored.current_revision(), 0);
+        assert_eq!(restored.transaction(1), history.transaction(1));
+        assert_eq!(restored.transaction(2), history.transaction(2));
+    }
+
     #[test]
     fn test_parse_undo_kind() {
         use UndoKind::*;
@@ -53,8 +53,10 @@ pub fn increment(selected_text: &str, amount: i64) -> Option<String> {
 
 static FORMATS: Lazy<Vec<Format>> = Lazy::new(|| {
     vec![
+        Format::new("%Y-%m-%dT%H:%M:%S"), // 2021-11-24T07:12:23
         Format::new("%Y-%m-%d %H:%M:%S"), // 2021-11-24 07:12:23
         Format::new("%Y/%m/%d %H:%M:%S"), // 2021/11/24 07:12:23
+        Format::new("%Y-%m-%dT%H:%M"),    // 2021-11-24T07:12
         Format::new("%Y-%m-%d %H:%M"),    // 2021-11-24 07:12
         Format::new("%Y/%m/%d %H:%M"),    // 2021/11/24 07:12
         Format::new("%Y-%m-%d"),          // 2021-11-24
@@ -272,6 +274,8 @@ fn test_increment_date_times() {
             ("1980/12/21", -1000, "1978/03/27"),
             ("2021-11-24 07:12:23", 1, "2021-11-24 07:13:23"),
             ("2021-11-24 07:12", 1, "2021-11-24 07:13"),
+            ("2021-11-24T23:59:23", 1, "2021-11-25T00:00:23"),
+            ("2021-11-24T07:12", -13, "2021-11-24T06:59"),
             ("Wed Nov 24 2021", 1, "Thu Nov 25 2021"),
             ("24-Nov-2021", 1, "25-Nov-2021"),
             ("2021 Nov 24", 1, "2021 Nov 25"),
@@ -1,5 +1,6 @@
 mod date_time;
 mod integer;
+mod word_cycle;
 
 pub fn integer(selected_text: &str, amount: i64) -> Option<String> {
     integer::increment(selected_text, amount)
@@ -8,3 +9,7 @@ pub fn integer(selected_text: &str, amount: i64) -> Option<String> {
 pub fn date_time(selected_text: &str, amount: i64) -> Option<String> {
     date_time::increment(selected_text, amount)
 }
+
+pub fn word_cycle(selected_text: &str, amount: i64, cycles: &[Vec<String>]) -> Option<String> {
+    word_cycle::increment(selected_text, amount, cycles)
+}
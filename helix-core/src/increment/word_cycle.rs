@@ -0,0 +1,78 @@
+/// Replace a word with the word `amount` places after it in the first cycle which contains it,
+/// wrapping around at the end of the cycle.
+///
+/// Words are matched case-insensitively. The replacement keeps the case of the selected text if
+/// it is all lowercase, all uppercase or capitalized.
+pub fn increment(selected_text: &str, amount: i64, cycles: &[Vec<String>]) -> Option<String> {
+    if selected_text.is_empty() {
+        return None;
+    }
+
+    cycles.iter().find_map(|cycle| {
+        let index = cycle
+            .iter()
+            .position(|word| word.to_lowercase() == selected_text.to_lowercase())?;
+        let next = &cycle[(index as i64 + amount).rem_euclid(cycle.len() as i64) as usize];
+        if cycle[index] == selected_text {
+            Some(next.clone())
+        } else {
+            Some(match_case(selected_text, next))
+        }
+    })
+}
+
+fn match_case(original: &str, word: &str) -> String {
+    let mut chars = original.chars();
+    let first_upper = chars.next().is_some_and(char::is_uppercase);
+    if original.chars().all(|ch| !ch.is_lowercase()) && original.chars().count() > 1 {
+        word.to_uppercase()
+    } else if original.chars().all(|ch| !ch.is_uppercase()) {
+        word.to_lowercase()
+    } else if first_upper && chars.all(|ch| !ch.is_uppercase()) {
+        let mut word_chars = word.chars();
+        word_chars
+            .next()
+            .map(|first| {
+                first
+                    .to_uppercase()
+                    .chain(word_chars.flat_map(char::to_lowercase))
+            })
+            .into_iter()
+            .flatten()
+            .collect()
+    } else {
+        word.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cycles() -> Vec<Vec<String>> {
+        [vec!["true", "false"], vec!["Mon", "Tue", "Wed"]]
+            .into_iter()
+            .map(|cycle| cycle.into_iter().map(String::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_increment_word_cycles() {
+        let tests = [
+            ("true", 1, "false"),
+            ("false", 1, "true"),
+            ("True", 1, "False"),
+            ("TRUE", 3, "FALSE"),
+            ("Mon", 1, "Tue"),
+            ("Mon", -1, "Wed"),
+            ("Wed", 4, "Mon"),
+            ("mon", 2, "wed"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount, &cycles()).unwrap(), expected);
+        }
+        assert_eq!(increment("maybe", 1, &cycles()), None);
+        assert_eq!(increment("", 1, &cycles()), None);
+    }
+}
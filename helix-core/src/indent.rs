@@ -1086,6 +1086,69 @@ pub fn indent_for_newline(
     indent_style.as_str().repeat(indent_level)
 }
 
+/// Returns the first and last line of the innermost indentation scope around `line`. The scopes
+/// are the nodes captured with `@indent` by the indent query of the language. Without a query, or
+/// one which doesn't capture `@indent`, the scope is the block of lines indented deeper than the
+/// line or the lines around it.
+pub fn indent_scope_lines(
+    loader: &syntax::Loader,
+    syntax: Option<&Syntax>,
+    text: RopeSlice,
+    line: usize,
+    tab_width: usize,
+    indent_width: usize,
+) -> Option<(usize, usize)> {
+    let Some((syntax, query)) = syntax.and_then(|syntax| {
+        let query = loader
+            .indent_query(syntax.root_language())
+            .filter(|query| {
+                query.indent_capture.is_some() || query.indent_always_capture.is_some()
+            })?;
+        Some((syntax, query))
+    }) else {
+        let fold = crate::fold::fold_at(text, line, None, tab_width, indent_width)?;
+        return Some((fold.start, fold.end));
+    };
+
+    let range = text.line_to_byte(line) as u32..text.line_to_byte(line + 1) as u32;
+    let mut cursor = InactiveQueryCursor::new(range, TREE_SITTER_MATCH_LIMIT).execute_query(
+        &query.query,
+        &syntax.tree().root_node(),
+        RopeInput::new(text),
+    );
+    let mut scope: Option<(usize, usize)> = None;
+    while let Some(m) = cursor.next_match() {
+        if query
+            .predicates
+            .get(&m.pattern())
+            .is_some_and(|preds| !preds.are_satisfied(&m, text, None))
+        {
+            continue;
+        }
+        for matched_node in m.matched_nodes() {
+            let capture = Some(matched_node.capture);
+            if capture != query.indent_capture && capture != query.indent_always_capture {
+                continue;
+            }
+            let node = &matched_node.node;
+            let first = text.byte_to_line(node.start_byte() as usize);
+            // The end byte is exclusive, so a node ending with a newline doesn't include the next line.
+            let last = text.byte_to_line(
+                (node.end_byte() as usize)
+                    .saturating_sub(1)
+                    .max(node.start_byte() as usize),
+            );
+            if first < last
+                && (first..=last).contains(&line)
+                && scope.is_none_or(|(start, end)| (first, end) > (start, last))
+            {
+                scope = Some((first, last));
+            }
+        }
+    }
+    scope
+}
+
 pub fn get_scopes<'a>(syntax: Option<&'a Syntax>, text: RopeSlice, pos: usize) -> Vec<&'a str> {
     let mut scopes = Vec::new();
     if let Some(syntax) = syntax {
@@ -1344,4 +1407,30 @@ fn test_relative_indent() {
             None
         );
     }
+
+    #[test]
+    fn test_indent_scope_lines() {
+        let loader = crate::config::default_lang_loader();
+        let text = Rope::from("fn a() {\n    if x {\n        b();\n    }\n}\n\nfn c() {}\n");
+        let language = loader.language_for_name("rust").unwrap();
+        let syntax = Syntax::new(text.slice(..), language, &loader).unwrap();
+        let scope = |line| indent_scope_lines(&loader, Some(&syntax), text.slice(..), line, 4, 4);
+        assert_eq!(scope(0), Some((0, 4)));
+        assert_eq!(scope(1), Some((1, 3)));
+        assert_eq!(scope(2), Some((1, 3)));
+        assert_eq!(scope(4), Some((0, 4)));
+        // Scopes on a single line aren't shown.
+        assert_eq!(scope(6), None);
+    }
+
+    #[test]
+    fn test_indent_scope_lines_without_query() {
+        let loader = syntax::Loader::default();
+        let text = Rope::from("def a():\n    b()\n\n    c()\nd()\n");
+        let scope = |line| indent_scope_lines(&loader, None, text.slice(..), line, 4, 4);
+        assert_eq!(scope(0), Some((0, 3)));
+        assert_eq!(scope(1), Some((0, 3)));
+        assert_eq!(scope(2), Some((0, 3)));
+        assert_eq!(scope(4), None);
+    }
 }
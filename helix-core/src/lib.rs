@@ -1,5 +1,6 @@
 pub use encoding_rs as encoding;
 
+pub mod ansi;
 pub mod auto_pairs;
 pub mod case_conversion;
 pub mod chars;
@@ -9,22 +10,28 @@
 pub mod config;
 pub mod diagnostic;
 pub mod diff;
+pub mod digraph;
 pub mod doc_formatter;
 pub mod editor_config;
+pub mod error_format;
+pub mod fold;
 pub mod fuzzy;
 pub mod graphemes;
+pub mod hex;
 pub mod history;
 pub mod increment;
 pub mod indent;
 pub mod line_ending;
 pub mod macros;
 pub mod match_brackets;
+pub mod modeline;
 pub mod movement;
 pub mod object;
 mod position;
 pub mod search;
 pub mod selection;
 pub mod snippets;
+pub mod spelling;
 pub mod surround;
 pub mod syntax;
 pub mod test;
@@ -1,9 +1,10 @@
-use std::iter;
+use std::{iter, ops};
 
 use crate::tree_sitter::Node;
 use ropey::RopeSlice;
 
 use crate::movement::Direction::{self, Backward, Forward};
+use crate::syntax::{Highlight, OverlayHighlights};
 use crate::Syntax;
 
 const MAX_PLAINTEXT_SCAN: usize = 10000;
@@ -205,6 +206,41 @@ pub fn find_matching_bracket_plaintext(doc: RopeSlice, cursor_pos: usize) -> Opt
     None
 }
 
+/// Highlights the brackets within the character `range` of `doc` with the rainbow colors of their
+/// nesting depth, for documents whose language has no `rainbows.scm` query. The depth is that of
+/// plaintext matching, counted from at most [`MAX_PLAINTEXT_SCAN`] characters before the range.
+/// Angle brackets are skipped since they're more often comparisons than brackets.
+pub fn rainbow_highlights_plaintext(
+    doc: RopeSlice,
+    rainbow_length: usize,
+    range: ops::Range<usize>,
+) -> OverlayHighlights {
+    let start = range.start.saturating_sub(MAX_PLAINTEXT_SCAN);
+    let end = range.end.min(doc.len_chars());
+    // The closing brackets of the currently open brackets.
+    let mut open = Vec::new();
+    let mut highlights = Vec::new();
+    for (pos, ch) in (start..end).zip(doc.chars_at(start)) {
+        if matches!(ch, '<' | '>') {
+            continue;
+        }
+        let depth = if is_open_bracket(ch) {
+            open.push(get_pair(ch).1);
+            open.len() - 1
+        } else if is_close_bracket(ch) && open.last() == Some(&ch) {
+            open.pop();
+            open.len()
+        } else {
+            continue;
+        };
+        if pos >= range.start {
+            let highlight = Highlight::new((depth % rainbow_length) as u32);
+            highlights.push((highlight, pos..pos + 1));
+        }
+    }
+    OverlayHighlights::Heterogenous { highlights }
+}
+
 /// Returns the open and closing chars pair. If not found in
 /// [`BRACKETS`] returns (ch, ch).
 ///
@@ -312,6 +348,33 @@ fn as_char(doc: RopeSlice, node: &Node) -> Option<(usize, char)> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn rainbow_plaintext() {
+        let doc = RopeSlice::from("(a [b] {c (d)}) x < y] (");
+        let OverlayHighlights::Heterogenous { highlights } =
+            rainbow_highlights_plaintext(doc, 3, 3..doc.len_chars())
+        else {
+            unreachable!()
+        };
+        let depths: Vec<_> = highlights
+            .into_iter()
+            .map(|(highlight, range)| (highlight.get(), range.start))
+            .collect();
+        assert_eq!(
+            depths,
+            [
+                (1, 3),
+                (1, 5),
+                (1, 7),
+                (2, 10),
+                (2, 12),
+                (1, 13),
+                (0, 14),
+                (0, 23)
+            ]
+        );
+    }
+
     #[test]
     fn find_matching_bracket_empty_file() {
         let actual = find_matching_bracket_plaintext("".into(), 0);
@@ -0,0 +1,317 @@
+//! Parsing of modelines, comments near the start or end of a file which configure the editor
+//! for that file.
+//!
+//! Three forms are recognized:
+//!
+//! * Vim: `vim: set ts=4 sw=4 et :` or `vim: ts=4:sw=4:et`. Also with `vi:` or `ex:`.
+//! * Emacs: `-*- mode: rust; indent-tabs-mode: nil; tab-width: 4 -*-` or `-*- rust -*-`.
+//! * Helix: `hx: language=rust indent=4 tab-width=4 line-ending=lf text-width=100 soft-wrap=true`,
+//!   where `indent` is `tabs` or a number of spaces.
+//!
+//! Only the options which can be represented as a [`Modeline`] are read, all others are
+//! ignored, so that a file can't change anything else about the editor.
+
+use std::{
+    borrow::Cow,
+    num::{NonZeroU16, NonZeroU8},
+};
+
+use anyhow::{anyhow, bail};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use ropey::RopeSlice;
+
+use crate::{
+    indent::{IndentStyle, MAX_INDENT},
+    LineEnding,
+};
+
+/// The number of lines at the start and at the end of a file which are searched, as in Vim.
+const LINES: usize = 5;
+
+/// The options of Helix modelines.
+pub const OPTIONS: &[&str] = &[
+    "language",
+    "indent",
+    "tab-width",
+    "line-ending",
+    "text-width",
+    "soft-wrap",
+];
+
+/// Settings declared by the modelines of a file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Modeline {
+    /// The name of the language as written in the modeline, such as Vim's `filetype`.
+    pub language: Option<String>,
+    pub indent_style: Option<IndentStyle>,
+    pub tab_width: Option<NonZeroU8>,
+    pub line_ending: Option<LineEnding>,
+    pub text_width: Option<NonZeroU16>,
+    pub soft_wrap: Option<bool>,
+}
+
+impl Modeline {
+    /// Reads the modelines in the first and last lines of the text. Settings of later modelines
+    /// override earlier ones.
+    pub fn parse(text: RopeSlice) -> Self {
+        let mut modeline = Self::default();
+        let len = text.len_lines();
+        let first = 0..len.min(LINES);
+        let last = len.saturating_sub(LINES).max(first.end)..len;
+        for line in first.chain(last) {
+            let line: Cow<str> = text.line(line).into();
+            modeline.parse_line(&line);
+        }
+        modeline
+    }
+
+    fn parse_line(&mut self, line: &str) {
+        static HX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:^|\s)hx:(.*)").unwrap());
+        static VIM: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"(?:^|\s)(?:vi|vim|ex):\s*(?:(?:set?)\s+([^:]*):|(.*))").unwrap()
+        });
+        static EMACS: Lazy<Regex> = Lazy::new(|| Regex::new(r"-\*-(.*?)-\*-").unwrap());
+
+        if let Some(captures) = HX.captures(line) {
+            self.parse_hx(&captures[1]);
+        } else if let Some(captures) = VIM.captures(line) {
+            match (captures.get(1), captures.get(2)) {
+                (Some(options), _) => self.parse_vim(options.as_str().split_whitespace()),
+                (_, Some(options)) => self.parse_vim(
+                    options
+                        .as_str()
+                        .split(|ch: char| ch == ':' || ch.is_whitespace()),
+                ),
+                _ => (),
+            }
+        } else if let Some(captures) = EMACS.captures(line) {
+            self.parse_emacs(&captures[1]);
+        }
+    }
+
+    fn parse_hx(&mut self, options: &str) {
+        for (key, value) in options
+            .split_whitespace()
+            .filter_map(|opt| opt.split_once('='))
+        {
+            if let Err(err) = self.set(key, value) {
+                log::debug!("ignoring modeline option: {err}");
+            }
+        }
+    }
+
+    /// Sets one of the [`OPTIONS`] as written in a Helix modeline.
+    pub fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        let invalid = || anyhow!("invalid value `{value}` for `{key}`");
+        match key {
+            "language" => self.language = Some(value.to_owned()),
+            "indent" if value == "tabs" => self.indent_style = Some(IndentStyle::Tabs),
+            "indent" => self.indent_style = Some(parse_spaces(value).ok_or_else(invalid)?),
+            "tab-width" => self.tab_width = Some(value.parse().map_err(|_| invalid())?),
+            "line-ending" => {
+                self.line_ending = Some(match value {
+                    "lf" => LineEnding::LF,
+                    "crlf" => LineEnding::Crlf,
+                    _ => bail!(invalid()),
+                })
+            }
+            "text-width" => self.text_width = Some(value.parse().map_err(|_| invalid())?),
+            "soft-wrap" => self.soft_wrap = Some(value.parse().map_err(|_| invalid())?),
+            _ => bail!("unknown option `{key}`"),
+        }
+        Ok(())
+    }
+
+    fn parse_vim<'a>(&mut self, options: impl Iterator<Item = &'a str>) {
+        let mut expand_tab = None;
+        let mut shift_width = None;
+        for option in options {
+            let (key, value) = option.split_once('=').unwrap_or((option, ""));
+            match key {
+                "ts" | "tabstop" => self.tab_width = value.parse().ok(),
+                "sw" | "shiftwidth" => shift_width = value.parse::<u8>().ok(),
+                "et" | "expandtab" => expand_tab = Some(true),
+                "noet" | "noexpandtab" => expand_tab = Some(false),
+                "ft" | "filetype" => self.language = Some(value.to_owned()),
+                "ff" | "fileformat" => {
+                    self.line_ending = match value {
+                        "unix" => Some(LineEnding::LF),
+                        "dos" => Some(LineEnding::Crlf),
+                        _ => None,
+                    }
+                }
+                "tw" | "textwidth" => self.text_width = value.parse().ok(),
+                "wrap" => self.soft_wrap = Some(true),
+                "nowrap" => self.soft_wrap = Some(false),
+                _ => (),
+            }
+        }
+        // A shift width of 0 means the tab width.
+        let width = shift_width
+            .filter(|&width| width > 0)
+            .or(self.tab_width.map(NonZeroU8::get));
+        match expand_tab {
+            Some(true) => {
+                if let Some(width) = width {
+                    self.indent_style = Some(IndentStyle::Spaces(width.min(MAX_INDENT)));
+                }
+            }
+            Some(false) => self.indent_style = Some(IndentStyle::Tabs),
+            None => (),
+        }
+    }
+
+    fn parse_emacs(&mut self, variables: &str) {
+        let variables = variables.trim();
+        if !variables.contains(':') {
+            self.language = Some(variables.to_lowercase());
+            return;
+        }
+        let mut indent_tabs = None;
+        let mut offset = None;
+        for variable in variables.split(';') {
+            let Some((key, value)) = variable.split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "mode" => self.language = Some(value.to_lowercase()),
+                "indent-tabs-mode" => indent_tabs = Some(value != "nil"),
+                "tab-width" => self.tab_width = value.parse().ok(),
+                "fill-column" => self.text_width = value.parse().ok(),
+                "truncate-lines" => self.soft_wrap = Some(value == "nil"),
+                key if key.ends_with("basic-offset")
+                    || key.ends_with("indent-offset")
+                    || key.ends_with("indent-level") =>
+                {
+                    offset = value.parse::<u8>().ok().filter(|&offset| offset > 0);
+                }
+                _ => (),
+            }
+        }
+        match indent_tabs {
+            Some(true) => self.indent_style = Some(IndentStyle::Tabs),
+            Some(false) => {
+                if let Some(width) = offset.or(self.tab_width.map(NonZeroU8::get)) {
+                    self.indent_style = Some(IndentStyle::Spaces(width.min(MAX_INDENT)));
+                }
+            }
+            None => (),
+        }
+    }
+}
+
+fn parse_spaces(value: &str) -> Option<IndentStyle> {
+    let spaces = value.parse::<u8>().ok().filter(|&spaces| spaces > 0)?;
+    Some(IndentStyle::Spaces(spaces.min(MAX_INDENT)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ropey::Rope;
+
+    fn parse(text: &str) -> Modeline {
+        Modeline::parse(Rope::from(text).slice(..))
+    }
+
+    #[test]
+    fn vim() {
+        let expected = Modeline {
+            language: Some("python".to_owned()),
+            indent_style: Some(IndentStyle::Spaces(4)),
+            tab_width: NonZeroU8::new(8),
+            text_width: NonZeroU16::new(79),
+            soft_wrap: Some(false),
+            ..Modeline::default()
+        };
+        assert_eq!(
+            parse("# vim: set ft=python ts=8 sw=4 et tw=79 nowrap :\nimport os\n"),
+            expected
+        );
+        assert_eq!(
+            parse("import os\n# vim: ft=python:ts=8:sw=4:expandtab:tw=79:nowrap\n"),
+            expected
+        );
+        assert_eq!(
+            parse("/* vi: noet ff=dos */"),
+            Modeline {
+                indent_style: Some(IndentStyle::Tabs),
+                line_ending: Some(LineEnding::Crlf),
+                ..Modeline::default()
+            }
+        );
+        // `vim:` must not be part of a word.
+        assert_eq!(parse("novim: ts=2"), Modeline::default());
+    }
+
+    #[test]
+    fn emacs() {
+        assert_eq!(
+            parse("// -*- mode: C++; indent-tabs-mode: nil; c-basic-offset: 2 -*-\n"),
+            Modeline {
+                language: Some("c++".to_owned()),
+                indent_style: Some(IndentStyle::Spaces(2)),
+                ..Modeline::default()
+            }
+        );
+        assert_eq!(
+            parse("#!/bin/sh\n# -*- Shell-Script -*-\n"),
+            Modeline {
+                language: Some("shell-script".to_owned()),
+                ..Modeline::default()
+            }
+        );
+    }
+
+    #[test]
+    fn helix() {
+        assert_eq!(
+            parse("<!-- hx: language=markdown indent=tabs soft-wrap=true unknown=1 -->"),
+            Modeline {
+                language: Some("markdown".to_owned()),
+                indent_style: Some(IndentStyle::Tabs),
+                soft_wrap: Some(true),
+                ..Modeline::default()
+            }
+        );
+        assert_eq!(
+            parse("# hx: indent=2 line-ending=lf text-width=72"),
+            Modeline {
+                indent_style: Some(IndentStyle::Spaces(2)),
+                line_ending: Some(LineEnding::LF),
+                text_width: NonZeroU16::new(72),
+                ..Modeline::default()
+            }
+        );
+    }
+
+    #[test]
+    fn set() {
+        let mut modeline = Modeline::default();
+        modeline.set("tab-width", "2").unwrap();
+        modeline.set("indent", "tabs").unwrap();
+        assert!(modeline.set("tab-width", "0").is_err());
+        assert!(modeline.set("line-ending", "cr").is_err());
+        assert!(modeline.set("width", "2").is_err());
+        assert_eq!(
+            modeline,
+            Modeline {
+                indent_style: Some(IndentStyle::Tabs),
+                tab_width: NonZeroU8::new(2),
+                ..Modeline::default()
+            }
+        );
+    }
+
+    #[test]
+    fn only_first_and_last_lines() {
+        let mut text = "x\n".repeat(5);
+        text.push_str("# vim: ts=2\n");
+        text.push_str(&"x\n".repeat(5));
+        assert_eq!(parse(&text), Modeline::default());
+        text.push_str("# vim: ts=3\n");
+        assert_eq!(parse(&text).tab_width, NonZeroU8::new(3));
+    }
+}
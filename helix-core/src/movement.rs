@@ -125,11 +125,8 @@ pub fn move_vertically(
     new_row = new_row.max(visual_pos.row as u32);
     let line_idx = slice.char_to_line(pos);
 
-    // Compute the new position.
-    let mut new_line_idx = match dir {
-        Direction::Forward => line_idx.saturating_add(count),
-        Direction::Backward => line_idx.saturating_sub(count),
-    };
+    // Compute the new position, skipping the lines hidden by folds.
+    let mut new_line_idx = annotations.visible_line_offset(line_idx, count, dir, slice.len_lines());
 
     let line = if new_line_idx >= slice.len_lines() - 1 {
         // there is no line terminator for the last line
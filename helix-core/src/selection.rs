@@ -7,10 +7,10 @@
         ensure_grapheme_boundary_next, ensure_grapheme_boundary_prev, next_grapheme_boundary,
         prev_grapheme_boundary,
     },
-    line_ending::get_line_ending,
+    line_ending::{get_line_ending, line_end_char_index},
     movement::Direction,
     tree_sitter::Node,
-    Assoc, ChangeSet, RopeSlice,
+    Assoc, ChangeSet, Position, RopeSlice,
 };
 use helix_stdx::range::is_subset;
 use helix_stdx::rope::{self, RopeSliceExt};
@@ -879,6 +879,54 @@ pub fn split_on_matches(text: RopeSlice, selection: &Selection, regex: &rope::Re
     Selection::new(result, 0)
 }
 
+/// Selects the rectangle of text spanned by the visual coordinates `anchor` and `head`, with a
+/// range for each line. Both columns are part of the rectangle.
+///
+/// Lines which end before the left edge of the rectangle are skipped, except for the line of
+/// `head`, whose range is primary. The ranges face towards the column of `head`.
+#[allow(deprecated)]
+pub fn select_block(
+    text: RopeSlice,
+    anchor: Position,
+    head: Position,
+    tab_width: usize,
+) -> Selection {
+    use crate::{pos_at_visual_coords, visual_coords_at_pos};
+
+    let last_line = text.len_lines().saturating_sub(1);
+    let (left, right) = (anchor.col.min(head.col), anchor.col.max(head.col));
+    let backward = head.col < anchor.col;
+    let mut ranges = SmallVec::new();
+    let mut primary_index = 0;
+    for line in anchor.row.min(head.row)..=anchor.row.max(head.row).min(last_line) {
+        let line_end = line_end_char_index(&text, line);
+        let start = pos_at_visual_coords(text, Position::new(line, left), tab_width);
+        if line != head.row
+            && start == line_end
+            && visual_coords_at_pos(text, start, tab_width).col < left
+        {
+            continue;
+        }
+        let end = pos_at_visual_coords(text, Position::new(line, right), tab_width);
+        let end = next_grapheme_boundary(text, end).min(line_end);
+        let end = end.max(start);
+        let mut range = if backward {
+            Range::new(end, start)
+        } else {
+            Range::new(start, end)
+        };
+        if line == head.row {
+            primary_index = ranges.len();
+            // Vertical motions keep the column of the head when its line is short.
+            if visual_coords_at_pos(text, range.cursor(text), tab_width).col < head.col {
+                range.old_visual_position = Some((0, head.col as u32));
+            }
+        }
+        ranges.push(range);
+    }
+    Selection::new(ranges, primary_index)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1393,4 +1441,46 @@ fn contains(a: Vec<(usize, usize)>, b: Vec<(usize, usize)>) -> bool {
             vec!((1, 2), (3, 4), (7, 9))
         ));
     }
+
+    #[test]
+    fn test_select_block() {
+        let text = Rope::from("abcdef\nab\n\tx\nabcdef\n");
+        let text = text.slice(..);
+        let ranges = |selection: Selection| {
+            let primary = selection.primary_index();
+            let ranges: Vec<_> = selection.iter().map(|r| (r.anchor, r.head)).collect();
+            (ranges, primary)
+        };
+
+        // The second line ends at the left edge and the tab covers the columns of the third.
+        assert_eq!(
+            ranges(select_block(
+                text,
+                Position::new(0, 2),
+                Position::new(3, 3),
+                4
+            )),
+            (vec![(2, 4), (9, 9), (10, 11), (15, 17)], 3)
+        );
+        // The second line is too short.
+        assert_eq!(
+            ranges(select_block(
+                text,
+                Position::new(0, 3),
+                Position::new(2, 3),
+                4
+            )),
+            (vec![(3, 4), (10, 11)], 1)
+        );
+        // Towards the column of the head, which is on a short line.
+        assert_eq!(
+            ranges(select_block(
+                text,
+                Position::new(0, 4),
+                Position::new(1, 1),
+                4
+            )),
+            (vec![(5, 1), (9, 8)], 1)
+        );
+    }
 }
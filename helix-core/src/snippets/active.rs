@@ -6,9 +6,9 @@
 use ropey::Rope;
 
 use crate::movement::Direction;
-use crate::snippets::render::{RenderedSnippet, Tabstop};
+use crate::snippets::render::{RenderedSnippet, Tabstop, TabstopKind};
 use crate::snippets::TabstopIdx;
-use crate::{Assoc, ChangeSet, Selection, Transaction};
+use crate::{selection, Assoc, ChangeSet, Selection, Tendril, Transaction};
 
 pub struct ActiveSnippet {
     ranges: Vec<Range>,
@@ -59,6 +59,41 @@ pub fn delete_placeholder(&self, doc: &Rope) -> Transaction {
         )
     }
 
+    /// The choices of the current tabstop, if it is a choice like `${1|a,b|}`.
+    pub fn current_choices(&self) -> Option<&[Tendril]> {
+        match &self[self.current_tabstop].kind {
+            TabstopKind::Choice { choices } => Some(choices),
+            _ => None,
+        }
+    }
+
+    /// Replaces the text of the current tabstop with `choice` and selects it.
+    pub fn choose(&self, doc: &Rope, current_selection: &Selection, choice: &str) -> Transaction {
+        let primary_idx = self.primary_idx(current_selection);
+        let tabstop = &self[self.current_tabstop];
+        let len = choice.chars().count();
+        // the ranges are sorted so each one is shifted by the length changes before it
+        let mut shift = 0isize;
+        let ranges = tabstop
+            .ranges
+            .iter()
+            .map(|range| {
+                let start = range.start.saturating_add_signed(shift);
+                shift += len as isize - (range.end - range.start) as isize;
+                selection::Range::new(start, start + len)
+            })
+            .collect();
+        let primary = primary_idx * (tabstop.ranges.len() / self.ranges.len());
+        Transaction::change(
+            doc,
+            tabstop
+                .ranges
+                .iter()
+                .map(|range| (range.start, range.end, Some(choice.into()))),
+        )
+        .with_selection(Selection::new(ranges, primary))
+    }
+
     /// maps the active snippets through a `ChangeSet` updating all tabstop ranges
     pub fn map(&mut self, changes: &ChangeSet) -> bool {
         let positions_to_map = self.ranges.iter_mut().flat_map(|range| {
@@ -230,9 +265,11 @@ mod tests {
     use std::iter::{self};
 
     use ropey::Rope;
+    use smallvec::smallvec;
 
+    use crate::movement::Direction;
     use crate::snippets::{ActiveSnippet, Snippet, SnippetRenderCtx};
-    use crate::{Selection, Transaction};
+    use crate::{Range, Selection, Transaction};
 
     #[test]
     fn fully_remove() {
@@ -269,4 +306,41 @@ fn tabstop_zero_with_placeholder() {
         assert_eq!(doc, "sizeof()\n");
         assert!(ActiveSnippet::new(snippet).is_none());
     }
+
+    #[test]
+    fn choose() {
+        let snippet = Snippet::parse("${1|one,three|} ${1} $0").unwrap();
+        let mut doc = Rope::from("\n\n");
+        let selection = Selection::new(smallvec![Range::point(0), Range::point(1)], 1);
+        let (transaction, _, snippet) = snippet.render(
+            &doc,
+            &selection,
+            |range| (range.from(), range.to()),
+            &mut SnippetRenderCtx::test_ctx(),
+        );
+        assert!(transaction.apply(&mut doc));
+        assert_eq!(doc, "one one \none one \n");
+        let selection = snippet.first_selection(Direction::Forward, 1);
+        let mut snippet = ActiveSnippet::new(snippet).unwrap();
+        assert_eq!(
+            snippet.current_choices(),
+            Some(&["one".into(), "three".into()][..])
+        );
+        let transaction = snippet.choose(&doc, &selection, "three");
+        assert!(transaction.apply(&mut doc));
+        snippet.map(transaction.changes());
+        assert_eq!(doc, "three three \nthree three \n");
+        assert_eq!(
+            transaction.selection(),
+            Some(&Selection::new(
+                smallvec![
+                    Range::new(0, 5),
+                    Range::new(6, 11),
+                    Range::new(13, 18),
+                    Range::new(19, 24)
+                ],
+                2
+            ))
+        );
+    }
 }
@@ -287,6 +287,11 @@ fn render_tabstop(&mut self, tabstop: TabstopIdx) {
                 self.dst[tabstop].kind = TabstopKind::Placeholder;
                 self.off
             }
+            // the first choice is inserted until another one is picked
+            elaborate::TabstopKind::Choice { choices } if !choices.is_empty() => {
+                self.push_multiline_str(&choices[0]);
+                self.off
+            }
             _ => start,
         };
         self.dst[tabstop].ranges.push(Range { start, end });
@@ -352,4 +357,26 @@ fn rust_macro() {
             ],
         );
     }
+
+    #[test]
+    fn choice() {
+        assert_snippet(
+            "let ${1|mut,ref|} = $1;",
+            "let mut = mut;",
+            &[
+                Tabstop {
+                    ranges: vec![Range { start: 4, end: 7 }, Range { start: 10, end: 13 }].into(),
+                    parent: None,
+                    kind: TabstopKind::Choice {
+                        choices: vec!["mut".into(), "ref".into()].into(),
+                    },
+                },
+                Tabstop {
+                    ranges: vec![Range { start: 14, end: 14 }].into(),
+                    parent: None,
+                    kind: TabstopKind::Empty,
+                },
+            ],
+        );
+    }
 }
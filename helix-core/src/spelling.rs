@@ -0,0 +1,644 @@
+//! Spell checking with hunspell dictionaries.
+//!
+//! A dictionary is made of two files: the `.dic` file lists the words, each followed by the
+//! flags of the affixes which may be added to it, and the `.aff` file defines the prefixes and
+//! suffixes of each flag. The words with all their affixes are expanded once when the dictionary
+//! is parsed. Compounding, morphological fields and the other options of the `.aff` file are
+//! ignored.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    sync::Arc,
+};
+
+use ropey::RopeSlice;
+
+use crate::{chars::char_is_word, syntax::Syntax, tree_sitter::Tree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagFormat {
+    /// Each character is a flag, the default.
+    Char,
+    /// Each pair of characters is a flag.
+    Long,
+    /// The flags are decimal numbers separated by commas.
+    Num,
+}
+
+type Flag = u64;
+
+impl FlagFormat {
+    fn parse(self, flags: &str) -> Vec<Flag> {
+        match self {
+            Self::Char => flags.chars().map(|ch| ch as Flag).collect(),
+            Self::Long => {
+                let chars: Vec<_> = flags.chars().collect();
+                chars
+                    .chunks(2)
+                    .map(|pair| pair.iter().fold(0, |flag, &ch| flag << 21 | ch as Flag))
+                    .collect()
+            }
+            Self::Num => flags
+                .split(',')
+                .filter_map(|flag| flag.trim().parse().ok())
+                .collect(),
+        }
+    }
+}
+
+/// A character of the condition of an affix.
+#[derive(Debug, PartialEq, Eq)]
+enum Condition {
+    Any,
+    Char(char),
+    Set { negated: bool, chars: Vec<char> },
+}
+
+impl Condition {
+    fn parse(condition: &str) -> Vec<Self> {
+        if condition == "." {
+            return Vec::new();
+        }
+        let mut conditions = Vec::new();
+        let mut chars = condition.chars();
+        while let Some(ch) = chars.next() {
+            conditions.push(match ch {
+                '.' => Self::Any,
+                '[' => {
+                    let mut set: Vec<_> = chars.by_ref().take_while(|&ch| ch != ']').collect();
+                    let negated = set.first() == Some(&'^');
+                    if negated {
+                        set.remove(0);
+                    }
+                    Self::Set {
+                        negated,
+                        chars: set,
+                    }
+                }
+                ch => Self::Char(ch),
+            });
+        }
+        conditions
+    }
+
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Char(expected) => ch == *expected,
+            Self::Set { negated, chars } => chars.contains(&ch) != *negated,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Affix {
+    prefix: bool,
+    /// Whether the affix may be combined with affixes of the other kind.
+    cross: bool,
+    strip: String,
+    add: String,
+    condition: Vec<Condition>,
+}
+
+impl Affix {
+    fn apply(&self, word: &str) -> Option<String> {
+        let chars: Vec<_> = word.chars().collect();
+        if chars.len() < self.condition.len() {
+            return None;
+        }
+        let matches = if self.prefix {
+            word.starts_with(&self.strip)
+                && self
+                    .condition
+                    .iter()
+                    .zip(&chars)
+                    .all(|(condition, &ch)| condition.matches(ch))
+        } else {
+            word.ends_with(&self.strip)
+                && self
+                    .condition
+                    .iter()
+                    .rev()
+                    .zip(chars.iter().rev())
+                    .all(|(condition, &ch)| condition.matches(ch))
+        };
+        if !matches || word.len() == self.strip.len() {
+            return None;
+        }
+        Some(if self.prefix {
+            format!("{}{}", self.add, &word[self.strip.len()..])
+        } else {
+            format!("{}{}", &word[..word.len() - self.strip.len()], self.add)
+        })
+    }
+}
+
+/// The affixes of an `.aff` file by flag.
+#[derive(Debug, Default)]
+struct Affixes {
+    format: Option<FlagFormat>,
+    affixes: HashMap<Flag, Vec<Affix>>,
+}
+
+impl Affixes {
+    fn parse(aff: &str) -> Self {
+        let mut affixes = Self::default();
+        // The number of rules which follow the last header.
+        let mut rules = 0;
+        let mut cross = false;
+        for line in aff.lines() {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", format, ..] => {
+                    affixes.format = match *format {
+                        "long" => Some(FlagFormat::Long),
+                        "num" => Some(FlagFormat::Num),
+                        _ => Some(FlagFormat::Char),
+                    }
+                }
+                ["PFX" | "SFX", _, cross_product, count] if rules == 0 => {
+                    cross = *cross_product == "Y";
+                    rules = count.parse().unwrap_or(0);
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] if rules > 0 => {
+                    rules -= 1;
+                    let empty = |affix: &str| if affix == "0" { "" } else { affix }.to_owned();
+                    // The flags of affixes which may follow this one aren't supported.
+                    let add = add.split('/').next().unwrap_or_default();
+                    let Some(flag) = affixes.format().parse(flag).first().copied() else {
+                        continue;
+                    };
+                    affixes.affixes.entry(flag).or_default().push(Affix {
+                        prefix: *kind == "PFX",
+                        cross,
+                        strip: empty(strip),
+                        add: empty(add),
+                        condition: Condition::parse(rest.first().unwrap_or(&".")),
+                    });
+                }
+                _ => (),
+            }
+        }
+        affixes
+    }
+
+    fn format(&self) -> FlagFormat {
+        self.format.unwrap_or(FlagFormat::Char)
+    }
+
+    /// Adds `word` and its forms with the affixes of `flags` to `words`.
+    fn expand(&self, word: &str, flags: &[Flag], words: &mut HashSet<String>) {
+        let affixes = || {
+            flags
+                .iter()
+                .filter_map(|flag| self.affixes.get(flag))
+                .flatten()
+        };
+        let mut suffixed = Vec::new();
+        for suffix in affixes().filter(|affix| !affix.prefix) {
+            if let Some(form) = suffix.apply(word) {
+                if suffix.cross {
+                    suffixed.push(form.clone());
+                }
+                words.insert(form);
+            }
+        }
+        for prefix in affixes().filter(|affix| affix.prefix) {
+            words.extend(prefix.apply(word));
+            if prefix.cross {
+                words.extend(suffixed.iter().filter_map(|form| prefix.apply(form)));
+            }
+        }
+        words.insert(word.to_owned());
+    }
+}
+
+/// The words of one or more dictionaries and those added by the user.
+#[derive(Debug, Clone, Default)]
+pub struct Dictionary {
+    words: Arc<Words>,
+    added: HashSet<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Words {
+    words: HashSet<Arc<str>>,
+    /// The words by their number of characters, so that suggestions are only compared to the
+    /// words of similar lengths.
+    by_length: Vec<Vec<Arc<str>>>,
+}
+
+impl Words {
+    fn insert(&mut self, word: String) {
+        if self.words.contains(word.as_str()) {
+            return;
+        }
+        let word: Arc<str> = word.into();
+        let len = word.chars().count();
+        if self.by_length.len() <= len {
+            self.by_length.resize_with(len + 1, Vec::new);
+        }
+        self.by_length[len].push(word.clone());
+        self.words.insert(word);
+    }
+}
+
+impl Dictionary {
+    /// Parses the `.aff` and `.dic` files of a dictionary.
+    pub fn parse(aff: &str, dic: &str) -> Self {
+        let mut dictionary = Self::default();
+        dictionary.extend(aff, dic);
+        dictionary
+    }
+
+    /// Adds the words of the `.aff` and `.dic` files of another dictionary.
+    pub fn extend(&mut self, aff: &str, dic: &str) {
+        let affixes = Affixes::parse(aff);
+        let words = Arc::make_mut(&mut self.words);
+        let mut forms = HashSet::new();
+        // The first line is the number of words.
+        for line in dic.lines().skip(1) {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = entry.split_once('/').unwrap_or((entry, ""));
+            affixes.expand(word, &affixes.format().parse(flags), &mut forms);
+            for form in forms.drain() {
+                words.insert(form);
+            }
+        }
+    }
+
+    /// Adds a word of the user, returning whether it's new.
+    pub fn add(&mut self, word: &str) -> bool {
+        !self.check(word) && self.added.insert(word.to_owned())
+    }
+
+    fn contains(&self, word: &str) -> bool {
+        self.words.words.contains(word) || self.added.contains(word)
+    }
+
+    /// Whether `word` is spelled correctly. Capitalized and uppercase words are also correct when
+    /// their lowercase form is.
+    pub fn check(&self, word: &str) -> bool {
+        let word = word.replace('’', "'");
+        if self.contains(&word) {
+            return true;
+        }
+        let mut chars = word.chars();
+        let Some(first) = chars.next() else {
+            return true;
+        };
+        let capitalized = first.is_uppercase() && chars.clone().all(|ch| !ch.is_uppercase());
+        let uppercase = word.chars().all(|ch| !ch.is_lowercase());
+        if capitalized {
+            return self.contains(&word.to_lowercase());
+        }
+        uppercase
+            && (self.contains(&word.to_lowercase())
+                || self.contains(&capitalize(&word.to_lowercase())))
+    }
+
+    /// The at most `limit` words closest to `word`, closest first, in the case of `word`.
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        const MAX_DISTANCE: usize = 2;
+
+        let lowercase: Vec<char> = word.chars().flat_map(char::to_lowercase).collect();
+        let len = word.chars().count();
+        let by_length = &self.words.by_length;
+        let end = (len + MAX_DISTANCE + 1).min(by_length.len());
+        let mut chars = Vec::new();
+        let mut suggestions: Vec<(usize, &str)> = by_length
+            .get(len.saturating_sub(MAX_DISTANCE)..end)
+            .unwrap_or_default()
+            .iter()
+            .flatten()
+            .map(AsRef::as_ref)
+            .chain(self.added.iter().map(String::as_str))
+            .filter_map(|candidate| {
+                chars.clear();
+                chars.extend(candidate.chars().flat_map(char::to_lowercase));
+                let distance = distance(&lowercase, &chars, MAX_DISTANCE)?;
+                Some((distance, candidate))
+            })
+            .collect();
+        suggestions.sort_unstable();
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        let mut seen = HashSet::new();
+        suggestions
+            .into_iter()
+            .map(|(_, suggestion)| match capitalized {
+                true => capitalize(suggestion),
+                false => suggestion.to_owned(),
+            })
+            .filter(|suggestion| suggestion != word && seen.insert(suggestion.clone()))
+            .take(limit)
+            .collect()
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// The number of insertions, deletions, substitutions and transpositions of adjacent characters
+/// which turn `a` into `b`, if it's at most `max`.
+fn distance(a: &[char], b: &[char], max: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let mut previous: Vec<usize> = Vec::new();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let before_previous = std::mem::replace(&mut previous, row.clone());
+        row[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (previous[j] + 1)
+                .min(row[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(before_previous[j - 2] + 1);
+            }
+        }
+        if row.iter().all(|&distance| distance > max) {
+            return None;
+        }
+    }
+    Some(row[b.len()]).filter(|&distance| distance <= max)
+}
+
+/// Whether a word next to `ch` is part of something other than prose, like a path, an URL, an
+/// escape sequence or a qualified name.
+fn is_code_boundary(ch: char) -> bool {
+    matches!(
+        ch,
+        '\\' | '/' | '@' | '.' | ':' | '$' | '%' | '#' | '&' | '='
+    )
+}
+
+/// The ranges of the words within `range` of `text` which are spell checked. Identifiers, like
+/// words with digits, underscores or uppercase letters after the first, and words which are part
+/// of paths, URLs or escape sequences are skipped.
+pub fn words(text: RopeSlice, range: Range<usize>) -> Vec<Range<usize>> {
+    let is_part = |ch: char| char_is_word(ch) || ch == '\'' || ch == '’';
+    let mut words = Vec::new();
+    let mut pos = range.start;
+    let mut chars = text.chars_at(pos).peekable();
+    while pos < range.end {
+        let Some(ch) = chars.next() else {
+            break;
+        };
+        if !char_is_word(ch) {
+            pos += 1;
+            continue;
+        }
+        let start = pos;
+        let mut word = String::from(ch);
+        pos += 1;
+        while pos < range.end {
+            match chars.peek() {
+                Some(&ch) if is_part(ch) => {
+                    word.push(ch);
+                    chars.next();
+                    pos += 1;
+                }
+                _ => break,
+            }
+        }
+        // Apostrophes are only part of words between letters.
+        let trimmed = word.trim_end_matches(['\'', '’']);
+        let end = start + trimmed.chars().count();
+        let before = start.checked_sub(1).map(|pos| text.char(pos));
+        let after = (pos < text.len_chars()).then(|| text.char(pos));
+        let is_identifier = trimmed.chars().count() < 2
+            || trimmed.chars().any(|ch| ch.is_numeric() || ch == '_')
+            || trimmed.chars().skip(1).any(char::is_uppercase);
+        // A period or colon after a word ends it, unless it's followed by more of a name.
+        let is_punctuation = |ch| {
+            matches!(ch, '.' | ':')
+                && text
+                    .get_char(pos + 1)
+                    .is_none_or(|next| !char_is_word(next) && !matches!(next, '/' | ':'))
+        };
+        let is_code = before.is_some_and(is_code_boundary)
+            || after.is_some_and(|ch| is_code_boundary(ch) && !is_punctuation(ch));
+        if !is_identifier && !is_code {
+            words.push(start..end);
+        }
+    }
+    words
+}
+
+/// The misspelled words within `ranges` of `text`.
+pub fn misspellings(
+    dictionary: &Dictionary,
+    text: RopeSlice,
+    ranges: &[Range<usize>],
+) -> Vec<Range<usize>> {
+    ranges
+        .iter()
+        .flat_map(|range| words(text, range.clone()))
+        .filter(|word| !dictionary.check(&text.slice(word.clone()).to_string()))
+        .collect()
+}
+
+/// The syntax trees of the layers of `syntax` which are spell checked by [checked_ranges]. The
+/// trees are cheap to clone, so that the ranges can be found away from the syntax.
+pub fn checked_trees(syntax: &Syntax, prose: bool) -> Vec<Tree> {
+    // The code of prose is excluded from the whole text, which is only checked once.
+    if prose {
+        return syntax
+            .layer(syntax.root_layer())
+            .tree()
+            .cloned()
+            .into_iter()
+            .collect();
+    }
+    let mut trees = Vec::new();
+    let mut layers = vec![syntax.root_layer()];
+    let mut visited = HashSet::new();
+    while let Some(layer) = layers.pop() {
+        if !visited.insert(layer) {
+            continue;
+        }
+        let data = syntax.layer(layer);
+        layers.extend(
+            data.injections_at_byte_idx(0)
+                .map(|injection| injection.layer),
+        );
+        trees.extend(data.tree().cloned());
+    }
+    trees
+}
+
+/// The ranges of `text` which are spell checked: its comments and strings, including those of
+/// injected languages, or all of it except for code when it's prose, like markup. The `trees`
+/// are the [checked_trees] of the syntax of `text`, if it has one.
+pub fn checked_ranges(trees: Option<&[Tree]>, text: RopeSlice, prose: bool) -> Vec<Range<usize>> {
+    let Some(trees) = trees else {
+        #[allow(clippy::single_range_in_vec_init)]
+        return if prose {
+            vec![0..text.len_chars()]
+        } else {
+            Vec::new()
+        };
+    };
+    let is_code = |kind: &str| kind.contains("code");
+    let is_checked = |kind: &str| kind.contains("comment") || kind.contains("string");
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for tree in trees {
+        let mut nodes = vec![tree.root_node()];
+        while let Some(node) = nodes.pop() {
+            let matched = if prose {
+                is_code(node.kind())
+            } else {
+                is_checked(node.kind())
+            };
+            if matched {
+                let range = node.byte_range();
+                ranges.push(
+                    text.byte_to_char(range.start as usize)..text.byte_to_char(range.end as usize),
+                );
+            } else {
+                nodes.extend(node.children());
+            }
+        }
+    }
+    ranges.sort_unstable_by_key(|range| (range.start, range.end));
+
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    if !prose {
+        return merged;
+    }
+    let mut checked = Vec::with_capacity(merged.len() + 1);
+    let mut start = 0;
+    for code in merged {
+        checked.push(start..code.start);
+        start = code.end;
+    }
+    checked.push(start..text.len_chars());
+    checked.retain(|range| !range.is_empty());
+    checked
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Rope;
+
+    const AFF: &str = "SET UTF-8
+TRY esianrtolcdugmphbyfvkwz'
+
+PFX U Y 1
+PFX U   0     un         .
+
+SFX S Y 4
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+SFX S   0     es         [sxzh]
+SFX S   0     s          [^sxzhy]
+
+SFX D Y 2
+SFX D   0     d          e
+SFX D   0     ed         [^e]
+";
+
+    const DIC: &str = "5
+helix
+lock/USD
+city/S
+box/S
+mistake/D
+";
+
+    #[test]
+    fn expand_affixes() {
+        let dictionary = Dictionary::parse(AFF, DIC);
+        for word in [
+            "helix", "lock", "locks", "locked", "unlock", "unlocks", "unlocked", "city", "cities",
+            "box", "boxes", "mistaked", "Helix", "HELIX", "Unlocked",
+        ] {
+            assert!(dictionary.check(word), "{word}");
+        }
+        for word in ["helixes", "citys", "unbox", "lockes", "hELIX", "unmistake"] {
+            assert!(!dictionary.check(word), "{word}");
+        }
+
+        let mut dictionary = dictionary;
+        assert!(dictionary.add("kakoune"));
+        assert!(!dictionary.add("box"));
+        assert!(dictionary.check("Kakoune"));
+    }
+
+    #[test]
+    fn long_flags() {
+        let aff = "FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n";
+        let dictionary = Dictionary::parse(aff, "1\nword/AaBb\n");
+        assert!(dictionary.check("words"));
+    }
+
+    #[test]
+    fn suggest() {
+        let dictionary = Dictionary::parse(AFF, DIC);
+        assert_eq!(dictionary.suggest("cites", 3), ["cities", "city"]);
+        assert_eq!(dictionary.suggest("Hleix", 1), ["Helix"]);
+        assert_eq!(dictionary.suggest("unlokced", 5), ["unlocked"]);
+        assert!(dictionary.suggest("xyz", 5).is_empty());
+        assert!(dictionary.suggest("unlockedboxes", 5).is_empty());
+
+        let mut dictionary = dictionary;
+        dictionary.add("kakoune");
+        assert_eq!(dictionary.suggest("kakuone", 5), ["kakoune"]);
+    }
+
+    #[test]
+    fn checked_words() {
+        let text = Rope::from(
+            "Spell these words, but not snake_case, camelCase, NASA, \\nescapes, v2, \
+            https://helix-editor.com/news or std::fs. Don't trust' it.",
+        );
+        let words: Vec<_> = words(text.slice(..), 0..text.len_chars())
+            .into_iter()
+            .map(|range| text.slice(range).to_string())
+            .collect();
+        assert_eq!(
+            words,
+            ["Spell", "these", "words", "but", "not", "or", "Don't", "trust", "it"]
+        );
+        assert_eq!(
+            words_in(&text, 6..13).len(),
+            1,
+            "words are cut at the end of the range"
+        );
+    }
+
+    fn words_in(text: &Rope, range: Range<usize>) -> Vec<Range<usize>> {
+        words(text.slice(..), range)
+    }
+
+    #[test]
+    fn misspelled() {
+        let dictionary = Dictionary::parse(AFF, DIC);
+        let text = Rope::from("helix lcoks boxes\nciti");
+        assert_eq!(
+            misspellings(&dictionary, text.slice(..), &[0..17, 18..22]),
+            [6..11, 18..22]
+        );
+        let whole = 0..text.len_chars();
+        assert_eq!(checked_ranges(None, text.slice(..), true), [whole]);
+        assert!(checked_ranges(None, text.slice(..), false).is_empty());
+    }
+}
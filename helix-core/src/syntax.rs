@@ -424,6 +424,11 @@ fn rainbow_query(&self, lang: Language) -> Option<&RainbowQuery> {
         self.language(lang).rainbow_query(self)
     }
 
+    /// Whether the language has a `rainbows.scm` query.
+    pub fn has_rainbow_query(&self, lang: Language) -> bool {
+        self.rainbow_query(lang).is_some()
+    }
+
     pub fn language_server_configs(&self) -> &HashMap<String, LanguageServerConfiguration> {
         &self.language_server_configs
     }
@@ -1046,12 +1051,23 @@ pub fn capture_nodes_any<'a>(
         capture_names: &[&str],
         node: &Node<'a>,
         slice: RopeSlice<'a>,
+    ) -> Option<impl Iterator<Item = CapturedNode<'a>>> {
+        self.capture_nodes_in_range(capture_names, node, slice, 0..u32::MAX)
+    }
+
+    /// Like [Self::capture_nodes_any] but only returns nodes which intersect the byte range.
+    pub fn capture_nodes_in_range<'a>(
+        &'a self,
+        capture_names: &[&str],
+        node: &Node<'a>,
+        slice: RopeSlice<'a>,
+        byte_range: ops::Range<u32>,
     ) -> Option<impl Iterator<Item = CapturedNode<'a>>> {
         let capture = capture_names
             .iter()
             .find_map(|cap| self.query.get_capture(cap))?;
 
-        let mut cursor = InactiveQueryCursor::new(0..u32::MAX, TREE_SITTER_MATCH_LIMIT)
+        let mut cursor = InactiveQueryCursor::new(byte_range, TREE_SITTER_MATCH_LIMIT)
             .execute_query(&self.query, node, RopeInput::new(slice));
         let capture_node = iter::from_fn(move || {
             let mat = cursor.next_match()?;
@@ -1073,6 +1089,75 @@ pub struct TagQuery {
     pub query: Query,
 }
 
+/// Compiles `source`, a query being written, for the language of the root layer of `syntax` and
+/// runs it on the whole tree. Returns the name and character range of each capture, ordered by
+/// their start and then by their length, longest first.
+pub fn query_captures(
+    syntax: &Syntax,
+    text: RopeSlice,
+    source: &str,
+) -> Result<Vec<(String, ops::Range<usize>)>, tree_sitter::query::ParseError> {
+    let root = syntax.tree().root_node();
+    // The predicates of Helix's own queries, like those of indents, are accepted.
+    let query = Query::new(root.grammar(), source, |_, _| Ok(()))?;
+    let mut cursor = InactiveQueryCursor::new(0..u32::MAX, TREE_SITTER_MATCH_LIMIT).execute_query(
+        &query,
+        &root,
+        RopeInput::new(text),
+    );
+    let mut captures = Vec::new();
+    while let Some(mat) = cursor.next_match() {
+        captures.extend(mat.matched_nodes().map(|matched| {
+            let start = text.byte_to_char(matched.node.start_byte() as usize);
+            let end = text.byte_to_char(matched.node.end_byte() as usize);
+            (query.capture_name(matched.capture).to_owned(), start..end)
+        }));
+    }
+    captures.sort_by_key(|(_, range)| (range.start, std::cmp::Reverse(range.end)));
+    captures.dedup();
+    Ok(captures)
+}
+
+/// Splits the sorted `ranges` of [query_captures] into non-overlapping ranges, where the parts of
+/// a range covered by the ranges it contains take their value.
+pub fn innermost_ranges<T: Clone>(
+    ranges: &[(T, ops::Range<usize>)],
+) -> Vec<(T, ops::Range<usize>)> {
+    let mut innermost = Vec::new();
+    // The ranges containing the current position, innermost last.
+    let mut enclosing: Vec<(&T, usize)> = Vec::new();
+    let mut pos = 0;
+    let mut push = |value: &T, range: ops::Range<usize>| {
+        if !range.is_empty() {
+            innermost.push((value.clone(), range));
+        }
+    };
+    for (value, range) in ranges {
+        while let Some(&(outer, end)) = enclosing.last() {
+            if end > range.start {
+                break;
+            }
+            push(outer, pos..end);
+            pos = pos.max(end);
+            enclosing.pop();
+        }
+        if let Some(&(outer, _)) = enclosing.last() {
+            push(outer, pos..range.start);
+        }
+        pos = pos.max(range.start);
+        // A range which isn't contained by the enclosing one ends with it.
+        let end = enclosing
+            .last()
+            .map_or(range.end, |&(_, end)| range.end.min(end));
+        enclosing.push((value, end));
+    }
+    while let Some((outer, end)) = enclosing.pop() {
+        push(outer, pos..end);
+        pos = pos.max(end);
+    }
+    innermost
+}
+
 pub fn pretty_print_tree<W: fmt::Write>(fmt: &mut W, node: Node) -> fmt::Result {
     if node.child_count() == 0 {
         if node_is_visible(&node) {
@@ -1204,6 +1289,28 @@ mod test {
 
     static LOADER: Lazy<Loader> = Lazy::new(crate::config::default_lang_loader);
 
+    #[test]
+    fn test_innermost_ranges() {
+        let ranges = [
+            ('a', 0..10),
+            ('b', 2..4),
+            ('c', 3..6),
+            ('d', 8..12),
+            ('e', 12..13),
+        ];
+        assert_eq!(
+            innermost_ranges(&ranges),
+            [
+                ('a', 0..2),
+                ('b', 2..3),
+                ('c', 3..4),
+                ('a', 4..8),
+                ('d', 8..10),
+                ('e', 12..13)
+            ]
+        );
+    }
+
     #[test]
     fn test_textobject_queries() {
         let query_str = r#"
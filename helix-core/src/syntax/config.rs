@@ -1,4 +1,4 @@
-use crate::{auto_pairs::AutoPairs, diagnostic::Severity, Language};
+use crate::{auto_pairs::AutoPairs, diagnostic::Severity, error_format::ErrorFormat, Language};
 
 use globset::GlobSet;
 use helix_stdx::rope;
@@ -63,6 +63,8 @@ pub struct LanguageConfiguration {
     pub path_completion: Option<bool>,
     /// If set, overrides `editor.word-completion`.
     pub word_completion: Option<WordCompletion>,
+    /// If set to `false`, documents of the language are never saved by `editor.auto-save`.
+    pub auto_save: Option<bool>,
 
     #[serde(default)]
     pub diagnostic_severity: Severity,
@@ -81,6 +83,14 @@ pub struct LanguageConfiguration {
         deserialize_with = "deserialize_lang_features"
     )]
     pub language_servers: Vec<LanguageServerFeatures>,
+    /// The language servers tried first for a feature, before the others in the order of
+    /// `language_servers`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub feature_priorities: HashMap<LanguageServerFeature, Vec<String>>,
+    /// The kinds of code actions applied before formatting when the document is written, such
+    /// as `source.organizeImports`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub code_actions_on_save: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub indent: Option<IndentationConfiguration>,
 
@@ -101,8 +111,19 @@ pub struct LanguageConfiguration {
     pub workspace_lsp_roots: Option<Vec<PathBuf>>,
     #[serde(default)]
     pub persistent_diagnostic_sources: Vec<String>,
+    /// Patterns used by `:make` to parse build output into diagnostics. Falls back to
+    /// [`DEFAULT_ERROR_FORMATS`](crate::error_format::DEFAULT_ERROR_FORMATS) when empty.
+    #[serde(default, skip_serializing)]
+    pub error_format: Vec<ErrorFormat>,
     /// Overrides the `editor.rainbow-brackets` config key for the language.
     pub rainbow_brackets: Option<bool>,
+    /// Overrides the `editor.minimap.enable` config key for the language.
+    pub minimap: Option<bool>,
+    /// Overrides the `editor.lsp.display-inlay-hints` config key for the language.
+    pub inlay_hints: Option<bool>,
+    /// The URL of the documentation of a symbol, with `{word}` replaced by the word under the
+    /// cursor, opened by `open_external_docs` when no language server knows the documentation.
+    pub documentation_url: Option<String>,
 }
 
 impl LanguageConfiguration {
@@ -274,6 +295,9 @@ pub enum LanguageServerFeature {
     RenameSymbol,
     InlayHints,
     DocumentColors,
+    CallHierarchy,
+    TypeHierarchy,
+    SemanticTokens,
 }
 
 impl Display for LanguageServerFeature {
@@ -299,6 +323,9 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             RenameSymbol => "rename-symbol",
             InlayHints => "inlay-hints",
             DocumentColors => "document-colors",
+            CallHierarchy => "call-hierarchy",
+            TypeHierarchy => "type-hierarchy",
+            SemanticTokens => "semantic-tokens",
         };
         write!(f, "{feature}",)
     }
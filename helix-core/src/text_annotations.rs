@@ -5,6 +5,7 @@
 use std::ptr::NonNull;
 
 use crate::doc_formatter::FormattedGrapheme;
+use crate::fold::Fold;
 use crate::syntax::{Highlight, OverlayHighlights};
 use crate::{Position, Tendril};
 
@@ -279,6 +280,7 @@ pub struct TextAnnotations<'a> {
     inline_annotations: Vec<Layer<'a, InlineAnnotation, Option<Highlight>>>,
     overlays: Vec<Layer<'a, Overlay, Option<Highlight>>>,
     line_annotations: Vec<(Cell<usize>, RawBox<dyn LineAnnotation + 'a>)>,
+    folds: &'a [Fold],
 }
 
 impl Debug for TextAnnotations<'_> {
@@ -286,6 +288,7 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TextAnnotations")
             .field("inline_annotations", &self.inline_annotations)
             .field("overlays", &self.overlays)
+            .field("folds", &self.folds)
             .finish_non_exhaustive()
     }
 }
@@ -364,6 +367,65 @@ pub fn add_line_annotation(&mut self, layer: Box<dyn LineAnnotation + 'a>) -> &m
         self
     }
 
+    /// Hide the lines of closed folds.
+    ///
+    /// The folds **must be sorted** by their first line. Folds may be nested.
+    pub fn add_folds(&mut self, folds: &'a [Fold]) -> &mut Self {
+        self.folds = folds;
+        self
+    }
+
+    /// The last line hidden by the folds starting on `line`, if there are any.
+    pub fn fold_end_at(&self, line: usize) -> Option<usize> {
+        let i = self.folds.partition_point(|fold| fold.start < line);
+        self.folds[i..]
+            .iter()
+            .take_while(|fold| fold.start == line)
+            .map(|fold| fold.end)
+            .max()
+    }
+
+    /// The outermost fold hiding `line`, if it is hidden.
+    pub fn fold_hiding(&self, line: usize) -> Option<Fold> {
+        self.folds.iter().find(|fold| fold.hides(line)).copied()
+    }
+
+    /// The `count`th line from `line` in the given direction which is not hidden by a fold.
+    pub fn visible_line_offset(
+        &self,
+        line: usize,
+        count: usize,
+        direction: crate::movement::Direction,
+        len_lines: usize,
+    ) -> usize {
+        use crate::movement::Direction;
+
+        if self.folds.is_empty() {
+            return match direction {
+                Direction::Forward => line.saturating_add(count),
+                Direction::Backward => line.saturating_sub(count),
+            };
+        }
+        let mut line = self.fold_hiding(line).map_or(line, |fold| fold.start);
+        for _ in 0..count {
+            line = match direction {
+                Direction::Forward => {
+                    let next = self.fold_end_at(line).unwrap_or(line) + 1;
+                    if next >= len_lines {
+                        break;
+                    }
+                    next
+                }
+                Direction::Backward if line == 0 => break,
+                Direction::Backward => {
+                    let prev = line - 1;
+                    self.fold_hiding(prev).map_or(prev, |fold| fold.start)
+                }
+            };
+        }
+        line
+    }
+
     /// Removes all line annotations, useful for vertical motions
     /// so that virtual text lines are automatically skipped.
     pub fn clear_line_annotations(&mut self) {
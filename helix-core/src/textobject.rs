@@ -289,6 +289,41 @@ pub fn textobject_treesitter(
     get_range().unwrap_or(range)
 }
 
+/// The textobjects whose first line is shown as the context of the lines inside of them.
+const SCOPE_TEXTOBJECTS: &[&str] = &["class.around", "function.around"];
+
+/// Returns the first lines of the classes and functions enclosing `line`, outermost first.
+/// Scopes which start on `line` itself are not included.
+pub fn enclosing_scope_lines(
+    slice: RopeSlice,
+    line: usize,
+    syntax: &Syntax,
+    loader: &syntax::Loader,
+) -> Vec<usize> {
+    let Some(query) = loader.textobject_query(syntax.root_language()) else {
+        return Vec::new();
+    };
+    let root = syntax.tree().root_node();
+    let line_start = slice.line_to_byte(line) as u32;
+    let line_end = slice.line_to_byte(line + 1).max(line_start as usize + 1) as u32;
+
+    let mut lines: Vec<_> = SCOPE_TEXTOBJECTS
+        .iter()
+        .filter_map(|capture| {
+            query.capture_nodes_in_range(&[capture], &root, slice, line_start..line_end)
+        })
+        .flatten()
+        .filter(|node| {
+            node.start_byte() < line_start as usize && node.end_byte() > line_start as usize
+        })
+        .map(|node| slice.byte_to_line(node.start_byte()))
+        .filter(|&start| start < line)
+        .collect();
+    lines.sort_unstable();
+    lines.dedup();
+    lines
+}
+
 #[cfg(test)]
 mod test {
     use super::TextObject::*;
@@ -587,4 +622,24 @@ fn test_textobject_surround() {
             }
         }
     }
+
+    #[test]
+    fn test_enclosing_scope_lines() {
+        let loader = crate::config::default_lang_loader();
+        let doc = Rope::from(
+            "impl Foo {\n    fn foo(&self) {\n        let x = 1;\n\n        bar(x);\n    }\n}\n\nfn baz() {}\n",
+        );
+        let language = loader.language_for_name("rust").unwrap();
+        let syntax = Syntax::new(doc.slice(..), language, &loader).unwrap();
+        let lines = |line| enclosing_scope_lines(doc.slice(..), line, &syntax, &loader);
+
+        // Scopes starting on the line aren't included.
+        assert!(lines(0).is_empty());
+        assert_eq!(lines(1), [0]);
+        assert_eq!(lines(2), [0, 1]);
+        assert_eq!(lines(3), [0, 1]);
+        assert_eq!(lines(5), [0, 1]);
+        assert_eq!(lines(6), [0]);
+        assert!(lines(8).is_empty());
+    }
 }
@@ -7,17 +7,25 @@ pub fn default_lang_config() -> toml::Value {
         .expect("Could not parse built-in languages.toml to valid toml")
 }
 
-/// User configured languages.toml file, merged with the default config.
+/// User configured languages.toml file, merged with the default config. The settings of the
+/// workspace's languages.toml which run programs are ignored unless the workspace is trusted.
 pub fn user_lang_config() -> Result<toml::Value, toml::de::Error> {
+    let (workspace, _) = crate::find_workspace();
+    let trusted = crate::trust::is_trusted(&workspace);
     let config = [
-        crate::config_dir(),
-        crate::find_workspace().0.join(".helix"),
+        (crate::config_dir(), true),
+        (workspace.join(".helix"), trusted),
     ]
     .into_iter()
-    .map(|path| path.join("languages.toml"))
-    .filter_map(|file| {
-        std::fs::read_to_string(file)
-            .map(|config| toml::from_str(&config))
+    .filter_map(|(path, trusted)| {
+        std::fs::read_to_string(path.join("languages.toml"))
+            .map(|config| {
+                let mut config = toml::from_str(&config)?;
+                if !trusted {
+                    crate::trust::strip_languages(&mut config);
+                }
+                Ok(config)
+            })
             .ok()
     })
     .collect::<Result<Vec<_>, _>>()?
@@ -607,8 +607,54 @@ fn mtime(path: &Path) -> Result<SystemTime> {
 }
 
 /// Gives the contents of a file from a language's `runtime/queries/<lang>`
-/// directory
+/// directory. A file with an `; extends` line among its leading comments is
+/// followed by the same file of the runtime directories of lower priority, like
+/// the bundled queries, instead of replacing it.
 pub fn load_runtime_file(language: &str, filename: &str) -> Result<String, std::io::Error> {
-    let path = crate::runtime_file(PathBuf::new().join("queries").join(language).join(filename));
-    std::fs::read_to_string(path)
+    let rel_path = PathBuf::new().join("queries").join(language).join(filename);
+    let mut contents: Option<String> = None;
+    for path in crate::runtime_dirs().iter().map(|dir| dir.join(&rel_path)) {
+        if !path.exists() {
+            continue;
+        }
+        let query = fs::read_to_string(path)?;
+        let extends = extends_lower_priority(&query);
+        match &mut contents {
+            Some(contents) => {
+                contents.push('\n');
+                contents.push_str(&query);
+            }
+            None => contents = Some(query),
+        }
+        if !extends {
+            break;
+        }
+    }
+    contents.ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound))
+}
+
+/// Whether a query has an `; extends` line among its leading comments.
+fn extends_lower_priority(query: &str) -> bool {
+    query
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with(';'))
+        .any(|line| line.trim_start_matches(';').trim() == "extends")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extends_lower_priority;
+
+    #[test]
+    fn extends() {
+        assert!(extends_lower_priority(
+            "; extends\n(jsx_attribute) @prop.around\n"
+        ));
+        assert!(extends_lower_priority(
+            ";; inherits: ecma\n\n;; extends\n(call) @call.around"
+        ));
+        assert!(!extends_lower_priority("(call) @call.around\n; extends\n"));
+        assert!(!extends_lower_priority("; extends the bundled queries\n"));
+    }
 }
@@ -1,5 +1,6 @@
 pub mod config;
 pub mod grammar;
+pub mod trust;
 
 use helix_stdx::{env::current_working_dir, path};
 
@@ -15,6 +16,8 @@
 
 static LOG_FILE: once_cell::sync::OnceCell<PathBuf> = once_cell::sync::OnceCell::new();
 
+static STATE_DIR: once_cell::sync::OnceCell<PathBuf> = once_cell::sync::OnceCell::new();
+
 pub fn initialize_config_file(specified_file: Option<PathBuf>) {
     let config_file = specified_file.unwrap_or_else(default_config_file);
     ensure_parent_dir(&config_file);
@@ -27,6 +30,12 @@ pub fn initialize_log_file(specified_file: Option<PathBuf>) {
     LOG_FILE.set(log_file).ok();
 }
 
+/// Overrides the directory returned by [state_dir]. It can only be set once, before the state
+/// directory is first used.
+pub fn initialize_state_dir(dir: PathBuf) {
+    STATE_DIR.set(dir).ok();
+}
+
 /// A list of runtime directories from highest to lowest priority
 ///
 /// The priority is:
@@ -132,6 +141,18 @@ pub fn cache_dir() -> PathBuf {
     path
 }
 
+/// The directory for state which should persist between sessions, such as saved window layouts.
+/// Platforms without a separate state directory use the data directory.
+pub fn state_dir() -> PathBuf {
+    if let Some(dir) = STATE_DIR.get() {
+        return dir.clone();
+    }
+    let strategy = choose_base_strategy().expect("Unable to find the state directory!");
+    let mut path = strategy.state_dir().unwrap_or_else(|| strategy.data_dir());
+    path.push("helix");
+    path
+}
+
 pub fn config_file() -> PathBuf {
     CONFIG_FILE.get().map(|path| path.to_path_buf()).unwrap()
 }
@@ -0,0 +1,122 @@
+//! Trust of workspaces, which decides whether the settings of their `.helix/config.toml` and
+//! `.helix/languages.toml` which run programs are used. Opening a repository must not start the
+//! language servers, formatters or shells it configures before the user agrees to it.
+//!
+//! The settings of `languages.toml` which run programs are stripped here. The ones of
+//! `config.toml` are stripped by `helix-term`, which can parse the command lines of key bindings.
+//!
+//! The paths of the trusted workspaces are kept in the `trusted-workspaces` file of the state
+//! directory, one per line.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use toml::Value;
+
+/// The keys of the `[[language]]` tables of `languages.toml` which run programs.
+const LANGUAGE_KEYS: &[&str] = &["formatter", "debugger"];
+
+/// The tables of `languages.toml` which run programs.
+const LANGUAGES_TABLES: &[&str] = &["language-server", "grammar"];
+
+fn trust_file() -> PathBuf {
+    crate::state_dir().join("trusted-workspaces")
+}
+
+fn trusted_workspaces() -> Vec<PathBuf> {
+    std::fs::read_to_string(trust_file())
+        .map(|file| file.lines().map(PathBuf::from).collect())
+        .unwrap_or_default()
+}
+
+/// Whether the settings of the workspace at `workspace` which run programs are used.
+pub fn is_trusted(workspace: &Path) -> bool {
+    // The workspace may be the config directory itself, such as when editing the config.
+    workspace.join(".helix") == crate::config_dir()
+        || trusted_workspaces().iter().any(|path| path == workspace)
+}
+
+/// Whether the current workspace is trusted.
+pub fn is_workspace_trusted() -> bool {
+    is_trusted(&crate::find_workspace().0)
+}
+
+/// Trusts or distrusts the workspace at `workspace`.
+pub fn set_trusted(workspace: &Path, trusted: bool) -> io::Result<()> {
+    let mut workspaces = trusted_workspaces();
+    workspaces.retain(|path| path != workspace);
+    if trusted {
+        workspaces.push(workspace.to_path_buf());
+    }
+    let mut file = String::new();
+    for path in workspaces {
+        file.push_str(&path.to_string_lossy());
+        file.push('\n');
+    }
+    std::fs::create_dir_all(crate::state_dir())?;
+    std::fs::write(trust_file(), file)
+}
+
+/// Removes the settings of a `languages.toml` which run programs, returning their keys.
+pub fn strip_languages(config: &mut Value) -> Vec<String> {
+    let Some(config) = config.as_table_mut() else {
+        return Vec::new();
+    };
+    let mut keys: Vec<String> = LANGUAGES_TABLES
+        .iter()
+        .filter(|table| config.remove(**table).is_some())
+        .map(|table| table.to_string())
+        .collect();
+    let languages = config
+        .get_mut("language")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_table_mut);
+    for language in languages {
+        let name = language
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("?")
+            .to_owned();
+        for key in LANGUAGE_KEYS {
+            if language.remove(*key).is_some() {
+                keys.push(format!("language.{name}.{key}"));
+            }
+        }
+    }
+    keys
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn strip_risky_languages() {
+        let mut languages: Value = toml::from_str(
+            r#"
+            [[language]]
+            name = "rust"
+            indent = { tab-width = 4, unit = "    " }
+            formatter = { command = "rustfmt" }
+            [language-server.rust-analyzer]
+            command = "evil"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            strip_languages(&mut languages),
+            ["language-server", "language.rust.formatter"]
+        );
+        assert_eq!(
+            languages,
+            toml::from_str(
+                "[[language]]\nname = \"rust\"\nindent = { tab-width = 4, unit = \"    \" }"
+            )
+            .unwrap()
+        );
+    }
+}
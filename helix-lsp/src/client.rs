@@ -1,6 +1,6 @@
 use crate::{
     file_operations::FileOperationsInterest,
-    find_lsp_workspace, jsonrpc,
+    find_lsp_workspace, jsonrpc, rust_analyzer,
     transport::{Payload, Transport},
     Call, Error, LanguageServerId, OffsetEncoding, Result,
 };
@@ -16,11 +16,14 @@
 use parking_lot::Mutex;
 use serde::Deserialize;
 use serde_json::Value;
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+};
 use std::{
     ffi::OsStr,
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
 };
@@ -46,6 +49,26 @@ fn workspace_for_uri(uri: lsp::Url) -> WorkspaceFolder {
     }
 }
 
+/// The number of request errors kept for [`Client::recent_errors`].
+const RECENT_ERRORS: usize = 10;
+
+/// Counts a request as pending until it's dropped, which includes the request's future being
+/// dropped before the server answers.
+struct PendingRequest(Arc<AtomicUsize>);
+
+impl PendingRequest {
+    fn new(count: &Arc<AtomicUsize>) -> Self {
+        count.fetch_add(1, Ordering::Relaxed);
+        Self(count.clone())
+    }
+}
+
+impl Drop for PendingRequest {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 #[derive(Debug)]
 pub struct Client {
     id: LanguageServerId,
@@ -53,6 +76,8 @@ pub struct Client {
     _process: Child,
     server_tx: UnboundedSender<Payload>,
     request_counter: AtomicU64,
+    pending_requests: Arc<AtomicUsize>,
+    recent_errors: Arc<Mutex<VecDeque<String>>>,
     pub(crate) capabilities: OnceCell<lsp::ServerCapabilities>,
     pub(crate) file_operation_interest: OnceLock<FileOperationsInterest>,
     config: Option<Value>,
@@ -250,6 +275,8 @@ pub fn start(
             _process: process,
             server_tx,
             request_counter: AtomicU64::new(0),
+            pending_requests: Arc::default(),
+            recent_errors: Arc::default(),
             capabilities: OnceCell::new(),
             file_operation_interest: OnceLock::new(),
             config,
@@ -287,6 +314,16 @@ fn value_into_params(value: Value) -> jsonrpc::Params {
         }
     }
 
+    /// The number of requests sent to the server which weren't answered yet.
+    pub fn pending_requests(&self) -> usize {
+        self.pending_requests.load(Ordering::Relaxed)
+    }
+
+    /// The errors of the last requests which failed, oldest first.
+    pub fn recent_errors(&self) -> Vec<String> {
+        self.recent_errors.lock().iter().cloned().collect()
+    }
+
     pub fn is_initialized(&self) -> bool {
         self.capabilities.get().is_some()
     }
@@ -373,6 +410,14 @@ pub fn supports_feature(&self, feature: LanguageServerFeature) -> bool {
             ),
             LanguageServerFeature::Diagnostics => true, // there's no extra server capability
             LanguageServerFeature::PullDiagnostics => capabilities.diagnostic_provider.is_some(),
+            LanguageServerFeature::SemanticTokens => matches!(
+                self.semantic_tokens_options()
+                    .and_then(|options| options.full.as_ref()),
+                Some(
+                    lsp::SemanticTokensFullOptions::Bool(true)
+                        | lsp::SemanticTokensFullOptions::Delta { .. }
+                )
+            ),
             LanguageServerFeature::RenameSymbol => matches!(
                 capabilities.rename_provider,
                 Some(OneOf::Left(true)) | Some(OneOf::Right(_))
@@ -389,6 +434,17 @@ pub fn supports_feature(&self, feature: LanguageServerFeature) -> bool {
                         | ColorProviderCapability::Options(_)
                 )
             ),
+            LanguageServerFeature::CallHierarchy => matches!(
+                capabilities.call_hierarchy_provider,
+                Some(
+                    CallHierarchyServerCapability::Simple(true)
+                        | CallHierarchyServerCapability::Options(_)
+                )
+            ),
+            LanguageServerFeature::TypeHierarchy => matches!(
+                capabilities.type_hierarchy_provider,
+                Some(OneOf::Left(true) | OneOf::Right(_))
+            ),
         }
     }
 
@@ -471,15 +527,29 @@ fn call_with_timeout<R: lsp::request::Request>(
                 Ok(rx)
             });
 
+        let pending = PendingRequest::new(&self.pending_requests);
+        let recent_errors = self.recent_errors.clone();
         async move {
             use std::time::Duration;
             use tokio::time::timeout;
+            let _pending = pending;
             // TODO: delay other calls until initialize success
-            timeout(Duration::from_secs(timeout_secs), rx?.recv())
-                .await
-                .map_err(|_| Error::Timeout(id))? // return Timeout
-                .ok_or(Error::StreamClosed)?
-                .and_then(|value| serde_json::from_value(value).map_err(Into::into))
+            let result = async {
+                timeout(Duration::from_secs(timeout_secs), rx?.recv())
+                    .await
+                    .map_err(|_| Error::Timeout(id))? // return Timeout
+                    .ok_or(Error::StreamClosed)?
+                    .and_then(|value| serde_json::from_value(value).map_err(Into::into))
+            }
+            .await;
+            if let Err(err) = &result {
+                let mut recent_errors = recent_errors.lock();
+                if recent_errors.len() == RECENT_ERRORS {
+                    recent_errors.pop_front();
+                }
+                recent_errors.push_back(format!("{}: {err}", R::METHOD));
+            }
+            result
         }
     }
 
@@ -606,6 +676,9 @@ pub(crate) async fn initialize(&self, enable_snippets: bool) -> Result<lsp::Init
                     diagnostic: Some(lsp::DiagnosticWorkspaceClientCapabilities {
                         refresh_support: Some(true),
                     }),
+                    semantic_tokens: Some(lsp::SemanticTokensWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
+                    }),
                     ..Default::default()
                 }),
                 text_document: Some(lsp::TextDocumentClientCapabilities {
@@ -687,6 +760,55 @@ pub(crate) async fn initialize(&self, enable_snippets: bool) -> Result<lsp::Init
                         dynamic_registration: Some(false),
                         related_document_support: Some(true),
                     }),
+                    semantic_tokens: Some(lsp::SemanticTokensClientCapabilities {
+                        dynamic_registration: Some(false),
+                        requests: lsp::SemanticTokensClientCapabilitiesRequests {
+                            range: Some(false),
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                        },
+                        token_types: vec![
+                            lsp::SemanticTokenType::NAMESPACE,
+                            lsp::SemanticTokenType::TYPE,
+                            lsp::SemanticTokenType::CLASS,
+                            lsp::SemanticTokenType::ENUM,
+                            lsp::SemanticTokenType::INTERFACE,
+                            lsp::SemanticTokenType::STRUCT,
+                            lsp::SemanticTokenType::TYPE_PARAMETER,
+                            lsp::SemanticTokenType::PARAMETER,
+                            lsp::SemanticTokenType::VARIABLE,
+                            lsp::SemanticTokenType::PROPERTY,
+                            lsp::SemanticTokenType::ENUM_MEMBER,
+                            lsp::SemanticTokenType::EVENT,
+                            lsp::SemanticTokenType::FUNCTION,
+                            lsp::SemanticTokenType::METHOD,
+                            lsp::SemanticTokenType::MACRO,
+                            lsp::SemanticTokenType::KEYWORD,
+                            lsp::SemanticTokenType::MODIFIER,
+                            lsp::SemanticTokenType::COMMENT,
+                            lsp::SemanticTokenType::STRING,
+                            lsp::SemanticTokenType::NUMBER,
+                            lsp::SemanticTokenType::REGEXP,
+                            lsp::SemanticTokenType::OPERATOR,
+                            lsp::SemanticTokenType::DECORATOR,
+                        ],
+                        token_modifiers: vec![
+                            lsp::SemanticTokenModifier::DECLARATION,
+                            lsp::SemanticTokenModifier::DEFINITION,
+                            lsp::SemanticTokenModifier::READONLY,
+                            lsp::SemanticTokenModifier::STATIC,
+                            lsp::SemanticTokenModifier::DEPRECATED,
+                            lsp::SemanticTokenModifier::ABSTRACT,
+                            lsp::SemanticTokenModifier::ASYNC,
+                            lsp::SemanticTokenModifier::MODIFICATION,
+                            lsp::SemanticTokenModifier::DOCUMENTATION,
+                            lsp::SemanticTokenModifier::DEFAULT_LIBRARY,
+                        ],
+                        formats: vec![lsp::TokenFormat::RELATIVE],
+                        overlapping_token_support: Some(false),
+                        multiline_token_support: Some(false),
+                        server_cancel_support: Some(true),
+                        augments_syntax_tokens: Some(true),
+                    }),
                     publish_diagnostics: Some(lsp::PublishDiagnosticsClientCapabilities {
                         version_support: Some(true),
                         tag_support: Some(lsp::TagSupport {
@@ -701,6 +823,12 @@ pub(crate) async fn initialize(&self, enable_snippets: bool) -> Result<lsp::Init
                         dynamic_registration: Some(false),
                         resolve_support: None,
                     }),
+                    call_hierarchy: Some(lsp::CallHierarchyClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    type_hierarchy: Some(lsp::TypeHierarchyClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
                     ..Default::default()
                 }),
                 window: Some(lsp::WindowClientCapabilities {
@@ -1242,15 +1370,8 @@ pub fn text_document_diagnostic(
         text_document: lsp::TextDocumentIdentifier,
         previous_result_id: Option<String>,
     ) -> Option<impl Future<Output = Result<lsp::DocumentDiagnosticReportResult>>> {
-        let capabilities = self.capabilities();
-
         // Return early if the server does not support pull diagnostic.
-        let identifier = match capabilities.diagnostic_provider.as_ref()? {
-            lsp::DiagnosticServerCapabilities::Options(cap) => cap.identifier.clone(),
-            lsp::DiagnosticServerCapabilities::RegistrationOptions(cap) => {
-                cap.diagnostic_options.identifier.clone()
-            }
-        };
+        let identifier = self.diagnostic_options()?.identifier.clone();
 
         let params = lsp::DocumentDiagnosticParams {
             text_document,
@@ -1263,6 +1384,89 @@ pub fn text_document_diagnostic(
         Some(self.call::<lsp::request::DocumentDiagnosticRequest>(params))
     }
 
+    /// Pulls the diagnostics of all the documents of the workspace, which the server only reports
+    /// for the documents whose diagnostics changed since the given result ids.
+    pub fn workspace_diagnostic(
+        &self,
+        previous_result_ids: Vec<lsp::PreviousResultId>,
+    ) -> Option<impl Future<Output = Result<lsp::WorkspaceDiagnosticReportResult>>> {
+        let options = self.diagnostic_options()?;
+        if !options.workspace_diagnostics {
+            return None;
+        }
+
+        let params = lsp::WorkspaceDiagnosticParams {
+            identifier: options.identifier.as_deref().map(str::to_owned),
+            previous_result_ids,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::WorkspaceDiagnosticRequest>(params))
+    }
+
+    fn diagnostic_options(&self) -> Option<&lsp::DiagnosticOptions> {
+        match self.capabilities().diagnostic_provider.as_ref()? {
+            lsp::DiagnosticServerCapabilities::Options(options) => Some(options),
+            lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+                Some(&options.diagnostic_options)
+            }
+        }
+    }
+
+    fn semantic_tokens_options(&self) -> Option<&lsp::SemanticTokensOptions> {
+        match self.capabilities().semantic_tokens_provider.as_ref()? {
+            lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => Some(options),
+            lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+                Some(&options.semantic_tokens_options)
+            }
+        }
+    }
+
+    /// The names of the types and modifiers of the server's semantic tokens.
+    pub fn semantic_tokens_legend(&self) -> Option<&lsp::SemanticTokensLegend> {
+        Some(&self.semantic_tokens_options()?.legend)
+    }
+
+    pub fn text_document_semantic_tokens_full(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+    ) -> Option<impl Future<Output = Result<Option<lsp::SemanticTokensResult>>>> {
+        if !self.supports_feature(LanguageServerFeature::SemanticTokens) {
+            return None;
+        }
+
+        let params = lsp::SemanticTokensParams {
+            text_document,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::SemanticTokensFullRequest>(params))
+    }
+
+    /// Requests the changes to the semantic tokens identified by `previous_result_id`, if the
+    /// server supports sending only the changes.
+    pub fn text_document_semantic_tokens_full_delta(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        previous_result_id: String,
+    ) -> Option<impl Future<Output = Result<Option<lsp::SemanticTokensFullDeltaResult>>>> {
+        match self.semantic_tokens_options()?.full {
+            Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }) => (),
+            _ => return None,
+        }
+
+        let params = lsp::SemanticTokensDeltaParams {
+            text_document,
+            previous_result_id,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        Some(self.call::<lsp::request::SemanticTokensFullDeltaRequest>(params))
+    }
+
     pub fn text_document_document_highlight(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -1410,6 +1614,98 @@ pub fn goto_implementation(
         ))
     }
 
+    pub fn prepare_call_hierarchy(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::CallHierarchyItem>>>>> {
+        if !self.supports_feature(LanguageServerFeature::CallHierarchy) {
+            return None;
+        }
+
+        let params = lsp::CallHierarchyPrepareParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document,
+                position,
+            },
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+        };
+
+        Some(self.call::<lsp::request::CallHierarchyPrepare>(params))
+    }
+
+    pub fn call_hierarchy_incoming_calls(
+        &self,
+        item: lsp::CallHierarchyItem,
+    ) -> impl Future<Output = Result<Option<Vec<lsp::CallHierarchyIncomingCall>>>> {
+        let params = lsp::CallHierarchyIncomingCallsParams {
+            item,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        self.call::<lsp::request::CallHierarchyIncomingCalls>(params)
+    }
+
+    pub fn call_hierarchy_outgoing_calls(
+        &self,
+        item: lsp::CallHierarchyItem,
+    ) -> impl Future<Output = Result<Option<Vec<lsp::CallHierarchyOutgoingCall>>>> {
+        let params = lsp::CallHierarchyOutgoingCallsParams {
+            item,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        self.call::<lsp::request::CallHierarchyOutgoingCalls>(params)
+    }
+
+    pub fn prepare_type_hierarchy(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+    ) -> Option<impl Future<Output = Result<Option<Vec<lsp::TypeHierarchyItem>>>>> {
+        if !self.supports_feature(LanguageServerFeature::TypeHierarchy) {
+            return None;
+        }
+
+        let params = lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document,
+                position,
+            },
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+        };
+
+        Some(self.call::<lsp::request::TypeHierarchyPrepare>(params))
+    }
+
+    pub fn type_hierarchy_supertypes(
+        &self,
+        item: lsp::TypeHierarchyItem,
+    ) -> impl Future<Output = Result<Option<Vec<lsp::TypeHierarchyItem>>>> {
+        let params = lsp::TypeHierarchySupertypesParams {
+            item,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        self.call::<lsp::request::TypeHierarchySupertypes>(params)
+    }
+
+    pub fn type_hierarchy_subtypes(
+        &self,
+        item: lsp::TypeHierarchyItem,
+    ) -> impl Future<Output = Result<Option<Vec<lsp::TypeHierarchyItem>>>> {
+        let params = lsp::TypeHierarchySubtypesParams {
+            item,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        };
+
+        self.call::<lsp::request::TypeHierarchySubtypes>(params)
+    }
+
     pub fn goto_reference(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -1580,6 +1876,70 @@ pub fn command(
         Some(self.call::<lsp::request::ExecuteCommand>(params))
     }
 
+    /// Expands the macro call at `position` recursively, a rust-analyzer extension.
+    pub fn rust_analyzer_expand_macro(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+    ) -> impl Future<Output = Result<Option<rust_analyzer::ExpandedMacro>>> {
+        let params = lsp::TextDocumentPositionParams {
+            text_document,
+            position,
+        };
+
+        self.call::<rust_analyzer::ExpandMacro>(params)
+    }
+
+    /// Locates the `Cargo.toml` of the crate of the document, a rust-analyzer extension.
+    pub fn rust_analyzer_open_cargo_toml(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+    ) -> impl Future<Output = Result<Option<lsp::Location>>> {
+        let params = rust_analyzer::OpenCargoTomlParams { text_document };
+
+        self.call::<rust_analyzer::OpenCargoToml>(params)
+    }
+
+    /// Renders the HIR of the function at `position`, a rust-analyzer extension.
+    pub fn rust_analyzer_view_hir(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+    ) -> impl Future<Output = Result<String>> {
+        let params = lsp::TextDocumentPositionParams {
+            text_document,
+            position,
+        };
+
+        self.call::<rust_analyzer::ViewHir>(params)
+    }
+
+    /// Locates the documentation of the symbol at `position` on the web, an extension of
+    /// rust-analyzer which servers advertise in their experimental capabilities.
+    pub fn external_docs(
+        &self,
+        text_document: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+    ) -> Option<impl Future<Output = Result<Option<lsp::Url>>>> {
+        let supported = self
+            .capabilities()
+            .experimental
+            .as_ref()
+            .and_then(|experimental| experimental.get("externalDocs"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        if !supported {
+            return None;
+        }
+
+        let params = lsp::TextDocumentPositionParams {
+            text_document,
+            position,
+        };
+
+        Some(self.call::<rust_analyzer::ExternalDocs>(params))
+    }
+
     pub fn did_change_watched_files(&self, changes: Vec<lsp::FileEvent>) {
         self.notify::<lsp::notification::DidChangeWatchedFiles>(lsp::DidChangeWatchedFilesParams {
             changes,
@@ -2,6 +2,7 @@
 pub mod file_event;
 mod file_operations;
 pub mod jsonrpc;
+pub mod rust_analyzer;
 mod transport;
 
 use arc_swap::ArcSwap;
@@ -282,6 +283,45 @@ pub fn lsp_range_to_range(
         Some(Range::new(start, end))
     }
 
+    /// Converts semantic tokens, whose positions are relative to the previous token, to the
+    /// character ranges of the tokens. Tokens outside of the document are skipped.
+    pub fn lsp_semantic_tokens_to_ranges<'a>(
+        doc: &'a Rope,
+        tokens: &'a [lsp::SemanticToken],
+        offset_encoding: OffsetEncoding,
+    ) -> impl Iterator<Item = (std::ops::Range<usize>, &'a lsp::SemanticToken)> + 'a {
+        let mut line = 0;
+        let mut character = 0;
+        tokens.iter().filter_map(move |token| {
+            if token.delta_line != 0 {
+                line += token.delta_line;
+                character = 0;
+            }
+            character += token.delta_start;
+            let range = lsp::Range::new(
+                lsp::Position::new(line, character),
+                lsp::Position::new(line, character + token.length),
+            );
+            let range = lsp_range_to_range(doc, range, offset_encoding)?;
+            Some((range.from()..range.to(), token))
+        })
+    }
+
+    /// Applies the edits of a semantic tokens delta to the previous tokens.
+    pub fn apply_semantic_tokens_edits(
+        tokens: &mut Vec<lsp::SemanticToken>,
+        mut edits: Vec<lsp::SemanticTokensEdit>,
+    ) {
+        // The offsets of the edits count the integers of the encoded tokens, which are five per
+        // token. Applying the last edit first keeps the offsets of the others valid.
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.start));
+        for edit in edits {
+            let start = (edit.start as usize / 5).min(tokens.len());
+            let end = (start + edit.delete_count as usize / 5).min(tokens.len());
+            tokens.splice(start..end, edit.data.unwrap_or_default());
+        }
+    }
+
     /// If the LS did not provide a range for the completion or the range of the
     /// primary cursor can not be used for the secondary cursor, this function
     /// can be used to find the completion range for a cursor
@@ -476,6 +516,7 @@ pub enum MethodCall {
     UnregisterCapability(lsp::UnregistrationParams),
     ShowDocument(lsp::ShowDocumentParams),
     WorkspaceDiagnosticRefresh,
+    SemanticTokensRefresh,
 }
 
 impl MethodCall {
@@ -508,6 +549,7 @@ pub fn parse(method: &str, params: jsonrpc::Params) -> Result<MethodCall> {
                 Self::ShowDocument(params)
             }
             lsp::request::WorkspaceDiagnosticRefresh::METHOD => Self::WorkspaceDiagnosticRefresh,
+            lsp::request::SemanticTokensRefresh::METHOD => Self::SemanticTokensRefresh,
             _ => {
                 return Err(Error::Unhandled);
             }
@@ -760,6 +802,28 @@ pub fn progress(&self) -> Option<&lsp::WorkDoneProgress> {
             ProgressStatus::Started { title: _, progress } => Some(progress),
         }
     }
+
+    /// Describes the progress like `42% Indexing ⋅ 10/24`, or returns `None` until it starts.
+    pub fn describe(&self) -> Option<String> {
+        let ProgressStatus::Started { title, progress } = self else {
+            return None;
+        };
+        let (message, percentage) = match progress {
+            lsp::WorkDoneProgress::Begin(begin) => (&begin.message, begin.percentage),
+            lsp::WorkDoneProgress::Report(report) => (&report.message, report.percentage),
+            lsp::WorkDoneProgress::End(end) => (&end.message, None),
+        };
+        let mut description = String::new();
+        if let Some(percentage) = percentage {
+            description.push_str(&format!("{percentage}% "));
+        }
+        description.push_str(title);
+        if let Some(message) = message {
+            description.push_str(" ⋅ ");
+            description.push_str(message);
+        }
+        Some(description)
+    }
 }
 
 #[derive(Default, Debug)]
@@ -1082,4 +1146,66 @@ fn emoji_format_gh_4791() {
         assert!(transaction.apply(&mut source));
         assert_eq!(source, "[\n  \"🇺🇸\",\n  \"🎄\",\n]");
     }
+
+    #[test]
+    fn semantic_tokens() {
+        use lsp::{SemanticToken, SemanticTokensEdit};
+
+        let token = |delta_line, delta_start, length, token_type| SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type,
+            token_modifiers_bitset: 0,
+        };
+        let doc = Rope::from(
+            "let a = b;
+foo(a);
+",
+        );
+        let mut tokens = vec![token(0, 0, 3, 0), token(0, 4, 1, 1), token(1, 4, 1, 1)];
+        let ranges = |tokens: &[SemanticToken]| {
+            lsp_semantic_tokens_to_ranges(&doc, tokens, OffsetEncoding::Utf8)
+                .map(|(range, token)| (range, token.token_type))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ranges(&tokens), [(0..3, 0), (4..5, 1), (15..16, 1)]);
+
+        // Replace the token of `a`, then insert a token for `b` after it.
+        apply_semantic_tokens_edits(
+            &mut tokens,
+            vec![
+                SemanticTokensEdit {
+                    start: 5,
+                    delete_count: 5,
+                    data: Some(vec![token(0, 4, 1, 2)]),
+                },
+                SemanticTokensEdit {
+                    start: 10,
+                    delete_count: 0,
+                    data: Some(vec![token(0, 4, 1, 1)]),
+                },
+            ],
+        );
+        assert_eq!(
+            ranges(&tokens),
+            [(0..3, 0), (4..5, 2), (8..9, 1), (15..16, 1)]
+        );
+    }
+
+    #[test]
+    fn describes_progress() {
+        use super::ProgressStatus;
+
+        assert_eq!(ProgressStatus::Created.describe(), None);
+        let status = ProgressStatus::Started {
+            title: "Indexing".to_string(),
+            progress: lsp::WorkDoneProgress::Report(lsp::WorkDoneProgressReport {
+                message: Some("10/24".to_string()),
+                percentage: Some(42),
+                ..Default::default()
+            }),
+        };
+        assert_eq!(status.describe().as_deref(), Some("42% Indexing ⋅ 10/24"));
+    }
 }
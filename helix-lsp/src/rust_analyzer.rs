@@ -0,0 +1,55 @@
+//! Requests of rust-analyzer's extensions to the protocol, see
+//! <https://rust-analyzer.github.io/book/contributing/lsp-extensions.html>.
+
+use serde::{Deserialize, Serialize};
+
+use crate::lsp;
+
+/// The name of rust-analyzer in the default `languages.toml`, whose extensions are requested.
+pub const NAME: &str = "rust-analyzer";
+
+pub enum ExpandMacro {}
+
+impl lsp::request::Request for ExpandMacro {
+    type Params = lsp::TextDocumentPositionParams;
+    type Result = Option<ExpandedMacro>;
+    const METHOD: &'static str = "rust-analyzer/expandMacro";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpandedMacro {
+    /// The name of the outermost macro.
+    pub name: String,
+    /// The recursive expansion of the macro call.
+    pub expansion: String,
+}
+
+pub enum OpenCargoToml {}
+
+impl lsp::request::Request for OpenCargoToml {
+    type Params = OpenCargoTomlParams;
+    type Result = Option<lsp::Location>;
+    const METHOD: &'static str = "experimental/openCargoToml";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenCargoTomlParams {
+    pub text_document: lsp::TextDocumentIdentifier,
+}
+
+pub enum ViewHir {}
+
+impl lsp::request::Request for ViewHir {
+    type Params = lsp::TextDocumentPositionParams;
+    type Result = String;
+    const METHOD: &'static str = "rust-analyzer/viewHir";
+}
+
+pub enum ExternalDocs {}
+
+impl lsp::request::Request for ExternalDocs {
+    type Params = lsp::TextDocumentPositionParams;
+    type Result = Option<lsp::Url>;
+    const METHOD: &'static str = "experimental/externalDocs";
+}
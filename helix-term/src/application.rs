@@ -4,14 +4,16 @@
 use helix_lsp::{
     lsp::{self, notification::Notification},
     util::lsp_range_to_range,
-    LanguageServerId, LspProgressMap,
+    LanguageServerId,
 };
 use helix_stdx::path::get_relative_path;
 use helix_view::{
     align_view,
     document::{DocumentOpenError, DocumentSavedEventResult},
-    editor::{ConfigEvent, EditorEvent},
+    editor::{ConfigEvent, EditorEvent, WorkspaceEditSource},
+    events::DocumentDidSave,
     graphics::Rect,
+    handlers::lsp::{ApplyEditError, PendingWorkspaceEdit},
     theme,
     tree::Layout,
     Align, Editor,
@@ -21,11 +23,14 @@
 
 use crate::{
     args::Args,
+    commands,
     compositor::{Compositor, Event},
     config::Config,
+    events::TerminalProgramDidExit,
     handlers,
-    job::Jobs,
+    job::{self, Jobs},
     keymap::Keymaps,
+    remote,
     ui::{self, overlay::overlaid},
 };
 
@@ -76,9 +81,10 @@ pub struct Application {
 
     signals: Signals,
     jobs: Jobs,
-    lsp_progress: LspProgressMap,
 
     theme_mode: Option<theme::Mode>,
+    /// Receives requests from `hx --remote` clients.
+    remote: Option<remote::Server>,
 }
 
 #[cfg(feature = "integration")]
@@ -153,6 +159,11 @@ pub fn new(args: Args, config: Config, lang_loader: syntax::Loader) -> Result<Se
 
         let jobs = Jobs::new();
 
+        crate::registers::restore(&mut editor);
+        if editor.config().dashboard.enable {
+            crate::recent::add_current_workspace();
+        }
+
         if args.load_tutor {
             let path = helix_loader::runtime_file(Path::new("tutor"));
             editor.open(&path, Action::VerticalSplit)?;
@@ -238,12 +249,38 @@ pub fn new(args: Args, config: Config, lang_loader: syntax::Loader) -> Result<Se
             }
         } else if stdin().is_terminal() || cfg!(feature = "integration") {
             editor.new_file(Action::VerticalSplit);
+            // Restore the layout saved as `default` for this workspace, if any.
+            let layout = commands::DEFAULT_LAYOUT_NAME;
+            if !cfg!(feature = "integration") && commands::layout_file(layout)?.exists() {
+                if let Err(err) = commands::load_layout(&mut editor, layout) {
+                    editor.set_error(err.to_string());
+                }
+            } else if !cfg!(feature = "integration") && editor.config().dashboard.enable {
+                compositor.push(Box::new(ui::Dashboard::new(&editor.config().dashboard)));
+            }
         } else {
             editor
                 .new_file_from_stdin(Action::VerticalSplit)
                 .unwrap_or_else(|_| editor.new_file(Action::VerticalSplit));
         }
 
+        crate::jumplist::restore(&mut editor);
+
+        if crate::state::is_enabled(editor.config().recovery.enable) {
+            let leftovers = crate::recovery::leftovers();
+            if !leftovers.is_empty() {
+                let picker = crate::recovery::picker(leftovers);
+                compositor.push(Box::new(ui::overlay::overlaid(picker)));
+            }
+        }
+
+        if !cfg!(feature = "integration") {
+            let ignored = crate::trust::ignored_keys();
+            if !ignored.is_empty() {
+                compositor.push(Box::new(crate::trust::prompt(ignored)));
+            }
+        }
+
         #[cfg(windows)]
         let signals = futures_util::stream::empty();
         #[cfg(not(windows))]
@@ -256,6 +293,14 @@ pub fn new(args: Args, config: Config, lang_loader: syntax::Loader) -> Result<Se
         ])
         .context("build signal handler")?;
 
+        let remote = if cfg!(feature = "integration") {
+            None
+        } else {
+            remote::Server::start()
+                .map_err(|err| log::warn!("remote control is unavailable: {err:#}"))
+                .ok()
+        };
+
         let app = Self {
             compositor,
             terminal,
@@ -263,8 +308,8 @@ pub fn new(args: Args, config: Config, lang_loader: syntax::Loader) -> Result<Se
             config,
             signals,
             jobs,
-            lsp_progress: LspProgressMap::new(),
             theme_mode,
+            remote,
         };
 
         Ok(app)
@@ -339,7 +384,7 @@ pub async fn event_loop_until_idle<S>(&mut self, input_stream: &mut S) -> bool
                     self.handle_terminal_events(event).await;
                 }
                 Some(callback) = self.jobs.callbacks.recv() => {
-                    self.jobs.handle_callback(&mut self.editor, &mut self.compositor, Ok(Some(callback)));
+                    self.handle_callback(Ok(Some(callback)));
                     self.render().await;
                 }
                 Some(msg) = self.jobs.status_messages.recv() => {
@@ -354,7 +399,11 @@ pub async fn event_loop_until_idle<S>(&mut self, input_stream: &mut S) -> bool
                     helix_event::request_redraw();
                 }
                 Some(callback) = self.jobs.wait_futures.next() => {
-                    self.jobs.handle_callback(&mut self.editor, &mut self.compositor, callback);
+                    self.handle_callback(callback);
+                    self.render().await;
+                }
+                Some((request, respond)) = remote::recv(&mut self.remote) => {
+                    self.handle_remote_request(request, respond);
                     self.render().await;
                 }
                 event = self.editor.wait_event() => {
@@ -369,6 +418,8 @@ pub async fn event_loop_until_idle<S>(&mut self, input_stream: &mut S) -> bool
                 }
             }
 
+            self.review_workspace_edits();
+
             // for integration tests only, reset the idle timer after every
             // event to signal when test events are done processing
             #[cfg(feature = "integration")]
@@ -378,6 +429,23 @@ pub async fn event_loop_until_idle<S>(&mut self, input_stream: &mut S) -> bool
         }
     }
 
+    /// Shows the next workspace edit waiting to be reviewed unless one is already shown.
+    fn review_workspace_edits(&mut self) {
+        if self.compositor.contains_id(ui::WorkspaceEditReview::ID) {
+            return;
+        }
+        let Some(pending) = self.editor.pending_workspace_edits.pop_front() else {
+            return;
+        };
+        let review = ui::WorkspaceEditReview::new(&self.editor, pending);
+        if review.is_empty() {
+            review.apply(&mut self.editor);
+            return;
+        }
+        self.compositor.push(Box::new(overlaid(review)));
+        helix_event::request_redraw();
+    }
+
     pub fn handle_config_events(&mut self, config_event: ConfigEvent) {
         let old_editor_config = self.editor.config();
 
@@ -532,22 +600,7 @@ pub async fn handle_signals(&mut self, signal: i32) -> bool {
                 }
             }
             signal::SIGCONT => {
-                // Copy/Paste from same issue from neovim:
-                // https://github.com/neovim/neovim/issues/12322
-                // https://github.com/neovim/neovim/pull/13084
-                for retries in 1..=10 {
-                    match self.terminal.claim() {
-                        Ok(()) => break,
-                        Err(err) if retries == 10 => panic!("Failed to claim terminal: {}", err),
-                        Err(_) => continue,
-                    }
-                }
-
-                // redraw the terminal
-                let area = self.terminal.size();
-                self.compositor.resize(area);
-                self.terminal.clear().expect("couldn't clear terminal");
-
+                self.reclaim_term();
                 self.render().await;
             }
             signal::SIGUSR1 => {
@@ -564,6 +617,152 @@ pub async fn handle_signals(&mut self, signal: i32) -> bool {
         true
     }
 
+    fn handle_callback(&mut self, callback: anyhow::Result<Option<job::Callback>>) {
+        match callback {
+            Ok(Some(job::Callback::Suspended(call))) => {
+                if let Err(err) = self.restore_term() {
+                    self.editor
+                        .set_error(format!("Failed to release the terminal: {err}"));
+                    return;
+                }
+                call(&mut self.editor);
+                self.reclaim_term();
+                helix_event::dispatch(TerminalProgramDidExit {
+                    editor: &mut self.editor,
+                });
+            }
+            Ok(Some(job::Callback::Macro(replaying, keys))) => {
+                let replayed = std::mem::replace(&mut self.editor.macro_replaying, replaying);
+                let mut cx = crate::compositor::Context {
+                    editor: &mut self.editor,
+                    jobs: &mut self.jobs,
+                    scroll: None,
+                };
+                for key in keys {
+                    self.compositor.handle_event(&Event::Key(key), &mut cx);
+                }
+                self.editor.macro_replaying = replayed;
+            }
+            Ok(Some(job::Callback::Commands(sequence))) => {
+                let mut cx = commands::Context {
+                    register: None,
+                    count: None,
+                    editor: &mut self.editor,
+                    callback: Vec::new(),
+                    on_next_key_callback: None,
+                    jobs: &mut self.jobs,
+                };
+                commands::run_commands(&mut cx, sequence);
+                let callbacks = std::mem::take(&mut cx.callback);
+                let mut cx = crate::compositor::Context {
+                    editor: &mut self.editor,
+                    jobs: &mut self.jobs,
+                    scroll: None,
+                };
+                for callback in callbacks {
+                    callback(&mut self.compositor, &mut cx);
+                }
+            }
+            callback => {
+                self.jobs
+                    .handle_callback(&mut self.editor, &mut self.compositor, callback);
+            }
+        }
+    }
+
+    fn handle_remote_request(
+        &mut self,
+        request: remote::Request,
+        respond: tokio::sync::oneshot::Sender<remote::Response>,
+    ) {
+        use helix_view::editor::Action;
+
+        let result = match request {
+            remote::Request::Open { files, wait } => {
+                let mut opened = Vec::new();
+                let result = files.into_iter().try_for_each(|(path, positions)| {
+                    // Show the first file and load the rest in the background.
+                    let action = if opened.is_empty() {
+                        Action::Replace
+                    } else {
+                        Action::Load
+                    };
+                    let doc_id = self.editor.open(&path, action)?;
+                    let view_id = self.editor.tree.focus;
+                    let doc = doc_mut!(self.editor, &doc_id);
+                    if !positions.is_empty() {
+                        let text = doc.text().slice(..);
+                        let selection = positions
+                            .into_iter()
+                            .map(|(row, col)| {
+                                Range::point(pos_at_coords(text, (row, col).into(), true))
+                            })
+                            .collect();
+                        doc.set_selection(view_id, selection);
+                    }
+                    opened.push(doc_id);
+                    anyhow::Ok(())
+                });
+                if let Some(&first) = opened.first() {
+                    let (view, doc) = current!(self.editor);
+                    if doc.id() == first {
+                        align_view(doc, view, Align::Center);
+                    }
+                }
+                if result.is_ok() && wait {
+                    let closed: Vec<_> = opened.into_iter().map(remote::wait_for_close).collect();
+                    tokio::spawn(async move {
+                        for closed in closed {
+                            let _ = closed.await;
+                        }
+                        let _ = respond.send(remote::Response::Ok);
+                    });
+                    return;
+                }
+                result
+            }
+            remote::Request::Command(command) => {
+                let mut cx = crate::compositor::Context {
+                    editor: &mut self.editor,
+                    jobs: &mut self.jobs,
+                    scroll: None,
+                };
+                let command = command.strip_prefix(':').unwrap_or(&command);
+                commands::execute_command_line(&mut cx, command, ui::PromptEvent::Validate)
+            }
+        };
+
+        let response = match result {
+            Ok(()) => remote::Response::Ok,
+            Err(err) => {
+                let err = err.to_string();
+                self.editor.set_error(err.clone());
+                remote::Response::Error(err)
+            }
+        };
+        let _ = respond.send(response);
+    }
+
+    /// Claims the terminal again after it was handed to another process and clears it so that
+    /// the next render redraws the whole UI.
+    fn reclaim_term(&mut self) {
+        // Copy/Paste from same issue from neovim:
+        // https://github.com/neovim/neovim/issues/12322
+        // https://github.com/neovim/neovim/pull/13084
+        for retries in 1..=10 {
+            match self.terminal.claim() {
+                Ok(()) => break,
+                Err(err) if retries == 10 => panic!("Failed to claim terminal: {}", err),
+                Err(_) => continue,
+            }
+        }
+
+        // redraw the terminal
+        let area = self.terminal.size();
+        self.compositor.resize(area);
+        self.terminal.clear().expect("couldn't clear terminal");
+    }
+
     pub async fn handle_idle_timeout(&mut self) {
         let mut cx = crate::compositor::Context {
             editor: &mut self.editor,
@@ -603,7 +802,15 @@ pub fn handle_document_write(&mut self, doc_save_event: DocumentSavedEventResult
             doc_save_event.revision
         );
 
-        doc.set_last_saved_revision(doc_save_event.revision, doc_save_event.save_time);
+        doc.set_saved(&doc_save_event);
+        crate::undo::save(
+            doc,
+            &doc_save_event,
+            &self.config.load().editor.persistent_undo,
+        );
+        if !doc.is_modified() {
+            crate::recovery::discard(doc.id());
+        }
 
         let lines = doc_save_event.text.len_lines();
         let size = doc_save_event.text.len_bytes();
@@ -635,13 +842,28 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             Size::HumanReadable(size, SUFFIX[i])
         };
 
+        let applied_code_actions = std::mem::take(&mut doc.applied_code_actions);
+        let applied_code_actions = if applied_code_actions.is_empty() {
+            String::new()
+        } else {
+            format!(", applied {}", applied_code_actions.join(", "))
+        };
+
         self.editor
             .set_doc_path(doc_save_event.doc_id, &doc_save_event.path);
+        if doc_save_event.path.file_name() == Some(".editorconfig".as_ref()) {
+            self.editor
+                .refresh_editor_config(doc_save_event.path.parent());
+        }
         // TODO: fix being overwritten by lsp
         self.editor.set_status(format!(
-            "'{}' written, {lines}L {size}",
+            "'{}' written, {lines}L {size}{applied_code_actions}",
             get_relative_path(&doc_save_event.path).to_string_lossy(),
         ));
+        helix_event::dispatch(DocumentDidSave {
+            editor: &mut self.editor,
+            doc: doc_save_event.doc_id,
+        });
     }
 
     #[inline(always)]
@@ -871,8 +1093,8 @@ macro_rules! language_server {
                                 if message.is_some() {
                                     (None, message, &None)
                                 } else {
-                                    self.lsp_progress.end_progress(server_id, &token);
-                                    if !self.lsp_progress.is_progressing(server_id) {
+                                    self.editor.lsp_progress.end_progress(server_id, &token);
+                                    if !self.editor.lsp_progress.is_progressing(server_id) {
                                         editor_view.spinners_mut().get_or_create(server_id).stop();
                                     }
                                     self.editor.clear_status();
@@ -885,7 +1107,7 @@ macro_rules! language_server {
 
                         if self.editor.config().lsp.display_progress_messages {
                             let title =
-                                title.or_else(|| self.lsp_progress.title(server_id, &token));
+                                title.or_else(|| self.editor.lsp_progress.title(server_id, &token));
                             if title.is_some() || percentage.is_some() || message.is_some() {
                                 use std::fmt::Write as _;
                                 let mut status = format!("{}: ", language_server!().name());
@@ -907,16 +1129,22 @@ macro_rules! language_server {
 
                         match work {
                             lsp::WorkDoneProgress::Begin(begin_status) => {
-                                self.lsp_progress
-                                    .begin(server_id, token.clone(), begin_status);
+                                self.editor.lsp_progress.begin(
+                                    server_id,
+                                    token.clone(),
+                                    begin_status,
+                                );
                             }
                             lsp::WorkDoneProgress::Report(report_status) => {
-                                self.lsp_progress
-                                    .update(server_id, token.clone(), report_status);
+                                self.editor.lsp_progress.update(
+                                    server_id,
+                                    token.clone(),
+                                    report_status,
+                                );
                             }
                             lsp::WorkDoneProgress::End(_) => {
-                                self.lsp_progress.end_progress(server_id, &token);
-                                if !self.lsp_progress.is_progressing(server_id) {
+                                self.editor.lsp_progress.end_progress(server_id, &token);
+                                if !self.editor.lsp_progress.is_progressing(server_id) {
                                     editor_view.spinners_mut().get_or_create(server_id).stop();
                                 };
                             }
@@ -983,7 +1211,7 @@ macro_rules! language_server {
                         })
                     }
                     Ok(MethodCall::WorkDoneProgressCreate(params)) => {
-                        self.lsp_progress.create(server_id, params.token);
+                        self.editor.lsp_progress.create(server_id, params.token);
 
                         let editor_view = self
                             .compositor
@@ -1000,18 +1228,45 @@ macro_rules! language_server {
                         let language_server = language_server!();
                         if language_server.is_initialized() {
                             let offset_encoding = language_server.offset_encoding();
-                            let res = self
-                                .editor
-                                .apply_workspace_edit(offset_encoding, &params.edit);
-
-                            Ok(json!(lsp::ApplyWorkspaceEditResponse {
-                                applied: res.is_ok(),
-                                failure_reason: res.as_ref().err().map(|err| err.kind.to_string()),
-                                failed_change: res
-                                    .as_ref()
-                                    .err()
-                                    .map(|err| err.failed_change_idx as u32),
-                            }))
+                            let title = params
+                                .label
+                                .unwrap_or_else(|| format!("Edit of {}", language_server.name()));
+                            let id = id.clone();
+                            let on_done =
+                                move |editor: &mut Editor, res: Result<(), ApplyEditError>| {
+                                    let Some(language_server) =
+                                        editor.language_server_by_id(server_id)
+                                    else {
+                                        return;
+                                    };
+                                    let reply = json!(lsp::ApplyWorkspaceEditResponse {
+                                        applied: res.is_ok(),
+                                        failure_reason: res
+                                            .as_ref()
+                                            .err()
+                                            .map(|err| err.kind.to_string()),
+                                        failed_change: res
+                                            .as_ref()
+                                            .err()
+                                            .map(|err| err.failed_change_idx as u32),
+                                    });
+                                    if let Err(err) = language_server.reply(id.clone(), Ok(reply)) {
+                                        log::error!(
+                                        "Failed to send reply to server '{}' request {id}: {err}",
+                                        language_server.name()
+                                    );
+                                    }
+                                };
+                            self.editor.submit_workspace_edit(PendingWorkspaceEdit {
+                                source: WorkspaceEditSource::Server,
+                                title,
+                                offset_encoding,
+                                edit: params.edit,
+                                on_done: Some(Box::new(on_done)),
+                            });
+                            // The reply is sent once the edit is applied, which may wait for it
+                            // to be reviewed.
+                            return;
                         } else {
                             Err(helix_lsp::jsonrpc::Error {
                                 code: helix_lsp::jsonrpc::ErrorCode::InvalidRequest,
@@ -1122,6 +1377,30 @@ macro_rules! language_server {
                                 document,
                             );
                         }
+                        handlers::diagnostics::request_workspace_diagnostics(
+                            &mut self.editor,
+                            language_server,
+                        );
+
+                        Ok(serde_json::Value::Null)
+                    }
+                    Ok(MethodCall::SemanticTokensRefresh) => {
+                        let language_server = language_server!().id();
+
+                        let documents: Vec<_> = self
+                            .editor
+                            .documents
+                            .values()
+                            .filter(|x| x.supports_language_server(language_server))
+                            .map(|x| x.id())
+                            .collect();
+
+                        for document in documents {
+                            handlers::semantic_tokens::request_semantic_tokens(
+                                &mut self.editor,
+                                document,
+                            );
+                        }
 
                         Ok(serde_json::Value::Null)
                     }
@@ -1291,6 +1570,9 @@ pub async fn close(&mut self) -> Vec<anyhow::Error> {
             log::error!("Error writing: {}", err);
             errs.push(err);
         }
+        crate::recovery::discard_all();
+        crate::jumplist::save(&self.editor);
+        crate::registers::save(&self.editor);
 
         if self.editor.close_language_servers(None).await.is_err() {
             log::error!("Timed out waiting for language servers to shutdown");
@@ -19,6 +19,12 @@ pub struct Args {
     pub config_file: Option<PathBuf>,
     pub files: IndexMap<PathBuf, Vec<Position>>,
     pub working_directory: Option<PathBuf>,
+    /// Open the files in a running editor instead of starting a new one.
+    pub remote: bool,
+    /// Like `remote`, but only exit once the files have been closed.
+    pub remote_wait: bool,
+    /// A command to run in a running editor.
+    pub remote_send: Option<String>,
 }
 
 impl Args {
@@ -55,6 +61,18 @@ pub fn parse_args() -> Result<Args> {
                     Some(_) => anyhow::bail!("can only set a split once of a specific type"),
                     None => args.split = Some(Layout::Horizontal),
                 },
+                "--remote" => args.remote = true,
+                "--remote-wait" => {
+                    args.remote = true;
+                    args.remote_wait = true;
+                }
+                "--remote-send" => match argv.next() {
+                    Some(command) => {
+                        args.remote = true;
+                        args.remote_send = Some(command);
+                    }
+                    None => anyhow::bail!("--remote-send must specify a command to run"),
+                },
                 "--health" => {
                     args.health = true;
                     args.health_arg = argv.next_if(|opt| !opt.starts_with('-'));
@@ -15,7 +15,7 @@
 pub use syntax::*;
 use tui::{
     text::{Span, Spans},
-    widgets::Cell,
+    widgets::{Cell, Row},
 };
 pub use typed::*;
 
@@ -23,7 +23,7 @@
     char_idx_at_visual_offset,
     chars::char_is_word,
     command_line::{self, Args},
-    comment,
+    comment, digraph,
     doc_formatter::TextFormat,
     encoding, find_workspace,
     graphemes::{self, next_grapheme_boundary},
@@ -45,12 +45,13 @@
     Selection, SmallVec, Syntax, Tendril, Transaction,
 };
 use helix_view::{
-    document::{FormatterError, Mode, SCRATCH_BUFFER_NAME},
-    editor::Action,
+    document::{FormatterError, Mode, PrivilegedWrite, SCRATCH_BUFFER_NAME},
+    editor::{Action, BlockSelection, ReplayedMacro, SearchConfig},
     expansion,
     info::Info,
     input::KeyEvent,
     keyboard::KeyCode,
+    register::Yank,
     theme::Style,
     tree,
     view::View,
@@ -64,7 +65,7 @@
 
 use crate::{
     compositor::{self, Component, Compositor},
-    filter_picker_entry,
+    ctrl, filter_picker_entry,
     job::Callback,
     ui::{self, overlay::overlaid, Picker, PickerColumn, Popup, Prompt, PromptEvent},
 };
@@ -260,20 +261,30 @@ pub fn execute(&self, cx: &mut Context) {
                     {
                         cx.editor.set_error(format!("{}", e));
                     }
+                } else if !args.is_empty() {
+                    cx.editor
+                        .set_error(format!("'{name}': custom commands take no arguments"));
                 } else {
-                    cx.editor.set_error(format!("no such command: '{name}'"));
+                    match custom_command(cx.editor, name) {
+                        Ok(commands) => run_commands(cx, commands),
+                        Err(err) => cx.editor.set_error(err.to_string()),
+                    }
                 }
             }
             Self::Static { fun, .. } => (fun)(cx),
             Self::Macro { keys, .. } => {
                 // Protect against recursive macros.
-                if cx.editor.macro_replaying.contains(&'@') {
+                if cx
+                    .editor
+                    .macro_replaying
+                    .contains(&ReplayedMacro::Register('@'))
+                {
                     cx.editor.set_error(
                         "Cannot execute macro because the [@] register is already playing a macro",
                     );
                     return;
                 }
-                cx.editor.macro_replaying.push('@');
+                cx.editor.macro_replaying.push(ReplayedMacro::Register('@'));
                 let keys = keys.clone();
                 cx.callback.push(Box::new(move |compositor, cx| {
                     for key in keys.into_iter() {
@@ -407,8 +418,14 @@ pub fn doc(&self) -> &str {
         file_explorer_in_current_buffer_directory, "Open file explorer at current buffer's directory",
         file_explorer_in_current_directory, "Open file explorer at current working directory",
         code_action, "Perform code action",
+        source_action, "Perform source action on the document",
         buffer_picker, "Open buffer picker",
         jumplist_picker, "Open jumplist picker",
+        global_mark_picker, "Open global mark picker",
+        set_global_mark, "Set global mark",
+        goto_global_mark, "Goto global mark",
+        shell_profile_picker, "Open shell profile picker",
+        quickfix_picker, "Open picker of file locations in selections or the buffer",
         symbol_picker, "Open symbol picker",
         syntax_symbol_picker, "Open symbol picker from syntax information",
         lsp_or_syntax_symbol_picker, "Open symbol picker from LSP or syntax information",
@@ -441,6 +458,10 @@ pub fn doc(&self) -> &str {
         goto_file_hsplit, "Goto files in selections (hsplit)",
         goto_file_vsplit, "Goto files in selections (vsplit)",
         goto_reference, "Goto references",
+        incoming_calls, "Open incoming calls picker",
+        outgoing_calls, "Open outgoing calls picker",
+        supertypes, "Open supertypes picker",
+        subtypes, "Open subtypes picker",
         goto_window_top, "Goto window top",
         goto_window_center, "Goto window center",
         goto_window_bottom, "Goto window bottom",
@@ -487,6 +508,7 @@ pub fn doc(&self) -> &str {
         redo, "Redo change",
         earlier, "Move backward in history",
         later, "Move forward in history",
+        undo_tree_picker, "Open undo tree picker",
         commit_undo_checkpoint, "Commit changes to new checkpoint",
         yank, "Yank selection",
         yank_to_clipboard, "Yank selections to clipboard",
@@ -499,8 +521,11 @@ pub fn doc(&self) -> &str {
         replace_with_yanked, "Replace with yanked text",
         replace_selections_with_clipboard, "Replace selections by clipboard content",
         replace_selections_with_primary_clipboard, "Replace selections by primary clipboard",
+        paste_from_history, "Open picker of recent yanks to paste",
+        paste_previous_yank, "Replace the pasted yank with the previous one in the yank history",
         paste_after, "Paste after selection",
         paste_before, "Paste before selection",
+        paste_block, "Paste the lines of a yank as a rectangle at the cursor",
         paste_clipboard_after, "Paste clipboard after selections",
         paste_clipboard_before, "Paste clipboard before selections",
         paste_primary_clipboard_after, "Paste primary clipboard after selections",
@@ -513,10 +538,13 @@ pub fn doc(&self) -> &str {
         keep_selections, "Keep selections matching regex",
         remove_selections, "Remove selections matching regex",
         align_selections, "Align selections in column",
+        select_block, "Select the rectangle between the anchor and cursor of the selection",
         keep_primary_selection, "Keep primary selection",
         remove_primary_selection, "Remove primary selection",
         completion, "Invoke completion popup",
         hover, "Show docs for item under cursor",
+        open_external_docs, "Open the documentation of the symbol under cursor",
+        spell_suggest, "Suggest corrections of the word under cursor",
         toggle_comments, "Comment/uncomment selections",
         toggle_line_comments, "Line comment/uncomment selections",
         toggle_block_comments, "Block comment/uncomment selections",
@@ -553,6 +581,9 @@ pub fn doc(&self) -> &str {
         wonly, "Close windows except current",
         select_register, "Select register",
         insert_register, "Insert register",
+        insert_digraph, "Insert the character of a digraph",
+        insert_unicode, "Insert a character by its code point, or the next key literally",
+        unicode_picker, "Open picker of symbols, digraphs and emoji to insert",
         copy_between_registers, "Copy between two registers",
         align_view_middle, "Align view middle",
         align_view_top, "Align view top",
@@ -560,6 +591,12 @@ pub fn doc(&self) -> &str {
         align_view_bottom, "Align view bottom",
         scroll_up, "Scroll view up",
         scroll_down, "Scroll view down",
+        fold, "Fold the region at each cursor",
+        unfold, "Unfold the folds at each cursor",
+        toggle_fold, "Toggle the fold at each cursor",
+        fold_all, "Fold every region",
+        unfold_all, "Unfold every fold",
+        toggle_zen_mode, "Toggle showing the focused view alone and centered",
         match_brackets, "Goto matching bracket",
         surround_add, "Surround add",
         surround_replace, "Surround replace",
@@ -593,7 +630,9 @@ pub fn doc(&self) -> &str {
         dap_variables, "List variables",
         dap_terminate, "End debug session",
         dap_edit_condition, "Edit breakpoint condition on current line",
+        dap_edit_hit_condition, "Edit breakpoint hit count on current line",
         dap_edit_log, "Edit breakpoint log message on current line",
+        dap_edit_breakpoint, "Edit breakpoint condition, hit count and log message on current line",
         dap_switch_thread, "Switch current thread",
         dap_switch_stack_frame, "Switch stack frame",
         dap_enable_exceptions, "Enable exception breakpoints",
@@ -613,7 +652,7 @@ pub fn doc(&self) -> &str {
         goto_word, "Jump to a two-character label",
         extend_to_word, "Extend to a two-character label",
         goto_next_tabstop, "Goto next snippet placeholder",
-        goto_prev_tabstop, "Goto next snippet placeholder",
+        goto_prev_tabstop, "Goto previous snippet placeholder",
         rotate_selections_first, "Make the first selection your primary one",
         rotate_selections_last, "Make the last selection your primary one",
     );
@@ -645,6 +684,56 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     }
 }
 
+/// Parses the commands of the custom command `name` set in `editor.commands`. They can't run
+/// custom commands themselves, which could recurse forever.
+pub fn custom_command(editor: &Editor, name: &str) -> anyhow::Result<Vec<MappableCommand>> {
+    let config = editor.config();
+    let Some(commands) = config.commands.get(name) else {
+        bail!("no such command: '{name}'");
+    };
+    commands
+        .iter()
+        .map(|command| {
+            let command: MappableCommand = command.parse()?;
+            match &command {
+                MappableCommand::Typable { name: inner, .. }
+                    if !typed::TYPABLE_COMMAND_MAP.contains_key(inner.as_str()) =>
+                {
+                    bail!("'{name}': custom commands can't run the custom command '{inner}'")
+                }
+                _ => Ok(command),
+            }
+        })
+        .collect()
+}
+
+/// Runs the commands one after the other. The commands after a macro run once its keys have
+/// been handled.
+pub fn run_commands(cx: &mut Context, commands: Vec<MappableCommand>) {
+    let mut commands = commands.into_iter();
+    while let Some(command) = commands.next() {
+        command.execute(cx);
+        if matches!(command, MappableCommand::Macro { .. }) {
+            let rest: Vec<_> = commands.collect();
+            cx.callback.push(Box::new(move |compositor, cx| {
+                let mut ctx = Context {
+                    register: None,
+                    count: None,
+                    editor: cx.editor,
+                    callback: Vec::new(),
+                    on_next_key_callback: None,
+                    jobs: cx.jobs,
+                };
+                run_commands(&mut ctx, rest);
+                for callback in ctx.callback {
+                    callback(compositor, cx);
+                }
+            }));
+            return;
+        }
+    }
+}
+
 impl std::str::FromStr for MappableCommand {
     type Err = anyhow::Error;
 
@@ -652,7 +741,7 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
         if let Some(suffix) = s.strip_prefix(':') {
             let (name, args, _) = command_line::split(suffix);
             ensure!(!name.is_empty(), "Expected typable command name");
-            typed::TYPABLE_COMMAND_MAP
+            Ok(typed::TYPABLE_COMMAND_MAP
                 .get(name)
                 .map(|cmd| {
                     let doc = if args.is_empty() {
@@ -666,7 +755,12 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
                         args: args.to_string(),
                     }
                 })
-                .ok_or_else(|| anyhow!("No TypableCommand named '{}'", s))
+                // Names of custom commands are checked once the config is loaded.
+                .unwrap_or_else(|| MappableCommand::Typable {
+                    name: name.to_owned(),
+                    doc: format!(":{name}"),
+                    args: args.to_string(),
+                }))
         } else if let Some(suffix) = s.strip_prefix('@') {
             helix_view::input::parse_macro(suffix).map(|keys| Self::Macro {
                 name: s.to_string(),
@@ -917,14 +1011,15 @@ fn goto_previous_buffer(cx: &mut Context) {
 fn goto_buffer(editor: &mut Editor, direction: Direction, count: usize) {
     let current = view!(editor).doc;
 
+    let order = editor.buffer_order();
     let id = match direction {
         Direction::Forward => {
-            let iter = editor.documents.keys();
+            let iter = order.iter();
             // skip 'count' times past current buffer
             iter.cycle().skip_while(|id| *id != &current).nth(count)
         }
         Direction::Backward => {
-            let iter = editor.documents.keys();
+            let iter = order.iter();
             // skip 'count' times past current buffer
             iter.rev()
                 .cycle()
@@ -1127,6 +1222,135 @@ fn align_selections(cx: &mut Context) {
     exit_select_mode(cx);
 }
 
+/// How `:align` lines up the selections or matches of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Alignment {
+    Left,
+    Center,
+    Right,
+}
+
+/// Pads the ranges with spaces so that the n-th range of each line lines up with the n-th
+/// ranges of the other lines, like the columns of a table.
+#[allow(deprecated)]
+pub(crate) fn align_ranges(
+    editor: &mut Editor,
+    ranges: &[Range],
+    alignment: Alignment,
+) -> anyhow::Result<()> {
+    use helix_core::visual_coords_at_pos;
+
+    struct Cell {
+        line: usize,
+        from: usize,
+        col: usize,
+        width: usize,
+    }
+
+    let (view, doc) = current!(editor);
+    let text = doc.text().slice(..);
+    let tab_width = doc.tab_width();
+
+    let mut columns: Vec<Vec<Cell>> = Vec::new();
+    let mut last_line = None;
+    let mut col = 0;
+    for range in ranges {
+        let start = visual_coords_at_pos(text, range.from(), tab_width);
+        let end = visual_coords_at_pos(text, range.to(), tab_width);
+        ensure!(
+            start.row == end.row,
+            "align cannot work with multi line selections"
+        );
+        col = if last_line == Some(start.row) {
+            col + 1
+        } else {
+            0
+        };
+        if col >= columns.len() {
+            columns.push(Vec::new());
+        }
+        columns[col].push(Cell {
+            line: start.row,
+            from: range.from(),
+            col: start.col,
+            width: end.col - start.col,
+        });
+        last_line = Some(start.row);
+    }
+
+    // The number of spaces inserted on each line so far.
+    let mut offsets: HashMap<usize, usize> = HashMap::new();
+    let mut changes = Vec::with_capacity(ranges.len());
+    for column in columns {
+        let start = column
+            .iter()
+            .map(|cell| cell.col + offsets.get(&cell.line).copied().unwrap_or(0))
+            .max()
+            .unwrap_or(0);
+        let width = column.iter().map(|cell| cell.width).max().unwrap_or(0);
+        for cell in column {
+            let offset = offsets.entry(cell.line).or_default();
+            let padding = width - cell.width;
+            let count = start - (cell.col + *offset)
+                + match alignment {
+                    Alignment::Left => 0,
+                    Alignment::Center => padding / 2,
+                    Alignment::Right => padding,
+                };
+            if count == 0 {
+                continue;
+            }
+            *offset += count;
+            changes.push((cell.from, cell.from, Some(" ".repeat(count).into())));
+        }
+    }
+
+    // The changeset has to be sorted
+    changes.sort_unstable_by_key(|(from, _, _)| *from);
+
+    let transaction = Transaction::change(doc.text(), changes.into_iter());
+    doc.apply(&transaction, view.id);
+    doc.append_changes_to_history(view);
+    Ok(())
+}
+
+/// Turns the primary selection into a rectangle with its anchor and cursor at opposite corners,
+/// with a range for each line, and keeps it in shape as the cursor moves in select mode. Using
+/// it again while the rectangle is extended leaves the ranges as separate selections.
+#[allow(deprecated)]
+fn select_block(cx: &mut Context) {
+    use helix_core::visual_coords_at_pos;
+
+    if cx.editor.block_selection.take().is_some() {
+        exit_select_mode(cx);
+        return;
+    }
+
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let tab_width = doc.tab_width();
+    let range = doc.selection(view.id).primary();
+    // The character under the anchor, which is the last of a backward range.
+    let anchor = if range.head < range.anchor {
+        graphemes::prev_grapheme_boundary(text, range.anchor)
+    } else {
+        range.anchor
+    };
+    let anchor = visual_coords_at_pos(text, anchor, tab_width);
+    let head = visual_coords_at_pos(text, range.cursor(text), tab_width);
+    doc.set_selection(
+        view.id,
+        selection::select_block(text, anchor, head, tab_width),
+    );
+    cx.editor.block_selection = Some(BlockSelection {
+        view_id: view.id,
+        version: doc.version(),
+        anchor,
+        head,
+    });
+    cx.editor.mode = Mode::Select;
+}
+
 fn goto_window(cx: &mut Context, align: Align) {
     let count = cx.count() - 1;
     let config = cx.editor.config();
@@ -2286,6 +2510,24 @@ fn searcher(cx: &mut Context, direction: Direction) {
     );
 }
 
+/// Builds the regex used to repeat the search for `query`, which is case insensitive if smart
+/// case is enabled and the query is all lowercase.
+pub(crate) fn search_regex(query: &str, search_config: &SearchConfig) -> Option<rope::Regex> {
+    let case_insensitive = if search_config.smart_case {
+        !query.chars().any(char::is_uppercase)
+    } else {
+        false
+    };
+    rope::RegexBuilder::new()
+        .syntax(
+            rope::Config::new()
+                .case_insensitive(case_insensitive)
+                .multi_line(true),
+        )
+        .build(query)
+        .ok()
+}
+
 fn search_next_or_prev_impl(cx: &mut Context, movement: Movement, direction: Direction) {
     let count = cx.count();
     let register = cx
@@ -2294,21 +2536,8 @@ fn search_next_or_prev_impl(cx: &mut Context, movement: Movement, direction: Dir
     let config = cx.editor.config();
     let scrolloff = config.scrolloff;
     if let Some(query) = cx.editor.registers.first(register, cx.editor) {
-        let search_config = &config.search;
-        let case_insensitive = if search_config.smart_case {
-            !query.chars().any(char::is_uppercase)
-        } else {
-            false
-        };
-        let wrap_around = search_config.wrap_around;
-        if let Ok(regex) = rope::RegexBuilder::new()
-            .syntax(
-                rope::Config::new()
-                    .case_insensitive(case_insensitive)
-                    .multi_line(true),
-            )
-            .build(&query)
-        {
+        let wrap_around = config.search.wrap_around;
+        if let Some(regex) = search_regex(&query, &config.search) {
             for _ in 0..count {
                 search_impl(
                     cx.editor,
@@ -2873,10 +3102,10 @@ enum YankAction {
 }
 
 fn delete_selection_impl(cx: &mut Context, op: Operation, yank: YankAction) {
-    let (view, doc) = current!(cx.editor);
+    let (view, doc) = current_ref!(cx.editor);
 
-    let selection = doc.selection(view.id);
-    let only_whole_lines = selection_is_linewise(selection, doc.text());
+    let selection = doc.selection(view.id).clone();
+    let only_whole_lines = selection_is_linewise(&selection, doc.text());
 
     if cx.register != Some('_') && matches!(yank, YankAction::Yank) {
         // yank the selection
@@ -2885,6 +3114,7 @@ fn delete_selection_impl(cx: &mut Context, op: Operation, yank: YankAction) {
         let reg_name = cx
             .register
             .unwrap_or_else(|| cx.editor.config.load().default_yank_register);
+        record_yank(cx.editor, reg_name, &values);
         if let Err(err) = cx.editor.registers.write(reg_name, values) {
             cx.editor.set_error(err.to_string());
             return;
@@ -2892,8 +3122,10 @@ fn delete_selection_impl(cx: &mut Context, op: Operation, yank: YankAction) {
     }
 
     // delete the selection
-    let transaction =
-        Transaction::delete_by_selection(doc.text(), selection, |range| (range.from(), range.to()));
+    let (view, doc) = current!(cx.editor);
+    let transaction = Transaction::delete_by_selection(doc.text(), &selection, |range| {
+        (range.from(), range.to())
+    });
     doc.apply(&transaction, view.id);
 
     match op {
@@ -3059,14 +3291,93 @@ fn append_mode(cx: &mut Context) {
     doc.set_selection(view.id, selection);
 }
 
+/// Runs the `editor.external-picker` command on the terminal released by the editor. The
+/// `candidates` are written to its stdin while it runs and the lines it prints are returned,
+/// which are none if the picker was cancelled.
+fn run_external_picker(
+    shell: &[String],
+    picker: &str,
+    cwd: &Path,
+    candidates: impl Iterator<Item = String> + Send + 'static,
+) -> anyhow::Result<Vec<String>> {
+    use std::io::{BufWriter, Write};
+    use std::process::{Command, Stdio};
+
+    ensure!(!shell.is_empty(), "No shell set");
+    let mut child = Command::new(&shell[0])
+        .args(&shell[1..])
+        .arg(picker)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start external picker '{picker}'"))?;
+
+    // Stream the candidates so that the picker can be used before they are all known. The
+    // writes start failing once the picker exits, which ends the iteration.
+    let stdin = child.stdin.take().expect("stdin is piped");
+    std::thread::spawn(move || {
+        let mut stdin = BufWriter::new(stdin);
+        for candidate in candidates {
+            if writeln!(stdin, "{candidate}").is_err() {
+                break;
+            }
+        }
+    });
+
+    let output = child.wait_with_output()?;
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()),
+        // fzf and skim exit with 1 if nothing matched and with 130 when cancelled.
+        Some(1 | 130) => Ok(Vec::new()),
+        _ => bail!("External picker '{picker}' failed: {}", output.status),
+    }
+}
+
+/// Opens the files below `root` with the configured external picker or the built-in one.
+fn open_file_picker(cx: &mut Context, root: PathBuf) {
+    let config = cx.editor.config();
+    let Some(picker) = config.external_picker.clone() else {
+        let picker = ui::file_picker(cx.editor, root);
+        cx.push_layer(Box::new(overlaid(picker)));
+        return;
+    };
+
+    let shell = config.shell.clone();
+    let prefix = root.clone();
+    let candidates = ui::picker_files(cx.editor, &root).map(move |path| {
+        let path = path.strip_prefix(&prefix).unwrap_or(&path);
+        path.to_string_lossy().into_owned()
+    });
+    let callback = Callback::Suspended(Box::new(move |editor: &mut Editor| {
+        let selected = match run_external_picker(&shell, &picker, &root, candidates) {
+            Ok(selected) => selected,
+            Err(err) => {
+                editor.set_error(err.to_string());
+                return;
+            }
+        };
+        for path in selected {
+            let path = root.join(path);
+            if let Err(err) = editor.open(&path, Action::Replace) {
+                editor.set_error(format!("Failed to open '{}': {err}", path.display()));
+            }
+        }
+    }));
+    cx.jobs.callback(async move { Ok(callback) });
+}
+
 fn file_picker(cx: &mut Context) {
     let root = find_workspace().0;
     if !root.exists() {
         cx.editor.set_error("Workspace directory does not exist");
         return;
     }
-    let picker = ui::file_picker(cx.editor, root);
-    cx.push_layer(Box::new(overlaid(picker)));
+    open_file_picker(cx, root);
 }
 
 fn file_picker_in_current_buffer_directory(cx: &mut Context) {
@@ -3082,8 +3393,7 @@ fn file_picker_in_current_buffer_directory(cx: &mut Context) {
         }
     };
 
-    let picker = ui::file_picker(cx.editor, path);
-    cx.push_layer(Box::new(overlaid(picker)));
+    open_file_picker(cx, path);
 }
 
 fn file_picker_in_current_directory(cx: &mut Context) {
@@ -3093,8 +3403,7 @@ fn file_picker_in_current_directory(cx: &mut Context) {
             .set_error("Current working directory does not exist");
         return;
     }
-    let picker = ui::file_picker(cx.editor, cwd);
-    cx.push_layer(Box::new(overlaid(picker)));
+    open_file_picker(cx, cwd);
 }
 
 fn file_explorer(cx: &mut Context) {
@@ -3154,7 +3463,7 @@ fn buffer_picker(cx: &mut Context) {
 
     struct BufferMeta {
         id: DocumentId,
-        path: Option<PathBuf>,
+        name: String,
         is_modified: bool,
         is_current: bool,
         focused_at: std::time::Instant,
@@ -3162,7 +3471,7 @@ struct BufferMeta {
 
     let new_meta = |doc: &Document| BufferMeta {
         id: doc.id(),
-        path: doc.path().cloned(),
+        name: doc.display_name().into_owned(),
         is_modified: doc.is_modified(),
         is_current: doc.id() == current,
         focused_at: doc.focused_at,
@@ -3178,6 +3487,42 @@ struct BufferMeta {
     // mru
     items.sort_unstable_by_key(|item| std::cmp::Reverse(item.focused_at));
 
+    let config = cx.editor.config();
+    if let Some(picker) = config.external_picker.clone() {
+        let shell = config.shell.clone();
+        // Prefix every buffer with its position so that the selected lines can be mapped back.
+        let candidates: Vec<_> = items
+            .iter()
+            .enumerate()
+            .map(|(i, meta)| {
+                let modified = if meta.is_modified { " [+]" } else { "" };
+                format!("{i}: {}{modified}", meta.name)
+            })
+            .collect();
+        let ids: Vec<_> = items.iter().map(|meta| meta.id).collect();
+        let callback = Callback::Suspended(Box::new(move |editor: &mut Editor| {
+            let cwd = helix_stdx::env::current_working_dir();
+            match run_external_picker(&shell, &picker, &cwd, candidates.into_iter()) {
+                Ok(selected) => {
+                    for line in selected {
+                        let id = line
+                            .split_once(':')
+                            .and_then(|(i, _)| ids.get(i.parse::<usize>().ok()?));
+                        match id {
+                            Some(&id) if editor.documents.contains_key(&id) => {
+                                editor.switch(id, Action::Replace)
+                            }
+                            _ => editor.set_error(format!("No buffer matching '{line}'")),
+                        }
+                    }
+                }
+                Err(err) => editor.set_error(err.to_string()),
+            }
+        }));
+        cx.jobs.callback(async move { Ok(callback) });
+        return;
+    }
+
     let columns = [
         PickerColumn::new("id", |meta: &BufferMeta, _| meta.id.to_string().into()),
         PickerColumn::new("flags", |meta: &BufferMeta, _| {
@@ -3190,17 +3535,7 @@ struct BufferMeta {
             }
             flags.into()
         }),
-        PickerColumn::new("path", |meta: &BufferMeta, _| {
-            let path = meta
-                .path
-                .as_deref()
-                .map(helix_stdx::path::get_relative_path);
-            path.as_deref()
-                .and_then(Path::to_str)
-                .unwrap_or(SCRATCH_BUFFER_NAME)
-                .to_string()
-                .into()
-        }),
+        PickerColumn::new("path", |meta: &BufferMeta, _| meta.name.as_str().into()),
     ];
     let initial_cursor = if items.len() <= 1 { 0 } else { 1 };
     let picker = Picker::new(columns, 2, items, (), |cx, meta, action| {
@@ -3208,7 +3543,7 @@ struct BufferMeta {
     })
     .with_initial_cursor(initial_cursor)
     .with_preview(|editor, meta| {
-        let doc = &editor.documents.get(&meta.id)?;
+        let doc = try_doc!(editor, &meta.id)?;
         let lines = doc.selections().values().next().map(|selection| {
             let cursor_line = selection.primary().cursor_line(doc.text().slice(..));
             (cursor_line, cursor_line)
@@ -3223,6 +3558,8 @@ struct JumpMeta {
         id: DocumentId,
         path: Option<PathBuf>,
         selection: Selection,
+        /// The 0-based line of the primary cursor.
+        line: usize,
         text: String,
         is_current: bool,
     }
@@ -3235,7 +3572,7 @@ struct JumpMeta {
     }
 
     let new_meta = |view: &View, doc_id: DocumentId, selection: Selection| {
-        let doc = &cx.editor.documents.get(&doc_id);
+        let doc = try_doc!(cx.editor, &doc_id);
         let text = doc.map_or("".into(), |d| {
             selection
                 .fragments(d.text().slice(..))
@@ -3243,11 +3580,13 @@ struct JumpMeta {
                 .collect::<Vec<_>>()
                 .join(" ")
         });
+        let line = doc.map_or(0, |d| selection.primary().cursor_line(d.text().slice(..)));
 
         JumpMeta {
             id: doc_id,
             path: doc.and_then(|d| d.path().cloned()),
             selection,
+            line,
             text,
             is_current: view.doc == doc_id,
         }
@@ -3266,6 +3605,9 @@ struct JumpMeta {
                 .to_string()
                 .into()
         }),
+        ui::PickerColumn::new("line", |item: &JumpMeta, _| {
+            (item.line + 1).to_string().into()
+        }),
         ui::PickerColumn::new("flags", |item: &JumpMeta, _| {
             let mut flags = Vec::new();
             if item.is_current {
@@ -3294,7 +3636,11 @@ struct JumpMeta {
         |cx, meta, action| {
             cx.editor.switch(meta.id, action);
             let config = cx.editor.config();
-            let (view, doc) = (view_mut!(cx.editor), doc_mut!(cx.editor, &meta.id));
+            let Some(doc) = try_doc_mut!(cx.editor, &meta.id) else {
+                cx.editor.set_error("The buffer has been closed");
+                return;
+            };
+            let view = view_mut!(cx.editor);
             doc.set_selection(view.id, meta.selection.clone());
             if action.align_view(view, doc.id()) {
                 view.ensure_cursor_in_view_center(doc, config.scrolloff);
@@ -3302,13 +3648,205 @@ struct JumpMeta {
         },
     )
     .with_preview(|editor, meta| {
-        let doc = &editor.documents.get(&meta.id)?;
-        let line = meta.selection.primary().cursor_line(doc.text().slice(..));
-        Some((meta.id.into(), Some((line, line))))
+        try_doc!(editor, &meta.id)?;
+        Some((meta.id.into(), Some((meta.line, meta.line))))
+    });
+    cx.push_layer(Box::new(overlaid(picker)));
+}
+
+fn global_marks_info(title: &'static str) -> Info {
+    let marks: Vec<_> = crate::marks::load()
+        .into_iter()
+        .map(|(name, mark)| {
+            let path = helix_stdx::path::get_relative_path(&mark.path);
+            (
+                name.to_string(),
+                format!("{}:{}", path.display(), mark.line + 1),
+            )
+        })
+        .collect();
+    Info::new(title, &marks)
+}
+
+fn set_global_mark(cx: &mut Context) {
+    cx.editor.autoinfo = Some(global_marks_info("Set global mark"));
+    cx.on_next_key(move |cx, event| {
+        cx.editor.autoinfo = None;
+        let Some(name) = event.char() else {
+            return;
+        };
+        match crate::marks::set(cx.editor, name) {
+            Ok(()) => cx.editor.set_status(format!("Set mark '{name}'")),
+            Err(err) => cx.editor.set_error(err.to_string()),
+        }
+    })
+}
+
+fn goto_global_mark(cx: &mut Context) {
+    cx.editor.autoinfo = Some(global_marks_info("Goto global mark"));
+    cx.on_next_key(move |cx, event| {
+        cx.editor.autoinfo = None;
+        let Some(name) = event.char() else {
+            return;
+        };
+        if let Err(err) = crate::marks::goto_name(cx.editor, name) {
+            cx.editor.set_error(err.to_string());
+        }
+    })
+}
+
+fn global_mark_picker(cx: &mut Context) {
+    cx.push_layer(Box::new(overlaid(crate::marks::picker())));
+}
+
+fn shell_profile_picker(cx: &mut Context) {
+    struct ProfileMeta {
+        name: String,
+        command: String,
+        is_default: bool,
+    }
+
+    let config = cx.editor.config();
+    let default = config.interactive.default_profile.as_deref();
+    let mut items: Vec<_> = config
+        .interactive
+        .profiles
+        .iter()
+        .map(|(name, profile)| ProfileMeta {
+            name: name.clone(),
+            command: std::iter::once(&profile.command)
+                .chain(&profile.args)
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+            is_default: Some(name.as_str()) == default,
+        })
+        .collect();
+    if items.is_empty() {
+        cx.editor
+            .set_error("No shell profiles in `editor.interactive.profiles`");
+        return;
+    }
+    // List the default profile first so that it is picked by pressing enter.
+    items.sort_by_key(|item| !item.is_default);
+
+    let columns = [
+        PickerColumn::new("name", |item: &ProfileMeta, _| item.name.as_str().into()),
+        PickerColumn::new("flags", |item: &ProfileMeta, _| {
+            if item.is_default { "*" } else { "" }.into()
+        }),
+        PickerColumn::new("command", |item: &ProfileMeta, _| {
+            item.command.as_str().into()
+        }),
+    ];
+    let picker = Picker::new(columns, 0, items, (), |cx, item, _action| {
+        if let Err(err) = typed::start_shell_profile(cx.jobs, cx.editor, &item.name) {
+            cx.editor.set_error(err.to_string());
+        }
     });
     cx.push_layer(Box::new(overlaid(picker)));
 }
 
+/// Opens a picker over the `path:line:column` references found in the selected text, or in the
+/// whole buffer when only single characters are selected. This is useful for jumping to code
+/// from the output of grep, test runners or build tools inserted with `:insert-output`.
+fn quickfix_picker(cx: &mut Context) {
+    struct QuickfixItem {
+        path: PathBuf,
+        /// 0 indexed lines
+        line: usize,
+        /// 0 indexed columns
+        column: Option<usize>,
+        text: String,
+    }
+
+    let (view, doc) = current_ref!(cx.editor);
+    let selection = doc.selection(view.id);
+    let text = if selection.ranges().iter().any(|range| range.len() > 1) {
+        selection
+            .fragments(doc.text().slice(..))
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        doc.text().to_string()
+    };
+
+    let cwd = helix_stdx::env::current_working_dir();
+    let mut items: Vec<QuickfixItem> = Vec::new();
+    for location in helix_core::error_format::find_locations(&text) {
+        let path = helix_stdx::path::normalize(cwd.join(&location.path));
+        let line = location.line.saturating_sub(1);
+        let column = location.column.map(|column| column.saturating_sub(1));
+        if !path.is_file()
+            || items
+                .iter()
+                .any(|item| item.path == path && item.line == line && item.column == column)
+        {
+            continue;
+        }
+        items.push(QuickfixItem {
+            path,
+            line,
+            column,
+            text: location.message,
+        });
+    }
+
+    if items.is_empty() {
+        cx.editor.set_error("No file locations found");
+        return;
+    }
+
+    let columns = [
+        ui::PickerColumn::new("path", |item: &QuickfixItem, _| {
+            let path = helix_stdx::path::get_relative_path(&item.path);
+            match item.column {
+                Some(column) => format!("{}:{}:{}", path.display(), item.line + 1, column + 1),
+                None => format!("{}:{}", path.display(), item.line + 1),
+            }
+            .into()
+        }),
+        ui::PickerColumn::new("contents", |item: &QuickfixItem, _| {
+            item.text.as_str().into()
+        }),
+    ];
+
+    let picker = Picker::new(columns, 0, items, (), |cx, item, action| {
+        let doc = match cx.editor.open(&item.path, action) {
+            Ok(id) => doc_mut!(cx.editor, &id),
+            Err(e) => {
+                cx.editor.set_error(format!(
+                    "Failed to open file '{}': {}",
+                    item.path.display(),
+                    e
+                ));
+                return;
+            }
+        };
+
+        let view = view_mut!(cx.editor);
+        let text = doc.text();
+        if item.line >= text.len_lines() {
+            cx.editor.set_error(
+                "The line you jumped to does not exist anymore because the file has changed.",
+            );
+            return;
+        }
+        let line_start = text.line_to_char(item.line);
+        let line_end = line_end_char_index(&text.slice(..), item.line);
+        let pos = (line_start + item.column.unwrap_or(0)).min(line_end);
+
+        push_jump(view, doc);
+        doc.set_selection(view.id, Selection::point(pos));
+        if action.align_view(view, doc.id()) {
+            align_view(doc, view, Align::Center);
+        }
+    })
+    .with_preview(|_editor, item| Some((item.path.as_path().into(), Some((item.line, item.line)))));
+
+    cx.push_layer(Box::new(overlaid(picker)));
+}
+
 fn changed_file_picker(cx: &mut Context) {
     pub struct FileChangeData {
         cwd: PathBuf,
@@ -3585,12 +4123,15 @@ fn insert_with_indent(cx: &mut Context, cursor_fallback: IndentFallbackPos) {
 //
 // TODO: provide some way to cancel this, probably as part of a more general job cancellation
 // scheme
+/// The name of the output buffer collecting the errors printed by formatters.
+const FORMATTER_OUTPUT_NAME: &str = "[formatter]";
+
 async fn make_format_callback(
     doc_id: DocumentId,
     doc_version: i32,
     view_id: ViewId,
     format: impl Future<Output = Result<Transaction, FormatterError>> + Send + 'static,
-    write: Option<(Option<PathBuf>, bool)>,
+    write: Option<(Option<PathBuf>, bool, Option<PrivilegedWrite>)>,
 ) -> anyhow::Result<job::Callback> {
     let format = format.await;
 
@@ -3599,6 +4140,11 @@ async fn make_format_callback(
             return;
         }
 
+        if let Err(FormatterError::NonZeroExitStatus(Some(stderr))) = &format {
+            let name = doc!(editor, &doc_id).display_name().into_owned();
+            editor.append_output(FORMATTER_OUTPUT_NAME, &format!("{name}:\n{stderr}\n"));
+        }
+
         let scrolloff = editor.config().scrolloff;
         let doc = doc_mut!(editor, &doc_id);
         let view = view_mut!(editor, view_id);
@@ -3623,9 +4169,13 @@ async fn make_format_callback(
             }
         }
 
-        if let Some((path, force)) = write {
+        if let Some((path, force, privileged)) = write {
             let id = doc.id();
-            if let Err(err) = editor.save(id, path, force) {
+            let result = match privileged {
+                Some(privileged) => editor.save_privileged(id, path, force, privileged),
+                None => editor.save(id, path, force),
+            };
+            if let Err(err) = result {
                 editor.set_error(format!("Error saving: {}", err));
             }
         }
@@ -4578,6 +5128,95 @@ fn later(cx: &mut Context) {
     }
 }
 
+fn undo_tree_picker(cx: &mut Context) {
+    struct RevisionMeta {
+        revision: usize,
+        /// How deep the revision is nested in branches of the history.
+        level: usize,
+        is_current: bool,
+        age: std::time::Duration,
+        change: String,
+    }
+
+    let (view, doc) = current!(cx.editor);
+    doc.append_changes_to_history(view);
+    let doc_id = doc.id();
+    let history = doc.history.get_mut();
+    let current = history.current_revision();
+    let items: Vec<_> = history
+        .tree()
+        .into_iter()
+        .map(|(revision, level)| RevisionMeta {
+            revision,
+            level,
+            is_current: revision == current,
+            age: history.timestamp(revision).elapsed(),
+            change: if revision == 0 {
+                "(original)".to_string()
+            } else {
+                describe_change(history.transaction(revision))
+            },
+        })
+        .collect();
+    let cursor = items
+        .iter()
+        .position(|item| item.is_current)
+        .unwrap_or_default();
+
+    let columns = [
+        ui::PickerColumn::new("tree", |item: &RevisionMeta, _| {
+            let node = if item.is_current { "@" } else { "o" };
+            format!("{}{node} {}", "│ ".repeat(item.level), item.revision).into()
+        }),
+        ui::PickerColumn::new("age", |item: &RevisionMeta, _| format_age(item.age).into()),
+        ui::PickerColumn::new("change", |item: &RevisionMeta, _| {
+            item.change.as_str().into()
+        }),
+    ];
+
+    let picker = Picker::new(columns, 2, items, (), move |cx, meta, _action| {
+        let scrolloff = cx.editor.config().scrolloff;
+        let (view, doc) = current!(cx.editor);
+        if doc.id() != doc_id {
+            cx.editor.set_error("The buffer is no longer focused");
+            return;
+        }
+        if doc.goto_revision(view, meta.revision) {
+            view.ensure_cursor_in_view(doc, scrolloff);
+        }
+    })
+    .with_initial_cursor(cursor as u32);
+    cx.push_layer(Box::new(overlaid(picker)));
+}
+
+/// Describes the text inserted or the number of characters deleted by a revision.
+fn describe_change(transaction: &Transaction) -> String {
+    let mut deleted = 0;
+    for (from, to, text) in transaction.changes_iter() {
+        match text {
+            Some(text) if !text.trim().is_empty() => {
+                return format!("+ {}", text.trim().replace('\n', "⏎"));
+            }
+            _ => deleted += to - from,
+        }
+    }
+    if deleted > 0 {
+        format!("- {deleted} characters")
+    } else {
+        "+ whitespace".to_string()
+    }
+}
+
+pub(crate) fn format_age(age: std::time::Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s ago"),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
 fn commit_undo_checkpoint(cx: &mut Context) {
     let (view, doc) = current!(cx.editor);
     doc.append_changes_to_history(view);
@@ -4615,6 +5254,7 @@ fn yank_impl(editor: &mut Editor, register: char) {
         .collect();
     let selections = values.len();
 
+    record_yank(editor, register, &values);
     match editor.registers.write(register, values) {
         Ok(_) => editor.set_status(format!(
             "yanked {selections} selection{} to register {register}",
@@ -4640,7 +5280,9 @@ fn yank_joined_impl(editor: &mut Editor, separator: &str, register: char) {
             acc
         });
 
-    match editor.registers.write(register, vec![joined]) {
+    let values = vec![joined];
+    record_yank(editor, register, &values);
+    match editor.registers.write(register, values) {
         Ok(_) => editor.set_status(format!(
             "joined and yanked {selections} selection{} to register {register}",
             if selections == 1 { "" } else { "s" }
@@ -4676,9 +5318,10 @@ fn yank_primary_selection_impl(editor: &mut Editor, register: char) {
     let (view, doc) = current!(editor);
     let text = doc.text().slice(..);
 
-    let selection = doc.selection(view.id).primary().fragment(text).to_string();
+    let values = vec![doc.selection(view.id).primary().fragment(text).to_string()];
 
-    match editor.registers.write(register, vec![selection]) {
+    record_yank(editor, register, &values);
+    match editor.registers.write(register, values) {
         Ok(_) => editor.set_status(format!("yanked primary selection to register {register}",)),
         Err(err) => editor.set_error(err.to_string()),
     }
@@ -4835,10 +5478,18 @@ fn replace_with_yanked_impl(editor: &mut Editor, register: char, count: usize) {
     else {
         return;
     };
+    let values: Vec<_> = values.map(Cow::into_owned).collect();
+    replace_with_values(editor, &values, count);
+}
+
+fn replace_with_values(editor: &mut Editor, values: &[String], count: usize) {
+    let Some(last) = values.last() else {
+        return;
+    };
     let scrolloff = editor.config().scrolloff;
     let (view, doc) = current_ref!(editor);
 
-    let map_value = |value: &Cow<str>| {
+    let map_value = |value: &str| {
         let value = LINE_ENDING_REGEX.replace_all(value, doc.line_ending.as_str());
         let mut out = Tendril::from(value.as_ref());
         for _ in 1..count {
@@ -4846,14 +5497,8 @@ fn replace_with_yanked_impl(editor: &mut Editor, register: char, count: usize) {
         }
         out
     };
-    let mut values_rev = values.rev().peekable();
-    // `values` is asserted to have at least one entry above.
-    let last = values_rev.peek().unwrap();
     let repeat = std::iter::repeat(map_value(last));
-    let mut values = values_rev
-        .rev()
-        .map(|value| map_value(&value))
-        .chain(repeat);
+    let mut values = values.iter().map(|value| map_value(value)).chain(repeat);
     let selection = doc.selection(view.id);
     let transaction = Transaction::change_by_selection(doc.text(), selection, |range| {
         if !range.is_empty() {
@@ -4912,6 +5557,155 @@ fn paste_before(cx: &mut Context) {
     exit_select_mode(cx);
 }
 
+/// Pastes the values of the register, or the lines of the value if there is only one, below each
+/// other starting at the column of the primary cursor. Short lines are padded with spaces and
+/// lines are added at the end of the document as needed.
+#[allow(deprecated)]
+fn paste_block(cx: &mut Context) {
+    use helix_core::{pos_at_visual_coords, visual_coords_at_pos};
+
+    let register = cx
+        .register
+        .unwrap_or(cx.editor.config().default_yank_register);
+    let Some(values) = cx.editor.registers.read(register, cx.editor) else {
+        return;
+    };
+    let values: Vec<_> = values.map(Cow::into_owned).collect();
+    let lines: Vec<&str> = match values.as_slice() {
+        [value] => value.lines().collect(),
+        values => values.iter().map(String::as_str).collect(),
+    };
+    if lines.is_empty() {
+        return;
+    }
+
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let tab_width = doc.tab_width();
+    let cursor = doc.selection(view.id).primary().cursor(text);
+    let Position { row, col } = visual_coords_at_pos(text, cursor, tab_width);
+    let line_ending = doc.line_ending.as_str();
+
+    let mut changes = Vec::with_capacity(lines.len());
+    let mut appended = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        if row + i < text.len_lines() {
+            let pos = pos_at_visual_coords(text, Position::new(row + i, col), tab_width);
+            let padding = col.saturating_sub(visual_coords_at_pos(text, pos, tab_width).col);
+            changes.push((
+                pos,
+                pos,
+                Some(format!("{}{line}", " ".repeat(padding)).into()),
+            ));
+        } else {
+            appended.push_str(line_ending);
+            appended.push_str(&" ".repeat(col));
+            appended.push_str(line);
+        }
+    }
+    if !appended.is_empty() {
+        let end = text.len_chars();
+        changes.push((end, end, Some(appended.into())));
+    }
+
+    let transaction = Transaction::change(doc.text(), changes.into_iter());
+    doc.apply(&transaction, view.id);
+
+    // Select the pasted rectangle.
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let anchor = Position::new(row, col);
+    let head = Position::new(row + lines.len() - 1, col + width.max(1) - 1);
+    let selection = selection::select_block(doc.text().slice(..), anchor, head, tab_width);
+    doc.set_selection(view.id, selection);
+    exit_select_mode(cx);
+}
+
+/// Adds the values yanked to `register` to the yank history.
+fn record_yank(editor: &mut Editor, register: char, values: &[String]) {
+    let max_len = editor.config().yank_history;
+    editor.registers.record_yank(register, values, max_len);
+}
+
+fn paste_from_history(cx: &mut Context) {
+    struct YankMeta {
+        yank: Yank,
+        /// The values on separate lines.
+        text: String,
+    }
+
+    let mut yanks: Vec<_> = cx.editor.registers.yank_history().cloned().collect();
+    // Text copied to the system clipboard by other programs is offered first.
+    if let Some(values) = cx.editor.registers.read('+', cx.editor) {
+        let values: Vec<_> = values.map(Cow::into_owned).collect();
+        if !values.iter().all(String::is_empty) && yanks.iter().all(|yank| yank.values != values) {
+            yanks.insert(
+                0,
+                Yank {
+                    register: '+',
+                    values,
+                },
+            );
+        }
+    }
+    if yanks.is_empty() {
+        cx.editor.set_error("The yank history is empty");
+        return;
+    }
+    let items = yanks.into_iter().map(|yank| YankMeta {
+        text: yank.values.join("\n"),
+        yank,
+    });
+
+    let columns = [
+        ui::PickerColumn::new("register", |item: &YankMeta, _| {
+            item.yank.register.to_string().into()
+        }),
+        ui::PickerColumn::new("selections", |item: &YankMeta, _| {
+            item.yank.values.len().to_string().into()
+        }),
+        ui::PickerColumn::new("contents", |item: &YankMeta, _| {
+            item.text.lines().next().unwrap_or_default().into()
+        }),
+    ];
+    let picker = Picker::new(columns, 2, items, (), |cx, item, _action| {
+        let count = 1;
+        let mode = cx.editor.mode;
+        let (view, doc) = current!(cx.editor);
+        paste_impl(&item.yank.values, doc, view, Paste::After, count, mode);
+        // The pasted yank becomes the most recent one.
+        record_yank(cx.editor, item.yank.register, &item.yank.values);
+    })
+    .with_preview(|_editor, item| Some((ui::picker::PathOrId::Text(&item.text), None)));
+    cx.push_layer(Box::new(overlaid(picker)));
+}
+
+fn paste_previous_yank(cx: &mut Context) {
+    let (view, doc) = current_ref!(cx.editor);
+    let text = doc.text().slice(..);
+    let fragments: Vec<_> = doc.selection(view.id).fragments(text).collect();
+    let line_ending = doc.line_ending.as_str();
+    // Pasting gives each selection the value of the same index or the last value.
+    let is_pasted = |yank: &Yank| {
+        fragments.iter().enumerate().all(|(i, fragment)| {
+            let value = &yank.values[i.min(yank.values.len() - 1)];
+            LINE_ENDING_REGEX.replace_all(value, line_ending) == *fragment
+        })
+    };
+    let yanks: Vec<_> = cx.editor.registers.yank_history().collect();
+    let Some(index) = yanks.iter().position(|yank| is_pasted(yank)) else {
+        cx.editor
+            .set_error("The selections don't contain a yank from the history");
+        return;
+    };
+    let values = yanks[(index + 1) % yanks.len()].values.clone();
+    replace_with_values(cx.editor, &values, 1);
+    exit_select_mode(cx);
+}
+
 fn get_lines(doc: &Document, view_id: ViewId) -> Vec<usize> {
     let mut lines = Vec::new();
 
@@ -5006,6 +5800,23 @@ fn format_selections(cx: &mut Context) {
         return;
     }
 
+    // Selections within an injected language are formatted with that language's formatter. The
+    // language servers of the document are for its own language, so they aren't used instead.
+    let (view, doc) = current_ref!(cx.editor);
+    match doc.format_injection(cx.editor, doc.selection(view.id).primary()) {
+        Some(Ok(format)) => {
+            let callback = make_format_callback(doc.id(), doc.version(), view_id, format, None);
+            cx.jobs.callback(callback);
+            return;
+        }
+        Some(Err(err)) => {
+            cx.editor.set_error(err.to_string());
+            return;
+        }
+        None => (),
+    }
+    let doc = doc_mut!(cx.editor);
+
     // TODO extra LanguageServerFeature::FormatSelections?
     // maybe such that LanguageServerFeature::Format contains it as well
     let Some(language_server) = doc
@@ -5646,6 +6457,7 @@ fn jump_forward(cx: &mut Context) {
 fn jump_backward(cx: &mut Context) {
     let count = cx.count();
     let config = cx.editor.config();
+    cx.editor.open_saved_jumps(cx.editor.tree.focus, count);
     let (view, doc) = current!(cx.editor);
     let doc_id = doc.id();
 
@@ -5809,6 +6621,136 @@ fn insert_register(cx: &mut Context) {
     })
 }
 
+/// Inserts the text at each cursor, the way `insert_register` does.
+fn insert_at_cursors(editor: &mut Editor, text: String) {
+    let mode = editor.mode;
+    let (view, doc) = current!(editor);
+    paste_impl(&[text], doc, view, Paste::Cursor, 1, mode);
+}
+
+fn insert_digraph(cx: &mut Context) {
+    cx.editor.autoinfo = Some(Info::new(
+        "Insert digraph",
+        &[
+            ("<char><char>", "Insert the character of the digraph"),
+            ("C-k", "Pick a symbol"),
+        ],
+    ));
+    cx.on_next_key(move |cx, event| {
+        cx.editor.autoinfo = None;
+        if event == ctrl!('k') {
+            unicode_picker(cx);
+            return;
+        }
+        let Some(first) = event.char() else {
+            return;
+        };
+        cx.editor.autoinfo = Some(Info::new(
+            "Insert digraph",
+            &[(
+                format!("{first}<char>"),
+                "Insert the character of the digraph",
+            )],
+        ));
+        cx.on_next_key(move |cx, event| {
+            cx.editor.autoinfo = None;
+            let Some(second) = event.char() else {
+                return;
+            };
+            match digraph::lookup(first, second) {
+                Some(ch) => insert_at_cursors(cx.editor, ch.to_string()),
+                None => cx
+                    .editor
+                    .set_error(format!("No digraph for '{first}{second}'")),
+            }
+        })
+    })
+}
+
+fn insert_unicode(cx: &mut Context) {
+    cx.editor.autoinfo = Some(Info::new(
+        "Insert character",
+        &[
+            ("u", "By a code point of up to 4 hex digits"),
+            ("U", "By a code point of up to 8 hex digits"),
+            ("x", "By a code point of up to 2 hex digits"),
+            ("<key>", "The key itself, such as tab"),
+        ],
+    ));
+    cx.on_next_key(move |cx, event| {
+        cx.editor.autoinfo = None;
+        let max_digits = match (event.code, event.char()) {
+            (_, Some('u')) => 4,
+            (_, Some('U')) => 8,
+            (_, Some('x')) => 2,
+            (_, Some(ch)) => return insert_at_cursors(cx.editor, ch.to_string()),
+            (KeyCode::Tab, _) => return insert_at_cursors(cx.editor, "\t".to_string()),
+            _ => return,
+        };
+        insert_code_point(cx, String::new(), max_digits);
+    })
+}
+
+/// Reads the hex digits of a code point until `max_digits` are typed or Enter is pressed, and
+/// inserts its character.
+fn insert_code_point(cx: &mut Context, mut digits: String, max_digits: usize) {
+    cx.editor.autoinfo = Some(Info::new(
+        "Insert character",
+        &[(format!("U+{digits}"), "Type hex digits, Enter to insert")],
+    ));
+    cx.on_next_key(move |cx, event| {
+        cx.editor.autoinfo = None;
+        match event.char() {
+            Some(ch) if ch.is_ascii_hexdigit() => {
+                digits.push(ch);
+                if digits.len() < max_digits {
+                    return insert_code_point(cx, digits, max_digits);
+                }
+            }
+            _ if event.code == KeyCode::Enter && !digits.is_empty() => (),
+            _ => return,
+        }
+        match digraph::code_point(&digits) {
+            Some(ch) => insert_at_cursors(cx.editor, ch.to_string()),
+            None => cx
+                .editor
+                .set_error(format!("U+{digits} is not a valid character")),
+        }
+    })
+}
+
+fn unicode_picker(cx: &mut Context) {
+    struct Symbol {
+        ch: char,
+        digraph: Option<&'static str>,
+        name: &'static str,
+    }
+
+    let symbols = digraph::DIGRAPHS
+        .iter()
+        .map(|&(digraph, ch, name)| Symbol {
+            ch,
+            digraph: Some(digraph),
+            name,
+        })
+        .chain(digraph::SYMBOLS.iter().map(|&(ch, name)| Symbol {
+            ch,
+            digraph: None,
+            name,
+        }));
+    let columns = [
+        ui::PickerColumn::new("symbol", |symbol: &Symbol, _| symbol.ch.to_string().into()),
+        ui::PickerColumn::new("digraph", |symbol: &Symbol, _| {
+            symbol.digraph.unwrap_or_default().into()
+        }),
+        ui::PickerColumn::new("name", |symbol: &Symbol, _| symbol.name.into()),
+    ];
+    let picker = Picker::new(columns, 2, symbols, (), |cx, symbol, _action| {
+        insert_at_cursors(cx.editor, symbol.ch.to_string());
+    });
+    cx.push_layer(Box::new(overlaid(picker)));
+}
+
 fn copy_between_registers(cx: &mut Context) {
     cx.editor.autoinfo = Some(Info::from_registers(
         "Copy from register",
@@ -5899,6 +6841,85 @@ fn scroll_down(cx: &mut Context) {
     scroll(cx, cx.count(), Direction::Forward, false);
 }
 
+/// The lines of the cursors of the current view, without duplicates.
+fn cursor_lines(doc: &Document, view_id: ViewId) -> Vec<usize> {
+    let text = doc.text().slice(..);
+    let mut lines: Vec<_> = doc
+        .selection(view_id)
+        .iter()
+        .map(|range| range.cursor_line(text))
+        .collect();
+    lines.dedup();
+    lines
+}
+
+fn fold_impl(cx: &mut Context, lines: Vec<usize>) {
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let folds: Vec<_> = lines
+        .into_iter()
+        .filter_map(|line| {
+            helix_core::fold::fold_at(
+                text,
+                line,
+                doc.syntax(),
+                doc.tab_width(),
+                doc.indent_width(),
+            )
+        })
+        .collect();
+    if folds.is_empty() {
+        cx.editor.set_error("No region to fold at the cursor");
+        return;
+    }
+    for fold in folds {
+        doc.add_fold(view.id, fold);
+    }
+}
+
+fn fold(cx: &mut Context) {
+    let (view, doc) = current_ref!(cx.editor);
+    let lines = cursor_lines(doc, view.id);
+    fold_impl(cx, lines);
+}
+
+fn unfold(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    for line in cursor_lines(doc, view.id) {
+        doc.remove_folds_at(view.id, line);
+    }
+}
+
+fn toggle_fold(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let lines = cursor_lines(doc, view.id)
+        .into_iter()
+        .filter(|&line| !doc.remove_folds_at(view.id, line))
+        .collect();
+    fold_impl(cx, lines);
+}
+
+fn fold_all(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let folds = helix_core::fold::fold_all(
+        doc.text().slice(..),
+        doc.syntax(),
+        doc.tab_width(),
+        doc.indent_width(),
+    );
+    doc.set_folds(view.id, folds);
+}
+
+fn unfold_all(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    doc.set_folds(view.id, Vec::new());
+}
+
+fn toggle_zen_mode(cx: &mut Context) {
+    let enable = !cx.editor.zen_mode();
+    cx.editor.set_zen_mode(enable);
+}
+
 fn goto_ts_object_impl(cx: &mut Context, object: &'static str, direction: Direction) {
     let count = cx.count();
     let motion = move |editor: &mut Editor| {
@@ -6006,6 +7027,7 @@ fn select_textobject(cx: &mut Context, objtype: textobject::TextObject) {
         cx.editor.autoinfo = None;
         if let Some(ch) = event.char() {
             let textobject = move |editor: &mut Editor| {
+                let custom = editor.config().textobjects.get(&ch).cloned();
                 let (view, doc) = current!(editor);
                 let loader = editor.syn_loader.load();
                 let text = doc.text().slice(..);
@@ -6041,6 +7063,9 @@ fn select_textobject(cx: &mut Context, objtype: textobject::TextObject) {
                 };
 
                 let selection = doc.selection(view.id).clone().transform(|range| {
+                    if let Some(name) = &custom {
+                        return textobject_treesitter(name, range);
+                    }
                     match ch {
                         'w' => textobject::textobject_word(text, range, objtype, count, false),
                         'W' => textobject::textobject_word(text, range, objtype, count, true),
@@ -6083,7 +7108,7 @@ fn select_textobject(cx: &mut Context, objtype: textobject::TextObject) {
         textobject::TextObject::Around => "Match around",
         _ => return,
     };
-    let help_text = [
+    let builtin = [
         ("w", "Word"),
         ("W", "WORD"),
         ("p", "Paragraph"),
@@ -6098,6 +7123,18 @@ fn select_textobject(cx: &mut Context, objtype: textobject::TextObject) {
         ("x", "(X)HTML element (tree-sitter)"),
         (" ", "... or any character acting as a pair"),
     ];
+    // The textobjects of the config take the place of the built-in ones with the same key.
+    let textobjects = &cx.editor.config().textobjects;
+    let help_text: Vec<_> = textobjects
+        .iter()
+        .map(|(key, name)| (key.to_string(), format!("{name} (tree-sitter)")))
+        .chain(
+            builtin
+                .iter()
+                .filter(|(key, _)| !textobjects.keys().any(|custom| custom.to_string() == *key))
+                .map(|(key, doc)| (key.to_string(), doc.to_string())),
+        )
+        .collect();
 
     cx.editor.autoinfo = Some(Info::new(title, &help_text));
 }
@@ -6272,6 +7309,15 @@ enum ShellBehavior {
     Append,
 }
 
+#[derive(Debug, Default, Clone, Copy)]
+struct ShellOptions {
+    /// Run the command with its output connected to a pseudo-terminal, so that it behaves as
+    /// if it was run interactively.
+    pty: bool,
+    /// Keep the ANSI escape sequences printed by a command run in a pseudo-terminal.
+    keep_ansi: bool,
+}
+
 fn shell_pipe(cx: &mut Context) {
     shell_prompt_for_behavior(cx, "pipe:".into(), ShellBehavior::Replace);
 }
@@ -6392,7 +7438,134 @@ async fn shell_impl_async(
     Ok(Tendril::from(output))
 }
 
-fn shell(cx: &mut compositor::Context, cmd: &str, behavior: &ShellBehavior) {
+/// Runs a shell command with its stdout and stderr connected to a pseudo-terminal and returns
+/// everything it printed.
+#[cfg(not(windows))]
+fn shell_impl_pty(shell: &[String], cmd: &str, input: Option<Rope>) -> anyhow::Result<Tendril> {
+    use std::{
+        fs::File,
+        io::{Read, Write},
+        os::{fd::AsRawFd, fd::FromRawFd, fd::OwnedFd, unix::process::CommandExt},
+        process::{Command, Stdio},
+        ptr,
+    };
+    ensure!(!shell.is_empty(), "No shell set");
+
+    let (mut master, slave) = unsafe {
+        let (mut master, mut slave) = (0, 0);
+        if libc::openpty(
+            &mut master,
+            &mut slave,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        ) != 0
+        {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        (File::from_raw_fd(master), OwnedFd::from_raw_fd(slave))
+    };
+
+    // Don't translate "\n" into "\r\n" so the output keeps the line endings the command wrote.
+    unsafe {
+        let mut termios = std::mem::zeroed();
+        if libc::tcgetattr(slave.as_raw_fd(), &mut termios) == 0 {
+            termios.c_oflag &= !libc::ONLCR;
+            libc::tcsetattr(slave.as_raw_fd(), libc::TCSANOW, &termios);
+        }
+    }
+
+    let mut process = Command::new(&shell[0]);
+    process
+        .args(&shell[1..])
+        .arg(cmd)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::from(slave.try_clone()?))
+        .stderr(Stdio::from(slave));
+    // Detach the command from the editor's controlling terminal.
+    unsafe {
+        process.pre_exec(|| {
+            libc::setsid();
+            Ok(())
+        });
+    }
+
+    let mut child = match process.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            log::error!("Failed to start shell: {}", e);
+            return Err(e.into());
+        }
+    };
+    // The command holds the parent's copies of the slave side of the pty. They must be closed
+    // for reads from the master to end once the child exits.
+    drop(process);
+
+    let mut output = Vec::new();
+    std::thread::scope(|scope| -> anyhow::Result<()> {
+        if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+            scope.spawn(move || {
+                for chunk in input.chunks() {
+                    if stdin.write_all(chunk.as_bytes()).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        let mut buf = [0; 4096];
+        loop {
+            match master.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => output.extend_from_slice(&buf[..n]),
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                // Linux reports EIO once every process closed the slave side.
+                Err(err) if err.raw_os_error() == Some(libc::EIO) => break,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(())
+    })?;
+
+    let status = child.wait()?;
+    if !status.success() && output.is_empty() {
+        match status.code() {
+            Some(exit_code) => bail!("Shell command failed: status {}", exit_code),
+            None => bail!("Shell command failed"),
+        }
+    }
+
+    Ok(Tendril::from(String::from_utf8_lossy(&output)))
+}
+
+/// Runs a shell command as configured by `options`, returning its output.
+fn shell_impl_with_options(
+    shell: &[String],
+    cmd: &str,
+    input: Option<Rope>,
+    options: ShellOptions,
+) -> anyhow::Result<Tendril> {
+    if !options.pty {
+        return shell_impl(shell, cmd, input);
+    }
+
+    #[cfg(not(windows))]
+    {
+        let output = tokio::task::block_in_place(|| shell_impl_pty(shell, cmd, input))?;
+        if options.keep_ansi {
+            Ok(output)
+        } else {
+            Ok(helix_core::ansi::strip_ansi(&output).into())
+        }
+    }
+    #[cfg(windows)]
+    bail!("Running shell commands in a pseudo-terminal is not supported on Windows")
+}
+
+fn shell(cx: &mut compositor::Context, cmd: &str, behavior: &ShellBehavior, options: ShellOptions) {
     let pipe = match behavior {
         ShellBehavior::Replace | ShellBehavior::Ignore => true,
         ShellBehavior::Insert | ShellBehavior::Append => false,
@@ -6414,7 +7587,7 @@ fn shell(cx: &mut compositor::Context, cmd: &str, behavior: &ShellBehavior) {
             output.clone()
         } else {
             let input = range.slice(text);
-            match shell_impl(shell, cmd, pipe.then(|| input.into())) {
+            match shell_impl_with_options(shell, cmd, pipe.then(|| input.into()), options) {
                 Ok(mut output) => {
                     if !input.ends_with("\n") && output.ends_with('\n') {
                         output.pop();
@@ -6498,7 +7671,12 @@ fn shell_prompt<F>(cx: &mut Context, prompt: Cow<'static, str>, mut callback_fn:
 
 fn shell_prompt_for_behavior(cx: &mut Context, prompt: Cow<'static, str>, behavior: ShellBehavior) {
     shell_prompt(cx, prompt, move |cx, args| {
-        shell(cx, args.join(" ").as_str(), &behavior)
+        shell(
+            cx,
+            args.join(" ").as_str(),
+            &behavior,
+            ShellOptions::default(),
+        )
     })
 }
 
@@ -6569,6 +7747,7 @@ fn increment_impl(cx: &mut Context, increment_direction: IncrementDirection) {
     // If the register is `#` then increase or decrease the `amount` by 1 per element
     let increase_by = if cx.register == Some('#') { sign } else { 0 };
 
+    let config = cx.editor.config();
     let (view, doc) = current!(cx.editor);
     let selection = doc.selection(view.id);
     let text = doc.text().slice(..);
@@ -6582,7 +7761,8 @@ fn increment_impl(cx: &mut Context, increment_direction: IncrementDirection) {
         let new_from = ((range.from() as i128) + cumulative_length_diff) as usize;
         let incremented = [increment::integer, increment::date_time]
             .iter()
-            .find_map(|incrementor| incrementor(selected_text.as_ref(), amount));
+            .find_map(|incrementor| incrementor(selected_text.as_ref(), amount))
+            .or_else(|| increment::word_cycle(&selected_text, amount, &config.word_cycles));
 
         amount += increase_by;
 
@@ -6595,8 +7775,10 @@ fn increment_impl(cx: &mut Context, increment_direction: IncrementDirection) {
                 new_selection_ranges.push(new_range);
             }
             Some(new_text) => {
-                let new_range = Range::new(new_from, new_from + new_text.len());
-                cumulative_length_diff += new_text.len() as i128 - selected_text.len() as i128;
+                // Words of the cycles may contain multi-byte characters.
+                let new_len = new_text.chars().count();
+                let new_range = Range::new(new_from, new_from + new_len);
+                cumulative_length_diff += new_len as i128 - range.len() as i128;
                 new_selection_ranges.push(new_range);
                 changes.push((range.from(), range.to(), Some(new_text.into())));
             }
@@ -6640,6 +7822,9 @@ fn goto_next_tabstop_impl(cx: &mut Context, direction: Direction) {
     if !last_tabstop {
         doc.active_snippet = Some(snippet)
     }
+    cx.callback.push(Box::new(|compositor, cx| {
+        show_snippet_choices(cx.editor, compositor)
+    }));
     if cx.editor.mode() == Mode::Insert {
         cx.on_next_key_fallback(|cx, key| {
             if let Some(c) = key.char() {
@@ -6653,6 +7838,131 @@ fn goto_next_tabstop_impl(cx: &mut Context, direction: Direction) {
     }
 }
 
+struct SnippetChoice(Tendril);
+
+impl ui::menu::Item for SnippetChoice {
+    type Data = ();
+    fn format(&self, _data: &Self::Data) -> Row<'_> {
+        self.0.as_str().into()
+    }
+}
+
+/// Shows a menu of the choices of the current tabstop of the active snippet, if any.
+pub(crate) fn show_snippet_choices(editor: &mut Editor, compositor: &mut Compositor) {
+    let Some(choices) = doc!(editor)
+        .active_snippet
+        .as_ref()
+        .and_then(|snippet| snippet.current_choices())
+    else {
+        return;
+    };
+    let choices = choices.iter().cloned().map(SnippetChoice).collect();
+    let mut menu = ui::Menu::new(choices, (), |editor, choice, event| {
+        if event != PromptEvent::Validate {
+            return;
+        }
+        let (view, doc) = current!(editor);
+        if let (Some(snippet), Some(SnippetChoice(choice))) = (&doc.active_snippet, choice) {
+            let transaction = snippet.choose(doc.text(), doc.selection(view.id), choice);
+            doc.apply(&transaction, view.id);
+        }
+    });
+    menu.move_down(); // pre-select the first choice
+
+    let popup = Popup::new("snippet-choice", menu)
+        .with_scrollbar(false)
+        .auto_close(true);
+    compositor.replace_or_push("snippet-choice", popup);
+}
+
+/// The range of the misspelled word at the cursor, or else of the word at the cursor.
+pub(crate) fn word_under_cursor(doc: &Document, view: &View) -> Option<std::ops::Range<usize>> {
+    let text = doc.text().slice(..);
+    let cursor = doc.selection(view.id).primary().cursor(text);
+    if let Some(misspelling) = doc
+        .misspellings
+        .iter()
+        .find(|range| range.start <= cursor && cursor < range.end)
+    {
+        return Some(misspelling.clone());
+    }
+    let range = textobject::textobject_word(
+        text,
+        Range::point(cursor),
+        textobject::TextObject::Inside,
+        1,
+        false,
+    );
+    (range.from() < range.to()).then(|| range.from()..range.to())
+}
+
+struct SpellingSuggestion(String);
+
+impl ui::menu::Item for SpellingSuggestion {
+    type Data = ();
+    fn format(&self, _data: &Self::Data) -> Row<'_> {
+        self.0.as_str().into()
+    }
+}
+
+fn spell_suggest(cx: &mut Context) {
+    const LIMIT: usize = 10;
+
+    let (view, doc) = current_ref!(cx.editor);
+    let Some(range) = word_under_cursor(doc, view) else {
+        cx.editor.set_error("No word under the cursor");
+        return;
+    };
+    let word = doc.text().slice(range.clone()).to_string();
+    let doc_id = doc.id();
+    let dictionaries = cx.editor.config().spell_check.dictionaries.clone();
+    let suggestions = tokio::task::spawn_blocking(move || {
+        let suggestions =
+            crate::handlers::spelling::dictionary(&dictionaries)?.suggest(&word, LIMIT);
+        anyhow::Ok((word, suggestions))
+    });
+
+    cx.jobs.callback(async move {
+        let (word, suggestions) = suggestions.await??;
+        let call = move |editor: &mut Editor, compositor: &mut Compositor| {
+            // The cursor may have left the word while the suggestions were found.
+            let (view, doc) = current_ref!(editor);
+            if doc.id() != doc_id || word_under_cursor(doc, view).as_ref() != Some(&range) {
+                return;
+            }
+            if suggestions.is_empty() {
+                editor.set_status(format!("No suggestions for {word}"));
+                return;
+            }
+
+            let suggestions = suggestions.into_iter().map(SpellingSuggestion).collect();
+            let mut menu = ui::Menu::new(suggestions, (), move |editor, suggestion, event| {
+                if event != PromptEvent::Validate {
+                    return;
+                }
+                let (view, doc) = current!(editor);
+                if let (true, Some(SpellingSuggestion(suggestion))) =
+                    (doc.id() == doc_id, suggestion)
+                {
+                    let transaction = Transaction::change(
+                        doc.text(),
+                        [(range.start, range.end, Some(suggestion.as_str().into()))].into_iter(),
+                    );
+                    doc.apply(&transaction, view.id);
+                }
+            });
+            menu.move_down(); // pre-select the closest suggestion
+
+            compositor.push(Box::new(
+                Popup::new("spelling-suggestions", menu)
+                    .with_scrollbar(false)
+                    .auto_close(true),
+            ));
+        };
+        Ok(Callback::EditorCompositor(Box::new(call)))
+    });
+}
+
 fn record_macro(cx: &mut Context) {
     if let Some((reg, mut keys)) = cx.editor.macro_recording.take() {
         // Remove the keypress which ends the recording
@@ -6685,7 +7995,11 @@ fn record_macro(cx: &mut Context) {
 fn replay_macro(cx: &mut Context) {
     let reg = cx.register.unwrap_or('@');
 
-    if cx.editor.macro_replaying.contains(&reg) {
+    if cx
+        .editor
+        .macro_replaying
+        .contains(&ReplayedMacro::Register(reg))
+    {
         cx.editor.set_error(format!(
             "Cannot replay from register [{}] because already replaying from same register",
             reg
@@ -6714,7 +8028,7 @@ fn replay_macro(cx: &mut Context) {
 
     // Once the macro has been fully validated, it's marked as being under replay
     // to ensure we don't fall into infinite recursion.
-    cx.editor.macro_replaying.push(reg);
+    cx.editor.macro_replaying.push(ReplayedMacro::Register(reg));
 
     let count = cx.count();
     cx.callback.push(Box::new(move |compositor, cx| {
@@ -15,7 +15,7 @@
 
 use std::collections::HashMap;
 use std::future::Future;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, bail};
 
@@ -77,16 +77,13 @@ fn thread_picker(
     );
 }
 
-fn get_breakpoint_at_current_line(editor: &mut Editor) -> Option<(usize, Breakpoint)> {
-    let (view, doc) = current!(editor);
-    let text = doc.text().slice(..);
-
-    let line = doc.selection(view.id).primary().cursor_line(text);
-    let path = doc.path()?;
-    editor.breakpoints.get(path).and_then(|breakpoints| {
-        let i = breakpoints.iter().position(|b| b.line == line);
-        i.map(|i| (i, breakpoints[i].clone()))
-    })
+fn get_breakpoint_at_line(editor: &Editor, path: &Path, line: usize) -> Option<Breakpoint> {
+    editor
+        .breakpoints
+        .get(path)?
+        .iter()
+        .find(|breakpoint| breakpoint.line == line)
+        .cloned()
 }
 
 // -- DAP
@@ -630,88 +627,134 @@ pub fn dap_disable_exceptions(cx: &mut Context) {
     )
 }
 
-// TODO: both edit condition and edit log need to be stable: we might get new breakpoints from the debugger which can change offsets
-pub fn dap_edit_condition(cx: &mut Context) {
-    if let Some((pos, breakpoint)) = get_breakpoint_at_current_line(cx.editor) {
-        let path = match doc!(cx.editor).path() {
-            Some(path) => path.clone(),
-            None => return,
-        };
-        let callback = Box::pin(async move {
-            let call: Callback = Callback::EditorCompositor(Box::new(move |editor, compositor| {
-                let mut prompt = Prompt::new(
-                    "condition:".into(),
-                    None,
-                    ui::completers::none,
-                    move |cx, input: &str, event: PromptEvent| {
-                        if event != PromptEvent::Validate {
-                            return;
-                        }
-
-                        let breakpoints = &mut cx.editor.breakpoints.get_mut(&path).unwrap();
-                        breakpoints[pos].condition = match input {
-                            "" => None,
-                            input => Some(input.to_owned()),
-                        };
-
-                        let debugger = debugger!(cx.editor);
-
-                        if let Err(e) = breakpoints_changed(debugger, path.clone(), breakpoints) {
-                            cx.editor
-                                .set_error(format!("Failed to set breakpoints: {}", e));
-                        }
-                    },
-                );
-                if let Some(condition) = breakpoint.condition {
-                    prompt.insert_str(&condition, editor)
-                }
-                compositor.push(Box::new(prompt));
-            }));
-            Ok(call)
-        });
-        cx.jobs.callback(callback);
+/// A field of a breakpoint edited with a prompt.
+#[derive(Debug, Clone, Copy)]
+enum BreakpointField {
+    Condition,
+    HitCondition,
+    LogMessage,
+}
+
+impl BreakpointField {
+    fn prompt(self) -> &'static str {
+        match self {
+            Self::Condition => "condition:",
+            Self::HitCondition => "hit-count:",
+            Self::LogMessage => "log-message:",
+        }
+    }
+
+    fn get_mut(self, breakpoint: &mut Breakpoint) -> &mut Option<String> {
+        match self {
+            Self::Condition => &mut breakpoint.condition,
+            Self::HitCondition => &mut breakpoint.hit_condition,
+            Self::LogMessage => &mut breakpoint.log_message,
+        }
     }
 }
 
-pub fn dap_edit_log(cx: &mut Context) {
-    if let Some((pos, breakpoint)) = get_breakpoint_at_current_line(cx.editor) {
-        let path = match doc!(cx.editor).path() {
-            Some(path) => path.clone(),
-            None => return,
-        };
-        let callback = Box::pin(async move {
-            let call: Callback = Callback::EditorCompositor(Box::new(move |editor, compositor| {
-                let mut prompt = Prompt::new(
-                    "log-message:".into(),
-                    None,
-                    ui::completers::none,
-                    move |cx, input: &str, event: PromptEvent| {
-                        if event != PromptEvent::Validate {
-                            return;
-                        }
-
-                        let breakpoints = &mut cx.editor.breakpoints.get_mut(&path).unwrap();
-                        breakpoints[pos].log_message = match input {
-                            "" => None,
-                            input => Some(input.to_owned()),
-                        };
-
-                        let debugger = debugger!(cx.editor);
-                        if let Err(e) = breakpoints_changed(debugger, path.clone(), breakpoints) {
-                            cx.editor
-                                .set_error(format!("Failed to set breakpoints: {}", e));
-                        }
-                    },
-                );
-                if let Some(log_message) = breakpoint.log_message {
-                    prompt.insert_str(&log_message, editor);
+/// Prompts for the first of `fields` of the breakpoint on `line`, and then for the rest of them.
+/// The breakpoint is created if there is none on the line yet and it is synced with the active
+/// debugger after every edit.
+fn breakpoint_prompt(
+    editor: &Editor,
+    path: PathBuf,
+    line: usize,
+    fields: &'static [BreakpointField],
+) -> Prompt {
+    let field = fields[0];
+    let value = get_breakpoint_at_line(editor, &path, line)
+        .and_then(|mut breakpoint| field.get_mut(&mut breakpoint).take());
+    let mut prompt = Prompt::new(
+        field.prompt().into(),
+        None,
+        ui::completers::none,
+        move |cx, input: &str, event: PromptEvent| {
+            if event != PromptEvent::Validate {
+                return;
+            }
+
+            let value = (!input.is_empty()).then(|| input.to_owned());
+            let breakpoints = cx.editor.breakpoints.entry(path.clone()).or_default();
+            // Breakpoints are looked up by line since the debugger may have changed them while
+            // the prompt was open.
+            match breakpoints.iter_mut().find(|b| b.line == line) {
+                Some(breakpoint) => *field.get_mut(breakpoint) = value,
+                None if value.is_some() => {
+                    let mut breakpoint = Breakpoint {
+                        line,
+                        ..Default::default()
+                    };
+                    *field.get_mut(&mut breakpoint) = value;
+                    breakpoints.push(breakpoint);
                 }
-                compositor.push(Box::new(prompt));
-            }));
-            Ok(call)
-        });
-        cx.jobs.callback(callback);
+                None => (),
+            }
+
+            if let Some(debugger) = cx.editor.debug_adapters.get_active_client_mut() {
+                let breakpoints = cx.editor.breakpoints.get_mut(&path).unwrap();
+                if let Err(e) = breakpoints_changed(debugger, path.clone(), breakpoints) {
+                    cx.editor
+                        .set_error(format!("Failed to set breakpoints: {}", e));
+                }
+            }
+
+            if fields.len() > 1 {
+                let path = path.clone();
+                let callback = Box::pin(async move {
+                    let call: Callback =
+                        Callback::EditorCompositor(Box::new(move |editor, compositor| {
+                            let prompt = breakpoint_prompt(editor, path, line, &fields[1..]);
+                            compositor.push(Box::new(prompt));
+                        }));
+                    Ok(call)
+                });
+                cx.jobs.callback(callback);
+            }
+        },
+    );
+    if let Some(value) = value {
+        prompt.insert_str(&value, editor);
     }
+    prompt
+}
+
+fn edit_breakpoint(cx: &mut Context, fields: &'static [BreakpointField]) {
+    let (view, doc) = current_ref!(cx.editor);
+    let Some(path) = doc.path().cloned() else {
+        cx.editor
+            .set_error("Can't edit breakpoint: document has no path");
+        return;
+    };
+    let line = doc
+        .selection(view.id)
+        .primary()
+        .cursor_line(doc.text().slice(..));
+    let prompt = breakpoint_prompt(cx.editor, path, line, fields);
+    cx.push_layer(Box::new(prompt));
+}
+
+pub fn dap_edit_condition(cx: &mut Context) {
+    edit_breakpoint(cx, &[BreakpointField::Condition]);
+}
+
+pub fn dap_edit_hit_condition(cx: &mut Context) {
+    edit_breakpoint(cx, &[BreakpointField::HitCondition]);
+}
+
+pub fn dap_edit_log(cx: &mut Context) {
+    edit_breakpoint(cx, &[BreakpointField::LogMessage]);
+}
+
+pub fn dap_edit_breakpoint(cx: &mut Context) {
+    edit_breakpoint(
+        cx,
+        &[
+            BreakpointField::Condition,
+            BreakpointField::HitCondition,
+            BreakpointField::LogMessage,
+        ],
+    );
 }
 
 pub fn dap_switch_thread(cx: &mut Context) {
@@ -1,12 +1,13 @@
-use futures_util::{stream::FuturesOrdered, FutureExt};
+use futures_util::{future::BoxFuture, stream::FuturesOrdered, FutureExt};
 use helix_lsp::{
     block_on,
     lsp::{
         self, CodeAction, CodeActionOrCommand, CodeActionTriggerKind, DiagnosticSeverity,
         NumberOrString,
     },
+    rust_analyzer,
     util::{diagnostic_to_lsp_diagnostic, lsp_range_to_range, range_to_lsp_range},
-    Client, LanguageServerId, OffsetEncoding,
+    Client, LanguageServerId, OffsetEncoding, Url,
 };
 use tokio_stream::StreamExt;
 use tui::{text::Span, widgets::Row};
@@ -20,19 +21,22 @@
 use helix_stdx::path;
 use helix_view::{
     document::{DocumentInlayHints, DocumentInlayHintsId},
-    editor::Action,
-    handlers::lsp::SignatureHelpInvoked,
+    editor::{Action, WorkspaceEditSource},
+    handlers::lsp::{PendingWorkspaceEdit, SignatureHelpInvoked, WorkspaceEditCallback},
     theme::Style,
-    Document, View,
+    Document, DocumentId, View,
 };
 
 use crate::{
     compositor::{self, Compositor},
-    job::Callback,
+    job::{Callback, Jobs},
     ui::{self, overlay::overlaid, FileLocation, Picker, Popup, PromptEvent},
 };
 
-use std::{cmp::Ordering, collections::HashSet, fmt::Display, future::Future, path::Path};
+use std::{
+    cmp::Ordering, collections::HashSet, fmt::Display, future::Future, path::Path, sync::Arc,
+    time::Duration,
+};
 
 /// Gets the first language server that is attached to a document which supports a specific feature.
 /// If there is no configured language server that supports the feature, this displays a status message.
@@ -203,31 +207,38 @@ enum DiagnosticsFormat {
 type DiagnosticsPicker = Picker<PickerDiagnostic, DiagnosticStyles>;
 
 fn diag_picker(
-    cx: &Context,
+    editor: &Editor,
     diagnostics: impl IntoIterator<Item = (Uri, Vec<(lsp::Diagnostic, DiagnosticProvider)>)>,
     format: DiagnosticsFormat,
 ) -> DiagnosticsPicker {
     // TODO: drop current_path comparison and instead use workspace: bool flag?
 
     // flatten the map to a vec of (url, diag) pairs
+    let config = editor.config();
     let mut flat_diag = Vec::new();
     for (uri, diags) in diagnostics {
         flat_diag.reserve(diags.len());
 
         for (diag, provider) in diags {
-            if let Some(ls) = provider
-                .language_server_id()
-                .and_then(|id| cx.editor.language_server_by_id(id))
-            {
-                flat_diag.push(PickerDiagnostic {
-                    location: Location {
-                        uri: uri.clone(),
-                        range: diag.range,
-                        offset_encoding: ls.offset_encoding(),
-                    },
-                    diag,
-                });
+            if config.hides_diagnostic(&diag) {
+                continue;
             }
+            let offset_encoding = match provider.language_server_id() {
+                Some(id) => match editor.language_server_by_id(id) {
+                    Some(ls) => ls.offset_encoding(),
+                    None => continue,
+                },
+                // Diagnostics from internal providers use character offsets.
+                None => OffsetEncoding::Utf32,
+            };
+            flat_diag.push(PickerDiagnostic {
+                location: Location {
+                    uri: uri.clone(),
+                    range: diag.range,
+                    offset_encoding,
+                },
+                diag,
+            });
         }
     }
 
@@ -239,10 +250,10 @@ fn diag_picker(
     });
 
     let styles = DiagnosticStyles {
-        hint: cx.editor.theme.get("hint"),
-        info: cx.editor.theme.get("info"),
-        warning: cx.editor.theme.get("warning"),
-        error: cx.editor.theme.get("error"),
+        hint: editor.theme.get("hint"),
+        info: editor.theme.get("info"),
+        warning: editor.theme.get("warning"),
+        error: editor.theme.get("error"),
     };
 
     let mut columns = vec![
@@ -570,7 +581,11 @@ pub fn diagnostics_picker(cx: &mut Context) {
     let doc = doc!(cx.editor);
     if let Some(uri) = doc.uri() {
         let diagnostics = cx.editor.diagnostics.get(&uri).cloned().unwrap_or_default();
-        let picker = diag_picker(cx, [(uri, diagnostics)], DiagnosticsFormat::HideSourcePath);
+        let picker = diag_picker(
+            cx.editor,
+            [(uri, diagnostics)],
+            DiagnosticsFormat::HideSourcePath,
+        );
         cx.push_layer(Box::new(overlaid(picker)));
     }
 }
@@ -578,10 +593,28 @@ pub fn diagnostics_picker(cx: &mut Context) {
 pub fn workspace_diagnostics_picker(cx: &mut Context) {
     // TODO not yet filtered by LanguageServerFeature, need to do something similar as Document::shown_diagnostics here for all open documents
     let diagnostics = cx.editor.diagnostics.clone();
-    let picker = diag_picker(cx, diagnostics, DiagnosticsFormat::ShowSourcePath);
+    let picker = diag_picker(cx.editor, diagnostics, DiagnosticsFormat::ShowSourcePath);
     cx.push_layer(Box::new(overlaid(picker)));
 }
 
+/// Opens a picker over the diagnostics reported by the last `:make` run.
+pub fn make_diagnostics_picker(editor: &Editor, compositor: &mut Compositor) {
+    let diagnostics: Vec<_> = editor
+        .diagnostics
+        .iter()
+        .map(|(uri, diags)| {
+            let diags = diags
+                .iter()
+                .filter(|(_, provider)| provider == &DiagnosticProvider::Make)
+                .cloned()
+                .collect();
+            (uri.clone(), diags)
+        })
+        .collect();
+    let picker = diag_picker(editor, diagnostics, DiagnosticsFormat::ShowSourcePath);
+    compositor.push(Box::new(overlaid(picker)));
+}
+
 struct CodeActionOrCommandItem {
     lsp_item: lsp::CodeActionOrCommand,
     language_server_id: LanguageServerId,
@@ -654,104 +687,313 @@ fn action_fixes_diagnostics(action: &CodeActionOrCommand) -> bool {
     )
 }
 
-pub fn code_action(cx: &mut Context) {
-    let (view, doc) = current!(cx.editor);
+/// Applies the code actions of the `code-actions-on-save` kinds of the document's language,
+/// waiting at most `lsp.code-actions-on-save-timeout` for all of them. The language servers are
+/// asked for the actions of each kind at once, and the first action of the kind is applied and its
+/// title recorded in `Document::applied_code_actions`. Actions without edits, which only run a
+/// command, are skipped.
+pub fn code_actions_on_save(editor: &mut Editor, doc_id: DocumentId) {
+    let Some(kinds) = editor
+        .document(doc_id)
+        .and_then(|doc| doc.language_config())
+        .map(|config| config.code_actions_on_save.clone())
+        .filter(|kinds| !kinds.is_empty())
+    else {
+        return;
+    };
+    let timeout = Duration::from_millis(editor.config().lsp.code_actions_on_save_timeout);
+    let deadline = tokio::time::Instant::now() + timeout;
 
-    let selection_range = doc.selection(view.id).primary();
+    // The kinds are requested one after the other since the edit of one changes the document.
+    for kind in kinds {
+        let doc = doc!(editor, &doc_id);
+        let mut seen_language_servers = HashSet::new();
+        let requests: Vec<_> = doc
+            .language_servers_with_feature(LanguageServerFeature::CodeAction)
+            .filter(|ls| seen_language_servers.insert(ls.id()))
+            .filter_map(|language_server| {
+                let offset_encoding = language_server.offset_encoding();
+                let text = doc.text();
+                let range = range_to_lsp_range(
+                    text,
+                    helix_core::Range::new(0, text.len_chars()),
+                    offset_encoding,
+                );
+                let context = lsp::CodeActionContext {
+                    diagnostics: doc
+                        .diagnostics()
+                        .iter()
+                        .map(|diag| diagnostic_to_lsp_diagnostic(text, diag, offset_encoding))
+                        .collect(),
+                    only: Some(vec![lsp::CodeActionKind::from(kind.clone())]),
+                    trigger_kind: Some(CodeActionTriggerKind::AUTOMATIC),
+                };
+                let request = language_server.code_actions(doc.identifier(), range, context)?;
+                Some((request, language_server.id()))
+            })
+            .collect();
+
+        let responses = tokio::task::block_in_place(|| {
+            block_on(futures_util::future::join_all(requests.into_iter().map(
+                |(request, language_server_id)| async move {
+                    let actions = tokio::time::timeout_at(deadline, request).await;
+                    (actions, language_server_id)
+                },
+            )))
+        });
 
+        for (actions, language_server_id) in responses {
+            let actions = match actions {
+                Ok(Ok(actions)) => actions.unwrap_or_default(),
+                Ok(Err(err)) => {
+                    log::error!("while gathering the code actions on save: {err}");
+                    continue;
+                }
+                Err(_) => {
+                    log::warn!("the code actions on save of kind '{kind}' timed out");
+                    continue;
+                }
+            };
+            let Some(code_action) = code_action_on_save(actions, &kind) else {
+                continue;
+            };
+            let Some(language_server) = editor.language_server_by_id(language_server_id) else {
+                continue;
+            };
+            let offset_encoding = language_server.offset_encoding();
+            let code_action = match &code_action.edit {
+                Some(_) => code_action,
+                None => language_server
+                    .resolve_code_action(&code_action)
+                    .and_then(|future| {
+                        tokio::task::block_in_place(|| {
+                            block_on(tokio::time::timeout_at(deadline, future))
+                        })
+                        .ok()?
+                        .ok()
+                    })
+                    .unwrap_or(code_action),
+            };
+            let Some(edit) = &code_action.edit else {
+                continue;
+            };
+            match editor.apply_workspace_edit(offset_encoding, edit) {
+                Ok(()) => {
+                    log::info!("applied the code action on save '{}'", code_action.title);
+                    doc_mut!(editor, &doc_id)
+                        .applied_code_actions
+                        .push(code_action.title);
+                    // The responses of the other language servers are outdated by the edit.
+                    break;
+                }
+                Err(err) => log::error!(
+                    "failed to apply the code action on save '{}': {:?}",
+                    code_action.title,
+                    err.kind
+                ),
+            }
+        }
+    }
+}
+
+/// Requests the code actions for `range` of the document from all of its language servers,
+/// sorted into the order they are shown in. If `kind` is given, only the actions of that kind
+/// and its sub-kinds are kept.
+fn request_code_actions(
+    doc: &Document,
+    range: helix_core::Range,
+    kind: Option<lsp::CodeActionKind>,
+) -> FuturesOrdered<impl Future<Output = anyhow::Result<Vec<CodeActionOrCommandItem>>>> {
     let mut seen_language_servers = HashSet::new();
+    let only = kind.clone().map(|kind| vec![kind]);
 
-    let mut futures: FuturesOrdered<_> = doc
-        .language_servers_with_feature(LanguageServerFeature::CodeAction)
+    doc.language_servers_with_feature(LanguageServerFeature::CodeAction)
         .filter(|ls| seen_language_servers.insert(ls.id()))
         // TODO this should probably already been filtered in something like "language_servers_with_feature"
         .filter_map(|language_server| {
             let offset_encoding = language_server.offset_encoding();
             let language_server_id = language_server.id();
-            let range = range_to_lsp_range(doc.text(), selection_range, offset_encoding);
+            let lsp_range = range_to_lsp_range(doc.text(), range, offset_encoding);
             // Filter and convert overlapping diagnostics
             let code_action_context = lsp::CodeActionContext {
                 diagnostics: doc
                     .diagnostics()
                     .iter()
                     .filter(|&diag| {
-                        selection_range
-                            .overlaps(&helix_core::Range::new(diag.range.start, diag.range.end))
+                        range.overlaps(&helix_core::Range::new(diag.range.start, diag.range.end))
                     })
                     .map(|diag| diagnostic_to_lsp_diagnostic(doc.text(), diag, offset_encoding))
                     .collect(),
-                only: None,
+                only: only.clone(),
                 trigger_kind: Some(CodeActionTriggerKind::INVOKED),
             };
             let code_action_request =
-                language_server.code_actions(doc.identifier(), range, code_action_context)?;
+                language_server.code_actions(doc.identifier(), lsp_range, code_action_context)?;
             Some((code_action_request, language_server_id))
         })
-        .map(|(request, ls_id)| async move {
-            let Some(mut actions) = request.await? else {
-                return anyhow::Ok(Vec::new());
-            };
+        .map(move |(request, ls_id)| {
+            let kind = kind.clone();
+            async move {
+                let Some(mut actions) = request.await? else {
+                    return anyhow::Ok(Vec::new());
+                };
 
-            // remove disabled code actions
-            actions.retain(|action| {
-                matches!(
-                    action,
-                    CodeActionOrCommand::Command(_)
-                        | CodeActionOrCommand::CodeAction(CodeAction { disabled: None, .. })
-                )
-            });
+                // remove disabled code actions
+                actions.retain(|action| {
+                    matches!(
+                        action,
+                        CodeActionOrCommand::Command(_)
+                            | CodeActionOrCommand::CodeAction(CodeAction { disabled: None, .. })
+                    )
+                });
 
-            // Sort codeactions into a useful order. This behaviour is only partially described in the LSP spec.
-            // Many details are modeled after vscode because language servers are usually tested against it.
-            // VScode sorts the codeaction two times:
-            //
-            // First the codeactions that fix some diagnostics are moved to the front.
-            // If both codeactions fix some diagnostics (or both fix none) the codeaction
-            // that is marked with `is_preferred` is shown first. The codeactions are then shown in separate
-            // submenus that only contain a certain category (see `action_category`) of actions.
-            //
-            // Below this done in in a single sorting step
-            actions.sort_by(|action1, action2| {
-                // sort actions by category
-                let order = action_category(action1).cmp(&action_category(action2));
-                if order != Ordering::Equal {
-                    return order;
-                }
-                // within the categories sort by relevancy.
-                // Modeled after the `codeActionsComparator` function in vscode:
-                // https://github.com/microsoft/vscode/blob/eaec601dd69aeb4abb63b9601a6f44308c8d8c6e/src/vs/editor/contrib/codeAction/browser/codeAction.ts
-
-                // if one code action fixes a diagnostic but the other one doesn't show it first
-                let order = action_fixes_diagnostics(action1)
-                    .cmp(&action_fixes_diagnostics(action2))
-                    .reverse();
-                if order != Ordering::Equal {
-                    return order;
+                // language servers may ignore `only`
+                if let Some(kind) = &kind {
+                    actions.retain(|action| match action {
+                        CodeActionOrCommand::CodeAction(CodeAction {
+                            kind: Some(action_kind),
+                            ..
+                        }) => is_code_action_kind(action_kind, kind.as_str()),
+                        _ => false,
+                    });
                 }
 
-                // if one of the codeactions is marked as preferred show it first
-                // otherwise keep the original LSP sorting
-                action_preferred(action1)
-                    .cmp(&action_preferred(action2))
-                    .reverse()
-            });
+                // Sort codeactions into a useful order. This behaviour is only partially described in the LSP spec.
+                // Many details are modeled after vscode because language servers are usually tested against it.
+                // VScode sorts the codeaction two times:
+                //
+                // First the codeactions that fix some diagnostics are moved to the front.
+                // If both codeactions fix some diagnostics (or both fix none) the codeaction
+                // that is marked with `is_preferred` is shown first. The codeactions are then shown in separate
+                // submenus that only contain a certain category (see `action_category`) of actions.
+                //
+                // Below this done in in a single sorting step
+                actions.sort_by(|action1, action2| {
+                    // sort actions by category
+                    let order = action_category(action1).cmp(&action_category(action2));
+                    if order != Ordering::Equal {
+                        return order;
+                    }
+                    // within the categories sort by relevancy.
+                    // Modeled after the `codeActionsComparator` function in vscode:
+                    // https://github.com/microsoft/vscode/blob/eaec601dd69aeb4abb63b9601a6f44308c8d8c6e/src/vs/editor/contrib/codeAction/browser/codeAction.ts
+
+                    // if one code action fixes a diagnostic but the other one doesn't show it first
+                    let order = action_fixes_diagnostics(action1)
+                        .cmp(&action_fixes_diagnostics(action2))
+                        .reverse();
+                    if order != Ordering::Equal {
+                        return order;
+                    }
 
-            Ok(actions
-                .into_iter()
-                .map(|lsp_item| CodeActionOrCommandItem {
-                    lsp_item,
-                    language_server_id: ls_id,
-                })
-                .collect())
+                    // if one of the codeactions is marked as preferred show it first
+                    // otherwise keep the original LSP sorting
+                    action_preferred(action1)
+                        .cmp(&action_preferred(action2))
+                        .reverse()
+                });
+
+                Ok(actions
+                    .into_iter()
+                    .map(|lsp_item| CodeActionOrCommandItem {
+                        lsp_item,
+                        language_server_id: ls_id,
+                    })
+                    .collect())
+            }
         })
-        .collect();
+        .collect()
+}
 
-    if futures.is_empty() {
-        cx.editor
-            .set_error("No configured language server supports code actions");
+/// The first enabled action of `kind`, or of one of its sub-kinds, among `actions`. Actions
+/// without a kind are never applied on save.
+fn code_action_on_save(actions: Vec<CodeActionOrCommand>, kind: &str) -> Option<CodeAction> {
+    actions.into_iter().find_map(|action| match action {
+        CodeActionOrCommand::CodeAction(action @ CodeAction { disabled: None, .. })
+            if action
+                .kind
+                .as_ref()
+                .is_some_and(|action_kind| is_code_action_kind(action_kind, kind)) =>
+        {
+            Some(action)
+        }
+        _ => None,
+    })
+}
+
+/// Whether the code action kind `action_kind` is `kind` or one of its sub-kinds, like
+/// `source.organizeImports` of `source`.
+fn is_code_action_kind(action_kind: &lsp::CodeActionKind, kind: &str) -> bool {
+    action_kind.as_str() == kind
+        || action_kind
+            .as_str()
+            .strip_prefix(kind)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+fn apply_code_action(editor: &mut Editor, action: &CodeActionOrCommandItem) {
+    let Some(language_server) = editor.language_server_by_id(action.language_server_id) else {
+        editor.set_error("Language Server disappeared");
         return;
+    };
+    let offset_encoding = language_server.offset_encoding();
+
+    match &action.lsp_item {
+        lsp::CodeActionOrCommand::Command(command) => {
+            log::debug!("code action command: {:?}", command);
+            editor.execute_lsp_command(command.clone(), action.language_server_id);
+        }
+        lsp::CodeActionOrCommand::CodeAction(code_action) => {
+            log::debug!("code action: {:?}", code_action);
+            // we support lsp "codeAction/resolve" for `edit` and `command` fields
+            let mut resolved_code_action = None;
+            if code_action.edit.is_none() || code_action.command.is_none() {
+                if let Some(future) = language_server.resolve_code_action(code_action) {
+                    if let Ok(code_action) = helix_lsp::block_on(future) {
+                        resolved_code_action = Some(code_action);
+                    }
+                }
+            }
+            let resolved_code_action = resolved_code_action.as_ref().unwrap_or(code_action);
+
+            let language_server_id = action.language_server_id;
+            let command = resolved_code_action.command.clone();
+            match &resolved_code_action.edit {
+                // if code action provides both edit and command first the edit
+                // should be applied and then the command, which is skipped if the
+                // edit is rejected while it is reviewed
+                Some(workspace_edit) => editor.submit_workspace_edit(PendingWorkspaceEdit {
+                    source: WorkspaceEditSource::CodeAction,
+                    title: code_action.title.clone(),
+                    offset_encoding,
+                    edit: workspace_edit.clone(),
+                    on_done: command.map(|command| -> WorkspaceEditCallback {
+                        Box::new(move |editor, result| {
+                            if result.is_ok() {
+                                editor.execute_lsp_command(command, language_server_id);
+                            }
+                        })
+                    }),
+                }),
+                None => {
+                    if let Some(command) = command {
+                        editor.execute_lsp_command(command, language_server_id);
+                    }
+                }
+            }
+        }
     }
+}
 
+/// Gathers the code actions of `futures` and shows them in a menu, or `empty` as error if there
+/// are none.
+fn code_action_menu(
+    cx: &mut Context,
+    mut futures: FuturesOrdered<
+        impl Future<Output = anyhow::Result<Vec<CodeActionOrCommandItem>>> + Send + 'static,
+    >,
+    empty: &'static str,
+) {
     cx.jobs.callback(async move {
         let mut actions = Vec::new();
 
@@ -764,7 +1006,7 @@ pub fn code_action(cx: &mut Context) {
 
         let call = move |editor: &mut Editor, compositor: &mut Compositor| {
             if actions.is_empty() {
-                editor.set_error("No code actions available");
+                editor.set_error(empty);
                 return;
             }
             let mut picker = ui::Menu::new(actions, (), move |editor, action, event| {
@@ -773,44 +1015,7 @@ pub fn code_action(cx: &mut Context) {
                 }
 
                 // always present here
-                let action = action.unwrap();
-                let Some(language_server) = editor.language_server_by_id(action.language_server_id)
-                else {
-                    editor.set_error("Language Server disappeared");
-                    return;
-                };
-                let offset_encoding = language_server.offset_encoding();
-
-                match &action.lsp_item {
-                    lsp::CodeActionOrCommand::Command(command) => {
-                        log::debug!("code action command: {:?}", command);
-                        editor.execute_lsp_command(command.clone(), action.language_server_id);
-                    }
-                    lsp::CodeActionOrCommand::CodeAction(code_action) => {
-                        log::debug!("code action: {:?}", code_action);
-                        // we support lsp "codeAction/resolve" for `edit` and `command` fields
-                        let mut resolved_code_action = None;
-                        if code_action.edit.is_none() || code_action.command.is_none() {
-                            if let Some(future) = language_server.resolve_code_action(code_action) {
-                                if let Ok(code_action) = helix_lsp::block_on(future) {
-                                    resolved_code_action = Some(code_action);
-                                }
-                            }
-                        }
-                        let resolved_code_action =
-                            resolved_code_action.as_ref().unwrap_or(code_action);
-
-                        if let Some(ref workspace_edit) = resolved_code_action.edit {
-                            let _ = editor.apply_workspace_edit(offset_encoding, workspace_edit);
-                        }
-
-                        // if code action provides both edit and command first the edit
-                        // should be applied and then the command
-                        if let Some(command) = &code_action.command {
-                            editor.execute_lsp_command(command.clone(), action.language_server_id);
-                        }
-                    }
-                }
+                apply_code_action(editor, action.unwrap());
             });
             picker.move_down(); // pre-select the first item
 
@@ -825,6 +1030,150 @@ pub fn code_action(cx: &mut Context) {
     });
 }
 
+pub fn code_action(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let selection_range = doc.selection(view.id).primary();
+    let futures = request_code_actions(doc, selection_range, None);
+
+    if futures.is_empty() {
+        cx.editor
+            .set_error("No configured language server supports code actions");
+        return;
+    }
+
+    code_action_menu(cx, futures, "No code actions available");
+}
+
+/// Shows the `source` code actions of the whole document, such as organizing its imports.
+pub fn source_action(cx: &mut Context) {
+    let doc = doc!(cx.editor);
+    let range = helix_core::Range::new(0, doc.text().len_chars());
+    let futures = request_code_actions(doc, range, Some(lsp::CodeActionKind::SOURCE));
+
+    if futures.is_empty() {
+        cx.editor
+            .set_error("No configured language server supports code actions");
+        return;
+    }
+
+    code_action_menu(cx, futures, "No source actions available");
+}
+
+/// Applies the first `source.organizeImports` code action of the document.
+pub fn organize_imports(editor: &mut Editor, jobs: &mut Jobs) {
+    let doc = doc!(editor);
+    let range = helix_core::Range::new(0, doc.text().len_chars());
+    let mut futures = request_code_actions(
+        doc,
+        range,
+        Some(lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+    );
+
+    if futures.is_empty() {
+        editor.set_error("No configured language server supports code actions");
+        return;
+    }
+
+    jobs.callback(async move {
+        let mut action = None;
+        while let Some(output) = futures.next().await {
+            match output {
+                Ok(lsp_items) => {
+                    if let Some(item) = lsp_items.into_iter().next() {
+                        action = Some(item);
+                        break;
+                    }
+                }
+                Err(err) => log::error!("while gathering code actions: {err}"),
+            }
+        }
+
+        let call = move |editor: &mut Editor| match action {
+            Some(action) => apply_code_action(editor, &action),
+            None => editor.set_error("No organize imports action available"),
+        };
+        Ok(Callback::Editor(Box::new(call)))
+    });
+}
+
+/// The rust-analyzer server of the document, whose extensions to the protocol are requested.
+fn rust_analyzer(doc: &Document) -> anyhow::Result<&Client> {
+    doc.language_servers()
+        .find(|language_server| language_server.name() == rust_analyzer::NAME)
+        .ok_or_else(|| anyhow::anyhow!("rust-analyzer is not running for the current document"))
+}
+
+/// Shows `text` in the output buffer called `name`, highlighted as `language`.
+fn show_output(editor: &mut Editor, name: &str, text: &str, language: Option<&str>) {
+    let id = editor.set_output(name, text);
+    if let Some(language) = language {
+        let loader = editor.syn_loader.load();
+        if let Err(err) = doc_mut!(editor, &id).set_language_by_language_id(language, &loader) {
+            log::error!("failed to highlight the output buffer {name}: {err}");
+        }
+    }
+    super::typed::open_output_buffer(editor, name);
+}
+
+/// Opens the recursive expansion of the macro call under the cursor in an output buffer.
+pub fn expand_macro(editor: &mut Editor, jobs: &mut Jobs) -> anyhow::Result<()> {
+    let (view, doc) = current_ref!(editor);
+    let language_server = rust_analyzer(doc)?;
+    let pos = doc.position(view.id, language_server.offset_encoding());
+    let future = language_server.rust_analyzer_expand_macro(doc.identifier(), pos);
+
+    jobs.callback(async move {
+        let expansion = future.await?;
+        let call = move |editor: &mut Editor| match expansion {
+            Some(expansion) => {
+                let text = format!(
+                    "// Recursive expansion of {}! macro\n\n{}\n",
+                    expansion.name, expansion.expansion
+                );
+                show_output(editor, "macro-expansion", &text, Some("rust"));
+            }
+            None => editor.set_error("No macro call under the cursor"),
+        };
+        Ok(Callback::Editor(Box::new(call)))
+    });
+    Ok(())
+}
+
+/// Opens the `Cargo.toml` of the crate of the current document.
+pub fn open_cargo_toml(editor: &mut Editor, jobs: &mut Jobs) -> anyhow::Result<()> {
+    let doc = doc!(editor);
+    let language_server = rust_analyzer(doc)?;
+    let offset_encoding = language_server.offset_encoding();
+    let future = language_server.rust_analyzer_open_cargo_toml(doc.identifier());
+
+    jobs.callback(async move {
+        let location = future.await?;
+        let call = move |editor: &mut Editor| match location
+            .and_then(|location| lsp_location_to_location(location, offset_encoding))
+        {
+            Some(location) => jump_to_location(editor, &location, Action::Replace),
+            None => editor.set_error("No Cargo.toml found for the current document"),
+        };
+        Ok(Callback::Editor(Box::new(call)))
+    });
+    Ok(())
+}
+
+/// Opens the HIR of the function under the cursor in an output buffer.
+pub fn view_hir(editor: &mut Editor, jobs: &mut Jobs) -> anyhow::Result<()> {
+    let (view, doc) = current_ref!(editor);
+    let language_server = rust_analyzer(doc)?;
+    let pos = doc.position(view.id, language_server.offset_encoding());
+    let future = language_server.rust_analyzer_view_hir(doc.identifier(), pos);
+
+    jobs.callback(async move {
+        let hir = future.await?;
+        let call = move |editor: &mut Editor| show_output(editor, "hir", &hir, None);
+        Ok(Callback::Editor(Box::new(call)))
+    });
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct ApplyEditError {
     pub kind: ApplyEditErrorKind,
@@ -1027,6 +1376,320 @@ pub fn goto_reference(cx: &mut Context) {
     });
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HierarchyDirection {
+    IncomingCalls,
+    OutgoingCalls,
+    Supertypes,
+    Subtypes,
+}
+
+/// An item of a call or type hierarchy.
+#[derive(Clone)]
+enum HierarchyItem {
+    Call(lsp::CallHierarchyItem),
+    Type(lsp::TypeHierarchyItem),
+}
+
+impl HierarchyItem {
+    fn name(&self) -> &str {
+        match self {
+            Self::Call(item) => &item.name,
+            Self::Type(item) => &item.name,
+        }
+    }
+
+    fn kind(&self) -> lsp::SymbolKind {
+        match self {
+            Self::Call(item) => item.kind,
+            Self::Type(item) => item.kind,
+        }
+    }
+
+    fn location(&self) -> lsp::Location {
+        match self {
+            Self::Call(item) => lsp::Location::new(item.uri.clone(), item.selection_range),
+            Self::Type(item) => lsp::Location::new(item.uri.clone(), item.selection_range),
+        }
+    }
+}
+
+/// An entry of the hierarchy picker, which shows a call or type hierarchy as a tree.
+#[derive(Clone)]
+struct HierarchyEntry {
+    item: HierarchyItem,
+    /// Where the entry jumps to: the first call site for incoming calls and the item otherwise.
+    location: Option<Location>,
+    depth: usize,
+    expanded: bool,
+}
+
+/// The tree of the hierarchy picker, which is shown again whenever an entry is expanded or
+/// collapsed.
+#[derive(Clone)]
+struct Hierarchy {
+    direction: HierarchyDirection,
+    language_server_id: LanguageServerId,
+    offset_encoding: OffsetEncoding,
+    entries: Vec<HierarchyEntry>,
+}
+
+/// Children of a hierarchy item along with the ranges of the call sites, which are empty for
+/// type hierarchies.
+type Children = Vec<(HierarchyItem, Vec<lsp::Range>)>;
+
+impl Hierarchy {
+    fn push(&mut self, item: HierarchyItem) {
+        let location = lsp_location_to_location(item.location(), self.offset_encoding);
+        self.entries.push(HierarchyEntry {
+            item,
+            location,
+            depth: 0,
+            expanded: false,
+        });
+    }
+
+    /// Shows the children of the entry at `index` below it.
+    fn expand(&mut self, index: usize, children: Children) {
+        let depth = self.entries[index].depth + 1;
+        let children: Vec<_> = children
+            .into_iter()
+            .map(|(item, ranges)| {
+                let mut location = item.location();
+                if let (HierarchyDirection::IncomingCalls, Some(range)) =
+                    (self.direction, ranges.first())
+                {
+                    location.range = *range;
+                }
+                HierarchyEntry {
+                    location: lsp_location_to_location(location, self.offset_encoding),
+                    item,
+                    depth,
+                    expanded: false,
+                }
+            })
+            .collect();
+        self.entries[index].expanded = true;
+        self.entries.splice(index + 1..index + 1, children);
+    }
+
+    /// Hides the descendants of the entry at `index`.
+    fn collapse(&mut self, index: usize) {
+        let depth = self.entries[index].depth;
+        let end = self.entries[index + 1..]
+            .iter()
+            .position(|entry| entry.depth <= depth)
+            .map_or(self.entries.len(), |len| index + 1 + len);
+        self.entries.drain(index + 1..end);
+        self.entries[index].expanded = false;
+    }
+}
+
+async fn request_children(
+    language_server: Arc<Client>,
+    direction: HierarchyDirection,
+    item: HierarchyItem,
+) -> anyhow::Result<Children> {
+    let children = match (direction, item) {
+        (HierarchyDirection::IncomingCalls, HierarchyItem::Call(item)) => language_server
+            .call_hierarchy_incoming_calls(item)
+            .await?
+            .into_iter()
+            .flatten()
+            .map(|call| (HierarchyItem::Call(call.from), call.from_ranges))
+            .collect(),
+        (HierarchyDirection::OutgoingCalls, HierarchyItem::Call(item)) => language_server
+            .call_hierarchy_outgoing_calls(item)
+            .await?
+            .into_iter()
+            .flatten()
+            .map(|call| (HierarchyItem::Call(call.to), call.from_ranges))
+            .collect(),
+        (HierarchyDirection::Supertypes, HierarchyItem::Type(item)) => language_server
+            .type_hierarchy_supertypes(item)
+            .await?
+            .into_iter()
+            .flatten()
+            .map(|item| (HierarchyItem::Type(item), Vec::new()))
+            .collect(),
+        (HierarchyDirection::Subtypes, HierarchyItem::Type(item)) => language_server
+            .type_hierarchy_subtypes(item)
+            .await?
+            .into_iter()
+            .flatten()
+            .map(|item| (HierarchyItem::Type(item), Vec::new()))
+            .collect(),
+        _ => unreachable!("hierarchy items match the direction of their hierarchy"),
+    };
+    Ok(children)
+}
+
+fn hierarchy_picker(
+    hierarchy: Hierarchy,
+    cursor: usize,
+) -> ui::Picker<(usize, HierarchyEntry), ()> {
+    type Item = (usize, HierarchyEntry);
+    let columns = [
+        ui::PickerColumn::new("name", |(_, entry): &Item, _| {
+            let marker = if entry.expanded { "▾" } else { "▸" };
+            format!("{}{marker} {}", "  ".repeat(entry.depth), entry.item.name()).into()
+        }),
+        ui::PickerColumn::new("kind", |(_, entry): &Item, _| {
+            display_symbol_kind(entry.item.kind()).into()
+        }),
+        ui::PickerColumn::new("path", |(_, entry): &Item, _| {
+            let Some(location) = &entry.location else {
+                return "".into();
+            };
+            match location.uri.as_path() {
+                Some(path) => format!(
+                    "{}:{}",
+                    path::get_relative_path(path).display(),
+                    location.range.start.line + 1
+                )
+                .into(),
+                None => location.uri.to_string().into(),
+            }
+        }),
+    ];
+
+    let items: Vec<_> = hierarchy.entries.iter().cloned().enumerate().collect();
+    Picker::new(columns, 0, items, (), |cx, (_, entry): &Item, action| {
+        if let Some(location) = &entry.location {
+            jump_to_location(cx.editor, location, action);
+        }
+    })
+    .with_preview(|_editor, (_, entry)| location_to_file_location(entry.location.as_ref()?))
+    .with_expand(move |cx, (index, _)| toggle_hierarchy_entry(cx, &hierarchy, *index))
+    .with_initial_cursor(cursor as u32)
+    .truncate_start(false)
+}
+
+/// Expands or collapses the entry at `index` of the hierarchy picker.
+fn toggle_hierarchy_entry(cx: &mut compositor::Context, hierarchy: &Hierarchy, index: usize) {
+    let mut hierarchy = hierarchy.clone();
+    let children = if hierarchy.entries[index].expanded {
+        hierarchy.collapse(index);
+        None
+    } else {
+        let Some(language_server) = cx
+            .editor
+            .language_servers
+            .get_by_id(hierarchy.language_server_id)
+            .cloned()
+        else {
+            cx.editor.set_error("Language Server disappeared");
+            return;
+        };
+        let item = hierarchy.entries[index].item.clone();
+        Some(request_children(language_server, hierarchy.direction, item))
+    };
+
+    cx.jobs.callback(async move {
+        if let Some(children) = children {
+            hierarchy.expand(index, children.await?);
+        }
+        let call = move |_editor: &mut Editor, compositor: &mut Compositor| {
+            let picker = hierarchy_picker(hierarchy, index);
+            compositor.replace_or_push(ui::picker::ID, overlaid(picker));
+        };
+        Ok(Callback::EditorCompositor(Box::new(call)))
+    });
+}
+
+fn hierarchy(cx: &mut Context, direction: HierarchyDirection) {
+    let (feature, name) = match direction {
+        HierarchyDirection::IncomingCalls | HierarchyDirection::OutgoingCalls => {
+            (LanguageServerFeature::CallHierarchy, "call")
+        }
+        HierarchyDirection::Supertypes | HierarchyDirection::Subtypes => {
+            (LanguageServerFeature::TypeHierarchy, "type")
+        }
+    };
+    let (view, doc) = current_ref!(cx.editor);
+    let Some(language_server) = doc.language_servers_with_feature(feature).next() else {
+        cx.editor.set_error(format!(
+            "No configured language server supports {name} hierarchies"
+        ));
+        return;
+    };
+    let offset_encoding = language_server.offset_encoding();
+    let language_server_id = language_server.id();
+    let pos = doc.position(view.id, offset_encoding);
+    let items: BoxFuture<'static, anyhow::Result<Vec<HierarchyItem>>> = match feature {
+        LanguageServerFeature::CallHierarchy => {
+            let future = language_server
+                .prepare_call_hierarchy(doc.identifier(), pos)
+                .unwrap();
+            async move {
+                let items = future.await?.into_iter().flatten();
+                Ok(items.map(HierarchyItem::Call).collect())
+            }
+            .boxed()
+        }
+        _ => {
+            let future = language_server
+                .prepare_type_hierarchy(doc.identifier(), pos)
+                .unwrap();
+            async move {
+                let items = future.await?.into_iter().flatten();
+                Ok(items.map(HierarchyItem::Type).collect())
+            }
+            .boxed()
+        }
+    };
+    let Some(language_server) = cx
+        .editor
+        .language_servers
+        .get_by_id(language_server_id)
+        .cloned()
+    else {
+        return;
+    };
+
+    cx.jobs.callback(async move {
+        let mut hierarchy = Hierarchy {
+            direction,
+            language_server_id,
+            offset_encoding,
+            entries: Vec::new(),
+        };
+        for item in items.await? {
+            hierarchy.push(item);
+        }
+        // Show the children of the symbol at the cursor right away.
+        if let Some(entry) = hierarchy.entries.first() {
+            let children = request_children(language_server, direction, entry.item.clone()).await?;
+            hierarchy.expand(0, children);
+        }
+        let call = move |editor: &mut Editor, compositor: &mut Compositor| {
+            if hierarchy.entries.is_empty() {
+                editor.set_error(format!("No {name} hierarchy found at the cursor"));
+                return;
+            }
+            let picker = hierarchy_picker(hierarchy, 0);
+            compositor.push(Box::new(overlaid(picker)));
+        };
+        Ok(Callback::EditorCompositor(Box::new(call)))
+    });
+}
+
+pub fn incoming_calls(cx: &mut Context) {
+    hierarchy(cx, HierarchyDirection::IncomingCalls);
+}
+
+pub fn outgoing_calls(cx: &mut Context) {
+    hierarchy(cx, HierarchyDirection::OutgoingCalls);
+}
+
+pub fn supertypes(cx: &mut Context) {
+    hierarchy(cx, HierarchyDirection::Supertypes);
+}
+
+pub fn subtypes(cx: &mut Context) {
+    hierarchy(cx, HierarchyDirection::Subtypes);
+}
+
 pub fn signature_help(cx: &mut Context) {
     cx.editor
         .handlers
@@ -1047,14 +1710,44 @@ pub fn hover(cx: &mut Context) {
         return;
     }
 
+    let pos = doc
+        .selection(view.id)
+        .primary()
+        .cursor(doc.text().slice(..));
+    let hovers = request_hovers(doc, pos);
+
+    cx.jobs.callback(async move {
+        let hovers = hovers.await;
+
+        let call = move |editor: &mut Editor, compositor: &mut Compositor| {
+            if hovers.is_empty() {
+                editor.set_status("No hover results available.");
+                return;
+            }
+
+            // create new popup
+            let contents = Hover::new(hovers, editor.syn_loader.clone());
+            let popup = Popup::new(Hover::ID, contents).auto_close(true);
+            compositor.replace_or_push(Hover::ID, popup);
+        };
+        Ok(Callback::EditorCompositor(Box::new(call)))
+    });
+}
+
+/// Requests the hover information at the char position `pos` of the document from all of its
+/// language servers supporting hover, resolving to the responses which have any.
+pub(crate) fn request_hovers(
+    doc: &Document,
+    pos: usize,
+) -> impl Future<Output = Vec<(String, lsp::Hover)>> {
     let mut seen_language_servers = HashSet::new();
     let mut futures: FuturesOrdered<_> = doc
         .language_servers_with_feature(LanguageServerFeature::Hover)
         .filter(|ls| seen_language_servers.insert(ls.id()))
         .map(|language_server| {
             let server_name = language_server.name().to_string();
-            // TODO: factor out a doc.position_identifier() that returns lsp::TextDocumentPositionIdentifier
-            let pos = doc.position(view.id, language_server.offset_encoding());
+            let pos =
+                helix_lsp::util::pos_to_lsp_pos(doc.text(), pos, language_server.offset_encoding());
             let request = language_server
                 .text_document_hover(doc.identifier(), pos, None)
                 .unwrap();
@@ -1063,7 +1756,7 @@ pub fn hover(cx: &mut Context) {
         })
         .collect();
 
-    cx.jobs.callback(async move {
+    async move {
         let mut hovers: Vec<(String, lsp::Hover)> = Vec::new();
 
         while let Some(response) = futures.next().await {
@@ -1073,37 +1766,162 @@ pub fn hover(cx: &mut Context) {
                 Err(err) => log::error!("Error requesting hover: {err}"),
             }
         }
+        hovers
+    }
+}
 
-        let call = move |editor: &mut Editor, compositor: &mut Compositor| {
-            if hovers.is_empty() {
-                editor.set_status("No hover results available.");
-                return;
-            }
+/// The primary selection, or the word under the cursor if it is a single character.
+fn get_prefill_from_word_boundary(editor: &Editor) -> String {
+    let (view, doc) = current_ref!(editor);
+    let text = doc.text().slice(..);
+    let primary_selection = doc.selection(view.id).primary();
+    if primary_selection.len() > 1 {
+        primary_selection
+    } else {
+        use helix_core::textobject::{textobject_word, TextObject};
+        textobject_word(text, primary_selection, TextObject::Inside, 1, false)
+    }
+    .fragment(text)
+    .into()
+}
 
-            // create new popup
-            let contents = Hover::new(hovers, editor.syn_loader.clone());
-            let popup = Popup::new(Hover::ID, contents).auto_close(true);
-            compositor.replace_or_push(Hover::ID, popup);
+/// Opens the documentation of the symbol under the cursor, which language servers locate with
+/// the `externalDocs` extension or link in their hover, falling back to the `documentation-url`
+/// of the language.
+pub fn open_external_docs(cx: &mut Context) {
+    let word = get_prefill_from_word_boundary(cx.editor);
+    let config = cx.editor.config();
+    let pager = config.documentation_pager.clone();
+    let shell = config.shell.clone();
+
+    let (view, doc) = current_ref!(cx.editor);
+    let mut futures: FuturesOrdered<BoxFuture<'static, anyhow::Result<Option<Url>>>> = doc
+        .language_servers()
+        .filter_map(|language_server| {
+            let pos = doc.position(view.id, language_server.offset_encoding());
+            let future = language_server.external_docs(doc.identifier(), pos)?;
+            Some(async move { anyhow::Ok(future.await?) }.boxed())
+        })
+        .collect();
+    let hovers = request_hovers(
+        doc,
+        doc.selection(view.id)
+            .primary()
+            .cursor(doc.text().slice(..)),
+    );
+    futures.push_back(
+        async move {
+            let hovers = hovers.await;
+            Ok(hover_link(hovers.iter().map(|(_, hover)| hover)))
+        }
+        .boxed(),
+    );
+    let fallback = doc
+        .language_config()
+        .and_then(|config| config.documentation_url.clone())
+        .filter(|_| !word.trim().is_empty())
+        .map(|template| {
+            let word: String = url::form_urlencoded::byte_serialize(word.as_bytes()).collect();
+            template.replace("{word}", &word)
+        });
+
+    cx.jobs.callback(async move {
+        let mut url = None;
+        while let Some(result) = futures.next().await {
+            match result {
+                Ok(Some(link)) => {
+                    url = Some(link);
+                    break;
+                }
+                Ok(None) => (),
+                Err(err) => log::error!("while locating the documentation: {err}"),
+            }
+        }
+        let url = match (url, fallback) {
+            (Some(url), _) => url,
+            (None, Some(fallback)) => match Url::parse(&fallback) {
+                Ok(url) => url,
+                Err(err) => {
+                    let call = move |editor: &mut Editor| {
+                        editor.set_error(format!("Invalid documentation URL {fallback}: {err}"))
+                    };
+                    return Ok(Callback::Editor(Box::new(call)));
+                }
+            },
+            (None, None) => {
+                let call = |editor: &mut Editor| {
+                    editor.set_error("No documentation found for the symbol under the cursor")
+                };
+                return Ok(Callback::Editor(Box::new(call)));
+            }
         };
-        Ok(Callback::EditorCompositor(Box::new(call)))
+
+        match pager {
+            Some(pager) => {
+                let command = format!("{pager} '{}'", url.as_str().replace('\'', "%27"));
+                let call = move |editor: &mut Editor| {
+                    super::typed::run_in_terminal(editor, &shell, &command, None, None)
+                };
+                Ok(Callback::Suspended(Box::new(call)))
+            }
+            None => crate::open_external_url_callback(url).await,
+        }
     });
 }
 
-pub fn rename_symbol(cx: &mut Context) {
-    fn get_prefill_from_word_boundary(editor: &Editor) -> String {
-        let (view, doc) = current_ref!(editor);
-        let text = doc.text().slice(..);
-        let primary_selection = doc.selection(view.id).primary();
-        if primary_selection.len() > 1 {
-            primary_selection
-        } else {
-            use helix_core::textobject::{textobject_word, TextObject};
-            textobject_word(text, primary_selection, TextObject::Inside, 1, false)
+/// Words in the text of a link, or the line around it, which mark it as a link to documentation.
+const DOC_LINK_WORDS: &[&str] = &["doc", "reference", "manual"];
+
+/// The link to the documentation of the symbol in the text of the hovers, which some language
+/// servers add. Links to documentation are preferred over the other web links, such as links to
+/// the source or to issues.
+fn hover_link<'a>(hovers: impl IntoIterator<Item = &'a lsp::Hover>) -> Option<Url> {
+    let text = |marked: &lsp::MarkedString| match marked {
+        lsp::MarkedString::String(text) => text.clone(),
+        // Code blocks don't contain links.
+        lsp::MarkedString::LanguageString(_) => String::new(),
+    };
+    let is_doc = |text: &str| {
+        let text = text.to_lowercase();
+        DOC_LINK_WORDS.iter().any(|word| text.contains(word))
+    };
+    let mut links = Vec::new();
+    for hover in hovers {
+        let text = match &hover.contents {
+            lsp::HoverContents::Scalar(marked) => text(marked),
+            lsp::HoverContents::Array(marked) => marked.iter().map(text).collect(),
+            lsp::HoverContents::Markup(markup) => markup.value.clone(),
+        };
+        for line in text.lines() {
+            for (start, _) in line.match_indices("http") {
+                let link = &line[start..];
+                let end = link
+                    .find(|ch: char| {
+                        ch.is_whitespace() || matches!(ch, ')' | '>' | ']' | '"' | '`')
+                    })
+                    .unwrap_or(link.len());
+                let Some(url) = Url::parse(&link[..end])
+                    .ok()
+                    .filter(|url| matches!(url.scheme(), "http" | "https"))
+                else {
+                    continue;
+                };
+                // The text of a markdown link describes it, otherwise the line does.
+                let before = &line[..start];
+                let context = match before.strip_suffix("](") {
+                    Some(before) => before.rfind('[').map_or(line, |open| &before[open..]),
+                    None => line,
+                };
+                let doc = is_doc(context) || is_doc(url.as_str());
+                links.push((url, doc));
+            }
         }
-        .fragment(text)
-        .into()
     }
+    let link = links.iter().find(|(_, doc)| *doc).or(links.first());
+    link.map(|(url, _)| url.clone())
+}
 
+pub fn rename_symbol(cx: &mut Context) {
     fn get_prefill_from_lsp_response(
         editor: &Editor,
         offset_encoding: OffsetEncoding,
@@ -1160,11 +1978,13 @@ fn create_rename_prompt(
                     .unwrap();
 
                 match block_on(future) {
-                    Ok(edits) => {
-                        let _ = cx
-                            .editor
-                            .apply_workspace_edit(offset_encoding, &edits.unwrap_or_default());
-                    }
+                    Ok(edits) => cx.editor.submit_workspace_edit(PendingWorkspaceEdit {
+                        source: WorkspaceEditSource::Rename,
+                        title: format!("Rename to '{input}'"),
+                        offset_encoding,
+                        edit: edits.unwrap_or_default(),
+                        on_done: None,
+                    }),
                     Err(err) => cx.editor.set_error(err.to_string()),
                 }
             },
@@ -1271,14 +2091,10 @@ pub fn select_references_to_symbol_under_cursor(cx: &mut Context) {
 }
 
 pub fn compute_inlay_hints_for_all_views(editor: &mut Editor, jobs: &mut crate::job::Jobs) {
-    if !editor.config().lsp.display_inlay_hints {
-        return;
-    }
-
     for (view, _) in editor.tree.views() {
         let doc = match editor.documents.get(&view.doc) {
-            Some(doc) => doc,
-            None => continue,
+            Some(doc) if doc.display_inlay_hints() => doc,
+            _ => continue,
         };
         if let Some(callback) = compute_inlay_hints_for_view(view, doc) {
             jobs.callback(callback);
@@ -1340,8 +2156,8 @@ fn compute_inlay_hints_for_view(
     let callback = super::make_job_callback(
         language_server.text_document_range_inlay_hints(doc.identifier(), range, None)?,
         move |editor, _compositor, response: Option<Vec<lsp::InlayHint>>| {
-            // The config was modified or the window was closed while the request was in flight
-            if !editor.config().lsp.display_inlay_hints || editor.tree.try_get(view_id).is_none() {
+            // The window was closed while the request was in flight
+            if editor.tree.try_get(view_id).is_none() {
                 return;
             }
 
@@ -1350,6 +2166,10 @@ fn compute_inlay_hints_for_view(
                 Some(doc) => doc,
                 None => return,
             };
+            // The config was modified while the request was in flight
+            if !doc.display_inlay_hints() {
+                return;
+            }
 
             // If we have neither hints nor an LSP, empty the inlay hints since they're now oudated
             let mut hints = match response {
@@ -1456,3 +2276,72 @@ fn compute_inlay_hints_for_view(
 
     Some(callback)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn code_actions_on_save_need_their_kind() {
+        let action = |title: &str, kind: Option<&str>| {
+            CodeActionOrCommand::CodeAction(CodeAction {
+                title: title.to_owned(),
+                kind: kind.map(|kind| lsp::CodeActionKind::from(kind.to_owned())),
+                ..Default::default()
+            })
+        };
+        let disabled = CodeActionOrCommand::CodeAction(CodeAction {
+            title: "disabled".to_owned(),
+            kind: Some(lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+            disabled: Some(lsp::CodeActionDisabled {
+                reason: "nothing to organize".to_owned(),
+            }),
+            ..Default::default()
+        });
+        let actions = vec![
+            action("quickfix", None),
+            disabled,
+            action("fix all", Some("source.fixAll")),
+            action("organize", Some("source.organizeImports.rust")),
+        ];
+
+        let title = |kind| code_action_on_save(actions.clone(), kind).map(|action| action.title);
+        assert_eq!(title("source.organizeImports").as_deref(), Some("organize"));
+        assert_eq!(title("source.fixAll").as_deref(), Some("fix all"));
+        assert_eq!(title("source").as_deref(), Some("fix all"));
+        assert_eq!(title("source.fix"), None);
+        assert_eq!(title("quickfix"), None);
+    }
+
+    #[test]
+    fn hover_links_to_docs() {
+        let hover = |value: &str| lsp::Hover {
+            contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                kind: lsp::MarkupKind::Markdown,
+                value: value.to_owned(),
+            }),
+            range: None,
+        };
+        let link = |hovers: &[lsp::Hover]| hover_link(hovers).map(String::from);
+
+        let source = hover("[Source](https://github.com/a/b) | [Docs](https://example.com/b)");
+        assert_eq!(link(&[source]).as_deref(), Some("https://example.com/b"));
+        let reference = hover(
+            "See https://example.com/issue\n\n[MDN Reference](https://developer.mozilla.org/css)",
+        );
+        assert_eq!(
+            link(&[reference]).as_deref(),
+            Some("https://developer.mozilla.org/css")
+        );
+        // Links to documentation in later hovers are preferred too.
+        let hovers = [
+            hover("https://example.com/issue"),
+            hover("https://docs.rs/b"),
+        ];
+        assert_eq!(link(&hovers).as_deref(), Some("https://docs.rs/b"));
+        // Without links to documentation the first link is used.
+        let hovers = [hover("`b`"), hover("https://a.com/b and https://a.com/c")];
+        assert_eq!(link(&hovers).as_deref(), Some("https://a.com/b"));
+        assert_eq!(link(&[hover("no links")]), None);
+    }
+}
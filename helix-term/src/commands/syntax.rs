@@ -171,8 +171,11 @@ pub fn syntax_symbol_picker(cx: &mut Context) {
         (),
         move |cx, tag, action| {
             cx.editor.switch(doc_id, action);
+            let Some(doc) = try_doc_mut!(cx.editor, &doc_id) else {
+                cx.editor.set_error("The buffer has been closed");
+                return;
+            };
             let view = view_mut!(cx.editor);
-            let doc = doc_mut!(cx.editor, &doc_id);
             doc.set_selection(view.id, Selection::single(tag.start, tag.end));
             if action.align_view(view, doc.id()) {
                 align_view(doc, view, Align::Center)
@@ -408,7 +411,10 @@ struct SearchState {
                     }
                 }
             };
-            let doc = doc_mut!(cx.editor, &doc_id);
+            let Some(doc) = try_doc_mut!(cx.editor, &doc_id) else {
+                cx.editor.set_error("The buffer has been closed");
+                return;
+            };
             let view = view_mut!(cx.editor);
             let len_chars = doc.text().len_chars();
             if tag.start >= len_chars || tag.end > len_chars {
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fmt::Write;
 use std::io::BufReader;
 use std::ops::{self, Deref};
@@ -8,14 +9,18 @@
 
 use helix_core::command_line::{Args, Flag, Signature, Token, TokenKind};
 use helix_core::fuzzy::fuzzy_match;
+use helix_core::hex;
 use helix_core::indent::MAX_INDENT;
 use helix_core::line_ending;
 use helix_stdx::path::home_dir;
 use helix_view::document::{read_to_string, DEFAULT_LANGUAGE_NAME};
-use helix_view::editor::{CloseError, ConfigEvent};
+use helix_view::editor::{
+    CloseError, ConfigEvent, InteractiveOnExit, QueryPlayground, ReplayedMacro,
+    SYNTAX_TREE_OUTPUT_NAME,
+};
 use helix_view::expansion;
 use serde_json::Value;
-use ui::completers::{self, Completer};
+use ui::completers::{self, Completer, ValueCompleter};
 
 #[derive(Clone)]
 pub struct TypableCommand {
@@ -36,6 +41,12 @@ pub struct CommandCompleter {
 
     // All remaining arguments will use this completion method, if set.
     var_args: Completer,
+
+    // Whether previously run shell commands complete the first argument.
+    shell_history: bool,
+
+    // Completes the second argument depending on the first, if set.
+    values: Option<ValueCompleter>,
 }
 
 impl CommandCompleter {
@@ -43,6 +54,8 @@ const fn none() -> Self {
         Self {
             positional_args: &[],
             var_args: completers::none,
+            shell_history: false,
+            values: None,
         }
     }
 
@@ -50,6 +63,8 @@ const fn positional(completers: &'static [Completer]) -> Self {
         Self {
             positional_args: completers,
             var_args: completers::none,
+            shell_history: false,
+            values: None,
         }
     }
 
@@ -57,6 +72,22 @@ const fn all(completer: Completer) -> Self {
         Self {
             positional_args: &[],
             var_args: completer,
+            shell_history: false,
+            values: None,
+        }
+    }
+
+    const fn with_shell_history(self) -> Self {
+        Self {
+            shell_history: true,
+            ..self
+        }
+    }
+
+    const fn with_values(self, completer: ValueCompleter) -> Self {
+        Self {
+            values: Some(completer),
+            ..self
         }
     }
 
@@ -80,6 +111,7 @@ fn exit(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow:
             WriteOptions {
                 force: false,
                 auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+                privileged: None,
             },
         )?;
     }
@@ -99,6 +131,7 @@ fn force_exit(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> a
             WriteOptions {
                 force: true,
                 auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+                privileged: None,
             },
         )?;
     }
@@ -144,6 +177,7 @@ fn open(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow:
 }
 
 fn open_impl(cx: &mut compositor::Context, args: Args, action: Action) -> anyhow::Result<()> {
+    let binary = args.has_flag(OPEN_BINARY_FLAG.name);
     for arg in args {
         let (path, pos) = crate::args::parse_file(&arg);
         let path = helix_stdx::path::expand_tilde(path);
@@ -163,7 +197,11 @@ fn open_impl(cx: &mut compositor::Context, args: Args, action: Action) -> anyhow
             cx.jobs.callback(callback);
         } else {
             // Otherwise, just open the file
-            let _ = cx.editor.open(&path, action)?;
+            if binary {
+                cx.editor.open_hex(&path, action)?;
+            } else {
+                cx.editor.open(&path, action)?;
+            }
             let (view, doc) = current!(cx.editor);
             let pos = Selection::point(pos_at_coords(doc.text().slice(..), pos, true));
             doc.set_selection(view.id, pos);
@@ -174,6 +212,50 @@ fn open_impl(cx: &mut compositor::Context, args: Args, action: Action) -> anyhow
     Ok(())
 }
 
+fn hex_search(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let pattern = args.join(" ");
+    let pattern =
+        hex::parse_pattern(&pattern).ok_or_else(|| anyhow!("invalid hex pattern '{pattern}'"))?;
+    let (view, doc) = current!(cx.editor);
+    ensure!(
+        doc.is_hex(),
+        "the buffer is not a hex dump, open the file with ':open --binary'"
+    );
+    let bytes = hex::parse_with_positions(&doc.text().to_string())?;
+
+    // Search from the byte after the cursor, wrapping around at the end.
+    let cursor = doc
+        .selection(view.id)
+        .primary()
+        .cursor(doc.text().slice(..));
+    let start = bytes.partition_point(|&(pos, _)| pos <= cursor);
+    let len = pattern.len();
+    let found = (start..bytes.len()).chain(0..start).find(|&i| {
+        bytes.len() - i >= len
+            && bytes[i..i + len]
+                .iter()
+                .map(|&(_, byte)| byte)
+                .eq(pattern.iter().copied())
+    });
+    let Some(i) = found else {
+        bail!("pattern not found");
+    };
+
+    // Select from the first digit of the first byte to the last digit of the last byte.
+    let anchor = bytes[i].0;
+    let head = bytes[i + len - 1].0 + 2;
+    doc.set_selection(view.id, Selection::single(anchor, head));
+    align_view(doc, view, Align::Center);
+    if i < start {
+        cx.editor.set_status("Wrapped around document");
+    }
+    Ok(())
+}
+
 fn buffer_close_by_ids_impl(
     cx: &mut compositor::Context,
     doc_ids: &[DocumentId],
@@ -375,6 +457,63 @@ fn buffer_previous(
     Ok(())
 }
 
+fn buffer_pin(cx: &mut compositor::Context, _args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let doc_id = view!(cx.editor).doc;
+    if !cx.editor.pinned_buffers.remove(&doc_id) {
+        cx.editor.pinned_buffers.insert(doc_id);
+    }
+    Ok(())
+}
+
+fn buffer_move_left(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let doc_id = view!(cx.editor).doc;
+    cx.editor.move_buffer(doc_id, -1);
+    Ok(())
+}
+
+fn buffer_move_right(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let doc_id = view!(cx.editor).doc;
+    cx.editor.move_buffer(doc_id, 1);
+    Ok(())
+}
+
+fn buffer_group(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let doc_id = view!(cx.editor).doc;
+    match args.first() {
+        Some(group) => cx.editor.buffer_groups.insert(doc_id, group.to_owned()),
+        None => cx.editor.buffer_groups.remove(&doc_id),
+    };
+    Ok(())
+}
+
 fn write_impl(
     cx: &mut compositor::Context,
     path: Option<&str>,
@@ -394,7 +533,13 @@ fn write_impl(
         insert_final_newline(doc, view.id);
     }
 
+    if options.auto_format {
+        let doc_id = doc.id();
+        lsp::code_actions_on_save(cx.editor, doc_id);
+    }
+
     // Save an undo checkpoint for any outstanding changes.
+    let (view, doc) = current!(cx.editor);
     doc.append_changes_to_history(view);
 
     let (view, doc) = current_ref!(cx.editor);
@@ -405,7 +550,11 @@ fn write_impl(
                 doc.version(),
                 view.id,
                 fmt,
-                Some((path.map(Into::into), options.force)),
+                Some((
+                    path.map(Into::into),
+                    options.force,
+                    options.privileged.clone(),
+                )),
             );
 
             jobs.add(Job::with_callback(callback).wait_before_exiting());
@@ -416,7 +565,12 @@ fn write_impl(
 
     if fmt.is_none() {
         let id = doc.id();
-        cx.editor.save(id, path, options.force)?;
+        match options.privileged {
+            Some(privileged) => cx
+                .editor
+                .save_privileged(id, path, options.force, privileged)?,
+            None => cx.editor.save(id, path, options.force)?,
+        }
     }
 
     Ok(())
@@ -481,10 +635,12 @@ fn insert_final_newline(doc: &mut Document, view_id: ViewId) {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct WriteOptions {
     pub force: bool,
     pub auto_format: bool,
+    /// Writes the file with elevated privileges, see `:write --sudo`.
+    pub privileged: Option<PrivilegedWrite>,
 }
 
 fn write(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
@@ -492,14 +648,15 @@ fn write(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow
         return Ok(());
     }
 
-    write_impl(
-        cx,
-        args.first(),
-        WriteOptions {
-            force: false,
-            auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
-        },
-    )
+    let options = WriteOptions {
+        force: false,
+        auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+        privileged: None,
+    };
+    if args.has_flag(WRITE_SUDO_FLAG.name) {
+        return write_privileged(cx, args.first().map(str::to_owned), options);
+    }
+    write_impl(cx, args.first(), options)
 }
 
 fn force_write(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
@@ -507,14 +664,69 @@ fn force_write(cx: &mut compositor::Context, args: Args, event: PromptEvent) ->
         return Ok(());
     }
 
-    write_impl(
-        cx,
-        args.first(),
-        WriteOptions {
-            force: true,
-            auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
-        },
-    )
+    let options = WriteOptions {
+        force: true,
+        auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+        privileged: None,
+    };
+    if args.has_flag(WRITE_SUDO_FLAG.name) {
+        return write_privileged(cx, args.first().map(str::to_owned), options);
+    }
+    write_impl(cx, args.first(), options)
+}
+
+/// Writes the document with elevated privileges, asking for the password first if the command
+/// needs one.
+fn write_privileged(
+    cx: &mut compositor::Context,
+    path: Option<String>,
+    options: WriteOptions,
+) -> anyhow::Result<()> {
+    let config = cx.editor.config().privileged_write.clone();
+    if !config.password {
+        let privileged = PrivilegedWrite {
+            command: config.command,
+            password: None,
+        };
+        let options = WriteOptions {
+            privileged: Some(privileged),
+            ..options
+        };
+        return write_impl(cx, path.as_deref(), options);
+    }
+
+    let callback = async move {
+        let call: job::Callback = job::Callback::EditorCompositor(Box::new(
+            move |_editor: &mut Editor, compositor: &mut Compositor| {
+                let prompt = ui::Prompt::new(
+                    "password: ".into(),
+                    None,
+                    ui::completers::none,
+                    move |cx: &mut compositor::Context, input: &str, event: PromptEvent| {
+                        if event != PromptEvent::Validate {
+                            return;
+                        }
+                        let privileged = PrivilegedWrite {
+                            command: config.command.clone(),
+                            password: Some(input.to_owned()),
+                        };
+                        let options = WriteOptions {
+                            privileged: Some(privileged),
+                            ..options.clone()
+                        };
+                        if let Err(err) = write_impl(cx, path.as_deref(), options) {
+                            cx.editor.set_error(err.to_string());
+                        }
+                    },
+                )
+                .with_hidden_input();
+                compositor.push(Box::new(prompt));
+            },
+        ));
+        Ok(call)
+    };
+    cx.jobs.callback(callback);
+    Ok(())
 }
 
 fn write_buffer_close(
@@ -532,6 +744,7 @@ fn write_buffer_close(
         WriteOptions {
             force: false,
             auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+            privileged: None,
         },
     )?;
 
@@ -554,6 +767,7 @@ fn force_write_buffer_close(
         WriteOptions {
             force: true,
             auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+            privileged: None,
         },
     )?;
 
@@ -742,6 +956,7 @@ fn write_quit(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> a
         WriteOptions {
             force: false,
             auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+            privileged: None,
         },
     )?;
     cx.block_try_flush_writes()?;
@@ -763,6 +978,7 @@ fn force_write_quit(
         WriteOptions {
             force: true,
             auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+            privileged: None,
         },
     )?;
     cx.block_try_flush_writes()?;
@@ -807,6 +1023,8 @@ pub struct WriteAllOptions {
     pub force: bool,
     pub write_scratch: bool,
     pub auto_format: bool,
+    /// Skip the documents whose language opts out of auto saving.
+    pub auto_save: bool,
 }
 
 pub fn write_all_impl(
@@ -825,7 +1043,7 @@ pub fn write_all_impl(
         .into_iter()
         .filter_map(|id| {
             let doc = doc!(cx.editor, &id);
-            if !doc.is_modified() {
+            if !doc.is_modified() || (options.auto_save && !doc.auto_save_enabled()) {
                 return None;
             }
             if doc.path().is_none() {
@@ -843,7 +1061,6 @@ pub fn write_all_impl(
 
     for (doc_id, target_view) in saves {
         let doc = doc_mut!(cx.editor, &doc_id);
-        let view = view_mut!(cx.editor, target_view);
 
         if doc.trim_trailing_whitespace() {
             trim_trailing_whitespace(doc, target_view);
@@ -855,7 +1072,13 @@ pub fn write_all_impl(
             insert_final_newline(doc, target_view);
         }
 
+        if options.auto_format && !options.auto_save {
+            lsp::code_actions_on_save(cx.editor, doc_id);
+        }
+
         // Save an undo checkpoint for any outstanding changes.
+        let doc = doc_mut!(cx.editor, &doc_id);
+        let view = view_mut!(cx.editor, target_view);
         doc.append_changes_to_history(view);
 
         let fmt = if options.auto_format && config.auto_format {
@@ -866,7 +1089,7 @@ pub fn write_all_impl(
                     doc.version(),
                     target_view,
                     fmt,
-                    Some((None, options.force)),
+                    Some((None, options.force, None)),
                 );
                 jobs.add(Job::with_callback(callback).wait_before_exiting());
             })
@@ -897,6 +1120,7 @@ fn write_all(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> an
             force: false,
             write_scratch: true,
             auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+            auto_save: false,
         },
     )
 }
@@ -916,6 +1140,7 @@ fn force_write_all(
             force: true,
             write_scratch: true,
             auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+            auto_save: false,
         },
     )
 }
@@ -934,6 +1159,7 @@ fn write_all_quit(
             force: false,
             write_scratch: true,
             auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+            auto_save: false,
         },
     )?;
     quit_all_impl(cx, false)
@@ -953,6 +1179,7 @@ fn force_write_all_quit(
             force: true,
             write_scratch: true,
             auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+            auto_save: false,
         },
     );
     quit_all_impl(cx, true)
@@ -1062,6 +1289,86 @@ fn theme(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow
     Ok(())
 }
 
+/// Opens a picker of the themes which previews the selected one. The preview pane shows a buffer
+/// listing the scopes of the theme, each styled by the theme.
+fn theme_preview(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let true_color = cx.editor.config.load().true_color || crate::true_color();
+    let names = completers::theme_names();
+    let current = cx.editor.theme.name();
+    let cursor = names.iter().position(|name| name == current).unwrap_or(0);
+    let scopes_id = show_theme_scopes(cx.editor);
+
+    let callback = async move {
+        let call: job::Callback = job::Callback::EditorCompositor(Box::new(
+            move |_editor: &mut Editor, compositor: &mut Compositor| {
+                let picker = theme_picker(names, cursor, scopes_id, true_color);
+                compositor.push(Box::new(overlaid(picker)));
+            },
+        ));
+        Ok(call)
+    };
+    cx.jobs.callback(callback);
+    Ok(())
+}
+
+fn theme_picker(
+    names: Vec<String>,
+    cursor: usize,
+    scopes_id: DocumentId,
+    true_color: bool,
+) -> ui::Picker<String, ()> {
+    let columns = [ui::PickerColumn::new("theme", |name: &String, _| {
+        name.as_str().into()
+    })];
+    ui::Picker::new(columns, 0, names, (), move |cx, name, _action| {
+        match cx.editor.theme_loader.load(name) {
+            Ok(theme) if true_color || theme.is_16_color() => cx.editor.set_theme(theme),
+            Ok(_) => {
+                cx.editor.unset_theme_preview();
+                cx.editor
+                    .set_error("Unsupported theme: theme requires true color support");
+            }
+            Err(err) => {
+                cx.editor.unset_theme_preview();
+                cx.editor.set_error(format!("Could not load theme: {err}"));
+            }
+        }
+    })
+    .with_initial_cursor(cursor as u32)
+    .with_preview(move |_editor, _name| Some((ui::picker::PathOrId::Id(scopes_id), None)))
+    .with_on_change(move |cx, name| {
+        if let Ok(theme) = cx.editor.theme_loader.load(name) {
+            if true_color || theme.is_16_color() {
+                cx.editor.set_theme_preview(theme);
+                show_theme_scopes(cx.editor);
+            }
+        }
+    })
+    .with_on_abort(|cx| {
+        cx.editor.unset_theme_preview();
+        show_theme_scopes(cx.editor);
+    })
+}
+
+/// Fills the output buffer of `:theme-preview` with the scopes of the current theme.
+fn show_theme_scopes(editor: &mut Editor) -> DocumentId {
+    let mut scopes = editor.theme.scopes().to_vec();
+    scopes.sort();
+    scopes.dedup();
+    let mut text = scopes.join("\n");
+    text.push('\n');
+
+    editor.set_output(helix_view::theme::SCOPES_OUTPUT_NAME, &text)
+}
+
 fn yank_main_selection_to_clipboard(
     cx: &mut compositor::Context,
     _args: Args,
@@ -1075,6 +1382,27 @@ fn yank_main_selection_to_clipboard(
     Ok(())
 }
 
+fn spell_add(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let word = match args.first() {
+        Some(word) => word.to_string(),
+        None => {
+            let (view, doc) = current_ref!(cx.editor);
+            let range = super::word_under_cursor(doc, view)
+                .ok_or_else(|| anyhow!("No word under the cursor"))?;
+            doc.text().slice(range).to_string()
+        }
+    };
+    crate::handlers::spelling::add_word(&word)?;
+    crate::handlers::spelling::request_all_spell_checks(cx.editor);
+    cx.editor
+        .set_status(format!("Added {word} to the dictionary"));
+    Ok(())
+}
+
 fn yank_joined(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
@@ -1294,12 +1622,76 @@ fn set_encoding(
 
     let doc = doc_mut!(cx.editor);
     if let Some(label) = args.first() {
-        doc.set_encoding(label)
+        doc.set_encoding(label)?;
+        warn_lossy_encoding(cx.editor);
     } else {
         let encoding = doc.encoding().name().to_owned();
         cx.editor.set_status(encoding);
-        Ok(())
     }
+    Ok(())
+}
+
+fn warn_lossy_encoding(editor: &mut Editor) {
+    let doc = doc!(editor);
+    if doc.is_encoding_lossy() {
+        let warning = format!(
+            "{} can't represent all of the text, writing it replaces some characters",
+            doc.encoding().name()
+        );
+        editor.set_warning(warning);
+    }
+}
+
+/// Reads the file again, decoding it with the given encoding.
+fn reinterpret_encoding(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let scrolloff = cx.editor.config().scrolloff;
+    let (view, doc) = current!(cx.editor);
+    ensure!(doc.path().is_some(), "the buffer has no file to read");
+    ensure!(
+        !doc.is_modified(),
+        "the buffer has unsaved changes, write or reload them first"
+    );
+    let encoding = doc.encoding();
+    doc.set_encoding(&args[0])?;
+    if let Err(err) = doc.reload(view, &cx.editor.diff_providers) {
+        doc.set_encoding(encoding.name())?;
+        return Err(err);
+    }
+    view.ensure_cursor_in_view(doc, scrolloff);
+
+    let doc = doc!(cx.editor);
+    if doc.is_encoding_lossy() {
+        let warning = format!(
+            "'{}' has bytes which are invalid in {}, writing it replaces them",
+            doc.display_name(),
+            doc.encoding().name()
+        );
+        cx.editor.set_warning(warning);
+    }
+    Ok(())
+}
+
+/// Keeps the text and writes it with the given encoding from now on.
+fn convert_encoding(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    doc_mut!(cx.editor).set_encoding(&args[0])?;
+    warn_lossy_encoding(cx.editor);
+    Ok(())
 }
 
 /// Shows info about the character under the primary cursor.
@@ -1452,12 +1844,18 @@ fn reload_all(cx: &mut compositor::Context, _args: Args, event: PromptEvent) ->
         return Ok(());
     }
 
-    let scrolloff = cx.editor.config().scrolloff;
-    let view_id = view!(cx.editor).id;
+    reload_documents(cx.editor, |_| true);
+    Ok(())
+}
 
-    let docs_view_ids: Vec<(DocumentId, Vec<ViewId>)> = cx
-        .editor
+/// Reloads the documents for which `filter` returns true from disk.
+pub(crate) fn reload_documents(editor: &mut Editor, filter: impl Fn(&Document) -> bool) {
+    let scrolloff = editor.config().scrolloff;
+    let view_id = view!(editor).id;
+
+    let docs_view_ids: Vec<(DocumentId, Vec<ViewId>)> = editor
         .documents_mut()
+        .filter(|doc| filter(doc))
         .map(|doc| {
             let mut view_ids: Vec<_> = doc.selections().keys().cloned().collect();
 
@@ -1471,34 +1869,67 @@ fn reload_all(cx: &mut compositor::Context, _args: Args, event: PromptEvent) ->
         .collect();
 
     for (doc_id, view_ids) in docs_view_ids {
-        let doc = doc_mut!(cx.editor, &doc_id);
+        let doc = doc_mut!(editor, &doc_id);
 
         // Every doc is guaranteed to have at least 1 view at this point.
-        let view = view_mut!(cx.editor, view_ids[0]);
+        let view = view_mut!(editor, view_ids[0]);
 
         // Ensure that the view is synced with the document's history.
         view.sync_changes(doc);
 
-        if let Err(error) = doc.reload(view, &cx.editor.diff_providers) {
-            cx.editor.set_error(format!("{}", error));
+        if let Err(error) = doc.reload(view, &editor.diff_providers) {
+            editor.set_error(format!("{}", error));
             continue;
         }
 
         if let Some(path) = doc.path() {
-            cx.editor
+            editor
                 .language_servers
                 .file_event_handler
                 .file_changed(path.clone());
         }
 
         for view_id in view_ids {
-            let view = view_mut!(cx.editor, view_id);
+            let view = view_mut!(editor, view_id);
             if view.doc.eq(&doc_id) {
                 view.ensure_cursor_in_view(doc, scrolloff);
             }
         }
     }
+}
+
+fn recover(cx: &mut compositor::Context, _args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let leftovers = crate::recovery::leftovers();
+    ensure!(!leftovers.is_empty(), "No documents to recover");
+    let callback = async move {
+        let call: job::Callback = job::Callback::EditorCompositor(Box::new(
+            move |_editor: &mut Editor, compositor: &mut Compositor| {
+                let picker = crate::recovery::picker(leftovers);
+                compositor.push(Box::new(overlaid(picker)));
+            },
+        ));
+        Ok(call)
+    };
+    cx.jobs.callback(callback);
+    Ok(())
+}
+
+fn recover_discard(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
 
+    let count = crate::recovery::discard_leftovers();
+    cx.editor
+        .set_status(format!("Discarded {count} recovery snapshot(s)"));
     Ok(())
 }
 
@@ -1516,6 +1947,7 @@ fn update(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyho
             WriteOptions {
                 force: false,
                 auto_format: !args.has_flag(WRITE_NO_FORMAT_FLAG.name),
+                privileged: None,
             },
         )
     } else {
@@ -1623,33 +2055,78 @@ fn lsp_workspace_command(
     Ok(())
 }
 
-fn lsp_restart(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+fn expand_macro(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
     }
 
-    let editor_config = cx.editor.config.load();
-    let doc = doc!(cx.editor);
-    let config = doc
-        .language_config()
-        .context("LSP not defined for the current document")?;
+    super::lsp::expand_macro(cx.editor, cx.jobs)
+}
 
-    let language_servers: Vec<_> = config
-        .language_servers
-        .iter()
-        .map(|ls| ls.name.as_str())
-        .collect();
-    let language_servers = if args.is_empty() {
-        language_servers
-    } else {
-        let (valid, invalid): (Vec<_>, Vec<_>) = args
-            .iter()
-            .map(|arg| arg.as_ref())
-            .partition(|name| language_servers.contains(name));
-        if !invalid.is_empty() {
-            let s = if invalid.len() == 1 { "" } else { "s" };
-            bail!("Unknown language server{s}: {}", invalid.join(", "));
-        }
+fn open_cargo_toml(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    super::lsp::open_cargo_toml(cx.editor, cx.jobs)
+}
+
+fn view_hir(cx: &mut compositor::Context, _args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    super::lsp::view_hir(cx.editor, cx.jobs)
+}
+
+fn organize_imports(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    super::lsp::organize_imports(cx.editor, cx.jobs);
+    Ok(())
+}
+
+fn lsp_restart(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let editor_config = cx.editor.config.load();
+    let doc = doc!(cx.editor);
+    let config = doc
+        .language_config()
+        .context("LSP not defined for the current document")?;
+
+    let language_servers: Vec<_> = config
+        .language_servers
+        .iter()
+        .map(|ls| ls.name.as_str())
+        .collect();
+    let language_servers = if args.is_empty() {
+        language_servers
+    } else {
+        let (valid, invalid): (Vec<_>, Vec<_>) = args
+            .iter()
+            .map(|arg| arg.as_ref())
+            .partition(|name| language_servers.contains(name));
+        if !invalid.is_empty() {
+            let s = if invalid.len() == 1 { "" } else { "s" };
+            bail!("Unknown language server{s}: {}", invalid.join(", "));
+        }
         valid
     };
 
@@ -1747,6 +2224,64 @@ fn lsp_stop(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> any
     Ok(())
 }
 
+fn lsp_status(cx: &mut compositor::Context, _args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    use std::fmt::Write as _;
+    let mut contents = String::new();
+    for client in cx.editor.language_servers.iter_clients() {
+        let state = if client.is_initialized() {
+            "running"
+        } else {
+            "initializing"
+        };
+        writeln!(contents, "**{}** ({state})\n", client.name())?;
+        let progress = cx
+            .editor
+            .lsp_progress
+            .progress_map(client.id())
+            .into_iter()
+            .flat_map(|progress| progress.values())
+            .filter_map(|status| status.describe());
+        for description in progress {
+            writeln!(contents, "- progress: {description}")?;
+        }
+        writeln!(
+            contents,
+            "- pending requests: {}",
+            client.pending_requests()
+        )?;
+        let errors = client.recent_errors();
+        if !errors.is_empty() {
+            writeln!(contents, "- recent errors:")?;
+            for error in errors {
+                writeln!(contents, "  - `{error}`")?;
+            }
+        }
+        contents.push('\n');
+    }
+    if contents.is_empty() {
+        bail!("No language servers are running");
+    }
+    contents.push_str("Restart a server with `:lsp-restart <name>`.");
+
+    let callback = async move {
+        let call: job::Callback = Callback::EditorCompositor(Box::new(
+            move |editor: &mut Editor, compositor: &mut Compositor| {
+                let contents = ui::Markdown::new(contents, editor.syn_loader.clone());
+                let popup = Popup::new("lsp-status", contents).auto_close(true);
+                compositor.replace_or_push("lsp-status", popup);
+            },
+        ));
+        Ok(call)
+    };
+    cx.jobs.callback(callback);
+
+    Ok(())
+}
+
 fn tree_sitter_scopes(
     cx: &mut compositor::Context,
     _args: Args,
@@ -1946,6 +2481,203 @@ fn hsplit_new(cx: &mut compositor::Context, _args: Args, event: PromptEvent) ->
     Ok(())
 }
 
+/// The layout which is restored when the editor starts in a workspace without any files.
+pub(crate) const DEFAULT_LAYOUT_NAME: &str = "default";
+
+/// Returns the directory storing the window layouts saved for the current workspace.
+pub(crate) fn layout_dir() -> PathBuf {
+    let (workspace, _) = helix_loader::find_workspace();
+    // One directory per workspace, named after its path.
+    let workspace = crate::state::encode_path(&workspace);
+    helix_loader::state_dir().join("layouts").join(workspace)
+}
+
+pub(crate) fn layout_file(name: &str) -> anyhow::Result<PathBuf> {
+    ensure!(
+        !name.is_empty() && !name.contains(['/', '\\']),
+        "Invalid layout name '{name}'"
+    );
+    Ok(layout_dir().join(format!("{name}.json")))
+}
+
+/// Replaces the window layout with the one saved as `name` for the current workspace.
+pub(crate) fn load_layout(editor: &mut Editor, name: &str) -> anyhow::Result<()> {
+    let path = layout_file(name)?;
+    let layout =
+        std::fs::read_to_string(&path).with_context(|| format!("No layout named '{name}'"))?;
+    let layout = serde_json::from_str(&layout)
+        .with_context(|| format!("Invalid layout file {}", path.display()))?;
+
+    let errors = editor.apply_window_layout(&layout);
+    if let Some((path, err)) = errors.first() {
+        bail!(
+            "Failed to open {} file(s) of layout '{name}', first '{}': {err}",
+            errors.len(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+fn layout_save(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let name = args.first().unwrap_or(DEFAULT_LAYOUT_NAME);
+    let path = layout_file(name)?;
+    let layout = serde_json::to_string_pretty(&cx.editor.window_layout())?;
+    std::fs::create_dir_all(layout_dir())?;
+    std::fs::write(&path, layout).with_context(|| format!("Failed to write {}", path.display()))?;
+    cx.editor.set_status(format!("Saved layout '{name}'"));
+
+    Ok(())
+}
+
+fn layout_load(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    load_layout(cx.editor, args.first().unwrap_or(DEFAULT_LAYOUT_NAME))
+}
+
+/// Returns the directory storing the macros saved by `:macro-save`.
+pub(crate) fn macro_dir() -> PathBuf {
+    helix_loader::state_dir().join("macros")
+}
+
+fn macro_file(name: &str) -> anyhow::Result<PathBuf> {
+    ensure!(
+        !name.is_empty() && !name.contains(['/', '\\']),
+        "Invalid macro name '{name}'"
+    );
+    Ok(macro_dir().join(format!("{name}.txt")))
+}
+
+/// Reads the keys of the macro saved as `name`. Line breaks are ignored so that long macros can
+/// be split over lines.
+fn load_macro(name: &str) -> anyhow::Result<Vec<KeyEvent>> {
+    let path = macro_file(name)?;
+    let keys =
+        std::fs::read_to_string(&path).with_context(|| format!("No macro named '{name}'"))?;
+    let keys: String = keys.lines().collect();
+    helix_view::input::parse_macro(&keys).with_context(|| format!("Invalid macro '{name}'"))
+}
+
+fn macro_save(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let name = &args[0];
+    let register = match args.get(1) {
+        Some(register) => {
+            ensure!(register.chars().count() == 1, "Invalid register {register}");
+            register.chars().next().unwrap()
+        }
+        None => '@',
+    };
+    let keys = cx
+        .editor
+        .registers
+        .first(register, cx.editor)
+        .filter(|keys| !keys.is_empty())
+        .with_context(|| format!("Register [{register}] empty"))?
+        .into_owned();
+    helix_view::input::parse_macro(&keys).context("Invalid macro")?;
+
+    let path = macro_file(name)?;
+    std::fs::create_dir_all(macro_dir())?;
+    std::fs::write(&path, keys).with_context(|| format!("Failed to write {}", path.display()))?;
+    cx.editor
+        .set_status(format!("Saved macro '{name}' from register [{register}]"));
+    Ok(())
+}
+
+fn macro_run(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let name = &args[0];
+    let replaying = saved_macro_replay(&cx.editor.macro_replaying, name)?;
+    let keys = load_macro(name)?;
+    // The keys are handled once the command returns, as if they were typed.
+    cx.jobs
+        .callback(async move { Ok(Callback::Macro(replaying, keys)) });
+    Ok(())
+}
+
+/// The macros under replay while the saved macro `name` replays from within the `replaying`
+/// ones. The keys of the macro are handled after the command returns, so the macros which were
+/// replaying when it ran are kept with them to catch macros replaying each other.
+fn saved_macro_replay(
+    replaying: &[ReplayedMacro],
+    name: &str,
+) -> anyhow::Result<Vec<ReplayedMacro>> {
+    let saved = ReplayedMacro::Saved(name.to_string());
+    ensure!(
+        !replaying.contains(&saved),
+        "Cannot replay macro '{name}' because it is already replaying"
+    );
+    let mut replaying = replaying.to_vec();
+    replaying.push(saved);
+    Ok(replaying)
+}
+
+fn macro_edit(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let path = macro_file(&args[0])?;
+    std::fs::create_dir_all(macro_dir())?;
+    cx.editor.open(&path, Action::Replace)?;
+    Ok(())
+}
+
+fn align(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let alignment = if args.has_flag(ALIGN_RIGHT_FLAG.name) {
+        Alignment::Right
+    } else if args.has_flag(ALIGN_CENTER_FLAG.name) {
+        Alignment::Center
+    } else {
+        Alignment::Left
+    };
+    let (view, doc) = current_ref!(cx.editor);
+    let text = doc.text().slice(..);
+    let selection = doc.selection(view.id);
+    let ranges: Vec<_> = match args.first() {
+        // The first match on each line of each selection.
+        Some(pattern) => {
+            let regex = rope::Regex::new(pattern)
+                .map_err(|err| anyhow!("Invalid regex '{pattern}': {err}"))?;
+            selection
+                .iter()
+                .filter_map(|range| {
+                    let range = Selection::single(range.anchor, range.head);
+                    selection::select_on_matches(text, &range, &regex)
+                })
+                .flat_map(|matches| {
+                    let mut last_line = None;
+                    matches.into_iter().filter(move |range| {
+                        let line = text.char_to_line(range.from());
+                        last_line.replace(line) != Some(line)
+                    })
+                })
+                .collect()
+        }
+        None => selection.ranges().to_vec(),
+    };
+    ensure!(!ranges.is_empty(), "No matches in the selections");
+    align_ranges(cx.editor, &ranges, alignment)
+}
+
 fn debug_eval(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
@@ -1962,12 +2694,58 @@ fn debug_eval(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> a
         // TODO: support no frame_id
 
         let frame_id = debugger.stack_frames[&thread_id][frame].id;
-        let response = helix_lsp::block_on(debugger.eval(args.join(" "), Some(frame_id)))?;
+        let expression = args.join(" ");
+        let response = helix_lsp::block_on(debugger.eval(expression.clone(), Some(frame_id)))?;
+        cx.editor.append_output(
+            helix_view::handlers::dap::DEBUG_CONSOLE_NAME,
+            &format!("> {expression}\n{}\n", response.result),
+        );
         cx.editor.set_status(response.result);
     }
     Ok(())
 }
 
+fn debug_console(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    open_output_buffer(cx.editor, helix_view::handlers::dap::DEBUG_CONSOLE_NAME);
+    Ok(())
+}
+
+fn output(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let name = &args[0];
+    ensure!(
+        cx.editor.output_buffers.contains_key(name),
+        "No output named '{name}'"
+    );
+    open_output_buffer(cx.editor, name);
+    Ok(())
+}
+
+/// Focuses a view showing the output buffer called `name`, opening one in a split if needed.
+pub(super) fn open_output_buffer(editor: &mut Editor, name: &str) {
+    let id = editor.output_buffer(name);
+    let view_id = editor
+        .tree
+        .views()
+        .find(|(view, _)| view.doc == id)
+        .map(|(view, _)| view.id);
+    match view_id {
+        Some(view_id) => editor.focus(view_id),
+        None => editor.switch(id, Action::HorizontalSplit),
+    }
+}
+
 fn debug_start(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
@@ -2087,24 +2865,43 @@ fn get_option(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> a
         return Ok(());
     }
 
-    let key = &args[0].to_lowercase();
+    show_option(cx.editor, &args[0])
+}
+
+fn show_option(editor: &mut Editor, key: &str) -> anyhow::Result<()> {
+    let key = &key.to_lowercase();
     let key_error = || anyhow::anyhow!("Unknown key `{}`", key);
 
-    let config = serde_json::json!(cx.editor.config().deref());
+    let config = serde_json::json!(editor.config().deref());
     let pointer = format!("/{}", key.replace('.', "/"));
     let value = config.pointer(&pointer).ok_or_else(key_error)?;
 
-    cx.editor.set_status(value.to_string());
+    editor.set_status(value.to_string());
     Ok(())
 }
 
+/// The key of `:set key?`, which shows the value of the option instead of setting it.
+fn queried_key<'a>(command: &str, args: &'a Args) -> anyhow::Result<&'a str> {
+    args[0].strip_suffix('?').ok_or_else(|| {
+        anyhow!(
+            "Missing value. To show the value of `{key}` use `:{command} {key}?`",
+            key = &args[0]
+        )
+    })
+}
+
 /// Change config at runtime. Access nested values by dot syntax, for
 /// example to disable smart case search, use `:set search.smart-case false`.
+/// With `:set search.smart-case?` the current value is shown instead.
 fn set_option(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
     }
 
+    if args.len() == 1 {
+        return show_option(cx.editor, queried_key("set", &args)?);
+    }
+
     let (key, arg) = (&args[0].to_lowercase(), args[1].trim());
 
     let key_error = || anyhow::anyhow!("Unknown key `{}`", key);
@@ -2120,15 +2917,69 @@ fn set_option(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> a
     } else {
         arg.parse().map_err(field_error)?
     };
+    let status = format!("'{key}' is now set to {value}");
     let config = serde_json::from_value(config).map_err(field_error)?;
 
     cx.editor
         .config_events
         .0
         .send(ConfigEvent::Update(config))?;
+    cx.editor.set_status(status);
     Ok(())
 }
 
+/// Change an option of the current buffer at runtime, taking precedence over its modelines and
+/// EditorConfig, for example `:set-local tab-width 2`. With `:set-local tab-width?` the current
+/// value is shown instead.
+fn set_local_option(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    if args.len() == 1 {
+        let key = queried_key("set-local", &args)?;
+        let doc = doc!(cx.editor);
+        let value = match key {
+            "language" => doc
+                .language_name()
+                .unwrap_or(DEFAULT_LANGUAGE_NAME)
+                .to_string(),
+            "indent" => match doc.indent_style {
+                IndentStyle::Tabs => "tabs".to_string(),
+                IndentStyle::Spaces(n) => n.to_string(),
+            },
+            "tab-width" => doc.tab_width().to_string(),
+            "line-ending" => match doc.line_ending {
+                LineEnding::Crlf => "crlf".to_string(),
+                LineEnding::LF => "lf".to_string(),
+                #[allow(unreachable_patterns)]
+                line_ending => format!("{line_ending:?}"),
+            },
+            "text-width" => doc.text_width().to_string(),
+            "soft-wrap" => doc.soft_wrap().to_string(),
+            _ => bail!("Unknown option `{key}`"),
+        };
+        cx.editor.set_status(value);
+        return Ok(());
+    }
+
+    let (key, value) = (&args[0], &args[1]);
+    if key == "language" {
+        set_language(cx.editor, value)?;
+    }
+    doc_mut!(cx.editor).set_local_option(key, value)?;
+    cx.editor
+        .set_status(format!("'{key}' is now set to {value} for this buffer"));
+    Ok(())
+}
+
+/// Shorter names of the options which `:toggle` is often used for.
+const TOGGLE_SHORTHANDS: &[(&str, &str)] = &[("inlay-hints", "lsp.display-inlay-hints")];
+
 /// Toggle boolean config option at runtime. Access nested values by dot
 /// syntax, for example to toggle smart case search, use `:toggle search.smart-
 /// case`.
@@ -2142,6 +2993,10 @@ fn toggle_option(
     }
 
     let key = &args[0].to_lowercase();
+    let key = TOGGLE_SHORTHANDS
+        .iter()
+        .find(|(shorthand, _)| shorthand == key)
+        .map_or(key.as_str(), |(_, key)| key);
 
     let key_error = || anyhow::anyhow!("Unknown key `{}`", key);
 
@@ -2221,7 +3076,18 @@ fn toggle_option(
         .config_events
         .0
         .send(ConfigEvent::Update(config))?;
-    cx.editor.set_status(status);
+    // The `inlay-hints` of the language overrides the editor's setting.
+    let language_inlay_hints = doc!(cx.editor)
+        .language_config()
+        .filter(|_| key == "lsp.display-inlay-hints")
+        .and_then(|config| Some((&config.language_id, config.inlay_hints?)));
+    match language_inlay_hints {
+        Some((language, enabled)) => cx.editor.set_warning(format!(
+            "{status}, but inlay hints stay {} for {language} which sets `inlay-hints`",
+            if enabled { "enabled" } else { "disabled" }
+        )),
+        None => cx.editor.set_status(status),
+    }
     Ok(())
 }
 
@@ -2238,67 +3104,244 @@ fn language(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> any
         return Ok(());
     }
 
-    let doc = doc_mut!(cx.editor);
+    set_language(cx.editor, &args[0])
+}
 
-    let loader = cx.editor.syn_loader.load();
-    if &args[0] == DEFAULT_LANGUAGE_NAME {
+fn set_language(editor: &mut Editor, language: &str) -> anyhow::Result<()> {
+    let doc = doc_mut!(editor);
+
+    let loader = editor.syn_loader.load();
+    if language == DEFAULT_LANGUAGE_NAME {
         doc.set_language(None, &loader)
     } else {
-        doc.set_language_by_language_id(&args[0], &loader)?;
+        doc.set_language_by_language_id(language, &loader)?;
     }
     doc.detect_indent_and_line_ending();
 
     let id = doc.id();
-    cx.editor.refresh_language_servers(id);
-    let doc = doc_mut!(cx.editor);
-    let diagnostics =
-        Editor::doc_diagnostics(&cx.editor.language_servers, &cx.editor.diagnostics, doc);
+    editor.refresh_language_servers(id);
+    let doc = doc_mut!(editor);
+    let diagnostics = Editor::doc_diagnostics(&editor.language_servers, &editor.diagnostics, doc);
     doc.replace_diagnostics(diagnostics, &[], None);
     Ok(())
 }
 
+/// Replaces the contents of the selections with the fragments returned by `f`, which is given
+/// the contents of the selections in order. If there is a single selection spanning multiple
+/// lines, its lines are used instead of the selections so that they can be sorted like with
+/// `sort` on the command line.
+///
+/// When `f` returns fewer fragments than it was given the remaining selections are emptied, or
+/// the remaining lines removed.
+fn rearrange_selections(
+    cx: &mut compositor::Context,
+    f: impl FnOnce(Vec<Tendril>) -> Vec<Tendril>,
+) -> anyhow::Result<()> {
+    let scrolloff = cx.editor.config().scrolloff;
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let selection = doc.selection(view.id);
+
+    let transaction = match selection.ranges() {
+        [range] => {
+            let (start, end) = range.line_range(text);
+            if start == end {
+                bail!("This command requires multiple selections or lines. Hint: split selection first");
+            }
+            let lines = (start..=end)
+                .map(|line| {
+                    let line = text.line(line);
+                    line.slice(
+                        ..line.len_chars()
+                            - line_ending::get_line_ending(&line).map_or(0, |le| le.len_chars()),
+                    )
+                    .chunks()
+                    .collect()
+                })
+                .collect();
+            let line_ending: &str = doc.line_ending.as_str();
+            let lines = f(lines).join(line_ending);
+            let from = text.line_to_char(start);
+            let to = line_ending::line_end_char_index(&text, end);
+            Transaction::change(doc.text(), [(from, to, Some(lines.into()))].into_iter())
+        }
+        ranges => {
+            let fragments = f(selection
+                .slices(text)
+                .map(|fragment| fragment.chunks().collect())
+                .collect());
+            Transaction::change(
+                doc.text(),
+                ranges
+                    .iter()
+                    .zip(
+                        fragments
+                            .into_iter()
+                            .map(Some)
+                            .chain(std::iter::repeat(None)),
+                    )
+                    .map(|(range, fragment)| (range.from(), range.to(), fragment)),
+            )
+        }
+    };
+
+    doc.apply(&transaction, view.id);
+    doc.append_changes_to_history(view);
+    view.ensure_cursor_in_view(doc, scrolloff);
+
+    Ok(())
+}
+
+/// The first number in the text, like `sort --numeric` and Vim's `:sort n`.
+fn first_number(text: &str) -> Option<f64> {
+    let start = text.find(|ch: char| ch.is_ascii_digit())?;
+    let start = if text[..start].ends_with('-') {
+        start - 1
+    } else {
+        start
+    };
+    let mut end = start + 1;
+    let mut fraction = false;
+    for (i, ch) in text[end..].char_indices() {
+        match ch {
+            '0'..='9' => (),
+            '.' if !fraction => fraction = true,
+            _ => break,
+        }
+        end = start + 1 + i + 1;
+    }
+    text[start..end].trim_end_matches('.').parse().ok()
+}
+
+/// Compares the texts so that numbers within them are ordered by their value, so that `a2`
+/// comes before `a10`.
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek(), b.peek()) {
+            (None, None) => return std::cmp::Ordering::Equal,
+            (None, Some(_)) => return std::cmp::Ordering::Less,
+            (Some(_), None) => return std::cmp::Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut number = String::new();
+                    while let Some(ch) = chars.next_if(char::is_ascii_digit) {
+                        number.push(ch);
+                    }
+                    number
+                };
+                let x = take_number(&mut a);
+                let y = take_number(&mut b);
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                let ordering = x.len().cmp(&y.len()).then_with(|| x.cmp(y));
+                if ordering.is_ne() {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.cmp(y);
+                if ordering.is_ne() {
+                    return ordering;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+/// Removes the fragments which are equal to an earlier one.
+fn dedup(fragments: Vec<Tendril>, insensitive: bool) -> Vec<Tendril> {
+    let mut seen = HashSet::new();
+    fragments
+        .into_iter()
+        .filter(|fragment| {
+            if insensitive {
+                seen.insert(fragment.to_lowercase())
+            } else {
+                seen.insert(fragment.to_string())
+            }
+        })
+        .collect()
+}
+
 fn sort(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
     }
 
-    let scrolloff = cx.editor.config().scrolloff;
-    let (view, doc) = current!(cx.editor);
-    let text = doc.text().slice(..);
+    let insensitive = args.has_flag("insensitive");
+    let numeric = args.has_flag("numeric");
+    let natural = args.has_flag("natural");
+    let reverse = args.has_flag("reverse");
+    let unique = args.has_flag("unique");
 
-    let selection = doc.selection(view.id);
+    rearrange_selections(cx, |mut fragments| {
+        let key = |fragment: &Tendril| {
+            if insensitive {
+                fragment.to_lowercase()
+            } else {
+                fragment.to_string()
+            }
+        };
+        if numeric {
+            // Fragments without a number come first.
+            fragments.sort_by(|a, b| first_number(a).partial_cmp(&first_number(b)).unwrap());
+        } else if natural {
+            fragments.sort_by(|a, b| natural_cmp(&key(a), &key(b)));
+        } else {
+            fragments.sort_by_cached_key(key);
+        }
+        if reverse {
+            fragments.reverse();
+        }
+        if unique {
+            fragments = dedup(fragments, insensitive);
+        }
+        fragments
+    })
+}
 
-    if selection.len() == 1 {
-        bail!("Sorting requires multiple selections. Hint: split selection first");
+fn unique(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
     }
 
-    let mut fragments: Vec<_> = selection
-        .slices(text)
-        .map(|fragment| fragment.chunks().collect())
-        .collect();
+    let insensitive = args.has_flag("insensitive");
+    rearrange_selections(cx, |fragments| dedup(fragments, insensitive))
+}
 
-    fragments.sort_by(
-        match (args.has_flag("insensitive"), args.has_flag("reverse")) {
-            (true, true) => |a: &Tendril, b: &Tendril| b.to_lowercase().cmp(&a.to_lowercase()),
-            (true, false) => |a: &Tendril, b: &Tendril| a.to_lowercase().cmp(&b.to_lowercase()),
-            (false, true) => |a: &Tendril, b: &Tendril| b.cmp(a),
-            (false, false) => |a: &Tendril, b: &Tendril| a.cmp(b),
-        },
-    );
+fn reverse(cx: &mut compositor::Context, _args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
 
-    let transaction = Transaction::change(
-        doc.text(),
-        selection
-            .into_iter()
-            .zip(fragments)
-            .map(|(s, fragment)| (s.from(), s.to(), Some(fragment))),
-    );
+    rearrange_selections(cx, |mut fragments| {
+        fragments.reverse();
+        fragments
+    })
+}
 
-    doc.apply(&transaction, view.id);
-    doc.append_changes_to_history(view);
-    view.ensure_cursor_in_view(doc, scrolloff);
+fn shuffle(cx: &mut compositor::Context, _args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
 
-    Ok(())
+    rearrange_selections(cx, |mut fragments| {
+        use std::hash::{BuildHasher, Hasher};
+
+        // A Fisher-Yates shuffle, with the randomly seeded hasher of the standard library as the
+        // source of randomness.
+        let random = std::collections::hash_map::RandomState::new();
+        for i in (1..fragments.len()).rev() {
+            let mut hasher = random.build_hasher();
+            hasher.write_usize(i);
+            let j = (hasher.finish() % (i as u64 + 1)) as usize;
+            fragments.swap(i, j);
+        }
+        fragments
+    })
 }
 
 fn reflow(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
@@ -2375,6 +3418,78 @@ fn tree_sitter_subtree(
     Ok(())
 }
 
+/// Opens a buffer for writing a tree-sitter query next to the current document, and the syntax
+/// tree of the document below it. The captures of the query are highlighted in the document as
+/// it's edited.
+fn tree_sitter_playground(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    const QUERY_BUFFER_NAME: &str = "[tree-sitter query]";
+
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let doc = doc!(cx.editor);
+    let source = doc.id();
+    if doc.syntax().is_none() {
+        bail!("Syntax information is not available");
+    }
+    let previous = cx.editor.query_playground.take();
+    if previous.is_some_and(|playground| playground.query == source) {
+        cx.editor.query_playground = previous;
+        bail!("The query playground can't query its own query");
+    }
+    if let Some(doc) = previous.and_then(|playground| cx.editor.document_mut(playground.source)) {
+        doc.query_captures.clear();
+    }
+
+    let shown = |editor: &Editor, id| editor.tree.views().any(|(view, _)| view.doc == id);
+    let query = match previous.map(|playground| playground.query) {
+        Some(query) if cx.editor.documents.contains_key(&query) => {
+            if !shown(cx.editor, query) {
+                cx.editor.switch(query, Action::VerticalSplit);
+            }
+            query
+        }
+        _ => {
+            let query = cx.editor.new_file(Action::VerticalSplit);
+            let loader = cx.editor.syn_loader.load();
+            let doc = doc_mut!(cx.editor, &query);
+            doc.set_scratch_name(QUERY_BUFFER_NAME);
+            if let Err(err) = doc.set_language_by_language_id("tsq", &loader) {
+                log::error!("failed to highlight the query playground: {err}");
+            }
+            query
+        }
+    };
+    cx.editor.query_playground = Some(QueryPlayground { source, query });
+    crate::handlers::query_playground::update_query_playground(cx.editor, true, true);
+
+    let query_view = cx
+        .editor
+        .tree
+        .views()
+        .find(|(view, _)| view.doc == query)
+        .map(|(view, _)| view.id);
+    if let Some(query_view) = query_view {
+        let tree = cx.editor.output_buffer(SYNTAX_TREE_OUTPUT_NAME);
+        let loader = cx.editor.syn_loader.load();
+        if let Err(err) = doc_mut!(cx.editor, &tree).set_language_by_language_id("tsq", &loader) {
+            log::error!("failed to highlight the syntax tree: {err}");
+        }
+        if !shown(cx.editor, tree) {
+            cx.editor.focus(query_view);
+            cx.editor.switch(tree, Action::HorizontalSplit);
+        }
+        cx.editor.focus(query_view);
+    }
+
+    Ok(())
+}
+
 fn open_config(
     cx: &mut compositor::Context,
     _args: Args,
@@ -2408,24 +3523,170 @@ fn open_log(cx: &mut compositor::Context, _args: Args, event: PromptEvent) -> an
         return Ok(());
     }
 
-    cx.editor.open(&helix_loader::log_file(), Action::Replace)?;
+    cx.editor.open(&helix_loader::log_file(), Action::Replace)?;
+    Ok(())
+}
+
+fn refresh_config(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    cx.editor.config_events.0.send(ConfigEvent::Refresh)?;
+    Ok(())
+}
+
+fn trust_workspace(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    crate::trust::set_trusted(cx.editor, true)
+}
+
+fn untrust_workspace(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    crate::trust::set_trusted(cx.editor, false)
+}
+
+fn append_output(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    shell(
+        cx,
+        &args.join(" "),
+        &ShellBehavior::Append,
+        shell_options(&args),
+    );
+    Ok(())
+}
+
+fn insert_output(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    shell(
+        cx,
+        &args.join(" "),
+        &ShellBehavior::Insert,
+        shell_options(&args),
+    );
+    Ok(())
+}
+
+fn pipe_to(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    pipe_impl(cx, args, event, &ShellBehavior::Ignore)
+}
+
+fn pipe(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    pipe_impl(cx, args, event, &ShellBehavior::Replace)
+}
+
+fn pipe_impl(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+    behavior: &ShellBehavior,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    shell(cx, &args.join(" "), behavior, shell_options(&args));
+    Ok(())
+}
+
+fn shell_options(args: &Args) -> ShellOptions {
+    ShellOptions {
+        pty: args.has_flag("pty"),
+        keep_ansi: args.has_flag("keep-ansi"),
+    }
+}
+
+fn run_shell_command(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let shell = cx.editor.config().shell.clone();
+    let args = args.join(" ");
+
+    let callback = async move {
+        let output = shell_impl_async(&shell, &args, None).await?;
+        let call: job::Callback = Callback::EditorCompositor(Box::new(
+            move |editor: &mut Editor, compositor: &mut Compositor| {
+                if !output.is_empty() {
+                    let contents = ui::Markdown::new(
+                        format!("```sh\n{}\n```", output.trim_end()),
+                        editor.syn_loader.clone(),
+                    );
+                    let popup = Popup::new("shell", contents).position(Some(
+                        helix_core::Position::new(editor.cursor().0.unwrap_or_default().row, 2),
+                    ));
+                    compositor.replace_or_push("shell", popup);
+                }
+                editor.set_status("Command run");
+            },
+        ));
+        Ok(call)
+    };
+    cx.jobs.callback(callback);
+
     Ok(())
 }
 
-fn refresh_config(
+fn run_shell_command_interactive(
     cx: &mut compositor::Context,
-    _args: Args,
+    args: Args,
     event: PromptEvent,
 ) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
     }
 
-    cx.editor.config_events.0.send(ConfigEvent::Refresh)?;
+    let shell = cx.editor.config().shell.clone();
+    ensure!(!shell.is_empty(), "No shell set");
+    let command = args.join(" ");
+
+    let callback = Callback::Suspended(Box::new(move |editor: &mut Editor| {
+        run_in_terminal(editor, &shell, &command, None, None)
+    }));
+    cx.jobs.callback(async move { Ok(callback) });
+
     Ok(())
 }
 
-fn append_output(
+fn terminal_pipe(
     cx: &mut compositor::Context,
     args: Args,
     event: PromptEvent,
@@ -2434,72 +3695,330 @@ fn append_output(
         return Ok(());
     }
 
-    shell(cx, &args.join(" "), &ShellBehavior::Append);
+    let shell = cx.editor.config().shell.clone();
+    ensure!(!shell.is_empty(), "No shell set");
+    let command = args.join(" ");
+
+    let (view, doc) = current_ref!(cx.editor);
+    let range = doc.selection(view.id).primary();
+    let input = if range.len() > 1 {
+        range.slice(doc.text().slice(..)).into()
+    } else {
+        doc.text().clone()
+    };
+
+    let callback = Callback::Suspended(Box::new(move |editor: &mut Editor| {
+        run_in_terminal(editor, &shell, &command, None, Some(input))
+    }));
+    cx.jobs.callback(async move { Ok(callback) });
+
     Ok(())
 }
 
-fn insert_output(
-    cx: &mut compositor::Context,
-    args: Args,
-    event: PromptEvent,
-) -> anyhow::Result<()> {
+fn tool(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
     }
 
-    shell(cx, &args.join(" "), &ShellBehavior::Insert);
-    Ok(())
-}
+    let config = cx.editor.config();
+    let name = args.first().unwrap_or("lazygit");
+    let command = config
+        .tools
+        .get(name)
+        .with_context(|| format!("No tool named '{name}'"))?
+        .clone();
+    let shell = config.shell.clone();
+    ensure!(!shell.is_empty(), "No shell set");
 
-fn pipe_to(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
-    pipe_impl(cx, args, event, &ShellBehavior::Ignore)
-}
+    let callback = Callback::Suspended(Box::new(move |editor: &mut Editor| {
+        let (workspace, _) = helix_loader::find_workspace();
+        run_in_terminal(editor, &shell, &command, Some(&workspace), None);
+        // Pick up the changes the tool made to files (e.g. checking out a branch).
+        reload_documents(editor, |doc| !doc.is_modified() && doc.is_changed_on_disk());
+    }));
+    cx.jobs.callback(async move { Ok(callback) });
 
-fn pipe(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
-    pipe_impl(cx, args, event, &ShellBehavior::Replace)
+    Ok(())
 }
 
-fn pipe_impl(
+fn shell_profile(
     cx: &mut compositor::Context,
     args: Args,
     event: PromptEvent,
-    behavior: &ShellBehavior,
 ) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
     }
 
-    shell(cx, &args.join(" "), behavior);
-    Ok(())
+    let name = match args.first() {
+        Some(name) => name.to_string(),
+        None => cx
+            .editor
+            .config()
+            .interactive
+            .default_profile
+            .clone()
+            .context(
+                "No shell profile given and `editor.interactive.default-profile` is not set",
+            )?,
+    };
+    start_shell_profile(cx.jobs, cx.editor, &name)
 }
 
-fn run_shell_command(
-    cx: &mut compositor::Context,
-    args: Args,
-    event: PromptEvent,
+/// Starts the shell profile `name` from `editor.interactive.profiles` in the editor's terminal.
+pub(crate) fn start_shell_profile(
+    jobs: &mut Jobs,
+    editor: &Editor,
+    name: &str,
 ) -> anyhow::Result<()> {
+    let profile = editor
+        .config()
+        .interactive
+        .profiles
+        .get(name)
+        .with_context(|| format!("No shell profile named '{name}'"))?
+        .clone();
+
+    let callback = Callback::Suspended(Box::new(move |editor: &mut Editor| {
+        let mut process = std::process::Command::new(&profile.command);
+        process.args(&profile.args);
+        run_process_in_terminal(editor, process, &profile.command, None, None, &profile.env);
+    }));
+    jobs.callback(async move { Ok(callback) });
+
+    Ok(())
+}
+
+/// Runs `command` in the editor's terminal, which must have been released by a
+/// [Callback::Suspended], optionally in the directory `cwd` and writing `input` to its stdin.
+///
+/// The command inherits the terminal so that it can be interactive and detect color support.
+/// Its environment, default working directory and whether its output stays visible until
+/// dismissed are configured in `editor.interactive`.
+pub(super) fn run_in_terminal(
+    editor: &mut Editor,
+    shell: &[String],
+    command: &str,
+    cwd: Option<&Path>,
+    input: Option<Rope>,
+) {
+    let mut process = std::process::Command::new(&shell[0]);
+    process.args(&shell[1..]).arg(command);
+    run_process_in_terminal(editor, process, command, cwd, input, &BTreeMap::new());
+}
+
+/// Like [run_in_terminal] but runs an arbitrary `process`, described as `command` in messages,
+/// with `env` set on top of the configured environment.
+fn run_process_in_terminal(
+    editor: &mut Editor,
+    mut process: std::process::Command,
+    command: &str,
+    cwd: Option<&Path>,
+    input: Option<Rope>,
+    env: &BTreeMap<String, String>,
+) {
+    use std::{io::Write, process::Stdio};
+
+    let config = editor.config();
+    let cwd = cwd.map(Path::to_path_buf).or_else(|| {
+        let (workspace, _) = helix_loader::find_workspace();
+        config
+            .interactive
+            .cwd
+            .as_ref()
+            .map(|cwd| workspace.join(cwd))
+    });
+
+    if let Some(cwd) = &cwd {
+        process.current_dir(cwd);
+    }
+    if let Some(hook) = &config.interactive.env_hook {
+        match hook_env(&config.shell, hook, cwd.as_deref()) {
+            Ok(env) => {
+                for (key, value) in env {
+                    match value {
+                        Some(value) => process.env(key, value),
+                        None => process.env_remove(key),
+                    };
+                }
+            }
+            Err(err) => println!("[env-hook '{hook}' failed: {err}]"),
+        }
+    }
+    process.envs(&config.interactive.env).envs(env);
+    if input.is_some() {
+        process.stdin(Stdio::piped());
+    }
+
+    loop {
+        let status = process.spawn().and_then(|mut child| {
+            if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input.clone()) {
+                // Write from another thread so that a command which starts printing before it
+                // consumed all of its input can't deadlock. Dropping `stdin` sends EOF.
+                std::thread::spawn(move || {
+                    for chunk in input.chunks() {
+                        if stdin.write_all(chunk.as_bytes()).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            child.wait()
+        });
+
+        let status = match status {
+            Ok(status) => status,
+            Err(err) => {
+                editor.set_error(format!("Failed to run '{command}': {err}"));
+                return;
+            }
+        };
+        let description = status
+            .code()
+            .map_or_else(|| "a signal".to_string(), |code| format!("status {code}"));
+        let keep = match config.interactive.on_exit {
+            InteractiveOnExit::Close => false,
+            InteractiveOnExit::Keep => true,
+            InteractiveOnExit::KeepOnError => !status.success(),
+        };
+        if keep {
+            print!(
+                "\n[process exited with {description}, \
+                 type r and press ENTER to restart or press ENTER to return to the editor]"
+            );
+            let _ = std::io::stdout().flush();
+            let mut answer = String::new();
+            let _ = std::io::stdin().read_line(&mut answer);
+            if answer.trim() == "r" {
+                // The previous output stays on screen above the marker.
+                println!("\n--- restarting '{command}' ---");
+                continue;
+            }
+        }
+
+        if status.success() {
+            editor.set_status(format!("'{command}' finished"));
+        } else {
+            editor.set_error(format!("'{command}' failed with {description}"));
+        }
+        return;
+    }
+}
+
+/// Runs an `env-hook` and returns the environment variables it printed, with `None` for
+/// variables which should be unset.
+fn hook_env(
+    shell: &[String],
+    hook: &str,
+    cwd: Option<&Path>,
+) -> anyhow::Result<HashMap<String, Option<String>>> {
+    ensure!(!shell.is_empty(), "No shell set");
+    let mut process = std::process::Command::new(&shell[0]);
+    process
+        .args(&shell[1..])
+        .arg(hook)
+        .stdin(std::process::Stdio::null());
+    if let Some(cwd) = cwd {
+        process.current_dir(cwd);
+    }
+    let output = process.output()?;
+    ensure!(output.status.success(), "{}", output.status);
+    if output.stdout.iter().all(u8::is_ascii_whitespace) {
+        return Ok(HashMap::new());
+    }
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+/// The name of the output buffer collecting the output of `:make`.
+const MAKE_OUTPUT_NAME: &str = "[make]";
+
+fn make(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    use helix_core::{
+        diagnostic::Severity,
+        error_format::{self, ErrorFormat},
+        Uri,
+    };
+    use helix_lsp::lsp;
+    use std::{collections::BTreeMap, process::Stdio};
+
     if event != PromptEvent::Validate {
         return Ok(());
     }
 
-    let shell = cx.editor.config().shell.clone();
-    let args = args.join(" ");
+    let config = cx.editor.config();
+    let command = match args.first() {
+        Some(task) => config
+            .make
+            .tasks
+            .get(task)
+            .with_context(|| format!("No make task named '{task}'"))?
+            .clone(),
+        None => config.make.command.clone(),
+    };
+    let shell = config.shell.clone();
+    ensure!(!shell.is_empty(), "No shell set");
+    let error_formats = doc!(cx.editor)
+        .language_config()
+        .map(|config| config.error_format.clone())
+        .filter(|formats| !formats.is_empty())
+        .unwrap_or_else(ErrorFormat::defaults);
+    let cwd = helix_stdx::env::current_working_dir();
+
+    cx.editor.set_status(format!("Running '{command}'"));
 
     let callback = async move {
-        let output = shell_impl_async(&shell, &args, None).await?;
+        let output = tokio::process::Command::new(&shell[0])
+            .args(&shell[1..])
+            .arg(&command)
+            .current_dir(&cwd)
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        let log = format!("$ {command}\n{text}");
+
+        let mut diagnostics: BTreeMap<Uri, Vec<lsp::Diagnostic>> = BTreeMap::new();
+        for error in error_format::parse_output(&error_formats, &text) {
+            let path = helix_stdx::path::normalize(cwd.join(&error.path));
+            let position = lsp::Position::new(
+                error.line.saturating_sub(1) as u32,
+                error.column.unwrap_or(1).saturating_sub(1) as u32,
+            );
+            let severity = error.severity.map(|severity| match severity {
+                Severity::Hint => lsp::DiagnosticSeverity::HINT,
+                Severity::Info => lsp::DiagnosticSeverity::INFORMATION,
+                Severity::Warning => lsp::DiagnosticSeverity::WARNING,
+                Severity::Error => lsp::DiagnosticSeverity::ERROR,
+            });
+            diagnostics
+                .entry(Uri::from(path))
+                .or_default()
+                .push(lsp::Diagnostic {
+                    range: lsp::Range::new(position, position),
+                    severity,
+                    source: Some("make".to_string()),
+                    message: error.message,
+                    ..Default::default()
+                });
+        }
+        let count: usize = diagnostics.values().map(Vec::len).sum();
+
         let call: job::Callback = Callback::EditorCompositor(Box::new(
             move |editor: &mut Editor, compositor: &mut Compositor| {
-                if !output.is_empty() {
-                    let contents = ui::Markdown::new(
-                        format!("```sh\n{}\n```", output.trim_end()),
-                        editor.syn_loader.clone(),
-                    );
-                    let popup = Popup::new("shell", contents).position(Some(
-                        helix_core::Position::new(editor.cursor().0.unwrap_or_default().row, 2),
-                    ));
-                    compositor.replace_or_push("shell", popup);
+                editor.append_output(MAKE_OUTPUT_NAME, &log);
+                editor.set_make_diagnostics(diagnostics);
+                if count > 0 {
+                    make_diagnostics_picker(editor, compositor);
+                }
+                match output.status.code() {
+                    Some(0) => editor
+                        .set_status(format!("'{command}' finished with {count} diagnostic(s)")),
+                    Some(code) => editor.set_error(format!(
+                        "'{command}' failed with status {code} and {count} diagnostic(s)"
+                    )),
+                    None => editor.set_error(format!("'{command}' was terminated")),
                 }
-                editor.set_status("Command run");
             },
         ));
         Ok(call)
@@ -2676,6 +4195,77 @@ fn yank_diagnostic(
     Ok(())
 }
 
+fn hide_diagnostics(
+    cx: &mut compositor::Context,
+    args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    use helix_core::diagnostic::{NumberOrString, Severity};
+    use helix_view::editor::HiddenDiagnostic;
+
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let severity = match args.get_flag("severity") {
+        Some("hint") => Some(Severity::Hint),
+        Some("info") => Some(Severity::Info),
+        Some("warning") => Some(Severity::Warning),
+        Some("error") => Some(Severity::Error),
+        Some(severity) => bail!("Unknown severity `{severity}`"),
+        None => None,
+    };
+    let mut rule = HiddenDiagnostic {
+        source: args.get_flag("source").map(String::from),
+        code: args.get_flag("code").map(String::from),
+        severity,
+    };
+
+    // Without flags hide the kind of diagnostic under the primary selection.
+    if rule.source.is_none() && rule.code.is_none() && rule.severity.is_none() {
+        let (view, doc) = current_ref!(cx.editor);
+        let primary = doc.selection(view.id).primary();
+        let diagnostic = doc
+            .diagnostics()
+            .iter()
+            .find(|d| primary.overlaps(&helix_core::Range::new(d.range.start, d.range.end)))
+            .ok_or_else(|| anyhow!("No diagnostics under primary selection"))?;
+        let code = match &diagnostic.code {
+            Some(NumberOrString::Number(code)) => code.to_string(),
+            Some(NumberOrString::String(code)) => code.clone(),
+            None => bail!("The diagnostic under the primary selection has no code"),
+        };
+        rule.source = diagnostic.source.clone();
+        rule.code = Some(code);
+    }
+
+    let mut config = cx.editor.config().deref().clone();
+    config.hidden_diagnostics.push(rule);
+    cx.editor
+        .config_events
+        .0
+        .send(ConfigEvent::Update(Box::new(config)))?;
+    Ok(())
+}
+
+fn show_diagnostics(
+    cx: &mut compositor::Context,
+    _args: Args,
+    event: PromptEvent,
+) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
+    }
+
+    let mut config = cx.editor.config().deref().clone();
+    config.hidden_diagnostics.clear();
+    cx.editor
+        .config_events
+        .0
+        .send(ConfigEvent::Update(Box::new(config)))?;
+    Ok(())
+}
+
 fn read(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
@@ -2750,12 +4340,61 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
     ..Signature::DEFAULT
 };
 
+/// Like [SHELL_SIGNATURE], but accepts flags controlling how the command is run before the
+/// command itself.
+const SHELL_OUTPUT_SIGNATURE: Signature = Signature {
+    positionals: (1, Some(2)),
+    raw_after: Some(1),
+    flags: &[
+        Flag {
+            name: "pty",
+            alias: Some('p'),
+            doc: "run the command in a pseudo-terminal, as if it was run interactively",
+            ..Flag::DEFAULT
+        },
+        Flag {
+            name: "keep-ansi",
+            doc: "keep ANSI escape sequences (colors, …) in the output of a --pty command",
+            ..Flag::DEFAULT
+        },
+    ],
+    ..Signature::DEFAULT
+};
+
 pub const SHELL_COMPLETER: CommandCompleter = CommandCompleter::positional(&[
     // Command name
     completers::program,
     // Shell argument(s)
     completers::repeating_filenames,
-]);
+])
+.with_shell_history();
+
+const OPEN_BINARY_FLAG: Flag = Flag {
+    name: "binary",
+    alias: Some('b'),
+    doc: "open the file as a hex dump of its bytes",
+    ..Flag::DEFAULT
+};
+
+const ALIGN_RIGHT_FLAG: Flag = Flag {
+    name: "right",
+    alias: Some('r'),
+    doc: "line up the ends of the selections or matches",
+    ..Flag::DEFAULT
+};
+
+const ALIGN_CENTER_FLAG: Flag = Flag {
+    name: "center",
+    alias: Some('c'),
+    doc: "center the selections or matches within the widest of their column",
+    ..Flag::DEFAULT
+};
+
+const WRITE_SUDO_FLAG: Flag = Flag {
+    name: "sudo",
+    doc: "write the file with elevated privileges, see `editor.privileged-write`",
+    ..Flag::DEFAULT
+};
 
 const WRITE_NO_FORMAT_FLAG: Flag = Flag {
     name: "no-format",
@@ -2816,6 +4455,18 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         doc: "Open a file from disk into the current view.",
         fun: open,
         completer: CommandCompleter::all(completers::filename),
+        signature: Signature {
+            positionals: (1, None),
+            flags: &[OPEN_BINARY_FLAG],
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "hex-search",
+        aliases: &[],
+        doc: "Select the next occurrence of a byte sequence given as hex digits, such as 'deadbeef', in a hex dump.",
+        fun: hex_search,
+        completer: CommandCompleter::none(),
         signature: Signature {
             positionals: (1, None),
             ..Signature::DEFAULT
@@ -2903,6 +4554,50 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "buffer-pin",
+        aliases: &[],
+        doc: "Pin the current buffer to the start of the bufferline, or unpin it if it is pinned.",
+        fun: buffer_pin,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "buffer-move-left",
+        aliases: &[],
+        doc: "Move the current buffer one place to the left in the bufferline.",
+        fun: buffer_move_left,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "buffer-move-right",
+        aliases: &[],
+        doc: "Move the current buffer one place to the right in the bufferline.",
+        fun: buffer_move_right,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "buffer-group",
+        aliases: &[],
+        doc: "Put the current buffer into a group of the bufferline, or remove it from its group without a name.",
+        fun: buffer_group,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "write",
         aliases: &["w"],
@@ -2911,7 +4606,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         completer: CommandCompleter::positional(&[completers::filename]),
         signature: Signature {
             positionals: (0, Some(1)),
-            flags: &[WRITE_NO_FORMAT_FLAG],
+            flags: &[WRITE_NO_FORMAT_FLAG, WRITE_SUDO_FLAG],
             ..Signature::DEFAULT
         },
     },
@@ -2923,7 +4618,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         completer: CommandCompleter::positional(&[completers::filename]),
         signature: Signature {
             positionals: (0, Some(1)),
-            flags: &[WRITE_NO_FORMAT_FLAG],
+            flags: &[WRITE_NO_FORMAT_FLAG, WRITE_SUDO_FLAG],
             ..Signature::DEFAULT
         },
     },
@@ -3147,6 +4842,28 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "theme-preview",
+        aliases: &[],
+        doc: "Pick a theme while previewing it, next to a buffer showing the scopes of the theme styled by it.",
+        fun: theme_preview,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "spell-add",
+        aliases: &[],
+        doc: "Add a word to the spell checking dictionary, by default the word under the cursor.",
+        fun: spell_add,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "yank-join",
         aliases: &[],
@@ -3312,6 +5029,28 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "set-encoding",
+        aliases: &[],
+        doc: "Read the file again, decoding it with the given encoding, e.g. when it was detected wrong.",
+        fun: reinterpret_encoding,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "convert-encoding",
+        aliases: &[],
+        doc: "Keep the text and encode it with the given encoding when the file is written.",
+        fun: convert_encoding,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "character-info",
         aliases: &["char"],
@@ -3324,10 +5063,32 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         },
     },
     TypableCommand {
-        name: "reload",
-        aliases: &["rl"],
-        doc: "Discard changes and reload from the source file.",
-        fun: reload,
+        name: "reload",
+        aliases: &["rl"],
+        doc: "Discard changes and reload from the source file.",
+        fun: reload,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "reload-all",
+        aliases: &["rla"],
+        doc: "Discard changes and reload all documents from the source files.",
+        fun: reload_all,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "recover",
+        aliases: &[],
+        doc: "Open a picker of the unsaved documents left behind by a crashed editor to recover them.",
+        fun: recover,
         completer: CommandCompleter::none(),
         signature: Signature {
             positionals: (0, Some(0)),
@@ -3335,10 +5096,10 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         },
     },
     TypableCommand {
-        name: "reload-all",
-        aliases: &["rla"],
-        doc: "Discard changes and reload all documents from the source files.",
-        fun: reload_all,
+        name: "recover-discard",
+        aliases: &[],
+        doc: "Delete the unsaved documents left behind by a crashed editor.",
+        fun: recover_discard,
         completer: CommandCompleter::none(),
         signature: Signature {
             positionals: (0, Some(0)),
@@ -3391,6 +5152,61 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "lsp-status",
+        aliases: &[],
+        doc: "Show the state, progress, pending requests and recent errors of the running language servers.",
+        fun: lsp_status,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "expand-macro",
+        aliases: &[],
+        doc: "Open the recursive expansion of the macro call under the cursor with rust-analyzer.",
+        fun: expand_macro,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "open-cargo-toml",
+        aliases: &[],
+        doc: "Open the Cargo.toml of the crate of the current file with rust-analyzer.",
+        fun: open_cargo_toml,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "view-hir",
+        aliases: &[],
+        doc: "Open the HIR of the function under the cursor with rust-analyzer.",
+        fun: view_hir,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "organize-imports",
+        aliases: &[],
+        doc: "Organize the imports of the current file with its language servers.",
+        fun: organize_imports,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "tree-sitter-scopes",
         aliases: &[],
@@ -3457,6 +5273,28 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "debug-console",
+        aliases: &[],
+        doc: "Open the debug console buffer collecting debugger output and evaluation results.",
+        fun: debug_console,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "output",
+        aliases: &[],
+        doc: "Open a read-only buffer collecting the output of a task, formatter or debugger, e.g. `:output [make]`.",
+        fun: output,
+        completer: CommandCompleter::positional(&[completers::output]),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "vsplit",
         aliases: &["vs"],
@@ -3465,6 +5303,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         completer: CommandCompleter::all(completers::filename),
         signature: Signature {
             positionals: (0, None),
+            flags: &[OPEN_BINARY_FLAG],
             ..Signature::DEFAULT
         },
     },
@@ -3487,6 +5326,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         completer: CommandCompleter::all(completers::filename),
         signature: Signature {
             positionals: (0, None),
+            flags: &[OPEN_BINARY_FLAG],
             ..Signature::DEFAULT
         },
     },
@@ -3501,6 +5341,73 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "layout-save",
+        aliases: &[],
+        doc: "Save the arrangement of the splits and their files under a name (defaults to `default`) for the current workspace. The `default` layout is restored when starting without files.",
+        fun: layout_save,
+        completer: CommandCompleter::positional(&[completers::layout]),
+        signature: Signature {
+            positionals: (0, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "layout-load",
+        aliases: &[],
+        doc: "Replace the splits with a layout saved by `:layout-save` (defaults to `default`).",
+        fun: layout_load,
+        completer: CommandCompleter::positional(&[completers::layout]),
+        signature: Signature {
+            positionals: (0, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "macro-save",
+        aliases: &[],
+        doc: "Save the macro recorded in a register (defaults to `@`) under a name, to replay it with `:macro`.",
+        fun: macro_save,
+        completer: CommandCompleter::positional(&[completers::macro_name, completers::register]),
+        signature: Signature {
+            positionals: (1, Some(2)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "macro",
+        aliases: &[],
+        doc: "Replay a macro saved by `:macro-save` or `:macro-edit`.",
+        fun: macro_run,
+        completer: CommandCompleter::positional(&[completers::macro_name]),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "macro-edit",
+        aliases: &[],
+        doc: "Open the keys of a saved macro for editing, or create a new macro. Writing the buffer saves it.",
+        fun: macro_edit,
+        completer: CommandCompleter::positional(&[completers::macro_name]),
+        signature: Signature {
+            positionals: (1, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "align",
+        aliases: &[],
+        doc: "Line up the selections of the lines like the columns of a table, or the first match of a regex on each line of the selections, such as `:align =`.",
+        fun: align,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(1)),
+            flags: &[ALIGN_RIGHT_FLAG, ALIGN_CENTER_FLAG],
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "tutor",
         aliases: &[],
@@ -3537,20 +5444,32 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
     TypableCommand {
         name: "set-option",
         aliases: &["set"],
-        doc: "Set a config option at runtime.\nFor example to disable smart case search, use `:set search.smart-case false`.",
+        doc: "Set a config option at runtime.\nFor example to disable smart case search, use `:set search.smart-case false`.\nUse `:set search.smart-case?` to show the current value.",
         fun: set_option,
-        // TODO: Add support for completion of the options value(s), when appropriate.
-        completer: CommandCompleter::positional(&[completers::setting]),
+        completer: CommandCompleter::positional(&[completers::setting])
+            .with_values(completers::setting_value),
         signature: Signature {
-            positionals: (2, Some(2)),
+            positionals: (1, Some(2)),
             raw_after: Some(1),
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "set-local",
+        aliases: &["setlocal"],
+        doc: "Set an option of the current buffer at runtime: language, indent, tab-width, line-ending, text-width or soft-wrap.\nFor example to indent with two spaces, use `:set-local indent 2`.\nUse `:set-local indent?` to show the current value.",
+        fun: set_local_option,
+        completer: CommandCompleter::positional(&[completers::local_option])
+            .with_values(completers::local_option_value),
+        signature: Signature {
+            positionals: (1, Some(2)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "toggle-option",
         aliases: &["toggle"],
-        doc: "Toggle a config option at runtime.\nFor example to toggle smart case search, use `:toggle search.smart-case`.",
+        doc: "Toggle a config option at runtime.\nFor example to toggle smart case search, use `:toggle search.smart-case`.\n`:toggle inlay-hints` toggles `lsp.display-inlay-hints`, which languages setting `inlay-hints` ignore.",
         fun: toggle_option,
         completer: CommandCompleter::positional(&[completers::setting]),
         signature: Signature {
@@ -3573,7 +5492,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
     TypableCommand {
         name: "sort",
         aliases: &[],
-        doc: "Sort ranges in selection.",
+        doc: "Sort ranges in selection, or the lines of a single selection.",
         fun: sort,
         completer: CommandCompleter::none(),
         signature: Signature {
@@ -3591,10 +5510,67 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
                     doc: "sort ranges in reverse order",
                     ..Flag::DEFAULT
                 },
+                Flag {
+                    name: "numeric",
+                    alias: Some('n'),
+                    doc: "sort by the first number in each range",
+                    ..Flag::DEFAULT
+                },
+                Flag {
+                    name: "natural",
+                    alias: Some('N'),
+                    doc: "compare the numbers within the ranges by their value",
+                    ..Flag::DEFAULT
+                },
+                Flag {
+                    name: "unique",
+                    alias: Some('u'),
+                    doc: "remove duplicate ranges",
+                    ..Flag::DEFAULT
+                },
             ],
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "unique",
+        aliases: &["uniq"],
+        doc: "Remove the ranges in selection which are equal to an earlier one, or the duplicate lines of a single selection.",
+        fun: unique,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            flags: &[Flag {
+                name: "insensitive",
+                alias: Some('i'),
+                doc: "compare the ranges case-insensitively",
+                ..Flag::DEFAULT
+            }],
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "reverse",
+        aliases: &[],
+        doc: "Reverse the order of the ranges in selection, or the lines of a single selection.",
+        fun: reverse,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "shuffle",
+        aliases: &[],
+        doc: "Shuffle the ranges in selection, or the lines of a single selection.",
+        fun: shuffle,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "reflow",
         aliases: &[],
@@ -3617,6 +5593,17 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "tree-sitter-playground",
+        aliases: &["ts-playground"],
+        doc: "Write a tree-sitter query next to the syntax tree of the current document, highlighting its captures in the document as it's edited.",
+        fun: tree_sitter_playground,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "config-reload",
         aliases: &[],
@@ -3628,6 +5615,28 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "trust-workspace",
+        aliases: &[],
+        doc: "Use the settings of the workspace config which run programs and reload the config.",
+        fun: trust_workspace,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "untrust-workspace",
+        aliases: &[],
+        doc: "Ignore the settings of the workspace config which run programs and reload the config.",
+        fun: untrust_workspace,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "config-open",
         aliases: &[],
@@ -3667,7 +5676,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         doc: "Run shell command, inserting output before each selection.",
         fun: insert_output,
         completer: SHELL_COMPLETER,
-        signature: SHELL_SIGNATURE,
+        signature: SHELL_OUTPUT_SIGNATURE,
     },
     TypableCommand {
         name: "append-output",
@@ -3675,7 +5684,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         doc: "Run shell command, appending output after each selection.",
         fun: append_output,
         completer: SHELL_COMPLETER,
-        signature: SHELL_SIGNATURE,
+        signature: SHELL_OUTPUT_SIGNATURE,
     },
     TypableCommand {
         name: "pipe",
@@ -3683,7 +5692,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         doc: "Pipe each selection to the shell command.",
         fun: pipe,
         completer: SHELL_COMPLETER,
-        signature: SHELL_SIGNATURE,
+        signature: SHELL_OUTPUT_SIGNATURE,
     },
     TypableCommand {
         name: "pipe-to",
@@ -3691,7 +5700,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         doc: "Pipe each selection to the shell command, ignoring output.",
         fun: pipe_to,
         completer: SHELL_COMPLETER,
-        signature: SHELL_SIGNATURE,
+        signature: SHELL_OUTPUT_SIGNATURE,
     },
     TypableCommand {
         name: "run-shell-command",
@@ -3701,6 +5710,55 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
         completer: SHELL_COMPLETER,
         signature: SHELL_SIGNATURE,
     },
+    TypableCommand {
+        name: "run-shell-command!",
+        aliases: &["sh!"],
+        doc: "Run a shell command interactively in the terminal, suspending the editor while it runs.",
+        fun: run_shell_command_interactive,
+        completer: SHELL_COMPLETER,
+        signature: SHELL_SIGNATURE,
+    },
+    TypableCommand {
+        name: "terminal-pipe",
+        aliases: &[],
+        doc: "Pipe the primary selection, or the whole buffer if nothing is selected, to a shell command run interactively in the terminal (e.g. a pager).",
+        fun: terminal_pipe,
+        completer: SHELL_COMPLETER,
+        signature: SHELL_SIGNATURE,
+    },
+    TypableCommand {
+        name: "shell-profile",
+        aliases: &[],
+        doc: "Start an interactive shell from `editor.interactive.profiles`, defaulting to `editor.interactive.default-profile`.",
+        fun: shell_profile,
+        completer: CommandCompleter::positional(&[completers::shell_profile]),
+        signature: Signature {
+            positionals: (0, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "tool",
+        aliases: &[],
+        doc: "Run a full-screen program from `editor.tools` (defaults to lazygit) in the workspace root, reloading the buffers it changed on exit.",
+        fun: tool,
+        completer: CommandCompleter::positional(&[completers::tool]),
+        signature: Signature {
+            positionals: (0, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "make",
+        aliases: &[],
+        doc: "Run the configured build command (or a named task from `editor.make.tasks`) and report its errors as diagnostics.",
+        fun: make,
+        completer: CommandCompleter::positional(&[completers::make_task]),
+        signature: Signature {
+            positionals: (0, Some(1)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "reset-diff-change",
         aliases: &["diffget", "diffg"],
@@ -3756,6 +5814,48 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             ..Signature::DEFAULT
         },
     },
+    TypableCommand {
+        name: "diagnostics-hide",
+        aliases: &[],
+        doc: "Hide the diagnostics matching the given source, code and maximum severity. Without flags the diagnostics with the source and code of the diagnostic under the primary selection are hidden.",
+        fun: hide_diagnostics,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            flags: &[
+                Flag {
+                    name: "source",
+                    doc: "hide the diagnostics reported by this source",
+                    completions: Some(&[]),
+                    ..Flag::DEFAULT
+                },
+                Flag {
+                    name: "code",
+                    doc: "hide the diagnostics with this code",
+                    completions: Some(&[]),
+                    ..Flag::DEFAULT
+                },
+                Flag {
+                    name: "severity",
+                    doc: "hide the diagnostics up to this severity",
+                    completions: Some(&["hint", "info", "warning", "error"]),
+                    ..Flag::DEFAULT
+                },
+            ],
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "diagnostics-show-all",
+        aliases: &[],
+        doc: "Show all diagnostics again, removing the rules of `hidden-diagnostics`.",
+        fun: show_diagnostics,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(0)),
+            ..Signature::DEFAULT
+        },
+    },
     TypableCommand {
         name: "read",
         aliases: &["r"],
@@ -3802,7 +5902,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
             .collect()
     });
 
-fn execute_command_line(
+pub(crate) fn execute_command_line(
     cx: &mut compositor::Context,
     input: &str,
     event: PromptEvent,
@@ -3820,7 +5920,16 @@ fn execute_command_line(
 
     match typed::TYPABLE_COMMAND_MAP.get(command) {
         Some(cmd) => execute_command(cx, cmd, rest, event),
-        None if event == PromptEvent::Validate => Err(anyhow!("no such command: '{command}'")),
+        None if event == PromptEvent::Validate => {
+            ensure!(
+                rest.trim().is_empty(),
+                "'{command}': custom commands take no arguments"
+            );
+            let commands = super::custom_command(cx.editor, command)?;
+            cx.jobs
+                .callback(async move { Ok(Callback::Commands(commands)) });
+            Ok(())
+        }
         None => Ok(()),
     }
 }
@@ -3939,14 +6048,15 @@ fn complete_command_line(editor: &Editor, input: &str) -> Vec<ui::prompt::Comple
     let (command, rest, complete_command) = command_line::split(input);
 
     if complete_command {
-        fuzzy_match(
-            input,
-            TYPABLE_COMMAND_LIST.iter().map(|command| command.name),
-            false,
-        )
-        .into_iter()
-        .map(|(name, _)| (0.., name.into()))
-        .collect()
+        let custom: Vec<_> = editor.config().commands.keys().cloned().collect();
+        let names = TYPABLE_COMMAND_LIST
+            .iter()
+            .map(|command| command.name)
+            .chain(custom.iter().map(String::as_str));
+        fuzzy_match(input, names, false)
+            .into_iter()
+            .map(|(name, _)| (0.., name.to_owned().into()))
+            .collect()
     } else {
         TYPABLE_COMMAND_MAP
             .get(command)
@@ -4016,12 +6126,29 @@ pub fn complete_command_args(
                         .len()
                         .checked_sub(1)
                         .expect("completion state to be positional");
-                    let completer = completer.for_argument_number(n);
+                    let mut completions = Vec::new();
+                    // History entries are whole command lines which replace the raw input as is.
+                    if n == 0 && completer.shell_history && token.kind == TokenKind::Unquoted {
+                        let history = shell_history(editor);
+                        completions.extend(
+                            fuzzy_match(&token.content, history.iter(), false)
+                                .into_iter()
+                                .map(|(entry, _)| {
+                                    ((offset + token.content_start).., entry.clone().into())
+                                }),
+                        );
+                    }
+                    let candidates = match completer.values {
+                        Some(values) if n == 1 => values(editor, &args[0], &token.content),
+                        _ => completer.for_argument_number(n)(editor, &token.content),
+                    };
 
-                    completer(editor, &token.content)
-                        .into_iter()
-                        .map(|(range, span)| quote_completion(&token, range, span, offset))
-                        .collect()
+                    completions.extend(
+                        candidates
+                            .into_iter()
+                            .map(|(range, span)| quote_completion(&token, range, span, offset)),
+                    );
+                    completions
                 }
                 CompletionState::Flag(_) => fuzzy_match(
                     token.content.trim_start_matches('-'),
@@ -4064,6 +6191,33 @@ pub fn complete_command_args(
     }
 }
 
+/// Returns previously run shell commands, most recent first: those entered into the shell
+/// prompts (`|`, `!`, …) and those passed to typable commands like `:sh`.
+fn shell_history(editor: &Editor) -> Vec<String> {
+    let prompts = editor
+        .registers
+        .read('|', editor)
+        .into_iter()
+        .flatten()
+        .map(Cow::into_owned);
+    let commands = editor
+        .registers
+        .read(':', editor)
+        .into_iter()
+        .flatten()
+        .filter_map(|line| {
+            let (command, rest, _) = command_line::split(&line);
+            let cmd = TYPABLE_COMMAND_MAP.get(command)?;
+            (cmd.completer.shell_history && !rest.is_empty()).then(|| rest.to_string())
+        });
+
+    let mut seen = HashSet::new();
+    prompts
+        .chain(commands)
+        .filter(|entry| seen.insert(entry.clone()))
+        .collect()
+}
+
 /// Replace the content and optionally update the range of a positional's completion to account
 /// for quoting.
 ///
@@ -4199,3 +6353,29 @@ fn complete_expansion_kind(content: &str, offset: usize) -> Vec<ui::prompt::Comp
     .map(|(name, _)| (offset.., (*name).into()))
     .collect()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn saved_macros_do_not_recurse() {
+        let saved = |name: &str| ReplayedMacro::Saved(name.to_string());
+
+        let replaying = saved_macro_replay(&[], "a").unwrap();
+        assert_eq!(replaying, [saved("a")]);
+        assert!(saved_macro_replay(&replaying, "a").is_err());
+
+        // Macros replaying each other.
+        let replaying = saved_macro_replay(&replaying, "b").unwrap();
+        assert_eq!(replaying, [saved("a"), saved("b")]);
+        assert!(saved_macro_replay(&replaying, "a").is_err());
+
+        // Replaying from a macro in a register.
+        let replaying = [ReplayedMacro::Register('@')];
+        assert_eq!(
+            saved_macro_replay(&replaying, "@").unwrap(),
+            [ReplayedMacro::Register('@'), saved("@")]
+        );
+    }
+}
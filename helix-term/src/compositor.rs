@@ -202,6 +202,13 @@ pub fn has_component(&self, type_name: &str) -> bool {
             .any(|component| component.type_name() == type_name)
     }
 
+    /// Whether a layer has the id `id`.
+    pub fn contains_id(&self, id: &'static str) -> bool {
+        self.layers
+            .iter()
+            .any(|component| component.id() == Some(id))
+    }
+
     pub fn find<T: 'static>(&mut self) -> Option<&mut T> {
         let type_name = std::any::type_name::<T>();
         self.layers
@@ -1,3 +1,4 @@
+use crate::commands::{typed::TYPABLE_COMMAND_MAP, MappableCommand};
 use crate::keymap;
 use crate::keymap::{merge_keys, KeyTrie};
 use helix_loader::merge_toml_values;
@@ -114,14 +115,46 @@ pub fn load(
             (Err(err), Err(_)) => return Err(err),
         };
 
+        res.check_commands()?;
         Ok(res)
     }
 
+    /// Checks that the typable commands bound to keys are built-in or custom commands.
+    fn check_commands(&self) -> Result<(), ConfigLoadError> {
+        let mut unknown = None;
+        for trie in self.keys.values() {
+            trie.for_each_command(&mut |command| match command {
+                MappableCommand::Typable { name, .. }
+                    if !TYPABLE_COMMAND_MAP.contains_key(name.as_str())
+                        && !self.editor.commands.contains_key(name) =>
+                {
+                    unknown = Some(name.clone());
+                }
+                _ => (),
+            });
+        }
+        match unknown {
+            Some(name) => Err(ConfigLoadError::BadConfig(serde::de::Error::custom(
+                format!("No TypableCommand named ':{name}'"),
+            ))),
+            None => Ok(()),
+        }
+    }
+
     pub fn load_default() -> Result<Config, ConfigLoadError> {
         let global_config =
             fs::read_to_string(helix_loader::config_file()).map_err(ConfigLoadError::Error);
         let local_config = fs::read_to_string(helix_loader::workspace_config_file())
-            .map_err(ConfigLoadError::Error);
+            .map_err(ConfigLoadError::Error)
+            .and_then(|config| {
+                if helix_loader::trust::is_workspace_trusted() {
+                    return Ok(config);
+                }
+                // Settings which run programs are only used from trusted workspaces.
+                let mut config = toml::from_str(&config).map_err(ConfigLoadError::BadConfig)?;
+                crate::trust::strip_config(&mut config);
+                toml::to_string(&config).map_err(|err| ConfigLoadError::Error(IOError::other(err)))
+            });
         Config::load(global_config, local_config)
     }
 }
@@ -184,4 +217,214 @@ fn keys_resolve_to_correct_defaults() {
         let default_keys = Config::default().keys;
         assert_eq!(default_keys, keymap::default());
     }
+
+    #[test]
+    fn parsing_statusline_sections() {
+        use helix_view::editor::{StatusLineElement, StatusLineSection};
+
+        let config = Config::load_test(
+            r#"
+            [editor.statusline]
+            left = ["mode", "spinner"]
+            right = "{?register [{register}]} {position:>8}"
+        "#,
+        );
+        let statusline = &config.editor.statusline;
+        assert_eq!(
+            statusline.left,
+            StatusLineSection::Elements(vec![StatusLineElement::Mode, StatusLineElement::Spinner])
+        );
+        assert!(matches!(statusline.right, StatusLineSection::Format(_)));
+
+        let invalid = "[editor.statusline]\nleft = \"{unknown}\"";
+        assert!(Config::load(Ok(invalid.to_owned()), Err(ConfigLoadError::default())).is_err());
+    }
+
+    #[test]
+    fn binding_custom_commands() {
+        let config = Config::load_test(
+            r#"
+            [keys.normal]
+            C-d = ":deploy"
+            [editor.commands]
+            deploy = [":write", ":sh make deploy FILE=%{buffer_name}"]
+        "#,
+        );
+        assert_eq!(config.editor.commands["deploy"].len(), 2);
+
+        let invalid = "[keys.normal]\nC-d = \":deploy\"";
+        assert!(Config::load(Ok(invalid.to_owned()), Err(ConfigLoadError::default())).is_err());
+    }
+
+    #[test]
+    fn parsing_textobjects() {
+        let config = Config::load_test(
+            r#"
+            [editor.textobjects]
+            P = "prop"
+            k = "test-case"
+        "#,
+        );
+        let textobjects = &config.editor.textobjects;
+        assert_eq!(textobjects[&'P'], "prop");
+        assert_eq!(textobjects[&'k'], "test-case");
+    }
+
+    #[test]
+    fn untrusted_config_runs_no_programs() {
+        use crate::trust::strip_config;
+
+        let untrusted = r#"
+            [editor]
+            shell = ["evil"]
+            make = { command = "evil" }
+            tools = { git = "evil" }
+            external-picker = "evil"
+            documentation-pager = "evil"
+            privileged-write = { command = ["evil"] }
+            clipboard-provider = { custom = { yank = { command = "evil" }, paste = { command = "evil" } } }
+            [editor.terminal]
+            command = "evil"
+            [editor.interactive.profiles.evil]
+            command = "evil"
+            [[editor.hooks]]
+            event = "open"
+            commands = [":sh evil"]
+            [editor.commands]
+            evil = [":sh evil"]
+            [[editor.dashboard.actions]]
+            key = "e"
+            label = "Evil"
+            command = ":sh evil"
+            [keys.normal]
+            j = ":sh evil"
+            k = ":evil"
+            l = "@:pipe evil<ret>"
+        "#;
+        let mut config: toml::Value = toml::from_str(untrusted).unwrap();
+        strip_config(&mut config);
+        let config = Config::load(
+            Ok(toml::to_string(&config).unwrap()),
+            Err(ConfigLoadError::default()),
+        )
+        .unwrap();
+        // Only the dashboard actions which don't run programs are left.
+        let mut expected = Config::default();
+        expected.editor.dashboard.actions.clear();
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn untrusted_settings_are_classified() {
+        // Settings of `[editor]` which can't run programs. A new setting must be added here or
+        // be stripped from untrusted workspaces by `helix_loader::trust`.
+        const SAFE: &[&str] = &[
+            "atomic-save",
+            "auto-completion",
+            "auto-format",
+            "auto-info",
+            "auto-pairs",
+            "auto-reload",
+            "auto-save",
+            "bufferline",
+            "bufferline-group-by",
+            "color-modes",
+            "completion-replace",
+            "completion-timeout",
+            "completion-trigger-len",
+            "continue-comments",
+            "cursor-shape",
+            "cursorcolumn",
+            "cursorline",
+            "default-line-ending",
+            "default-yank-register",
+            "editor-config",
+            "end-of-line-diagnostics",
+            "file-explorer",
+            "file-picker",
+            "gutters",
+            "hidden-diagnostics",
+            "idle-timeout",
+            "indent-guides",
+            "indent-heuristic",
+            "inline-diagnostics",
+            "insert-final-newline",
+            "jump-label-alphabet",
+            "kitty-keyboard-protocol",
+            "large-file",
+            "line-number",
+            "lsp",
+            "middle-click-paste",
+            "minimap",
+            "modeline",
+            "mouse",
+            "mouse-hover",
+            "path-completion",
+            "persistent-jumplist",
+            "persistent-registers",
+            "persistent-undo",
+            "popup-border",
+            "preview-completion-insert",
+            "rainbow-brackets",
+            "recovery",
+            "rulers",
+            "scroll-lines",
+            "scrollbar",
+            "scrolloff",
+            "search",
+            "smart-tab",
+            "smooth-scroll",
+            "soft-wrap",
+            "spell-check",
+            "statusline",
+            "sticky-context",
+            "text-width",
+            "textobjects",
+            "trim-final-newlines",
+            "trim-trailing-whitespace",
+            "true-color",
+            "undercurl",
+            "whitespace",
+            "word-completion",
+            "word-cycles",
+            "workspace-lsp-roots",
+            "yank-history",
+            "zen-mode",
+        ];
+
+        let editor = toml::Value::try_from(helix_view::editor::Config::default()).unwrap();
+        let unclassified: Vec<_> = editor
+            .as_table()
+            .unwrap()
+            .iter()
+            .filter(|(key, value)| {
+                let mut config = toml::Value::try_from(HashMap::from([(
+                    "editor",
+                    HashMap::from([(key.as_str(), (*value).clone())]),
+                )]))
+                .unwrap();
+                let stripped = !crate::trust::strip_config(&mut config).is_empty();
+                stripped == SAFE.contains(&key.as_str())
+            })
+            .map(|(key, _)| key)
+            .collect();
+        assert!(
+            unclassified.is_empty(),
+            "{unclassified:?} must either be stripped from untrusted workspaces or be listed as safe"
+        );
+    }
+
+    #[test]
+    fn default_word_cycles() {
+        use helix_core::increment::word_cycle;
+
+        let cycles = Config::load_test("").editor.word_cycles;
+        let increment = |word, amount| word_cycle(word, amount, &cycles);
+        assert_eq!(increment("May", 1).as_deref(), Some("June"));
+        assert_eq!(increment("May", -1).as_deref(), Some("April"));
+        assert_eq!(increment("Apr", 1).as_deref(), Some("May"));
+        assert_eq!(increment("Jun", 1).as_deref(), Some("Jul"));
+        assert_eq!(increment("Dec", 1).as_deref(), Some("Jan"));
+        assert_eq!(increment("sunday", 1).as_deref(), Some("monday"));
+    }
 }
@@ -1,9 +1,10 @@
 use helix_event::{events, register_event};
-use helix_view::document::Mode;
 use helix_view::events::{
     ConfigDidChange, DiagnosticsDidChange, DocumentDidChange, DocumentDidClose, DocumentDidOpen,
-    DocumentFocusLost, LanguageServerExited, LanguageServerInitialized, SelectionDidChange,
+    DocumentDidSave, DocumentFocusLost, LanguageServerExited, LanguageServerInitialized,
+    SelectionDidChange,
 };
+use helix_view::{document::Mode, Editor};
 
 use crate::commands;
 use crate::keymap::MappableCommand;
@@ -12,6 +13,8 @@
     OnModeSwitch<'a, 'cx> { old_mode: Mode, new_mode: Mode, cx: &'a mut commands::Context<'cx> }
     PostInsertChar<'a, 'cx> { c: char, cx: &'a mut commands::Context<'cx> }
     PostCommand<'a, 'cx> { command: & 'a MappableCommand, cx: &'a mut commands::Context<'cx> }
+    // called after a program run interactively in the terminal exits
+    TerminalProgramDidExit<'a> { editor: &'a mut Editor }
 }
 
 pub fn register() {
@@ -20,6 +23,7 @@ pub fn register() {
     register_event::<PostCommand>();
     register_event::<DocumentDidOpen>();
     register_event::<DocumentDidChange>();
+    register_event::<DocumentDidSave>();
     register_event::<DocumentDidClose>();
     register_event::<DocumentFocusLost>();
     register_event::<SelectionDidChange>();
@@ -27,4 +31,5 @@ pub fn register() {
     register_event::<LanguageServerInitialized>();
     register_event::<LanguageServerExited>();
     register_event::<ConfigDidChange>();
+    register_event::<TerminalProgramDidExit>();
 }
@@ -8,27 +8,46 @@
 use crate::events;
 use crate::handlers::auto_save::AutoSaveHandler;
 use crate::handlers::diagnostics::PullDiagnosticsHandler;
+use crate::handlers::recovery::RecoveryHandler;
 use crate::handlers::signature_help::SignatureHelpHandler;
 
 pub use helix_view::handlers::{word_index, Handlers};
 
 use self::document_colors::DocumentColorsHandler;
+use self::document_highlight::DocumentHighlightHandler;
+use self::query_playground::QueryPlaygroundHandler;
+use self::semantic_tokens::SemanticTokensHandler;
+use self::spelling::SpellCheckHandler;
 
+mod auto_reload;
 mod auto_save;
+mod block_selection;
 pub mod completion;
 pub mod diagnostics;
 mod document_colors;
+mod document_highlight;
+mod hooks;
 mod prompt;
+pub mod query_playground;
+mod recovery;
+pub mod semantic_tokens;
 mod signature_help;
 mod snippet;
+pub mod spelling;
 
 pub fn setup(config: Arc<ArcSwap<Config>>) -> Handlers {
     events::register();
 
+    auto_reload::spawn(config.clone());
     let event_tx = completion::CompletionHandler::new(config).spawn();
     let signature_hints = SignatureHelpHandler::new().spawn();
     let auto_save = AutoSaveHandler::new().spawn();
+    let recovery = RecoveryHandler.spawn();
     let document_colors = DocumentColorsHandler::default().spawn();
+    let document_highlight = DocumentHighlightHandler::default().spawn();
+    let semantic_tokens = SemanticTokensHandler::default().spawn();
+    let spell_check = SpellCheckHandler::default().spawn();
+    let query_playground = QueryPlaygroundHandler::default().spawn();
     let word_index = word_index::Handler::spawn();
     let pull_diagnostics = PullDiagnosticsHandler::default().spawn();
     let pull_all_documents_diagnostics = PullAllDocumentsDiagnosticHandler::default().spawn();
@@ -37,7 +56,12 @@ pub fn setup(config: Arc<ArcSwap<Config>>) -> Handlers {
         completions: helix_view::handlers::completion::CompletionHandler::new(event_tx),
         signature_hints,
         auto_save,
+        recovery,
         document_colors,
+        document_highlight,
+        semantic_tokens,
+        spell_check,
+        query_playground,
         word_index,
         pull_diagnostics,
         pull_all_documents_diagnostics,
@@ -47,9 +71,20 @@ pub fn setup(config: Arc<ArcSwap<Config>>) -> Handlers {
     completion::register_hooks(&handlers);
     signature_help::register_hooks(&handlers);
     auto_save::register_hooks(&handlers);
+    block_selection::register_hooks(&handlers);
+    recovery::register_hooks(&handlers);
     diagnostics::register_hooks(&handlers);
     snippet::register_hooks(&handlers);
     document_colors::register_hooks(&handlers);
+    document_highlight::register_hooks(&handlers);
+    semantic_tokens::register_hooks(&handlers);
+    spelling::register_hooks(&handlers);
+    query_playground::register_hooks(&handlers);
+    hooks::register_hooks(&handlers);
     prompt::register_hooks(&handlers);
+    crate::marks::register_hooks();
+    crate::recent::register_hooks();
+    crate::remote::register_hooks();
+    crate::undo::register_hooks();
     handlers
 }
@@ -0,0 +1,346 @@
+//! Polls the files of open documents for changes by other programs. Unmodified documents are
+//! reloaded and for modified ones a prompt shows the changes and asks whether to reload them.
+//!
+//! The files of the theme are polled as well and the theme is reloaded when they change.
+//!
+//! The poll only runs while one of them is enabled. The files are checked in a blocking task and
+//! only the documents and the theme which changed are reloaded by the editor.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
+
+use arc_swap::ArcSwap;
+use helix_core::{diff::unified_diff, Rope};
+use helix_event::register_hook;
+use helix_view::{editor::AutoReloadConfig, events::ConfigDidChange, Document, DocumentId, Editor};
+use tokio::sync::oneshot;
+
+use crate::{
+    commands::typed::reload_documents,
+    compositor::{self, Compositor},
+    config::Config,
+    job,
+    ui::{self, PromptEvent},
+};
+
+/// The number of lines of the diff shown above the prompt.
+const MAX_DIFF_LINES: usize = 20;
+
+/// Whether the poll is running.
+static POLLING: AtomicBool = AtomicBool::new(false);
+
+/// The name of the theme and the modification times of its files when they were last checked. The
+/// files are only looked up again once the theme or one of its files changes, since finding the
+/// themes it inherits from parses them.
+type ThemeFiles = Option<(String, Vec<(PathBuf, Option<SystemTime>)>)>;
+
+/// A file of an open document as it was when the document was last loaded or saved.
+struct WatchedFile {
+    doc_id: DocumentId,
+    path: PathBuf,
+    saved_time: SystemTime,
+    modified: bool,
+    /// The text of modified documents which are diffed with their files. Diffing large files
+    /// takes too long.
+    text: Option<Rope>,
+}
+
+/// A modified document whose file changed on disk.
+struct Conflict {
+    doc_id: DocumentId,
+    mtime: SystemTime,
+    diff: String,
+}
+
+fn is_enabled(config: &AutoReloadConfig) -> bool {
+    (config.enable || config.theme) && !cfg!(feature = "integration")
+}
+
+/// Starts the poll, and starts it again when a config enabling it is loaded after it stopped.
+pub(super) fn spawn(config: Arc<ArcSwap<Config>>) {
+    if is_enabled(&config.load().editor.auto_reload) {
+        start(config.clone());
+    }
+    register_hook!(move |event: &mut ConfigDidChange<'_>| {
+        if is_enabled(&event.new.auto_reload) {
+            start(config.clone());
+        }
+        Ok(())
+    });
+}
+
+fn start(config: Arc<ArcSwap<Config>>) {
+    if POLLING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    tokio::spawn(async move {
+        // The modification times of the files for which the user was already asked to reload.
+        let mut prompted = HashMap::new();
+        let mut theme_files = None;
+        loop {
+            let auto_reload = config.load().editor.auto_reload.clone();
+            if !is_enabled(&auto_reload) {
+                POLLING.store(false, Ordering::Release);
+                // The poll may have been enabled again before it was marked as stopped.
+                if is_enabled(&config.load().editor.auto_reload)
+                    && !POLLING.swap(true, Ordering::AcqRel)
+                {
+                    continue;
+                }
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(auto_reload.interval.max(100))).await;
+            if auto_reload.theme {
+                poll_theme(&mut theme_files).await;
+            }
+            if auto_reload.enable {
+                poll_documents(&mut prompted).await;
+            }
+        }
+    });
+}
+
+fn mtime(path: &Path) -> Option<SystemTime> {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Runs `f` with the editor and returns its result.
+async fn with_editor<T: Send + 'static>(
+    f: impl FnOnce(&mut Editor, &mut Compositor) -> T + Send + 'static,
+) -> Option<T> {
+    let (tx, rx) = oneshot::channel();
+    job::dispatch(move |editor, compositor| {
+        let _ = tx.send(f(editor, compositor));
+    })
+    .await;
+    rx.await.ok()
+}
+
+async fn poll_theme(theme_files: &mut ThemeFiles) {
+    let theme = with_editor(|editor, _| {
+        // The theme shown while previewing others is restored when the preview ends.
+        editor
+            .last_theme
+            .is_none()
+            .then(|| (editor.theme.name().to_owned(), editor.theme_loader.clone()))
+    })
+    .await;
+    let Some(Some((name, loader))) = theme else {
+        return;
+    };
+
+    let last_files = match theme_files.take() {
+        Some((last_name, files)) if last_name == name => Some(files),
+        _ => None,
+    };
+    let theme_name = name.clone();
+    let checked = tokio::task::spawn_blocking(move || {
+        let files = || -> Vec<_> {
+            loader
+                .paths(&theme_name)
+                .into_iter()
+                .map(|path| {
+                    let mtime = mtime(&path);
+                    (path, mtime)
+                })
+                .collect()
+        };
+        match last_files {
+            Some(files) if files.iter().all(|(path, last)| mtime(path) == *last) => (files, None),
+            // The theme may inherit from other themes now.
+            Some(_) => (files(), Some(loader.load(&theme_name))),
+            None => (files(), None),
+        }
+    })
+    .await;
+    let Ok((files, theme)) = checked else {
+        return;
+    };
+    *theme_files = Some((name.clone(), files));
+    let Some(theme) = theme else {
+        return;
+    };
+
+    job::dispatch(move |editor, _| {
+        // The theme may have been changed while its files were read.
+        if editor.last_theme.is_some() || editor.theme.name() != name {
+            return;
+        }
+        match theme {
+            Ok(theme) => {
+                // Setting an invalid theme fails with an error.
+                editor.clear_status();
+                editor.set_theme(theme);
+                if !editor.is_err() {
+                    editor.set_status(format!("Theme '{name}' changed on disk and was reloaded"));
+                }
+            }
+            Err(err) => editor.set_error(format!("Failed to reload theme '{name}': {err}")),
+        }
+    })
+    .await;
+}
+
+async fn poll_documents(prompted: &mut HashMap<DocumentId, SystemTime>) {
+    let files = with_editor(|editor, _| {
+        // The file changes while a document is written until the save is handled.
+        if editor.write_count > 0 {
+            return Vec::new();
+        }
+        editor
+            .documents()
+            .filter_map(|doc| {
+                Some(WatchedFile {
+                    doc_id: doc.id(),
+                    path: doc.path()?.clone(),
+                    saved_time: doc.last_saved_time(),
+                    modified: doc.is_modified(),
+                    text: (doc.is_modified() && !doc.is_large_file()).then(|| doc.text().clone()),
+                })
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default();
+    if files.is_empty() {
+        return;
+    }
+
+    let last_prompted = prompted.clone();
+    let checked = tokio::task::spawn_blocking(move || {
+        let changed: Vec<_> = files
+            .into_iter()
+            .filter_map(|file| {
+                let mtime = mtime(&file.path).filter(|mtime| *mtime > file.saved_time)?;
+                Some((file, mtime))
+            })
+            .collect();
+        let reloaded: Vec<_> = changed
+            .iter()
+            .filter(|(file, _)| !file.modified)
+            .map(|(file, mtime)| (file.doc_id, *mtime))
+            .collect();
+        // Ask about one document at a time.
+        let conflict = changed
+            .iter()
+            .find(|(file, mtime)| file.modified && last_prompted.get(&file.doc_id) != Some(mtime))
+            .map(|(file, mtime)| Conflict {
+                doc_id: file.doc_id,
+                mtime: *mtime,
+                diff: file
+                    .text
+                    .as_ref()
+                    .map(|text| {
+                        let disk_text = std::fs::read_to_string(&file.path).unwrap_or_default();
+                        diff(text, &disk_text)
+                    })
+                    .unwrap_or_default(),
+            });
+        (reloaded, conflict)
+    })
+    .await;
+    let Ok((reloaded, conflict)) = checked else {
+        return;
+    };
+    if reloaded.is_empty() && conflict.is_none() {
+        return;
+    }
+
+    let shown = with_editor(move |editor, compositor| {
+        reload(editor, &reloaded);
+        let conflict = conflict?;
+        prompt(editor, compositor, &conflict).then_some((conflict.doc_id, conflict.mtime))
+    })
+    .await
+    .flatten();
+    if let Some((doc_id, mtime)) = shown {
+        prompted.insert(doc_id, mtime);
+    }
+}
+
+/// The first [MAX_DIFF_LINES] lines of the diff from `text` to `disk_text`.
+fn diff(text: &Rope, disk_text: &str) -> String {
+    let diff = unified_diff(&text.to_string(), disk_text);
+    let mut diff: Vec<_> = diff.lines().map(str::to_owned).collect();
+    if diff.len() > MAX_DIFF_LINES {
+        let more = diff.len() - MAX_DIFF_LINES;
+        diff.truncate(MAX_DIFF_LINES);
+        diff.push(format!("... {more} more lines"));
+    }
+    diff.join("\n")
+}
+
+/// Reloads the documents which are still unmodified and were not saved since their files were
+/// checked.
+fn reload(editor: &mut Editor, changed: &[(DocumentId, SystemTime)]) {
+    let is_changed = |doc: &Document| {
+        !doc.is_modified()
+            && changed
+                .iter()
+                .any(|(doc_id, mtime)| *doc_id == doc.id() && *mtime > doc.last_saved_time())
+    };
+    if editor.write_count > 0 {
+        return;
+    }
+    let reloaded: Vec<_> = editor
+        .documents()
+        .filter(|doc| is_changed(doc))
+        .map(|doc| doc.display_name().into_owned())
+        .collect();
+    if reloaded.is_empty() {
+        return;
+    }
+    reload_documents(editor, is_changed);
+    match reloaded.as_slice() {
+        [name] => editor.set_status(format!("'{name}' changed on disk and was reloaded")),
+        names => editor.set_status(format!(
+            "{} files changed on disk and were reloaded",
+            names.len()
+        )),
+    }
+}
+
+/// Asks whether to reload the modified document whose file changed. Returns whether the prompt
+/// was shown, which it isn't while another prompt is open.
+fn prompt(editor: &mut Editor, compositor: &mut Compositor, conflict: &Conflict) -> bool {
+    if compositor.find::<ui::Prompt>().is_some() {
+        return false;
+    }
+    let Some(doc) = editor.document(conflict.doc_id) else {
+        return false;
+    };
+    if !doc.is_modified() || conflict.mtime <= doc.last_saved_time() {
+        return false;
+    }
+
+    let diff = conflict.diff.clone();
+    let doc_id = doc.id();
+    let mut prompt = ui::Prompt::new(
+        format!(
+            "'{}' changed on disk, discard the changes in the buffer? (y/n): ",
+            doc.display_name()
+        )
+        .into(),
+        None,
+        ui::completers::none,
+        move |cx: &mut compositor::Context, input: &str, event: PromptEvent| {
+            if event != PromptEvent::Validate || !input.eq_ignore_ascii_case("y") {
+                return;
+            }
+            reload_documents(cx.editor, |doc| doc.id() == doc_id);
+        },
+    );
+    prompt.doc_fn = Box::new(move |_| (!diff.is_empty()).then(|| Cow::Owned(diff.clone())));
+    compositor.push(Box::new(prompt));
+    true
+}
@@ -90,7 +90,8 @@ fn request_auto_save(editor: &mut Editor) {
     let options = commands::WriteAllOptions {
         force: false,
         write_scratch: false,
-        auto_format: false,
+        auto_format: context.editor.config().auto_save.format,
+        auto_save: true,
     };
 
     if let Err(e) = commands::typed::write_all_impl(context, options) {
@@ -102,7 +103,7 @@ pub(super) fn register_hooks(handlers: &Handlers) {
     let tx = handlers.auto_save.clone();
     register_hook!(move |event: &mut DocumentDidChange<'_>| {
         let config = event.doc.config.load();
-        if config.auto_save.after_delay.enable {
+        if config.auto_save.after_delay.enable && event.doc.auto_save_enabled() {
             send_blocking(
                 &tx,
                 AutoSaveEvent::DocumentChanged {
@@ -0,0 +1,49 @@
+use helix_core::{line_ending::line_end_char_index, selection};
+use helix_event::register_hook;
+use helix_view::{current, document::Mode, handlers::Handlers, Editor};
+
+use crate::events::PostCommand;
+
+/// Reshapes the rectangle started by `select_block` after each command so that it spans from its
+/// anchor to the new position of the primary cursor. The rectangle ends when select mode is left,
+/// the document is changed or another view is focused.
+#[allow(deprecated)]
+fn update_block_selection(editor: &mut Editor) {
+    use helix_core::visual_coords_at_pos;
+
+    let Some(mut block) = editor.block_selection else {
+        return;
+    };
+    let mode = editor.mode;
+    let (view, doc) = current!(editor);
+    if mode != Mode::Select || view.id != block.view_id || doc.version() != block.version {
+        editor.block_selection = None;
+        return;
+    }
+
+    let text = doc.text().slice(..);
+    let tab_width = doc.tab_width();
+    let cursor = doc.selection(view.id).primary().cursor(text);
+    let mut head = visual_coords_at_pos(text, cursor, tab_width);
+    // Moving across shorter lines keeps the column of the rectangle.
+    let line_end = line_end_char_index(&text, head.row);
+    if head.row != block.head.row
+        && head.col < block.head.col
+        && visual_coords_at_pos(text, line_end, tab_width).col <= block.head.col
+    {
+        head.col = block.head.col;
+    }
+    doc.set_selection(
+        view.id,
+        selection::select_block(text, block.anchor, head, tab_width),
+    );
+    block.head = head;
+    editor.block_selection = Some(block);
+}
+
+pub(super) fn register_hooks(_handlers: &Handlers) {
+    register_hook!(move |event: &mut PostCommand<'_, '_>| {
+        update_block_selection(event.cx.editor);
+        Ok(())
+    });
+}
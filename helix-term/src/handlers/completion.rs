@@ -1,7 +1,6 @@
 use std::collections::HashMap;
 
 use helix_core::chars::char_is_word;
-use helix_core::completion::CompletionProvider;
 use helix_core::syntax::config::LanguageServerFeature;
 use helix_event::{register_hook, TaskHandle};
 use helix_lsp::lsp;
@@ -14,7 +13,7 @@
 use crate::commands;
 use crate::compositor::Compositor;
 use crate::events::{OnModeSwitch, PostCommand, PostInsertChar};
-use crate::handlers::completion::request::{request_incomplete_completion_list, Trigger};
+use crate::handlers::completion::request::request_incomplete_completion_list;
 use crate::job::dispatch;
 use crate::keymap::MappableCommand;
 use crate::ui::lsp::signature_help::SignatureHelp;
@@ -23,13 +22,17 @@
 use super::Handlers;
 
 pub use item::{CompletionItem, CompletionItems, CompletionResponse, LspCompletionItem};
-pub use request::CompletionHandler;
+pub use provider::register;
+pub(crate) use provider::resolve;
+pub use request::{CompletionHandler, Trigger};
 pub use resolve::ResolveHandler;
 
 mod item;
 mod path;
+mod provider;
 mod request;
 mod resolve;
+mod snippet;
 mod word;
 
 async fn handle_response(
@@ -84,7 +87,7 @@ fn show_completion(
     editor: &mut Editor,
     compositor: &mut Compositor,
     mut items: Vec<CompletionItem>,
-    context: HashMap<CompletionProvider, ResponseContext>,
+    context: HashMap<helix_core::completion::CompletionProvider, ResponseContext>,
     trigger: Trigger,
 ) {
     let (view, doc) = current_ref!(editor);
@@ -242,7 +245,19 @@ fn completion_post_command_hook(
     Ok(())
 }
 
+/// Registers the completion providers, whose completions are requested in this order. Providers
+/// behind feature flags are registered here after the built-in ones, under a `#[cfg(feature)]`,
+/// and own the items of a [helix_core::completion::CompletionProvider::Other] id.
+fn register_providers() {
+    register(request::LanguageServers);
+    register(path::Paths);
+    register(snippet::Snippets);
+    register(word::Words);
+}
+
 pub(super) fn register_hooks(_handlers: &Handlers) {
+    register_providers();
+
     register_hook!(move |event: &mut PostCommand<'_, '_>| completion_post_command_hook(event));
 
     register_hook!(move |event: &mut OnModeSwitch<'_, '_>| {
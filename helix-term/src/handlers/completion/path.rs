@@ -12,9 +12,85 @@
 use helix_view::{document::SavePoint, handlers::completion::ResponseContext, Document};
 use url::Url;
 
+use super::provider::{self, RequestContext};
 use crate::handlers::completion::{item::CompletionResponse, CompletionItem, CompletionItems};
 
-pub(crate) fn path_completion(
+/// The paths of files and directories typed before the cursor.
+pub(super) struct Paths;
+
+impl provider::CompletionProvider for Paths {
+    fn owns(&self, id: CompletionProvider) -> bool {
+        id == CompletionProvider::Path
+    }
+
+    fn request(&self, cx: &mut RequestContext<'_>) {
+        let (view, doc) = current_ref!(cx.editor);
+        let selection = doc.selection(view.id).clone();
+        if let Some(request) =
+            path_completion(selection, doc, cx.handle.clone(), cx.savepoint.clone())
+        {
+            cx.requests.spawn_blocking(request);
+        }
+    }
+}
+
+/// Resolves a relative path typed in a document of `doc_dir`. Paths which don't start with `./` or
+/// `../`, like `src/main.rs`, are relative to the workspace of the document if they aren't found in
+/// `doc_dir`.
+fn relative_path(doc_dir: &Path, path: &Path, ends_with_slash: bool) -> PathBuf {
+    let in_doc_dir = doc_dir.join(path);
+    let dir_exists = |path: &Path| {
+        if ends_with_slash {
+            path.is_dir()
+        } else {
+            path.parent().is_some_and(Path::is_dir)
+        }
+    };
+    if path.starts_with(".") || path.starts_with("..") || dir_exists(&in_doc_dir) {
+        return in_doc_dir;
+    }
+    let in_workspace = helix_loader::find_workspace_in(doc_dir).0.join(path);
+    if dir_exists(&in_workspace) {
+        in_workspace
+    } else {
+        in_doc_dir
+    }
+}
+
+/// The directory of the path typed before the cursor, `matched_path`, and the start of the name
+/// of the file typed in it, if any.
+fn typed_path(matched_path: &str, doc_dir: Option<&Path>) -> Option<(PathBuf, Option<String>)> {
+    let path: Cow<_> = if matched_path.starts_with("file://") {
+        Url::from_str(matched_path)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())?
+            .into()
+    } else {
+        Path::new(matched_path).into()
+    };
+    let path = path::expand(&path);
+    #[cfg(windows)]
+    let ends_with_slash = matches!(matched_path.as_bytes().last(), Some(b'/' | b'\\'));
+    #[cfg(not(windows))]
+    let ends_with_slash = matches!(matched_path.as_bytes().last(), Some(b'/'));
+    let path = match doc_dir {
+        Some(doc_dir) if path.is_relative() => relative_path(doc_dir, &path, ends_with_slash),
+        _ => path.into_owned(),
+    };
+
+    if ends_with_slash {
+        Some((path, None))
+    } else {
+        path.parent().map(|parent_path| {
+            (
+                PathBuf::from(parent_path),
+                path.file_name().and_then(|f| f.to_str().map(String::from)),
+            )
+        })
+    }
+}
+
+fn path_completion(
     selection: Selection,
     doc: &Document,
     handle: TaskHandle,
@@ -30,39 +106,9 @@ pub(crate) fn path_completion(
     let start = text.line_to_char(cur_line).max(cursor.saturating_sub(1000));
     let line_until_cursor = text.slice(start..cursor);
 
-    let (dir_path, typed_file_name) =
-        get_path_suffix(line_until_cursor, false).and_then(|matched_path| {
-            let matched_path = Cow::from(matched_path);
-            let path: Cow<_> = if matched_path.starts_with("file://") {
-                Url::from_str(&matched_path)
-                    .ok()
-                    .and_then(|url| url.to_file_path().ok())?
-                    .into()
-            } else {
-                Path::new(&*matched_path).into()
-            };
-            let path = path::expand(&path);
-            let parent_dir = doc.path().and_then(|dp| dp.parent());
-            let path = match parent_dir {
-                Some(parent_dir) if path.is_relative() => parent_dir.join(&path),
-                _ => path.into_owned(),
-            };
-            #[cfg(windows)]
-            let ends_with_slash = matches!(matched_path.as_bytes().last(), Some(b'/' | b'\\'));
-            #[cfg(not(windows))]
-            let ends_with_slash = matches!(matched_path.as_bytes().last(), Some(b'/'));
-
-            if ends_with_slash {
-                Some((PathBuf::from(path.as_path()), None))
-            } else {
-                path.parent().map(|parent_path| {
-                    (
-                        PathBuf::from(parent_path),
-                        path.file_name().and_then(|f| f.to_str().map(String::from)),
-                    )
-                })
-            }
-        })?;
+    let matched_path = String::from(get_path_suffix(line_until_cursor, false)?);
+    // The path is resolved with the file system when the completions are requested, not here.
+    let doc_dir = doc.path().and_then(|path| path.parent()).map(PathBuf::from);
 
     if handle.is_canceled() {
         return None;
@@ -71,7 +117,13 @@ pub(crate) fn path_completion(
     // TODO: handle properly in the future
     const PRIORITY: i8 = 1;
     let future = move || {
-        let Ok(read_dir) = std::fs::read_dir(&dir_path) else {
+        let typed_path = typed_path(&matched_path, doc_dir.as_deref());
+        let Some((dir_path, typed_file_name, read_dir)) =
+            typed_path.and_then(|(dir_path, typed_file_name)| {
+                let read_dir = std::fs::read_dir(&dir_path).ok()?;
+                Some((dir_path, typed_file_name, read_dir))
+            })
+        else {
             return CompletionResponse {
                 items: CompletionItems::Other(Vec::new()),
                 provider: CompletionProvider::Path,
@@ -114,6 +166,7 @@ pub(crate) fn path_completion(
                     label: file_name.into(),
                     transaction,
                     documentation: Some(documentation),
+                    snippet: None,
                     provider: CompletionProvider::Path,
                 }))
             })
@@ -208,3 +261,31 @@ fn path_kind(md: &fs::Metadata) -> &'static str {
         "file"
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolve_relative_paths() {
+        let workspace = tempfile::tempdir().unwrap();
+        let workspace = workspace.path();
+        for dir in [".git", "src", "docs/notes"] {
+            fs::create_dir_all(workspace.join(dir)).unwrap();
+        }
+        let docs = workspace.join("docs");
+
+        let resolve =
+            |path: &str, ends_with_slash| relative_path(&docs, Path::new(path), ends_with_slash);
+        assert_eq!(resolve("notes/a", false), docs.join("notes/a"));
+        assert_eq!(resolve("src/main.rs", false), workspace.join("src/main.rs"));
+        assert_eq!(resolve("src/", true), workspace.join("src/"));
+        assert_eq!(resolve("./src/main.rs", false), docs.join("./src/main.rs"));
+        assert_eq!(resolve("missing/a", false), docs.join("missing/a"));
+
+        assert_eq!(
+            typed_path("src/ma", Some(&docs)),
+            Some((workspace.join("src"), Some("ma".to_string())))
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! The sources of completions. The language servers, the paths, the snippets and the words of the
+//! open documents are built in, and more can be registered with [register] without changing how
+//! completions are requested and shown.
+
+use std::sync::{Arc, RwLock};
+
+use helix_core::{self as core, completion::CompletionProvider as ProviderId};
+use helix_event::{runtime_local, TaskHandle};
+use helix_view::{document::SavePoint, Document, Editor};
+use tokio::task::JoinSet;
+
+use super::{CompletionResponse, Trigger};
+
+/// A source of completions.
+///
+/// The items of all providers are shown in the same menu, sorted by how well they match the typed
+/// text. The items of a response which match as well are kept in their order, so providers rank
+/// their items by ordering them, like the words by recency.
+pub trait CompletionProvider: Send + Sync + 'static {
+    /// Whether the items of `id` are from this provider. Providers which aren't built in give
+    /// their items an [ProviderId::Other] id.
+    fn owns(&self, id: ProviderId) -> bool;
+
+    /// Requests the completions at the cursor of the current view by spawning their requests in
+    /// `cx.requests`, if the provider completes at the cursor. Blocking work, like reading files,
+    /// should be spawned with `spawn_blocking`.
+    fn request(&self, cx: &mut RequestContext<'_>);
+
+    /// Resolves an item once it is accepted in `doc`, like to compute what's too expensive to
+    /// compute for every item.
+    fn resolve(&self, _doc: &Document, item: core::CompletionItem) -> core::CompletionItem {
+        item
+    }
+}
+
+pub struct RequestContext<'a> {
+    pub editor: &'a Editor,
+    pub trigger: Trigger,
+    /// Canceled once the completions are outdated.
+    pub handle: TaskHandle,
+    /// The state of the document when the completions are requested, which is restored before an
+    /// item is accepted.
+    pub savepoint: Arc<SavePoint>,
+    pub requests: &'a mut JoinSet<CompletionResponse>,
+}
+
+runtime_local! {
+    static PROVIDERS: RwLock<Vec<Arc<dyn CompletionProvider>>> = RwLock::new(Vec::new());
+}
+
+/// Registers a provider when the handlers are set up, like one behind a feature flag. Its
+/// completions are requested after those of the providers registered before it, the built-in
+/// ones first.
+pub fn register(provider: impl CompletionProvider) {
+    PROVIDERS.write().unwrap().push(Arc::new(provider));
+}
+
+pub(super) fn providers() -> Vec<Arc<dyn CompletionProvider>> {
+    PROVIDERS.read().unwrap().clone()
+}
+
+/// Resolves an accepted item with the provider which owns it.
+pub(crate) fn resolve(doc: &Document, item: core::CompletionItem) -> core::CompletionItem {
+    let provider = providers()
+        .into_iter()
+        .find(|provider| provider.owns(item.provider));
+    match provider {
+        Some(provider) => provider.resolve(doc, item),
+        None => item,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arc_swap::ArcSwap;
+    use helix_core::{syntax, Rope, Transaction};
+    use helix_view::editor::Config;
+
+    use super::*;
+
+    struct Uppercase;
+
+    impl CompletionProvider for Uppercase {
+        fn owns(&self, id: ProviderId) -> bool {
+            id == ProviderId::Other("uppercase")
+        }
+
+        fn request(&self, _cx: &mut RequestContext<'_>) {}
+
+        fn resolve(&self, _doc: &Document, mut item: core::CompletionItem) -> core::CompletionItem {
+            item.label = item.label.to_uppercase().into();
+            item
+        }
+    }
+
+    #[test]
+    fn resolve_with_owner() {
+        register(Uppercase);
+        let text = Rope::from("word");
+        let doc = Document::from(
+            text.clone(),
+            None,
+            Arc::new(ArcSwap::from_pointee(Config::default())),
+            Arc::new(ArcSwap::from_pointee(syntax::Loader::default())),
+        );
+        let item = |provider| core::CompletionItem {
+            transaction: Transaction::new(&text),
+            label: "word".into(),
+            kind: "".into(),
+            documentation: None,
+            snippet: None,
+            provider,
+        };
+
+        let resolved = resolve(&doc, item(ProviderId::Other("uppercase")));
+        assert_eq!(resolved.label, "WORD");
+        let resolved = resolve(&doc, item(ProviderId::Other("lowercase")));
+        assert_eq!(resolved.label, "word");
+    }
+}
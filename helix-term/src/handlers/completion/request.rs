@@ -20,7 +20,6 @@
 use crate::compositor::Compositor;
 use crate::config::Config;
 use crate::handlers::completion::item::CompletionResponse;
-use crate::handlers::completion::path::path_completion;
 use crate::handlers::completion::{
     handle_response, replace_completions, show_completion, CompletionItems,
 };
@@ -28,21 +27,25 @@
 use crate::ui;
 use crate::ui::editor::InsertEvent;
 
-use super::word;
+use super::provider::{self, RequestContext};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub(super) enum TriggerKind {
+pub enum TriggerKind {
+    /// Typing a word.
     Auto,
+    /// Typing a trigger character of a language server.
     TriggerChar,
+    /// The `completion` command.
     Manual,
 }
 
 #[derive(Debug, Clone, Copy)]
-pub(super) struct Trigger {
-    pub(super) pos: usize,
-    pub(super) view: ViewId,
-    pub(super) doc: DocumentId,
-    pub(super) kind: TriggerKind,
+pub struct Trigger {
+    /// The cursor when completion is requested.
+    pub pos: usize,
+    pub view: ViewId,
+    pub doc: DocumentId,
+    pub kind: TriggerKind,
 }
 
 #[derive(Debug)]
@@ -191,67 +194,17 @@ fn request_completions(
     trigger.pos = cursor;
     let doc = doc_mut!(editor, &doc.id());
     let savepoint = doc.savepoint(view);
-    let text = doc.text();
-    let trigger_text = text.slice(..cursor);
 
-    let mut seen_language_servers = HashSet::new();
-    let language_servers: Vec<_> = doc
-        .language_servers_with_feature(LanguageServerFeature::Completion)
-        .filter(|ls| seen_language_servers.insert(ls.id()))
-        .collect();
     let mut requests = JoinSet::new();
-    for (priority, ls) in language_servers.iter().enumerate() {
-        let context = if trigger.kind == TriggerKind::Manual {
-            lsp::CompletionContext {
-                trigger_kind: lsp::CompletionTriggerKind::INVOKED,
-                trigger_character: None,
-            }
-        } else {
-            let trigger_char =
-                ls.capabilities()
-                    .completion_provider
-                    .as_ref()
-                    .and_then(|provider| {
-                        provider
-                            .trigger_characters
-                            .as_deref()?
-                            .iter()
-                            .find(|&trigger| trigger_text.ends_with(trigger))
-                    });
-
-            if trigger_char.is_some() {
-                lsp::CompletionContext {
-                    trigger_kind: lsp::CompletionTriggerKind::TRIGGER_CHARACTER,
-                    trigger_character: trigger_char.cloned(),
-                }
-            } else {
-                lsp::CompletionContext {
-                    trigger_kind: lsp::CompletionTriggerKind::INVOKED,
-                    trigger_character: None,
-                }
-            }
-        };
-        requests.spawn(request_completions_from_language_server(
-            ls,
-            doc,
-            view.id,
-            context,
-            -(priority as i8),
-            savepoint.clone(),
-        ));
-    }
-    if let Some(path_completion_request) = path_completion(
-        doc.selection(view.id).clone(),
-        doc,
-        handle.clone(),
-        savepoint.clone(),
-    ) {
-        requests.spawn_blocking(path_completion_request);
-    }
-    if let Some(word_completion_request) =
-        word::completion(editor, trigger, handle.clone(), savepoint)
-    {
-        requests.spawn_blocking(word_completion_request);
+    let mut cx = RequestContext {
+        editor,
+        trigger,
+        handle: handle.clone(),
+        savepoint,
+        requests: &mut requests,
+    };
+    for provider in provider::providers() {
+        provider.request(&mut cx);
     }
 
     let ui = compositor.find::<ui::EditorView>().unwrap();
@@ -289,6 +242,67 @@ fn request_completions(
     tokio::spawn(cancelable_future(request_completions, handle));
 }
 
+/// The completions of the language servers of the document.
+pub(super) struct LanguageServers;
+
+impl provider::CompletionProvider for LanguageServers {
+    fn owns(&self, id: CompletionProvider) -> bool {
+        matches!(id, CompletionProvider::Lsp(_))
+    }
+
+    fn request(&self, cx: &mut RequestContext<'_>) {
+        let (view, doc) = current_ref!(cx.editor);
+        let text = doc.text();
+        let trigger_text = text.slice(..cx.trigger.pos);
+
+        let mut seen_language_servers = HashSet::new();
+        let language_servers: Vec<_> = doc
+            .language_servers_with_feature(LanguageServerFeature::Completion)
+            .filter(|ls| seen_language_servers.insert(ls.id()))
+            .collect();
+        for (priority, ls) in language_servers.iter().enumerate() {
+            let context = if cx.trigger.kind == TriggerKind::Manual {
+                lsp::CompletionContext {
+                    trigger_kind: lsp::CompletionTriggerKind::INVOKED,
+                    trigger_character: None,
+                }
+            } else {
+                let trigger_char =
+                    ls.capabilities()
+                        .completion_provider
+                        .as_ref()
+                        .and_then(|provider| {
+                            provider
+                                .trigger_characters
+                                .as_deref()?
+                                .iter()
+                                .find(|&trigger| trigger_text.ends_with(trigger))
+                        });
+
+                if trigger_char.is_some() {
+                    lsp::CompletionContext {
+                        trigger_kind: lsp::CompletionTriggerKind::TRIGGER_CHARACTER,
+                        trigger_character: trigger_char.cloned(),
+                    }
+                } else {
+                    lsp::CompletionContext {
+                        trigger_kind: lsp::CompletionTriggerKind::INVOKED,
+                        trigger_character: None,
+                    }
+                }
+            };
+            cx.requests.spawn(request_completions_from_language_server(
+                ls,
+                doc,
+                view.id,
+                context,
+                -(priority as i8),
+                cx.savepoint.clone(),
+            ));
+        }
+    }
+}
+
 fn request_completions_from_language_server(
     ls: &helix_lsp::Client,
     doc: &Document,
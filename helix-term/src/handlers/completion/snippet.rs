@@ -0,0 +1,275 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use helix_core::{
+    self as core,
+    chars::char_is_word,
+    completion::CompletionProvider,
+    fuzzy::fuzzy_match,
+    snippets::{Snippet, SnippetRenderCtx},
+};
+use helix_event::TaskHandle;
+use helix_lsp::util;
+use helix_view::{document::SavePoint, handlers::completion::ResponseContext, Editor};
+use serde::Deserialize;
+
+use super::provider::{self, RequestContext};
+use super::{request::TriggerKind, CompletionItem, CompletionItems, CompletionResponse, Trigger};
+
+const COMPLETION_KIND: &str = "snippet";
+
+/// A snippet of the `snippets/<language>.toml` file of the config directory, whose tables are
+/// named after the snippets.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct UserSnippet {
+    /// The word which is completed to the snippet.
+    prefix: String,
+    /// The snippet, in the syntax of the LSP specification.
+    body: String,
+    description: Option<String>,
+}
+
+fn snippets_file(language: &str) -> PathBuf {
+    helix_loader::config_dir()
+        .join("snippets")
+        .join(format!("{language}.toml"))
+}
+
+type UserSnippets = Arc<BTreeMap<String, UserSnippet>>;
+
+/// The snippets read from each snippets file, with the time the file was modified then.
+static SNIPPETS: Mutex<BTreeMap<PathBuf, (Option<SystemTime>, UserSnippets)>> =
+    Mutex::new(BTreeMap::new());
+
+/// Loads the snippets of `language` from the snippets file at `path` by name, which are empty if
+/// there's no such file. The file is only read again once it's modified.
+fn load(path: &Path, language: &str) -> UserSnippets {
+    let modified = std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    let mut cache = SNIPPETS.lock().unwrap();
+    if let Some((_, snippets)) = cache.get(path).filter(|(cached, _)| *cached == modified) {
+        return snippets.clone();
+    }
+    let snippets = Arc::new(read(path, language));
+    cache.insert(path.to_owned(), (modified, snippets.clone()));
+    snippets
+}
+
+fn read(path: &Path, language: &str) -> BTreeMap<String, UserSnippet> {
+    let Ok(file) = std::fs::read_to_string(path) else {
+        return BTreeMap::new();
+    };
+    toml::from_str(&file).unwrap_or_else(|err| {
+        log::error!("failed to read the snippets of {language}: {err}");
+        BTreeMap::new()
+    })
+}
+
+/// The snippets whose prefix matches the word `typed` before the cursor, as the completion menu
+/// filters its items.
+fn matching<'a>(
+    snippets: &'a BTreeMap<String, UserSnippet>,
+    typed: &str,
+) -> impl Iterator<Item = (&'a String, &'a UserSnippet)> {
+    let prefixes: Option<HashSet<&str>> = (!typed.is_empty()).then(|| {
+        fuzzy_match(
+            typed,
+            snippets.values().map(|snippet| snippet.prefix.as_str()),
+            false,
+        )
+        .into_iter()
+        .map(|(prefix, _)| prefix)
+        .collect()
+    });
+    snippets.iter().filter(move |(_, snippet)| {
+        prefixes
+            .as_ref()
+            .is_none_or(|prefixes| prefixes.contains(snippet.prefix.as_str()))
+    })
+}
+
+fn documentation(name: &str, snippet: &UserSnippet, language: &str) -> String {
+    let description = snippet.description.as_deref().unwrap_or(name);
+    format!("{description}\n\n```{language}\n{}\n```", snippet.body)
+}
+
+/// The snippets of the user for the language of the document.
+pub(super) struct Snippets;
+
+impl provider::CompletionProvider for Snippets {
+    fn owns(&self, id: CompletionProvider) -> bool {
+        id == CompletionProvider::Snippet
+    }
+
+    fn request(&self, cx: &mut RequestContext<'_>) {
+        if let Some(request) = completion(
+            cx.editor,
+            cx.trigger,
+            cx.handle.clone(),
+            cx.savepoint.clone(),
+        ) {
+            cx.requests.spawn_blocking(request);
+        }
+    }
+}
+
+fn completion(
+    editor: &Editor,
+    trigger: Trigger,
+    handle: TaskHandle,
+    savepoint: Arc<SavePoint>,
+) -> Option<impl FnOnce() -> CompletionResponse> {
+    let (view, doc) = current_ref!(editor);
+    let language = doc.language_name()?.to_owned();
+    let rope = doc.text().clone();
+    let selection = doc.selection(view.id).clone();
+    let cursor = selection.primary().cursor(rope.slice(..));
+    // Snippets are only completed automatically after their prefix is typed.
+    if trigger.kind != TriggerKind::Manual
+        && !cursor
+            .checked_sub(1)
+            .and_then(|pos| rope.get_char(pos))
+            .is_some_and(char_is_word)
+    {
+        return None;
+    }
+    let mut typed: Vec<char> = rope
+        .chars_at(cursor)
+        .reversed()
+        .take_while(|&ch| char_is_word(ch))
+        .collect();
+    typed.reverse();
+    let typed: String = typed.into_iter().collect();
+    let replace_mode = editor.config().completion_replace;
+    let (tab_width, indent_style, line_ending) =
+        (doc.tab_width(), doc.indent_style, doc.line_ending.as_str());
+
+    let future = move || {
+        let snippets = load(&snippets_file(&language), &language);
+        let items = matching(&snippets, &typed)
+            .take_while(|_| !handle.is_canceled())
+            .filter_map(|(name, snippet)| {
+                let parsed = match Snippet::parse(&snippet.body) {
+                    Ok(parsed) => parsed,
+                    Err(err) => {
+                        log::error!("invalid snippet {name} of {language}: {err}");
+                        return None;
+                    }
+                };
+                // The preview doesn't resolve variables, which is done once the item is accepted.
+                let mut ctx = SnippetRenderCtx {
+                    resolve_var: Box::new(|_| None),
+                    tab_width,
+                    indent_style,
+                    line_ending,
+                };
+                let (transaction, _) = util::generate_transaction_from_snippet(
+                    &rope,
+                    &selection,
+                    None,
+                    replace_mode,
+                    parsed,
+                    &mut ctx,
+                );
+                Some(CompletionItem::Other(core::CompletionItem {
+                    transaction,
+                    documentation: Some(documentation(name, snippet, &language)),
+                    label: snippet.prefix.clone().into(),
+                    kind: Cow::Borrowed(COMPLETION_KIND),
+                    snippet: Some(snippet.body.as_str().into()),
+                    provider: CompletionProvider::Snippet,
+                }))
+            })
+            .collect();
+
+        CompletionResponse {
+            items: CompletionItems::Other(items),
+            provider: CompletionProvider::Snippet,
+            context: ResponseContext {
+                is_incomplete: false,
+                priority: 0,
+                savepoint,
+            },
+        }
+    };
+
+    Some(future)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn parse_snippets() {
+        let snippets: BTreeMap<String, UserSnippet> = toml::from_str(
+            r#"
+            [function]
+            prefix = "fn"
+            body = "fn ${1:name}() {\n\t$0\n}"
+            description = "A function"
+            "#,
+        )
+        .unwrap();
+        let snippet = &snippets["function"];
+        assert_eq!(snippet.prefix, "fn");
+        assert!(Snippet::parse(&snippet.body).is_ok());
+        assert_eq!(
+            documentation("function", snippet, "rust"),
+            "A function\n\n```rust\nfn ${1:name}() {\n\t$0\n}\n```"
+        );
+        assert!(toml::from_str::<BTreeMap<String, UserSnippet>>("[a]\nbody = \"b\"").is_err());
+    }
+
+    #[test]
+    fn match_prefixes() {
+        let snippets: BTreeMap<String, UserSnippet> = toml::from_str(
+            r#"
+            function = { prefix = "fn", body = "fn $1() {}" }
+            format = { prefix = "format", body = "format!($1)" }
+            match = { prefix = "match", body = "match $1 {}" }
+            "#,
+        )
+        .unwrap();
+        let names = |typed| {
+            matching(&snippets, typed)
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(""), ["format", "function", "match"]);
+        assert_eq!(names("f"), ["format", "function"]);
+        assert_eq!(names("fmt"), ["format"]);
+        assert!(names("x").is_empty());
+    }
+
+    #[test]
+    fn cache_snippets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rust.toml");
+        std::fs::write(&path, "a = { prefix = \"a\", body = \"a\" }").unwrap();
+        let snippets = load(&path, "rust");
+        assert_eq!(snippets.len(), 1);
+        assert!(Arc::ptr_eq(&snippets, &load(&path, "rust")));
+
+        let file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        writeln!(&file, "\nb = {{ prefix = \"b\", body = \"b\" }}").unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(load(&path, "rust").len(), 2);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(load(&path, "rust").is_empty());
+    }
+}
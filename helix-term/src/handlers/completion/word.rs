@@ -9,11 +9,32 @@
     document::SavePoint, handlers::completion::ResponseContext, Document, Editor, ViewId,
 };
 
+use super::provider::{self, RequestContext};
 use super::{request::TriggerKind, CompletionItem, CompletionItems, CompletionResponse, Trigger};
 
 const COMPLETION_KIND: &str = "word";
 
-pub(super) fn completion(
+/// The words of the open documents.
+pub(super) struct Words;
+
+impl provider::CompletionProvider for Words {
+    fn owns(&self, id: CompletionProvider) -> bool {
+        id == CompletionProvider::Word
+    }
+
+    fn request(&self, cx: &mut RequestContext<'_>) {
+        if let Some(request) = completion(
+            cx.editor,
+            cx.trigger,
+            cx.handle.clone(),
+            cx.savepoint.clone(),
+        ) {
+            cx.requests.spawn_blocking(request);
+        }
+    }
+}
+
+fn completion(
     editor: &Editor,
     trigger: Trigger,
     handle: TaskHandle,
@@ -86,6 +107,7 @@ pub(super) fn completion(
                     label: word.into(),
                     kind: Cow::Borrowed(COMPLETION_KIND),
                     documentation: None,
+                    snippet: None,
                     provider: CompletionProvider::Word,
                 })
             })
@@ -1,5 +1,5 @@
 use futures_util::stream::FuturesUnordered;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::mem;
 use std::time::Duration;
 use tokio::time::Instant;
@@ -12,12 +12,13 @@
 use helix_lsp::{lsp, LanguageServerId};
 use helix_view::document::Mode;
 use helix_view::events::{
-    DiagnosticsDidChange, DocumentDidChange, DocumentDidOpen, LanguageServerInitialized,
+    DiagnosticsDidChange, DocumentDidChange, DocumentDidOpen, DocumentDidSave,
+    LanguageServerExited, LanguageServerInitialized,
 };
 use helix_view::handlers::diagnostics::DiagnosticEvent;
 use helix_view::handlers::lsp::{PullAllDocumentsDiagnosticsEvent, PullDiagnosticsEvent};
 use helix_view::handlers::Handlers;
-use helix_view::{DocumentId, Editor};
+use helix_view::{Document, DocumentId, Editor};
 
 use crate::events::OnModeSwitch;
 use crate::job;
@@ -88,12 +89,41 @@ pub(super) fn register_hooks(handlers: &Handlers) {
         Ok(())
     });
 
+    // The whole workspace is only pulled once a document is saved rather than as documents change,
+    // since servers may take a while to check it.
+    register_hook!(move |event: &mut DocumentDidSave<'_>| {
+        let Some(doc) = event.editor.document(event.doc) else {
+            return Ok(());
+        };
+        let language_servers: Vec<_> = doc
+            .language_servers_with_feature(LanguageServerFeature::PullDiagnostics)
+            .map(|language_server| language_server.id())
+            .collect();
+        for language_server in language_servers {
+            request_workspace_diagnostics(event.editor, language_server);
+        }
+
+        Ok(())
+    });
+
     register_hook!(move |event: &mut LanguageServerInitialized<'_>| {
         let doc_ids: Vec<_> = event.editor.documents.keys().copied().collect();
 
         for doc_id in doc_ids {
             request_document_diagnostics(event.editor, doc_id);
         }
+        request_workspace_diagnostics(event.editor, event.server_id);
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerExited<'_>| {
+        event.editor.diagnostic_result_ids.remove(&event.server_id);
+        // Dropping the controller cancels the ongoing workspace diagnostic request.
+        event
+            .editor
+            .workspace_diagnostic_controllers
+            .remove(&event.server_id);
 
         Ok(())
     });
@@ -164,7 +194,10 @@ fn request_document_diagnostics_for_language_severs(
     doc_id: DocumentId,
     language_servers: HashSet<LanguageServerId>,
 ) {
-    let Some(doc) = editor.document_mut(doc_id) else {
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+    let Some(uri) = doc.uri() else {
         return;
     };
 
@@ -174,28 +207,15 @@ fn request_document_diagnostics_for_language_severs(
         .iter()
         .filter_map(|x| doc.language_servers().find(|y| &y.id() == x))
         .filter_map(|language_server| {
-            let future = language_server
-                .text_document_diagnostic(doc.identifier(), doc.previous_diagnostic_id.clone())?;
-
-            let identifier = language_server
-                .capabilities()
-                .diagnostic_provider
-                .as_ref()
-                .and_then(|diagnostic_provider| match diagnostic_provider {
-                    lsp::DiagnosticServerCapabilities::Options(options) => {
-                        options.identifier.clone()
-                    }
-                    lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
-                        options.diagnostic_options.identifier.clone()
-                    }
-                });
-
-            let language_server_id = language_server.id();
-            let provider = DiagnosticProvider::Lsp {
-                server_id: language_server_id,
-                identifier,
-            };
-            let uri = doc.uri()?;
+            let previous_result_id = editor
+                .diagnostic_result_ids
+                .get(&language_server.id())
+                .and_then(|result_ids| result_ids.get(&uri))
+                .cloned();
+            let future =
+                language_server.text_document_diagnostic(doc.identifier(), previous_result_id)?;
+            let provider = diagnostic_provider(language_server);
+            let uri = uri.clone();
 
             Some(async move {
                 let result = future.await;
@@ -215,24 +235,17 @@ fn request_document_diagnostics_for_language_severs(
             match cancelable_future(futures.next(), &cancel).await {
                 Some(Some((Ok(result), provider, uri))) => {
                     job::dispatch(move |editor, _| {
-                        handle_pull_diagnostics_response(editor, result, provider, uri, doc_id);
+                        handle_pull_diagnostics_response(editor, result, provider, uri);
                     })
                     .await;
                 }
                 Some(Some((Err(err), DiagnosticProvider::Lsp { server_id, .. }, _))) => {
-                    let parsed_cancellation_data = if let helix_lsp::Error::Rpc(error) = err {
-                        error.data.and_then(|data| {
-                            serde_json::from_value::<lsp::DiagnosticServerCancellationData>(data)
-                                .ok()
-                        })
-                    } else {
-                        log::error!("Pull diagnostic request failed: {err}");
-                        continue;
-                    };
-                    if parsed_cancellation_data.is_some_and(|data| data.retrigger_request) {
+                    if should_retrigger(err) {
                         retry_language_servers.insert(server_id);
                     }
                 }
+                // Pull diagnostics are only requested from language servers.
+                Some(Some((Err(_), DiagnosticProvider::Make, _))) => continue,
                 Some(None) => break,
                 // The request was cancelled.
                 None => return,
@@ -267,35 +280,259 @@ pub fn request_document_diagnostics(editor: &mut Editor, doc_id: DocumentId) {
     request_document_diagnostics_for_language_severs(editor, doc_id, language_servers);
 }
 
+/// Pulls the diagnostics of the whole workspace from the language server, if some documents pull
+/// their diagnostics from it and it supports it.
+pub fn request_workspace_diagnostics(editor: &mut Editor, language_server_id: LanguageServerId) {
+    if !editor
+        .documents()
+        .any(|doc| pulls_diagnostics(doc, language_server_id))
+    {
+        return;
+    }
+    let Some(language_server) = editor.language_server_by_id(language_server_id) else {
+        return;
+    };
+
+    let previous_result_ids =
+        previous_result_ids(editor.diagnostic_result_ids.get(&language_server_id));
+    let Some(future) = language_server.workspace_diagnostic(previous_result_ids) else {
+        return;
+    };
+    let provider = diagnostic_provider(language_server);
+
+    let cancel = editor
+        .workspace_diagnostic_controllers
+        .entry(language_server_id)
+        .or_default()
+        .restart();
+
+    tokio::spawn(async move {
+        match cancelable_future(future, &cancel).await {
+            Some(Ok(result)) => {
+                job::dispatch(move |editor, _| {
+                    if !cancel.is_canceled() {
+                        handle_workspace_diagnostics_response(editor, result, provider);
+                    }
+                })
+                .await;
+            }
+            Some(Err(err)) => {
+                if should_retrigger(err) {
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+
+                    job::dispatch(move |editor, _| {
+                        request_workspace_diagnostics(editor, language_server_id);
+                    })
+                    .await;
+                }
+            }
+            // The request was cancelled.
+            None => (),
+        }
+    });
+}
+
+fn diagnostic_provider(language_server: &helix_lsp::Client) -> DiagnosticProvider {
+    let identifier = language_server
+        .capabilities()
+        .diagnostic_provider
+        .as_ref()
+        .and_then(|diagnostic_provider| match diagnostic_provider {
+            lsp::DiagnosticServerCapabilities::Options(options) => options.identifier.clone(),
+            lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+                options.diagnostic_options.identifier.clone()
+            }
+        });
+
+    DiagnosticProvider::Lsp {
+        server_id: language_server.id(),
+        identifier,
+    }
+}
+
+fn pulls_diagnostics(doc: &Document, language_server_id: LanguageServerId) -> bool {
+    doc.language_servers_with_feature(LanguageServerFeature::PullDiagnostics)
+        .any(|language_server| language_server.id() == language_server_id)
+}
+
+/// Whether a failed request is sent again, which servers ask for when they cancel it.
+fn should_retrigger(err: helix_lsp::Error) -> bool {
+    let helix_lsp::Error::Rpc(error) = err else {
+        log::error!("Pull diagnostic request failed: {err}");
+        return false;
+    };
+    error
+        .data
+        .and_then(|data| serde_json::from_value::<lsp::DiagnosticServerCancellationData>(data).ok())
+        .is_some_and(|data| data.retrigger_request)
+}
+
 fn handle_pull_diagnostics_response(
     editor: &mut Editor,
     result: lsp::DocumentDiagnosticReportResult,
     provider: DiagnosticProvider,
     uri: Uri,
-    document_id: DocumentId,
 ) {
-    match result {
-        lsp::DocumentDiagnosticReportResult::Report(report) => {
-            let result_id = match report {
-                lsp::DocumentDiagnosticReport::Full(report) => {
-                    editor.handle_lsp_diagnostics(
-                        &provider,
-                        uri,
-                        None,
-                        report.full_document_diagnostic_report.items,
-                    );
-
-                    report.full_document_diagnostic_report.result_id
-                }
-                lsp::DocumentDiagnosticReport::Unchanged(report) => {
-                    Some(report.unchanged_document_diagnostic_report.result_id)
-                }
-            };
+    let lsp::DocumentDiagnosticReportResult::Report(report) = result else {
+        return;
+    };
+    let (report, related_documents) = match report {
+        lsp::DocumentDiagnosticReport::Full(report) => (
+            lsp::DocumentDiagnosticReportKind::Full(report.full_document_diagnostic_report),
+            report.related_documents,
+        ),
+        lsp::DocumentDiagnosticReport::Unchanged(report) => (
+            lsp::DocumentDiagnosticReportKind::Unchanged(
+                report.unchanged_document_diagnostic_report,
+            ),
+            report.related_documents,
+        ),
+    };
+    handle_diagnostic_report(editor, &provider, uri, report);
+
+    // Servers with inter-file dependencies may report the documents affected by the requested one.
+    for (url, report) in related_documents.into_iter().flatten() {
+        match Uri::try_from(url) {
+            Ok(uri) => handle_diagnostic_report(editor, &provider, uri, report),
+            Err(err) => log::error!("{err}"),
+        }
+    }
+}
 
-            if let Some(doc) = editor.document_mut(document_id) {
-                doc.previous_diagnostic_id = result_id;
-            };
+fn handle_workspace_diagnostics_response(
+    editor: &mut Editor,
+    result: lsp::WorkspaceDiagnosticReportResult,
+    provider: DiagnosticProvider,
+) {
+    let DiagnosticProvider::Lsp { server_id, .. } = provider else {
+        return;
+    };
+    let items = match result {
+        lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+        lsp::WorkspaceDiagnosticReportResult::Partial(report) => report.items,
+    };
+
+    for item in items {
+        let (url, report) = match item {
+            lsp::WorkspaceDocumentDiagnosticReport::Full(report) => (
+                report.uri,
+                lsp::DocumentDiagnosticReportKind::Full(report.full_document_diagnostic_report),
+            ),
+            lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                report.uri,
+                lsp::DocumentDiagnosticReportKind::Unchanged(
+                    report.unchanged_document_diagnostic_report,
+                ),
+            ),
+        };
+        let uri = match Uri::try_from(url) {
+            Ok(uri) => uri,
+            Err(err) => {
+                log::error!("{err}");
+                continue;
+            }
+        };
+        // The diagnostics of open documents are pulled on their own as they change, the report of
+        // the workspace may be older.
+        if uri
+            .as_path()
+            .and_then(|path| editor.document_by_path(path))
+            .is_some_and(|doc| pulls_diagnostics(doc, server_id))
+        {
+            continue;
         }
-        lsp::DocumentDiagnosticReportResult::Partial(_) => {}
+        handle_diagnostic_report(editor, &provider, uri, report);
+    }
+}
+
+/// The result ids of the last reports of a server's documents, sent with a workspace diagnostic
+/// request so that the server only reports the documents whose diagnostics changed.
+fn previous_result_ids(result_ids: Option<&BTreeMap<Uri, String>>) -> Vec<lsp::PreviousResultId> {
+    result_ids
+        .into_iter()
+        .flatten()
+        .filter_map(|(uri, result_id)| {
+            Some(lsp::PreviousResultId {
+                uri: uri.to_url().ok()?,
+                value: result_id.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Remembers the result id of the report of `uri` to send it with the next request, or forgets
+/// the previous one if the report has none. Returns the diagnostics of a full report.
+fn record_result_id(
+    result_ids: &mut BTreeMap<Uri, String>,
+    uri: &Uri,
+    report: lsp::DocumentDiagnosticReportKind,
+) -> Option<Vec<lsp::Diagnostic>> {
+    let (result_id, diagnostics) = match report {
+        lsp::DocumentDiagnosticReportKind::Full(report) => (report.result_id, Some(report.items)),
+        lsp::DocumentDiagnosticReportKind::Unchanged(report) => (Some(report.result_id), None),
+    };
+    match result_id {
+        Some(result_id) => result_ids.insert(uri.clone(), result_id),
+        None => result_ids.remove(uri),
+    };
+    diagnostics
+}
+
+/// Sets the diagnostics of a report and remembers its result id to send it with the next request.
+fn handle_diagnostic_report(
+    editor: &mut Editor,
+    provider: &DiagnosticProvider,
+    uri: Uri,
+    report: lsp::DocumentDiagnosticReportKind,
+) {
+    let DiagnosticProvider::Lsp { server_id, .. } = provider else {
+        return;
     };
+    let result_ids = editor.diagnostic_result_ids.entry(*server_id).or_default();
+    if let Some(diagnostics) = record_result_id(result_ids, &uri, report) {
+        editor.handle_lsp_diagnostics(provider, uri, None, diagnostics);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn result_ids() {
+        let full = |result_id: Option<&str>| {
+            lsp::DocumentDiagnosticReportKind::Full(lsp::FullDocumentDiagnosticReport {
+                result_id: result_id.map(String::from),
+                items: Vec::new(),
+            })
+        };
+        let unchanged = |result_id: &str| {
+            lsp::DocumentDiagnosticReportKind::Unchanged(lsp::UnchangedDocumentDiagnosticReport {
+                result_id: result_id.to_string(),
+            })
+        };
+        let a = Uri::from(std::path::PathBuf::from("/a.rs"));
+        let b = Uri::from(std::path::PathBuf::from("/b.rs"));
+        let mut result_ids = BTreeMap::new();
+
+        assert_eq!(
+            record_result_id(&mut result_ids, &a, full(Some("1"))),
+            Some(Vec::new())
+        );
+        assert_eq!(record_result_id(&mut result_ids, &b, unchanged("2")), None);
+        assert_eq!(result_ids[&a], "1");
+        assert_eq!(result_ids[&b], "2");
+
+        let previous = previous_result_ids(Some(&result_ids));
+        assert_eq!(previous.len(), 2);
+        assert_eq!(previous[0].uri.path(), "/a.rs");
+        assert_eq!(previous[0].value, "1");
+
+        // A full report without a result id invalidates the previous one.
+        record_result_id(&mut result_ids, &a, full(None));
+        assert!(!result_ids.contains_key(&a));
+        record_result_id(&mut result_ids, &b, full(Some("3")));
+        assert_eq!(result_ids[&b], "3");
+        assert!(previous_result_ids(None).is_empty());
+    }
 }
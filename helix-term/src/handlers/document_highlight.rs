@@ -0,0 +1,139 @@
+//! Highlights the occurrences of the symbol under the cursor once the cursor rests, with
+//! `editor.lsp.auto-document-highlight`. The highlights are cleared as soon as the cursor moves or
+//! the document changes.
+
+use std::time::Duration;
+
+use helix_core::syntax::config::LanguageServerFeature;
+use helix_event::{cancelable_future, register_hook, send_blocking};
+use helix_lsp::{lsp, util::lsp_range_to_range};
+use helix_view::{
+    document::DocumentHighlight,
+    events::{DocumentDidChange, SelectionDidChange},
+    handlers::{lsp::DocumentHighlightEvent, Handlers},
+    DocumentId, Editor, ViewId,
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Default)]
+pub(super) struct DocumentHighlightHandler {
+    cursor: Option<(DocumentId, ViewId)>,
+}
+
+impl helix_event::AsyncHook for DocumentHighlightHandler {
+    type Event = DocumentHighlightEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        self.cursor = Some((event.doc, event.view));
+        Some(Instant::now() + Duration::from_millis(event.delay))
+    }
+
+    fn finish_debounce(&mut self) {
+        let Some((doc, view)) = self.cursor.take() else {
+            return;
+        };
+        job::dispatch_blocking(move |editor, _compositor| {
+            request_document_highlight(editor, doc, view)
+        });
+    }
+}
+
+fn request_document_highlight(editor: &mut Editor, doc_id: DocumentId, view_id: ViewId) {
+    if !editor.config().lsp.auto_document_highlight || !editor.tree.contains(view_id) {
+        return;
+    }
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+    let Some(language_server) = doc
+        .language_servers_with_feature(LanguageServerFeature::DocumentHighlight)
+        .next()
+    else {
+        return;
+    };
+    let offset_encoding = language_server.offset_encoding();
+    let pos = doc.position(view_id, offset_encoding);
+    let future = language_server
+        .text_document_document_highlight(doc.identifier(), pos, None)
+        .unwrap();
+    let text = doc.text().clone();
+    let cancel = doc.document_highlight_controller.restart();
+
+    tokio::spawn(async move {
+        let highlights = match cancelable_future(future, &cancel).await {
+            Some(Ok(highlights)) => highlights,
+            Some(Err(err)) => {
+                log::error!("document highlight request failed: {err}");
+                return;
+            }
+            // The cursor moved or the document changed.
+            None => return,
+        };
+        let mut highlights: Vec<_> = highlights
+            .into_iter()
+            .flatten()
+            .filter_map(|highlight| {
+                let range = lsp_range_to_range(&text, highlight.range, offset_encoding)?;
+                Some(DocumentHighlight {
+                    range: range.from()..range.to(),
+                    kind: highlight.kind.unwrap_or(lsp::DocumentHighlightKind::TEXT),
+                })
+            })
+            .collect();
+        // Overlays must be sorted and must not overlap.
+        highlights.sort_by_key(|highlight| highlight.range.start);
+        highlights.dedup_by(|next, prev| next.range.start < prev.range.end);
+        job::dispatch(move |editor, _| {
+            if cancel.is_canceled() {
+                return;
+            }
+            if let Some(doc) = editor.documents.get_mut(&doc_id) {
+                doc.document_highlights.insert(view_id, highlights);
+            }
+        })
+        .await;
+    });
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    let tx = handlers.document_highlight.clone();
+    register_hook!(move |event: &mut SelectionDidChange<'_>| {
+        event.doc.document_highlights.remove(&event.view);
+        event.doc.document_highlight_controller.cancel();
+        let config = event.doc.config.load();
+        if config.lsp.auto_document_highlight {
+            send_blocking(
+                &tx,
+                DocumentHighlightEvent {
+                    doc: event.doc.id(),
+                    view: event.view,
+                    delay: config.lsp.document_highlight_delay,
+                },
+            );
+        }
+        Ok(())
+    });
+
+    let tx = handlers.document_highlight.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        // The highlights of every view are out of date.
+        event.doc.document_highlights.clear();
+        event.doc.document_highlight_controller.cancel();
+        let config = event.doc.config.load();
+        // Like document colors, avoid requesting highlights the language server can't know about
+        // during completion.
+        if config.lsp.auto_document_highlight && !event.ghost_transaction {
+            send_blocking(
+                &tx,
+                DocumentHighlightEvent {
+                    doc: event.doc.id(),
+                    view: event.view,
+                    delay: config.lsp.document_highlight_delay,
+                },
+            );
+        }
+        Ok(())
+    });
+}
@@ -0,0 +1,98 @@
+//! Runs the commands of the `editor.hooks` set for an event once it happens. The commands run
+//! after the event is handled, one after the other, in the focused view.
+
+use std::path::Path;
+
+use globset::{Glob, GlobSetBuilder};
+use helix_event::register_hook;
+use helix_view::{
+    document::Mode,
+    editor::{HookConfig, HookEvent},
+    events::{DocumentDidClose, DocumentDidOpen, DocumentDidSave, DocumentFocusLost},
+    handlers::Handlers,
+    DocumentId, Editor,
+};
+
+use crate::{
+    commands::MappableCommand,
+    events::{OnModeSwitch, TerminalProgramDidExit},
+    job::{self, Callback},
+};
+
+/// Whether the file at `path` matches the `files` patterns of the hook. Patterns are matched
+/// against the path relative to the workspace and against the file name.
+fn matches_files(hook: &HookConfig, path: Option<&Path>) -> anyhow::Result<bool> {
+    if hook.files.is_empty() {
+        return Ok(true);
+    }
+    let Some(path) = path else {
+        return Ok(false);
+    };
+    let mut builder = GlobSetBuilder::new();
+    for pattern in &hook.files {
+        builder.add(Glob::new(pattern)?);
+    }
+    let globs = builder.build()?;
+    let (workspace, _) = helix_loader::find_workspace();
+    let relative = path.strip_prefix(&workspace).unwrap_or(path);
+    Ok(globs.is_match(relative) || path.file_name().is_some_and(|name| globs.is_match(name)))
+}
+
+fn run_hooks(editor: &mut Editor, event: HookEvent, path: Option<&Path>, mode: Option<Mode>) {
+    let config = editor.config();
+    let mut commands = Vec::new();
+    let result = config
+        .hooks
+        .iter()
+        .filter(|hook| hook.event == event && (hook.mode.is_none() || hook.mode == mode))
+        .try_for_each(|hook| {
+            if matches_files(hook, path)? {
+                for command in &hook.commands {
+                    commands.push(command.parse::<MappableCommand>()?);
+                }
+            }
+            anyhow::Ok(())
+        });
+    drop(config);
+    match result {
+        Err(err) => editor.set_error(format!("Failed to run the hooks: {err}")),
+        Ok(()) if !commands.is_empty() => {
+            job::dispatch_callback_blocking(Callback::Commands(commands));
+        }
+        Ok(()) => (),
+    }
+}
+
+fn run_document_hooks(editor: &mut Editor, event: HookEvent, doc: DocumentId) {
+    let path = editor.document(doc).and_then(|doc| doc.path().cloned());
+    run_hooks(editor, event, path.as_deref(), None);
+}
+
+pub(super) fn register_hooks(_handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        run_document_hooks(event.editor, HookEvent::Open, event.doc);
+        Ok(())
+    });
+    register_hook!(move |event: &mut DocumentDidSave<'_>| {
+        run_document_hooks(event.editor, HookEvent::Write, event.doc);
+        Ok(())
+    });
+    register_hook!(move |event: &mut DocumentDidClose<'_>| {
+        let path = event.doc.path().cloned();
+        run_hooks(event.editor, HookEvent::Close, path.as_deref(), None);
+        Ok(())
+    });
+    register_hook!(move |event: &mut DocumentFocusLost<'_>| {
+        run_document_hooks(event.editor, HookEvent::FocusChange, event.doc);
+        Ok(())
+    });
+    register_hook!(move |event: &mut OnModeSwitch<'_, '_>| {
+        let mode = Some(event.new_mode);
+        run_hooks(event.cx.editor, HookEvent::ModeChange, None, mode);
+        Ok(())
+    });
+    register_hook!(move |event: &mut TerminalProgramDidExit<'_>| {
+        run_hooks(event.editor, HookEvent::TerminalExit, None, None);
+        Ok(())
+    });
+}
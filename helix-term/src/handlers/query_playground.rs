@@ -0,0 +1,143 @@
+//! Runs the query of `:tree-sitter-playground` on the syntax tree of its source document after
+//! either of them changes, and shows the syntax tree next to the query.
+
+use std::{collections::HashSet, time::Duration};
+
+use helix_core::syntax;
+use helix_event::register_hook;
+use helix_view::{
+    editor::SYNTAX_TREE_OUTPUT_NAME,
+    events::{DocumentDidChange, DocumentDidClose},
+    handlers::{Handlers, QueryPlaygroundEvent},
+    DocumentId, Editor,
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Default)]
+pub(super) struct QueryPlaygroundHandler {
+    docs: HashSet<DocumentId>,
+}
+
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(150);
+
+impl helix_event::AsyncHook for QueryPlaygroundHandler {
+    type Event = QueryPlaygroundEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        let QueryPlaygroundEvent(doc_id) = event;
+        self.docs.insert(doc_id);
+        Some(Instant::now() + DOCUMENT_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let docs = std::mem::take(&mut self.docs);
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            let Some(playground) = editor.query_playground else {
+                return;
+            };
+            let query_changed = docs.contains(&playground.query);
+            let tree_changed = docs.contains(&playground.source);
+            if query_changed || tree_changed {
+                update_query_playground(editor, query_changed, tree_changed);
+            }
+        });
+    }
+}
+
+/// Runs the query of the query playground on the syntax tree of its source, and shows the tree
+/// when `tree_changed` is set. The number of captures or the error compiling the query is
+/// reported when `query_changed` is set.
+pub fn update_query_playground(editor: &mut Editor, query_changed: bool, tree_changed: bool) {
+    let Some(playground) = editor.query_playground else {
+        return;
+    };
+    let Some(query) = editor.document(playground.query) else {
+        return;
+    };
+    let query = query.text().to_string();
+    let Some(source) = editor.document_mut(playground.source) else {
+        return;
+    };
+    let Some(syntax) = source.syntax() else {
+        source.query_captures.clear();
+        return;
+    };
+
+    let tree = tree_changed.then(|| {
+        let mut tree = String::new();
+        if let Err(err) = syntax::pretty_print_tree(&mut tree, syntax.tree().root_node()) {
+            log::error!("failed to print the syntax tree: {err}");
+        }
+        tree.push('\n');
+        tree
+    });
+
+    let captures = if query.trim().is_empty() {
+        Ok(Vec::new())
+    } else {
+        syntax::query_captures(syntax, source.text().slice(..), &query)
+    };
+    let status = match captures {
+        Ok(captures) => {
+            source.query_captures = syntax::innermost_ranges(&captures);
+            Ok(format!("{} captures", captures.len()))
+        }
+        Err(err) => {
+            source.query_captures.clear();
+            // The following lines show where the error is in the query.
+            let err = err.to_string();
+            Err(err.lines().next().unwrap_or_default().to_owned())
+        }
+    };
+
+    if let Some(tree) = tree {
+        editor.set_output(SYNTAX_TREE_OUTPUT_NAME, &tree);
+    }
+    match status {
+        Ok(status) if query_changed && !query.trim().is_empty() => editor.set_status(status),
+        Err(err) if query_changed => editor.set_error(format!("Invalid query: {err}")),
+        _ => (),
+    }
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    let tx = handlers.query_playground.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        let captures = &mut event.doc.query_captures;
+        if !captures.is_empty() {
+            event
+                .changes
+                .update_positions(captures.iter_mut().flat_map(|(_, range)| {
+                    [
+                        (&mut range.start, helix_core::Assoc::After),
+                        (&mut range.end, helix_core::Assoc::Before),
+                    ]
+                }));
+            captures.retain(|(_, range)| !range.is_empty());
+        }
+
+        if !event.ghost_transaction {
+            helix_event::send_blocking(&tx, QueryPlaygroundEvent(event.doc.id()));
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut DocumentDidClose<'_>| {
+        let Some(playground) = event.editor.query_playground else {
+            return Ok(());
+        };
+        let closed = event.doc.id();
+        if closed == playground.query || closed == playground.source {
+            event.editor.query_playground = None;
+            if let Some(source) = event.editor.document_mut(playground.source) {
+                source.query_captures.clear();
+            }
+        }
+
+        Ok(())
+    });
+}
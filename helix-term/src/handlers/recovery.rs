@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use helix_event::{register_hook, send_blocking};
+use helix_view::{
+    events::{DocumentDidChange, DocumentDidClose},
+    handlers::{Handlers, RecoveryEvent},
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Debug)]
+pub(super) struct RecoveryHandler;
+
+impl helix_event::AsyncHook for RecoveryHandler {
+    type Event = RecoveryEvent;
+
+    fn handle_event(
+        &mut self,
+        event: Self::Event,
+        existing_debounce: Option<Instant>,
+    ) -> Option<Instant> {
+        // Keep a pending snapshot so they are written periodically while editing rather than
+        // only after a pause.
+        existing_debounce
+            .or_else(|| Some(Instant::now() + Duration::from_millis(event.snapshot_after)))
+    }
+
+    fn finish_debounce(&mut self) {
+        job::dispatch_blocking(|editor, _| crate::recovery::snapshot(editor));
+    }
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    let tx = handlers.recovery.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        let config = event.doc.config.load();
+        if config.recovery.enable {
+            send_blocking(
+                &tx,
+                RecoveryEvent {
+                    snapshot_after: config.recovery.interval,
+                },
+            );
+        }
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut DocumentDidClose<'_>| {
+        crate::recovery::discard(event.doc.id());
+        Ok(())
+    });
+}
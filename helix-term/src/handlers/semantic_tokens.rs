@@ -0,0 +1,329 @@
+use std::{collections::HashSet, time::Duration};
+
+use futures_util::{future::BoxFuture, FutureExt};
+use helix_core::syntax::config::LanguageServerFeature;
+use helix_event::{cancelable_future, register_hook};
+use helix_lsp::{lsp, util};
+use helix_view::{
+    document::DocumentSemanticTokens,
+    editor::SemanticTokensMode,
+    events::{
+        ConfigDidChange, DocumentDidChange, DocumentDidOpen, LanguageServerExited,
+        LanguageServerInitialized,
+    },
+    handlers::{lsp::SemanticTokensEvent, Handlers},
+    DocumentId, Editor,
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Default)]
+pub(super) struct SemanticTokensHandler {
+    docs: HashSet<DocumentId>,
+}
+
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+impl helix_event::AsyncHook for SemanticTokensHandler {
+    type Event = SemanticTokensEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        let SemanticTokensEvent(doc_id) = event;
+        self.docs.insert(doc_id);
+        Some(Instant::now() + DOCUMENT_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let docs = std::mem::take(&mut self.docs);
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            for doc in docs {
+                request_semantic_tokens(editor, doc);
+            }
+        });
+    }
+}
+
+/// The result id and the data of semantic tokens.
+type Tokens = (Option<String>, Vec<lsp::SemanticToken>);
+
+pub fn request_semantic_tokens(editor: &mut Editor, doc_id: DocumentId) {
+    if editor.config().lsp.semantic_tokens == SemanticTokensMode::Off {
+        return;
+    }
+
+    let Some(doc) = editor.document_mut(doc_id) else {
+        return;
+    };
+    let Some(language_server) = doc
+        .language_servers_with_feature(LanguageServerFeature::SemanticTokens)
+        .next()
+    else {
+        return;
+    };
+    let language_server_id = language_server.id();
+    let offset_encoding = language_server.offset_encoding();
+    let Some(legend) = language_server.semantic_tokens_legend().cloned() else {
+        return;
+    };
+
+    // Only the changes to the previous tokens of the same server are requested, if it supports it.
+    let previous = doc
+        .semantic_tokens
+        .as_ref()
+        .filter(|tokens| tokens.language_server_id == language_server_id)
+        .and_then(|tokens| Some((tokens.result_id.clone()?, tokens.data.clone())));
+    let delta = previous.and_then(|(result_id, mut data)| {
+        let future = language_server
+            .text_document_semantic_tokens_full_delta(doc.identifier(), result_id)?;
+        let future: BoxFuture<'static, anyhow::Result<Option<Tokens>>> = async move {
+            let tokens = match future.await? {
+                Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens)) => {
+                    (tokens.result_id, tokens.data)
+                }
+                Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+                    util::apply_semantic_tokens_edits(&mut data, delta.edits);
+                    (delta.result_id, data)
+                }
+                Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits }) => {
+                    util::apply_semantic_tokens_edits(&mut data, edits);
+                    (None, data)
+                }
+                None => return Ok(None),
+            };
+            Ok(Some(tokens))
+        }
+        .boxed();
+        Some(future)
+    });
+    let future = delta.unwrap_or_else(|| {
+        let future = language_server
+            .text_document_semantic_tokens_full(doc.identifier())
+            .unwrap();
+        async move {
+            let tokens = match future.await? {
+                Some(lsp::SemanticTokensResult::Tokens(tokens)) => {
+                    Some((tokens.result_id, tokens.data))
+                }
+                Some(lsp::SemanticTokensResult::Partial(tokens)) => Some((None, tokens.data)),
+                None => None,
+            };
+            Ok(tokens)
+        }
+        .boxed()
+    });
+
+    let text = doc.text().clone();
+    let cancel = doc.semantic_tokens_controller.restart();
+
+    tokio::spawn(async move {
+        let tokens = match cancelable_future(future, &cancel).await {
+            Some(Ok(tokens)) => tokens,
+            Some(Err(err)) => {
+                log::error!("semantic tokens request failed: {err}");
+                return;
+            }
+            // The request was cancelled.
+            None => return,
+        };
+        let tokens = tokens.map(|(result_id, data)| {
+            let mut scopes: Vec<Vec<String>> = Vec::new();
+            let mut ranges = Vec::with_capacity(data.len());
+            for (range, token) in util::lsp_semantic_tokens_to_ranges(&text, &data, offset_encoding)
+            {
+                let token_scopes = token_scopes(&legend, token);
+                let scope = match scopes.iter().position(|scopes| *scopes == token_scopes) {
+                    Some(scope) => scope,
+                    None => {
+                        scopes.push(token_scopes);
+                        scopes.len() - 1
+                    }
+                };
+                ranges.push((range, scope));
+            }
+            DocumentSemanticTokens {
+                language_server_id,
+                result_id,
+                data,
+                ranges,
+                scopes,
+            }
+        });
+        job::dispatch(move |editor, _| {
+            if cancel.is_canceled() {
+                return;
+            }
+            if let Some(doc) = editor.documents.get_mut(&doc_id) {
+                doc.semantic_tokens = tokens;
+            }
+        })
+        .await;
+    });
+}
+
+/// The theme scopes of a semantic token, most specific first: the scope of its type with each of
+/// its modifiers, like `variable.mutable`, then the scope of its type.
+fn token_scopes(legend: &lsp::SemanticTokensLegend, token: &lsp::SemanticToken) -> Vec<String> {
+    let Some(token_type) = legend.token_types.get(token.token_type as usize) else {
+        return Vec::new();
+    };
+    let scope = token_type_scope(token_type.as_str());
+    let mut scopes: Vec<_> = legend
+        .token_modifiers
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i < 32 && token.token_modifiers_bitset >> i & 1 == 1)
+        .map(|(_, modifier)| format!("{scope}.{}", kebab_case(modifier.as_str())))
+        .collect();
+    scopes.push(scope);
+    scopes
+}
+
+/// The theme scope of the standard semantic token types, which match the tree-sitter scopes of
+/// the same syntax. Other types, which language servers may add, are named in kebab-case.
+fn token_type_scope(token_type: &str) -> String {
+    let scope = match token_type {
+        "namespace" => "namespace",
+        "type" => "type",
+        "class" => "type.class",
+        "enum" => "type.enum",
+        "interface" => "type.interface",
+        "struct" => "type.struct",
+        "typeParameter" => "type.parameter",
+        "parameter" => "variable.parameter",
+        "variable" => "variable",
+        "property" => "variable.other.member",
+        "enumMember" => "type.enum.variant",
+        "event" => "variable.event",
+        "function" => "function",
+        "method" => "function.method",
+        "macro" => "function.macro",
+        "keyword" => "keyword",
+        "modifier" => "keyword.storage.modifier",
+        "comment" => "comment",
+        "string" => "string",
+        "number" => "constant.numeric",
+        "regexp" => "string.regexp",
+        "operator" => "operator",
+        "decorator" => "attribute",
+        _ => return kebab_case(token_type),
+    };
+    scope.to_string()
+}
+
+fn kebab_case(name: &str) -> String {
+    let mut kebab = String::with_capacity(name.len());
+    for ch in name.chars() {
+        if ch.is_uppercase() {
+            kebab.push('-');
+            kebab.extend(ch.to_lowercase());
+        } else {
+            kebab.push(ch);
+        }
+    }
+    kebab
+}
+
+fn request_all_semantic_tokens(editor: &mut Editor) {
+    let doc_ids: Vec<_> = editor.documents().map(|doc| doc.id()).collect();
+
+    for doc_id in doc_ids {
+        request_semantic_tokens(editor, doc_id);
+    }
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        request_semantic_tokens(event.editor, event.doc);
+
+        Ok(())
+    });
+
+    let tx = handlers.semantic_tokens.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        // Keep the tokens in place until the language server sends the new ones.
+        if let Some(tokens) = &mut event.doc.semantic_tokens {
+            event
+                .changes
+                .update_positions(tokens.ranges.iter_mut().flat_map(|(range, _)| {
+                    [
+                        (&mut range.start, helix_core::Assoc::After),
+                        (&mut range.end, helix_core::Assoc::Before),
+                    ]
+                }));
+        }
+
+        // Like document colors, avoid requesting tokens for changes the language server doesn't
+        // know about (completion).
+        if !event.ghost_transaction {
+            event.doc.semantic_tokens_controller.cancel();
+            helix_event::send_blocking(&tx, SemanticTokensEvent(event.doc.id()));
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerInitialized<'_>| {
+        request_all_semantic_tokens(event.editor);
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut LanguageServerExited<'_>| {
+        for doc in event.editor.documents_mut() {
+            if doc
+                .semantic_tokens
+                .as_ref()
+                .is_some_and(|tokens| tokens.language_server_id == event.server_id)
+            {
+                doc.semantic_tokens = None;
+            }
+        }
+        request_all_semantic_tokens(event.editor);
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut ConfigDidChange<'_>| {
+        let (old, new) = (event.old.lsp.semantic_tokens, event.new.lsp.semantic_tokens);
+        if old == SemanticTokensMode::Off && new != SemanticTokensMode::Off {
+            request_all_semantic_tokens(event.editor);
+        }
+
+        Ok(())
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn semantic_token_scopes() {
+        let legend = lsp::SemanticTokensLegend {
+            token_types: vec![
+                lsp::SemanticTokenType::VARIABLE,
+                lsp::SemanticTokenType::new("builtinType"),
+            ],
+            token_modifiers: vec![
+                lsp::SemanticTokenModifier::new("mutable"),
+                lsp::SemanticTokenModifier::DEFAULT_LIBRARY,
+            ],
+        };
+        let token = |token_type, token_modifiers_bitset| lsp::SemanticToken {
+            delta_line: 0,
+            delta_start: 0,
+            length: 1,
+            token_type,
+            token_modifiers_bitset,
+        };
+        assert_eq!(token_scopes(&legend, &token(0, 0)), ["variable"]);
+        assert_eq!(
+            token_scopes(&legend, &token(0, 0b11)),
+            ["variable.mutable", "variable.default-library", "variable"]
+        );
+        assert_eq!(token_scopes(&legend, &token(1, 0)), ["builtin-type"]);
+        assert!(token_scopes(&legend, &token(2, 0)).is_empty());
+    }
+}
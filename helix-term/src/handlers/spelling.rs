@@ -0,0 +1,235 @@
+//! Spell checking of the comments, strings and prose of documents with the hunspell dictionaries
+//! of `editor.spell-check`.
+
+use std::{
+    collections::HashSet,
+    io::Write,
+    path::PathBuf,
+    sync::{Mutex, RwLock},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use helix_core::spelling::{self, Dictionary};
+use helix_event::register_hook;
+use helix_view::{
+    events::{ConfigDidChange, DocumentDidChange, DocumentDidOpen},
+    handlers::{Handlers, SpellCheckEvent},
+    DocumentId, Editor,
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Default)]
+pub(super) struct SpellCheckHandler {
+    docs: HashSet<DocumentId>,
+}
+
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+impl helix_event::AsyncHook for SpellCheckHandler {
+    type Event = SpellCheckEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        let SpellCheckEvent(doc_id) = event;
+        self.docs.insert(doc_id);
+        Some(Instant::now() + DOCUMENT_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let docs = std::mem::take(&mut self.docs);
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            for doc in docs {
+                request_spell_check(editor, doc);
+            }
+        });
+    }
+}
+
+/// The dictionary made of the dictionaries of the names, or the error loading it, which isn't
+/// loaded again until the names change.
+type Loaded = (Vec<String>, Result<Dictionary, String>);
+
+static DICTIONARY: RwLock<Option<Loaded>> = RwLock::new(None);
+
+/// The words added with `:spell-add`, one per line.
+fn words_file() -> PathBuf {
+    helix_loader::config_dir().join("dictionary.txt")
+}
+
+/// Finds the `.aff` and `.dic` files of the dictionary `name` in the `dictionaries` directory of
+/// the runtime directories, then in the directories of the system's hunspell dictionaries.
+fn dictionary_files(name: &str) -> Option<(PathBuf, PathBuf)> {
+    let system = [
+        "/usr/share/hunspell",
+        "/usr/share/myspell",
+        "/usr/share/myspell/dicts",
+        "/usr/local/share/hunspell",
+        "/Library/Spelling",
+    ]
+    .map(PathBuf::from);
+    helix_loader::runtime_dirs()
+        .iter()
+        .map(|dir| dir.join("dictionaries"))
+        .chain(system)
+        .map(|dir| {
+            (
+                dir.join(format!("{name}.aff")),
+                dir.join(format!("{name}.dic")),
+            )
+        })
+        .find(|(aff, dic)| aff.exists() && dic.exists())
+}
+
+/// Reads a dictionary file, which may also be encoded in ISO-8859-1.
+fn read(path: PathBuf) -> anyhow::Result<String> {
+    let bytes = std::fs::read(path)?;
+    Ok(String::from_utf8(bytes)
+        .unwrap_or_else(|err| err.into_bytes().into_iter().map(char::from).collect()))
+}
+
+fn load(names: &[String]) -> anyhow::Result<Dictionary> {
+    let mut dictionary = Dictionary::default();
+    for name in names {
+        let (aff, dic) =
+            dictionary_files(name).ok_or_else(|| anyhow!("dictionary {name} not found"))?;
+        dictionary.extend(&read(aff)?, &read(dic)?);
+    }
+    if let Ok(words) = std::fs::read_to_string(words_file()) {
+        for word in words.lines().map(str::trim).filter(|word| !word.is_empty()) {
+            dictionary.add(word);
+        }
+    }
+    Ok(dictionary)
+}
+
+/// Held while a dictionary loads so that it's only loaded once.
+static LOADING: Mutex<()> = Mutex::new(());
+
+/// The dictionary made of the dictionaries `names`, loading it first if needed. Loading parses
+/// and expands the dictionaries, so it isn't done on the UI thread.
+pub fn dictionary(names: &[String]) -> anyhow::Result<Dictionary> {
+    let loaded = || {
+        DICTIONARY
+            .read()
+            .unwrap()
+            .as_ref()
+            .filter(|(loaded, _)| loaded == names)
+            .map(|(_, dictionary)| dictionary.clone())
+    };
+    let dictionary = match loaded() {
+        Some(dictionary) => dictionary,
+        None => {
+            let _loading = LOADING.lock().unwrap();
+            // The dictionary may have been loaded while waiting for the lock.
+            loaded().unwrap_or_else(|| {
+                let dictionary = load(names).map_err(|err| {
+                    log::error!("failed to load the spell checking dictionary: {err}");
+                    err.to_string()
+                });
+                *DICTIONARY.write().unwrap() = Some((names.to_vec(), dictionary.clone()));
+                dictionary
+            })
+        }
+    };
+    dictionary.map_err(|err| anyhow!("{err}"))
+}
+
+/// Adds a word to the words of the user, which are correct with any dictionaries.
+pub fn add_word(word: &str) -> anyhow::Result<()> {
+    if let Some((_, Ok(dictionary))) = DICTIONARY.write().unwrap().as_mut() {
+        dictionary.add(word);
+    }
+    std::fs::create_dir_all(helix_loader::config_dir())?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(words_file())?;
+    writeln!(file, "{word}")?;
+    Ok(())
+}
+
+pub fn request_spell_check(editor: &mut Editor, doc_id: DocumentId) {
+    let config = editor.config().spell_check.clone();
+    let Some(doc) = editor.document_mut(doc_id) else {
+        return;
+    };
+    if !config.enable || doc.is_large_file() {
+        doc.spell_check_controller.cancel();
+        doc.misspellings.clear();
+        return;
+    }
+    let prose = doc
+        .language_name()
+        .is_none_or(|language| config.prose_languages.iter().any(|prose| prose == language));
+    let text = doc.text().clone();
+    let trees = doc
+        .syntax()
+        .map(|syntax| spelling::checked_trees(syntax, prose));
+    let cancel = doc.spell_check_controller.restart();
+
+    tokio::task::spawn_blocking(move || {
+        let Ok(dictionary) = dictionary(&config.dictionaries) else {
+            return;
+        };
+        let ranges = spelling::checked_ranges(trees.as_deref(), text.slice(..), prose);
+        let misspellings = spelling::misspellings(&dictionary, text.slice(..), &ranges);
+        job::dispatch_blocking(move |editor, _| {
+            if cancel.is_canceled() {
+                return;
+            }
+            if let Some(doc) = editor.documents.get_mut(&doc_id) {
+                doc.misspellings = misspellings;
+            }
+        });
+    });
+}
+
+pub fn request_all_spell_checks(editor: &mut Editor) {
+    let doc_ids: Vec<_> = editor.documents().map(|doc| doc.id()).collect();
+
+    for doc_id in doc_ids {
+        request_spell_check(editor, doc_id);
+    }
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        request_spell_check(event.editor, event.doc);
+
+        Ok(())
+    });
+
+    let tx = handlers.spell_check.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        let misspellings = &mut event.doc.misspellings;
+        if !misspellings.is_empty() {
+            event
+                .changes
+                .update_positions(misspellings.iter_mut().flat_map(|range| {
+                    [
+                        (&mut range.start, helix_core::Assoc::After),
+                        (&mut range.end, helix_core::Assoc::Before),
+                    ]
+                }));
+            misspellings.retain(|range| !range.is_empty());
+        }
+
+        if !event.ghost_transaction && event.doc.config.load().spell_check.enable {
+            event.doc.spell_check_controller.cancel();
+            helix_event::send_blocking(&tx, SpellCheckEvent(event.doc.id()));
+        }
+
+        Ok(())
+    });
+
+    register_hook!(move |event: &mut ConfigDidChange<'_>| {
+        if event.old.spell_check != event.new.spell_check {
+            request_all_spell_checks(event.editor);
+        }
+
+        Ok(())
+    });
+}
@@ -1,9 +1,9 @@
 use helix_event::status::StatusMessage;
 use helix_event::{runtime_local, send_blocking};
-use helix_view::Editor;
+use helix_view::{editor::ReplayedMacro, input::KeyEvent, Editor};
 use once_cell::sync::OnceCell;
 
-use crate::compositor::Compositor;
+use crate::{commands::MappableCommand, compositor::Compositor};
 
 use futures_util::future::{BoxFuture, Future, FutureExt};
 use futures_util::stream::{FuturesUnordered, StreamExt};
@@ -27,6 +27,10 @@ pub async fn dispatch(job: impl FnOnce(&mut Editor, &mut Compositor) + Send + 's
         .await;
 }
 
+pub fn dispatch_callback_blocking(job: Callback) {
+    send_blocking(JOB_QUEUE.wait(), job)
+}
+
 pub fn dispatch_blocking(job: impl FnOnce(&mut Editor, &mut Compositor) + Send + 'static) {
     let jobs = JOB_QUEUE.wait();
     send_blocking(jobs, Callback::EditorCompositor(Box::new(job)))
@@ -35,6 +39,15 @@ pub fn dispatch_blocking(job: impl FnOnce(&mut Editor, &mut Compositor) + Send +
 pub enum Callback {
     EditorCompositor(EditorCompositorCallback),
     Editor(EditorCallback),
+    /// Runs while the editor has released the terminal, so that the callback can hand it over
+    /// to an interactive child process. The UI is redrawn once the callback returns.
+    Suspended(EditorCallback),
+    /// Replays the keys of a saved macro as if they were typed. The macros are the ones under
+    /// replay while the keys are handled, see [helix_view::Editor::macro_replaying].
+    Macro(Vec<ReplayedMacro>, Vec<KeyEvent>),
+    /// Runs the commands one after the other, for example those of a custom command run from
+    /// the command line.
+    Commands(Vec<MappableCommand>),
 }
 
 pub type JobFuture = BoxFuture<'static, anyhow::Result<Option<Callback>>>;
@@ -109,7 +122,11 @@ pub fn handle_callback(
             Ok(None) => {}
             Ok(Some(call)) => match call {
                 Callback::EditorCompositor(call) => call(editor, compositor),
-                Callback::Editor(call) => call(editor),
+                // Releasing the terminal is handled by the `Application` before it gets here.
+                Callback::Editor(call) | Callback::Suspended(call) => call(editor),
+                // Macros and commands are handled by the `Application`, which has the jobs for
+                // the compositor.
+                Callback::Macro(..) | Callback::Commands(_) => (),
             },
             Err(e) => {
                 editor.set_error(format!("Async job failed: {}", e));
@@ -0,0 +1,105 @@
+//! Saves the jumplist of the focused view when the editor exits and restores it at startup, so
+//! that `C-o` jumps back to the locations of the previous session in the same workspace.
+//!
+//! Each workspace gets its own JSON file in the `jumplists` directory of the state directory,
+//! named after the workspace's path. Only jumps into files are saved. The files are only opened
+//! once they're jumped back to.
+
+use std::path::PathBuf;
+
+use helix_core::{Range, Selection};
+use helix_view::{view::SavedJump, Editor};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Jump {
+    path: PathBuf,
+    /// The anchor and head of each range of the selection.
+    ranges: Vec<(usize, usize)>,
+    primary_index: usize,
+}
+
+fn jumplist_file() -> PathBuf {
+    crate::state::workspace_file("jumplists")
+}
+
+/// Saves the jumplist of the focused view, or of the last closed view if none is left.
+pub fn save(editor: &Editor) {
+    if !crate::state::is_enabled(editor.config().persistent_jumplist) {
+        return;
+    }
+    let Some(jumps) = editor
+        .tree
+        .try_get(editor.tree.focus)
+        .map(|view| &view.jumps)
+        .or(editor.closed_jumps.as_ref())
+    else {
+        return;
+    };
+    let saved = jumps
+        .saved()
+        .iter()
+        .map(|jump| (jump.path.clone(), &jump.selection));
+    let opened = jumps.iter().filter_map(|(doc_id, selection)| {
+        let path = editor.document(*doc_id)?.path()?.clone();
+        Some((path, selection))
+    });
+    let jumps: Vec<_> = saved
+        .chain(opened)
+        .map(|(path, selection)| Jump {
+            path,
+            ranges: selection
+                .ranges()
+                .iter()
+                .map(|range| (range.anchor, range.head))
+                .collect(),
+            primary_index: selection.primary_index(),
+        })
+        .collect();
+    let file = jumplist_file();
+    let result = file
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(anyhow::Error::from)
+        .and_then(|_| Ok(std::fs::write(&file, serde_json::to_string(&jumps)?)?));
+    if let Err(err) = result {
+        log::error!("failed to save the jumplist: {err}");
+    }
+}
+
+/// Inserts the jumps of the saved jumplist before the ones of the focused view. Jumps into files
+/// which no longer exist are dropped.
+pub fn restore(editor: &mut Editor) {
+    if !crate::state::is_enabled(editor.config().persistent_jumplist) {
+        return;
+    }
+    let Ok(jumps) = std::fs::read_to_string(jumplist_file()) else {
+        return;
+    };
+    let jumps: Vec<Jump> = match serde_json::from_str(&jumps) {
+        Ok(jumps) => jumps,
+        Err(err) => {
+            log::error!("failed to read the saved jumplist: {err}");
+            return;
+        }
+    };
+    let jumps = jumps
+        .into_iter()
+        .filter(|jump| jump.path.is_file() && !jump.ranges.is_empty())
+        .map(|jump| {
+            let ranges = jump
+                .ranges
+                .iter()
+                .map(|&(anchor, head)| Range::new(anchor, head))
+                .collect();
+            let primary_index = jump.primary_index.min(jump.ranges.len() - 1);
+            SavedJump {
+                path: jump.path,
+                selection: Selection::new(ranges, primary_index),
+            }
+        })
+        .collect();
+    let view_id = editor.tree.focus;
+    editor.tree.get_mut(view_id).jumps.prepend_saved(jumps);
+}
@@ -208,6 +208,15 @@ fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
 }
 
 impl KeyTrie {
+    /// Calls `f` with every command bound in the trie, including the commands of sequences.
+    pub fn for_each_command(&self, f: &mut impl FnMut(&MappableCommand)) {
+        match self {
+            KeyTrie::MappableCommand(command) => f(command),
+            KeyTrie::Sequence(commands) => commands.iter().for_each(f),
+            KeyTrie::Node(node) => node.map.values().for_each(|trie| trie.for_each_command(f)),
+        }
+    }
+
     pub fn reverse_map(&self) -> ReverseKeymap {
         // recursively visit all nodes in keymap
         fn map_node(cmd_map: &mut ReverseKeymap, node: &KeyTrie, keys: &mut Vec<KeyEvent>) {
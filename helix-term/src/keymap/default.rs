@@ -108,6 +108,8 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
             "a" => select_textobject_around,
             "i" => select_textobject_inner,
         },
+        "M" => set_global_mark,
+        "'" => goto_global_mark,
         "[" => { "Left bracket"
             "d" => goto_prev_diag,
             "D" => goto_first_diag,
@@ -156,6 +158,8 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
         "p" => paste_after,
         // paste_all
         "P" => paste_before,
+        "A-P" => paste_from_history,
+        "C-p" => paste_previous_yank,
 
         "Q" => record_macro,
         "q" => replay_macro,
@@ -175,6 +179,7 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
         // "Q" => replay_macro,
 
         "&" => align_selections,
+        "C-v" => select_block,
         "_" => trim_selections,
 
         "(" => rotate_selections_backward,
@@ -229,12 +234,16 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
             "E" => file_explorer_in_current_buffer_directory,
             "b" => buffer_picker,
             "j" => jumplist_picker,
+            "m" => global_mark_picker,
+            "u" => undo_tree_picker,
+            "U" => unicode_picker,
             "s" => lsp_or_syntax_symbol_picker,
             "S" => lsp_or_syntax_workspace_symbol_picker,
             "d" => diagnostics_picker,
             "D" => workspace_diagnostics_picker,
             "g" => changed_file_picker,
             "a" => code_action,
+            "A" => source_action,
             "'" => last_picker,
             "G" => { "Debug (experimental)" sticky=true
                 "l" => dap_launch,
@@ -247,7 +256,9 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
                 "n" => dap_next,
                 "v" => dap_variables,
                 "t" => dap_terminate,
+                "B" => dap_edit_breakpoint,
                 "C-c" => dap_edit_condition,
+                "C-n" => dap_edit_hit_condition,
                 "C-l" => dap_edit_log,
                 "s" => { "Switch"
                     "t" => dap_switch_thread,
@@ -304,6 +315,11 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
             "C-f" | "pagedown" => page_down,
             "C-u" | "backspace" => page_cursor_half_up,
             "C-d" | "space" => page_cursor_half_down,
+            "a" => toggle_fold,
+            "f" => fold,
+            "o" => unfold,
+            "M" => fold_all,
+            "R" => unfold_all,
 
             "/" => search,
             "?" => rsearch,
@@ -321,6 +337,11 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
             "C-f" | "pagedown" => page_down,
             "C-u" | "backspace" => page_cursor_half_up,
             "C-d" | "space" => page_cursor_half_down,
+            "a" => toggle_fold,
+            "f" => fold,
+            "o" => unfold,
+            "M" => fold_all,
+            "R" => unfold_all,
 
             "/" => search,
             "?" => rsearch,
@@ -384,16 +405,19 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
         "C-s" => commit_undo_checkpoint,
         "C-x" => completion,
         "C-r" => insert_register,
+        "C-v" => insert_unicode,
 
         "C-w" | "A-backspace" => delete_word_backward,
         "A-d" | "A-del" => delete_word_forward,
         "C-u" => kill_to_line_start,
-        "C-k" => kill_to_line_end,
+        "C-k" => insert_digraph,
         "C-h" | "backspace" | "S-backspace" => delete_char_backward,
         "C-d" | "del" => delete_char_forward,
         "C-j" | "ret" => insert_newline,
         "tab" => smart_tab,
         "S-tab" => insert_tab,
+        "A-n" => goto_next_tabstop,
+        "A-p" => goto_prev_tabstop,
 
         "up" => move_visual_line_up,
         "down" => move_visual_line_down,
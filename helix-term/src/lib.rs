@@ -9,8 +9,17 @@
 pub mod events;
 pub mod health;
 pub mod job;
+pub mod jumplist;
 pub mod keymap;
+pub mod marks;
+pub mod recent;
+pub mod recovery;
+pub mod registers;
+pub mod remote;
+pub mod state;
+pub mod trust;
 pub mod ui;
+pub mod undo;
 
 #[cfg(not(windows))]
 use std::env::var_os;
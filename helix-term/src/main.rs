@@ -75,6 +75,10 @@ async fn main_impl() -> Result<i32> {
     --vsplit                       Split all given files vertically into different windows
     --hsplit                       Split all given files horizontally into different windows
     -w, --working-dir <path>       Specify an initial working directory
+    --remote                       Open the given files in the most recently started editor
+                                   (or the one whose socket is named by $HELIX_SERVER)
+    --remote-wait                  Like --remote, but wait until the files are closed
+    --remote-send <command>        Run a command such as ':write' in a running editor
     +[N]                           Open the first given file at line number N, or the last line, if
                                    N is not specified.
 ",
@@ -104,6 +108,10 @@ async fn main_impl() -> Result<i32> {
         std::process::exit(0);
     }
 
+    if args.remote {
+        return helix_term::remote::send(args);
+    }
+
     if args.fetch_grammars {
         helix_loader::grammar::fetch_grammars()?;
         return Ok(0);
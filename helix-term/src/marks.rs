@@ -0,0 +1,280 @@
+//! Global marks: named positions in files, set with `M` followed by an uppercase letter and
+//! jumped to with `'` followed by the letter, like Vim's file marks.
+//!
+//! The marks of each workspace are kept in a JSON file in the `marks` directory of the state
+//! directory, named after the workspace's path. Marks in open documents follow their edits and
+//! are moved in the file once the document is written.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::{bail, Context as _};
+use helix_core::{coords_at_pos, pos_at_coords, Assoc, ChangeSet, Position, Rope, Selection};
+use helix_view::{
+    align_view, current, current_ref,
+    editor::Action,
+    events::{DocumentDidChange, DocumentDidOpen, DocumentDidSave},
+    Align, Editor,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::ui;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Mark {
+    pub path: PathBuf,
+    /// The 0-based line and column of the cursor.
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The marks known to this editor.
+#[derive(Default)]
+struct Marks {
+    marks: BTreeMap<char, Mark>,
+    /// The marks which were moved by edits since their document was written.
+    moved: BTreeSet<char>,
+}
+
+/// The marks are loaded once the first document is opened, so that edits can move them.
+static MARKS: Mutex<Option<Marks>> = Mutex::new(None);
+
+fn marks_file() -> PathBuf {
+    crate::state::workspace_file("marks")
+}
+
+/// Whether marks can be named `name`.
+pub fn is_valid_name(name: char) -> bool {
+    name.is_ascii_uppercase()
+}
+
+/// Loads the marks of the workspace, which are empty if none were set or they can't be read.
+/// Marks moved by edits which aren't written yet are at their new positions.
+pub fn load() -> BTreeMap<char, Mark> {
+    let mut marks: BTreeMap<char, Mark> = crate::state::read(&marks_file());
+    let mut known = MARKS.lock().unwrap();
+    let known = known.get_or_insert_with(Marks::default);
+    for name in &known.moved {
+        marks.insert(*name, known.marks[name].clone());
+    }
+    known.marks = marks.clone();
+    marks
+}
+
+/// Sets the mark `name` to the cursor of the current document.
+pub fn set(editor: &Editor, name: char) -> anyhow::Result<()> {
+    if !is_valid_name(name) {
+        bail!("Invalid mark '{name}', marks are named A to Z");
+    }
+    let (view, doc) = current_ref!(editor);
+    let Some(path) = doc.path() else {
+        bail!("Cannot set a mark in a buffer without a path");
+    };
+    let text = doc.text().slice(..);
+    let Position { row, col } = coords_at_pos(text, doc.selection(view.id).primary().cursor(text));
+    let mark = Mark {
+        path: path.clone(),
+        line: row,
+        column: col,
+    };
+
+    crate::state::update(&marks_file(), |marks: &mut BTreeMap<char, Mark>| {
+        marks.insert(name, mark.clone());
+    })?;
+    let mut known = MARKS.lock().unwrap();
+    let known = known.get_or_insert_with(Marks::default);
+    known.marks.insert(name, mark);
+    known.moved.remove(&name);
+    Ok(())
+}
+
+/// Moves `mark` through the `changes` from `old_text` to `text`. Returns whether it moved.
+fn map_mark(mark: &mut Mark, old_text: &Rope, text: &Rope, changes: &ChangeSet) -> bool {
+    let pos = pos_at_coords(
+        old_text.slice(..),
+        Position::new(mark.line, mark.column),
+        true,
+    );
+    let pos = changes.map_pos(pos, Assoc::After);
+    let Position { row, col } = coords_at_pos(text.slice(..), pos);
+    let moved = (row, col) != (mark.line, mark.column);
+    mark.line = row;
+    mark.column = col;
+    moved
+}
+
+/// Writes the marks in the file at `path` which were moved by edits.
+fn write_moved(path: &Path) {
+    let moved: Vec<_> = {
+        let mut known = MARKS.lock().unwrap();
+        let Some(known) = known.as_mut() else {
+            return;
+        };
+        let moved: Vec<_> = (known.moved.iter())
+            .filter(|name| known.marks[name].path == *path)
+            .map(|name| (*name, known.marks[name].clone()))
+            .collect();
+        for (name, _) in &moved {
+            known.moved.remove(name);
+        }
+        moved
+    };
+    if moved.is_empty() {
+        return;
+    }
+    tokio::task::spawn_blocking(move || {
+        let result = crate::state::update(&marks_file(), |marks: &mut BTreeMap<char, Mark>| {
+            for (name, mark) in moved {
+                // Another editor may have set the mark elsewhere in the meantime.
+                if marks
+                    .get(&name)
+                    .is_some_and(|saved| saved.path == mark.path)
+                {
+                    marks.insert(name, mark);
+                }
+            }
+        });
+        if let Err(err) = result {
+            log::error!("failed to save the moved marks: {err}");
+        }
+    });
+}
+
+pub(crate) fn register_hooks() {
+    helix_event::register_hook!(move |_event: &mut DocumentDidOpen<'_>| {
+        if MARKS.lock().unwrap().is_none() {
+            tokio::task::spawn_blocking(load);
+        }
+        Ok(())
+    });
+
+    helix_event::register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        let Some(path) = event.doc.path() else {
+            return Ok(());
+        };
+        let mut known = MARKS.lock().unwrap();
+        let Some(known) = known.as_mut() else {
+            return Ok(());
+        };
+        for (name, mark) in &mut known.marks {
+            if mark.path == *path && map_mark(mark, event.old_text, event.doc.text(), event.changes)
+            {
+                known.moved.insert(*name);
+            }
+        }
+        Ok(())
+    });
+
+    helix_event::register_hook!(move |event: &mut DocumentDidSave<'_>| {
+        if let Some(path) = event.editor.document(event.doc).and_then(|doc| doc.path()) {
+            write_moved(path);
+        }
+        Ok(())
+    });
+}
+
+/// Opens the file of the mark and moves the cursor to it, which adds a jump to the jumplist.
+pub fn goto(editor: &mut Editor, mark: &Mark, action: Action) -> anyhow::Result<()> {
+    editor
+        .open(&mark.path, action)
+        .with_context(|| format!("Failed to open {}", mark.path.display()))?;
+    let (view, doc) = current!(editor);
+    let pos = pos_at_coords(
+        doc.text().slice(..),
+        Position::new(mark.line, mark.column),
+        true,
+    );
+    doc.set_selection(view.id, Selection::point(pos));
+    align_view(doc, view, Align::Center);
+    Ok(())
+}
+
+/// Jumps to the mark `name`.
+pub fn goto_name(editor: &mut Editor, name: char) -> anyhow::Result<()> {
+    let Some(mark) = load().remove(&name) else {
+        bail!("Mark '{name}' is not set");
+    };
+    goto(editor, &mark, Action::Replace)
+}
+
+/// Lists the marks of the workspace, previewing their lines.
+pub fn picker() -> ui::Picker<(char, Mark), ()> {
+    let columns = [
+        ui::PickerColumn::new("mark", |(name, _): &(char, Mark), _| {
+            name.to_string().into()
+        }),
+        ui::PickerColumn::new("path", |(_, mark): &(char, Mark), _| {
+            helix_stdx::path::get_relative_path(&mark.path)
+                .to_string_lossy()
+                .into_owned()
+                .into()
+        }),
+        ui::PickerColumn::new("line", |(_, mark): &(char, Mark), _| {
+            (mark.line + 1).to_string().into()
+        }),
+    ];
+    ui::Picker::new(columns, 1, load(), (), |cx, (_, mark), action| {
+        if let Err(err) = goto(cx.editor, mark, action) {
+            cx.editor.set_error(err.to_string());
+        }
+    })
+    .with_preview(|_editor, (_, mark)| {
+        Some((mark.path.as_path().into(), Some((mark.line, mark.line))))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use helix_core::Transaction;
+
+    #[test]
+    fn valid_names() {
+        assert!(is_valid_name('A'));
+        assert!(!is_valid_name('a'));
+        assert!(!is_valid_name('1'));
+    }
+
+    #[test]
+    fn marks_follow_edits() {
+        let old_text = Rope::from("one\ntwo\nthree\n");
+        let mut mark = Mark {
+            path: PathBuf::from("file"),
+            line: 1,
+            column: 2,
+        };
+
+        // Inserting a line above moves the mark down.
+        let transaction =
+            Transaction::change(&old_text, [(0, 0, Some("zero\n".into()))].into_iter());
+        let mut text = old_text.clone();
+        transaction.apply(&mut text);
+        assert!(map_mark(&mut mark, &old_text, &text, transaction.changes()));
+        assert_eq!((mark.line, mark.column), (2, 2));
+
+        // Edits after the mark don't move it.
+        let old_text = text;
+        let transaction = Transaction::change(&old_text, [(14, 19, None)].into_iter());
+        let mut text = old_text.clone();
+        transaction.apply(&mut text);
+        assert!(!map_mark(
+            &mut mark,
+            &old_text,
+            &text,
+            transaction.changes()
+        ));
+        assert_eq!((mark.line, mark.column), (2, 2));
+
+        // Deleting the text of the mark moves it to the start of the deletion.
+        let old_text = text;
+        let transaction = Transaction::change(&old_text, [(5, 14, None)].into_iter());
+        let mut text = old_text.clone();
+        transaction.apply(&mut text);
+        assert!(map_mark(&mut mark, &old_text, &text, transaction.changes()));
+        assert_eq!((mark.line, mark.column), (1, 0));
+    }
+}
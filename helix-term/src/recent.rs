@@ -0,0 +1,99 @@
+//! Remembers the files and workspaces opened recently so the dashboard can offer them.
+//!
+//! The lists are kept in `recent.json` in the state directory.
+
+use std::path::{Path, PathBuf};
+
+use helix_view::events::DocumentDidOpen;
+use serde::{Deserialize, Serialize};
+
+/// The number of entries kept in each list.
+const MAX_ENTRIES: usize = 50;
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Recent {
+    /// Most recently opened first.
+    pub files: Vec<PathBuf>,
+    /// Most recently opened first.
+    pub workspaces: Vec<PathBuf>,
+}
+
+impl Recent {
+    /// Loads the lists, which are empty if they were never saved or can't be read.
+    pub fn load() -> Self {
+        crate::state::read(&recent_file())
+    }
+
+    /// Drops the paths which no longer exist.
+    pub fn retain_existing(&mut self) {
+        self.files.retain(|path| path.is_file());
+        self.workspaces.retain(|path| path.is_dir());
+    }
+
+    fn update(f: impl FnOnce(&mut Self)) -> anyhow::Result<()> {
+        crate::state::update(&recent_file(), f)
+    }
+}
+
+fn recent_file() -> PathBuf {
+    helix_loader::state_dir().join("recent.json")
+}
+
+/// Moves `path` to the front of the list.
+fn push(list: &mut Vec<PathBuf>, path: &Path) {
+    list.retain(|entry| entry != path);
+    list.insert(0, path.to_owned());
+    list.truncate(MAX_ENTRIES);
+}
+
+/// Records the workspace of the current working directory, unless it is only the working
+/// directory itself.
+pub fn add_current_workspace() {
+    let (workspace, is_cwd) = helix_loader::find_workspace();
+    if is_cwd || !crate::state::is_enabled(true) {
+        return;
+    }
+    if let Err(err) = Recent::update(|recent| push(&mut recent.workspaces, &workspace)) {
+        log::error!("failed to record the recent workspace: {err}");
+    }
+}
+
+pub(crate) fn register_hooks() {
+    helix_event::register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        if !crate::state::is_enabled(event.editor.config().dashboard.enable) {
+            return Ok(());
+        }
+        if let Some(path) = event.editor.documents[&event.doc].path().cloned() {
+            tokio::task::spawn_blocking(move || {
+                if let Err(err) = Recent::update(|recent| push(&mut recent.files, &path)) {
+                    log::error!("failed to record the recent file: {err}");
+                }
+            });
+        }
+        Ok(())
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn push_moves_to_front() {
+        let mut list = vec![PathBuf::from("a"), PathBuf::from("b")];
+        push(&mut list, Path::new("b"));
+        assert_eq!(list, [PathBuf::from("b"), PathBuf::from("a")]);
+
+        push(&mut list, Path::new("c"));
+        assert_eq!(
+            list,
+            [PathBuf::from("c"), PathBuf::from("b"), PathBuf::from("a")]
+        );
+
+        list.extend((0..MAX_ENTRIES).map(|i| PathBuf::from(i.to_string())));
+        push(&mut list, Path::new("d"));
+        assert_eq!(list.len(), MAX_ENTRIES);
+        assert_eq!(list[0], PathBuf::from("d"));
+    }
+}
@@ -0,0 +1,266 @@
+//! Snapshots of modified documents which weren't saved, to recover them after a crash.
+//!
+//! A while after each change the text of every modified document is written to the `recovery`
+//! directory in the state directory, named after the process and the document. The snapshot of
+//! a document is removed when it is saved or closed and all snapshots of the editor are removed
+//! when it exits, so the ones of editors which are no longer running were left by a crash. They
+//! are offered at startup and by `:recover`.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard,
+    },
+    time::{Duration, SystemTime},
+};
+
+use helix_core::{
+    diff::{compare_ropes, unified_diff},
+    Rope,
+};
+use helix_view::{
+    doc_mut, document::SCRATCH_BUFFER_NAME, editor::Action, view_mut, DocumentId, Editor,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{commands, ui};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Snapshot {
+    /// The path of the document or `None` for a scratch buffer.
+    path: Option<PathBuf>,
+    /// Seconds since the unix epoch.
+    time: u64,
+    text: String,
+}
+
+/// A snapshot left behind by an editor which is no longer running.
+pub struct Leftover {
+    file: PathBuf,
+    path: Option<PathBuf>,
+    time: SystemTime,
+    text: String,
+    /// The changes of the snapshot to the file on disk as a unified diff.
+    diff: String,
+}
+
+fn recovery_dir() -> PathBuf {
+    helix_loader::state_dir().join("recovery")
+}
+
+fn snapshot_file(doc_id: DocumentId) -> PathBuf {
+    recovery_dir().join(format!("{}-{doc_id}.json", std::process::id()))
+}
+
+/// Held while snapshots are written or removed.
+static WRITING: Mutex<()> = Mutex::new(());
+
+/// Set once the snapshots of this editor were removed when it exits, after which snapshots which
+/// are still being written in the background are dropped.
+static EXITED: AtomicBool = AtomicBool::new(false);
+
+fn lock() -> MutexGuard<'static, ()> {
+    WRITING.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Writes the snapshots of the modified documents and removes the ones of unmodified documents.
+/// The files are written by a blocking task.
+pub fn snapshot(editor: &Editor) {
+    if !crate::state::is_enabled(editor.config().recovery.enable) {
+        return;
+    }
+    let docs: Vec<_> = editor
+        .documents()
+        .map(|doc| {
+            // Snapshots of large files would take too long to write.
+            let modified = (doc.is_modified() && !doc.is_large_file()).then(|| ModifiedDoc {
+                path: doc.path().cloned(),
+                text: doc.text().clone(),
+                name: doc.display_name().into_owned(),
+            });
+            (snapshot_file(doc.id()), modified)
+        })
+        .collect();
+    tokio::task::spawn_blocking(move || write_snapshots(docs));
+}
+
+/// A modified document whose snapshot is written.
+struct ModifiedDoc {
+    path: Option<PathBuf>,
+    text: Rope,
+    name: String,
+}
+
+/// Writes the snapshot files of the modified documents and removes the other files.
+fn write_snapshots(docs: Vec<(PathBuf, Option<ModifiedDoc>)>) {
+    let _writing = lock();
+    if EXITED.load(Ordering::Acquire) {
+        return;
+    }
+    if let Err(err) = std::fs::create_dir_all(recovery_dir()) {
+        log::error!("failed to create the recovery directory: {err}");
+        return;
+    }
+    for (file, doc) in docs {
+        let Some(ModifiedDoc { path, text, name }) = doc else {
+            let _ = std::fs::remove_file(file);
+            continue;
+        };
+        let snapshot = Snapshot {
+            path,
+            time: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs()),
+            text: text.to_string(),
+        };
+        let result = serde_json::to_string(&snapshot)
+            .map_err(anyhow::Error::from)
+            .and_then(|snapshot| crate::state::write(&file, snapshot));
+        if let Err(err) = result {
+            log::error!("failed to write the recovery snapshot of {name}: {err}");
+        }
+    }
+}
+
+/// Removes the snapshot of the document.
+pub fn discard(doc_id: DocumentId) {
+    let _writing = lock();
+    let _ = std::fs::remove_file(snapshot_file(doc_id));
+}
+
+/// Removes the snapshots of this editor.
+pub fn discard_all() {
+    let _writing = lock();
+    EXITED.store(true, Ordering::Release);
+    let prefix = format!("{}-", std::process::id());
+    let Ok(entries) = std::fs::read_dir(recovery_dir()) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with(&prefix) {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    // SAFETY: sending no signal only checks whether the process exists.
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn is_running(pid: u32) -> bool {
+    pid == std::process::id()
+}
+
+/// Lists the snapshots of editors which are no longer running, most recent first.
+pub fn leftovers() -> Vec<Leftover> {
+    let Ok(entries) = std::fs::read_dir(recovery_dir()) else {
+        return Vec::new();
+    };
+    let mut leftovers: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file = entry.path();
+            if file.extension()? != "json" {
+                return None;
+            }
+            let pid = file
+                .file_stem()?
+                .to_str()?
+                .split_once('-')?
+                .0
+                .parse()
+                .ok()?;
+            if is_running(pid) {
+                return None;
+            }
+            let snapshot: Snapshot =
+                serde_json::from_str(&std::fs::read_to_string(&file).ok()?).ok()?;
+            let diff = diff(snapshot.path.as_deref(), &snapshot.text);
+            Some(Leftover {
+                file,
+                path: snapshot.path,
+                time: SystemTime::UNIX_EPOCH + Duration::from_secs(snapshot.time),
+                text: snapshot.text,
+                diff,
+            })
+        })
+        .collect();
+    leftovers.sort_unstable_by(|a, b| b.time.cmp(&a.time));
+    leftovers
+}
+
+/// The changes of the snapshot to the file on disk, which is empty if it doesn't exist.
+fn diff(path: Option<&Path>, text: &str) -> String {
+    let before = path
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    let name = path.map_or(SCRATCH_BUFFER_NAME.into(), |path| path.to_string_lossy());
+    format!(
+        "--- {name}\n+++ {name} (recovered)\n{}",
+        unified_diff(&before, text)
+    )
+}
+
+impl Leftover {
+    fn discard(&self) {
+        let _ = std::fs::remove_file(&self.file);
+    }
+
+    /// Opens the document of the snapshot and replaces its text with the snapshot, as a change
+    /// which can be undone or written.
+    fn recover(&self, editor: &mut Editor, action: Action) -> anyhow::Result<()> {
+        let doc_id = match &self.path {
+            Some(path) => editor.open(path, action)?,
+            None => editor.new_file(action),
+        };
+        let view = view_mut!(editor);
+        let doc = doc_mut!(editor, &doc_id);
+        let transaction = compare_ropes(doc.text(), &Rope::from(self.text.as_str()));
+        doc.apply(&transaction, view.id);
+        doc.append_changes_to_history(view);
+        self.discard();
+        Ok(())
+    }
+}
+
+/// Removes the snapshots of editors which are no longer running.
+pub fn discard_leftovers() -> usize {
+    let leftovers = leftovers();
+    for leftover in &leftovers {
+        leftover.discard();
+    }
+    leftovers.len()
+}
+
+/// Lists the snapshots which can be recovered, previewing their changes to the files on disk.
+pub fn picker(leftovers: Vec<Leftover>) -> ui::Picker<Leftover, ()> {
+    let columns = [
+        ui::PickerColumn::new("path", |leftover: &Leftover, _| {
+            leftover
+                .path
+                .as_deref()
+                .map_or(SCRATCH_BUFFER_NAME.into(), |path| {
+                    helix_stdx::path::get_relative_path(path)
+                        .to_string_lossy()
+                        .into_owned()
+                })
+                .into()
+        }),
+        ui::PickerColumn::new("saved", |leftover: &Leftover, _| {
+            let age = leftover.time.elapsed().unwrap_or_default();
+            commands::format_age(age).into()
+        }),
+    ];
+    ui::Picker::new(columns, 0, leftovers, (), |cx, leftover, action| {
+        if let Err(err) = leftover.recover(cx.editor, action) {
+            cx.editor.set_error(format!("Failed to recover: {err}"));
+        }
+    })
+    .with_preview(|_editor, leftover| Some((ui::picker::PathOrId::Text(&leftover.diff), None)))
+}
@@ -0,0 +1,143 @@
+//! Saves the search register and the histories of the command and shell prompts when the editor
+//! exits and restores them at startup. Registers holding yanks may contain secrets, so the named
+//! registers are only saved when they are listed in the config.
+//!
+//! The registers are kept in a JSON file in the state directory, which only the user can read.
+//! The values saved by an editor are merged with the ones saved by the others before it.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use helix_view::{editor::PersistentRegistersConfig, Editor};
+
+/// The registers which are always saved.
+const SAVED_REGISTERS: &[char] = &['/', ':', '|'];
+
+/// Whether the register `name` is saved with `config`.
+fn is_saved(name: char, config: &PersistentRegistersConfig) -> bool {
+    SAVED_REGISTERS.contains(&name) || config.registers.contains(&name)
+}
+
+type SavedRegisters = BTreeMap<char, Vec<String>>;
+
+fn registers_file() -> PathBuf {
+    helix_loader::state_dir().join("registers.json")
+}
+
+/// Saves the values of the registers within the configured limits.
+pub fn save(editor: &Editor) {
+    let config = editor.config().persistent_registers.clone();
+    if !crate::state::is_enabled(config.enable) {
+        return;
+    }
+    let path = registers_file();
+    let saved = read(&path).unwrap_or_default();
+    let registers = merge(saved, editor.registers.saved_values(), &config);
+    let result = serde_json::to_string(&registers)
+        .map_err(anyhow::Error::from)
+        .and_then(|registers| {
+            std::fs::create_dir_all(helix_loader::state_dir())?;
+            crate::state::write(&path, registers)
+        });
+    if let Err(err) = result {
+        log::error!("failed to save the registers: {err}");
+    }
+}
+
+/// Restores the registers saved by a previous session.
+pub fn restore(editor: &mut Editor) {
+    let config = editor.config().persistent_registers.clone();
+    if !crate::state::is_enabled(config.enable) {
+        return;
+    }
+    let Some(registers) = read(&registers_file()) else {
+        return;
+    };
+    for (name, values) in registers {
+        if is_saved(name, &config) {
+            editor.registers.restore(name, values);
+        }
+    }
+}
+
+fn read(path: &Path) -> Option<SavedRegisters> {
+    let registers = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&registers)
+        .inspect_err(|err| log::error!("failed to read the saved registers: {err}"))
+        .ok()
+}
+
+/// Merges the values of the registers of this editor into the ones saved before, keeping the
+/// registers saved with `config` within its limits. The values of this editor are the newest.
+fn merge<'a>(
+    mut saved: SavedRegisters,
+    registers: impl Iterator<Item = (char, &'a [String])>,
+    config: &PersistentRegistersConfig,
+) -> SavedRegisters {
+    for (name, values) in registers {
+        let merged = saved.entry(name).or_default();
+        merged.retain(|value| !values.contains(value));
+        merged.extend(values.iter().cloned());
+    }
+    saved.retain(|name, _| is_saved(*name, config));
+    for values in saved.values_mut() {
+        values.retain(|value| value.len() <= config.max_value_size);
+        // The newest values are last.
+        let start = values.len().saturating_sub(config.max_values);
+        values.drain(..start);
+    }
+    saved
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn values(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn merge_registers() {
+        let mut config = PersistentRegistersConfig {
+            enable: true,
+            max_values: 3,
+            max_value_size: 5,
+            registers: Vec::new(),
+        };
+        let saved = BTreeMap::from([(':', values(&["w", "q", "sh ls"])), ('a', values(&["a"]))]);
+        let commands = values(&["q", "o foo", "too long"]);
+        let yanks = values(&["secret"]);
+        let search = values(&["fn"]);
+        let registers = [
+            (':', commands.as_slice()),
+            ('"', yanks.as_slice()),
+            ('/', search.as_slice()),
+        ];
+
+        let merged = merge(saved.clone(), registers.into_iter(), &config);
+        assert_eq!(
+            merged,
+            BTreeMap::from([
+                ('/', values(&["fn"])),
+                (':', values(&["sh ls", "q", "o foo"])),
+            ])
+        );
+
+        // Named registers are saved when they are configured.
+        config.registers = vec!['a', 'b'];
+        let named = values(&["named"]);
+        let registers = [('b', named.as_slice()), ('"', yanks.as_slice())];
+        let merged = merge(saved, registers.into_iter(), &config);
+        assert_eq!(
+            merged,
+            BTreeMap::from([
+                (':', values(&["w", "q", "sh ls"])),
+                ('a', values(&["a"])),
+                ('b', values(&["named"])),
+            ])
+        );
+    }
+}
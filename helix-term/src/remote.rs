@@ -0,0 +1,255 @@
+//! Lets other `hx` processes drive a running editor with `hx --remote`.
+//!
+//! Each editor listens on a unix socket in a per-user runtime directory. A client connects,
+//! writes a single JSON encoded [Request] line and reads back a single [Response] line.
+
+use std::{collections::HashMap, path::PathBuf, sync::Mutex};
+
+use anyhow::bail;
+#[cfg(unix)]
+use anyhow::Context;
+use helix_view::{events::DocumentDidClose, DocumentId};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::args::Args;
+
+/// The environment variable naming the socket of the editor a client should talk to. Without
+/// it the most recently started editor is used.
+pub const SERVER_ENV: &str = "HELIX_SERVER";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Request {
+    /// Opens files at the given 0-based `(row, column)` positions. With `wait` the response is
+    /// only sent once all of them have been closed.
+    Open {
+        files: Vec<(PathBuf, Vec<(usize, usize)>)>,
+        wait: bool,
+    },
+    /// Runs a typable command line such as `write` or `:open foo.rs`.
+    Command(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Response {
+    Ok,
+    Error(String),
+}
+
+/// A request received by the server along with the channel its response is sent to.
+pub type PendingRequest = (Request, oneshot::Sender<Response>);
+
+/// The listening side of the socket. The socket file is removed when it is dropped.
+pub struct Server {
+    path: PathBuf,
+    requests: mpsc::Receiver<PendingRequest>,
+}
+
+impl Server {
+    /// Starts listening on a socket named after the current process.
+    #[cfg(unix)]
+    pub fn start() -> anyhow::Result<Self> {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+        use tokio::net::UnixListener;
+
+        let dir = socket_dir();
+        create_private_dir(&dir)?;
+        let path = dir.join(format!("{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path)
+            .with_context(|| format!("failed to listen on {}", path.display()))?;
+
+        let (tx, requests) = mpsc::channel(32);
+        tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(err) => {
+                        log::error!("remote: failed to accept a connection: {err}");
+                        return;
+                    }
+                };
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut line = String::new();
+                    BufReader::new(reader).read_line(&mut line).await?;
+                    let response = match serde_json::from_str(&line) {
+                        Ok(request) => {
+                            let (respond, response) = oneshot::channel();
+                            tx.send((request, respond)).await?;
+                            // The editor drops the sender if it quits before responding.
+                            response.await.unwrap_or(Response::Ok)
+                        }
+                        Err(err) => Response::Error(format!("invalid request: {err}")),
+                    };
+                    let mut response = serde_json::to_string(&response)?;
+                    response.push('\n');
+                    writer.write_all(response.as_bytes()).await?;
+                    anyhow::Ok(())
+                });
+            }
+        });
+
+        Ok(Self { path, requests })
+    }
+
+    #[cfg(not(unix))]
+    pub fn start() -> anyhow::Result<Self> {
+        bail!("remote control is only supported on unix")
+    }
+
+    pub async fn recv(&mut self) -> Option<PendingRequest> {
+        self.requests.recv().await
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Waits for the next request of an optional server, never finishing without one.
+pub async fn recv(server: &mut Option<Server>) -> Option<PendingRequest> {
+    match server {
+        Some(server) => server.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(unix)]
+fn socket_dir() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir).join("helix"),
+        None => std::env::temp_dir().join(format!("helix-{}", unsafe { libc::getuid() })),
+    }
+}
+
+/// Creates `dir` readable only by the current user, as anyone who can connect to a socket in
+/// it can run commands in the editor.
+#[cfg(unix)]
+fn create_private_dir(dir: &std::path::Path) -> anyhow::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+    match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => return Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => (),
+        Err(err) => return Err(err.into()),
+    }
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if !metadata.is_dir()
+        || metadata.uid() != unsafe { libc::getuid() }
+        || metadata.permissions().mode() & 0o077 != 0
+    {
+        bail!("{} is not a private directory", dir.display());
+    }
+    Ok(())
+}
+
+static WAITING: Lazy<Mutex<HashMap<DocumentId, Vec<oneshot::Sender<()>>>>> =
+    Lazy::new(Default::default);
+
+/// Returns a receiver which completes once the document is closed.
+pub fn wait_for_close(doc: DocumentId) -> oneshot::Receiver<()> {
+    let (tx, rx) = oneshot::channel();
+    WAITING.lock().unwrap().entry(doc).or_default().push(tx);
+    rx
+}
+
+pub(crate) fn register_hooks() {
+    helix_event::register_hook!(move |event: &mut DocumentDidClose<'_>| {
+        if let Some(waiting) = WAITING.lock().unwrap().remove(&event.doc.id()) {
+            for tx in waiting {
+                let _ = tx.send(());
+            }
+        }
+        Ok(())
+    });
+}
+
+/// Sends the request described by the `--remote*` arguments to a running editor and returns
+/// the exit code for the client.
+#[cfg(unix)]
+pub fn send(args: Args) -> anyhow::Result<i32> {
+    use std::io::{BufRead, BufReader, Write};
+
+    let request = match args.remote_send {
+        Some(command) => Request::Command(command),
+        None => {
+            if args.files.is_empty() {
+                bail!("--remote needs at least one file to open");
+            }
+            let files = args
+                .files
+                .into_iter()
+                .map(|(path, positions)| {
+                    let positions = positions.iter().map(|pos| (pos.row, pos.col)).collect();
+                    // The editor may run in another directory than the client.
+                    (helix_stdx::path::canonicalize(path), positions)
+                })
+                .collect();
+            Request::Open {
+                files,
+                wait: args.remote_wait,
+            }
+        }
+    };
+
+    let mut stream = connect()?;
+    let mut request = serde_json::to_string(&request)?;
+    request.push('\n');
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+    match serde_json::from_str(&response).context("invalid response from the editor")? {
+        Response::Ok => Ok(0),
+        Response::Error(err) => {
+            eprintln!("{err}");
+            Ok(1)
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn send(_args: Args) -> anyhow::Result<i32> {
+    bail!("remote control is only supported on unix")
+}
+
+/// Connects to the socket named by [SERVER_ENV] or else to the most recently started editor
+/// which is still running.
+#[cfg(unix)]
+fn connect() -> anyhow::Result<std::os::unix::net::UnixStream> {
+    use std::os::unix::net::UnixStream;
+
+    if let Some(path) = std::env::var_os(SERVER_ENV) {
+        let path = PathBuf::from(path);
+        return UnixStream::connect(&path)
+            .with_context(|| format!("failed to connect to {}", path.display()));
+    }
+
+    let mut sockets: Vec<_> = std::fs::read_dir(socket_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "sock"))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    sockets.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+
+    for (_, path) in sockets {
+        match UnixStream::connect(&path) {
+            Ok(stream) => return Ok(stream),
+            // The editor which created the socket exited without removing it.
+            Err(err) if err.kind() == std::io::ErrorKind::ConnectionRefused => {
+                let _ = std::fs::remove_file(&path);
+            }
+            Err(_) => (),
+        }
+    }
+    bail!("no running editor found")
+}
@@ -0,0 +1,127 @@
+//! Helpers for the files in the state directory which keep the state of the editor between
+//! sessions, such as the marks, jumplists and recent files.
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Whether state enabled by the config is saved and restored. The editors of the integration
+/// tests always start without the state of previous sessions.
+pub fn is_enabled(enable: bool) -> bool {
+    enable && !cfg!(feature = "integration")
+}
+
+/// Encodes `path` as a file name. Path separators, the other characters which can't be part of
+/// file names and `%` are written as `%` followed by their hexadecimal code, so every path gets
+/// its own name.
+pub fn encode_path(path: &Path) -> String {
+    let mut name = String::new();
+    for c in path.to_string_lossy().chars() {
+        match c {
+            '%' | '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => {
+                name.push_str(&format!("%{:02X}", c as u32))
+            }
+            c => name.push(c),
+        }
+    }
+    name
+}
+
+/// The file named after the current workspace in the directory `dir` of the state directory.
+pub fn workspace_file(dir: &str) -> PathBuf {
+    let (workspace, _) = helix_loader::find_workspace();
+    helix_loader::state_dir()
+        .join(dir)
+        .join(format!("{}.json", encode_path(&workspace)))
+}
+
+/// Reads the JSON file, which is empty if it doesn't exist or can't be read.
+pub fn read<T: DeserializeOwned + Default>(file: &Path) -> T {
+    std::fs::read_to_string(file)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Changes the contents of the JSON file with `f`. The file may be shared by several running
+/// editors, so it is read again right before writing it back. The updates of this editor, which
+/// may run in background threads, are made one at a time.
+pub fn update<T: Serialize + DeserializeOwned + Default>(
+    file: &Path,
+    f: impl FnOnce(&mut T),
+) -> anyhow::Result<()> {
+    static UPDATING: Mutex<()> = Mutex::new(());
+    let _updating = UPDATING.lock().unwrap_or_else(|err| err.into_inner());
+    let mut contents = read(file);
+    f(&mut contents);
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(file, serde_json::to_string_pretty(&contents)?)?;
+    Ok(())
+}
+
+/// Writes `contents` to the file at `path`, which only the user may read. Used for the files
+/// which may contain the contents of documents.
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> anyhow::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // Files saved by earlier versions may be readable by others.
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(path)?.write_all(contents.as_ref())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode_paths() {
+        assert_eq!(encode_path(Path::new("/home/a b")), "%2Fhome%2Fa b");
+        assert_eq!(encode_path(Path::new("C:\\src")), "C%3A%5Csrc");
+        // Paths which only differ in the encoded characters get their own names.
+        assert_ne!(
+            encode_path(Path::new("/a%2Fb")),
+            encode_path(Path::new("/a/b"))
+        );
+        assert_eq!(encode_path(Path::new("/a%2Fb")), "%2Fa%252Fb");
+    }
+
+    #[test]
+    fn update_rereads() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("state").join("list.json");
+        update(&file, |list: &mut Vec<u32>| list.push(1)).unwrap();
+        // Another editor writes the file in between.
+        std::fs::write(&file, "[1, 2]").unwrap();
+        update(&file, |list: &mut Vec<u32>| list.push(3)).unwrap();
+        assert_eq!(read::<Vec<u32>>(&file), [1, 2, 3]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("registers.json");
+        // Files saved by earlier versions are readable by others.
+        std::fs::write(&file, "{}").unwrap();
+        write(&file, "{\":\":[\"w\"]}").unwrap();
+        let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "{\":\":[\"w\"]}");
+    }
+}
@@ -0,0 +1,312 @@
+//! Asks at startup whether to trust the workspace when its `.helix/config.toml` or
+//! `.helix/languages.toml` have settings which run programs. They are ignored until it is
+//! trusted, see [`helix_loader::trust`].
+//!
+//! The settings of `config.toml` are stripped here rather than by `helix_loader`, since finding
+//! the key bindings which run programs needs the command line parsing of `helix_core`.
+
+use helix_core::command_line::{ExpansionKind, TokenKind, Tokenizer};
+use helix_view::{editor::ConfigEvent, Editor};
+use toml::Value;
+
+use crate::{
+    compositor,
+    ui::{self, PromptEvent},
+};
+
+/// The keys of the `[editor]` table of `config.toml` which run programs.
+const EDITOR_KEYS: &[&str] = &[
+    "shell",
+    "terminal",
+    "make",
+    "tools",
+    "interactive",
+    "external-picker",
+    "documentation-pager",
+    "privileged-write",
+    "clipboard-provider",
+    "hooks",
+    "commands",
+];
+
+/// The typable commands, with their aliases, which run a shell command given as argument. Key
+/// bindings of the workspace running them are removed.
+const SHELL_COMMANDS: &[&str] = &[
+    "insert-output",
+    "append-output",
+    "pipe",
+    "|",
+    "pipe-to",
+    "run-shell-command",
+    "sh",
+    "!",
+    "run-shell-command!",
+    "sh!",
+    "terminal-pipe",
+];
+
+/// Removes the settings of a `config.toml` which run programs, returning their keys.
+pub fn strip_config(config: &mut Value) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut custom_commands = Vec::new();
+    if let Some(editor) = config.get_mut("editor").and_then(Value::as_table_mut) {
+        if let Some(Value::Table(commands)) = editor.get("commands") {
+            custom_commands.extend(commands.keys().cloned());
+        }
+        keys.extend(
+            EDITOR_KEYS
+                .iter()
+                .filter(|key| editor.remove(**key).is_some())
+                .map(|key| format!("editor.{key}")),
+        );
+        let actions = editor
+            .get_mut("dashboard")
+            .and_then(|dashboard| dashboard.get_mut("actions"))
+            .and_then(Value::as_array_mut);
+        if let Some(actions) = actions {
+            let len = actions.len();
+            actions.retain(|action| {
+                let command = action.get("command").and_then(Value::as_str);
+                !command.is_some_and(|command| runs_program(command, &custom_commands))
+            });
+            if actions.len() != len {
+                keys.push("editor.dashboard.actions".to_owned());
+            }
+        }
+    }
+    if let Some(bindings) = config.get_mut("keys").and_then(Value::as_table_mut) {
+        strip_bindings(bindings, "keys", &custom_commands, &mut keys);
+    }
+    keys
+}
+
+/// Removes the key bindings in `bindings`, a table of `[keys]` named `path`, which run shell
+/// commands or the custom commands `custom_commands`.
+fn strip_bindings(
+    bindings: &mut toml::Table,
+    path: &str,
+    custom_commands: &[String],
+    stripped: &mut Vec<String>,
+) {
+    bindings.retain(|key, binding| {
+        let path = format!("{path}.{key}");
+        let runs_program = match binding {
+            Value::Table(bindings) => {
+                strip_bindings(bindings, &path, custom_commands, stripped);
+                false
+            }
+            Value::String(command) => runs_program(command, custom_commands),
+            Value::Array(commands) => commands
+                .iter()
+                .filter_map(Value::as_str)
+                .any(|command| runs_program(command, custom_commands)),
+            _ => false,
+        };
+        if runs_program {
+            stripped.push(path);
+        }
+        !runs_program
+    });
+}
+
+/// Whether the key binding `command` runs a shell command or one of `custom_commands`, either as
+/// a typable command or within a macro, or expands `%sh{…}`.
+fn runs_program(command: &str, custom_commands: &[String]) -> bool {
+    if !command.starts_with([':', '@']) {
+        return false;
+    }
+    expands_shell(command)
+        || command.split(':').skip(1).any(|command| {
+            let name = command
+                .split(|c: char| c.is_whitespace() || c == '<')
+                .next()
+                .unwrap_or_default();
+            SHELL_COMMANDS.contains(&name) || custom_commands.iter().any(|custom| custom == name)
+        })
+}
+
+/// Whether the command line has a `%sh{…}` expansion. Some commands expand the rest of the line
+/// as a whole, quotes included, so the expansions anywhere in it are checked.
+fn expands_shell(line: &str) -> bool {
+    let mut start = 0;
+    while let Some(offset) = line[start..].find('%') {
+        let idx = start + offset;
+        // Two percents in a row are an escaped percent.
+        if line[idx + 1..].starts_with('%') {
+            start = idx + 2;
+            continue;
+        }
+        let mut tokenizer = Tokenizer::new(&line[idx..], false);
+        if let Some(Ok(token)) = tokenizer.parse_percent_token() {
+            if token.kind == TokenKind::Expansion(ExpansionKind::Shell) {
+                return true;
+            }
+        }
+        start = idx + tokenizer.pos().max(1);
+    }
+    false
+}
+
+/// The keys of the workspace's config files which are ignored because the workspace isn't
+/// trusted.
+pub fn ignored_keys() -> Vec<String> {
+    let (workspace, _) = helix_loader::find_workspace();
+    if helix_loader::trust::is_trusted(&workspace) {
+        return Vec::new();
+    }
+    ["config.toml", "languages.toml"]
+        .into_iter()
+        .filter_map(|name| {
+            let file = std::fs::read_to_string(workspace.join(".helix").join(name)).ok()?;
+            let mut config = toml::from_str(&file).ok()?;
+            Some(match name {
+                "config.toml" => strip_config(&mut config),
+                _ => helix_loader::trust::strip_languages(&mut config),
+            })
+        })
+        .flatten()
+        .collect()
+}
+
+/// Trusts or distrusts the current workspace and reloads the config.
+pub fn set_trusted(editor: &mut Editor, trusted: bool) -> anyhow::Result<()> {
+    let (workspace, _) = helix_loader::find_workspace();
+    helix_loader::trust::set_trusted(&workspace, trusted)?;
+    editor.config_events.0.send(ConfigEvent::Refresh)?;
+    Ok(())
+}
+
+/// Asks whether to trust the workspace, listing the `ignored` settings.
+pub fn prompt(ignored: Vec<String>) -> ui::Prompt {
+    let mut prompt = ui::Prompt::new(
+        "The workspace config has settings which run programs, trust it? (y/n): ".into(),
+        None,
+        ui::completers::none,
+        |cx: &mut compositor::Context, input: &str, event: PromptEvent| {
+            if event != PromptEvent::Validate || !input.eq_ignore_ascii_case("y") {
+                return;
+            }
+            if let Err(err) = set_trusted(cx.editor, true) {
+                cx.editor.set_error(err.to_string());
+            }
+        },
+    );
+    let doc = format!("Ignored settings:\n{}", ignored.join("\n"));
+    prompt.doc_fn = Box::new(move |_| Some(doc.clone().into()));
+    prompt
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn strip_risky_keys() {
+        let mut config: Value = toml::from_str(
+            r#"
+            theme = "onedark"
+            [editor]
+            shell = ["zsh", "-c"]
+            scrolloff = 3
+            [editor.terminal]
+            command = "tmux"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            strip_config(&mut config),
+            ["editor.shell", "editor.terminal"]
+        );
+        assert_eq!(
+            config,
+            toml::from_str("theme = \"onedark\"\n[editor]\nscrolloff = 3").unwrap()
+        );
+
+        let mut config: Value = toml::from_str(
+            r#"
+            [[editor.hooks]]
+            event = "open"
+            commands = [":sh touch pwned"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(strip_config(&mut config), ["editor.hooks"]);
+        assert_eq!(config, toml::from_str("[editor]").unwrap());
+
+        let mut config: Value = toml::from_str(
+            r#"
+            [editor.commands]
+            deploy = [":sh ./deploy"]
+            [keys.normal]
+            j = ":sh rm -rf ~"
+            k = ["move_line_up", ":pipe sort"]
+            l = "@:insert-output date<ret>"
+            m = ":deploy"
+            n = ":write"
+            [keys.normal.space]
+            x = ":run-shell-command! make"
+            y = "yank"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            strip_config(&mut config),
+            [
+                "editor.commands",
+                "keys.normal.j",
+                "keys.normal.k",
+                "keys.normal.l",
+                "keys.normal.m",
+                "keys.normal.space.x"
+            ]
+        );
+        assert_eq!(
+            config,
+            toml::from_str(
+                "[editor]\n[keys.normal]\nn = \":write\"\n[keys.normal.space]\ny = \"yank\""
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn strip_shell_expansions() {
+        let mut config: Value = toml::from_str(
+            r#"
+            [[editor.dashboard.actions]]
+            key = "e"
+            label = "Echo"
+            command = ":echo %sh{rm -rf ~}"
+            [keys.normal]
+            j = ":echo %sh{rm -rf ~}"
+            k = "@:echo \"user: %sh{id}\"<ret>"
+            l = ":set-option shell-history '%sh{id}'"
+            m = ":echo 100%%sh{not expanded}"
+            n = ":echo %{cursor_line}"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            strip_config(&mut config),
+            [
+                "editor.dashboard.actions",
+                "keys.normal.j",
+                "keys.normal.k",
+                "keys.normal.l"
+            ]
+        );
+        assert_eq!(
+            config,
+            toml::from_str(
+                r#"
+                [editor.dashboard]
+                actions = []
+                [keys.normal]
+                m = ":echo 100%%sh{not expanded}"
+                n = ":echo %{cursor_line}"
+                "#
+            )
+            .unwrap()
+        );
+    }
+}
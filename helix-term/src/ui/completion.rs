@@ -3,11 +3,11 @@
 use crate::{
     compositor::{Component, Context, Event, EventResult},
     handlers::completion::{
-        trigger_auto_completion, CompletionItem, CompletionResponse, ResolveHandler,
+        self, trigger_auto_completion, CompletionItem, CompletionResponse, ResolveHandler,
     },
 };
 use helix_core::snippets::{ActiveSnippet, RenderedSnippet, Snippet};
-use helix_core::{self as core, chars, fuzzy::MATCHER, Change, Transaction};
+use helix_core::{self as core, chars, fuzzy::MATCHER, Assoc, Change, Transaction};
 use helix_lsp::{lsp, util, OffsetEncoding};
 use helix_view::{
     editor::CompleteAction,
@@ -214,7 +214,13 @@ macro_rules! language_server {
                     doc.append_changes_to_history(view);
 
                     // item always present here
-                    let (transaction, additional_edits, snippet) = match item.clone() {
+                    let item = match item.clone() {
+                        CompletionItem::Other(item) => {
+                            CompletionItem::Other(completion::resolve(doc, item))
+                        }
+                        item => item,
+                    };
+                    let (transaction, additional_edits, snippet) = match item {
                         CompletionItem::Lsp(mut item) => {
                             let language_server = language_server!(item);
 
@@ -245,11 +251,45 @@ macro_rules! language_server {
                                 snippet,
                             )
                         }
+                        CompletionItem::Other(core::CompletionItem {
+                            snippet: Some(snippet),
+                            ..
+                        }) => match Snippet::parse(&snippet) {
+                            // rendered again to resolve its variables
+                            Ok(snippet) => {
+                                let (transaction, snippet) =
+                                    util::generate_transaction_from_snippet(
+                                        doc.text(),
+                                        doc.selection(view.id),
+                                        None,
+                                        replace_mode,
+                                        snippet,
+                                        &mut doc.snippet_ctx(),
+                                    );
+                                (transaction, None, Some(snippet))
+                            }
+                            Err(err) => {
+                                log::error!("{err}");
+                                (Transaction::new(doc.text()), None, None)
+                            }
+                        },
                         CompletionItem::Other(core::CompletionItem { transaction, .. }) => {
                             (transaction, None, None)
                         }
                     };
 
+                    // the additional edits, like imports, are relative to the text before the
+                    // completion
+                    let additional_edits = additional_edits
+                        .filter(|(edits, _)| !edits.is_empty())
+                        .map(|(edits, offset_encoding)| {
+                            util::generate_transaction_from_edits(
+                                doc.text(),
+                                edits,
+                                offset_encoding, // TODO: should probably transcode in Client
+                            )
+                        });
+
                     doc.apply(&transaction, view.id);
                     let placeholder = snippet.is_some();
                     if let Some(snippet) = snippet {
@@ -257,6 +297,16 @@ macro_rules! language_server {
                             Some(active) => active.insert_subsnippet(snippet),
                             None => ActiveSnippet::new(snippet),
                         };
+                        if doc
+                            .active_snippet
+                            .as_ref()
+                            .is_some_and(|snippet| snippet.current_choices().is_some())
+                        {
+                            // once the completion menu is closed
+                            crate::job::dispatch_blocking(|editor, compositor| {
+                                crate::commands::show_snippet_choices(editor, compositor)
+                            });
+                        }
                     }
 
                     editor.last_completion = Some(CompleteAction::Applied {
@@ -266,15 +316,19 @@ macro_rules! language_server {
                     });
 
                     // TODO: add additional _edits to completion_changes?
-                    if let Some((additional_edits, offset_encoding)) = additional_edits {
-                        if !additional_edits.is_empty() {
-                            let transaction = util::generate_transaction_from_edits(
-                                doc.text(),
-                                additional_edits,
-                                offset_encoding, // TODO: should probably transcode in Client
-                            );
-                            doc.apply(&transaction, view.id);
-                        }
+                    if let Some(additional_edits) = additional_edits {
+                        let changes = transaction.changes();
+                        let additional_edits = Transaction::change(
+                            doc.text(),
+                            additional_edits.changes_iter().map(|(from, to, text)| {
+                                (
+                                    changes.map_pos(from, Assoc::Before),
+                                    changes.map_pos(to, Assoc::Before),
+                                    text,
+                                )
+                            }),
+                        );
+                        doc.apply(&additional_edits, view.id);
                     }
                     // we could have just inserted a trigger char (like a `crate::` completion for rust
                     // so we want to retrigger immediately when accepting a completion.
@@ -489,13 +543,24 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
         let doc = doc!(cx.editor);
         let language = doc.language_name().unwrap_or("");
 
+        let additional_text = match option {
+            CompletionItem::Lsp(option) => additional_edits_text(&option.item),
+            CompletionItem::Other(_) => None,
+        };
         let markdowned = |lang: &str, detail: Option<&str>, doc: Option<&str>| {
-            let md = match (detail, doc) {
+            let mut md = match (detail, doc) {
                 (Some(detail), Some(doc)) => format!("```{lang}\n{detail}\n```\n{doc}"),
                 (Some(detail), None) => format!("```{lang}\n{detail}\n```"),
                 (None, Some(doc)) => doc.to_string(),
                 (None, None) => String::new(),
             };
+            // such as the import of the completed item
+            if let Some(text) = &additional_text {
+                if !md.is_empty() {
+                    md.push_str("\n\n");
+                }
+                md.push_str(&format!("Also adds:\n```{lang}\n{text}\n```"));
+            }
             Markdown::new(md, cx.editor.syn_loader.clone())
         };
 
@@ -516,7 +581,7 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
                     // TODO: set language based on doc scope
                     markdowned(language, option.item.detail.as_deref(), Some(contents))
                 }
-                None if option.item.detail.is_some() => {
+                None if option.item.detail.is_some() || additional_text.is_some() => {
                     // TODO: set language based on doc scope
                     markdowned(language, option.item.detail.as_deref(), None)
                 }
@@ -656,6 +721,18 @@ fn lsp_item_to_transaction(
     }
 }
 
+/// The text which accepting `item` adds elsewhere in the document, like an import.
+fn additional_edits_text(item: &lsp::CompletionItem) -> Option<String> {
+    let text: Vec<_> = item
+        .additional_text_edits
+        .iter()
+        .flatten()
+        .map(|edit| edit.new_text.trim())
+        .filter(|text| !text.is_empty())
+        .collect();
+    (!text.is_empty()).then(|| text.join("\n"))
+}
+
 fn completion_changes(transaction: &Transaction, trigger_offset: usize) -> Vec<Change> {
     transaction
         .changes_iter()
@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+
+use helix_view::{
+    editor::{Action, DashboardAction, DashboardConfig},
+    graphics::{Modifier, Rect},
+    input::{Event, KeyEvent},
+};
+use tui::buffer::Buffer as Surface;
+
+use crate::{
+    commands::{self, MappableCommand},
+    compositor::{Component, Compositor, Context, EventResult},
+    ctrl, key,
+    recent::{self, Recent},
+    shift,
+    ui::{self, overlay::overlaid},
+};
+
+#[derive(Debug, Clone)]
+enum Entry {
+    File(PathBuf),
+    Workspace(PathBuf),
+    Action(DashboardAction),
+}
+
+/// Lists the recently opened files and workspaces along with the configured actions, for
+/// starting the editor without files.
+pub struct Dashboard {
+    entries: Vec<Entry>,
+    cursor: usize,
+}
+
+impl Dashboard {
+    pub const ID: &'static str = "dashboard";
+
+    pub fn new(config: &DashboardConfig) -> Self {
+        let mut recent = Recent::load();
+        recent.retain_existing();
+        let files = recent.files.into_iter().take(config.recent_files);
+        let workspaces = recent.workspaces.into_iter().take(config.recent_workspaces);
+        let entries = files
+            .map(Entry::File)
+            .chain(workspaces.map(Entry::Workspace))
+            .chain(config.actions.iter().cloned().map(Entry::Action))
+            .collect();
+        Self { entries, cursor: 0 }
+    }
+
+    fn move_cursor(&mut self, forward: bool) {
+        let len = self.entries.len();
+        if len == 0 {
+            return;
+        }
+        self.cursor = if forward {
+            (self.cursor + 1) % len
+        } else {
+            (self.cursor + len - 1) % len
+        };
+    }
+
+    /// Closes the dashboard and opens the entry.
+    fn activate(entry: Entry) -> EventResult {
+        EventResult::Consumed(Some(Box::new(
+            move |compositor: &mut Compositor, cx: &mut Context| {
+                compositor.remove(Self::ID);
+                match entry {
+                    Entry::File(path) => {
+                        if let Err(err) = cx.editor.open(&path, Action::Replace) {
+                            cx.editor
+                                .set_error(format!("Failed to open {}: {err}", path.display()));
+                        }
+                    }
+                    Entry::Workspace(path) => open_workspace(compositor, cx, &path),
+                    Entry::Action(action) => run_command(compositor, cx, &action.command),
+                }
+            },
+        )))
+    }
+
+    /// The lines to render along with the entry each of them shows.
+    fn lines(&self) -> Vec<(Option<usize>, String)> {
+        let mut lines = Vec::new();
+        let mut section = None;
+        for (i, entry) in self.entries.iter().enumerate() {
+            let (title, text) = match entry {
+                Entry::File(path) => ("Recent files", display_path(path)),
+                Entry::Workspace(path) => ("Recent workspaces", display_path(path)),
+                Entry::Action(action) => ("Actions", format!("{:<6}{}", action.key, action.label)),
+            };
+            if section != Some(title) {
+                section = Some(title);
+                lines.push((None, String::new()));
+                lines.push((None, title.to_owned()));
+            }
+            lines.push((Some(i), text));
+        }
+        lines
+    }
+}
+
+fn display_path(path: &Path) -> String {
+    helix_stdx::path::fold_home_dir(path).display().to_string()
+}
+
+/// Changes the working directory to the workspace and restores its default layout or, if
+/// there is none, opens the file picker.
+fn open_workspace(compositor: &mut Compositor, cx: &mut Context, path: &Path) {
+    if let Err(err) = cx.editor.set_cwd(path) {
+        cx.editor.set_error(format!(
+            "Could not change working directory to '{}': {err}",
+            path.display()
+        ));
+        return;
+    }
+    recent::add_current_workspace();
+
+    let layout = commands::DEFAULT_LAYOUT_NAME;
+    if commands::layout_file(layout).is_ok_and(|file| file.exists()) {
+        if let Err(err) = commands::load_layout(cx.editor, layout) {
+            cx.editor.set_error(err.to_string());
+        }
+    } else {
+        let picker = ui::file_picker(cx.editor, path.to_path_buf());
+        compositor.push(Box::new(overlaid(picker)));
+    }
+}
+
+fn run_command(compositor: &mut Compositor, cx: &mut Context, command: &str) {
+    let command: MappableCommand = match command.parse() {
+        Ok(command) => command,
+        Err(err) => {
+            cx.editor.set_error(err.to_string());
+            return;
+        }
+    };
+    let mut ctx = commands::Context {
+        register: None,
+        count: None,
+        editor: cx.editor,
+        callback: Vec::new(),
+        on_next_key_callback: None,
+        jobs: cx.jobs,
+    };
+    command.execute(&mut ctx);
+    for callback in ctx.callback {
+        callback(compositor, cx);
+    }
+}
+
+impl Component for Dashboard {
+    fn handle_event(&mut self, event: &Event, _cx: &mut Context) -> EventResult {
+        let key: KeyEvent = match event {
+            Event::Key(key) => *key,
+            Event::Mouse(_) => return EventResult::Consumed(None),
+            _ => return EventResult::Ignored(None),
+        };
+
+        let action = self.entries.iter().find(|entry| match entry {
+            Entry::Action(action) => action.key == key,
+            _ => false,
+        });
+        if let Some(action) = action {
+            return Self::activate(action.clone());
+        }
+
+        match key {
+            key!(Down) | key!('j') | key!(Tab) | ctrl!('n') => self.move_cursor(true),
+            key!(Up) | key!('k') | shift!(Tab) | ctrl!('p') => self.move_cursor(false),
+            key!(Enter) => {
+                if let Some(entry) = self.entries.get(self.cursor) {
+                    return Self::activate(entry.clone());
+                }
+            }
+            key!(Esc) => {
+                return EventResult::Consumed(Some(Box::new(|compositor: &mut Compositor, _| {
+                    compositor.remove(Self::ID);
+                })))
+            }
+            // Let the editor open the command prompt.
+            key!(':') => return EventResult::Ignored(None),
+            _ => (),
+        }
+        EventResult::Consumed(None)
+    }
+
+    fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let theme = &cx.editor.theme;
+        let text_style = theme.get("ui.text");
+        let title_style = text_style.add_modifier(Modifier::BOLD);
+        let selected_style = theme.get("ui.menu.selected");
+        surface.clear_with(area, theme.get("ui.background"));
+
+        let header = format!("helix {}", helix_loader::VERSION_AND_GIT_HASH);
+        let lines = self.lines();
+        let width = area.width.min(60);
+        let x = area.x + (area.width - width) / 2;
+        let height = lines.len() as u16 + 1;
+        let mut y = area.y + area.height.saturating_sub(height) / 2;
+        // Scroll the lines so the cursor stays visible on small screens.
+        let cursor_line = lines
+            .iter()
+            .position(|(entry, _)| *entry == Some(self.cursor))
+            .unwrap_or(0);
+        let skip = (cursor_line + 2).saturating_sub(area.height as usize);
+
+        if skip == 0 {
+            surface.set_stringn(x, y, header, width as usize, title_style);
+            y += 1;
+        }
+        for (entry, text) in lines.into_iter().skip(skip) {
+            if y >= area.bottom() {
+                break;
+            }
+            match entry {
+                Some(i) => {
+                    let style = if i == self.cursor {
+                        surface.set_style(Rect::new(x, y, width, 1), selected_style);
+                        text_style.patch(selected_style)
+                    } else {
+                        text_style
+                    };
+                    surface.set_stringn(x + 2, y, text, width.saturating_sub(2) as usize, style);
+                }
+                None => {
+                    surface.set_stringn(x, y, text, width as usize, title_style);
+                }
+            }
+            y += 1;
+        }
+    }
+
+    fn id(&self) -> Option<&'static str> {
+        Some(Self::ID)
+    }
+}
@@ -7,7 +7,7 @@
 use helix_core::text_annotations::TextAnnotations;
 use helix_core::{visual_offset_from_block, Position, RopeSlice};
 use helix_stdx::rope::RopeSliceExt;
-use helix_view::editor::{WhitespaceConfig, WhitespaceRenderValue};
+use helix_view::editor::{SemanticTokensMode, WhitespaceConfig, WhitespaceRenderValue};
 use helix_view::graphics::Rect;
 use helix_view::theme::Style;
 use helix_view::view::ViewPosition;
@@ -27,6 +27,29 @@ pub struct LinePos {
     pub visual_line: u16,
 }
 
+/// The indent guide of an indentation scope, which is drawn with its own style on the lines
+/// after the first line of the scope.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct IndentGuideScope {
+    /// The indent level of the guide
+    pub level: usize,
+    pub first_line: usize,
+    pub last_line: usize,
+}
+
+impl IndentGuideScope {
+    fn contains(&self, level: usize, doc_line: usize) -> bool {
+        level == self.level && self.first_line < doc_line && doc_line <= self.last_line
+    }
+}
+
+/// The highlights of the semantic tokens of a document, which are layered with its syntax
+/// highlights.
+pub struct SemanticHighlights {
+    pub highlights: OverlayHighlights,
+    pub mode: SemanticTokensMode,
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn render_document(
     surface: &mut Surface,
@@ -35,9 +58,11 @@ pub fn render_document(
     offset: ViewPosition,
     doc_annotations: &TextAnnotations,
     syntax_highlighter: Option<Highlighter<'_>>,
+    semantic_highlights: Option<SemanticHighlights>,
     overlay_highlights: Vec<syntax::OverlayHighlights>,
     theme: &Theme,
     decorations: DecorationManager,
+    indent_guide_scope: Option<IndentGuideScope>,
 ) {
     let mut renderer = TextRenderer::new(
         surface,
@@ -46,6 +71,7 @@ pub fn render_document(
         Position::new(offset.vertical_offset, offset.horizontal_offset),
         viewport,
     );
+    renderer.indent_guide_scope = indent_guide_scope;
     render_text(
         &mut renderer,
         doc.text().slice(..),
@@ -53,6 +79,7 @@ pub fn render_document(
         &doc.text_format(viewport.width, Some(theme)),
         doc_annotations,
         syntax_highlighter,
+        semantic_highlights,
         overlay_highlights,
         theme,
         decorations,
@@ -67,6 +94,7 @@ pub fn render_text(
     text_fmt: &TextFormat,
     text_annotations: &TextAnnotations,
     syntax_highlighter: Option<Highlighter<'_>>,
+    semantic_highlights: Option<SemanticHighlights>,
     overlay_highlights: Vec<syntax::OverlayHighlights>,
     theme: &Theme,
     mut decorations: DecorationManager,
@@ -80,6 +108,12 @@ pub fn render_text(
     let mut syntax_highlighter =
         SyntaxHighlighter::new(syntax_highlighter, text, theme, renderer.text_style);
     let mut overlay_highlighter = OverlayHighlighter::new(overlay_highlights, theme);
+    let mut semantic_highlighter = semantic_highlights.map(|semantic| {
+        (
+            OverlayHighlighter::new(vec![semantic.highlights], theme),
+            semantic.mode,
+        )
+    });
 
     let mut last_line_pos = LinePos {
         first_visual_line: false,
@@ -119,7 +153,7 @@ pub fn render_text(
             // in that case we don't need to draw indent guides/virtual text
             if last_line_pos.doc_line != usize::MAX {
                 // draw indent guides for the last line
-                renderer.draw_indent_guides(last_line_indent_level, last_line_pos.visual_line);
+                renderer.draw_indent_guides(last_line_indent_level, last_line_pos);
                 is_in_indent_area = true;
                 decorations.render_virtual_lines(renderer, last_line_pos, last_line_end)
             }
@@ -138,6 +172,15 @@ pub fn render_text(
         while grapheme.char_idx >= overlay_highlighter.pos {
             overlay_highlighter.advance();
         }
+        let mut syntax_style = syntax_highlighter.style;
+        if let Some((semantic_highlighter, mode)) = &mut semantic_highlighter {
+            while grapheme.char_idx >= semantic_highlighter.pos {
+                semantic_highlighter.advance();
+            }
+            if *mode == SemanticTokensMode::Override || !syntax_highlighter.highlighted {
+                syntax_style = syntax_style.patch(semantic_highlighter.style);
+            }
+        }
 
         let grapheme_style = if let GraphemeSource::VirtualText { highlight } = grapheme.source {
             let mut style = renderer.text_style;
@@ -150,7 +193,7 @@ pub fn render_text(
             }
         } else {
             GraphemeStyle {
-                syntax_style: syntax_highlighter.style,
+                syntax_style,
                 overlay_style: overlay_highlighter.style,
             }
         };
@@ -168,7 +211,7 @@ pub fn render_text(
         last_line_end = grapheme.visual_pos.col + grapheme_width;
     }
 
-    renderer.draw_indent_guides(last_line_indent_level, last_line_pos.visual_line);
+    renderer.draw_indent_guides(last_line_indent_level, last_line_pos);
     decorations.render_virtual_lines(renderer, last_line_pos, last_line_end)
 }
 
@@ -179,6 +222,10 @@ pub struct TextRenderer<'a> {
     pub whitespace_style: Style,
     pub indent_guide_char: String,
     pub indent_guide_style: Style,
+    /// Styles of the indent guides by their level, empty unless rainbow guides are enabled
+    pub indent_guide_rainbow: Vec<Style>,
+    pub indent_guide_scope_style: Style,
+    pub indent_guide_scope: Option<IndentGuideScope>,
     pub newline: String,
     pub nbsp: String,
     pub nnbsp: String,
@@ -246,6 +293,22 @@ pub fn new(
 
         let indent_width = doc.indent_style.indent_width(tab_width) as u16;
 
+        let indent_guide_style = text_style.patch(
+            theme
+                .try_get("ui.virtual.indent-guide")
+                .unwrap_or_else(|| theme.get("ui.virtual.whitespace")),
+        );
+        let indent_guide_rainbow = if editor_config.indent_guides.rainbow {
+            (0..theme.rainbow_length())
+                .map(|i| indent_guide_style.patch(theme.get(&format!("rainbow.{i}"))))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let indent_guide_scope_style = theme
+            .try_get_exact("ui.virtual.indent-guide.scope")
+            .unwrap_or_else(|| theme.get("ui.linenr.selected"));
+
         TextRenderer {
             surface,
             indent_guide_char: editor_config.indent_guides.character.into(),
@@ -260,11 +323,10 @@ pub fn new(
             starting_indent: offset.col / indent_width as usize
                 + (offset.col % indent_width as usize != 0) as usize
                 + editor_config.indent_guides.skip_levels as usize,
-            indent_guide_style: text_style.patch(
-                theme
-                    .try_get("ui.virtual.indent-guide")
-                    .unwrap_or_else(|| theme.get("ui.virtual.whitespace")),
-            ),
+            indent_guide_style,
+            indent_guide_rainbow,
+            indent_guide_scope_style,
+            indent_guide_scope: None,
             text_style,
             draw_indent_guides: editor_config.indent_guides.render,
             viewport,
@@ -390,11 +452,11 @@ pub fn column_in_bounds(&self, colum: usize, width: usize) -> bool {
     /// Overlay indentation guides ontop of a rendered line
     /// The indentation level is computed in `draw_lines`.
     /// Therefore this function must always be called afterwards.
-    pub fn draw_indent_guides(&mut self, indent_level: usize, mut row: u16) {
-        if !self.draw_indent_guides || self.offset.row > row as usize {
+    pub fn draw_indent_guides(&mut self, indent_level: usize, pos: LinePos) {
+        if !self.draw_indent_guides || self.offset.row > pos.visual_line as usize {
             return;
         }
-        row -= self.offset.row as u16;
+        let row = pos.visual_line - self.offset.row as u16;
 
         // Don't draw indent guides outside of view
         let end_indent = min(
@@ -409,8 +471,19 @@ pub fn draw_indent_guides(&mut self, indent_level: usize, mut row: u16) {
                 as u16;
             let y = self.viewport.y + row;
             debug_assert!(self.surface.in_bounds(x, y));
+            let mut style = if self.indent_guide_rainbow.is_empty() {
+                self.indent_guide_style
+            } else {
+                self.indent_guide_rainbow[i % self.indent_guide_rainbow.len()]
+            };
+            if self
+                .indent_guide_scope
+                .is_some_and(|scope| scope.contains(i, pos.doc_line))
+            {
+                style = style.patch(self.indent_guide_scope_style);
+            }
             self.surface
-                .set_string(x, y, &self.indent_guide_char, self.indent_guide_style);
+                .set_string(x, y, &self.indent_guide_char, style);
         }
     }
 
@@ -480,6 +553,8 @@ struct SyntaxHighlighter<'h, 'r, 't> {
     theme: &'t Theme,
     text_style: Style,
     style: Style,
+    /// Whether any highlight is active, unlike for text without syntax highlights.
+    highlighted: bool,
 }
 
 impl<'h, 'r, 't> SyntaxHighlighter<'h, 'r, 't> {
@@ -496,6 +571,7 @@ fn new(
             theme,
             style: text_style,
             text_style,
+            highlighted: false,
         };
         highlighter.update_pos();
         highlighter
@@ -523,14 +599,16 @@ fn advance(&mut self) {
         };
 
         let (event, highlights) = highlighter.advance();
-        let base = match event {
-            HighlightEvent::Refresh => self.text_style,
-            HighlightEvent::Push => self.style,
+        let (base, mut highlighted) = match event {
+            HighlightEvent::Refresh => (self.text_style, false),
+            HighlightEvent::Push => (self.style, self.highlighted),
         };
 
         self.style = highlights.fold(base, |acc, highlight| {
+            highlighted = true;
             acc.patch(self.theme.highlight(highlight))
         });
+        self.highlighted = highlighted;
         self.update_pos();
     }
 }
@@ -1,15 +1,16 @@
 use crate::{
     commands::{self, OnKeyCallback, OnKeyCallbackKind},
-    compositor::{Component, Context, Event, EventResult},
+    compositor::{Component, Compositor, Context, Event, EventResult},
     events::{OnModeSwitch, PostCommand},
     handlers::completion::CompletionItem,
     key,
     keymap::{KeymapResult, Keymaps},
     ui::{
-        document::{render_document, LinePos, TextRenderer},
+        document::{render_document, IndentGuideScope, LinePos, SemanticHighlights, TextRenderer},
+        lsp::hover::Hover,
         statusline,
         text_decorations::{self, Decoration, DecorationManager, InlineDiagnostics},
-        Completion, ProgressSpinners,
+        Completion, Popup, ProgressSpinners,
     },
 };
 
@@ -17,24 +18,40 @@
     diagnostic::NumberOrString,
     graphemes::{next_grapheme_boundary, prev_grapheme_boundary},
     movement::Direction,
-    syntax::{self, OverlayHighlights},
+    syntax::{self, config::LanguageServerFeature, OverlayHighlights},
     text_annotations::TextAnnotations,
     unicode::width::UnicodeWidthStr,
     visual_offset_from_block, Change, Position, Range, Selection, Transaction,
 };
+use helix_lsp::lsp;
 use helix_view::{
+    align_view,
     annotations::diagnostics::DiagnosticFilter,
-    document::{Mode, SCRATCH_BUFFER_NAME},
-    editor::{CompleteAction, CursorShapeConfig},
+    document::Mode,
+    editor::{Action, CloseError, CompleteAction, CursorShapeConfig, SemanticTokensMode},
     graphics::{Color, CursorKind, Modifier, Rect, Style},
     input::{KeyEvent, MouseButton, MouseEvent, MouseEventKind},
     keyboard::{KeyCode, KeyModifiers},
-    Document, Editor, Theme, View,
+    theme,
+    tree::SplitBorder,
+    view::ViewPosition,
+    Align, Document, DocumentId, Editor, Theme, View, ViewId,
+};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    mem::take,
+    num::NonZeroUsize,
+    ops,
+    rc::Rc,
+    time::{Duration, Instant},
 };
-use std::{mem::take, num::NonZeroUsize, ops, path::PathBuf, rc::Rc};
 
 use tui::{buffer::Buffer as Surface, text::Span};
 
+/// The time between the frames of smooth scrolling animations.
+const SMOOTH_SCROLL_FRAME: Duration = Duration::from_millis(16);
+
 pub struct EditorView {
     pub keymaps: Keymaps,
     on_next_key: Option<(OnKeyCallback, OnKeyCallbackKind)>,
@@ -44,6 +61,41 @@ pub struct EditorView {
     spinners: ProgressSpinners,
     /// Tracks if the terminal window is focused by reaction to terminal focus events
     terminal_focused: bool,
+    /// The view whose scrollbar is being dragged with the mouse.
+    scrollbar_drag: Option<ViewId>,
+    /// The border between two splits which is being dragged with the mouse.
+    split_drag: Option<SplitBorder>,
+    /// The text position the mouse rests over.
+    mouse_hover: Option<MouseHover>,
+    /// The visual coordinates where a rectangular selection is started by dragging with `Alt`.
+    block_drag: Option<Position>,
+    /// The lines of the matches of the last search in each document, marked on the scrollbar.
+    search_marks: HashMap<DocumentId, SearchMarks>,
+}
+
+/// The lines matching a search in a version of a document.
+struct SearchMarks {
+    query: String,
+    smart_case: bool,
+    version: i32,
+    lines: Vec<usize>,
+}
+
+/// The ID of the popup showing the diagnostics and hover information under the mouse.
+const MOUSE_HOVER_ID: &str = "mouse-hover";
+
+#[derive(Debug, Clone, Copy)]
+struct MouseHover {
+    view_id: ViewId,
+    pos: usize,
+    /// The screen position of the mouse, next to which the popup is shown.
+    screen: Position,
+}
+
+impl PartialEq for MouseHover {
+    fn eq(&self, other: &Self) -> bool {
+        self.view_id == other.view_id && self.pos == other.pos
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +119,11 @@ pub fn new(keymaps: Keymaps) -> Self {
             completion: None,
             spinners: ProgressSpinners::default(),
             terminal_focused: true,
+            scrollbar_drag: None,
+            split_drag: None,
+            mouse_hover: None,
+            block_drag: None,
+            search_marks: HashMap::new(),
         }
     }
 
@@ -75,7 +132,7 @@ pub fn spinners_mut(&mut self) -> &mut ProgressSpinners {
     }
 
     pub fn render_view(
-        &self,
+        &mut self,
         editor: &Editor,
         doc: &Document,
         view: &View,
@@ -90,6 +147,9 @@ pub fn render_view(
         let loader = editor.syn_loader.load();
 
         let view_offset = doc.view_offset(view.id);
+        // The number of document lines in the viewport, which is larger than its height when
+        // lines are hidden by folds.
+        let text_height = view.viewport_doc_lines(doc).min(u16::MAX as usize) as u16;
 
         let text_annotations = view.text_annotations(doc, Some(theme));
         let mut decorations = DecorationManager::default();
@@ -117,13 +177,13 @@ pub fn render_view(
         }
 
         let syntax_highlighter =
-            Self::doc_syntax_highlighter(doc, view_offset.anchor, inner.height, &loader);
+            Self::doc_syntax_highlighter(doc, view_offset.anchor, text_height, &loader);
         let mut overlays = Vec::new();
 
         overlays.push(Self::overlay_syntax_highlights(
             doc,
             view_offset.anchor,
-            inner.height,
+            text_height,
             &text_annotations,
         ));
 
@@ -133,7 +193,7 @@ pub fn render_view(
             .unwrap_or(config.rainbow_brackets)
         {
             if let Some(overlay) =
-                Self::doc_rainbow_highlights(doc, view_offset.anchor, inner.height, theme, &loader)
+                Self::doc_rainbow_highlights(doc, view_offset.anchor, text_height, theme, &loader)
             {
                 overlays.push(overlay);
             }
@@ -141,6 +201,24 @@ pub fn render_view(
 
         Self::doc_diagnostics_highlights_into(doc, theme, &mut overlays);
 
+        if let Some(overlay) = Self::misspelling_highlights(doc, theme) {
+            overlays.push(overlay);
+        }
+
+        if let Some(overlay) = Self::query_capture_highlights(doc, theme) {
+            overlays.push(overlay);
+        }
+
+        if let Some(overlay) =
+            Self::theme_scope_highlights(editor, doc, view_offset.anchor, text_height, theme)
+        {
+            overlays.push(overlay);
+        }
+
+        if let Some(overlay) = Self::document_highlights(doc, view, theme) {
+            overlays.push(overlay);
+        }
+
         if is_focused {
             if let Some(tabstops) = Self::tabstop_highlights(doc, theme) {
                 overlays.push(tabstops);
@@ -196,6 +274,12 @@ pub fn render_view(
             inline_diagnostic_config,
             config.end_of_line_diagnostics,
         ));
+        let indent_guide_scope =
+            if config.indent_guides.render && config.indent_guides.highlight_scope {
+                Self::indent_guide_scope(doc, primary_cursor, &loader)
+            } else {
+                None
+            };
         render_document(
             surface,
             inner,
@@ -203,13 +287,25 @@ pub fn render_view(
             view_offset,
             &text_annotations,
             syntax_highlighter,
+            Self::semantic_highlights(editor, doc, view_offset.anchor, text_height, theme),
             overlays,
             theme,
             decorations,
+            indent_guide_scope,
         );
 
+        if config.sticky_context.enable {
+            Self::render_sticky_context(doc, view, inner, surface, theme, &loader);
+        }
+
+        Self::render_minimap(doc, view, surface, theme);
+        if view.scrollbar_area(doc).width != 0 {
+            let search_lines = self.search_lines(editor, doc);
+            Self::render_scrollbar(doc, view, search_lines, surface, theme);
+        }
+
         // if we're not at the edge of the screen, draw a right border
-        if viewport.right() != view.area.right() {
+        if viewport.right() != view.area.right() && !editor.zen_mode() {
             let x = area.right();
             let border_style = theme.get("ui.window");
             for y in area.top()..area.bottom() {
@@ -226,6 +322,10 @@ pub fn render_view(
             Self::render_diagnostics(doc, view, inner, surface, theme);
         }
 
+        if editor.zen_mode() {
+            return;
+        }
+
         let statusline_area = view
             .area
             .clip_top(view.area.height.saturating_sub(1))
@@ -323,10 +423,21 @@ pub fn doc_rainbow_highlights(
         theme: &Theme,
         loader: &syntax::Loader,
     ) -> Option<OverlayHighlights> {
-        let syntax = doc.syntax()?;
         let text = doc.text().slice(..);
         let row = text.char_to_line(anchor.min(text.len_chars()));
         let visible_range = Self::viewport_byte_range(text, row, height);
+        let Some(syntax) = doc
+            .syntax()
+            .filter(|syntax| loader.has_rainbow_query(syntax.root_language()))
+        else {
+            let range =
+                text.byte_to_char(visible_range.start)..text.byte_to_char(visible_range.end);
+            return Some(helix_core::match_brackets::rainbow_highlights_plaintext(
+                text,
+                theme.rainbow_length(),
+                range,
+            ));
+        };
         let start = syntax::child_for_byte_range(
             &syntax.tree().root_node(),
             visible_range.start as u32..visible_range.end as u32,
@@ -337,6 +448,70 @@ pub fn doc_rainbow_highlights(
         Some(syntax.rainbow_highlights(text, theme.rainbow_length(), loader, range))
     }
 
+    /// The indent guide of the indentation scope around the cursor.
+    fn indent_guide_scope(
+        doc: &Document,
+        cursor: usize,
+        loader: &syntax::Loader,
+    ) -> Option<IndentGuideScope> {
+        let text = doc.text().slice(..);
+        let tab_width = doc.tab_width();
+        let indent_width = doc.indent_width();
+        let (first_line, last_line) = helix_core::indent::indent_scope_lines(
+            loader,
+            doc.syntax(),
+            text,
+            text.char_to_line(cursor),
+            tab_width,
+            indent_width,
+        )?;
+        Some(IndentGuideScope {
+            level: helix_core::indent::indent_level_for_line(
+                text.line(first_line),
+                tab_width,
+                indent_width,
+            ),
+            first_line,
+            last_line,
+        })
+    }
+
+    /// Moves the views which are smoothly scrolling to the top line of their next frame and
+    /// returns the offsets to restore once they are rendered.
+    fn smooth_scroll_frame(
+        editor: &mut Editor,
+        duration: Duration,
+    ) -> Vec<(DocumentId, ViewId, ViewPosition)> {
+        let now = Instant::now();
+        let view_ids: Vec<_> = editor.tree.views().map(|(view, _)| view.id).collect();
+        let mut scrolled = Vec::new();
+        for view_id in view_ids {
+            let view = editor.tree.get_mut(view_id);
+            let doc = doc_mut!(editor, &view.doc);
+            let Some(line) = view.smooth_scroll_line(doc, duration, now) else {
+                continue;
+            };
+            let offset = doc.view_offset(view_id);
+            scrolled.push((view.doc, view_id, offset));
+            doc.set_view_offset(
+                view_id,
+                ViewPosition {
+                    anchor: doc.text().line_to_char(line),
+                    horizontal_offset: offset.horizontal_offset,
+                    vertical_offset: 0,
+                },
+            );
+        }
+
+        if !scrolled.is_empty() {
+            tokio::spawn(async {
+                tokio::time::sleep(SMOOTH_SCROLL_FRAME).await;
+                helix_event::request_redraw();
+            });
+        }
+        scrolled
+    }
+
     /// Get highlight spans for document diagnostics
     pub fn doc_diagnostics_highlights_into(
         doc: &Document,
@@ -581,6 +756,140 @@ pub fn highlight_focused_view_elements(
         Some(OverlayHighlights::single(highlight, pos..pos + 1))
     }
 
+    /// Styles each line of the buffer of `:theme-preview` with the scope it names.
+    pub fn theme_scope_highlights(
+        editor: &Editor,
+        doc: &Document,
+        anchor: usize,
+        height: u16,
+        theme: &Theme,
+    ) -> Option<OverlayHighlights> {
+        if editor.output_buffers.get(theme::SCOPES_OUTPUT_NAME) != Some(&doc.id()) {
+            return None;
+        }
+        let text = doc.text().slice(..);
+        let first_line = text.char_to_line(anchor.min(text.len_chars()));
+        let last_line = (first_line + height as usize).min(text.len_lines());
+        let highlights = (first_line..last_line)
+            .filter_map(|line| {
+                let start = text.line_to_char(line);
+                let scope = text.line(line).to_string();
+                let scope = scope.trim_end();
+                let highlight = theme.find_highlight_exact(scope)?;
+                Some((highlight, start..start + scope.chars().count()))
+            })
+            .collect();
+        Some(OverlayHighlights::Heterogenous { highlights })
+    }
+
+    /// Highlights the semantic tokens of the visible lines with the first of their scopes the
+    /// theme styles.
+    pub fn semantic_highlights(
+        editor: &Editor,
+        doc: &Document,
+        anchor: usize,
+        height: u16,
+        theme: &Theme,
+    ) -> Option<SemanticHighlights> {
+        let mode = editor.config().lsp.semantic_tokens;
+        if mode == SemanticTokensMode::Off {
+            return None;
+        }
+        let tokens = doc.semantic_tokens.as_ref()?;
+        let text = doc.text().slice(..);
+        let first_line = text.char_to_line(anchor.min(text.len_chars()));
+        let last_line = (first_line + height as usize).min(text.len_lines());
+        let (start, end) = (text.line_to_char(first_line), text.line_to_char(last_line));
+
+        let scopes: Vec<_> = tokens
+            .scopes
+            .iter()
+            .map(|scopes| match scopes.split_last() {
+                Some((scope, modified)) => modified
+                    .iter()
+                    .find_map(|scope| theme.find_highlight_exact(scope))
+                    .or_else(|| theme.find_highlight(scope)),
+                None => None,
+            })
+            .collect();
+        let first = tokens
+            .ranges
+            .partition_point(|(range, _)| range.end <= start);
+        let mut highlights = Vec::new();
+        let mut last_end = 0;
+        for (range, scope) in &tokens.ranges[first..] {
+            if range.start >= end {
+                break;
+            }
+            // Edits since the tokens were received may have made them empty or overlapping.
+            if range.start < last_end || range.is_empty() {
+                continue;
+            }
+            if let Some(highlight) = scopes[*scope] {
+                highlights.push((highlight, range.clone()));
+                last_end = range.end;
+            }
+        }
+        Some(SemanticHighlights {
+            highlights: OverlayHighlights::Heterogenous { highlights },
+            mode,
+        })
+    }
+
+    /// Highlights the misspelled words found by `editor.spell-check`.
+    pub fn misspelling_highlights(doc: &Document, theme: &Theme) -> Option<OverlayHighlights> {
+        if doc.misspellings.is_empty() {
+            return None;
+        }
+        let highlight = theme.find_highlight("diagnostic.spelling")?;
+        Some(OverlayHighlights::Homogeneous {
+            highlight,
+            ranges: doc.misspellings.clone(),
+        })
+    }
+
+    /// Highlights the captures of the query of `:tree-sitter-playground` with the scope they
+    /// name, or with `ui.selection` if the theme doesn't style it.
+    pub fn query_capture_highlights(doc: &Document, theme: &Theme) -> Option<OverlayHighlights> {
+        if doc.query_captures.is_empty() {
+            return None;
+        }
+        let selection = theme.find_highlight_exact("ui.selection");
+        let highlights = doc
+            .query_captures
+            .iter()
+            .filter_map(|(name, range)| {
+                let highlight = theme.find_highlight(name).or(selection)?;
+                Some((highlight, range.clone()))
+            })
+            .collect();
+        Some(OverlayHighlights::Heterogenous { highlights })
+    }
+
+    /// Highlights the occurrences of the symbol under the cursor found by
+    /// `lsp.auto-document-highlight`.
+    pub fn document_highlights(
+        doc: &Document,
+        view: &View,
+        theme: &Theme,
+    ) -> Option<OverlayHighlights> {
+        let highlights = doc.document_highlights.get(&view.id)?;
+        // The read and write scopes fall back to `ui.highlight.reference`.
+        let scope = |kind| match kind {
+            lsp::DocumentHighlightKind::READ => "ui.highlight.reference.read",
+            lsp::DocumentHighlightKind::WRITE => "ui.highlight.reference.write",
+            _ => "ui.highlight.reference",
+        };
+        let highlights = highlights
+            .iter()
+            .filter_map(|highlight| {
+                let scope = theme.find_highlight(scope(highlight.kind))?;
+                Some((scope, highlight.range.clone()))
+            })
+            .collect();
+        Some(OverlayHighlights::Heterogenous { highlights })
+    }
+
     pub fn tabstop_highlights(doc: &Document, theme: &Theme) -> Option<OverlayHighlights> {
         let snippet = doc.active_snippet.as_ref()?;
         let highlight = theme.find_highlight_exact("tabstop")?;
@@ -593,7 +902,6 @@ pub fn tabstop_highlights(doc: &Document, theme: &Theme) -> Option<OverlayHighli
 
     /// Render bufferline at the top
     pub fn render_bufferline(editor: &Editor, viewport: Rect, surface: &mut Surface) {
-        let scratch = PathBuf::from(SCRATCH_BUFFER_NAME); // default filename to use for scratch buffer
         surface.clear_with(
             viewport,
             editor
@@ -612,25 +920,21 @@ pub fn render_bufferline(editor: &Editor, viewport: Rect, surface: &mut Surface)
             .try_get("ui.bufferline")
             .unwrap_or_else(|| editor.theme.get("ui.statusline.inactive"));
 
+        let bufferline_group = editor
+            .theme
+            .try_get("ui.bufferline.group")
+            .unwrap_or_else(|| bufferline_inactive.add_modifier(Modifier::BOLD));
+
         let mut x = viewport.x;
         let current_doc = view!(editor).doc;
 
-        for doc in editor.documents() {
-            let fname = doc
-                .path()
-                .unwrap_or(&scratch)
-                .file_name()
-                .unwrap_or_default()
-                .to_str()
-                .unwrap_or_default();
-
-            let style = if current_doc == doc.id() {
-                bufferline_active
-            } else {
-                bufferline_inactive
+        for (doc_id, text) in Self::bufferline_segments(editor) {
+            let style = match doc_id {
+                Some(doc_id) if doc_id == current_doc => bufferline_active,
+                Some(_) => bufferline_inactive,
+                None => bufferline_group,
             };
 
-            let text = format!(" {}{} ", fname, if doc.is_modified() { "[+]" } else { "" });
             let used_width = viewport.x.saturating_sub(x);
             let rem_width = surface.area.width.saturating_sub(used_width);
 
@@ -644,6 +948,55 @@ pub fn render_bufferline(editor: &Editor, viewport: Rect, surface: &mut Surface)
         }
     }
 
+    /// The text of each entry of the bufferline along with its buffer, or `None` for the labels
+    /// of groups.
+    fn bufferline_segments(editor: &Editor) -> Vec<(Option<DocumentId>, String)> {
+        let mut segments = Vec::new();
+        let mut group = None;
+        for doc_id in editor.buffer_order() {
+            let doc = &editor.documents[&doc_id];
+            let pinned = editor.pinned_buffers.contains(&doc_id);
+            let doc_group = if pinned {
+                None
+            } else {
+                editor.buffer_group(doc)
+            };
+            if let Some(label) = doc_group.as_ref().filter(|_| doc_group != group) {
+                segments.push((None, format!(" {label}: ")));
+            }
+            group = doc_group;
+
+            let fname = match doc.path() {
+                Some(path) => path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_str()
+                    .unwrap_or_default(),
+                None => doc.scratch_name(),
+            };
+            let text = format!(
+                " {}{}{} ",
+                if pinned { "• " } else { "" },
+                fname,
+                if doc.is_modified() { "[+]" } else { "" }
+            );
+            segments.push((Some(doc_id), text));
+        }
+        segments
+    }
+
+    /// The buffer of the bufferline entry at the screen column.
+    fn bufferline_doc_at(editor: &Editor, column: u16) -> Option<DocumentId> {
+        let mut x = 0;
+        for (doc_id, text) in Self::bufferline_segments(editor) {
+            x += text.width();
+            if (column as usize) < x {
+                return doc_id;
+            }
+        }
+        None
+    }
+
     pub fn render_gutter<'d>(
         editor: &'d Editor,
         doc: &'d Document,
@@ -709,6 +1062,305 @@ pub fn render_gutter<'d>(
         }
     }
 
+    /// Render the first lines of the functions and classes enclosing the top of the viewport
+    /// over the top rows of the view. The row of the primary cursor is never covered.
+    pub fn render_sticky_context(
+        doc: &Document,
+        view: &View,
+        viewport: Rect,
+        surface: &mut Surface,
+        theme: &Theme,
+        loader: &syntax::Loader,
+    ) {
+        let Some(syntax) = doc.syntax() else {
+            return;
+        };
+        let config = doc.config.load();
+        let text = doc.text().slice(..);
+        let view_offset = doc.view_offset(view.id);
+        let top = text.char_to_line(view_offset.anchor.min(text.len_chars()));
+
+        let max_rows = view
+            .screen_coords_at_pos(doc, text, doc.selection(view.id).primary().cursor(text))
+            .map_or(viewport.height as usize, |pos| pos.row)
+            .min(config.sticky_context.max_depth);
+        // The headers cover the first rows of the viewport, so their context is shown instead.
+        let covered = helix_core::textobject::enclosing_scope_lines(text, top, syntax, loader)
+            .len()
+            .min(max_rows);
+        let scopes = helix_core::textobject::enclosing_scope_lines(
+            text,
+            (top + covered).min(text.len_lines().saturating_sub(1)),
+            syntax,
+            loader,
+        );
+        let scopes = &scopes[scopes.len().saturating_sub(max_rows)..];
+
+        let style = theme
+            .try_get("ui.sticky-context")
+            .unwrap_or_else(|| theme.get("ui.cursorline.secondary"));
+        let tab = " ".repeat(doc.tab_width());
+        for (y, &line) in (viewport.top()..viewport.bottom()).zip(scopes) {
+            let row = Rect::new(view.area.x, y, viewport.right() - view.area.x, 1);
+            surface.clear_with(row, style);
+            let header: String = text
+                .line(line)
+                .chars()
+                .filter(|ch| !matches!(ch, '\n' | '\r'))
+                .skip(view_offset.horizontal_offset)
+                .collect();
+            surface.set_stringn(
+                viewport.x,
+                y,
+                header.replace('\t', &tab),
+                viewport.width as usize,
+                style,
+            );
+        }
+    }
+
+    /// Render a condensed overview of the document using braille characters, each of which
+    /// shows 4 lines and 4 columns of the text. Rows showing the viewport are highlighted and
+    /// lines with diagnostics or changes are colored.
+    pub fn render_minimap(doc: &Document, view: &View, surface: &mut Surface, theme: &Theme) {
+        use helix_core::diagnostic::Severity;
+        use helix_view::view::MINIMAP_LINES_PER_ROW;
+
+        const CHARS_PER_DOT: usize = 2;
+        // The bits of the braille dots, indexed by column then row.
+        const DOTS: [[u32; MINIMAP_LINES_PER_ROW]; 2] =
+            [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+        let area = view.minimap_area(doc);
+        if area.width == 0 {
+            return;
+        }
+
+        let text = doc.text().slice(..);
+        let len_lines = text.len_lines();
+        let first_line = view.minimap_first_line(doc);
+        let view_offset = doc.view_offset(view.id);
+        let viewport_start = text.char_to_line(view_offset.anchor.min(text.len_chars()));
+        let viewport = viewport_start..viewport_start + view.inner_height();
+        let horizontal_offset = view_offset.horizontal_offset;
+
+        let base_style = theme.get("ui.minimap");
+        let viewport_style = base_style.patch(
+            theme
+                .try_get("ui.minimap.viewport")
+                .unwrap_or_else(|| theme.get("ui.cursorline.primary")),
+        );
+        let error = theme.get("error");
+        let warning = theme.get("warning");
+        let info = theme.get("info");
+        let hint = theme.get("hint");
+        let added = theme.get("diff.plus");
+        let modified = theme.get("diff.delta");
+        let deleted = theme.get("diff.minus");
+
+        let diff = doc.diff_handle().map(|handle| handle.load());
+        let mut hunk_i = 0;
+        let line_style = |line: usize, hunk_i: &mut u32| -> Option<Style> {
+            let severity = doc
+                .diagnostics()
+                .iter()
+                .filter(|diagnostic| diagnostic.line == line)
+                .filter_map(|diagnostic| diagnostic.severity)
+                .max();
+            if let Some(severity) = severity {
+                return Some(match severity {
+                    Severity::Error => error,
+                    Severity::Warning => warning,
+                    Severity::Info => info,
+                    Severity::Hint => hint,
+                });
+            }
+            let diff = diff.as_ref()?;
+            let line = line as u32;
+            let mut hunk = diff.nth_hunk(*hunk_i);
+            while hunk.after.end < line || !hunk.is_pure_removal() && line == hunk.after.end {
+                *hunk_i += 1;
+                hunk = diff.nth_hunk(*hunk_i);
+            }
+            if hunk.after.start > line {
+                None
+            } else if hunk.is_pure_insertion() {
+                Some(added)
+            } else if hunk.is_pure_removal() {
+                Some(deleted)
+            } else {
+                Some(modified)
+            }
+        };
+
+        for (row, y) in (area.top()..area.bottom()).enumerate() {
+            let row_start = first_line + row * MINIMAP_LINES_PER_ROW;
+            let row_lines = row_start..(row_start + MINIMAP_LINES_PER_ROW).min(len_lines);
+            let mut style = if row_lines.start < viewport.end && viewport.start < row_lines.end {
+                viewport_style
+            } else {
+                base_style
+            };
+
+            let mut cells = vec![0u32; area.width as usize];
+            for (dot_row, line) in row_lines.enumerate() {
+                if let Some(line_style) = line_style(line, &mut hunk_i) {
+                    style = style.patch(line_style);
+                }
+                let chars = text.line(line).chars().skip(horizontal_offset);
+                for (i, ch) in chars
+                    .take(area.width as usize * 2 * CHARS_PER_DOT)
+                    .enumerate()
+                {
+                    if !ch.is_whitespace() {
+                        let dot = i / CHARS_PER_DOT;
+                        cells[dot / 2] |= DOTS[dot % 2][dot_row];
+                    }
+                }
+            }
+
+            for (x, bits) in (area.left()..area.right()).zip(cells) {
+                let symbol = char::from_u32(0x2800 + bits).unwrap_or(' ');
+                surface[(x, y)].set_char(symbol).set_style(style);
+            }
+        }
+    }
+
+    /// Returns the lines of `doc` matching the last search. They're only searched again when the
+    /// document or the search changes.
+    fn search_lines(&mut self, editor: &Editor, doc: &Document) -> &[usize] {
+        use helix_stdx::rope::RopeSliceExt;
+
+        let register = editor.registers.last_search_register;
+        let query = editor.registers.first(register, editor).unwrap_or_default();
+        let smart_case = editor.config().search.smart_case;
+        let version = doc.version();
+        let outdated = self.search_marks.get(&doc.id()).is_none_or(|marks| {
+            marks.query != query || marks.smart_case != smart_case || marks.version != version
+        });
+        if outdated {
+            let text = doc.text().slice(..);
+            let mut lines = Vec::new();
+            if let Some(regex) = (!query.is_empty())
+                .then(|| commands::search_regex(&query, &editor.config().search))
+                .flatten()
+            {
+                for mat in regex.find_iter(text.regex_input()) {
+                    let line = text.byte_to_line(mat.start());
+                    if lines.last() != Some(&line) {
+                        lines.push(line);
+                    }
+                }
+            }
+            let marks = SearchMarks {
+                query: query.into_owned(),
+                smart_case,
+                version,
+                lines,
+            };
+            self.search_marks.insert(doc.id(), marks);
+        }
+        &self.search_marks[&doc.id()].lines
+    }
+
+    /// Render a scrollbar showing the position of the viewport in the document. Rows of the
+    /// scrollbar are marked when their lines have diagnostics, matches of the last search
+    /// (`search_lines`) or changes.
+    pub fn render_scrollbar(
+        doc: &Document,
+        view: &View,
+        search_lines: &[usize],
+        surface: &mut Surface,
+        theme: &Theme,
+    ) {
+        use helix_core::diagnostic::Severity;
+
+        let area = view.scrollbar_area(doc);
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let text = doc.text().slice(..);
+        let len_lines = text.len_lines();
+        let height = area.height as usize;
+        let viewport_start =
+            text.char_to_line(doc.view_offset(view.id).anchor.min(text.len_chars()));
+        let viewport_end = viewport_start + view.inner_height();
+        let total = len_lines.max(viewport_end);
+        let thumb_start = viewport_start * height / total;
+        let thumb_end = (viewport_end * height).div_ceil(total).max(thumb_start + 1);
+
+        // Marks with a higher priority replace the marks of lower priority on the same row.
+        let mut marks: Vec<Option<(u8, Style)>> = vec![None; height];
+        let mut mark = |line: usize, priority: u8, style: Style| {
+            let row = &mut marks[view.scrollbar_row(doc, line) as usize];
+            if row.map_or(true, |(current, _)| current < priority) {
+                *row = Some((priority, style));
+            }
+        };
+
+        if let Some(diff) = doc.diff_handle().map(|handle| handle.load()) {
+            let added = theme.get("diff.plus");
+            let modified = theme.get("diff.delta");
+            let deleted = theme.get("diff.minus");
+            for i in 0..diff.len() {
+                let hunk = diff.nth_hunk(i);
+                let style = if hunk.is_pure_insertion() {
+                    added
+                } else if hunk.is_pure_removal() {
+                    deleted
+                } else {
+                    modified
+                };
+                let lines = hunk.after.start as usize
+                    ..(hunk.after.end as usize).max(hunk.after.start as usize + 1);
+                for line in lines {
+                    mark(line, 0, style);
+                }
+            }
+        }
+
+        let style = theme
+            .try_get("ui.scrollbar.search")
+            .unwrap_or_else(|| theme.get("special"));
+        for &line in search_lines {
+            mark(line, 1, style);
+        }
+
+        for diagnostic in doc.diagnostics() {
+            let (priority, scope) = match diagnostic.severity {
+                Some(Severity::Hint) => (2, "hint"),
+                Some(Severity::Info) => (3, "info"),
+                Some(Severity::Warning) | None => (4, "warning"),
+                Some(Severity::Error) => (5, "error"),
+            };
+            mark(diagnostic.line, priority, theme.get(scope));
+        }
+
+        let scrollbar_style = theme
+            .try_get("ui.scrollbar")
+            .unwrap_or_else(|| theme.get("ui.menu.scroll"));
+        let thumb = scrollbar_style.fg.unwrap_or(Color::Reset);
+        let track = scrollbar_style.bg.unwrap_or(Color::Reset);
+        let x = area.left();
+        for (i, (y, mark)) in (area.top()..area.bottom()).zip(marks).enumerate() {
+            let in_thumb = (thumb_start..thumb_end).contains(&i);
+            let cell = &mut surface[(x, y)];
+            match mark {
+                Some((_, style)) => {
+                    cell.set_symbol("━").set_style(style);
+                    if in_thumb {
+                        cell.set_bg(thumb);
+                    }
+                }
+                None => {
+                    cell.set_symbol("▐")
+                        .set_fg(if in_thumb { thumb } else { track });
+                }
+            }
+        }
+    }
+
     pub fn render_diagnostics(
         doc: &Document,
         view: &View,
@@ -1117,6 +1769,28 @@ fn handle_non_key_input(&mut self, cxt: &mut commands::Context) {
         self.pseudo_pending.clear();
     }
 
+    /// Scroll the view so that the lines shown by the scrollbar at the screen row are in the
+    /// middle of the viewport.
+    fn scroll_to_scrollbar_row(cxt: &mut commands::Context, view_id: ViewId, row: u16) {
+        let current_view = cxt.editor.tree.focus;
+        cxt.editor.tree.focus = view_id;
+
+        let (view, doc) = current!(cxt.editor);
+        let line = view.scrollbar_line_at_screen_row(doc, row);
+        let top = line.saturating_sub(view.inner_height() / 2);
+        let text = doc.text().slice(..);
+        let current_top = text.char_to_line(doc.view_offset(view.id).anchor.min(text.len_chars()));
+        match top.cmp(&current_top) {
+            Ordering::Greater => {
+                commands::scroll(cxt, top - current_top, Direction::Forward, false)
+            }
+            Ordering::Less => commands::scroll(cxt, current_top - top, Direction::Backward, false),
+            Ordering::Equal => (),
+        }
+
+        cxt.editor.tree.focus = current_view;
+    }
+
     fn handle_mouse_event(
         &mut self,
         event: &MouseEvent,
@@ -1136,7 +1810,7 @@ fn handle_mouse_event(
         } = *event;
 
         let pos_and_view = |editor: &Editor, row, column, ignore_virtual_text| {
-            editor.tree.views().find_map(|(view, _focus)| {
+            editor.tree.visible_views().find_map(|(view, _focus)| {
                 view.pos_at_screen_coords(
                     &editor.documents[&view.doc],
                     row,
@@ -1148,14 +1822,53 @@ fn handle_mouse_event(
         };
 
         let gutter_coords_and_view = |editor: &Editor, row, column| {
-            editor.tree.views().find_map(|(view, _focus)| {
+            editor.tree.visible_views().find_map(|(view, _focus)| {
                 view.gutter_coords_at_screen_coords(row, column)
                     .map(|coords| (coords, view.id))
             })
         };
 
         match kind {
+            // The bufferline is rendered above the views.
+            MouseEventKind::Down(MouseButton::Left) if row < cxt.editor.tree.area().y => {
+                if let Some(doc_id) = Self::bufferline_doc_at(cxt.editor, column) {
+                    cxt.editor.switch(doc_id, Action::Replace);
+                }
+                EventResult::Consumed(None)
+            }
+
+            MouseEventKind::Up(MouseButton::Middle) if row < cxt.editor.tree.area().y => {
+                if let Some(doc_id) = Self::bufferline_doc_at(cxt.editor, column) {
+                    if let Err(CloseError::BufferModified(name)) =
+                        cxt.editor.close_document(doc_id, false)
+                    {
+                        cxt.editor
+                            .set_error(format!("Buffer {name} has unsaved changes"));
+                    }
+                }
+                EventResult::Consumed(None)
+            }
+
             MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(border) = cxt.editor.tree.split_border_at(row, column) {
+                    self.split_drag = Some(border);
+                    return EventResult::Consumed(None);
+                }
+
+                let scrollbar_view = cxt.editor.tree.visible_views().find_map(|(view, _focus)| {
+                    view.is_scrollbar_at_screen_coords(
+                        &cxt.editor.documents[&view.doc],
+                        row,
+                        column,
+                    )
+                    .then_some(view.id)
+                });
+                if let Some(view_id) = scrollbar_view {
+                    self.scrollbar_drag = Some(view_id);
+                    Self::scroll_to_scrollbar_row(cxt, view_id, row);
+                    return EventResult::Consumed(None);
+                }
+
                 let editor = &mut cxt.editor;
 
                 if let Some((pos, view_id)) = pos_and_view(editor, row, column, true) {
@@ -1167,6 +1880,13 @@ fn handle_mouse_event(
                     if modifiers == KeyModifiers::ALT {
                         let selection = doc.selection(view_id).clone();
                         doc.set_selection(view_id, selection.push(Range::point(pos)));
+                        #[allow(deprecated)]
+                        let coords = helix_core::visual_coords_at_pos(
+                            doc.text().slice(..),
+                            pos,
+                            doc.tab_width(),
+                        );
+                        self.block_drag = Some(coords);
                     } else if editor.mode == Mode::Select {
                         // Discards non-primary selections for consistent UX with normal mode
                         let primary = doc.selection(view_id).primary().put_cursor(
@@ -1189,6 +1909,24 @@ fn handle_mouse_event(
                     return EventResult::Consumed(None);
                 }
 
+                let minimap_line_and_view =
+                    editor.tree.visible_views().find_map(|(view, _focus)| {
+                        view.minimap_line_at_screen_coords(
+                            &editor.documents[&view.doc],
+                            row,
+                            column,
+                        )
+                        .map(|line| (line, view.id))
+                    });
+                if let Some((line, view_id)) = minimap_line_and_view {
+                    editor.focus(view_id);
+                    let (view, doc) = current!(editor);
+                    let pos = doc.text().line_to_char(line);
+                    doc.set_selection(view.id, Selection::point(pos));
+                    align_view(doc, view, Align::Center);
+                    return EventResult::Consumed(None);
+                }
+
                 if let Some((coords, view_id)) = gutter_coords_and_view(editor, row, column) {
                     editor.focus(view_id);
 
@@ -1212,6 +1950,24 @@ fn handle_mouse_event(
             }
 
             MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some(border) = self.split_drag {
+                    let editor = &mut cxt.editor;
+                    editor.tree.resize_split(border, row, column);
+                    for (view, _) in editor.tree.views_mut() {
+                        let doc = doc_mut!(editor, &view.doc);
+                        view.ensure_cursor_in_view(doc, config.scrolloff);
+                    }
+                    return EventResult::Consumed(None);
+                }
+
+                if let Some(view_id) = self.scrollbar_drag {
+                    if cxt.editor.tree.contains(view_id) {
+                        Self::scroll_to_scrollbar_row(cxt, view_id, row);
+                        return EventResult::Consumed(None);
+                    }
+                    self.scrollbar_drag = None;
+                }
+
                 let (view, doc) = current!(cxt.editor);
 
                 let pos = match view.pos_at_screen_coords(doc, row, column, true) {
@@ -1219,9 +1975,17 @@ fn handle_mouse_event(
                     None => return EventResult::Ignored(None),
                 };
 
-                let mut selection = doc.selection(view.id).clone();
-                let primary = selection.primary_mut();
-                *primary = primary.put_cursor(doc.text().slice(..), pos, true);
+                let selection = if let Some(anchor) = self.block_drag {
+                    let text = doc.text().slice(..);
+                    #[allow(deprecated)]
+                    let head = helix_core::visual_coords_at_pos(text, pos, doc.tab_width());
+                    helix_core::selection::select_block(text, anchor, head, doc.tab_width())
+                } else {
+                    let mut selection = doc.selection(view.id).clone();
+                    let primary = selection.primary_mut();
+                    *primary = primary.put_cursor(doc.text().slice(..), pos, true);
+                    selection
+                };
                 doc.set_selection(view.id, selection);
                 let view_id = view.id;
                 cxt.editor.ensure_cursor_in_view(view_id);
@@ -1252,6 +2016,13 @@ fn handle_mouse_event(
             }
 
             MouseEventKind::Up(MouseButton::Left) => {
+                if self.scrollbar_drag.take().is_some()
+                    || self.split_drag.take().is_some()
+                    || self.block_drag.take().is_some()
+                {
+                    return EventResult::Consumed(None);
+                }
+
                 if !config.middle_click_paste {
                     return EventResult::Ignored(None);
                 }
@@ -1295,7 +2066,7 @@ fn handle_mouse_event(
                                 KeyModifiers::ALT => {
                                     commands::MappableCommand::dap_edit_log.execute(cxt)
                                 }
-                                _ => commands::MappableCommand::dap_edit_condition.execute(cxt),
+                                _ => commands::MappableCommand::dap_edit_breakpoint.execute(cxt),
                             };
                         }
                     }
@@ -1331,9 +2102,117 @@ fn handle_mouse_event(
                 EventResult::Ignored(None)
             }
 
+            MouseEventKind::Moved if config.mouse_hover.enable => {
+                self.handle_mouse_hover(cxt, row, column)
+            }
+
             _ => EventResult::Ignored(None),
         }
     }
+
+    /// Tracks the text position the mouse rests over, showing its diagnostics and hover
+    /// information once the mouse stayed over it for the configured delay.
+    fn handle_mouse_hover(
+        &mut self,
+        cxt: &mut commands::Context,
+        row: u16,
+        column: u16,
+    ) -> EventResult {
+        let hover = cxt.editor.tree.visible_views().find_map(|(view, _focus)| {
+            let pos =
+                view.pos_at_screen_coords(&cxt.editor.documents[&view.doc], row, column, true)?;
+            Some(MouseHover {
+                view_id: view.id,
+                pos,
+                screen: Position::new(row as usize, column as usize),
+            })
+        });
+        if hover == self.mouse_hover {
+            return EventResult::Ignored(None);
+        }
+        self.mouse_hover = hover;
+
+        if let Some(hover) = hover {
+            let delay = cxt.editor.config().mouse_hover.delay;
+            cxt.jobs.callback(async move {
+                tokio::time::sleep(delay).await;
+                let call = move |editor: &mut Editor, compositor: &mut Compositor| {
+                    Self::show_mouse_hover(editor, compositor, hover)
+                };
+                Ok(crate::job::Callback::EditorCompositor(Box::new(call)))
+            });
+        }
+
+        // Close the popup of the position the mouse left.
+        EventResult::Consumed(Some(Box::new(|compositor: &mut Compositor, _| {
+            compositor.remove(MOUSE_HOVER_ID);
+        })))
+    }
+
+    fn show_mouse_hover(editor: &mut Editor, compositor: &mut Compositor, hover: MouseHover) {
+        let is_hovered = move |compositor: &mut Compositor| {
+            compositor
+                .find::<EditorView>()
+                .is_some_and(|editor_view| editor_view.mouse_hover == Some(hover))
+        };
+        if !is_hovered(compositor) || !editor.tree.contains(hover.view_id) {
+            return;
+        }
+        let doc = &editor.documents[&editor.tree.get(hover.view_id).doc];
+
+        let diagnostics: Vec<_> = doc
+            .diagnostics()
+            .iter()
+            .filter(|diagnostic| {
+                (diagnostic.range.start..diagnostic.range.end.max(diagnostic.range.start + 1))
+                    .contains(&hover.pos)
+            })
+            .map(|diagnostic| match &diagnostic.source {
+                Some(source) => format!("{} ({source})", diagnostic.message),
+                None => diagnostic.message.clone(),
+            })
+            .collect();
+        let mut hovers = Vec::new();
+        if !diagnostics.is_empty() {
+            let contents = lsp::HoverContents::Markup(lsp::MarkupContent {
+                kind: lsp::MarkupKind::PlainText,
+                value: diagnostics.join("\n\n"),
+            });
+            hovers.push((
+                "diagnostics".to_string(),
+                lsp::Hover {
+                    contents,
+                    range: None,
+                },
+            ));
+        }
+
+        let show = move |editor: &mut Editor, compositor: &mut Compositor, hovers: Vec<_>| {
+            if hovers.is_empty() || !is_hovered(compositor) {
+                return;
+            }
+            let contents = Hover::new(hovers, editor.syn_loader.clone());
+            let popup = Popup::new(MOUSE_HOVER_ID, contents)
+                .fixed_position(hover.screen)
+                .auto_close(true);
+            compositor.replace_or_push(MOUSE_HOVER_ID, popup);
+        };
+
+        let has_hover_servers = doc
+            .language_servers_with_feature(LanguageServerFeature::Hover)
+            .next()
+            .is_some();
+        if !editor.config().mouse_hover.lsp || !has_hover_servers {
+            show(editor, compositor, hovers);
+            return;
+        }
+        let request = commands::lsp::request_hovers(doc, hover.pos);
+        tokio::spawn(async move {
+            hovers.extend(request.await);
+            crate::job::dispatch(move |editor, compositor| show(editor, compositor, hovers)).await;
+        });
+    }
+
     fn on_next_key(
         &mut self,
         kind: OnKeyCallbackKind,
@@ -1510,7 +2389,8 @@ fn handle_event(
                     let options = commands::WriteAllOptions {
                         force: false,
                         write_scratch: false,
-                        auto_format: false,
+                        auto_format: context.editor.config().auto_save.format,
+                        auto_save: true,
                     };
                     if let Err(e) = commands::typed::write_all_impl(context, options) {
                         context.editor.set_error(format!("{}", e));
@@ -1530,6 +2410,7 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
         // check if bufferline should be rendered
         use helix_view::editor::BufferLine;
         let use_bufferline = match config.bufferline {
+            _ if cx.editor.zen_mode() => false,
             BufferLine::Always => true,
             BufferLine::Multiple if cx.editor.documents.len() > 1 => true,
             _ => false,
@@ -1548,11 +2429,23 @@ fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
             Self::render_bufferline(cx.editor, area.with_height(1), surface);
         }
 
-        for (view, is_focused) in cx.editor.tree.views() {
+        let scrolled = if config.smooth_scroll.enable {
+            Self::smooth_scroll_frame(cx.editor, config.smooth_scroll.duration)
+        } else {
+            Vec::new()
+        };
+
+        self.search_marks
+            .retain(|doc_id, _| cx.editor.documents.contains_key(doc_id));
+        for (view, is_focused) in cx.editor.tree.visible_views() {
             let doc = cx.editor.document(view.doc).unwrap();
             self.render_view(cx.editor, doc, view, area, surface, is_focused);
         }
 
+        for (doc_id, view_id, offset) in scrolled {
+            doc_mut!(cx.editor, &doc_id).set_view_offset(view_id, offset);
+        }
+
         if config.auto_info {
             if let Some(mut info) = cx.editor.autoinfo.take() {
                 info.render(area, surface, cx);
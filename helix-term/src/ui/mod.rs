@@ -1,4 +1,5 @@
 mod completion;
+mod dashboard;
 mod document;
 pub(crate) mod editor;
 mod info;
@@ -13,11 +14,13 @@
 mod statusline;
 mod text;
 mod text_decorations;
+mod workspace_edit;
 
 use crate::compositor::Compositor;
 use crate::filter_picker_entry;
 use crate::job::{self, Callback};
 pub use completion::Completion;
+pub use dashboard::Dashboard;
 pub use editor::EditorView;
 use helix_stdx::rope;
 use helix_view::theme::Style;
@@ -28,6 +31,7 @@
 pub use prompt::{Prompt, PromptEvent};
 pub use spinner::{ProgressSpinners, Spinner};
 pub use text::Text;
+pub use workspace_edit::WorkspaceEditReview;
 
 use helix_view::Editor;
 use tui::text::{Span, Spans};
@@ -208,24 +212,16 @@ pub struct FilePickerData {
 }
 type FilePicker = Picker<PathBuf, FilePickerData>;
 
-pub fn file_picker(editor: &Editor, root: PathBuf) -> FilePicker {
+/// Walks the files below `root` which are shown by the file picker, as configured by
+/// `editor.file-picker`.
+pub fn picker_files(editor: &Editor, root: &Path) -> impl Iterator<Item = PathBuf> + Send {
     use ignore::WalkBuilder;
-    use std::time::Instant;
 
     let config = editor.config();
-    let data = FilePickerData {
-        root: root.clone(),
-        directory_style: editor.theme.get("ui.text.directory"),
-    };
-
-    let now = Instant::now();
-
     let dedup_symlinks = config.file_picker.deduplicate_links;
-    let absolute_root = root.canonicalize().unwrap_or_else(|_| root.clone());
+    let absolute_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
 
-    let mut walk_builder = WalkBuilder::new(&root);
-
-    let mut files = walk_builder
+    WalkBuilder::new(root)
         .hidden(config.file_picker.hidden)
         .parents(config.file_picker.parents)
         .ignore(config.file_picker.ignore)
@@ -246,7 +242,20 @@ pub fn file_picker(editor: &Editor, root: PathBuf) -> FilePicker {
                 return None;
             }
             Some(entry.into_path())
-        });
+        })
+}
+
+pub fn file_picker(editor: &Editor, root: PathBuf) -> FilePicker {
+    use std::time::Instant;
+
+    let data = FilePickerData {
+        root: root.clone(),
+        directory_style: editor.theme.get("ui.text.directory"),
+    };
+
+    let now = Instant::now();
+
+    let mut files = picker_files(editor, &root);
     log::debug!("file_picker init {:?}", Instant::now().duration_since(now));
 
     let columns = [PickerColumn::new(
@@ -418,15 +427,17 @@ pub mod completers {
     use helix_core::command_line::{self, Tokenizer};
     use helix_core::fuzzy::fuzzy_match;
     use helix_core::syntax::config::LanguageServerFeature;
-    use helix_view::document::SCRATCH_BUFFER_NAME;
     use helix_view::theme;
     use helix_view::{editor::Config, Editor};
     use once_cell::sync::Lazy;
     use std::borrow::Cow;
     use std::collections::BTreeSet;
+    use std::ops::Deref;
     use tui::text::Span;
 
     pub type Completer = fn(&Editor, &str) -> Vec<Completion>;
+    /// Completes the value of the key given as the first argument, such as an option's value.
+    pub type ValueCompleter = fn(&Editor, &str, &str) -> Vec<Completion>;
 
     pub fn none(_editor: &Editor, _input: &str) -> Vec<Completion> {
         Vec::new()
@@ -436,7 +447,7 @@ pub fn buffer(editor: &Editor, input: &str) -> Vec<Completion> {
         let names = editor.documents.values().map(|doc| {
             doc.relative_path()
                 .map(|p| p.display().to_string().into())
-                .unwrap_or_else(|| Cow::from(SCRATCH_BUFFER_NAME))
+                .unwrap_or_else(|| Cow::from(doc.scratch_name().to_string()))
         });
 
         fuzzy_match(input, names, true)
@@ -445,7 +456,8 @@ pub fn buffer(editor: &Editor, input: &str) -> Vec<Completion> {
             .collect()
     }
 
-    pub fn theme(_editor: &Editor, input: &str) -> Vec<Completion> {
+    /// The sorted names of the themes in the config and runtime directories.
+    pub fn theme_names() -> Vec<String> {
         let mut names = theme::Loader::read_names(&helix_loader::config_dir().join("themes"));
         for rt_dir in helix_loader::runtime_dirs() {
             names.extend(theme::Loader::read_names(&rt_dir.join("themes")));
@@ -454,8 +466,11 @@ pub fn theme(_editor: &Editor, input: &str) -> Vec<Completion> {
         names.push("base16_default".into());
         names.sort();
         names.dedup();
+        names
+    }
 
-        fuzzy_match(input, names, false)
+    pub fn theme(_editor: &Editor, input: &str) -> Vec<Completion> {
+        fuzzy_match(input, theme_names(), false)
             .into_iter()
             .map(|(name, _)| ((0..), name.into()))
             .collect()
@@ -516,6 +531,100 @@ pub fn setting(_editor: &Editor, input: &str) -> Vec<Completion> {
             .collect()
     }
 
+    /// The values of the config options which are enums, keyed like `:set`.
+    pub(crate) const SETTING_VARIANTS: &[(&str, &[&str])] = &[
+        ("bufferline", &["never", "always", "multiple"]),
+        ("bufferline-group-by", &["none", "directory"]),
+        (
+            "clipboard-provider",
+            &[
+                "pasteboard",
+                "wayland",
+                "x-clip",
+                "x-sel",
+                "win32-yank",
+                "tmux",
+                "termux",
+                "termcode",
+                "none",
+            ],
+        ),
+        ("cursor-shape.insert", CURSOR_SHAPES),
+        ("cursor-shape.normal", CURSOR_SHAPES),
+        ("cursor-shape.select", CURSOR_SHAPES),
+        ("default-line-ending", &["native", "lf", "crlf"]),
+        ("end-of-line-diagnostics", SEVERITIES),
+        ("indent-heuristic", &["simple", "tree-sitter", "hybrid"]),
+        ("inline-diagnostics.cursor-line", SEVERITIES),
+        ("inline-diagnostics.other-lines", SEVERITIES),
+        ("interactive.on-exit", &["close", "keep", "keep-on-error"]),
+        ("kitty-keyboard-protocol", &["auto", "disabled", "enabled"]),
+        ("line-number", &["absolute", "relative"]),
+        ("lsp.semantic-tokens", &["off", "override", "fallback"]),
+        ("popup-border", &["none", "all", "popup", "menu"]),
+        ("whitespace.render", &["none", "all"]),
+    ];
+    const CURSOR_SHAPES: &[&str] = &["block", "bar", "underline", "hidden"];
+    const SEVERITIES: &[&str] = &["disable", "hint", "info", "warning", "error"];
+
+    /// Completes the values of the config option `key`: `true` and `false` for booleans, the
+    /// variants of enums and otherwise the current value.
+    pub fn setting_value(editor: &Editor, key: &str, input: &str) -> Vec<Completion> {
+        let key = key.to_lowercase();
+        let config = serde_json::json!(editor.config().deref());
+        let pointer = format!("/{}", key.replace('.', "/"));
+        let Some(value) = config.pointer(&pointer) else {
+            return Vec::new();
+        };
+        let values: Vec<String> = match value {
+            serde_json::Value::Bool(_) => vec!["true".into(), "false".into()],
+            serde_json::Value::String(current) => {
+                let variants = SETTING_VARIANTS
+                    .iter()
+                    .find(|(name, _)| *name == key)
+                    .map_or(&[][..], |(_, variants)| *variants);
+                std::iter::once(current.as_str())
+                    .chain(
+                        variants
+                            .iter()
+                            .copied()
+                            .filter(|variant| variant != current),
+                    )
+                    .map(String::from)
+                    .collect()
+            }
+            serde_json::Value::Number(_) => vec![value.to_string()],
+            _ => Vec::new(),
+        };
+
+        fuzzy_match(input, &values, false)
+            .into_iter()
+            .map(|(value, _)| ((0..), value.clone().into()))
+            .collect()
+    }
+
+    /// Completes the options of Helix modelines, which can be set with `:set-local`.
+    pub fn local_option(_editor: &Editor, input: &str) -> Vec<Completion> {
+        fuzzy_match(input, helix_core::modeline::OPTIONS, false)
+            .into_iter()
+            .map(|(name, _)| ((0..), Span::raw(*name)))
+            .collect()
+    }
+
+    pub fn local_option_value(editor: &Editor, key: &str, input: &str) -> Vec<Completion> {
+        let values: &[&str] = match key {
+            "language" => return language(editor, input),
+            "indent" => &["tabs", "2", "4", "8"],
+            "line-ending" => &["lf", "crlf"],
+            "soft-wrap" => &["true", "false"],
+            _ => &[],
+        };
+        fuzzy_match(input, values, false)
+            .into_iter()
+            .map(|(value, _)| ((0..), Span::raw(*value)))
+            .collect()
+    }
+
     pub fn filename(editor: &Editor, input: &str) -> Vec<Completion> {
         filename_with_git_ignore(editor, input, true)
     }
@@ -724,6 +833,83 @@ pub fn register(editor: &Editor, input: &str) -> Vec<Completion> {
             .collect()
     }
 
+    pub fn make_task(editor: &Editor, input: &str) -> Vec<Completion> {
+        let config = editor.config();
+        let tasks = config.make.tasks.keys().map(String::as_str);
+
+        fuzzy_match(input, tasks, false)
+            .into_iter()
+            .map(|(name, _)| ((0..), name.to_owned().into()))
+            .collect()
+    }
+
+    pub fn output(editor: &Editor, input: &str) -> Vec<Completion> {
+        let names = editor.output_buffers.keys().map(String::as_str);
+
+        fuzzy_match(input, names, false)
+            .into_iter()
+            .map(|(name, _)| ((0..), name.to_owned().into()))
+            .collect()
+    }
+
+    pub fn tool(editor: &Editor, input: &str) -> Vec<Completion> {
+        let config = editor.config();
+        let tools = config.tools.keys().map(String::as_str);
+
+        fuzzy_match(input, tools, false)
+            .into_iter()
+            .map(|(name, _)| ((0..), name.to_owned().into()))
+            .collect()
+    }
+
+    pub fn macro_name(_editor: &Editor, input: &str) -> Vec<Completion> {
+        let macros: Vec<_> = std::fs::read_dir(crate::commands::macro_dir())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "txt" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_str()?.to_string())
+            })
+            .collect();
+
+        fuzzy_match(input, macros, false)
+            .into_iter()
+            .map(|(name, _)| ((0..), name.into()))
+            .collect()
+    }
+
+    pub fn layout(_editor: &Editor, input: &str) -> Vec<Completion> {
+        let layouts: Vec<_> = std::fs::read_dir(crate::commands::layout_dir())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_str()?.to_string())
+            })
+            .collect();
+
+        fuzzy_match(input, layouts, false)
+            .into_iter()
+            .map(|(name, _)| ((0..), name.into()))
+            .collect()
+    }
+
+    pub fn shell_profile(editor: &Editor, input: &str) -> Vec<Completion> {
+        let config = editor.config();
+        let profiles = config.interactive.profiles.keys().map(String::as_str);
+
+        fuzzy_match(input, profiles, false)
+            .into_iter()
+            .map(|(name, _)| ((0..), name.to_owned().into()))
+            .collect()
+    }
+
     pub fn program(_editor: &Editor, input: &str) -> Vec<Completion> {
         static PROGRAMS_IN_PATH: Lazy<BTreeSet<String>> = Lazy::new(|| {
             // Go through the entire PATH and read all files into a set.
@@ -807,4 +993,58 @@ fn test_get_child_if_single_dir() {
 
         assert_eq!(get_child_if_single_dir(root.path()), None);
     }
+
+    /// Collects the keys of the string options in `config`.
+    fn string_settings(config: &serde_json::Value, key: &str, keys: &mut Vec<String>) {
+        match config {
+            serde_json::Value::String(_) => keys.push(key.to_string()),
+            serde_json::Value::Object(options) => {
+                for (name, option) in options {
+                    let key = if key.is_empty() {
+                        name.clone()
+                    } else {
+                        format!("{key}.{name}")
+                    };
+                    string_settings(option, &key, keys);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    #[test]
+    fn setting_variants() {
+        use helix_view::editor::Config;
+
+        let config = serde_json::json!(Config::default());
+        let with_value = |key: &str, value: &str| {
+            let mut config = config.clone();
+            *config
+                .pointer_mut(&format!("/{}", key.replace('.', "/")))
+                .unwrap() = value.into();
+            serde_json::from_value::<Config>(config)
+        };
+
+        for (key, variants) in completers::SETTING_VARIANTS {
+            for variant in *variants {
+                assert!(with_value(key, variant).is_ok(), "{key} = {variant}");
+            }
+        }
+
+        let mut keys = Vec::new();
+        string_settings(&config, "", &mut keys);
+        for key in keys {
+            let Err(err) = with_value(&key, "x-invalid") else {
+                continue;
+            };
+            if err.to_string().contains("unknown variant") {
+                assert!(
+                    completers::SETTING_VARIANTS
+                        .iter()
+                        .any(|(name, _)| *name == key),
+                    "the variants of {key} aren't listed"
+                );
+            }
+        }
+    }
 }
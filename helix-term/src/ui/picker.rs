@@ -42,7 +42,7 @@
 use crate::ui::{Prompt, PromptEvent};
 use helix_core::{
     char_idx_at_visual_offset, fuzzy::MATCHER, movement::Direction,
-    text_annotations::TextAnnotations, unicode::segmentation::UnicodeSegmentation, Position,
+    text_annotations::TextAnnotations, unicode::segmentation::UnicodeSegmentation, Position, Rope,
 };
 use helix_view::{
     editor::Action,
@@ -64,6 +64,8 @@
 pub enum PathOrId<'a> {
     Id(DocumentId),
     Path(&'a Path),
+    /// Text which isn't in a file, such as the contents of a register.
+    Text(&'a str),
 }
 
 impl<'a> From<&'a Path> for PathOrId<'a> {
@@ -259,10 +261,20 @@ pub struct Picker<T: 'static + Send + Sync, D: 'static> {
 
     callback_fn: PickerCallback<T>,
     default_action: Action,
+    /// Called when another item is selected, for example to preview it in the editor.
+    change_fn: Option<ChangeCallback<T>>,
+    /// The index in the matcher of the item `change_fn` was last called with.
+    changed_item: Option<u32>,
+    /// Called when the picker is closed without picking an item.
+    abort_fn: Option<AbortCallback>,
+    /// Called with the selected item on `Ctrl-o`, for example to show its children.
+    expand_fn: Option<ExpandCallback<T>>,
 
     pub truncate_start: bool,
     /// Caches paths to documents
     preview_cache: HashMap<Arc<Path>, CachedPreview>,
+    /// The last previewed text and its document.
+    text_preview: Option<(String, CachedPreview)>,
     read_buffer: Vec<u8>,
     /// Given an item in the picker, return the file path and line number to display.
     file_fn: Option<FileCallback<T>>,
@@ -387,9 +399,14 @@ fn with(
             show_preview: true,
             callback_fn: Box::new(callback_fn),
             default_action: Action::Replace,
+            change_fn: None,
+            changed_item: None,
+            abort_fn: None,
+            expand_fn: None,
             completion_height: 0,
             widths,
             preview_cache: HashMap::new(),
+            text_preview: None,
             read_buffer: Vec::with_capacity(1024),
             file_fn: None,
             preview_highlight_handler: PreviewHighlightHandler::<T, D>::default().spawn(),
@@ -455,6 +472,49 @@ pub fn with_default_action(mut self, action: Action) -> Self {
         self
     }
 
+    /// Calls `change_fn` with the selected item whenever another one is selected.
+    pub fn with_on_change(mut self, change_fn: impl Fn(&mut Context, &T) + 'static) -> Self {
+        self.change_fn = Some(Box::new(change_fn));
+        self
+    }
+
+    /// Calls `abort_fn` when the picker is closed without picking an item.
+    pub fn with_on_abort(mut self, abort_fn: impl Fn(&mut Context) + 'static) -> Self {
+        self.abort_fn = Some(Box::new(abort_fn));
+        self
+    }
+
+    /// Calls `expand_fn` with the selected item on `Ctrl-o`, for example to show its children.
+    pub fn with_expand(mut self, expand_fn: impl Fn(&mut Context, &T) + 'static) -> Self {
+        self.expand_fn = Some(Box::new(expand_fn));
+        self
+    }
+
+    /// Calls `change_fn` if another item is selected than when it was last called. Items are told
+    /// apart by their index in the matcher, which doesn't change as items are matched or added.
+    fn notify_change(&mut self, cx: &mut Context) {
+        let Some(change_fn) = &self.change_fn else {
+            return;
+        };
+        // The items of a changed query are only matched once the picker is rendered otherwise.
+        self.matcher.tick(10);
+        let snapshot = self.matcher.snapshot();
+        let Some(item) = snapshot.get_matched_item(self.cursor).map(|item| item.data) else {
+            return;
+        };
+        let is_item = |index: &u32| {
+            snapshot
+                .get_item(*index)
+                .is_some_and(|other| std::ptr::eq(other.data, item))
+        };
+        if self.changed_item.as_ref().is_some_and(is_item) {
+            return;
+        }
+        // The matcher only finds items by their index, not the index of an item.
+        self.changed_item = (0..snapshot.item_count()).find(is_item);
+        change_fn(cx, item);
+    }
+
     /// Move the cursor by a number of lines, either down (`Forward`) or up (`Backward`)
     pub fn move_by(&mut self, amount: u32, direction: Direction) {
         let len = self.matcher.snapshot().matched_item_count();
@@ -678,6 +738,24 @@ fn get_preview<'picker, 'editor>(
                 let doc = editor.documents.get(&id).unwrap();
                 Some((Preview::EditorDocument(doc), range))
             }
+            PathOrId::Text(text) => {
+                if self
+                    .text_preview
+                    .as_ref()
+                    .is_none_or(|(last, _)| last != text)
+                {
+                    let doc = Document::from(
+                        Rope::from(text),
+                        None,
+                        editor.config.clone(),
+                        editor.syn_loader.clone(),
+                    );
+                    let preview = CachedPreview::Document(Box::new(doc));
+                    self.text_preview = Some((text.to_owned(), preview));
+                }
+                let (_, preview) = self.text_preview.as_ref()?;
+                Some((Preview::Cached(preview), range))
+            }
         }
     }
 
@@ -969,6 +1047,15 @@ fn render_preview(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context
                 &cx.editor.theme,
                 &mut overlay_highlights,
             );
+            if let Some(overlay) = EditorView::theme_scope_highlights(
+                cx.editor,
+                doc,
+                offset.anchor,
+                area.height,
+                &cx.editor.theme,
+            ) {
+                overlay_highlights.push(overlay);
+            }
 
             let mut decorations = DecorationManager::default();
 
@@ -1000,9 +1087,11 @@ fn render_preview(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context
                 // TODO: compute text annotations asynchronously here (like inlay hints)
                 &TextAnnotations::default(),
                 syntax_highlighter,
+                None,
                 overlay_highlights,
                 &cx.editor.theme,
                 decorations,
+                None,
             );
         }
     }
@@ -1040,7 +1129,11 @@ fn handle_event(&mut self, event: &Event, ctx: &mut Context) -> EventResult {
 
         let key_event = match event {
             Event::Key(event) => *event,
-            Event::Paste(..) => return self.prompt_handle_event(event, ctx),
+            Event::Paste(..) => {
+                self.prompt_handle_event(event, ctx);
+                self.notify_change(ctx);
+                return EventResult::Consumed(None);
+            }
             Event::Resize(..) => return EventResult::Consumed(None),
             _ => return EventResult::Ignored(None),
         };
@@ -1087,7 +1180,12 @@ fn handle_event(&mut self, event: &Event, ctx: &mut Context) -> EventResult {
             key!(End) => {
                 self.to_end();
             }
-            key!(Esc) | ctrl!('c') => return close_fn(self),
+            key!(Esc) | ctrl!('c') => {
+                if let Some(abort_fn) = &self.abort_fn {
+                    abort_fn(ctx);
+                }
+                return close_fn(self);
+            }
             alt!(Enter) => {
                 if let Some(option) = self.selection() {
                     (self.callback_fn)(ctx, option, self.default_action);
@@ -1143,11 +1241,17 @@ fn handle_event(&mut self, event: &Event, ctx: &mut Context) -> EventResult {
             ctrl!('t') => {
                 self.toggle_preview();
             }
+            ctrl!('o') if self.expand_fn.is_some() => {
+                if let (Some(expand_fn), Some(option)) = (&self.expand_fn, self.selection()) {
+                    expand_fn(ctx, option);
+                }
+            }
             _ => {
                 self.prompt_handle_event(event, ctx);
             }
         }
 
+        self.notify_change(ctx);
         EventResult::Consumed(None)
     }
 
@@ -1187,3 +1291,6 @@ fn drop(&mut self) {
 }
 
 type PickerCallback<T> = Box<dyn Fn(&mut Context, &T, Action)>;
+type ChangeCallback<T> = Box<dyn Fn(&mut Context, &T)>;
+type AbortCallback = Box<dyn Fn(&mut Context)>;
+type ExpandCallback<T> = Box<dyn Fn(&mut Context, &T)>;
@@ -32,6 +32,8 @@ struct RenderInfo {
 pub struct Popup<T: Component> {
     contents: T,
     position: Option<Position>,
+    /// Whether the popup stays at its position instead of following the cursor.
+    fixed_position: bool,
     area: Rect,
     position_bias: Open,
     scroll_half_pages: usize,
@@ -46,6 +48,7 @@ pub fn new(id: &'static str, contents: T) -> Self {
         Self {
             contents,
             position: None,
+            fixed_position: false,
             position_bias: Open::Below,
             area: Rect::new(0, 0, 0, 0),
             scroll_half_pages: 0,
@@ -65,6 +68,13 @@ pub fn position(mut self, pos: Option<Position>) -> Self {
         self
     }
 
+    /// Draw the popup next to the screen-space `pos` even when the cursor is on another row.
+    pub fn fixed_position(mut self, pos: Position) -> Self {
+        self.position = Some(pos);
+        self.fixed_position = true;
+        self
+    }
+
     pub fn get_position(&self) -> Option<Position> {
         self.position
     }
@@ -127,7 +137,7 @@ fn render_info(&mut self, viewport: Rect, editor: &Editor) -> RenderInfo {
         let mut position = editor.cursor().0.unwrap_or_default();
         if let Some(old_position) = self
             .position
-            .filter(|old_position| old_position.row == position.row)
+            .filter(|old_position| self.fixed_position || old_position.row == position.row)
         {
             position = old_position;
         } else {
@@ -236,6 +246,8 @@ fn handle_mouse_event(
         }
 
         match kind {
+            // Don't let the views under the popup react to the mouse hovering over it.
+            MouseEventKind::Moved => EventResult::Consumed(None),
             MouseEventKind::ScrollDown if self.has_scrollbar => {
                 self.scroll_half_page_down();
                 EventResult::Consumed(None)
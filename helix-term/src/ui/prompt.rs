@@ -47,6 +47,8 @@ pub struct Prompt {
     pub doc_fn: DocFn,
     next_char_handler: Option<PromptCharHandler>,
     language: Option<(&'static str, Arc<ArcSwap<syntax::Loader>>)>,
+    /// Whether the input is hidden, e.g. for passwords.
+    hidden: bool,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -103,6 +105,7 @@ pub fn new(
             doc_fn: Box::new(|_| None),
             next_char_handler: None,
             language: None,
+            hidden: false,
         }
     }
 
@@ -133,6 +136,12 @@ pub fn with_language(
         self
     }
 
+    /// Hides the input, e.g. for passwords.
+    pub fn with_hidden_input(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
     pub fn line(&self) -> &String {
         &self.line
     }
@@ -516,7 +525,9 @@ pub fn render_prompt(&mut self, area: Rect, surface: &mut Surface, cx: &mut Cont
             .clip_top(line)
             .clip_right(2);
 
-        if self.line.is_empty() {
+        if self.hidden {
+            // Render nothing of the input, not even its length.
+        } else if self.line.is_empty() {
             // Show the most recently entered value as a suggestion.
             if let Some(suggestion) = self.first_history_completion(cx.editor) {
                 surface.set_string(
@@ -766,6 +777,14 @@ fn cursor(&self, area: Rect, editor: &Editor) -> (Option<Position>, CursorKind)
             .clip_left(self.prompt.len() as u16)
             .clip_right(if self.prompt.is_empty() { 2 } else { 0 });
 
+        if self.hidden {
+            let line = area.height as usize - 1;
+            return (
+                Some(Position::new(area.y as usize + line, area.left() as usize)),
+                editor.config().cursor_shape.from_mode(Mode::Insert),
+            );
+        }
+
         let mut col = area.left() as usize + self.line[self.anchor..self.cursor].width();
 
         // ensure the cursor does not go beyond elipses
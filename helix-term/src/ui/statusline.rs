@@ -2,16 +2,12 @@
 use helix_core::{coords_at_pos, encoding, unicode::width::UnicodeWidthStr, Position};
 use helix_lsp::lsp::DiagnosticSeverity;
 use helix_view::document::DEFAULT_LANGUAGE_NAME;
-use helix_view::{
-    document::{Mode, SCRATCH_BUFFER_NAME},
-    graphics::Rect,
-    theme::Style,
-    Document, Editor, View,
-};
+use helix_view::{document::Mode, graphics::Rect, theme::Style, Document, Editor, View};
 
 use crate::ui::ProgressSpinners;
 
-use helix_view::editor::StatusLineElement as StatusLineElementID;
+use helix_view::editor::{StatusLineElement as StatusLineElementID, StatusLineSection};
+use helix_view::statusline::{Alignment, FormatItem};
 use tui::buffer::Buffer as Surface;
 use tui::text::{Span, Spans};
 
@@ -48,6 +44,8 @@ pub struct RenderBuffer<'a> {
     pub left: Spans<'a>,
     pub center: Spans<'a>,
     pub right: Spans<'a>,
+    /// The output of a single element of a format string.
+    element: Spans<'a>,
 }
 
 pub fn render(context: &mut RenderContext, viewport: Rect, surface: &mut Surface) {
@@ -63,12 +61,9 @@ pub fn render(context: &mut RenderContext, viewport: Rect, surface: &mut Surface
 
     let config = context.editor.config();
 
-    for element_id in &config.statusline.left {
-        let render = get_render_function(*element_id);
-        (render)(context, |context, span| {
-            append(&mut context.parts.left, span, base_style)
-        });
-    }
+    render_section(context, &config.statusline.left, base_style, |parts| {
+        &mut parts.left
+    });
 
     surface.set_spans(
         viewport.x,
@@ -79,12 +74,9 @@ pub fn render(context: &mut RenderContext, viewport: Rect, surface: &mut Surface
 
     // Right side of the status line.
 
-    for element_id in &config.statusline.right {
-        let render = get_render_function(*element_id);
-        (render)(context, |context, span| {
-            append(&mut context.parts.right, span, base_style)
-        })
-    }
+    render_section(context, &config.statusline.right, base_style, |parts| {
+        &mut parts.right
+    });
 
     surface.set_spans(
         viewport.x
@@ -98,12 +90,9 @@ pub fn render(context: &mut RenderContext, viewport: Rect, surface: &mut Surface
 
     // Center of the status line.
 
-    for element_id in &config.statusline.center {
-        let render = get_render_function(*element_id);
-        (render)(context, |context, span| {
-            append(&mut context.parts.center, span, base_style)
-        })
-    }
+    render_section(context, &config.statusline.center, base_style, |parts| {
+        &mut parts.center
+    });
 
     // Width of the empty space between the left and center area and between the center and right area.
     let spacing = 1u16;
@@ -125,6 +114,92 @@ fn append<'a>(buffer: &mut Spans<'a>, mut span: Span<'a>, base_style: Style) {
     buffer.0.push(span);
 }
 
+type SectionBuffer<'a> = for<'b> fn(&'b mut RenderBuffer<'a>) -> &'b mut Spans<'a>;
+
+fn render_section<'a>(
+    context: &mut RenderContext<'a>,
+    section: &StatusLineSection,
+    base_style: Style,
+    buffer: SectionBuffer<'a>,
+) {
+    match section {
+        StatusLineSection::Elements(elements) => {
+            for element_id in elements {
+                let render = get_render_function(*element_id);
+                (render)(context, |context, span| {
+                    append(buffer(&mut context.parts), span, base_style)
+                });
+            }
+        }
+        StatusLineSection::Format(format) => {
+            let mut style = base_style;
+            render_format(context, format.items(), base_style, &mut style, buffer);
+        }
+    }
+}
+
+fn render_format<'a>(
+    context: &mut RenderContext<'a>,
+    items: &[FormatItem],
+    base_style: Style,
+    style: &mut Style,
+    buffer: SectionBuffer<'a>,
+) {
+    for item in items {
+        match item {
+            FormatItem::Text(text) => {
+                append(buffer(&mut context.parts), text.clone().into(), *style);
+            }
+            FormatItem::Style(scope) => {
+                *style = match scope {
+                    Some(scope) => base_style.patch(context.editor.theme.get(scope)),
+                    None => base_style,
+                };
+            }
+            FormatItem::Element { element, padding } => {
+                let spans = render_element(context, *element);
+                let (before, after) = match *padding {
+                    Some((alignment, width)) => {
+                        let padding = width.saturating_sub(spans.width());
+                        match alignment {
+                            Alignment::Left => (0, padding),
+                            Alignment::Right => (padding, 0),
+                            Alignment::Center => (padding / 2, padding - padding / 2),
+                        }
+                    }
+                    None => (0, 0),
+                };
+                let buffer = buffer(&mut context.parts);
+                if before > 0 {
+                    append(buffer, " ".repeat(before).into(), *style);
+                }
+                for span in spans.0 {
+                    append(buffer, span, *style);
+                }
+                if after > 0 {
+                    append(buffer, " ".repeat(after).into(), *style);
+                }
+            }
+            FormatItem::Conditional { element, items } => {
+                let spans = render_element(context, *element);
+                if spans.0.iter().any(|span| !span.content.trim().is_empty()) {
+                    render_format(context, items, base_style, style, buffer);
+                }
+            }
+        }
+    }
+}
+
+/// Renders a single element of a format string.
+fn render_element<'a>(
+    context: &mut RenderContext<'a>,
+    element_id: StatusLineElementID,
+) -> Spans<'a> {
+    let render = get_render_function(element_id);
+    (render)(context, |context, span| context.parts.element.0.push(span));
+    std::mem::take(&mut context.parts.element)
+}
+
 fn get_render_function<'a, F>(element_id: StatusLineElementID) -> impl Fn(&mut RenderContext<'a>, F)
 where
     F: Fn(&mut RenderContext<'a>, Span<'a>) + Copy,
@@ -132,6 +207,7 @@ fn get_render_function<'a, F>(element_id: StatusLineElementID) -> impl Fn(&mut R
     match element_id {
         helix_view::editor::StatusLineElement::Mode => render_mode,
         helix_view::editor::StatusLineElement::Spinner => render_lsp_spinner,
+        helix_view::editor::StatusLineElement::LspProgress => render_lsp_progress,
         helix_view::editor::StatusLineElement::FileBaseName => render_file_base_name,
         helix_view::editor::StatusLineElement::FileName => render_file_name,
         helix_view::editor::StatusLineElement::FileAbsolutePath => render_file_absolute_path,
@@ -157,6 +233,8 @@ fn get_render_function<'a, F>(element_id: StatusLineElementID) -> impl Fn(&mut R
         helix_view::editor::StatusLineElement::VersionControl => render_version_control,
         helix_view::editor::StatusLineElement::Register => render_register,
         helix_view::editor::StatusLineElement::CurrentWorkingDirectory => render_cwd,
+        helix_view::editor::StatusLineElement::MacroRecording => render_macro_recording,
+        helix_view::editor::StatusLineElement::LanguageServers => render_language_servers,
     }
 }
 
@@ -211,6 +289,24 @@ fn render_lsp_spinner<'a, F>(context: &mut RenderContext<'a>, write: F)
     );
 }
 
+fn render_lsp_progress<'a, F>(context: &mut RenderContext<'a>, write: F)
+where
+    F: Fn(&mut RenderContext<'a>, Span<'a>) + Copy,
+{
+    let progress = context.doc.language_servers().find_map(|server| {
+        let description = context
+            .editor
+            .lsp_progress
+            .progress_map(server.id())?
+            .values()
+            .find_map(|status| status.describe())?;
+        Some(format!(" {}: {description} ", server.name()))
+    });
+    if let Some(progress) = progress {
+        write(context, progress.into());
+    }
+}
+
 fn render_diagnostics<'a, F>(context: &mut RenderContext<'a>, write: F)
 where
     F: Fn(&mut RenderContext<'a>, Span<'a>) + Copy,
@@ -265,9 +361,14 @@ fn render_workspace_diagnostics<'a, F>(context: &mut RenderContext<'a>, write: F
     F: Fn(&mut RenderContext<'a>, Span<'a>) + Copy,
 {
     use helix_core::diagnostic::Severity;
-    let (hints, info, warnings, errors) = context.editor.diagnostics.values().flatten().fold(
-        (0u32, 0u32, 0u32, 0u32),
-        |mut counts, (diag, _)| {
+    let config = context.editor.config();
+    let (hints, info, warnings, errors) = context
+        .editor
+        .diagnostics
+        .values()
+        .flatten()
+        .filter(|(diag, _)| !config.hides_diagnostic(diag))
+        .fold((0u32, 0u32, 0u32, 0u32), |mut counts, (diag, _)| {
             match diag.severity {
                 // PERF: For large workspace diagnostics, this loop can be very tight.
                 //
@@ -281,8 +382,7 @@ fn render_workspace_diagnostics<'a, F>(context: &mut RenderContext<'a>, write: F
                 _ => counts.0 += 1,
             }
             counts
-        },
-    );
+        });
 
     let sevs_to_show = &context.editor.config().statusline.workspace_diagnostics;
 
@@ -403,7 +503,10 @@ fn render_file_encoding<'a, F>(context: &mut RenderContext<'a>, write: F)
 {
     let enc = context.doc.encoding();
 
-    if enc != encoding::UTF_8 {
+    if context.doc.is_encoding_lossy() {
+        let style = context.editor.theme.get("warning");
+        write(context, Span::styled(format!(" {}! ", enc.name()), style));
+    } else if enc != encoding::UTF_8 {
         write(context, format!(" {} ", enc.name()).into());
     }
 }
@@ -451,7 +554,7 @@ fn render_file_name<'a, F>(context: &mut RenderContext<'a>, write: F)
         let path = rel_path
             .as_ref()
             .map(|p| p.to_string_lossy())
-            .unwrap_or_else(|| SCRATCH_BUFFER_NAME.into());
+            .unwrap_or_else(|| context.doc.scratch_name().into());
         format!(" {} ", path)
     };
 
@@ -467,7 +570,7 @@ fn render_file_absolute_path<'a, F>(context: &mut RenderContext<'a>, write: F)
         let path = path
             .as_ref()
             .map(|p| p.to_string_lossy())
-            .unwrap_or_else(|| SCRATCH_BUFFER_NAME.into());
+            .unwrap_or_else(|| context.doc.scratch_name().into());
         format!(" {} ", path)
     };
 
@@ -508,7 +611,7 @@ fn render_file_base_name<'a, F>(context: &mut RenderContext<'a>, write: F)
         let path = rel_path
             .as_ref()
             .and_then(|p| p.file_name().map(|s| s.to_string_lossy()))
-            .unwrap_or_else(|| SCRATCH_BUFFER_NAME.into());
+            .unwrap_or_else(|| context.doc.scratch_name().into());
         format!(" {} ", path)
     };
 
@@ -583,3 +686,26 @@ fn render_cwd<'a, F>(context: &mut RenderContext<'a>, write: F)
         .to_string();
     write(context, cwd.into())
 }
+
+fn render_macro_recording<'a, F>(context: &mut RenderContext<'a>, write: F)
+where
+    F: Fn(&mut RenderContext<'a>, Span<'a>) + Copy,
+{
+    if let Some((reg, _)) = context.editor.macro_recording {
+        write(context, format!(" rec @{} ", reg).into())
+    }
+}
+
+fn render_language_servers<'a, F>(context: &mut RenderContext<'a>, write: F)
+where
+    F: Fn(&mut RenderContext<'a>, Span<'a>) + Copy,
+{
+    let names: Vec<_> = context
+        .doc
+        .language_servers()
+        .map(|server| server.name())
+        .collect();
+    if !names.is_empty() {
+        write(context, format!(" {} ", names.join(", ")).into())
+    }
+}
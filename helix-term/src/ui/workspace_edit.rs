@@ -0,0 +1,558 @@
+use std::path::{Path, PathBuf};
+
+use helix_core::{Rope, Uri};
+use helix_lsp::{lsp, util::lsp_pos_to_pos, OffsetEncoding};
+use helix_view::{
+    document::from_reader,
+    graphics::{Modifier, Rect},
+    handlers::lsp::{
+        ApplyEditError, ApplyEditErrorKind, PendingWorkspaceEdit, WorkspaceEditCallback,
+    },
+    input::{Event, KeyEvent},
+    Editor,
+};
+use tui::buffer::Buffer as Surface;
+
+use crate::{
+    compositor::{Component, Compositor, Context, EventResult},
+    ctrl, key, shift,
+};
+
+/// An edit of a file along with the lines it changes.
+struct Hunk {
+    edit: lsp::TextEdit,
+    /// The 0-based line the edit starts on.
+    line: usize,
+    removed: Vec<String>,
+    added: Vec<String>,
+    included: bool,
+}
+
+enum Change {
+    Edit {
+        uri: lsp::Url,
+        path: PathBuf,
+        version: Option<i32>,
+        hunks: Vec<Hunk>,
+    },
+    Resource {
+        op: lsp::ResourceOp,
+        included: bool,
+    },
+}
+
+/// A row of the review, which is a file, one of its hunks or a resource operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    Change(usize),
+    Hunk(usize, usize),
+}
+
+/// Reviews a pending workspace edit, showing its changes grouped by file. Files and single
+/// hunks can be accepted or rejected, and the accepted ones are applied with one undo step per
+/// document.
+pub struct WorkspaceEditReview {
+    title: String,
+    changes: Vec<Change>,
+    offset_encoding: OffsetEncoding,
+    on_done: Option<WorkspaceEditCallback>,
+    cursor: usize,
+}
+
+impl WorkspaceEditReview {
+    pub const ID: &'static str = "workspace-edit-review";
+
+    pub fn new(editor: &Editor, pending: PendingWorkspaceEdit) -> Self {
+        let PendingWorkspaceEdit {
+            title,
+            offset_encoding,
+            edit: workspace_edit,
+            on_done,
+            ..
+        } = pending;
+        let mut review = Self {
+            title,
+            changes: Vec::new(),
+            offset_encoding,
+            on_done,
+            cursor: 0,
+        };
+        match &workspace_edit.document_changes {
+            Some(lsp::DocumentChanges::Edits(edits)) => {
+                for edit in edits {
+                    review.push_document_edit(editor, edit);
+                }
+            }
+            Some(lsp::DocumentChanges::Operations(operations)) => {
+                for operation in operations {
+                    match operation {
+                        lsp::DocumentChangeOperation::Op(op) => {
+                            review.changes.push(Change::Resource {
+                                op: op.clone(),
+                                included: true,
+                            })
+                        }
+                        lsp::DocumentChangeOperation::Edit(edit) => {
+                            review.push_document_edit(editor, edit)
+                        }
+                    }
+                }
+            }
+            None => {
+                let mut changes: Vec<_> = workspace_edit.changes.iter().flatten().collect();
+                changes.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+                for (uri, edits) in changes {
+                    review.push_edits(editor, uri, None, edits.iter().cloned());
+                }
+            }
+        }
+        review
+    }
+
+    /// Whether the workspace edit changes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Moves the review out of the component, which is closed.
+    fn take(&mut self) -> Self {
+        Self {
+            title: std::mem::take(&mut self.title),
+            changes: std::mem::take(&mut self.changes),
+            on_done: self.on_done.take(),
+            ..*self
+        }
+    }
+
+    fn push_document_edit(&mut self, editor: &Editor, edit: &lsp::TextDocumentEdit) {
+        let edits = edit.edits.iter().map(|edit| match edit {
+            lsp::OneOf::Left(text_edit) => text_edit.clone(),
+            lsp::OneOf::Right(annotated) => annotated.text_edit.clone(),
+        });
+        self.push_edits(
+            editor,
+            &edit.text_document.uri,
+            edit.text_document.version,
+            edits,
+        );
+    }
+
+    fn push_edits(
+        &mut self,
+        editor: &Editor,
+        uri: &lsp::Url,
+        version: Option<i32>,
+        edits: impl Iterator<Item = lsp::TextEdit>,
+    ) {
+        let Some(path) = Uri::try_from(uri)
+            .ok()
+            .and_then(|uri| uri.as_path().map(Path::to_path_buf))
+        else {
+            log::error!("cannot review the edits of {uri}");
+            return;
+        };
+        let text = match editor.document_by_path(&path) {
+            Some(doc) => doc.text().clone(),
+            None => std::fs::File::open(&path)
+                .and_then(|mut file| from_reader(&mut file, None))
+                .map(|(text, ..)| text)
+                .unwrap_or_default(),
+        };
+        let mut hunks: Vec<_> = edits
+            .map(|edit| hunk(&text, edit, self.offset_encoding))
+            .collect();
+        hunks.sort_by_key(|hunk| hunk.edit.range.start);
+
+        // Multiple edits of one document are merged so that it gets a single undo step.
+        let previous = self.changes.iter_mut().find_map(|change| match change {
+            Change::Edit {
+                uri: previous,
+                hunks,
+                ..
+            } if previous == uri => Some(hunks),
+            _ => None,
+        });
+        match previous {
+            Some(previous) => {
+                previous.extend(hunks);
+                previous.sort_by_key(|hunk| hunk.edit.range.start);
+            }
+            None if !hunks.is_empty() => self.changes.push(Change::Edit {
+                uri: uri.clone(),
+                path,
+                version,
+                hunks,
+            }),
+            None => (),
+        }
+    }
+
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for (i, change) in self.changes.iter().enumerate() {
+            rows.push(Row::Change(i));
+            if let Change::Edit { hunks, .. } = change {
+                rows.extend((0..hunks.len()).map(|j| Row::Hunk(i, j)));
+            }
+        }
+        rows
+    }
+
+    fn move_cursor(&mut self, forward: bool) {
+        let len = self.rows().len();
+        if len == 0 {
+            return;
+        }
+        self.cursor = if forward {
+            (self.cursor + 1) % len
+        } else {
+            (self.cursor + len - 1) % len
+        };
+    }
+
+    /// Accepts or rejects the changes of the selected row, or toggles them if `accept` is `None`.
+    fn set_included(&mut self, accept: Option<bool>) {
+        let Some(row) = self.rows().get(self.cursor).copied() else {
+            return;
+        };
+        match row {
+            Row::Change(i) => match &mut self.changes[i] {
+                Change::Edit { hunks, .. } => {
+                    let included =
+                        accept.unwrap_or_else(|| !hunks.iter().all(|hunk| hunk.included));
+                    for hunk in hunks {
+                        hunk.included = included;
+                    }
+                }
+                Change::Resource { included, .. } => *included = accept.unwrap_or(!*included),
+            },
+            Row::Hunk(i, j) => {
+                if let Change::Edit { hunks, .. } = &mut self.changes[i] {
+                    hunks[j].included = accept.unwrap_or(!hunks[j].included);
+                }
+            }
+        }
+    }
+
+    /// The workspace edit of the included changes.
+    fn workspace_edit(&self) -> lsp::WorkspaceEdit {
+        let operations = self
+            .changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::Edit {
+                    uri,
+                    version,
+                    hunks,
+                    ..
+                } => {
+                    let edits: Vec<_> = hunks
+                        .iter()
+                        .filter(|hunk| hunk.included)
+                        .map(|hunk| lsp::OneOf::Left(hunk.edit.clone()))
+                        .collect();
+                    (!edits.is_empty()).then(|| {
+                        lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                            text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                                uri: uri.clone(),
+                                version: *version,
+                            },
+                            edits,
+                        })
+                    })
+                }
+                Change::Resource { op, included } => {
+                    included.then(|| lsp::DocumentChangeOperation::Op(op.clone()))
+                }
+            })
+            .collect();
+        lsp::WorkspaceEdit {
+            document_changes: Some(lsp::DocumentChanges::Operations(operations)),
+            ..Default::default()
+        }
+    }
+
+    /// Applies the included changes unless one of the documents changed since the review was
+    /// opened, in which case nothing is applied.
+    pub fn apply(self, editor: &mut Editor) {
+        let outdated = self
+            .changes
+            .iter()
+            .enumerate()
+            .find_map(|(i, change)| match change {
+                Change::Edit {
+                    path,
+                    version: Some(version),
+                    ..
+                } => editor
+                    .document_by_path(path)
+                    .filter(|doc| doc.version() != *version)
+                    .map(|_| (i, path)),
+                _ => None,
+            });
+        let result = match outdated {
+            Some((i, path)) => {
+                editor.set_error(format!(
+                    "Not applying the outdated workspace edit of {}",
+                    helix_stdx::path::get_relative_path(path).display()
+                ));
+                Err(ApplyEditError {
+                    kind: ApplyEditErrorKind::DocumentChanged,
+                    failed_change_idx: i,
+                })
+            }
+            None => editor
+                .apply_workspace_edit(self.offset_encoding, &self.workspace_edit())
+                .inspect_err(|err| {
+                    editor.set_error(format!("Failed to apply the workspace edit: {}", err.kind))
+                }),
+        };
+        if let Some(on_done) = self.on_done {
+            on_done(editor, result);
+        }
+    }
+
+    /// Rejects all changes.
+    fn reject(self, editor: &mut Editor) {
+        if let Some(on_done) = self.on_done {
+            let err = ApplyEditError {
+                kind: ApplyEditErrorKind::Rejected,
+                failed_change_idx: 0,
+            };
+            on_done(editor, Err(err));
+        }
+    }
+
+    /// The lines to render along with the row each of them belongs to.
+    fn lines(&self) -> Vec<(Row, Line)> {
+        let mut lines = Vec::new();
+        for (i, change) in self.changes.iter().enumerate() {
+            match change {
+                Change::Edit { path, hunks, .. } => {
+                    let included = hunks.iter().filter(|hunk| hunk.included).count();
+                    let text = format!(
+                        "{} {} ({included}/{} edits)",
+                        checkbox(included == hunks.len()),
+                        helix_stdx::path::get_relative_path(path).display(),
+                        hunks.len()
+                    );
+                    lines.push((Row::Change(i), Line::Title(text)));
+                    for (j, hunk) in hunks.iter().enumerate() {
+                        let text = format!("  {} line {}", checkbox(hunk.included), hunk.line + 1);
+                        lines.push((Row::Hunk(i, j), Line::Text(text)));
+                        lines.extend(
+                            hunk.removed
+                                .iter()
+                                .map(|line| (Row::Hunk(i, j), Line::Removed(line.clone()))),
+                        );
+                        lines.extend(
+                            hunk.added
+                                .iter()
+                                .map(|line| (Row::Hunk(i, j), Line::Added(line.clone()))),
+                        );
+                    }
+                }
+                Change::Resource { op, included } => {
+                    let text = format!("{} {}", checkbox(*included), describe_op(op));
+                    lines.push((Row::Change(i), Line::Title(text)));
+                }
+            }
+        }
+        lines
+    }
+
+    /// Closes the review, applying the accepted changes or rejecting all of them.
+    fn close(&mut self, apply: bool) -> EventResult {
+        let review = self.take();
+        EventResult::Consumed(Some(Box::new(
+            move |compositor: &mut Compositor, cx: &mut Context| {
+                compositor.remove(Self::ID);
+                if apply {
+                    review.apply(cx.editor);
+                } else {
+                    review.reject(cx.editor);
+                }
+            },
+        )))
+    }
+}
+
+enum Line {
+    Title(String),
+    Text(String),
+    Removed(String),
+    Added(String),
+}
+
+fn checkbox(checked: bool) -> &'static str {
+    if checked {
+        "[x]"
+    } else {
+        "[ ]"
+    }
+}
+
+fn describe_op(op: &lsp::ResourceOp) -> String {
+    let path = |uri: &lsp::Url| match Uri::try_from(uri).ok().and_then(|uri| {
+        uri.as_path().map(|path| {
+            helix_stdx::path::get_relative_path(path)
+                .display()
+                .to_string()
+        })
+    }) {
+        Some(path) => path,
+        None => uri.to_string(),
+    };
+    match op {
+        lsp::ResourceOp::Create(op) => format!("create {}", path(&op.uri)),
+        lsp::ResourceOp::Rename(op) => {
+            format!("rename {} to {}", path(&op.old_uri), path(&op.new_uri))
+        }
+        lsp::ResourceOp::Delete(op) => format!("delete {}", path(&op.uri)),
+    }
+}
+
+/// Computes the lines removed and added by `edit`.
+fn hunk(text: &Rope, edit: lsp::TextEdit, offset_encoding: OffsetEncoding) -> Hunk {
+    let len = text.len_chars();
+    let start = lsp_pos_to_pos(text, edit.range.start, offset_encoding).unwrap_or(len);
+    let end = lsp_pos_to_pos(text, edit.range.end, offset_encoding)
+        .unwrap_or(len)
+        .max(start);
+    let line = text.char_to_line(start);
+    let line_start = text.line_to_char(line);
+    let end_line = text.char_to_line(end);
+    let line_end = if end_line + 1 < text.len_lines() {
+        text.line_to_char(end_line + 1)
+    } else {
+        len
+    };
+
+    let old = text.slice(line_start..line_end).to_string();
+    let mut new = text.slice(line_start..start).to_string();
+    new.push_str(&edit.new_text);
+    new.push_str(&text.slice(end..line_end).to_string());
+    let lines = |text: &str| -> Vec<String> { text.lines().map(str::to_owned).collect() };
+    Hunk {
+        removed: lines(&old),
+        added: lines(&new),
+        edit,
+        line,
+        included: true,
+    }
+}
+
+impl Component for WorkspaceEditReview {
+    fn handle_event(&mut self, event: &Event, _cx: &mut Context) -> EventResult {
+        let key: KeyEvent = match event {
+            Event::Key(key) => *key,
+            Event::Mouse(_) => return EventResult::Consumed(None),
+            _ => return EventResult::Ignored(None),
+        };
+
+        match key {
+            key!(Down) | key!('j') | ctrl!('n') => self.move_cursor(true),
+            key!(Up) | key!('k') | ctrl!('p') => self.move_cursor(false),
+            key!(' ') | key!(Tab) | shift!(Tab) => self.set_included(None),
+            key!('y') => {
+                self.set_included(Some(true));
+                self.move_cursor(true);
+            }
+            key!('n') => {
+                self.set_included(Some(false));
+                self.move_cursor(true);
+            }
+            key!(Enter) => return self.close(true),
+            key!(Esc) | key!('q') | ctrl!('c') => return self.close(false),
+            _ => (),
+        }
+        EventResult::Consumed(None)
+    }
+
+    fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let theme = &cx.editor.theme;
+        let text_style = theme.get("ui.text");
+        let title_style = text_style.add_modifier(Modifier::BOLD);
+        let selected_style = theme.get("ui.menu.selected");
+        let removed_style = text_style.patch(theme.get("diff.minus"));
+        let added_style = text_style.patch(theme.get("diff.plus"));
+        surface.clear_with(area, theme.get("ui.background"));
+
+        let width = area.width as usize;
+        let help = "y: accept, n: reject, space: toggle, enter: apply accepted, esc: reject all";
+        surface.set_stringn(area.x, area.y, &self.title, width, title_style);
+        surface.set_stringn(
+            area.x,
+            area.y + 1,
+            help,
+            width,
+            theme.get("ui.text.inactive"),
+        );
+
+        let rows = self.rows();
+        let cursor = rows.get(self.cursor).copied();
+        let lines = self.lines();
+        let height = area.height.saturating_sub(3) as usize;
+        // Scroll the lines so the cursor stays visible.
+        let cursor_line = lines
+            .iter()
+            .position(|(row, _)| Some(*row) == cursor)
+            .unwrap_or(0);
+        let skip = (cursor_line + 1).saturating_sub(height);
+
+        let mut y = area.y + 3;
+        let mut previous = None;
+        for (row, line) in lines.into_iter().skip(skip) {
+            if y >= area.bottom() {
+                break;
+            }
+            let first = previous != Some(row);
+            previous = Some(row);
+            let (text, style) = match line {
+                Line::Title(text) => (text, title_style),
+                Line::Text(text) => (text, text_style),
+                Line::Removed(text) => (format!("      - {text}"), removed_style),
+                Line::Added(text) => (format!("      + {text}"), added_style),
+            };
+            let style = if first && Some(row) == cursor {
+                surface.set_style(Rect::new(area.x, y, area.width, 1), selected_style);
+                style.patch(selected_style)
+            } else {
+                style
+            };
+            surface.set_stringn(area.x, y, text, width, style);
+            y += 1;
+        }
+    }
+
+    fn id(&self) -> Option<&'static str> {
+        Some(Self::ID)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hunk_lines() {
+        let text = Rope::from("fn foo() {\n    foo();\n}\n");
+        let edit = |start: (u32, u32), end: (u32, u32), new_text: &str| lsp::TextEdit {
+            range: lsp::Range::new(
+                lsp::Position::new(start.0, start.1),
+                lsp::Position::new(end.0, end.1),
+            ),
+            new_text: new_text.to_owned(),
+        };
+
+        let rename = hunk(&text, edit((1, 4), (1, 7), "bar"), OffsetEncoding::Utf8);
+        assert_eq!(rename.line, 1);
+        assert_eq!(rename.removed, ["    foo();"]);
+        assert_eq!(rename.added, ["    bar();"]);
+
+        let join = hunk(&text, edit((0, 10), (2, 0), ""), OffsetEncoding::Utf8);
+        assert_eq!(join.line, 0);
+        assert_eq!(join.removed, ["fn foo() {", "    foo();", "}"]);
+        assert_eq!(join.added, ["fn foo() {}"]);
+    }
+}
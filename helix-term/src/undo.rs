@@ -0,0 +1,156 @@
+//! Saves the history of documents when they are written so their changes can be undone after
+//! reopening them in a later session.
+//!
+//! Each file gets its own JSON file in the `undo` directory of the state directory, named after
+//! a hash of the file's path. The history is only restored if the file still has the content it
+//! was written with.
+
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use helix_core::{
+    history::{History, PersistentHistory},
+    Rope,
+};
+use helix_view::{
+    document::DocumentSavedEvent, editor::PersistentUndoConfig, events::DocumentDidOpen, Document,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct UndoFile {
+    path: PathBuf,
+    /// The hash of the text of the file when it was written.
+    content_hash: u64,
+    history: PersistentHistory,
+}
+
+fn undo_dir() -> PathBuf {
+    helix_loader::state_dir().join("undo")
+}
+
+fn undo_file(path: &Path) -> PathBuf {
+    let hash = stable_hash([path.as_os_str().as_encoded_bytes()]);
+    undo_dir().join(format!("{hash:016x}.json"))
+}
+
+fn content_hash(text: &Rope) -> u64 {
+    stable_hash(text.chunks().map(str::as_bytes))
+}
+
+/// Hashes the bytes with 64 bit FNV-1a, which unlike the hashers of the standard library is the
+/// same in every release so that the saved histories are found again.
+fn stable_hash<'a>(bytes: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    bytes
+        .into_iter()
+        .flatten()
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+        })
+}
+
+fn is_expired(path: &Path, max_age: Duration) -> bool {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > max_age)
+}
+
+fn max_age(config: &PersistentUndoConfig) -> Duration {
+    Duration::from_secs(config.max_age.saturating_mul(24 * 60 * 60))
+}
+
+/// Saves the history of the document as it was written by `event`.
+pub fn save(doc: &Document, event: &DocumentSavedEvent, config: &PersistentUndoConfig) {
+    if !crate::state::is_enabled(config.enable) {
+        return;
+    }
+    let history = doc.history.take();
+    let persistent = history.to_persistent(event.revision, config.max_revisions);
+    doc.history.set(history);
+
+    let undo_file = UndoFile {
+        path: event.path.clone(),
+        content_hash: content_hash(&event.text),
+        history: persistent,
+    };
+    let max_age = max_age(config);
+    tokio::task::spawn_blocking(move || {
+        if let Err(err) = write(&undo_file, max_age) {
+            log::error!(
+                "failed to save the history of {}: {err}",
+                undo_file.path.display()
+            );
+        }
+    });
+}
+
+fn write(undo_file: &UndoFile, max_age: Duration) -> anyhow::Result<()> {
+    let dir = undo_dir();
+    std::fs::create_dir_all(&dir)?;
+    crate::state::write(
+        &self::undo_file(&undo_file.path),
+        serde_json::to_string(undo_file)?,
+    )?;
+
+    // Discard the histories of files which haven't been written in a while.
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if is_expired(&path, max_age) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    Ok(())
+}
+
+/// Reads the saved history of the file if it was written with `text`.
+fn load(path: &Path, text: &Rope, config: &PersistentUndoConfig) -> Option<History> {
+    let file = undo_file(path);
+    if is_expired(&file, max_age(config)) {
+        let _ = std::fs::remove_file(file);
+        return None;
+    }
+    let undo_file: UndoFile = serde_json::from_str(&std::fs::read_to_string(file).ok()?).ok()?;
+    if undo_file.path != path || undo_file.content_hash != content_hash(text) {
+        return None;
+    }
+    History::from_persistent(undo_file.history, text)
+}
+
+pub(crate) fn register_hooks() {
+    helix_event::register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        let config = event.editor.config().persistent_undo.clone();
+        if !crate::state::is_enabled(config.enable) {
+            return Ok(());
+        }
+        let doc = event.editor.documents.get_mut(&event.doc).unwrap();
+        let history = doc.path().and_then(|path| load(path, doc.text(), &config));
+        if let Some(history) = history {
+            doc.history.set(history);
+            doc.reset_modified();
+        }
+        Ok(())
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stable_hashes() {
+        // The hashes name the saved files, so they must never change.
+        assert_eq!(stable_hash([&b""[..]]), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash([&b"a"[..]]), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(
+            content_hash(&Rope::from("foobar")),
+            stable_hash([&b"foo"[..], &b"bar"[..]])
+        );
+    }
+}
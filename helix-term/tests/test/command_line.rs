@@ -1,6 +1,7 @@
 use super::*;
 
 use helix_core::diagnostic::Severity;
+use helix_view::doc;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn history_completion() -> anyhow::Result<()> {
@@ -17,6 +18,23 @@ async fn history_completion() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn shell_history_completion() -> anyhow::Result<()> {
+    test_key_sequence(
+        &mut AppBuilder::new().build()?,
+        Some(":insert-output echo -n hello<ret>:insert-output ech<tab><ret>"),
+        Some(&|app| {
+            assert!(!app.editor.is_err());
+            assert_eq!(doc!(app.editor).text(), "hellohello\n");
+        }),
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
 async fn test_statusline(
     line: &str,
     expected_status: &str,
@@ -101,3 +119,25 @@ async fn percent_escaping() -> anyhow::Result<()> {
     .await?;
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn custom_commands() -> anyhow::Result<()> {
+    let mut config = test_config();
+    config.editor.commands.insert(
+        "append-mark".to_string(),
+        vec![
+            "goto_file_end".to_string(),
+            "@o!<esc>".to_string(),
+            "goto_file_start".to_string(),
+        ],
+    );
+
+    // The command after the macro runs once the macro's keys have been handled.
+    test_with_config(
+        AppBuilder::new().with_config(config),
+        ("#[a|]#\nb", ":append-mark<ret>", "#[a|]#\nb\n!"),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -221,6 +221,164 @@ async fn test_multi_selection_paste() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_paste_previous_yank() -> anyhow::Result<()> {
+    // Replaces the pasted line with the previous yank and then cycles back to the newest one.
+    test((
+        indoc! {"\
+            #[|one]#
+            two
+            "},
+        "yjxyp<C-p><C-p>",
+        indoc! {"\
+            one
+            two
+            #[two
+            |]#"},
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_align() -> anyhow::Result<()> {
+    test((
+        indoc! {"\
+            #[a = 1
+            long = 2|]#
+            "},
+        ":align =<ret>",
+        indoc! {"\
+            #[a    = 1
+            long = 2|]#
+            "},
+    ))
+    .await?;
+
+    test((
+        indoc! {"\
+            #[100|]#
+            #(1|)#
+            #(10|)#
+            "},
+        ":align --right<ret>",
+        indoc! {"\
+            #[100|]#
+              #(1|)#
+             #(10|)#
+            "},
+    ))
+    .await?;
+
+    test((
+        indoc! {"\
+            #[abc|]#
+            #(a|)#
+            "},
+        ":align -c<ret>",
+        indoc! {"\
+            #[abc|]#
+             #(a|)#
+            "},
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sort_and_unique() -> anyhow::Result<()> {
+    // The lines of a single selection.
+    test((
+        indoc! {"\
+            #[item 10
+            item 2
+            item 10
+            item 1|]#
+            "},
+        ":sort --natural --unique<ret>",
+        indoc! {"\
+            #[item 1
+            item 2
+            item 10|]#
+            "},
+    ))
+    .await?;
+
+    test((
+        indoc! {"\
+            #[|b: 20]#
+            #(|a: 3)#
+            #(|c: -1)#
+            "},
+        ":sort -n<ret>",
+        indoc! {"\
+            #[|c: -1]#
+            #(|a: 3)#
+            #(|b: 20)#
+            "},
+    ))
+    .await?;
+
+    test((
+        "#[one|]# #(Two|)# #(two|)#",
+        ":unique -i<ret>",
+        "#[one|]# #(Two|)# #(\n|)#",
+    ))
+    .await?;
+
+    test((
+        indoc! {"\
+            #[one
+            two|]#
+            three
+            "},
+        ":reverse<ret>",
+        indoc! {"\
+            #[two
+            one|]#
+            three
+            "},
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_select_block() -> anyhow::Result<()> {
+    // The rectangle keeps its columns across the short line and is left as separate selections.
+    test((
+        indoc! {"\
+            ab#[c|]#d
+            a
+            abcd
+            "},
+        "vl<C-v>jj<C-v>iX<esc>",
+        indoc! {"\
+            abX#(|cd)#
+            a
+            abX#[|cd]#
+            "},
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_insert_digraph_and_unicode() -> anyhow::Result<()> {
+    test((
+        "#[|]#",
+        "i<C-k>a:<C-k>'e<C-v>u20ac<C-v>x4<ret><C-v><tab><esc>",
+        "äé€\u{4}\t#[|\n]#",
+    ))
+    .await?;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_multi_selection_shell_commands() -> anyhow::Result<()> {
     // pipe
@@ -274,6 +432,30 @@ async fn test_multi_selection_shell_commands() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_shell_commands_in_pty() -> anyhow::Result<()> {
+    // The command's stdout is a terminal and escape sequences are stripped by default.
+    test((
+        "#[|lorem]#",
+        r#":insert-output --pty test -t 1 && printf '\033[1mtty\033[0m'<ret>"#,
+        "#[|tty]#lorem",
+    ))
+    .await?;
+
+    test((
+        "#[|lorem]#",
+        r#":append-output -p --keep-ansi printf '\033[1mtty'<ret>"#,
+        "lorem#[|\x1b[1mtty]#",
+    ))
+    .await?;
+
+    // Selections are written to the command's stdin.
+    test(("#[|lorem]#", ":pipe --pty tr a-z A-Z<ret>", "#[|LOREM]#")).await?;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_undo_redo() -> anyhow::Result<()> {
     // A jumplist selection is created at a point which is undone.
@@ -417,6 +599,93 @@ async fn test_character_info() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_theme_preview() -> anyhow::Result<()> {
+    test_key_sequences(
+        &mut helpers::AppBuilder::new().build()?,
+        vec![
+            (Some(":theme-preview<ret>"), None),
+            (
+                Some("term16_dark"),
+                Some(&|app| {
+                    assert_eq!("term16_dark", app.editor.theme.name());
+                    let scopes = app.editor.output_buffers["theme-scopes"];
+                    let text = app.editor.document(scopes).unwrap().text().to_string();
+                    assert!(text.lines().any(|line| line == "ui.selection"));
+                }),
+            ),
+            (
+                Some("<esc>"),
+                Some(&|app| {
+                    // The default theme without true color support.
+                    assert_eq!("base16_default", app.editor.theme.name());
+                }),
+            ),
+            (Some(":theme-preview<ret>"), None),
+            (Some("term16_dark"), None),
+            (
+                Some("<ret>"),
+                Some(&|app| {
+                    assert_eq!("term16_dark", app.editor.theme.name());
+                    assert!(app.editor.last_theme.is_none());
+                }),
+            ),
+        ],
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_set_option() -> anyhow::Result<()> {
+    test_key_sequence(
+        &mut helpers::AppBuilder::new().build()?,
+        Some(":set scrolloff?<ret>"),
+        Some(&|app| {
+            assert_eq!("5", app.editor.get_status().unwrap().0);
+        }),
+        false,
+    )
+    .await?;
+
+    test_key_sequence(
+        &mut helpers::AppBuilder::new().build()?,
+        Some(":set scrolloff<ret>"),
+        Some(&|app| {
+            assert!(app.editor.is_err());
+        }),
+        false,
+    )
+    .await?;
+
+    test_key_sequence(
+        &mut helpers::AppBuilder::new().build()?,
+        Some(":set-local tab-width 2<ret>:set-local indent tabs<ret>:set-local tab-width?<ret>"),
+        Some(&|app| {
+            assert_eq!("2", app.editor.get_status().unwrap().0);
+            let doc = helix_view::doc!(app.editor);
+            assert_eq!(2, doc.tab_width());
+            assert_eq!(helix_core::indent::IndentStyle::Tabs, doc.indent_style);
+        }),
+        false,
+    )
+    .await?;
+
+    test_key_sequence(
+        &mut helpers::AppBuilder::new().build()?,
+        Some(":set-local tab-width 0<ret>"),
+        Some(&|app| {
+            assert!(app.editor.is_err());
+        }),
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_delete_char_backward() -> anyhow::Result<()> {
     // don't panic when deleting overlapping ranges
@@ -844,3 +1113,46 @@ async fn global_search_with_multibyte_chars() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_buffer_pin_and_move() -> anyhow::Result<()> {
+    let file1 = tempfile::NamedTempFile::new()?;
+    let file2 = tempfile::NamedTempFile::new()?;
+    let file3 = tempfile::NamedTempFile::new()?;
+
+    let mut app = helpers::AppBuilder::new()
+        .with_file(file1.path(), None)
+        .build()?;
+
+    let keys = format!(
+        ":o {}<ret>:o {}<ret>:buffer-pin<ret>:buffer-next<ret>:buffer-next<ret>:buffer-move-left<ret>",
+        file2.path().to_string_lossy(),
+        file3.path().to_string_lossy()
+    );
+    test_key_sequence(
+        &mut app,
+        Some(&keys),
+        Some(&|app| {
+            helpers::assert_status_not_error(&app.editor);
+            let order: Vec<_> = app
+                .editor
+                .buffer_order()
+                .into_iter()
+                .map(|id| app.editor.documents[&id].path().cloned())
+                .collect();
+            let expected: Vec<_> = [&file3, &file2, &file1]
+                .into_iter()
+                .map(|file| Some(helix_stdx::path::normalize(file.path())))
+                .collect();
+            assert_eq!(order, expected);
+            assert_eq!(
+                helix_view::doc!(app.editor).path(),
+                Some(&helix_stdx::path::normalize(file2.path()))
+            );
+        }),
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
@@ -933,3 +933,77 @@ async fn test_move_file_when_given_dir_only() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_write_hooks() -> anyhow::Result<()> {
+    use helix_view::editor::{HookConfig, HookEvent};
+
+    let file = tempfile::NamedTempFile::new()?;
+    let name = file.path().file_name().unwrap().to_string_lossy();
+    let mut config = helpers::test_config();
+    config.editor.hooks = vec![
+        HookConfig {
+            event: HookEvent::Write,
+            files: vec![name.into_owned()],
+            mode: None,
+            commands: vec!["goto_file_start".to_string(), "@iwritten <esc>".to_string()],
+        },
+        HookConfig {
+            event: HookEvent::Write,
+            files: vec!["*.rs".to_string()],
+            mode: None,
+            commands: vec!["@iunmatched <esc>".to_string()],
+        },
+    ];
+    let mut app = helpers::AppBuilder::new()
+        .with_config(config)
+        .with_file(file.path(), None)
+        .build()?;
+
+    test_key_sequences(
+        &mut app,
+        vec![(
+            Some("ihello<esc>:w<ret>"),
+            Some(&|app| {
+                let doc = doc!(app.editor);
+                assert_eq!(
+                    LineFeedHandling::Native.apply("written hello\n"),
+                    doc.text().to_string()
+                );
+            }),
+        )],
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_write_replaces_invalid_bytes() -> anyhow::Result<()> {
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.as_file_mut().write_all(b"a\xffb")?;
+    let mut app = helpers::AppBuilder::new()
+        .with_file(file.path(), None)
+        .build()?;
+
+    test_key_sequences(
+        &mut app,
+        vec![
+            (
+                Some(":set-encoding utf-8<ret>"),
+                Some(&|app| assert!(doc!(app.editor).is_encoding_lossy())),
+            ),
+            (
+                Some(":w<ret>"),
+                Some(&|app| assert!(!doc!(app.editor).is_encoding_lossy())),
+            ),
+        ],
+        false,
+    )
+    .await?;
+
+    helpers::assert_file_has_content(&mut file, &LineFeedHandling::Native.apply("a\u{FFFD}b"))?;
+
+    Ok(())
+}
@@ -2,6 +2,7 @@
     io::{Read, Write},
     mem::replace,
     path::PathBuf,
+    sync::OnceLock,
     time::Duration,
 };
 
@@ -384,6 +385,7 @@ pub fn build(self) -> anyhow::Result<Application> {
             bail!("Having the directory {path:?} in args.files[0] is not yet supported for integration tests");
         }
 
+        helix_loader::initialize_state_dir(test_state_dir());
         let mut app = Application::new(self.args, self.config, self.syn_loader)?;
 
         if let Some((text, selection)) = self.input {
@@ -402,6 +404,15 @@ pub fn build(self) -> anyhow::Result<Application> {
     }
 }
 
+/// The state directory shared by the editors of the tests, which keeps them from using the state
+/// of the user.
+pub fn test_state_dir() -> PathBuf {
+    static STATE_DIR: OnceLock<PathBuf> = OnceLock::new();
+    STATE_DIR
+        .get_or_init(|| tempfile::tempdir().unwrap().keep())
+        .clone()
+}
+
 pub async fn run_event_loop_until_idle(app: &mut Application) {
     let (_, rx) = tokio::sync::mpsc::unbounded_channel();
     let mut rx_stream = UnboundedReceiverStream::new(rx);
@@ -726,3 +726,86 @@ async fn tree_sitter_motions_work_across_injections() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vertical_motions_skip_folds() -> anyhow::Result<()> {
+    let text = indoc! {"\
+        #[f|]#n a() {
+            one();
+            two();
+        }
+        after
+    "};
+
+    test((
+        text,
+        "zfjgh",
+        indoc! {"\
+            fn a() {
+                one();
+                two();
+            #[}|]#
+            after
+        "},
+    ))
+    .await?;
+    test((text, "zfjkgh", text)).await?;
+    test((
+        text,
+        "zfzojgh",
+        indoc! {"\
+            fn a() {
+            #[ |]#   one();
+                two();
+            }
+            after
+        "},
+    ))
+    .await?;
+    test((
+        text,
+        "zazajgh",
+        indoc! {"\
+            fn a() {
+            #[ |]#   one();
+                two();
+            }
+            after
+        "},
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn moving_into_a_fold_opens_it() -> anyhow::Result<()> {
+    use helix_view::{doc, view};
+
+    test_key_sequences(
+        &mut AppBuilder::new()
+            .with_input_text("#[f|]#n a() {\n    one();\n}\nfn b() {\n    two();\n}\n")
+            .build()?,
+        vec![
+            (
+                Some("zM"),
+                Some(&|app| {
+                    let folds = doc!(app.editor).folds(view!(app.editor).id);
+                    assert_eq!(folds.len(), 2);
+                }),
+            ),
+            (
+                Some("/two<ret>"),
+                Some(&|app| {
+                    let folds = doc!(app.editor).folds(view!(app.editor).id);
+                    assert_eq!(folds.len(), 1);
+                    assert_eq!(folds[0].start, 0);
+                }),
+            ),
+        ],
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
@@ -195,3 +195,57 @@ async fn test_changes_in_splits_apply_to_all_views() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_layout_save_load() -> anyhow::Result<()> {
+    use helix_view::tree::{Layout, LayoutNode};
+
+    let file1 = tempfile::NamedTempFile::new()?;
+    let file2 = tempfile::NamedTempFile::new()?;
+    let file3 = tempfile::NamedTempFile::new()?;
+
+    let mut app = helpers::AppBuilder::new()
+        .with_file(file1.path(), None)
+        .build()?;
+
+    let keys = format!(
+        ":vsplit {}<ret>:hsplit {}<ret>:layout-save test<ret><C-w>o:layout-load test<ret>",
+        file2.path().to_string_lossy(),
+        file3.path().to_string_lossy()
+    );
+    let expected = |file: &tempfile::NamedTempFile, focused| LayoutNode::View {
+        path: Some(path::normalize(file.path())),
+        focused,
+    };
+    test_key_sequences(
+        &mut app,
+        vec![
+            (
+                Some(&keys),
+                Some(&|app| {
+                    helpers::assert_status_not_error(&app.editor);
+                    assert_eq!(
+                        app.editor.window_layout(),
+                        LayoutNode::Split {
+                            layout: Layout::Vertical,
+                            children: vec![
+                                expected(&file1, false),
+                                LayoutNode::Split {
+                                    layout: Layout::Horizontal,
+                                    children: vec![expected(&file2, false), expected(&file3, true)],
+                                    weights: vec![1.0, 1.0],
+                                },
+                            ],
+                            weights: vec![1.0, 1.0],
+                        }
+                    );
+                }),
+            ),
+            (Some(":qa!<ret>"), None),
+        ],
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
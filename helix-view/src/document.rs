@@ -9,6 +9,7 @@
 use helix_core::diagnostic::DiagnosticProvider;
 use helix_core::doc_formatter::TextFormat;
 use helix_core::encoding::Encoding;
+use helix_core::fold::Fold;
 use helix_core::snippets::{ActiveSnippet, SnippetRenderCtx};
 use helix_core::syntax::config::LanguageServerFeature;
 use helix_core::text_annotations::{InlineAnnotation, Overlay};
@@ -35,12 +36,14 @@
 
 use helix_core::{
     editor_config::EditorConfig,
-    encoding,
+    encoding, hex,
     history::{History, State, UndoKind},
     indent::{auto_detect_indent_style, IndentStyle},
     line_ending::auto_detect_line_ending,
+    modeline::Modeline,
     syntax::{self, config::LanguageConfiguration},
-    ChangeSet, Diagnostic, LineEnding, Range, Rope, RopeBuilder, Selection, Syntax, Transaction,
+    ChangeSet, Diagnostic, LineEnding, Range, Rope, RopeBuilder, RopeSlice, Selection, Syntax,
+    Transaction,
 };
 
 use crate::{
@@ -118,6 +121,63 @@ pub struct DocumentSavedEvent {
     pub doc_id: DocumentId,
     pub path: PathBuf,
     pub text: Rope,
+    /// Whether the encoding of the file can't represent some characters of the text.
+    pub encoding_lossy: bool,
+}
+
+/// Writes a file with elevated privileges through a command such as `sudo tee`, which gets the
+/// contents of the file on its standard input and the path as its last argument.
+#[derive(Clone)]
+pub struct PrivilegedWrite {
+    pub command: Vec<String>,
+    /// The password is written on the first line of the standard input of the command, before
+    /// the contents of the file, as read by `sudo -S`.
+    pub password: Option<String>,
+}
+
+impl fmt::Debug for PrivilegedWrite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrivilegedWrite")
+            .field("command", &self.command)
+            .field("password", &self.password.as_ref().map(|_| "..."))
+            .finish()
+    }
+}
+
+impl PrivilegedWrite {
+    async fn write(&self, path: &Path, contents: &[u8]) -> anyhow::Result<()> {
+        use tokio::io::AsyncWriteExt as _;
+
+        let (program, args) = self
+            .command
+            .split_first()
+            .ok_or_else(|| anyhow!("the command for privileged writes is empty"))?;
+        let mut command = tokio::process::Command::new(program);
+        command
+            .args(args)
+            .arg(path)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped());
+        let mut child = command.spawn()?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        // The command may exit before reading its input, e.g. for a wrong password, in which
+        // case its exit status tells what went wrong.
+        if let Some(password) = &self.password {
+            let _ = stdin.write_all(format!("{password}\n").as_bytes()).await;
+        }
+        let _ = stdin.write_all(contents).await;
+        drop(stdin);
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            match stderr.trim().lines().last() {
+                Some(err) => bail!("{program} failed: {err}"),
+                None => bail!("{program} failed with {}", output.status),
+            }
+        }
+        Ok(())
+    }
 }
 
 pub type DocumentSavedEventResult = Result<DocumentSavedEvent, anyhow::Error>;
@@ -134,6 +194,8 @@ pub struct SavePoint {
 pub enum DocumentOpenError {
     #[error("path must be a regular file, symlink, or directory")]
     IrregularFile,
+    #[error("the document has unsaved changes")]
+    Modified,
     #[error(transparent)]
     IoError(#[from] io::Error),
 }
@@ -150,20 +212,34 @@ pub struct Document {
     /// To know if they're up-to-date, check the `id` field in `DocumentInlayHints`.
     pub(crate) inlay_hints: HashMap<ViewId, DocumentInlayHints>,
     pub(crate) jump_labels: HashMap<ViewId, Vec<Overlay>>,
+    /// The occurrences of the symbol under the cursor of each view, as the language server
+    /// highlights them with `editor.lsp.auto-document-highlight`.
+    pub document_highlights: HashMap<ViewId, Vec<DocumentHighlight>>,
+    pub document_highlight_controller: TaskController,
     /// Set to `true` when the document is updated, reset to `false` on the next inlay hints
     /// update from the LSP
     pub inlay_hints_oudated: bool,
+    /// The titles of the `code-actions-on-save` applied before the pending write, which are
+    /// shown once it finishes.
+    pub applied_code_actions: Vec<String>,
 
     path: Option<PathBuf>,
     relative_path: OnceCell<Option<PathBuf>>,
     encoding: &'static encoding::Encoding,
     has_bom: bool,
+    /// Whether writing the text in its encoding loses information, because the file had bytes
+    /// which are invalid in the encoding or the text has characters the encoding can't represent.
+    encoding_lossy: bool,
 
     pub restore_cursor: bool,
 
     /// Current indent style.
     pub indent_style: IndentStyle,
     editor_config: EditorConfig,
+    /// Settings from the modelines of the file, which take precedence over EditorConfig.
+    modeline: Modeline,
+    /// Settings from `:set-local`, which take precedence over the modelines.
+    local_options: Modeline,
 
     /// The document's default line ending.
     pub line_ending: LineEnding,
@@ -195,6 +271,9 @@ pub struct Document {
 
     pub(crate) diagnostics: Vec<Diagnostic>,
     pub(crate) language_servers: HashMap<LanguageServerName, Arc<Client>>,
+    /// The order in which the language servers are tried for the features with
+    /// `feature-priorities`, see [`language_server_order`].
+    language_server_order: HashMap<LanguageServerFeature, Vec<usize>>,
 
     diff_handle: Option<DiffHandle>,
     version_control_head: Option<Arc<ArcSwap<Box<str>>>>,
@@ -203,8 +282,14 @@ pub struct Document {
     pub focused_at: std::time::Instant,
 
     pub readonly: bool,
-
-    pub previous_diagnostic_id: Option<String>,
+    /// Whether the file was over `editor.large-file.size` when it was opened, which disables
+    /// syntax highlighting, language servers, soft wrap, the diff gutter and word completion.
+    large_file: bool,
+    /// Whether the document is a hex dump of a binary file, which is parsed back into bytes when
+    /// it is written.
+    hex: bool,
+    /// Name displayed while the document has no path. Defaults to [`SCRATCH_BUFFER_NAME`].
+    scratch_name: Option<String>,
 
     /// Annotations for LSP document color swatches
     pub color_swatches: Option<DocumentColorSwatches>,
@@ -212,6 +297,15 @@ pub struct Document {
     // large refactor that would make `&mut Editor` available on the `DocumentDidChange` event.
     pub color_swatch_controller: TaskController,
     pub pull_diagnostic_controller: TaskController,
+    /// The semantic tokens of the document, with `editor.lsp.semantic-tokens`.
+    pub semantic_tokens: Option<DocumentSemanticTokens>,
+    pub semantic_tokens_controller: TaskController,
+    /// The sorted character ranges of the misspelled words, with `editor.spell-check`.
+    pub misspellings: Vec<std::ops::Range<usize>>,
+    pub spell_check_controller: TaskController,
+    /// The innermost captures of the query of `:tree-sitter-playground` by their character
+    /// ranges, when the document is its source.
+    pub query_captures: Vec<(String, std::ops::Range<usize>)>,
 
     // NOTE: this field should eventually go away - we should use the Editor's syn_loader instead
     // of storing a copy on every doc. Then we can remove the surrounding `Arc` and use the
@@ -226,6 +320,28 @@ pub struct DocumentColorSwatches {
     pub color_swatches_padding: Vec<InlineAnnotation>,
 }
 
+/// The semantic tokens of a language server for a document.
+#[derive(Debug, Clone)]
+pub struct DocumentSemanticTokens {
+    pub language_server_id: LanguageServerId,
+    /// Identifies the tokens to the language server, which then only sends the changes to them.
+    pub result_id: Option<String>,
+    /// The tokens as the language server sent them.
+    pub data: Vec<lsp::SemanticToken>,
+    /// The sorted character ranges of the tokens, with the index of their theme scopes in
+    /// `scopes`.
+    pub ranges: Vec<(std::ops::Range<usize>, usize)>,
+    /// The theme scopes of the tokens, most specific first.
+    pub scopes: Vec<Vec<String>>,
+}
+
+/// An occurrence of the symbol under the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentHighlight {
+    pub range: std::ops::Range<usize>,
+    pub kind: helix_lsp::lsp::DocumentHighlightKind,
+}
+
 /// Inlay hints for a single `(Document, View)` combo.
 ///
 /// There are `*_inlay_hints` field for each kind of hints an LSP can send since we offer the
@@ -426,6 +542,57 @@ fn encode_from_utf8(
     }
 }
 
+/// The order in which the language servers of `config` are tried for each feature of its
+/// `feature-priorities`, as indices into its `language-servers`. The prioritized servers come
+/// first and the others keep their configured order.
+fn language_server_order(
+    config: &LanguageConfiguration,
+) -> HashMap<LanguageServerFeature, Vec<usize>> {
+    let servers = &config.language_servers;
+    config
+        .feature_priorities
+        .iter()
+        .map(|(feature, priorities)| {
+            for name in priorities {
+                if !servers.iter().any(|features| features.name == *name) {
+                    log::warn!(
+                        "'{name}' in the feature-priorities of {} is not one of its language servers",
+                        config.language_id
+                    );
+                }
+            }
+            let mut order: Vec<_> = (0..servers.len()).collect();
+            // The sort is stable, so the other servers keep their order.
+            order.sort_by_key(|&i| {
+                priorities
+                    .iter()
+                    .position(|name| *name == servers[i].name)
+                    .unwrap_or(usize::MAX)
+            });
+            (*feature, order)
+        })
+        .collect()
+}
+
+/// Whether the file at `path` is opened in large file mode, see `LargeFileConfig`.
+fn is_large_file(path: &Path, config: &Config) -> bool {
+    config.large_file.enable
+        && path
+            .metadata()
+            .is_ok_and(|metadata| metadata.len() >= config.large_file.size)
+}
+
+/// Whether the file looks binary, judging by its first bytes.
+fn is_binary_file(path: &Path) -> bool {
+    let mut buf = [0u8; BUF_SIZE];
+    std::fs::File::open(path)
+        .and_then(|mut file| io::Read::read(&mut file, &mut buf))
+        .is_ok_and(|read| {
+            let buf = &buf[..read];
+            Encoding::for_bom(buf).is_none() && detect_utf16(buf).is_none() && hex::is_binary(buf)
+        })
+}
+
 // The documentation and implementation of this function should be up-to-date with
 // its sibling function, `to_writer()`.
 //
@@ -436,6 +603,15 @@ pub fn from_reader<R: std::io::Read + ?Sized>(
     reader: &mut R,
     encoding: Option<&'static Encoding>,
 ) -> Result<(Rope, &'static Encoding, bool), io::Error> {
+    decode_reader(reader, encoding).map(|(rope, encoding, has_bom, _)| (rope, encoding, has_bom))
+}
+
+/// The same as [`from_reader`], but also returns whether malformed bytes were replaced, in which
+/// case writing the text won't reproduce the original bytes.
+fn decode_reader<R: std::io::Read + ?Sized>(
+    reader: &mut R,
+    encoding: Option<&'static Encoding>,
+) -> Result<(Rope, &'static Encoding, bool, bool), io::Error> {
     // These two buffers are 8192 bytes in size each and are used as
     // intermediaries during the decoding process. Text read into `buf`
     // from `reader` is decoded into `buf_out` as UTF-8. Once either
@@ -467,6 +643,7 @@ pub fn from_reader<R: std::io::Read + ?Sized>(
     // contain valid UTF-8.
     let buf_str = unsafe { std::str::from_utf8_unchecked_mut(&mut buf_out[..]) };
     let mut total_written = 0usize;
+    let mut lossy = false;
     loop {
         let mut total_read = 0usize;
 
@@ -475,11 +652,12 @@ pub fn from_reader<R: std::io::Read + ?Sized>(
         // which would happen in cases where the output buffer is filled to
         // capacity.
         loop {
-            let (result, read, written, ..) = decoder.decode_to_str(
+            let (result, read, written, had_replacements) = decoder.decode_to_str(
                 &slice[total_read..],
                 &mut buf_str[total_written..],
                 is_empty,
             );
+            lossy |= had_replacements;
 
             // These variables act as the read and write cursors of `buf` and `buf_str` respectively.
             // They are necessary in case the output buffer fills before decoding of the entire input
@@ -516,7 +694,7 @@ pub fn from_reader<R: std::io::Read + ?Sized>(
         is_empty = read == 0;
     }
     let rope = builder.finish();
-    Ok((rope, encoding, has_bom))
+    Ok((rope, encoding, has_bom, lossy))
 }
 
 pub fn read_to_string<R: std::io::Read + ?Sized>(
@@ -584,6 +762,7 @@ fn read_and_detect_encoding<R: std::io::Read + ?Sized>(
     let (encoding, has_bom) = encoding
         .map(|encoding| (encoding, false))
         .or_else(|| encoding::Encoding::for_bom(buf).map(|(encoding, _bom_size)| (encoding, true)))
+        .or_else(|| detect_utf16(&buf[..read]).map(|encoding| (encoding, false)))
         .unwrap_or_else(|| {
             let mut encoding_detector = chardetng::EncodingDetector::new();
             encoding_detector.feed(buf, is_empty);
@@ -594,6 +773,34 @@ fn read_and_detect_encoding<R: std::io::Read + ?Sized>(
     Ok((encoding, has_bom, decoder, read))
 }
 
+/// Detects UTF-16 without a BOM, which `chardetng` doesn't, from mostly ASCII text where every
+/// other byte is NUL.
+fn detect_utf16(buf: &[u8]) -> Option<&'static Encoding> {
+    let pairs = buf.len() / 2;
+    if pairs < 2 {
+        return None;
+    }
+    let count = |nul: usize| {
+        buf.chunks_exact(2)
+            .filter(|pair| pair[nul] == 0 && pair[1 - nul] != 0)
+            .count()
+    };
+    // At least 3 out of 4 characters must be ASCII.
+    if count(1) * 4 >= pairs * 3 {
+        Some(encoding::UTF_16LE)
+    } else if count(0) * 4 >= pairs * 3 {
+        Some(encoding::UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Whether the text can be written in the encoding without replacing characters.
+pub fn can_encode(text: &Rope, encoding: &'static Encoding) -> bool {
+    let encoding = encoding.output_encoding();
+    encoding == encoding::UTF_8 || text.chunks().all(|chunk| !encoding.encode(chunk).2)
+}
+
 // The documentation and implementation of this function should be up-to-date with
 // its sibling function, `from_reader()`.
 //
@@ -701,13 +908,17 @@ pub fn from(
             relative_path: OnceCell::new(),
             encoding,
             has_bom,
+            encoding_lossy: false,
             text,
             selections: HashMap::default(),
             inlay_hints: HashMap::default(),
             inlay_hints_oudated: false,
+            applied_code_actions: Vec::new(),
             view_data: Default::default(),
             indent_style: DEFAULT_INDENT,
             editor_config: EditorConfig::default(),
+            modeline: Modeline::default(),
+            local_options: Modeline::default(),
             line_ending,
             restore_cursor: false,
             syntax: None,
@@ -722,17 +933,27 @@ pub fn from(
             last_saved_revision: 0,
             modified_since_accessed: false,
             language_servers: HashMap::new(),
+            language_server_order: HashMap::new(),
             diff_handle: None,
             config,
             version_control_head: None,
             focused_at: std::time::Instant::now(),
             readonly: false,
+            large_file: false,
+            hex: false,
+            scratch_name: None,
             jump_labels: HashMap::new(),
+            document_highlights: HashMap::new(),
+            document_highlight_controller: TaskController::new(),
             color_swatches: None,
             color_swatch_controller: TaskController::new(),
             syn_loader,
-            previous_diagnostic_id: None,
             pull_diagnostic_controller: TaskController::new(),
+            semantic_tokens: None,
+            semantic_tokens_controller: TaskController::new(),
+            misspellings: Vec::new(),
+            spell_check_controller: TaskController::new(),
+            query_captures: Vec::new(),
         }
     }
 
@@ -759,6 +980,9 @@ pub fn open(
         if path.metadata().is_ok_and(|metadata| !metadata.is_file()) {
             return Err(DocumentOpenError::IrregularFile);
         }
+        if is_binary_file(path) {
+            return Self::open_hex(path, config, syn_loader);
+        }
 
         let editor_config = if config.load().editor_config {
             EditorConfig::find(path)
@@ -767,20 +991,25 @@ pub fn open(
         };
         encoding = encoding.or(editor_config.encoding);
 
+        let large_file = is_large_file(path, &config.load());
+
         // Open the file if it exists, otherwise assume it is a new file (and thus empty).
-        let (rope, encoding, has_bom) = if path.exists() {
+        let (rope, encoding, has_bom, lossy) = if path.exists() {
             let mut file = std::fs::File::open(path)?;
-            from_reader(&mut file, encoding)?
+            decode_reader(&mut file, encoding)?
         } else {
             let line_ending = editor_config
                 .line_ending
                 .unwrap_or_else(|| config.load().default_line_ending.into());
             let encoding = encoding.unwrap_or(encoding::UTF_8);
-            (Rope::from(line_ending.as_str()), encoding, false)
+            (Rope::from(line_ending.as_str()), encoding, false, false)
         };
 
         let loader = syn_loader.load();
         let mut doc = Self::from(rope, Some((encoding, has_bom)), config, syn_loader);
+        doc.large_file = large_file;
+        doc.encoding_lossy = lossy;
+        doc.detect_modeline();
 
         // set the path and try detecting the language
         doc.set_path(Some(path));
@@ -794,6 +1023,56 @@ pub fn open(
         Ok(doc)
     }
 
+    /// Opens the file as a hex dump of its bytes, see [`helix_core::hex`].
+    pub fn open_hex(
+        path: &Path,
+        config: Arc<dyn DynAccess<Config>>,
+        syn_loader: Arc<ArcSwap<syntax::Loader>>,
+    ) -> Result<Self, DocumentOpenError> {
+        if path.metadata().is_ok_and(|metadata| !metadata.is_file()) {
+            return Err(DocumentOpenError::IrregularFile);
+        }
+        let bytes = if path.exists() {
+            std::fs::read(path)?
+        } else {
+            Vec::new()
+        };
+        let large_file = is_large_file(path, &config.load());
+        let rope = Rope::from(hex::dump(&bytes));
+        let mut doc = Self::from(rope, Some((encoding::UTF_8, false)), config, syn_loader);
+        doc.large_file = large_file;
+        doc.hex = true;
+        doc.line_ending = LineEnding::LF;
+        doc.set_path(Some(path));
+        Ok(doc)
+    }
+
+    /// Turns the document into a hex dump of its file like [`Document::open_hex`], unless it
+    /// has unsaved changes. Its language is unset and its history cleared, since the text it had
+    /// can't be written as a hex dump.
+    pub fn reload_as_hex(
+        &mut self,
+        view: &mut View,
+        provider_registry: &DiffProviderRegistry,
+    ) -> Result<(), DocumentOpenError> {
+        if self.is_modified() {
+            return Err(DocumentOpenError::Modified);
+        }
+        let loader = self.syn_loader.load();
+        self.set_language(None, &loader);
+        self.hex = true;
+        self.large_file = self
+            .path
+            .as_deref()
+            .is_some_and(|path| is_large_file(path, &self.config.load()));
+        self.reload(view, provider_registry)
+            .map_err(|err| io::Error::other(err.to_string()))?;
+        self.line_ending = LineEnding::LF;
+        self.history = Cell::new(History::default());
+        self.reset_modified();
+        Ok(())
+    }
+
     /// The same as [`format`], but only returns formatting changes if auto-formatting
     /// is configured.
     pub fn auto_format(
@@ -807,6 +1086,143 @@ pub fn auto_format(
         }
     }
 
+    /// Returns the changes formatting `range` with the formatter of the language injected there,
+    /// if `range` is within an injection of another language than the document's, or an error if
+    /// that language has no formatter. The indentation shared by the lines of `range` is removed
+    /// from the formatter's input and added back to its output.
+    pub fn format_injection(
+        &self,
+        editor: &Editor,
+        range: helix_core::Range,
+    ) -> Option<anyhow::Result<BoxFuture<'static, Result<Transaction, FormatterError>>>> {
+        let syntax = self.syntax()?;
+        let text = self.text().clone();
+        let (from, to) = (range.from(), range.to());
+        let layer = syntax
+            .layer_for_byte_range(text.char_to_byte(from) as u32, text.char_to_byte(to) as u32);
+        let language = syntax.layer(layer).language;
+        if layer == syntax.root_layer() || language == syntax.root_language() {
+            return None;
+        }
+        let loader = ArcSwap::load(&editor.syn_loader);
+        let config = loader.language(language).config().clone();
+        let name = &config.language_id;
+        let Some(formatter) = config.formatter.as_ref() else {
+            return Some(Err(anyhow!("No formatter configured for {name}")));
+        };
+        let fmt_cmd = match helix_stdx::env::which(&formatter.command) {
+            Ok(fmt_cmd) => fmt_cmd,
+            Err(err) => return Some(Err(anyhow!("Formatter of {name}: {err}"))),
+        };
+        log::debug!(
+            "formatting {} in '{}' with command '{}', args {:?}",
+            config.language_id,
+            self.display_name(),
+            fmt_cmd.display(),
+            formatter.args,
+        );
+
+        let selected = text.slice(from..to).to_string();
+        let partial_first_line = text.line_to_char(text.char_to_line(from)) != from;
+        let (indent, input) = dedent(&selected, partial_first_line);
+        let Some(output) = self.run_formatter(editor, fmt_cmd, &formatter.args, Rope::from(input))
+        else {
+            return Some(Err(anyhow!(
+                "Failed to expand the formatter arguments of {name}"
+            )));
+        };
+        let formatting_future = async move {
+            let output = output.await?;
+            let formatted = indent_lines(
+                &output,
+                &indent,
+                partial_first_line,
+                selected.ends_with('\n'),
+            );
+            let mut formatted_text = text.clone();
+            formatted_text.remove(from..to);
+            formatted_text.insert(from, &formatted);
+            Ok(helix_core::diff::compare_ropes(&text, &formatted_text))
+        };
+        Some(Ok(formatting_future.boxed()))
+    }
+
+    /// Runs the formatter `fmt_cmd` with `fmt_args` in the directory of the document, writing
+    /// `input` to its stdin. Returns `None` if the arguments can't be expanded.
+    fn run_formatter(
+        &self,
+        editor: &Editor,
+        fmt_cmd: PathBuf,
+        fmt_args: &[String],
+        input: Rope,
+    ) -> Option<impl Future<Output = Result<String, FormatterError>> + Send + 'static> {
+        use std::process::Stdio;
+
+        let mut process = tokio::process::Command::new(&fmt_cmd);
+
+        if let Some(doc_dir) = self.path.as_ref().and_then(|path| path.parent()) {
+            process.current_dir(doc_dir);
+        }
+
+        let args = match fmt_args
+            .iter()
+            .map(|content| expansion::expand(editor, Token::expand(content)))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(args) => args,
+            Err(err) => {
+                log::error!("Failed to expand formatter arguments: {err}");
+                return None;
+            }
+        };
+
+        process
+            .args(args.iter().map(AsRef::as_ref))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        Some(async move {
+            let mut process = process
+                .spawn()
+                .map_err(|e| FormatterError::SpawningFailed {
+                    command: fmt_cmd.to_string_lossy().into(),
+                    error: e.kind(),
+                })?;
+
+            let mut stdin = process.stdin.take().ok_or(FormatterError::BrokenStdin)?;
+            let input_task = tokio::spawn(async move {
+                to_writer(&mut stdin, (encoding::UTF_8, false), &input).await
+                // Note that `stdin` is dropped here, causing the pipe to close. This can
+                // avoid a deadlock with `wait_with_output` below if the process is waiting on
+                // stdin to close before exiting.
+            });
+            let (input_result, output_result) = tokio::join! {
+                input_task,
+                process.wait_with_output(),
+            };
+            let _ = input_result.map_err(|_| FormatterError::BrokenStdin)?;
+            let output = output_result.map_err(|_| FormatterError::WaitForOutputFailed)?;
+
+            if !output.status.success() {
+                if !output.stderr.is_empty() {
+                    let err = String::from_utf8_lossy(&output.stderr).to_string();
+                    log::error!("Formatter error: {}", err);
+                    return Err(FormatterError::NonZeroExitStatus(Some(err)));
+                }
+
+                return Err(FormatterError::NonZeroExitStatus(None));
+            } else if !output.stderr.is_empty() {
+                log::debug!(
+                    "Formatter printed to stderr: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+
+            String::from_utf8(output.stdout).map_err(|_| FormatterError::InvalidUtf8Output)
+        })
+    }
+
     /// If supported, returns the changes that should be applied to this document in order
     /// to format it nicely.
     // We can't use anyhow::Result here since the output of the future has to be
@@ -830,75 +1246,11 @@ pub fn format(
                 self.display_name(),
                 fmt_cmd.display(),
             );
-            use std::process::Stdio;
             let text = self.text().clone();
-
-            let mut process = tokio::process::Command::new(&fmt_cmd);
-
-            if let Some(doc_dir) = self.path.as_ref().and_then(|path| path.parent()) {
-                process.current_dir(doc_dir);
-            }
-
-            let args = match fmt_args
-                .iter()
-                .map(|content| expansion::expand(editor, Token::expand(content)))
-                .collect::<Result<Vec<_>, _>>()
-            {
-                Ok(args) => args,
-                Err(err) => {
-                    log::error!("Failed to expand formatter arguments: {err}");
-                    return None;
-                }
-            };
-
-            process
-                .args(args.iter().map(AsRef::as_ref))
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped());
-
+            let output = self.run_formatter(editor, fmt_cmd, fmt_args, text.clone())?;
             let formatting_future = async move {
-                let mut process = process
-                    .spawn()
-                    .map_err(|e| FormatterError::SpawningFailed {
-                        command: fmt_cmd.to_string_lossy().into(),
-                        error: e.kind(),
-                    })?;
-
-                let mut stdin = process.stdin.take().ok_or(FormatterError::BrokenStdin)?;
-                let input_text = text.clone();
-                let input_task = tokio::spawn(async move {
-                    to_writer(&mut stdin, (encoding::UTF_8, false), &input_text).await
-                    // Note that `stdin` is dropped here, causing the pipe to close. This can
-                    // avoid a deadlock with `wait_with_output` below if the process is waiting on
-                    // stdin to close before exiting.
-                });
-                let (input_result, output_result) = tokio::join! {
-                    input_task,
-                    process.wait_with_output(),
-                };
-                let _ = input_result.map_err(|_| FormatterError::BrokenStdin)?;
-                let output = output_result.map_err(|_| FormatterError::WaitForOutputFailed)?;
-
-                if !output.status.success() {
-                    if !output.stderr.is_empty() {
-                        let err = String::from_utf8_lossy(&output.stderr).to_string();
-                        log::error!("Formatter error: {}", err);
-                        return Err(FormatterError::NonZeroExitStatus(Some(err)));
-                    }
-
-                    return Err(FormatterError::NonZeroExitStatus(None));
-                } else if !output.stderr.is_empty() {
-                    log::debug!(
-                        "Formatter printed to stderr: {}",
-                        String::from_utf8_lossy(&output.stderr)
-                    );
-                }
-
-                let str = std::str::from_utf8(&output.stdout)
-                    .map_err(|_| FormatterError::InvalidUtf8Output)?;
-
-                Ok(helix_core::diff::compare_ropes(&text, &Rope::from(str)))
+                let output = output.await?;
+                Ok(helix_core::diff::compare_ropes(&text, &Rope::from(output)))
             };
             return Some(formatting_future.boxed());
         };
@@ -945,17 +1297,33 @@ impl Future<Output = Result<DocumentSavedEvent, anyhow::Error>> + 'static + Send
         anyhow::Error,
     > {
         let path = path.map(|path| path.into());
-        self.save_impl(path, force)
+        self.save_impl(path, force, None)
 
         // futures_util::future::Ready<_>,
     }
 
+    /// The same as [`Document::save`], but the file is written by a command with elevated
+    /// privileges.
+    pub fn save_privileged<P: Into<PathBuf>>(
+        &mut self,
+        path: Option<P>,
+        force: bool,
+        privileged: PrivilegedWrite,
+    ) -> Result<
+        impl Future<Output = Result<DocumentSavedEvent, anyhow::Error>> + 'static + Send,
+        anyhow::Error,
+    > {
+        let path = path.map(|path| path.into());
+        self.save_impl(path, force, Some(privileged))
+    }
+
     /// The `Document`'s text is encoded according to its encoding and written to the file located
     /// at its `path()`.
     fn save_impl(
         &mut self,
         path: Option<PathBuf>,
         force: bool,
+        privileged: Option<PrivilegedWrite>,
     ) -> Result<
         impl Future<Output = Result<DocumentSavedEvent, anyhow::Error>> + 'static + Send,
         anyhow::Error,
@@ -968,6 +1336,11 @@ impl Future<Output = Result<DocumentSavedEvent, anyhow::Error>> + 'static + Send
         // we clone and move text + path into the future so that we asynchronously save the current
         // state without blocking any further edits.
         let text = self.text().clone();
+        let hex_bytes = if self.hex {
+            Some(hex::parse(&text.to_string())?)
+        } else {
+            None
+        };
 
         let path = match path {
             Some(path) => helix_stdx::path::canonicalize(path),
@@ -989,10 +1362,11 @@ impl Future<Output = Result<DocumentSavedEvent, anyhow::Error>> + 'static + Send
 
         let encoding_with_bom_info = (self.encoding, self.has_bom);
         let last_saved_time = self.last_saved_time;
+        let hex = self.hex;
 
         // We encode the file according to the `Document`'s encoding.
         let future = async move {
-            use tokio::fs;
+            use tokio::{fs, io::AsyncWriteExt as _};
             if let Some(parent) = path.parent() {
                 // TODO: display a prompt asking the user if the directories should be created
                 if !parent.exists() {
@@ -1026,7 +1400,7 @@ impl Future<Output = Result<DocumentSavedEvent, anyhow::Error>> + 'static + Send
                 })
                 .unwrap_or_else(|| path.clone());
 
-            if readonly(&write_path) {
+            if privileged.is_none() && readonly(&write_path) {
                 bail!(std::io::Error::new(
                     std::io::ErrorKind::PermissionDenied,
                     "Path is read only"
@@ -1035,7 +1409,8 @@ impl Future<Output = Result<DocumentSavedEvent, anyhow::Error>> + 'static + Send
 
             // Assume it is a hardlink to prevent data loss if the metadata cant be read (e.g. on certain Windows configurations)
             let is_hardlink = helix_stdx::faccess::hardlink_count(&write_path).unwrap_or(2) > 1;
-            let backup = if path.exists() && atomic_save {
+            // The file can't be backed up next to itself without the privileges.
+            let backup = if privileged.is_none() && path.exists() && atomic_save {
                 let path_ = write_path.clone();
                 // hacks: we use tempfile to handle the complex task of creating
                 // non clobbered temporary path for us we don't want
@@ -1068,8 +1443,19 @@ impl Future<Output = Result<DocumentSavedEvent, anyhow::Error>> + 'static + Send
             };
 
             let write_result: anyhow::Result<_> = async {
+                if let Some(privileged) = &privileged {
+                    let mut contents = Vec::new();
+                    match &hex_bytes {
+                        Some(bytes) => contents.extend_from_slice(bytes),
+                        None => to_writer(&mut contents, encoding_with_bom_info, &text).await?,
+                    }
+                    return privileged.write(&write_path, &contents).await;
+                }
                 let mut dst = tokio::fs::File::create(&write_path).await?;
-                to_writer(&mut dst, encoding_with_bom_info, &text).await?;
+                match &hex_bytes {
+                    Some(bytes) => dst.write_all(bytes).await?,
+                    None => to_writer(&mut dst, encoding_with_bom_info, &text).await?,
+                }
                 dst.sync_all().await?;
                 Ok(())
             }
@@ -1121,6 +1507,7 @@ impl Future<Output = Result<DocumentSavedEvent, anyhow::Error>> + 'static + Send
                 save_time,
                 doc_id,
                 path,
+                encoding_lossy: !hex && !can_encode(&text, encoding_with_bom_info.0),
                 text: text.clone(),
             };
 
@@ -1149,8 +1536,19 @@ pub fn detect_language_config(
         &self,
         loader: &syntax::Loader,
     ) -> Option<Arc<syntax::config::LanguageConfiguration>> {
-        let language = loader
-            .language_for_filename(self.path.as_ref()?)
+        // `:set-local language` takes the name of a language like `:set-language`, while
+        // modelines may use the names of other editors.
+        if let Some(name) = self.local_options.language.as_deref() {
+            let language = loader.language_for_name(name.to_string())?;
+            return Some(loader.language(language).config().clone());
+        }
+        let modeline_language = self
+            .modeline
+            .language
+            .as_deref()
+            .and_then(|name| loader.language_for_match(RopeSlice::from(name)));
+        let language = modeline_language
+            .or_else(|| loader.language_for_filename(self.path.as_ref()?))
             .or_else(|| loader.language_for_shebang(self.text().slice(..)))?;
 
         Some(loader.language(language).config().clone())
@@ -1160,7 +1558,12 @@ pub fn detect_language_config(
     /// configured in `languages.toml`, with a fallback to tabs if it isn't specified. Line ending
     /// is likewise auto-detected, and will remain unchanged if no line endings were detected.
     pub fn detect_indent_and_line_ending(&mut self) {
-        self.indent_style = if let Some(indent_style) = self.editor_config.indent_style {
+        let indent_style = self
+            .local_options
+            .indent_style
+            .or(self.modeline.indent_style)
+            .or(self.editor_config.indent_style);
+        self.indent_style = if let Some(indent_style) = indent_style {
             indent_style
         } else {
             auto_detect_indent_style(&self.text).unwrap_or_else(|| {
@@ -1170,22 +1573,61 @@ pub fn detect_indent_and_line_ending(&mut self) {
             })
         };
         if let Some(line_ending) = self
-            .editor_config
+            .local_options
             .line_ending
+            .or(self.modeline.line_ending)
+            .or(self.editor_config.line_ending)
             .or_else(|| auto_detect_line_ending(&self.text))
         {
             self.line_ending = line_ending;
         }
     }
 
+    /// Sets one of the options of Helix modelines for the document, overriding its modelines and
+    /// EditorConfig. A language set this way is only used the next time the language is detected.
+    pub fn set_local_option(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        self.local_options.set(key, value)?;
+        if let Some(indent_style) = self.local_options.indent_style {
+            self.indent_style = indent_style;
+        }
+        if let Some(line_ending) = self.local_options.line_ending {
+            self.line_ending = line_ending;
+        }
+        Ok(())
+    }
+
+    /// Reads the modelines of the text if they're enabled by `editor.modeline`.
+    pub fn detect_modeline(&mut self) {
+        self.modeline = if self.config.load().modeline && !self.large_file && !self.hex {
+            Modeline::parse(self.text.slice(..))
+        } else {
+            Modeline::default()
+        };
+    }
+
+    /// Reads the `.editorconfig` files which apply to the document's path if they're enabled by
+    /// `editor.editor-config`.
     pub fn detect_editor_config(&mut self) {
-        if self.config.load().editor_config {
-            if let Some(path) = self.path.as_ref() {
-                self.editor_config = EditorConfig::find(path);
-            }
+        if !self.config.load().editor_config {
+            self.editor_config = EditorConfig::default();
+        } else if let Some(path) = self.path.as_ref() {
+            self.editor_config = EditorConfig::find(path);
         }
     }
 
+    /// Whether the file was modified on disk since the document was last loaded or saved.
+    pub fn is_changed_on_disk(&self) -> bool {
+        self.path()
+            .and_then(|path| path.metadata().ok())
+            .and_then(|metadata| metadata.modified().ok())
+            .is_some_and(|mtime| mtime > self.last_saved_time)
+    }
+
+    /// The modification time of the file when the document was last loaded or saved.
+    pub fn last_saved_time(&self) -> SystemTime {
+        self.last_saved_time
+    }
+
     pub fn pickup_last_saved_time(&mut self) {
         self.last_saved_time = match self.path() {
             Some(path) => match path.metadata() {
@@ -1232,17 +1674,32 @@ pub fn reload(
         // Once we have a valid path we check if its readonly status has changed
         self.detect_readonly();
 
-        let mut file = std::fs::File::open(&path)?;
-        let (rope, ..) = from_reader(&mut file, Some(encoding))?;
+        let rope = if self.hex {
+            Rope::from(hex::dump(&std::fs::read(&path)?))
+        } else {
+            let mut file = std::fs::File::open(&path)?;
+            let (rope, _, _, lossy) = decode_reader(&mut file, Some(encoding))?;
+            self.encoding_lossy = lossy;
+            rope
+        };
 
         // Calculate the difference between the buffer and source text, and apply it.
         // This is not considered a modification of the contents of the file regardless
         // of the encoding.
-        let transaction = helix_core::diff::compare_ropes(self.text(), &rope);
+        // Diffing large files takes too long, their text is replaced instead.
+        let transaction = if self.large_file {
+            Transaction::change(
+                self.text(),
+                [(0, self.text.len_chars(), Some(rope.to_string().into()))].into_iter(),
+            )
+        } else {
+            helix_core::diff::compare_ropes(self.text(), &rope)
+        };
         self.apply(&transaction, view.id);
         self.append_changes_to_history(view);
         self.reset_modified();
         self.pickup_last_saved_time();
+        self.detect_modeline();
         self.detect_indent_and_line_ending();
 
         match provider_registry.get_diff_base(&path) {
@@ -1261,6 +1718,7 @@ pub fn set_encoding(&mut self, label: &str) -> Result<(), Error> {
             Encoding::for_label(label.as_bytes()).ok_or_else(|| anyhow!("unknown encoding"))?;
 
         self.encoding = encoding;
+        self.encoding_lossy = !can_encode(&self.text, encoding);
 
         Ok(())
     }
@@ -1270,6 +1728,12 @@ pub fn encoding(&self) -> &'static Encoding {
         self.encoding
     }
 
+    /// Whether writing the document loses information because of its encoding: the file had
+    /// bytes which are invalid in the encoding or the encoding can't represent some characters.
+    pub fn is_encoding_lossy(&self) -> bool {
+        self.encoding_lossy
+    }
+
     /// sets the document path without sending events to various
     /// observers (like LSP), in most cases `Editor::set_doc_path`
     /// should be used instead
@@ -1296,6 +1760,10 @@ pub fn set_language(
         loader: &syntax::Loader,
     ) {
         self.language = language_config;
+        if self.large_file {
+            self.syntax = None;
+            return;
+        }
         self.syntax = self.language.as_ref().and_then(|config| {
             Syntax::new(self.text.slice(..), config.language(), loader)
                 .map_err(|err| {
@@ -1330,6 +1798,7 @@ pub fn set_selection(&mut self, view_id: ViewId, selection: Selection) {
         // TODO: use a transaction?
         self.selections
             .insert(view_id, selection.ensure_invariants(self.text().slice(..)));
+        self.reveal_cursors(view_id);
         helix_event::dispatch(SelectionDidChange {
             doc: self,
             view: view_id,
@@ -1374,6 +1843,7 @@ pub fn remove_view(&mut self, view_id: ViewId) {
         self.selections.remove(&view_id);
         self.inlay_hints.remove(&view_id);
         self.jump_labels.remove(&view_id);
+        self.document_highlights.remove(&view_id);
     }
 
     /// Apply a [`Transaction`] to the [`Document`] to change its text.
@@ -1397,6 +1867,7 @@ fn apply_impl(
                     view_id,
                     selection.clone().ensure_invariants(self.text.slice(..)),
                 );
+                self.reveal_cursors(view_id);
                 helix_event::dispatch(SelectionDidChange {
                     doc: self,
                     view: view_id,
@@ -1417,10 +1888,20 @@ fn apply_impl(
                 .ensure_invariants(self.text.slice(..));
         }
 
-        for view_data in self.view_data.values_mut() {
+        let view_ids: Vec<_> = self.view_data.keys().copied().collect();
+        for view_id in view_ids {
+            let view_data = self.view_data_mut(view_id);
             view_data.view_position.anchor = transaction
                 .changes()
                 .map_pos(view_data.view_position.anchor, Assoc::Before);
+            if !view_data.folds.is_empty() {
+                let folds = mem::take(&mut self.view_data_mut(view_id).folds);
+                let folds = folds
+                    .into_iter()
+                    .filter_map(|fold| map_fold(fold, &old_doc, &self.text, changes))
+                    .collect();
+                self.set_folds(view_id, folds);
+            }
         }
 
         // generate revert to savepoint
@@ -1585,6 +2066,20 @@ pub fn apply_temporary(&mut self, transaction: &Transaction, view_id: ViewId) ->
         self.apply_inner(transaction, view_id, false)
     }
 
+    /// Apply a [`Transaction`] to the [`Document`] without recording it in the history, which is
+    /// cleared since its revisions no longer apply to the text. This is used for buffers whose
+    /// text is generated, like output buffers, which never need to be saved.
+    pub fn apply_without_history(&mut self, transaction: &Transaction, view: &mut View) -> bool {
+        let success = self.apply(transaction, view.id);
+        self.changes = ChangeSet::new(self.text().slice(..));
+        self.old_state = None;
+        self.history = Cell::new(History::default());
+        self.reset_modified();
+        // Update jumplist entries in the view.
+        view.apply(transaction, self);
+        success
+    }
+
     fn undo_redo_impl(&mut self, view: &mut View, undo: bool) -> bool {
         if undo {
             self.append_changes_to_history(view);
@@ -1681,6 +2176,17 @@ fn earlier_later_impl(&mut self, view: &mut View, uk: UndoKind, earlier: bool) -
         } else {
             self.history.get_mut().later(uk)
         };
+        self.apply_history_jump(view, txns)
+    }
+
+    /// Jumps to any revision in the history, including the ones on other branches.
+    pub fn goto_revision(&mut self, view: &mut View, revision: usize) -> bool {
+        self.append_changes_to_history(view);
+        let txns = self.history.get_mut().jump_to(revision);
+        self.apply_history_jump(view, txns)
+    }
+
+    fn apply_history_jump(&mut self, view: &mut View, txns: Vec<Transaction>) -> bool {
         let mut success = false;
         for txn in txns {
             if self.apply_impl(&txn, view.id, true) {
@@ -1768,6 +2274,13 @@ pub fn set_last_saved_revision(&mut self, rev: usize, save_time: SystemTime) {
         self.last_saved_time = save_time;
     }
 
+    /// Updates the document after it was written as in `event`. The file no longer has bytes which
+    /// are invalid in the encoding, but may miss characters the encoding can't represent.
+    pub fn set_saved(&mut self, event: &DocumentSavedEvent) {
+        self.set_last_saved_revision(event.revision, event.save_time);
+        self.encoding_lossy = event.encoding_lossy;
+    }
+
     /// Get the document's latest saved revision.
     pub fn get_last_saved_revision(&mut self) -> usize {
         self.last_saved_revision
@@ -1816,9 +2329,41 @@ pub fn version(&self) -> i32 {
     }
 
     pub fn word_completion_enabled(&self) -> bool {
+        !self.large_file
+            && self
+                .language_config()
+                .and_then(|lang_config| lang_config.word_completion.and_then(|c| c.enable))
+                .unwrap_or_else(|| self.config.load().word_completion.enable)
+    }
+
+    /// Whether the file was too large to enable all features when it was opened, see
+    /// `editor.large-file`.
+    pub fn is_large_file(&self) -> bool {
+        self.large_file
+    }
+
+    /// Whether the document is a hex dump of a binary file.
+    pub fn is_hex(&self) -> bool {
+        self.hex
+    }
+
+    /// Formats the hex dump anew from its bytes, updating the offsets and the ASCII column
+    /// after bytes were edited.
+    pub fn refresh_hex_dump(&mut self, view: &mut View) -> Result<(), hex::ParseError> {
+        let dump = Rope::from(hex::dump(&hex::parse(&self.text.to_string())?));
+        if dump != self.text {
+            let transaction = helix_core::diff::compare_ropes(self.text(), &dump);
+            self.apply(&transaction, view.id);
+            self.append_changes_to_history(view);
+        }
+        Ok(())
+    }
+
+    /// Whether the document may be saved by `editor.auto-save`.
+    pub fn auto_save_enabled(&self) -> bool {
         self.language_config()
-            .and_then(|lang_config| lang_config.word_completion.and_then(|c| c.enable))
-            .unwrap_or_else(|| self.config.load().word_completion.enable)
+            .and_then(|lang_config| lang_config.auto_save)
+            .unwrap_or(true)
     }
 
     pub fn path_completion_enabled(&self) -> bool {
@@ -1841,6 +2386,19 @@ pub fn language_servers(&self) -> impl Iterator<Item = &helix_lsp::Client> {
         })
     }
 
+    /// Sets the running language servers of the document and sorts them for the features with
+    /// `feature-priorities` of its language.
+    pub(crate) fn set_language_servers(
+        &mut self,
+        language_servers: HashMap<LanguageServerName, Arc<Client>>,
+    ) {
+        self.language_servers = language_servers;
+        self.language_server_order = self
+            .language_config()
+            .map(language_server_order)
+            .unwrap_or_default();
+    }
+
     pub fn remove_language_server_by_name(&mut self, name: &str) -> Option<Arc<Client>> {
         self.language_servers.remove(name)
     }
@@ -1850,7 +2408,12 @@ pub fn language_servers_with_feature(
         feature: LanguageServerFeature,
     ) -> impl Iterator<Item = &helix_lsp::Client> {
         self.language_config().into_iter().flat_map(move |config| {
-            config.language_servers.iter().filter_map(move |features| {
+            let order = self.language_server_order.get(&feature);
+            // Without priorities the servers are tried in the configured order.
+            let servers = (0..config.language_servers.len())
+                .map(move |i| order.and_then(|order| order.get(i)).copied().unwrap_or(i))
+                .filter_map(|i| config.language_servers.get(i));
+            servers.filter_map(move |features| {
                 let ls = &**self.language_servers.get(&features.name)?;
                 if ls.is_initialized()
                     && ls.supports_feature(feature)
@@ -1874,6 +2437,19 @@ pub fn diff_handle(&self) -> Option<&DiffHandle> {
 
     /// Intialize/updates the differ for this document with a new base.
     pub fn set_diff_base(&mut self, diff_base: Vec<u8>) {
+        if self.large_file {
+            return;
+        }
+        if self.hex {
+            let diff_base = Rope::from(hex::dump(&diff_base));
+            match &self.diff_handle {
+                Some(differ) => {
+                    differ.update_diff_base(diff_base);
+                }
+                None => self.diff_handle = Some(DiffHandle::new(diff_base, self.text.clone())),
+            }
+            return;
+        }
         if let Ok((diff_base, ..)) = from_reader(&mut diff_base.as_slice(), Some(self.encoding)) {
             if let Some(differ) = &self.diff_handle {
                 differ.update_diff_base(diff_base);
@@ -1904,8 +2480,10 @@ pub fn syntax(&self) -> Option<&Syntax> {
 
     /// The width that the tab character is rendered at
     pub fn tab_width(&self) -> usize {
-        self.editor_config
+        self.local_options
             .tab_width
+            .or(self.modeline.tab_width)
+            .or(self.editor_config.tab_width)
             .map(|n| n.get() as usize)
             .unwrap_or_else(|| {
                 self.language_config()
@@ -1989,6 +2567,100 @@ pub fn set_view_offset(&mut self, view_id: ViewId, new_offset: ViewPosition) {
         self.view_data_mut(view_id).view_position = new_offset;
     }
 
+    /// The closed folds of the view, sorted by their first line.
+    pub fn folds(&self, view_id: ViewId) -> &[Fold] {
+        self.view_data
+            .get(&view_id)
+            .map_or(&[], |view_data| &view_data.folds)
+    }
+
+    pub(crate) fn fold_markers(&self, view_id: ViewId) -> &[InlineAnnotation] {
+        self.view_data
+            .get(&view_id)
+            .map_or(&[], |view_data| &view_data.fold_markers)
+    }
+
+    /// Closes a fold. Cursors on the lines it hides are moved to its first line.
+    pub fn add_fold(&mut self, view_id: ViewId, fold: Fold) {
+        let mut folds = self.folds(view_id).to_vec();
+        if let Err(i) = folds.binary_search(&fold) {
+            folds.insert(i, fold);
+        }
+        self.set_folds(view_id, folds);
+    }
+
+    /// Opens the folds which start on `line` or hide it. Returns whether any fold was opened.
+    pub fn remove_folds_at(&mut self, view_id: ViewId, line: usize) -> bool {
+        let mut folds = self.folds(view_id).to_vec();
+        let len = folds.len();
+        folds.retain(|fold| fold.start != line && !fold.hides(line));
+        let removed = folds.len() != len;
+        self.set_folds(view_id, folds);
+        removed
+    }
+
+    /// Replaces the closed folds of the view. Cursors on the lines they hide are moved to the
+    /// first line of the fold.
+    pub fn set_folds(&mut self, view_id: ViewId, mut folds: Vec<Fold>) {
+        folds.sort_unstable();
+        folds.dedup();
+
+        let text = self.text.slice(..);
+        if let Some(selection) = self.selections.get(&view_id) {
+            let hidden_by = |line| folds.iter().find(|fold: &&Fold| fold.hides(line));
+            if selection
+                .iter()
+                .any(|range| hidden_by(range.cursor_line(text)).is_some())
+            {
+                let selection =
+                    selection
+                        .clone()
+                        .transform(|range| match hidden_by(range.cursor_line(text)) {
+                            Some(fold) => Range::point(text.line_to_char(fold.start)),
+                            None => range,
+                        });
+                self.selections.insert(view_id, selection);
+            }
+        }
+
+        let mut fold_markers: Vec<_> = folds
+            .iter()
+            .map(|fold| {
+                let pos = helix_core::line_ending::line_end_char_index(&text, fold.start);
+                InlineAnnotation::new(pos, " ⋯")
+            })
+            .collect();
+        fold_markers.dedup_by_key(|marker| marker.char_idx);
+
+        let view_data = self.view_data_mut(view_id);
+        view_data.folds = folds;
+        view_data.fold_markers = fold_markers;
+    }
+
+    /// Opens the folds which hide a cursor of the view.
+    fn reveal_cursors(&mut self, view_id: ViewId) {
+        let (Some(selection), Some(view_data)) =
+            (self.selections.get(&view_id), self.view_data.get(&view_id))
+        else {
+            return;
+        };
+        let text = self.text.slice(..);
+        let hides_cursor = |fold: &Fold| {
+            selection
+                .iter()
+                .any(|range| fold.hides(range.cursor_line(text)))
+        };
+        if view_data.folds.iter().any(hides_cursor) {
+            let folds = view_data
+                .folds
+                .iter()
+                .filter(|fold| !hides_cursor(fold))
+                .copied()
+                .collect();
+            self.set_folds(view_id, folds);
+        }
+    }
+
     pub fn relative_path(&self) -> Option<&Path> {
         self.relative_path
             .get_or_init(|| {
@@ -2001,7 +2673,16 @@ pub fn relative_path(&self) -> Option<&Path> {
 
     pub fn display_name(&self) -> Cow<'_, str> {
         self.relative_path()
-            .map_or_else(|| SCRATCH_BUFFER_NAME.into(), |path| path.to_string_lossy())
+            .map_or_else(|| self.scratch_name().into(), |path| path.to_string_lossy())
+    }
+
+    /// The name displayed for the document while it has no path.
+    pub fn scratch_name(&self) -> &str {
+        self.scratch_name.as_deref().unwrap_or(SCRATCH_BUFFER_NAME)
+    }
+
+    pub fn set_scratch_name(&mut self, name: impl Into<String>) {
+        self.scratch_name = Some(name.into());
     }
 
     // transact(Fn) ?
@@ -2201,9 +2882,12 @@ pub fn auto_pairs<'a>(
     }
 
     pub fn snippet_ctx(&self) -> SnippetRenderCtx {
+        let path = self.path().cloned();
+        let language = self.language.clone();
         SnippetRenderCtx {
-            // TODO snippet variable resolution
-            resolve_var: Box::new(|_| None),
+            resolve_var: Box::new(move |name| {
+                snippet_variable(name, path.as_deref(), language.as_deref()).map(Cow::Owned)
+            }),
             tab_width: self.tab_width(),
             indent_style: self.indent_style,
             line_ending: self.line_ending.as_str(),
@@ -2211,13 +2895,50 @@ pub fn snippet_ctx(&self) -> SnippetRenderCtx {
     }
 
     pub fn text_width(&self) -> usize {
-        self.editor_config
-            .max_line_length
+        self.local_options
+            .text_width
+            .or(self.modeline.text_width)
+            .or(self.editor_config.max_line_length)
             .map(|n| n.get() as usize)
             .or_else(|| self.language_config().and_then(|config| config.text_width))
             .unwrap_or_else(|| self.config.load().text_width)
     }
 
+    /// Whether soft wrapping is enabled for the document, regardless of the viewport's width.
+    pub fn soft_wrap(&self) -> bool {
+        self.local_options
+            .soft_wrap
+            .or(self.modeline.soft_wrap)
+            .or_else(|| {
+                self.language_config()
+                    .and_then(|config| config.soft_wrap.as_ref())
+                    .and_then(|soft_wrap| soft_wrap.enable)
+            })
+            .or(self.config.load().soft_wrap.enable)
+            .unwrap_or(false)
+    }
+
+    /// The width of the minimap beside the text, `0` if it is disabled for the document.
+    pub fn minimap_width(&self) -> u16 {
+        let config = self.config.load();
+        let enable = self
+            .language_config()
+            .and_then(|config| config.minimap)
+            .unwrap_or(config.minimap.enable);
+        if enable {
+            config.minimap.width
+        } else {
+            0
+        }
+    }
+
+    /// Whether the inlay hints of language servers are shown in the document.
+    pub fn display_inlay_hints(&self) -> bool {
+        self.language_config()
+            .and_then(|config| config.inlay_hints)
+            .unwrap_or(self.config.load().lsp.display_inlay_hints)
+    }
+
     pub fn text_format(&self, mut viewport_width: u16, theme: Option<&Theme>) -> TextFormat {
         let config = self.config.load();
         let text_width = self.text_width();
@@ -2246,10 +2967,7 @@ pub fn text_format(&self, mut viewport_width: u16, theme: Option<&Theme>) -> Tex
             .language
             .as_ref()
             .and_then(|config| config.soft_wrap.as_ref());
-        let enable_soft_wrap = language_soft_wrap
-            .and_then(|soft_wrap| soft_wrap.enable)
-            .or(editor_soft_wrap.enable)
-            .unwrap_or(false);
+        let enable_soft_wrap = self.soft_wrap();
         let max_wrap = language_soft_wrap
             .and_then(|soft_wrap| soft_wrap.max_wrap)
             .or(config.soft_wrap.max_wrap)
@@ -2264,7 +2982,7 @@ pub fn text_format(&self, mut viewport_width: u16, theme: Option<&Theme>) -> Tex
             .unwrap_or_else(|| "↪ ".into());
         let tab_width = self.tab_width() as u16;
         TextFormat {
-            soft_wrap: enable_soft_wrap && viewport_width > 10,
+            soft_wrap: enable_soft_wrap && !self.large_file && viewport_width > 10,
             tab_width,
             max_wrap: max_wrap.min(viewport_width / 4),
             max_indent_retain: max_indent_retain.min(viewport_width * 2 / 5),
@@ -2307,9 +3025,120 @@ pub fn has_language_server_with_feature(&self, feature: LanguageServerFeature) -
     }
 }
 
+/// Splits the indentation shared by the non-blank lines of `text` from it, returning the
+/// indentation and `text` with it removed from every line. The first line is left as is when
+/// `partial_first_line` is set, as it doesn't start at the beginning of a line.
+fn dedent(text: &str, partial_first_line: bool) -> (String, String) {
+    let skip = usize::from(partial_first_line);
+    let indent = text
+        .lines()
+        .skip(skip)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .reduce(|common, indent| {
+            let len = common
+                .bytes()
+                .zip(indent.bytes())
+                .take_while(|(a, b)| a == b)
+                .count();
+            &common[..len]
+        })
+        .unwrap_or_default()
+        .to_owned();
+    let dedented = text
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(i, line)| match line.strip_prefix(indent.as_str()) {
+            _ if i < skip => line,
+            Some(line) => line,
+            None => line.trim_start_matches([' ', '\t']),
+        })
+        .collect();
+    (indent, dedented)
+}
+
+/// Prefixes the non-blank lines of `text` with `indent`, except for the first one when
+/// `partial_first_line` is set. A trailing line ending is kept only if `trailing_newline` is set.
+fn indent_lines(
+    text: &str,
+    indent: &str,
+    partial_first_line: bool,
+    trailing_newline: bool,
+) -> String {
+    let mut indented: String = text
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 && partial_first_line {
+                line.to_owned()
+            } else if line.trim().is_empty() {
+                line.trim_start_matches([' ', '\t']).to_owned()
+            } else {
+                format!("{indent}{line}")
+            }
+        })
+        .collect();
+    if !trailing_newline {
+        let len = indented.trim_end_matches(['\r', '\n']).len();
+        indented.truncate(len);
+    }
+    indented
+}
+
+/// Maps the lines of a fold through changes to the text, dropping it if it no longer spans
+/// several lines.
+fn map_fold(fold: Fold, old_text: &Rope, text: &Rope, changes: &ChangeSet) -> Option<Fold> {
+    use helix_core::{line_ending::line_end_char_index, Assoc};
+
+    let start = changes.map_pos(old_text.line_to_char(fold.start), Assoc::After);
+    let end = changes.map_pos(
+        line_end_char_index(&old_text.slice(..), fold.end),
+        Assoc::Before,
+    );
+    let fold = Fold {
+        start: text.char_to_line(start),
+        end: text.char_to_line(end),
+    };
+    (fold.end > fold.start).then_some(fold)
+}
+
+/// The value of the snippet variable `name` for the file at `path`, like `$TM_FILENAME`.
+fn snippet_variable(
+    name: &str,
+    path: Option<&Path>,
+    language: Option<&helix_core::syntax::config::LanguageConfiguration>,
+) -> Option<String> {
+    let file_name = || Some(path?.file_name()?.to_string_lossy().into_owned());
+    let workspace = || helix_loader::find_workspace().0;
+    let block_comment = || language?.block_comment_tokens.as_ref()?.first();
+    match name {
+        "TM_FILENAME" => file_name(),
+        "TM_FILENAME_BASE" => Some(path?.file_stem()?.to_string_lossy().into_owned()),
+        "TM_FILEPATH" => Some(path?.to_string_lossy().into_owned()),
+        "TM_DIRECTORY" => Some(path?.parent()?.to_string_lossy().into_owned()),
+        "RELATIVE_FILEPATH" => Some(
+            path?
+                .strip_prefix(workspace())
+                .unwrap_or(path?)
+                .to_string_lossy()
+                .into_owned(),
+        ),
+        "WORKSPACE_FOLDER" => Some(workspace().to_string_lossy().into_owned()),
+        "WORKSPACE_NAME" => Some(workspace().file_name()?.to_string_lossy().into_owned()),
+        "LINE_COMMENT" => language?.comment_tokens.as_ref()?.first().cloned(),
+        "BLOCK_COMMENT_START" => block_comment().map(|token| token.start.clone()),
+        "BLOCK_COMMENT_END" => block_comment().map(|token| token.end.clone()),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ViewData {
     view_position: ViewPosition,
+    /// Closed folds, sorted by their first line.
+    folds: Vec<Fold>,
+    /// The markers shown at the end of the first line of each fold.
+    fold_markers: Vec<InlineAnnotation>,
 }
 
 #[derive(Clone, Debug)]
@@ -2349,6 +3178,25 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn dedent_and_indent_lines() {
+        let text = "    select *\n      from t\n\n    where x\n";
+        let (indent, dedented) = dedent(text, false);
+        assert_eq!(indent, "    ");
+        assert_eq!(dedented, "select *\n  from t\n\nwhere x\n");
+        assert_eq!(indent_lines(&dedented, &indent, false, true), text);
+        assert_eq!(
+            indent_lines("select *\nfrom t\n", "  ", false, false),
+            "  select *\n  from t"
+        );
+
+        let text = "select *\n        from t\n        where x";
+        let (indent, dedented) = dedent(text, true);
+        assert_eq!(indent, "        ");
+        assert_eq!(dedented, "select *\nfrom t\nwhere x");
+        assert_eq!(indent_lines(&dedented, &indent, true, false), text);
+    }
+
     #[test]
     fn changeset_to_changes_ignore_line_endings() {
         use helix_lsp::{lsp, Client, OffsetEncoding};
@@ -2388,6 +3236,81 @@ fn changeset_to_changes_ignore_line_endings() {
         );
     }
 
+    #[test]
+    fn apply_without_history() {
+        use crate::{editor::GutterConfig, View};
+
+        let mut doc = Document::from(
+            Rope::from("hello"),
+            None,
+            Arc::new(ArcSwap::new(Arc::new(Config::default()))),
+            Arc::new(ArcSwap::from_pointee(syntax::Loader::default())),
+        );
+        let mut view = View::new(doc.id(), GutterConfig::default());
+        doc.ensure_view_init(view.id);
+        for text in ["hello world", "bye"] {
+            let transaction = Transaction::change(
+                doc.text(),
+                [(0, doc.text().len_chars(), Some(text.into()))].into_iter(),
+            );
+            assert!(doc.apply_without_history(&transaction, &mut view));
+            assert_eq!(doc.text(), text);
+        }
+
+        assert!(!doc.is_modified());
+        assert_eq!(doc.get_current_revision(), 0);
+        assert!(!doc.undo(&mut view));
+        assert_eq!(doc.text(), "bye");
+    }
+
+    #[test]
+    fn feature_priorities() {
+        let config: LanguageConfiguration = toml::from_str(
+            r#"
+            name = "html"
+            scope = "text.html.basic"
+            file-types = ["html"]
+            language-servers = ["html-ls", "tailwind-ls", "typos-lsp"]
+            feature-priorities = { hover = ["typos-lsp", "tailwind-ls"], completion = ["unknown", "tailwind-ls"] }
+            "#,
+        )
+        .unwrap();
+        let order = language_server_order(&config);
+        assert_eq!(order.len(), 2);
+        assert_eq!(order[&LanguageServerFeature::Hover], [2, 1, 0]);
+        // Unknown servers are ignored and the others keep their order.
+        assert_eq!(order[&LanguageServerFeature::Completion], [1, 0, 2]);
+    }
+
+    #[test]
+    fn large_file_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        std::fs::write(&path, "0123456789").unwrap();
+        let open = |enable, size| {
+            let config = Config {
+                large_file: crate::editor::LargeFileConfig { enable, size },
+                ..Default::default()
+            };
+            Document::open(
+                &path,
+                None,
+                false,
+                Arc::new(ArcSwap::new(Arc::new(config))),
+                Arc::new(ArcSwap::from_pointee(syntax::Loader::default())),
+            )
+            .unwrap()
+        };
+
+        assert!(open(true, 10).is_large_file());
+        assert!(!open(true, 11).is_large_file());
+        assert!(!open(false, 10).is_large_file());
+        // Large files are opened without the features which scale with their size.
+        let doc = open(true, 10);
+        assert!(!doc.word_completion_enabled());
+        assert_eq!(doc.text(), "0123456789");
+    }
+
     #[test]
     fn changeset_to_changes() {
         use helix_lsp::{lsp, Client, OffsetEncoding};
@@ -2520,6 +3443,76 @@ fn test_line_ending() {
         );
     }
 
+    #[test]
+    fn detect_encoding() {
+        let utf16le: Vec<u8> = "hello\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let (rope, encoding, has_bom) = from_reader(&mut utf16le.as_slice(), None).unwrap();
+        assert_eq!(
+            (rope.to_string().as_str(), encoding, has_bom),
+            ("hello\n", encoding::UTF_16LE, false)
+        );
+        let utf16be: Vec<u8> = "hello\n"
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect();
+        assert_eq!(detect_utf16(&utf16be), Some(encoding::UTF_16BE));
+        assert_eq!(detect_utf16(b"hello\n"), None);
+
+        let (.., lossy) =
+            decode_reader(&mut b"caf\xc3\xa9".as_slice(), Some(encoding::UTF_8)).unwrap();
+        assert!(!lossy);
+        let (.., lossy) = decode_reader(&mut b"caf\xe9".as_slice(), Some(encoding::UTF_8)).unwrap();
+        assert!(lossy);
+
+        assert!(can_encode(&Rope::from("café"), encoding::WINDOWS_1252));
+        assert!(!can_encode(&Rope::from("café ☕"), encoding::WINDOWS_1252));
+        assert!(can_encode(&Rope::from("café ☕"), encoding::UTF_16LE));
+    }
+
+    #[test]
+    fn snippet_variables() {
+        let path = Path::new("/src/helix/main.rs");
+        let var = |name| snippet_variable(name, Some(path), None);
+        assert_eq!(var("TM_FILENAME").as_deref(), Some("main.rs"));
+        assert_eq!(var("TM_FILENAME_BASE").as_deref(), Some("main"));
+        assert_eq!(var("TM_DIRECTORY").as_deref(), Some("/src/helix"));
+        assert_eq!(var("LINE_COMMENT"), None);
+        assert_eq!(var("UNKNOWN"), None);
+        assert_eq!(snippet_variable("TM_FILENAME", None, None), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn privileged_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+        let privileged = |command: &[&str]| PrivilegedWrite {
+            command: command.iter().map(|arg| arg.to_string()).collect(),
+            password: None,
+        };
+
+        privileged(&["sh", "-c", "cat > \"$0\""])
+            .write(&path, b"contents")
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"contents");
+        let err = privileged(&["sh", "-c", "echo denied >&2; exit 1"])
+            .write(&path, b"contents")
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "sh failed: denied");
+
+        let with_password = PrivilegedWrite {
+            password: Some("secret".to_owned()),
+            ..privileged(&["sh", "-c", "read pw; [ \"$pw\" = secret ] && cat > \"$0\""])
+        };
+        with_password.write(&path, b"new contents").await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"new contents");
+    }
+
     macro_rules! decode {
         ($name:ident, $label:expr, $label_override:expr) => {
             #[test]
@@ -2,30 +2,33 @@
     annotations::diagnostics::{DiagnosticFilter, InlineDiagnosticsConfig},
     clipboard::ClipboardProvider,
     document::{
-        DocumentOpenError, DocumentSavedEventFuture, DocumentSavedEventResult, Mode, SavePoint,
+        DocumentOpenError, DocumentSavedEventFuture, DocumentSavedEventResult, Mode,
+        PrivilegedWrite, SavePoint,
     },
-    events::{DocumentDidClose, DocumentDidOpen, DocumentFocusLost},
+    events::{DiagnosticsDidChange, DocumentDidClose, DocumentDidOpen, DocumentFocusLost},
     graphics::{CursorKind, Rect},
-    handlers::Handlers,
+    handlers::{lsp::PendingWorkspaceEdit, Handlers},
     info::Info,
     input::KeyEvent,
     register::Registers,
+    statusline::StatusLineFormat,
     theme::{self, Theme},
-    tree::{self, Tree},
+    tree::{self, LayoutNode, Tree},
+    view::JumpList,
     Document, DocumentId, View, ViewId,
 };
-use helix_event::dispatch;
+use helix_event::{dispatch, TaskController};
 use helix_vcs::DiffProviderRegistry;
 
 use futures_util::stream::select_all::SelectAll;
 use futures_util::{future, StreamExt};
-use helix_lsp::{Call, LanguageServerId};
+use helix_lsp::{Call, LanguageServerId, OffsetEncoding};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 use std::{
     borrow::Cow,
     cell::Cell,
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     fs,
     io::{self, stdin},
     num::{NonZeroU8, NonZeroUsize},
@@ -45,11 +48,12 @@
 use helix_core::{
     auto_pairs::AutoPairs,
     diagnostic::DiagnosticProvider,
+    line_ending::rope_is_line_ending,
     syntax::{
         self,
         config::{AutoPairConfig, IndentationHeuristic, LanguageServerFeature, SoftWrap},
     },
-    Change, LineEnding, Position, Range, Selection, Uri, NATIVE_LINE_ENDING,
+    Change, LineEnding, Position, Range, Selection, Transaction, Uri, NATIVE_LINE_ENDING,
 };
 use helix_dap::{self as dap, registry::DebugAdapterId};
 use helix_lsp::lsp;
@@ -298,6 +302,8 @@ pub struct Config {
     pub scroll_lines: isize,
     /// Mouse support. Defaults to true.
     pub mouse: bool,
+    /// Popups for the diagnostics and hover information under the mouse.
+    pub mouse_hover: MouseHoverConfig,
     /// Shell to use for shell commands. Defaults to ["cmd", "/C"] on Windows and ["sh", "-c"] otherwise.
     pub shell: Vec<String>,
     /// Line number mode.
@@ -361,6 +367,13 @@ pub struct Config {
     /// Whether to display infoboxes. Defaults to true.
     pub auto_info: bool,
     pub file_picker: FilePickerConfig,
+    /// A shell command such as `fzf --multi` used in place of the built-in file and buffer
+    /// pickers. Candidates are written to its stdin, one per line, and the lines it prints are
+    /// opened.
+    pub external_picker: Option<String>,
+    /// A shell command such as `w3m` run interactively in the terminal with the URL opened by
+    /// `open_external_docs` as its last argument, in place of the browser.
+    pub documentation_pager: Option<String>,
     pub file_explorer: FileExplorerConfig,
     /// Configuration of the statusline elements
     pub statusline: StatusLineConfig,
@@ -375,16 +388,57 @@ pub struct Config {
     pub search: SearchConfig,
     pub lsp: LspConfig,
     pub terminal: Option<TerminalConfig>,
+    /// Commands run by `:make`.
+    pub make: MakeConfig,
+    /// Named full-screen programs (e.g. `lazygit`) run by `:tool`.
+    pub tools: BTreeMap<String, String>,
+    /// Custom typable commands, each running a sequence of commands written like key bindings.
+    pub commands: BTreeMap<String, Vec<String>>,
+    /// Textobjects selected by `mi` and `ma` followed by their key, by the name of their
+    /// captures in `textobjects.scm` queries, like `prop` for `@prop.inside` and `@prop.around`.
+    pub textobjects: BTreeMap<char, String>,
+    /// Commands run when editor events happen, such as writing a file.
+    pub hooks: Vec<HookConfig>,
+    /// How programs are run interactively in the terminal (`:sh!`, `:tool`, …).
+    pub interactive: InteractiveConfig,
     /// Column numbers at which to draw the rulers. Defaults to `[]`, meaning no rulers.
     pub rulers: Vec<u16>,
     #[serde(default)]
     pub whitespace: WhitespaceConfig,
     /// Persistently display open buffers along the top
     pub bufferline: BufferLine,
+    /// How buffers without a group set with `:buffer-group` are grouped in the bufferline.
+    pub bufferline_group_by: BufferLineGroupBy,
     /// Vertical indent width guides.
     pub indent_guides: IndentGuidesConfig,
+    /// Condensed overview of the document beside the text.
+    pub minimap: MinimapConfig,
+    /// Whether to render a scrollbar on the right of document views. Defaults to `false`.
+    pub scrollbar: bool,
+    /// Animation of large jumps of the viewport.
+    pub smooth_scroll: SmoothScrollConfig,
+    /// Headers of the scopes enclosing the top of the viewport.
+    pub sticky_context: StickyContextConfig,
+    /// Checking the spelling of comments, strings and prose.
+    pub spell_check: SpellCheckConfig,
     /// Whether to color modes with different colors. Defaults to `false`.
     pub color_modes: bool,
+    /// The dashboard shown when starting without files.
+    pub dashboard: DashboardConfig,
+    /// The view shown alone by `toggle_zen_mode`.
+    pub zen_mode: ZenModeConfig,
+    /// Saving the history of documents to undo changes in later sessions.
+    pub persistent_undo: PersistentUndoConfig,
+    /// Saving registers and prompt histories to restore them in later sessions.
+    pub persistent_registers: PersistentRegistersConfig,
+    /// Snapshots of unsaved documents to recover them after a crash.
+    pub recovery: RecoveryConfig,
+    /// Reloading documents when their files are changed by other programs.
+    pub auto_reload: AutoReloadConfig,
+    /// Writing files with elevated privileges by `:write --sudo`.
+    pub privileged_write: PrivilegedWriteConfig,
+    /// Opening files over a size with expensive features disabled.
+    pub large_file: LargeFileConfig,
     pub soft_wrap: SoftWrap,
     /// Workspace specific lsp ceiling dirs
     pub workspace_lsp_roots: Vec<PathBuf>,
@@ -418,17 +472,78 @@ pub struct Config {
     /// Display diagnostic below the line they occur.
     pub inline_diagnostics: InlineDiagnosticsConfig,
     pub end_of_line_diagnostics: DiagnosticFilter,
+    /// Diagnostics which are neither displayed nor counted.
+    pub hidden_diagnostics: Vec<HiddenDiagnostic>,
     // Set to override the default clipboard provider
     pub clipboard_provider: ClipboardProvider,
     /// Whether to read settings from [EditorConfig](https://editorconfig.org) files. Defaults to
     /// `true`.
     pub editor_config: bool,
+    /// Whether to read indentation, language and wrapping settings from vim, emacs or `hx:`
+    /// modelines in the first and last lines of files. Defaults to `true`.
+    pub modeline: bool,
+    /// Whether to save the jumplist of the focused view when exiting and restore it when starting
+    /// in the same workspace. Defaults to `false`.
+    pub persistent_jumplist: bool,
+    /// Number of yanks and deletes kept in the history of `paste_from_history`. Defaults to 30.
+    pub yank_history: usize,
+    /// Groups of words which `increment` and `decrement` cycle through, such as `true` and
+    /// `false`. Defaults to booleans, weekdays and months.
+    pub word_cycles: Vec<Vec<String>>,
     /// Whether to render rainbow colors for matching brackets. Defaults to `false`.
     pub rainbow_brackets: bool,
     /// Whether to enable Kitty Keyboard Protocol
     pub kitty_keyboard_protocol: KittyKeyboardProtocolConfig,
 }
 
+impl Config {
+    /// Whether the diagnostic matches one of the `hidden-diagnostics` rules.
+    pub fn hides_diagnostic(&self, diagnostic: &lsp::Diagnostic) -> bool {
+        self.hidden_diagnostics
+            .iter()
+            .any(|rule| rule.matches(diagnostic))
+    }
+}
+
+/// A rule hiding the diagnostics which match all of its fields.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct HiddenDiagnostic {
+    /// The server or tool reporting the diagnostic, like `rust-analyzer`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Hides the diagnostics up to this severity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+}
+
+impl HiddenDiagnostic {
+    pub fn matches(&self, diagnostic: &lsp::Diagnostic) -> bool {
+        let severity = match diagnostic.severity {
+            Some(lsp::DiagnosticSeverity::ERROR) => Severity::Error,
+            Some(lsp::DiagnosticSeverity::WARNING) => Severity::Warning,
+            Some(lsp::DiagnosticSeverity::INFORMATION) => Severity::Info,
+            // Diagnostics without a severity are treated as hints.
+            _ => Severity::Hint,
+        };
+        let code = diagnostic.code.as_ref().map(|code| match code {
+            lsp::NumberOrString::Number(code) => Cow::Owned(code.to_string()),
+            lsp::NumberOrString::String(code) => Cow::Borrowed(code.as_str()),
+        });
+
+        self.source
+            .as_ref()
+            .is_none_or(|source| diagnostic.source.as_ref() == Some(source))
+            && self
+                .code
+                .as_deref()
+                .is_none_or(|hidden| code.as_deref() == Some(hidden))
+            && self.severity.is_none_or(|hidden| severity <= hidden)
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, Clone, Copy)]
 #[serde(rename_all = "kebab-case")]
 pub enum KittyKeyboardProtocolConfig {
@@ -463,6 +578,94 @@ pub struct TerminalConfig {
     pub args: Vec<String>,
 }
 
+/// The editor events which run hooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    /// A buffer was opened.
+    Open,
+    /// A buffer was written to its file.
+    Write,
+    /// A buffer was closed.
+    Close,
+    /// The mode changed.
+    ModeChange,
+    /// The focus moved away from a buffer.
+    FocusChange,
+    /// A program run interactively in the terminal, such as by `:sh!` or `:tool`, exited.
+    TerminalExit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct HookConfig {
+    pub event: HookEvent,
+    /// Glob patterns of the files of `open`, `write`, `close` and `focus-change` hooks. Hooks
+    /// without patterns run for every buffer.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    /// The mode switched to by `mode-change` hooks. Hooks without a mode run for every change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<Mode>,
+    /// The commands run in the focused view, written like key bindings.
+    pub commands: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct MakeConfig {
+    /// Shell command run by `:make` without arguments. Defaults to `make`.
+    pub command: String,
+    /// Named shell commands run by `:make <task>`.
+    pub tasks: BTreeMap<String, String>,
+}
+
+impl Default for MakeConfig {
+    fn default() -> Self {
+        Self {
+            command: "make".to_string(),
+            tasks: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct InteractiveConfig {
+    /// What to do when the program exits. Defaults to `keep-on-error`.
+    pub on_exit: InteractiveOnExit,
+    /// Extra environment variables for the program.
+    pub env: BTreeMap<String, String>,
+    /// Working directory of the program, relative to the workspace root. Defaults to the
+    /// current working directory.
+    pub cwd: Option<PathBuf>,
+    /// Shell command printing a JSON object of environment variables for the program, such as
+    /// `direnv export json`. It runs in the program's working directory.
+    pub env_hook: Option<String>,
+    /// Named interactive shells started by `:shell-profile` and the shell profile picker.
+    pub profiles: BTreeMap<String, ShellProfile>,
+    /// The profile `:shell-profile` starts when no name is given.
+    pub default_profile: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ShellProfile {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Environment variables set on top of `editor.interactive.env`.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+fn default_tools() -> BTreeMap<String, String> {
+    ["gitui", "lazygit", "tig"]
+        .into_iter()
+        .map(|tool| (tool.to_string(), tool.to_string()))
+        .collect()
+}
+
 #[cfg(windows)]
 pub fn get_terminal_provider() -> Option<TerminalConfig> {
     use helix_stdx::env::binary_exists;
@@ -527,10 +730,48 @@ pub struct LspConfig {
     pub inlay_hints_length_limit: Option<NonZeroU8>,
     /// Display document color swatches
     pub display_color_swatches: bool,
+    /// Highlight the occurrences of the symbol under the cursor once the cursor rests
+    pub auto_document_highlight: bool,
+    /// How long the cursor must rest before its symbol is highlighted, in milliseconds.
+    pub document_highlight_delay: u64,
     /// Whether to enable snippet support
     pub snippets: bool,
     /// Whether to include declaration in the goto reference query
     pub goto_reference_include_declaration: bool,
+    /// How long to wait for the code actions of all language servers when writing a document
+    /// with `code-actions-on-save`, in milliseconds.
+    pub code_actions_on_save_timeout: u64,
+    /// The sources of workspace edits which are reviewed before they are applied.
+    pub review_workspace_edits: Vec<WorkspaceEditSource>,
+    /// How the semantic tokens of language servers highlight documents.
+    pub semantic_tokens: SemanticTokensMode,
+}
+
+/// How semantic tokens are layered with the tree-sitter highlights.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SemanticTokensMode {
+    /// Semantic tokens are not requested.
+    #[default]
+    Off,
+    /// Semantic tokens take precedence over the tree-sitter highlights.
+    Override,
+    /// Semantic tokens only highlight the text which tree-sitter doesn't.
+    Fallback,
+}
+
+/// What produced a workspace edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WorkspaceEditSource {
+    /// Renaming a symbol.
+    Rename,
+    /// Applying a code action.
+    CodeAction,
+    /// Moving or renaming a file, like updating its imports.
+    WillRename,
+    /// A language server, like after running one of its commands.
+    Server,
 }
 
 impl Default for LspConfig {
@@ -546,6 +787,11 @@ fn default() -> Self {
             snippets: true,
             goto_reference_include_declaration: true,
             display_color_swatches: true,
+            auto_document_highlight: false,
+            document_highlight_delay: 250,
+            code_actions_on_save_timeout: 2000,
+            review_workspace_edits: Vec::new(),
+            semantic_tokens: SemanticTokensMode::default(),
         }
     }
 }
@@ -562,9 +808,9 @@ pub struct SearchConfig {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct StatusLineConfig {
-    pub left: Vec<StatusLineElement>,
-    pub center: Vec<StatusLineElement>,
-    pub right: Vec<StatusLineElement>,
+    pub left: StatusLineSection,
+    pub center: StatusLineSection,
+    pub right: StatusLineSection,
     pub separator: String,
     pub mode: ModeConfig,
     pub diagnostics: Vec<Severity>,
@@ -576,21 +822,21 @@ fn default() -> Self {
         use StatusLineElement as E;
 
         Self {
-            left: vec![
+            left: StatusLineSection::Elements(vec![
                 E::Mode,
                 E::Spinner,
                 E::FileName,
                 E::ReadOnlyIndicator,
                 E::FileModificationIndicator,
-            ],
-            center: vec![],
-            right: vec![
+            ]),
+            center: StatusLineSection::Elements(vec![]),
+            right: StatusLineSection::Elements(vec![
                 E::Diagnostics,
                 E::Selections,
                 E::Register,
                 E::Position,
                 E::FileEncoding,
-            ],
+            ]),
             separator: String::from("│"),
             mode: ModeConfig::default(),
             diagnostics: vec![Severity::Warning, Severity::Error],
@@ -599,6 +845,51 @@ fn default() -> Self {
     }
 }
 
+/// The contents of an area of the statusline: either a list of elements or a format string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum StatusLineSection {
+    Elements(Vec<StatusLineElement>),
+    Format(StatusLineFormat),
+}
+
+impl<'de> Deserialize<'de> for StatusLineSection {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SectionVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for SectionVisitor {
+            type Value = StatusLineSection;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a list of statusline elements or a format string")
+            }
+
+            fn visit_str<E>(self, format: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                format
+                    .parse()
+                    .map(StatusLineSection::Format)
+                    .map_err(E::custom)
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let deserializer = serde::de::value::SeqAccessDeserializer::new(seq);
+                Vec::deserialize(deserializer).map(StatusLineSection::Elements)
+            }
+        }
+
+        deserializer.deserialize_any(SectionVisitor)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct ModeConfig {
@@ -626,6 +917,9 @@ pub enum StatusLineElement {
     /// The LSP activity spinner
     Spinner,
 
+    /// The work done progress reported by the language servers of the document
+    LspProgress,
+
     /// The file basename (the leaf of the open file's path)
     FileBaseName,
 
@@ -688,6 +982,12 @@ pub enum StatusLineElement {
 
     /// The base of current working directory
     CurrentWorkingDirectory,
+
+    /// The register a macro is being recorded into
+    MacroRecording,
+
+    /// The names of the language servers of the document
+    LanguageServers,
 }
 
 // Cursor shape is read and used on every rendered frame and so needs
@@ -757,6 +1057,30 @@ pub enum BufferLine {
     Multiple,
 }
 
+/// bufferline grouping modes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BufferLineGroupBy {
+    /// Only group buffers with a group set manually
+    #[default]
+    None,
+    /// Group buffers by the directory of their file
+    Directory,
+}
+
+/// Whether the output of an interactive program is kept on screen once it exits
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InteractiveOnExit {
+    /// Return to the editor immediately
+    Close,
+    /// Wait for a key press before returning to the editor
+    Keep,
+    /// Wait for a key press only if the program failed
+    #[default]
+    KeepOnError,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum LineNumber {
@@ -791,6 +1115,8 @@ pub enum GutterType {
     Spacer,
     /// Highlight local changes
     Diff,
+    /// Mark the first lines of closed folds
+    Folds,
 }
 
 impl std::str::FromStr for GutterType {
@@ -802,8 +1128,9 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             "spacer" => Ok(Self::Spacer),
             "line-numbers" => Ok(Self::LineNumbers),
             "diff" => Ok(Self::Diff),
+            "folds" => Ok(Self::Folds),
             _ => anyhow::bail!(
-                "Gutter type can only be `diagnostics`, `spacer`, `line-numbers` or `diff`."
+                "Gutter type can only be `diagnostics`, `spacer`, `line-numbers`, `diff` or `folds`."
             ),
         }
     }
@@ -900,6 +1227,9 @@ pub struct AutoSave {
     /// Auto save on focus lost. Defaults to false.
     #[serde(default)]
     pub focus_lost: bool,
+    /// Format documents with `auto-format` enabled when auto saving them. Defaults to false.
+    #[serde(default)]
+    pub format: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -976,6 +1306,8 @@ pub struct IndentGuidesConfig {
     pub render: bool,
     pub character: char,
     pub skip_levels: u8,
+    pub highlight_scope: bool,
+    pub rainbow: bool,
 }
 
 impl Default for IndentGuidesConfig {
@@ -984,6 +1316,311 @@ fn default() -> Self {
             skip_levels: 0,
             render: false,
             character: '│',
+            highlight_scope: false,
+            rainbow: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct MouseHoverConfig {
+    /// Whether to show a popup for the position the mouse rests over. Defaults to `false`.
+    pub enable: bool,
+    /// Time in milliseconds the mouse has to rest before the popup is shown. Defaults to 500ms.
+    #[serde(
+        serialize_with = "serialize_duration_millis",
+        deserialize_with = "deserialize_duration_millis"
+    )]
+    pub delay: Duration,
+    /// Whether to request hover information from the language servers. Defaults to `true`.
+    pub lsp: bool,
+}
+
+impl Default for MouseHoverConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            delay: Duration::from_millis(500),
+            lsp: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct SmoothScrollConfig {
+    /// Whether to animate jumps of the viewport. Defaults to `false`.
+    pub enable: bool,
+    /// Time in milliseconds an animation takes. Defaults to 150ms.
+    #[serde(
+        serialize_with = "serialize_duration_millis",
+        deserialize_with = "deserialize_duration_millis"
+    )]
+    pub duration: Duration,
+}
+
+impl Default for SmoothScrollConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            duration: Duration::from_millis(150),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DashboardConfig {
+    /// Whether to show the dashboard when starting without files. Defaults to `false`.
+    pub enable: bool,
+    /// Number of recently opened files to list. Defaults to 10.
+    pub recent_files: usize,
+    /// Number of recently opened workspaces to list. Defaults to 5.
+    pub recent_workspaces: usize,
+    /// Commands run by a key press on the dashboard.
+    pub actions: Vec<DashboardAction>,
+}
+
+impl Default for DashboardConfig {
+    fn default() -> Self {
+        let action = |key: &str, label: &str, command: &str| DashboardAction {
+            key: key.parse().unwrap(),
+            label: label.to_owned(),
+            command: command.to_owned(),
+        };
+        Self {
+            enable: false,
+            recent_files: 10,
+            recent_workspaces: 5,
+            actions: vec![
+                action("f", "Open file picker", "file_picker"),
+                action("n", "New file", ":new"),
+                action("l", "Restore layout", ":layout-load"),
+                action("t", "Open terminal", ":run-shell-command! $SHELL"),
+                action("q", "Quit", ":quit"),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DashboardAction {
+    pub key: KeyEvent,
+    pub label: String,
+    /// A static command such as `file_picker` or a typable command such as `:open foo`.
+    pub command: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct AutoReloadConfig {
+    /// Whether to watch the files of open documents. Defaults to `false`.
+    pub enable: bool,
+    /// Time in milliseconds between checks of the files. Defaults to 1000.
+    pub interval: u64,
+    /// Whether to reload the theme when its files change. Defaults to `true`.
+    pub theme: bool,
+}
+
+impl Default for AutoReloadConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            interval: 1000,
+            theme: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PrivilegedWriteConfig {
+    /// The command and its arguments which write their standard input to the file given as the
+    /// last argument. Defaults to `["sudo", "-k", "-S", "-p", "", "tee", "--"]`.
+    pub command: Vec<String>,
+    /// Whether the command reads a password from the first line of its standard input, which
+    /// makes the editor prompt for it. Defaults to `true`.
+    pub password: bool,
+}
+
+impl Default for PrivilegedWriteConfig {
+    fn default() -> Self {
+        Self {
+            command: ["sudo", "-k", "-S", "-p", "", "tee", "--"]
+                .map(String::from)
+                .to_vec(),
+            password: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct LargeFileConfig {
+    /// Whether to open large files with syntax highlighting, language servers, soft wrap and
+    /// other features which scale with the size of the file disabled. Defaults to `true`.
+    pub enable: bool,
+    /// Size in bytes from which a file is large. Defaults to 50 MiB.
+    pub size: u64,
+}
+
+impl Default for LargeFileConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            size: 50 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RecoveryConfig {
+    /// Whether to write snapshots of modified documents. Defaults to `false`.
+    pub enable: bool,
+    /// Time in milliseconds from a change until the snapshots are written. Defaults to 5000.
+    pub interval: u64,
+}
+
+impl Default for RecoveryConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            interval: 5000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PersistentUndoConfig {
+    /// Whether to save the history of documents when writing them. Defaults to `false`.
+    pub enable: bool,
+    /// Number of revisions to save for each document. Defaults to 1000.
+    pub max_revisions: usize,
+    /// Number of days after which the history of a document is discarded. Defaults to 30.
+    pub max_age: u64,
+}
+
+impl Default for PersistentUndoConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            max_revisions: 1000,
+            max_age: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PersistentRegistersConfig {
+    /// Whether to save the search register and the prompt histories when exiting and restore
+    /// them at startup. Defaults to `false`.
+    pub enable: bool,
+    /// Number of values saved for each register, the newest are kept. Defaults to 100.
+    pub max_values: usize,
+    /// Size in bytes over which values are not saved. Defaults to 10000.
+    pub max_value_size: usize,
+    /// Registers saved in addition to the search register and the prompt histories, such as
+    /// named registers. Defaults to none.
+    pub registers: Vec<char>,
+}
+
+impl Default for PersistentRegistersConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            max_values: 100,
+            max_value_size: 10_000,
+            registers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ZenModeConfig {
+    /// Width of the centered view. Defaults to 100.
+    pub width: u16,
+}
+
+impl Default for ZenModeConfig {
+    fn default() -> Self {
+        Self { width: 100 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct MinimapConfig {
+    /// Whether to render an overview of the document beside the text. Defaults to `false`.
+    pub enable: bool,
+    /// Width of the minimap in columns. Each column shows 4 characters of the text.
+    pub width: u16,
+}
+
+impl Default for MinimapConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            width: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct StickyContextConfig {
+    /// Whether to pin the first lines of the functions and classes enclosing the top of the
+    /// viewport above the text. Defaults to `false`.
+    pub enable: bool,
+    /// The number of innermost enclosing scopes shown. Defaults to `3`.
+    pub max_depth: usize,
+}
+
+impl Default for StickyContextConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            max_depth: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct SpellCheckConfig {
+    /// Whether to underline misspelled words. Defaults to `false`.
+    pub enable: bool,
+    /// The names of the hunspell dictionaries, like `en_US`. Defaults to `["en_US"]`.
+    pub dictionaries: Vec<String>,
+    /// The languages whose whole text is checked rather than only comments and strings, except
+    /// for code. Documents without a language are always checked as prose.
+    pub prose_languages: Vec<String>,
+}
+
+impl Default for SpellCheckConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            dictionaries: vec!["en_US".to_owned()],
+            prose_languages: [
+                "markdown",
+                "git-commit",
+                "jjdescription",
+                "mail",
+                "latex",
+                "typst",
+                "rst",
+                "org",
+                "djot",
+                "markdoc",
+            ]
+            .map(String::from)
+            .to_vec(),
         }
     }
 }
@@ -1053,12 +1690,53 @@ fn default() -> Self {
     }
 }
 
+fn default_word_cycles() -> Vec<Vec<String>> {
+    [
+        &["true", "false"][..],
+        &["yes", "no"],
+        &["on", "off"],
+        &["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+        &[
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ],
+        // "May" is in both cycles of months and the first cycle containing a word is used, so
+        // "May" is followed by "June".
+        &[
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ],
+        &[
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ],
+    ]
+    .iter()
+    .map(|cycle| cycle.iter().map(|word| word.to_string()).collect())
+    .collect()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             scrolloff: 5,
             scroll_lines: 3,
             mouse: true,
+            mouse_hover: MouseHoverConfig::default(),
             shell: if cfg!(windows) {
                 vec!["cmd".to_owned(), "/C".to_owned()]
             } else {
@@ -1082,6 +1760,8 @@ fn default() -> Self {
             completion_trigger_len: 2,
             auto_info: true,
             file_picker: FilePickerConfig::default(),
+            external_picker: None,
+            documentation_pager: None,
             file_explorer: FileExplorerConfig::default(),
             statusline: StatusLineConfig::default(),
             cursor_shape: CursorShapeConfig::default(),
@@ -1090,11 +1770,31 @@ fn default() -> Self {
             search: SearchConfig::default(),
             lsp: LspConfig::default(),
             terminal: get_terminal_provider(),
+            make: MakeConfig::default(),
+            tools: default_tools(),
+            commands: BTreeMap::new(),
+            textobjects: BTreeMap::new(),
+            hooks: Vec::new(),
+            interactive: InteractiveConfig::default(),
             rulers: Vec::new(),
             whitespace: WhitespaceConfig::default(),
             bufferline: BufferLine::default(),
+            bufferline_group_by: BufferLineGroupBy::default(),
             indent_guides: IndentGuidesConfig::default(),
+            minimap: MinimapConfig::default(),
+            scrollbar: false,
+            smooth_scroll: SmoothScrollConfig::default(),
+            sticky_context: StickyContextConfig::default(),
+            spell_check: SpellCheckConfig::default(),
             color_modes: false,
+            dashboard: DashboardConfig::default(),
+            zen_mode: ZenModeConfig::default(),
+            persistent_undo: PersistentUndoConfig::default(),
+            persistent_registers: PersistentRegistersConfig::default(),
+            recovery: RecoveryConfig::default(),
+            auto_reload: AutoReloadConfig::default(),
+            privileged_write: PrivilegedWriteConfig::default(),
+            large_file: LargeFileConfig::default(),
             soft_wrap: SoftWrap {
                 enable: Some(false),
                 ..SoftWrap::default()
@@ -1114,8 +1814,13 @@ fn default() -> Self {
             jump_label_alphabet: ('a'..='z').collect(),
             inline_diagnostics: InlineDiagnosticsConfig::default(),
             end_of_line_diagnostics: DiagnosticFilter::Enable(Severity::Hint),
+            hidden_diagnostics: Vec::new(),
             clipboard_provider: ClipboardProvider::default(),
             editor_config: true,
+            modeline: true,
+            persistent_jumplist: false,
+            yank_history: 30,
+            word_cycles: default_word_cycles(),
             rainbow_brackets: false,
             kitty_keyboard_protocol: Default::default(),
         }
@@ -1144,6 +1849,38 @@ pub struct Breakpoint {
     pub log_message: Option<String>,
 }
 
+/// A rectangular selection being extended in select mode, see `select_block`.
+#[derive(Debug, Clone, Copy)]
+pub struct BlockSelection {
+    pub view_id: ViewId,
+    /// The version of the document, the block ends when the document changes.
+    pub version: i32,
+    /// The visual coordinates of the corners of the block.
+    pub anchor: Position,
+    pub head: Position,
+}
+
+/// The name of the output buffer showing the syntax tree of the source of the query playground.
+pub const SYNTAX_TREE_OUTPUT_NAME: &str = "[syntax tree]";
+
+/// The buffers of `:tree-sitter-playground`: the query written in the `query` buffer runs on the
+/// syntax tree of the `source` document, whose captures are highlighted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryPlayground {
+    pub source: DocumentId,
+    pub query: DocumentId,
+}
+
+/// A macro under replay, see [Editor::macro_replaying]. A macro isn't replayed again while it is
+/// already replaying so that a macro which replays itself doesn't recurse forever.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayedMacro {
+    /// A macro in the register.
+    Register(char),
+    /// A macro saved with `:macro-save` under the name.
+    Saved(String),
+}
+
 use futures_util::stream::{Flatten, Once};
 
 type Diagnostics = BTreeMap<Uri, Vec<(lsp::Diagnostic, DiagnosticProvider)>>;
@@ -1165,13 +1902,33 @@ pub struct Editor {
     pub selected_register: Option<char>,
     pub registers: Registers,
     pub macro_recording: Option<(char, Vec<KeyEvent>)>,
-    pub macro_replaying: Vec<char>,
+    pub macro_replaying: Vec<ReplayedMacro>,
+    /// The order of the buffers set with [Editor::move_buffer], see [Editor::buffer_order].
+    buffer_order: Vec<DocumentId>,
+    /// Buffers listed first in the bufferline.
+    pub pinned_buffers: HashSet<DocumentId>,
+    /// Groups of the bufferline set for buffers.
+    pub buffer_groups: HashMap<DocumentId, String>,
+    /// Whether only the focused view is shown, without gutters, statusline and bufferline.
+    zen_mode: bool,
     pub language_servers: helix_lsp::Registry,
+    /// The work done progress reported by the language servers.
+    pub lsp_progress: helix_lsp::LspProgressMap,
+    /// The workspace edits waiting to be reviewed, see `LspConfig::review_workspace_edits`.
+    pub pending_workspace_edits: VecDeque<PendingWorkspaceEdit>,
     pub diagnostics: Diagnostics,
+    /// The result ids of the last diagnostics pulled from each language server by document, which
+    /// are sent back so that the server only reports the diagnostics that changed.
+    pub diagnostic_result_ids: HashMap<LanguageServerId, BTreeMap<Uri, String>>,
+    /// The workspace diagnostic requests of the language servers which report the diagnostics of
+    /// the whole workspace on demand.
+    pub workspace_diagnostic_controllers: HashMap<LanguageServerId, TaskController>,
     pub diff_providers: DiffProviderRegistry,
 
     pub debug_adapters: dap::registry::Registry,
     pub breakpoints: HashMap<PathBuf, Vec<Breakpoint>>,
+    /// Read-only scratch buffers collecting the output of debug adapters, tasks, … by name.
+    pub output_buffers: HashMap<String, DocumentId>,
 
     pub syn_loader: Arc<ArcSwap<syntax::Loader>>,
     pub theme_loader: Arc<theme::Loader>,
@@ -1186,6 +1943,13 @@ pub struct Editor {
     /// restored when the preview is aborted, or added to the jumplist when it is
     /// confirmed.
     pub last_selection: Option<Selection>,
+    /// The jumplist of the last view which was closed while focused, so that it can still be
+    /// saved after the last view is closed.
+    pub closed_jumps: Option<JumpList>,
+    /// The rectangular selection being extended in select mode, if any.
+    pub block_selection: Option<BlockSelection>,
+    /// The buffers of `:tree-sitter-playground`, if it's open.
+    pub query_playground: Option<QueryPlayground>,
 
     pub status_msg: Option<(Cow<'static, str>, Severity)>,
     pub autoinfo: Option<Info>,
@@ -1311,16 +2075,28 @@ pub fn new(
             selected_register: None,
             macro_recording: None,
             macro_replaying: Vec::new(),
+            buffer_order: Vec::new(),
+            pinned_buffers: HashSet::new(),
+            buffer_groups: HashMap::new(),
+            zen_mode: false,
             theme: theme_loader.default(),
             language_servers,
+            lsp_progress: helix_lsp::LspProgressMap::new(),
+            pending_workspace_edits: VecDeque::new(),
             diagnostics: Diagnostics::new(),
+            diagnostic_result_ids: HashMap::new(),
+            workspace_diagnostic_controllers: HashMap::new(),
             diff_providers: DiffProviderRegistry::default(),
             debug_adapters: dap::registry::Registry::new(),
             breakpoints: HashMap::new(),
+            output_buffers: HashMap::new(),
             syn_loader,
             theme_loader,
             last_theme: None,
             last_selection: None,
+            closed_jumps: None,
+            block_selection: None,
+            query_playground: None,
             registers: Registers::new(Box::new(arc_swap::access::Map::new(
                 Arc::clone(&config),
                 |config: &Config| &config.clipboard_provider,
@@ -1381,6 +2157,16 @@ pub fn refresh_config(&mut self, old_config: &Config) {
         let config = self.config();
         self.auto_pairs = (&config.auto_pairs).into();
         self.reset_idle_timer();
+        if config.editor_config != old_config.editor_config {
+            self.refresh_editor_config(None);
+        }
+        if config.hidden_diagnostics != old_config.hidden_diagnostics {
+            for doc in self.documents.values_mut() {
+                let diagnostics =
+                    Editor::doc_diagnostics(&self.language_servers, &self.diagnostics, doc);
+                doc.replace_diagnostics(diagnostics, &[], None);
+            }
+        }
         self._refresh();
         helix_event::dispatch(crate::events::ConfigDidChange {
             editor: self,
@@ -1389,6 +2175,18 @@ pub fn refresh_config(&mut self, old_config: &Config) {
         })
     }
 
+    /// Reads the EditorConfig of the documents within `dir`, or of all documents, again and
+    /// applies their indentation and line ending.
+    pub fn refresh_editor_config(&mut self, dir: Option<&Path>) {
+        for doc in self.documents.values_mut() {
+            let within = |dir: &Path| doc.path().is_some_and(|path| path.starts_with(dir));
+            if dir.is_none_or(within) {
+                doc.detect_editor_config();
+                doc.detect_indent_and_line_ending();
+            }
+        }
+    }
+
     pub fn clear_idle_timer(&mut self) {
         // equivalent to internal Instant::far_future() (30 years)
         self.idle_timer
@@ -1523,9 +2321,17 @@ pub fn move_path(&mut self, old_path: &Path, new_path: &Path) -> io::Result<()>
                     continue;
                 }
             };
-            if let Err(err) = self.apply_workspace_edit(language_server.offset_encoding(), &edit) {
-                log::error!("failed to apply workspace edit: {err:?}")
-            }
+            self.submit_workspace_edit(PendingWorkspaceEdit {
+                source: WorkspaceEditSource::WillRename,
+                title: format!("Update the references to {}", old_path.display()),
+                offset_encoding: language_server.offset_encoding(),
+                edit,
+                on_done: Some(Box::new(|_, result| {
+                    if let Err(err) = result {
+                        log::error!("failed to apply workspace edit: {err:?}")
+                    }
+                })),
+            });
         }
 
         if old_path.exists() {
@@ -1603,6 +2409,9 @@ fn launch_language_servers(&mut self, doc_id: DocumentId) {
         let Some(doc_url) = doc.url() else {
             return;
         };
+        if doc.is_large_file() {
+            return;
+        }
         let (lang, path) = (doc.language.clone(), doc.path().cloned());
         let config = doc.config.load();
         let root_dirs = &config.workspace_lsp_roots;
@@ -1667,7 +2476,7 @@ fn launch_language_servers(&mut self, doc_id: DocumentId) {
             );
         }
 
-        doc.language_servers = language_servers;
+        doc.set_language_servers(language_servers);
     }
 
     fn _refresh(&mut self) {
@@ -1679,20 +2488,56 @@ fn _refresh(&mut self) {
         // We can't simply check this config when rendering because inlay hints are only parts of
         // the possible annotations, and others could still be active, so we need to selectively
         // drop the inlay hints.
-        if !config.lsp.display_inlay_hints {
-            for doc in self.documents_mut() {
+        for doc in self.documents_mut() {
+            if !doc.display_inlay_hints() {
                 doc.reset_all_inlay_hints();
             }
         }
 
+        if self.zen_mode {
+            self.tree.set_zoom(Some(config.zen_mode.width));
+        }
+        let gutters = self.gutter_config();
         for (view, _) in self.tree.views_mut() {
             let doc = doc_mut!(self, &view.doc);
             view.sync_changes(doc);
-            view.gutters = config.gutters.clone();
+            view.gutters = gutters.clone();
             view.ensure_cursor_in_view(doc, config.scrolloff)
         }
     }
 
+    /// The gutters of new views, which zen mode hides.
+    fn gutter_config(&self) -> GutterConfig {
+        let gutters = self.config().gutters.clone();
+        if self.zen_mode {
+            GutterConfig {
+                layout: Vec::new(),
+                ..gutters
+            }
+        } else {
+            gutters
+        }
+    }
+
+    pub fn zen_mode(&self) -> bool {
+        self.zen_mode
+    }
+
+    /// Shows only the focused view centered in the editor without gutters, statusline and
+    /// bufferline, or everything again.
+    pub fn set_zen_mode(&mut self, enable: bool) {
+        self.zen_mode = enable;
+        let width = enable.then_some(self.config().zen_mode.width);
+        self.tree.set_zoom(width);
+        let scrolloff = self.config().scrolloff;
+        let gutters = self.gutter_config();
+        for (view, _) in self.tree.views_mut() {
+            view.gutters = gutters.clone();
+            let doc = doc_mut!(self, &view.doc);
+            view.ensure_cursor_in_view(doc, scrolloff);
+        }
+    }
+
     fn replace_document_in_view(&mut self, current_view: ViewId, doc_id: DocumentId) {
         let scrolloff = self.config().scrolloff;
         let view = self.tree.get_mut(current_view);
@@ -1790,7 +2635,7 @@ pub fn switch(&mut self, id: DocumentId, action: Action) {
                     .try_get(self.tree.focus)
                     .filter(|v| id == v.doc) // Different Document
                     .cloned()
-                    .unwrap_or_else(|| View::new(id, self.config().gutters.clone()));
+                    .unwrap_or_else(|| View::new(id, self.gutter_config()));
                 let view_id = self.tree.split(
                     view,
                     match action {
@@ -1817,7 +2662,7 @@ pub fn switch(&mut self, id: DocumentId, action: Action) {
     }
 
     /// Generate an id for a new document and register it.
-    fn new_document(&mut self, mut doc: Document) -> DocumentId {
+    pub(crate) fn new_document(&mut self, mut doc: Document) -> DocumentId {
         let id = self.next_document_id;
         // Safety: adding 1 from 1 is fine, probably impossible to reach usize max
         self.next_document_id =
@@ -1873,19 +2718,49 @@ pub fn document_id_by_path(&self, path: &Path) -> Option<DocumentId> {
 
     // ??? possible use for integration tests
     pub fn open(&mut self, path: &Path, action: Action) -> Result<DocumentId, DocumentOpenError> {
+        self.open_impl(path, action, false)
+    }
+
+    /// Opens the file as a hex dump of its bytes, which is also how binary files are opened by
+    /// [`Editor::open`].
+    pub fn open_hex(
+        &mut self,
+        path: &Path,
+        action: Action,
+    ) -> Result<DocumentId, DocumentOpenError> {
+        self.open_impl(path, action, true)
+    }
+
+    fn open_impl(
+        &mut self,
+        path: &Path,
+        action: Action,
+        hex: bool,
+    ) -> Result<DocumentId, DocumentOpenError> {
         let path = helix_stdx::path::canonicalize(path);
         let id = self.document_id_by_path(&path);
 
         let id = if let Some(id) = id {
+            let doc = doc_mut!(self, &id);
+            if hex && !doc.is_hex() {
+                let view_id = self.get_synced_view_id(id);
+                let doc = doc_mut!(self, &id);
+                doc.reload_as_hex(view_mut!(self, view_id), &self.diff_providers)?;
+                self.refresh_language_servers(id);
+            }
             id
         } else {
-            let mut doc = Document::open(
-                &path,
-                None,
-                true,
-                self.config.clone(),
-                self.syn_loader.clone(),
-            )?;
+            let mut doc = if hex {
+                Document::open_hex(&path, self.config.clone(), self.syn_loader.clone())?
+            } else {
+                Document::open(
+                    &path,
+                    None,
+                    true,
+                    self.config.clone(),
+                    self.syn_loader.clone(),
+                )?
+            };
 
             let diagnostics =
                 Editor::doc_diagnostics(&self.language_servers, &self.diagnostics, &doc);
@@ -1896,6 +2771,20 @@ pub fn open(&mut self, path: &Path, action: Action) -> Result<DocumentId, Docume
             }
             doc.set_version_control_head(self.diff_providers.get_current_head_name(&path));
 
+            if doc.is_large_file() {
+                self.set_status(format!(
+                    "'{}' is a large file, syntax highlighting and language servers are disabled",
+                    doc.display_name()
+                ));
+            }
+            if doc.is_encoding_lossy() {
+                self.set_warning(format!(
+                    "'{}' has bytes which are invalid in {}, writing it replaces them",
+                    doc.display_name(),
+                    doc.encoding().name()
+                ));
+            }
+
             let id = self.new_document(doc);
             self.launch_language_servers(id);
 
@@ -1912,7 +2801,41 @@ pub fn open(&mut self, path: &Path, action: Action) -> Result<DocumentId, Docume
         Ok(id)
     }
 
+    /// Opens the files of the saved jumps of the view which are needed to jump back `count`
+    /// times and moves the jumps into its jumplist. Jumps into files which can't be opened are
+    /// dropped.
+    pub fn open_saved_jumps(&mut self, view_id: ViewId, count: usize) {
+        let saved = self.tree.get_mut(view_id).jumps.take_saved(count);
+        if saved.is_empty() {
+            return;
+        }
+        let jumps = saved
+            .into_iter()
+            .filter_map(|jump| {
+                let doc_id = self.open(&jump.path, Action::Load).ok()?;
+                let text = self.document(doc_id)?.text().slice(..);
+                // The file may have been changed by other programs since.
+                let len = text.len_chars();
+                let ranges = jump
+                    .selection
+                    .ranges()
+                    .iter()
+                    .map(|range| Range::new(range.anchor.min(len), range.head.min(len)))
+                    .collect();
+                let selection =
+                    Selection::new(ranges, jump.selection.primary_index()).ensure_invariants(text);
+                Some((doc_id, selection))
+            })
+            .collect();
+        self.tree.get_mut(view_id).jumps.prepend(jumps);
+    }
+
     pub fn close(&mut self, id: ViewId) {
+        if id == self.tree.focus {
+            if let Some(view) = self.tree.try_get(id) {
+                self.closed_jumps = Some(view.jumps.clone());
+            }
+        }
         // Remove selections for the closed view on all documents.
         for doc in self.documents_mut() {
             doc.remove_view(id);
@@ -1921,6 +2844,141 @@ pub fn close(&mut self, id: ViewId) {
         self._refresh();
     }
 
+    /// Describes the arrangement of the views and the documents they show.
+    pub fn window_layout(&self) -> LayoutNode {
+        self.tree
+            .layout_node(|view| self.documents[&view.doc].path().cloned())
+    }
+
+    /// Replaces all views with the arrangement described by `layout`. Returns the paths which
+    /// could not be opened: their views keep the document of the view they were split from.
+    pub fn apply_window_layout(
+        &mut self,
+        layout: &LayoutNode,
+    ) -> Vec<(PathBuf, DocumentOpenError)> {
+        let focus = self.tree.focus;
+        let others: Vec<_> = self
+            .tree
+            .views()
+            .map(|(view, _)| view.id)
+            .filter(|&id| id != focus)
+            .collect();
+        for id in others {
+            self.close(id);
+        }
+
+        let mut focused = focus;
+        let mut errors = Vec::new();
+        self.build_window_layout(layout, focus, &mut focused, &mut errors);
+        self.focus(focused);
+        errors
+    }
+
+    fn build_window_layout(
+        &mut self,
+        layout: &LayoutNode,
+        view_id: ViewId,
+        focused: &mut ViewId,
+        errors: &mut Vec<(PathBuf, DocumentOpenError)>,
+    ) {
+        self.tree.focus = view_id;
+        match layout {
+            LayoutNode::View {
+                path,
+                focused: is_focused,
+            } => {
+                if let Some(path) = path {
+                    if let Err(err) = self.open(path, Action::Replace) {
+                        errors.push((path.clone(), err));
+                    }
+                }
+                if *is_focused {
+                    *focused = view_id;
+                }
+            }
+            LayoutNode::Split {
+                layout,
+                children,
+                weights,
+            } => {
+                let action = match layout {
+                    tree::Layout::Horizontal => Action::HorizontalSplit,
+                    tree::Layout::Vertical => Action::VerticalSplit,
+                };
+                // Split off a view per child before building any of them: building a child
+                // may nest its view in a new container and splits from there would land in it.
+                let mut view_ids = vec![view_id];
+                for _ in 1..children.len() {
+                    self.switch(view!(self).doc, action);
+                    view_ids.push(self.tree.focus);
+                }
+                self.tree.set_split_weights(view_id, weights);
+                for (child, view_id) in children.iter().zip(view_ids) {
+                    self.build_window_layout(child, view_id, focused, errors);
+                }
+            }
+        }
+    }
+
+    /// The group of the buffer in the bufferline: the one set in [Editor::buffer_groups] or,
+    /// when grouping by directory, the directory of its file.
+    pub fn buffer_group(&self, doc: &Document) -> Option<String> {
+        if let Some(group) = self.buffer_groups.get(&doc.id()) {
+            return Some(group.clone());
+        }
+        match self.config().bufferline_group_by {
+            BufferLineGroupBy::None => None,
+            BufferLineGroupBy::Directory => {
+                let path = doc.relative_path()?;
+                let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty())?;
+                Some(dir.to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    /// The documents in the order of the bufferline and of `buffer-next`. Pinned buffers come
+    /// first, the others are grouped with the groups ordered by their first buffer. Within those
+    /// the buffers keep the order set with [Editor::move_buffer] or else the one they were opened
+    /// in.
+    pub fn buffer_order(&self) -> Vec<DocumentId> {
+        let mut order: Vec<_> = self
+            .buffer_order
+            .iter()
+            .copied()
+            .filter(|id| self.documents.contains_key(id))
+            .collect();
+        order.extend(
+            self.documents
+                .keys()
+                .filter(|id| !self.buffer_order.contains(id)),
+        );
+
+        let groups: Vec<_> = order
+            .iter()
+            .map(|id| self.buffer_group(&self.documents[id]))
+            .collect();
+        let mut sorted: Vec<_> = order.iter().copied().zip(&groups).collect();
+        sorted.sort_by_key(|(id, group)| {
+            let pinned = self.pinned_buffers.contains(id);
+            let group = groups.iter().position(|other| &other == group);
+            (!pinned, if pinned { None } else { group })
+        });
+        sorted.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Moves the buffer by `offset` places in the [Editor::buffer_order]. It stays next to the
+    /// other buffers of its group, after the pinned buffers.
+    pub fn move_buffer(&mut self, doc_id: DocumentId, offset: isize) {
+        let mut order = self.buffer_order();
+        let Some(pos) = order.iter().position(|&id| id == doc_id) else {
+            return;
+        };
+        let new_pos = pos.saturating_add_signed(offset).min(order.len() - 1);
+        order.remove(pos);
+        order.insert(new_pos, doc_id);
+        self.buffer_order = order;
+    }
+
     pub fn close_document(&mut self, doc_id: DocumentId, force: bool) -> Result<(), CloseError> {
         let doc = match self.documents.get(&doc_id) {
             Some(doc) => doc,
@@ -1932,6 +2990,9 @@ pub fn close_document(&mut self, doc_id: DocumentId, force: bool) -> Result<(),
 
         // This will also disallow any follow-up writes
         self.saves.remove(&doc_id);
+        self.buffer_order.retain(|&id| id != doc_id);
+        self.pinned_buffers.remove(&doc_id);
+        self.buffer_groups.remove(&doc_id);
 
         enum Action {
             Close(ViewId),
@@ -1986,7 +3047,7 @@ enum Action {
                         self.syn_loader.clone(),
                     ))
                 });
-            let view = View::new(doc_id, self.config().gutters.clone());
+            let view = View::new(doc_id, self.gutter_config());
             let view_id = self.tree.insert(view);
             let doc = doc_mut!(self, &doc_id);
             doc.ensure_view_init(view_id);
@@ -2005,13 +3066,41 @@ pub fn save<P: Into<PathBuf>>(
         doc_id: DocumentId,
         path: Option<P>,
         force: bool,
+    ) -> anyhow::Result<()> {
+        self.save_impl(doc_id, path.map(Into::into), force, None)
+    }
+
+    /// The same as [`Editor::save`], but the file is written with elevated privileges.
+    pub fn save_privileged<P: Into<PathBuf>>(
+        &mut self,
+        doc_id: DocumentId,
+        path: Option<P>,
+        force: bool,
+        privileged: PrivilegedWrite,
+    ) -> anyhow::Result<()> {
+        self.save_impl(doc_id, path.map(Into::into), force, Some(privileged))
+    }
+
+    fn save_impl(
+        &mut self,
+        doc_id: DocumentId,
+        path: Option<PathBuf>,
+        force: bool,
+        privileged: Option<PrivilegedWrite>,
     ) -> anyhow::Result<()> {
         // convert a channel of futures to pipe into main queue one by one
         // via stream.then() ? then push into main future
 
-        let path = path.map(|path| path.into());
         let doc = doc_mut!(self, &doc_id);
-        let doc_save_future = doc.save(path, force)?;
+        if doc.is_hex() {
+            if let Some(view_id) = doc.selections().keys().next().copied() {
+                doc.refresh_hex_dump(self.tree.get_mut(view_id))?;
+            }
+        }
+        let doc_save_future: DocumentSavedEventFuture = match privileged {
+            Some(privileged) => Box::pin(doc.save_privileged(path, force, privileged)?),
+            None => Box::pin(doc.save(path, force)?),
+        };
 
         // When a file is written to, notify the file event handler.
         // Note: This can be removed once proper file watching is implemented.
@@ -2060,6 +3149,11 @@ pub fn focus(&mut self, view_id: ViewId) {
         }
 
         let prev_id = std::mem::replace(&mut self.tree.focus, view_id);
+        if self.tree.zoom().is_some() {
+            // Zoom into the newly focused view.
+            self.tree.recalculate();
+            self.ensure_cursor_in_view(view_id);
+        }
         doc_mut!(self).mark_as_focused();
 
         let focus_lost = self.tree.get(prev_id).doc;
@@ -2152,40 +3246,163 @@ pub fn doc_diagnostics_with_filter<'a>(
     ) -> impl Iterator<Item = helix_core::Diagnostic> + 'a {
         let text = document.text().clone();
         let language_config = document.language.clone();
+        let hidden_diagnostics = document.config.load().hidden_diagnostics.clone();
         document
             .uri()
             .and_then(|uri| diagnostics.get(&uri))
             .map(|diags| {
                 diags.iter().filter_map(move |(diagnostic, provider)| {
-                    let server_id = provider.language_server_id()?;
-                    let ls = language_servers.get_by_id(server_id)?;
-                    language_config
-                        .as_ref()
-                        .and_then(|c| {
-                            c.language_servers.iter().find(|features| {
-                                features.name == ls.name()
-                                    && features.has_feature(LanguageServerFeature::Diagnostics)
-                            })
-                        })
-                        .and_then(|_| {
-                            if filter(diagnostic, provider) {
-                                Document::lsp_diagnostic_to_diagnostic(
-                                    &text,
-                                    language_config.as_deref(),
-                                    diagnostic,
-                                    provider.clone(),
-                                    ls.offset_encoding(),
-                                )
-                            } else {
-                                None
-                            }
-                        })
+                    let offset_encoding = match provider.language_server_id() {
+                        Some(server_id) => {
+                            let ls = language_servers.get_by_id(server_id)?;
+                            language_config.as_ref().and_then(|c| {
+                                c.language_servers.iter().find(|features| {
+                                    features.name == ls.name()
+                                        && features.has_feature(LanguageServerFeature::Diagnostics)
+                                })
+                            })?;
+                            ls.offset_encoding()
+                        }
+                        // Diagnostics from internal providers use character offsets.
+                        None => OffsetEncoding::Utf32,
+                    };
+                    if !filter(diagnostic, provider)
+                        || hidden_diagnostics
+                            .iter()
+                            .any(|rule| rule.matches(diagnostic))
+                    {
+                        return None;
+                    }
+                    Document::lsp_diagnostic_to_diagnostic(
+                        &text,
+                        language_config.as_deref(),
+                        diagnostic,
+                        provider.clone(),
+                        offset_encoding,
+                    )
                 })
             })
             .into_iter()
             .flatten()
     }
 
+    /// Replaces all diagnostics produced by `:make` with `diagnostics` and refreshes the
+    /// diagnostics of every open document.
+    pub fn set_make_diagnostics(&mut self, diagnostics: BTreeMap<Uri, Vec<lsp::Diagnostic>>) {
+        let provider = DiagnosticProvider::Make;
+        for diags in self.diagnostics.values_mut() {
+            diags.retain(|(_, d_provider)| d_provider != &provider);
+        }
+        self.diagnostics.retain(|_, diags| !diags.is_empty());
+
+        for (uri, diags) in diagnostics {
+            let current_diagnostics = self.diagnostics.entry(uri).or_default();
+            current_diagnostics.extend(diags.into_iter().map(|d| (d, provider.clone())));
+            // Sort diagnostics first by severity and then by line numbers.
+            // Note: The `lsp::DiagnosticSeverity` enum is already defined in decreasing order
+            current_diagnostics
+                .sort_by_key(|(d, provider)| (d.severity, d.range.start, provider.clone()));
+        }
+
+        let doc_ids: Vec<_> = self.documents.keys().copied().collect();
+        for doc_id in doc_ids {
+            let Some(doc) = self.documents.get_mut(&doc_id) else {
+                continue;
+            };
+            let diagnostics = Self::doc_diagnostics_with_filter(
+                &self.language_servers,
+                &self.diagnostics,
+                doc,
+                |_, d_provider| d_provider == &DiagnosticProvider::Make,
+            );
+            doc.replace_diagnostics(diagnostics, &[], Some(&provider));
+            helix_event::dispatch(DiagnosticsDidChange {
+                editor: self,
+                doc: doc_id,
+            });
+        }
+    }
+
+    /// Returns the output buffer called `name`, creating it if it doesn't exist.
+    pub fn output_buffer(&mut self, name: &str) -> DocumentId {
+        if let Some(&id) = self
+            .output_buffers
+            .get(name)
+            .filter(|id| self.documents.contains_key(id))
+        {
+            return id;
+        }
+
+        let mut doc = Document::default(self.config.clone(), self.syn_loader.clone());
+        doc.set_scratch_name(name);
+        doc.readonly = true;
+        let id = self.new_document(doc);
+        self.output_buffers.insert(name.to_string(), id);
+        id
+    }
+
+    /// Replaces the text of the output buffer called `name` with `text`.
+    pub fn set_output(&mut self, name: &str, text: &str) -> DocumentId {
+        let id = self.output_buffer(name);
+        let view_id = self.tree.focus;
+        let Some(doc) = self.documents.get_mut(&id) else {
+            return id;
+        };
+        if *doc.text() == text {
+            return id;
+        }
+        let transaction = Transaction::change(
+            doc.text(),
+            [(0, doc.text().len_chars(), Some(text.into()))].into_iter(),
+        );
+        doc.ensure_view_init(view_id);
+        doc.apply_without_history(&transaction, self.tree.get_mut(view_id));
+        id
+    }
+
+    /// Appends `text` to the output buffer called `name`. Views showing the buffer with the
+    /// cursor on its last line keep following the output.
+    pub fn append_output(&mut self, name: &str, text: &str) {
+        let id = self.output_buffer(name);
+        let scrolloff = self.config().scrolloff;
+        let Some(doc) = self.documents.get_mut(&id) else {
+            return;
+        };
+
+        let following: Vec<ViewId> = self
+            .tree
+            .views()
+            .filter(|(view, _)| view.doc == id)
+            .filter(|(view, _)| {
+                let text = doc.text().slice(..);
+                let cursor = doc.selection(view.id).primary().cursor(text);
+                text.char_to_line(cursor) + 2 >= text.len_lines()
+            })
+            .map(|(view, _)| view.id)
+            .collect();
+
+        // A new buffer only contains a line ending which the output replaces.
+        let end = doc.text().len_chars();
+        let start = if rope_is_line_ending(doc.text().slice(..)) {
+            0
+        } else {
+            end
+        };
+        let transaction =
+            Transaction::change(doc.text(), [(start, end, Some(text.into()))].into_iter());
+
+        let view_id = following.first().copied().unwrap_or(self.tree.focus);
+        doc.ensure_view_init(view_id);
+        doc.apply_without_history(&transaction, self.tree.get_mut(view_id));
+
+        for view_id in following {
+            let view = self.tree.get_mut(view_id);
+            let end = doc.text().len_chars();
+            doc.set_selection(view_id, Selection::point(end));
+            view.ensure_cursor_in_view(doc, scrolloff);
+        }
+    }
+
     /// Gets the primary cursor position in screen coordinates,
     /// or `None` if the primary cursor is not visible on screen.
     pub fn cursor(&self) -> (Option<Position>, CursorKind) {
@@ -2283,8 +3500,10 @@ pub async fn flush_writes(&mut self) -> anyhow::Result<()> {
                     }
                 };
 
-                let doc = doc_mut!(self, &save_event.doc_id);
-                doc.set_last_saved_revision(save_event.revision, save_event.save_time);
+                // The document may have been closed while it was being saved.
+                if let Some(doc) = try_doc_mut!(self, &save_event.doc_id) {
+                    doc.set_saved(&save_event);
+                }
             }
         }
 
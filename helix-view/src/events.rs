@@ -16,6 +16,10 @@
         changes: &'a ChangeSet,
         ghost_transaction: bool
     }
+    DocumentDidSave<'a> {
+        editor: &'a mut Editor,
+        doc: DocumentId
+    }
     DocumentDidClose<'a> {
         editor: &'a mut Editor,
         doc: Document
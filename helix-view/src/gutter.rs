@@ -32,6 +32,7 @@ pub fn style<'doc>(
             GutterType::LineNumbers => line_numbers(editor, doc, view, theme, is_focused),
             GutterType::Spacer => padding(editor, doc, view, theme, is_focused),
             GutterType::Diff => diff(editor, doc, view, theme, is_focused),
+            GutterType::Folds => folds(editor, doc, view, theme, is_focused),
         }
     }
 
@@ -41,6 +42,7 @@ pub fn width(self, view: &View, doc: &Document) -> usize {
             GutterType::LineNumbers => line_numbers_width(view, doc),
             GutterType::Spacer => 1,
             GutterType::Diff => 1,
+            GutterType::Folds => 1,
         }
     }
 }
@@ -87,6 +89,32 @@ pub fn diagnostic<'doc>(
     )
 }
 
+pub fn folds<'doc>(
+    _editor: &'doc Editor,
+    doc: &'doc Document,
+    view: &View,
+    theme: &Theme,
+    _is_focused: bool,
+) -> GutterFn<'doc> {
+    let style = theme
+        .try_get("ui.virtual.fold")
+        .unwrap_or_else(|| theme.get("ui.linenr"));
+    let folds = doc.folds(view.id);
+    Box::new(
+        move |line: usize, _selected: bool, first_visual_line: bool, out: &mut String| {
+            if !first_visual_line
+                || folds
+                    .binary_search_by_key(&line, |fold| fold.start)
+                    .is_err()
+            {
+                return None;
+            }
+            write!(out, "▸").unwrap();
+            Some(style)
+        },
+    )
+}
+
 pub fn diff<'doc>(
     _editor: &'doc Editor,
     doc: &'doc Document,
@@ -254,17 +282,28 @@ pub fn breakpoints<'doc>(
                 .iter()
                 .find(|breakpoint| breakpoint.line == line)?;
 
-            let style = if breakpoint.condition.is_some() && breakpoint.log_message.is_some() {
+            let conditional = breakpoint.condition.is_some() || breakpoint.hit_condition.is_some();
+            let logpoint = breakpoint.log_message.is_some();
+            let style = if conditional && logpoint {
                 error.underline_style(UnderlineStyle::Line)
-            } else if breakpoint.condition.is_some() {
+            } else if conditional {
                 error
-            } else if breakpoint.log_message.is_some() {
+            } else if logpoint {
                 info
             } else {
                 breakpoint_style
             };
 
-            let sym = if breakpoint.verified { "●" } else { "◯" };
+            // Logpoints are shown as diamonds and conditional breakpoints as rings, filled
+            // once they are verified by the debugger.
+            let sym = match (logpoint, conditional, breakpoint.verified) {
+                (true, _, true) => "◆",
+                (true, _, false) => "◇",
+                (false, true, true) => "◉",
+                (false, true, false) => "◎",
+                (false, false, true) => "●",
+                (false, false, false) => "◯",
+            };
             write!(out, "{}", sym).unwrap();
             Some(style)
         },
@@ -17,12 +17,30 @@ pub enum AutoSaveEvent {
     LeftInsertMode,
 }
 
+#[derive(Debug)]
+pub struct RecoveryEvent {
+    pub snapshot_after: u64,
+}
+
+/// Spell checks a document after it changes.
+#[derive(Debug)]
+pub struct SpellCheckEvent(pub DocumentId);
+
+/// Runs the query of the query playground again after its query or source changes.
+#[derive(Debug)]
+pub struct QueryPlaygroundEvent(pub DocumentId);
+
 pub struct Handlers {
     // only public because most of the actual implementation is in helix-term right now :/
     pub completions: CompletionHandler,
     pub signature_hints: Sender<lsp::SignatureHelpEvent>,
     pub auto_save: Sender<AutoSaveEvent>,
+    pub recovery: Sender<RecoveryEvent>,
     pub document_colors: Sender<lsp::DocumentColorsEvent>,
+    pub document_highlight: Sender<lsp::DocumentHighlightEvent>,
+    pub semantic_tokens: Sender<lsp::SemanticTokensEvent>,
+    pub spell_check: Sender<SpellCheckEvent>,
+    pub query_playground: Sender<QueryPlaygroundEvent>,
     pub word_index: word_index::Handler,
     pub pull_diagnostics: Sender<lsp::PullDiagnosticsEvent>,
     pub pull_all_documents_diagnostics: Sender<lsp::PullAllDocumentsDiagnosticsEvent>,
@@ -90,37 +90,7 @@ pub fn breakpoints_changed(
     path: PathBuf,
     breakpoints: &mut [Breakpoint],
 ) -> Result<(), anyhow::Error> {
-    // TODO: handle capabilities correctly again, by filtering breakpoints when emitting
-    // if breakpoint.condition.is_some()
-    //     && !debugger
-    //         .caps
-    //         .as_ref()
-    //         .unwrap()
-    //         .supports_conditional_breakpoints
-    //         .unwrap_or_default()
-    // {
-    //     bail!(
-    //         "Can't edit breakpoint: debugger does not support conditional breakpoints"
-    //     )
-    // }
-    // if breakpoint.log_message.is_some()
-    //     && !debugger
-    //         .caps
-    //         .as_ref()
-    //         .unwrap()
-    //         .supports_log_points
-    //         .unwrap_or_default()
-    // {
-    //     bail!("Can't edit breakpoint: debugger does not support logpoints")
-    // }
-    let source_breakpoints = breakpoints
-        .iter()
-        .map(|breakpoint| helix_dap::SourceBreakpoint {
-            line: breakpoint.line + 1, // convert from 0-indexing to 1-indexing (TODO: could set debugger to 0-indexing on init)
-            ..Default::default()
-        })
-        .collect::<Vec<_>>();
-
+    let (source_breakpoints, unsupported) = source_breakpoints(breakpoints, debugger.caps.as_ref());
     let request = debugger.set_breakpoints(path, source_breakpoints);
     match block_on(request) {
         Ok(Some(dap_breakpoints)) => {
@@ -139,9 +109,70 @@ pub fn breakpoints_changed(
         Err(e) => anyhow::bail!("Failed to set breakpoints: {}", e),
         _ => {}
     };
+
+    if let Some(unsupported) = unsupported {
+        anyhow::bail!(unsupported);
+    }
     Ok(())
 }
 
+/// The breakpoints sent to a debugger with the capabilities `caps`. Conditions, hit conditions and
+/// log messages are only sent when the debugger supports them, the breakpoints are still set
+/// without them otherwise and the error names the ones which were left out.
+fn source_breakpoints(
+    breakpoints: &[Breakpoint],
+    caps: Option<&dap::DebuggerCapabilities>,
+) -> (Vec<dap::SourceBreakpoint>, Option<String>) {
+    let supports = |cap: fn(&dap::DebuggerCapabilities) -> Option<bool>| {
+        caps.is_none_or(|caps| cap(caps).unwrap_or_default())
+    };
+    let supported = [
+        supports(|caps| caps.supports_conditional_breakpoints),
+        supports(|caps| caps.supports_hit_conditional_breakpoints),
+        supports(|caps| caps.supports_log_points),
+    ];
+    let mut unsupported = [false; 3];
+    let source_breakpoints = breakpoints
+        .iter()
+        .map(|breakpoint| {
+            let mut fields = [
+                breakpoint.condition.clone(),
+                breakpoint.hit_condition.clone(),
+                breakpoint.log_message.clone(),
+            ];
+            for (i, field) in fields.iter_mut().enumerate() {
+                if !supported[i] && field.take().is_some() {
+                    unsupported[i] = true;
+                }
+            }
+            let [condition, hit_condition, log_message] = fields;
+            dap::SourceBreakpoint {
+                line: breakpoint.line + 1, // convert from 0-indexing to 1-indexing (TODO: could set debugger to 0-indexing on init)
+                condition,
+                hit_condition,
+                log_message,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    let unsupported: Vec<_> = ["conditions", "hit conditions", "log messages"]
+        .into_iter()
+        .zip(unsupported)
+        .filter_map(|(name, unsupported)| unsupported.then_some(name))
+        .collect();
+    let error = (!unsupported.is_empty()).then(|| {
+        format!(
+            "Debugger does not support breakpoint {}",
+            unsupported.join(" or ")
+        )
+    });
+    (source_breakpoints, error)
+}
+
+/// The name of the output buffer holding the debug console.
+pub const DEBUG_CONSOLE_NAME: &str = "[debug console]";
+
 impl Editor {
     pub async fn handle_debugger_message(
         &mut self,
@@ -302,6 +333,7 @@ pub async fn handle_debugger_message(
                         };
 
                         log::info!("{}", output);
+                        self.append_output(DEBUG_CONSOLE_NAME, &output);
                         self.set_status(format!("{} {}", prefix, output));
                     }
                     Event::Initialized(_) => {
@@ -314,7 +346,11 @@ pub async fn handle_debugger_message(
                         // send existing breakpoints
                         for (path, breakpoints) in &mut self.breakpoints {
                             // TODO: call futures in parallel, await all
-                            let _ = breakpoints_changed(debugger, path.clone(), breakpoints);
+                            if let Err(err) =
+                                breakpoints_changed(debugger, path.clone(), breakpoints)
+                            {
+                                warn!("{err}");
+                            }
                         }
                         // TODO: fetch breakpoints (in case we're attaching)
 
@@ -418,11 +454,21 @@ pub async fn handle_debugger_message(
                             return true;
                         };
 
-                        let process = match std::process::Command::new(&config.command)
-                            .args(&config.args)
-                            .arg(arguments.args.join(" "))
-                            .spawn()
-                        {
+                        let mut command = std::process::Command::new(&config.command);
+                        command.args(&config.args).arg(arguments.args.join(" "));
+                        // The debuggee expects to be started in the requested directory and
+                        // environment. `None` values unset the variable.
+                        if !arguments.cwd.is_empty() {
+                            command.current_dir(&arguments.cwd);
+                        }
+                        for (key, value) in arguments.env.iter().flatten() {
+                            match value {
+                                Some(value) => command.env(key, value),
+                                None => command.env_remove(key),
+                            };
+                        }
+
+                        let process = match command.spawn() {
                             Ok(process) => process,
                             Err(err) => {
                                 self.set_error(format!(
@@ -512,3 +558,56 @@ pub async fn handle_debugger_message(
         true
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn breakpoints_without_capabilities() {
+        let breakpoints = [
+            Breakpoint {
+                line: 1,
+                condition: Some("x > 1".to_owned()),
+                log_message: Some("x = {x}".to_owned()),
+                ..Default::default()
+            },
+            Breakpoint {
+                line: 4,
+                hit_condition: Some("3".to_owned()),
+                ..Default::default()
+            },
+        ];
+        let caps: dap::DebuggerCapabilities = serde_json::from_value(json!({
+            "supportsConditionalBreakpoints": true,
+            "supportsHitConditionalBreakpoints": false,
+        }))
+        .unwrap();
+
+        let (sent, error) = source_breakpoints(&breakpoints, Some(&caps));
+        assert_eq!(
+            sent,
+            [
+                dap::SourceBreakpoint {
+                    line: 2,
+                    condition: Some("x > 1".to_owned()),
+                    ..Default::default()
+                },
+                dap::SourceBreakpoint {
+                    line: 5,
+                    ..Default::default()
+                },
+            ]
+        );
+        assert_eq!(
+            error.as_deref(),
+            Some("Debugger does not support breakpoint hit conditions or log messages")
+        );
+
+        // Everything is sent before the debugger told its capabilities.
+        let (sent, error) = source_breakpoints(&breakpoints, None);
+        assert_eq!(sent[0].log_message.as_deref(), Some("x = {x}"));
+        assert_eq!(sent[1].hit_condition.as_deref(), Some("3"));
+        assert_eq!(error, None);
+    }
+}
@@ -2,11 +2,11 @@
 use std::collections::HashSet;
 use std::fmt::Display;
 
-use crate::editor::Action;
+use crate::editor::{Action, WorkspaceEditSource};
 use crate::events::{
     DiagnosticsDidChange, DocumentDidChange, DocumentDidClose, LanguageServerInitialized,
 };
-use crate::{DocumentId, Editor};
+use crate::{DocumentId, Editor, ViewId};
 use helix_core::diagnostic::DiagnosticProvider;
 use helix_core::Uri;
 use helix_event::register_hook;
@@ -17,6 +17,15 @@
 
 pub struct DocumentColorsEvent(pub DocumentId);
 
+pub struct SemanticTokensEvent(pub DocumentId);
+
+pub struct DocumentHighlightEvent {
+    pub doc: DocumentId,
+    pub view: ViewId,
+    /// How long the cursor must rest, in milliseconds.
+    pub delay: u64,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum SignatureHelpInvoked {
     Automatic,
@@ -45,9 +54,25 @@ pub struct ApplyEditError {
     pub failed_change_idx: usize,
 }
 
+/// Called with the result of applying a workspace edit.
+pub type WorkspaceEditCallback =
+    Box<dyn FnOnce(&mut Editor, Result<(), ApplyEditError>) + Send + 'static>;
+
+/// A workspace edit which is applied once it was reviewed, see
+/// `LspConfig::review_workspace_edits`.
+pub struct PendingWorkspaceEdit {
+    pub source: WorkspaceEditSource,
+    /// Describes the edit, like which symbol is renamed.
+    pub title: String,
+    pub offset_encoding: OffsetEncoding,
+    pub edit: lsp::WorkspaceEdit,
+    pub on_done: Option<WorkspaceEditCallback>,
+}
+
 #[derive(Debug)]
 pub enum ApplyEditErrorKind {
     DocumentChanged,
+    Rejected,
     FileNotFound,
     InvalidUrl(helix_core::uri::UrlConversionError),
     IoError(std::io::Error),
@@ -71,6 +96,7 @@ impl Display for ApplyEditErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ApplyEditErrorKind::DocumentChanged => f.write_str("document has changed"),
+            ApplyEditErrorKind::Rejected => f.write_str("rejected by the user"),
             ApplyEditErrorKind::FileNotFound => f.write_str("file not found"),
             ApplyEditErrorKind::InvalidUrl(err) => f.write_str(&format!("{err}")),
             ApplyEditErrorKind::IoError(err) => f.write_str(&format!("{err}")),
@@ -130,6 +156,24 @@ fn apply_text_edits(
         Ok(())
     }
 
+    /// Applies the workspace edit, or queues it in `pending_workspace_edits` if its source is
+    /// reviewed first.
+    pub fn submit_workspace_edit(&mut self, pending: PendingWorkspaceEdit) {
+        if self
+            .config()
+            .lsp
+            .review_workspace_edits
+            .contains(&pending.source)
+        {
+            self.pending_workspace_edits.push_back(pending);
+            return;
+        }
+        let result = self.apply_workspace_edit(pending.offset_encoding, &pending.edit);
+        if let Some(on_done) = pending.on_done {
+            on_done(self, result);
+        }
+    }
+
     // TODO make this transactional (and set failureMode to transactional)
     pub fn apply_workspace_edit(
         &mut self,
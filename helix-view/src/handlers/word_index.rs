@@ -3,7 +3,7 @@
 //! This provides an eventually consistent set of words used in any open buffers. This set is
 //! later used for lexical completion.
 
-use std::{borrow::Cow, collections::HashMap, iter, mem, sync::Arc, time::Duration};
+use std::{borrow::Cow, cmp::Reverse, collections::HashMap, iter, mem, sync::Arc, time::Duration};
 
 use helix_core::{
     chars::char_is_word, fuzzy::fuzzy_match, movement, ChangeSet, Range, Rope, RopeSlice,
@@ -129,6 +129,15 @@ fn finish_debounce(&mut self) {
 
 type Word = kstring::KString;
 
+#[derive(Debug, Default)]
+struct WordEntry {
+    /// The number of occurrences of the word in the open documents.
+    count: u32,
+    /// The generation of the last edit which inserted the word, or zero if it was only found in
+    /// documents when they were opened.
+    edited: u64,
+}
+
 #[derive(Debug, Default)]
 struct WordIndexInner {
     /// Reference counted storage for words.
@@ -136,7 +145,9 @@ struct WordIndexInner {
     /// Words are very likely to be reused many times. Instead of storing duplicates we keep a
     /// reference count of times a word is used. When the reference count drops to zero the word
     /// is removed from the index.
-    words: HashMap<Word, u32>,
+    words: HashMap<Word, WordEntry>,
+    /// Incremented by each edit, to rank the words which were typed recently first.
+    generation: u64,
 }
 
 impl WordIndexInner {
@@ -144,26 +155,33 @@ fn words(&self) -> impl Iterator<Item = &Word> {
         self.words.keys()
     }
 
-    fn insert(&mut self, word: RopeSlice) {
+    fn insert(&mut self, word: RopeSlice, edited: bool) {
         let word: Cow<str> = word.into();
-        if let Some(rc) = self.words.get_mut(word.as_ref()) {
-            *rc = rc.saturating_add(1);
+        let entry = if let Some(entry) = self.words.get_mut(word.as_ref()) {
+            entry.count = entry.count.saturating_add(1);
+            entry
         } else {
             let word = match word {
                 Cow::Owned(s) => Word::from_string(s),
                 Cow::Borrowed(s) => Word::from_ref(s),
             };
-            self.words.insert(word, 1);
+            self.words.entry(word).or_insert(WordEntry {
+                count: 1,
+                edited: 0,
+            })
+        };
+        if edited {
+            entry.edited = self.generation;
         }
     }
 
     fn remove(&mut self, word: RopeSlice) {
         let word: Cow<str> = word.into();
         match self.words.get_mut(word.as_ref()) {
-            Some(1) => {
+            Some(WordEntry { count: 1, .. }) => {
                 self.words.remove(word.as_ref());
             }
-            Some(n) => *n -= 1,
+            Some(entry) => entry.count -= 1,
             None => (),
         }
     }
@@ -179,10 +197,15 @@ pub struct WordIndex {
 }
 
 impl WordIndex {
+    /// The words which fuzzy match `pattern`, best first. Words with the same score are ranked
+    /// by how recently they were typed, then by how often they occur.
     pub fn matches(&self, pattern: &str) -> Vec<String> {
         let inner = self.inner.read();
         let mut matches = fuzzy_match(pattern, inner.words(), false);
-        matches.sort_unstable_by_key(|(_, score)| *score);
+        matches.sort_unstable_by_key(|(word, score)| {
+            let entry = &inner.words[*word];
+            (Reverse(*score), Reverse(entry.edited), Reverse(entry.count))
+        });
         matches
             .into_iter()
             .map(|(word, _)| word.to_string())
@@ -192,16 +215,17 @@ pub fn matches(&self, pattern: &str) -> Vec<String> {
     fn add_document(&self, text: &Rope) {
         let mut inner = self.inner.write();
         for word in words(text.slice(..)) {
-            inner.insert(word);
+            inner.insert(word, false);
         }
     }
 
     fn update_document(&self, old_text: &Rope, text: &Rope, changes: &ChangeSet) {
         let mut inner = self.inner.write();
+        inner.generation += 1;
         for (old_window, new_window) in changed_windows(old_text.slice(..), text.slice(..), changes)
         {
             for word in words(new_window) {
-                inner.insert(word);
+                inner.insert(word, true);
             }
             for word in words(old_window) {
                 inner.remove(word);
@@ -498,6 +522,17 @@ fn assert_diff<S, R, I>(before: &str, after: &str, expect_removed: R, expect_ins
         );
     }
 
+    #[test]
+    fn ranking() {
+        let index = WordIndex::default();
+        index.add_document(&Rope::from("foobar foobaz foobaz"));
+        assert_eq!(index.matches("foo"), ["foobaz", "foobar"]);
+        let before = Rope::from("foobar");
+        let after = Rope::from("foobar foobar foobar");
+        index.update_document(&before, &after, compare_ropes(&before, &after).changes());
+        assert_eq!(index.matches("foo"), ["foobar", "foobaz"]);
+    }
+
     #[test]
     fn diff() {
         assert_diff("one two three", "one five three", ["two"], ["five"]);
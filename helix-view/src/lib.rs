@@ -14,6 +14,7 @@
 pub mod input;
 pub mod keyboard;
 pub mod register;
+pub mod statusline;
 pub mod theme;
 pub mod tree;
 pub mod view;
@@ -30,6 +30,9 @@ macro_rules! current_ref {
 
 /// Get the current document mutably.
 /// Returns `&mut Document`
+///
+/// Panics if the document with the given id was closed. Use `try_doc_mut!` for ids which
+/// were held across a picker, an async job or anything else which may close documents.
 #[macro_export]
 macro_rules! doc_mut {
     ($editor:expr, $id:expr) => {{
@@ -64,6 +67,10 @@ macro_rules! view {
     }};
 }
 
+/// Get the current document immutably.
+/// Returns `&Document`
+///
+/// Panics if the document with the given id was closed, see `try_doc!`.
 #[macro_export]
 macro_rules! doc {
     ($editor:expr, $id:expr) => {{
@@ -73,3 +80,21 @@ macro_rules! doc {
         $crate::current_ref!($editor).1
     }};
 }
+
+/// Get a document mutably, or `None` if it was closed.
+/// Returns `Option<&mut Document>`
+#[macro_export]
+macro_rules! try_doc_mut {
+    ($editor:expr, $id:expr) => {{
+        $editor.documents.get_mut($id)
+    }};
+}
+
+/// Get a document immutably, or `None` if it was closed.
+/// Returns `Option<&Document>`
+#[macro_export]
+macro_rules! try_doc {
+    ($editor:expr, $id:expr) => {{
+        $editor.documents.get($id)
+    }};
+}
@@ -1,4 +1,8 @@
-use std::{borrow::Cow, collections::HashMap, iter};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    iter,
+};
 
 use anyhow::Result;
 use arc_swap::access::DynAccess;
@@ -29,6 +33,16 @@ pub struct Registers {
     inner: HashMap<char, Vec<String>>,
     clipboard_provider: Box<dyn DynAccess<ClipboardProvider>>,
     pub last_search_register: char,
+    /// The most recent yanks, newest first.
+    history: VecDeque<Yank>,
+}
+
+/// The values written to a register by a yank or delete, recorded in the yank history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Yank {
+    pub register: char,
+    /// One value for each selection.
+    pub values: Vec<String>,
 }
 
 impl Registers {
@@ -37,6 +51,7 @@ pub fn new(clipboard_provider: Box<dyn DynAccess<ClipboardProvider>>) -> Self {
             inner: Default::default(),
             clipboard_provider,
             last_search_register: '/',
+            history: VecDeque::new(),
         }
     }
 
@@ -140,6 +155,43 @@ pub fn push(&mut self, name: char, mut value: String) -> Result<()> {
         }
     }
 
+    /// The values of the registers holding text, without the clipboard registers, in the order
+    /// which [Registers::restore] expects.
+    pub fn saved_values(&self) -> impl Iterator<Item = (char, &[String])> {
+        self.inner
+            .iter()
+            .filter(|(name, _)| !matches!(name, '*' | '+'))
+            .map(|(name, values)| (*name, values.as_slice()))
+    }
+
+    /// Sets the values of a register as returned by [Registers::saved_values], for example in a
+    /// later session. Special and clipboard registers are ignored.
+    pub fn restore(&mut self, name: char, values: Vec<String>) {
+        if !matches!(name, '_' | '#' | '.' | '%' | '*' | '+') {
+            self.inner.insert(name, values);
+        }
+    }
+
+    /// Adds a yank to the front of the yank history, which keeps at most `max_len` yanks.
+    /// Yanks to the black hole register are not recorded.
+    pub fn record_yank(&mut self, register: char, values: &[String], max_len: usize) {
+        if register == '_' || values.iter().all(String::is_empty) {
+            return;
+        }
+        // Yanking the same text again moves it to the front.
+        self.history.retain(|yank| yank.values != values);
+        self.history.push_front(Yank {
+            register,
+            values: values.to_vec(),
+        });
+        self.history.truncate(max_len);
+    }
+
+    /// The recorded yanks, newest first.
+    pub fn yank_history(&self) -> impl ExactSizeIterator<Item = &Yank> {
+        self.history.iter()
+    }
+
     pub fn first<'a>(&'a self, name: char, editor: &'a Editor) -> Option<Cow<'a, str>> {
         self.read(name, editor).and_then(|mut values| values.next())
     }
@@ -178,7 +230,8 @@ pub fn iter_preview(&self) -> impl Iterator<Item = (char, &str)> {
     pub fn clear(&mut self) {
         self.clear_clipboard(ClipboardType::Clipboard);
         self.clear_clipboard(ClipboardType::Selection);
-        self.inner.clear()
+        self.inner.clear();
+        self.history.clear();
     }
 
     pub fn remove(&mut self, name: char) -> bool {
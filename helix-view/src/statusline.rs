@@ -0,0 +1,256 @@
+//! Format strings composing a section of the statusline.
+//!
+//! A format string is text mixed with placeholders:
+//!
+//! * `{element}` renders a statusline element such as `{file-name}`.
+//! * `{element:<N}`, `{element:>N}` and `{element:^N}` pad the element with spaces to at least
+//!   `N` columns, aligned to the left, right or center.
+//! * `{#scope}` styles the following text with a theme scope and `{#}` resets the style.
+//! * `{?element text}` renders the format string `text` only if the element is not blank.
+//!
+//! `\{`, `\}` and `\\` escape braces and backslashes.
+
+use std::fmt;
+
+use serde::{
+    de::{self, value::StrDeserializer, IntoDeserializer},
+    Deserialize, Serialize,
+};
+
+use crate::editor::StatusLineElement;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatItem {
+    Text(String),
+    Element {
+        element: StatusLineElement,
+        /// The alignment and minimal width of the element.
+        padding: Option<(Alignment, usize)>,
+    },
+    /// Styles the following items with a theme scope, or the statusline style for `None`.
+    Style(Option<String>),
+    /// Items which are only rendered if the element is not blank.
+    Conditional {
+        element: StatusLineElement,
+        items: Vec<FormatItem>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusLineFormat {
+    source: String,
+    items: Vec<FormatItem>,
+}
+
+impl StatusLineFormat {
+    pub fn items(&self) -> &[FormatItem] {
+        &self.items
+    }
+}
+
+impl std::str::FromStr for StatusLineFormat {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut chars = source.chars();
+        let items = parse_items(&mut chars, false)?;
+        Ok(Self {
+            source: source.to_owned(),
+            items,
+        })
+    }
+}
+
+impl fmt::Display for StatusLineFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Parses items up to the end of the input or, if `nested`, up to the closing brace.
+fn parse_items(chars: &mut std::str::Chars, nested: bool) -> Result<Vec<FormatItem>, String> {
+    let mut items = Vec::new();
+    let mut text = String::new();
+    loop {
+        let Some(ch) = chars.next() else {
+            if nested {
+                return Err("unclosed '{'".to_owned());
+            }
+            break;
+        };
+        match ch {
+            '\\' => match chars.next() {
+                Some(ch @ ('{' | '}' | '\\')) => text.push(ch),
+                _ => return Err("'\\' must be followed by '{', '}' or '\\'".to_owned()),
+            },
+            '}' if nested => break,
+            '}' => return Err("unmatched '}'".to_owned()),
+            '{' => {
+                if !text.is_empty() {
+                    items.push(FormatItem::Text(std::mem::take(&mut text)));
+                }
+                items.push(parse_placeholder(chars)?);
+            }
+            ch => text.push(ch),
+        }
+    }
+    if !text.is_empty() {
+        items.push(FormatItem::Text(text));
+    }
+    Ok(items)
+}
+
+/// Parses a placeholder after its opening brace.
+fn parse_placeholder(chars: &mut std::str::Chars) -> Result<FormatItem, String> {
+    if let Some(rest) = chars.as_str().strip_prefix('?') {
+        let name_len = rest
+            .find(|ch: char| ch.is_whitespace() || ch == '}')
+            .unwrap_or(rest.len());
+        let element = parse_element(&rest[..name_len])?;
+        *chars = rest[name_len..].chars();
+        // A single space separates the element from the text.
+        if chars.as_str().starts_with(char::is_whitespace) {
+            chars.next();
+        }
+        return Ok(FormatItem::Conditional {
+            element,
+            items: parse_items(chars, true)?,
+        });
+    }
+
+    let placeholder = take_placeholder(chars)?;
+    if let Some(scope) = placeholder.strip_prefix('#') {
+        let scope = scope.trim();
+        return Ok(FormatItem::Style(
+            (!scope.is_empty()).then(|| scope.to_owned()),
+        ));
+    }
+    let (name, padding) = match placeholder.split_once(':') {
+        Some((name, spec)) => (name, Some(parse_padding(spec)?)),
+        None => (placeholder.as_str(), None),
+    };
+    Ok(FormatItem::Element {
+        element: parse_element(name.trim())?,
+        padding,
+    })
+}
+
+/// Takes the text up to the closing brace of a placeholder which can't contain other ones.
+fn take_placeholder(chars: &mut std::str::Chars) -> Result<String, String> {
+    let mut placeholder = String::new();
+    for ch in chars.by_ref() {
+        match ch {
+            '}' => return Ok(placeholder),
+            '{' => return Err("'{' inside of a placeholder".to_owned()),
+            ch => placeholder.push(ch),
+        }
+    }
+    Err("unclosed '{'".to_owned())
+}
+
+fn parse_padding(spec: &str) -> Result<(Alignment, usize), String> {
+    let mut spec_chars = spec.chars();
+    let alignment = match spec_chars.next() {
+        Some('<') => Alignment::Left,
+        Some('>') => Alignment::Right,
+        Some('^') => Alignment::Center,
+        _ => return Err(format!("invalid padding '{spec}', expected e.g. '<10'")),
+    };
+    let width = spec_chars
+        .as_str()
+        .parse()
+        .map_err(|_| format!("invalid padding width in '{spec}'"))?;
+    Ok((alignment, width))
+}
+
+fn parse_element(name: &str) -> Result<StatusLineElement, String> {
+    let deserializer: StrDeserializer<'_, de::value::Error> = name.into_deserializer();
+    StatusLineElement::deserialize(deserializer)
+        .map_err(|_| format!("unknown statusline element '{name}'"))
+}
+
+impl Serialize for StatusLineFormat {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for StatusLineFormat {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let source = String::deserialize(deserializer)?;
+        source.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use StatusLineElement as E;
+
+    fn parse(source: &str) -> Result<Vec<FormatItem>, String> {
+        source
+            .parse::<StatusLineFormat>()
+            .map(|format| format.items)
+    }
+
+    #[test]
+    fn parse_format() {
+        assert_eq!(
+            parse(r"{#error}\{{mode}\}{#} {position:>8}"),
+            Ok(vec![
+                FormatItem::Style(Some("error".to_owned())),
+                FormatItem::Text("{".to_owned()),
+                FormatItem::Element {
+                    element: E::Mode,
+                    padding: None
+                },
+                FormatItem::Text("}".to_owned()),
+                FormatItem::Style(None),
+                FormatItem::Text(" ".to_owned()),
+                FormatItem::Element {
+                    element: E::Position,
+                    padding: Some((Alignment::Right, 8))
+                },
+            ])
+        );
+        assert_eq!(
+            parse("{?register reg: {register}}{?spinner}"),
+            Ok(vec![
+                FormatItem::Conditional {
+                    element: E::Register,
+                    items: vec![
+                        FormatItem::Text("reg: ".to_owned()),
+                        FormatItem::Element {
+                            element: E::Register,
+                            padding: None
+                        },
+                    ],
+                },
+                FormatItem::Conditional {
+                    element: E::Spinner,
+                    items: vec![],
+                },
+            ])
+        );
+
+        assert!(parse("{mode").is_err());
+        assert!(parse("mode}").is_err());
+        assert!(parse("{?mode {mode}").is_err());
+        assert!(parse("{unknown}").is_err());
+        assert!(parse("{mode:10}").is_err());
+        assert!(parse(r"\n").is_err());
+    }
+}
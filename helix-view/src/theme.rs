@@ -15,6 +15,9 @@
 use crate::graphics::UnderlineStyle;
 pub use crate::graphics::{Color, Modifier, Style};
 
+/// The name of the output buffer of `:theme-preview`, which lists the scopes of the theme.
+pub const SCOPES_OUTPUT_NAME: &str = "theme-scopes";
+
 pub static DEFAULT_THEME_DATA: Lazy<Value> = Lazy::new(|| {
     let bytes = include_bytes!("../../theme.toml");
     toml::from_str(str::from_utf8(bytes).unwrap()).expect("Failed to parse base default theme")
@@ -188,6 +191,29 @@ fn load_theme(&self, name: &str, visited_paths: &mut HashSet<PathBuf>) -> Result
         Ok(theme_toml)
     }
 
+    /// Returns the paths of the files the theme is loaded from: its own file followed by the
+    /// files of the themes it inherits from. Built-in themes have no files.
+    pub fn paths(&self, name: &str) -> Vec<PathBuf> {
+        let mut visited_paths = HashSet::new();
+        let mut paths = Vec::new();
+        let mut name = name.to_owned();
+        while !matches!(name.as_str(), "default" | "base16_default") {
+            let Ok(path) = self.path(&name, &mut visited_paths) else {
+                break;
+            };
+            paths.push(path.clone());
+            let Some(parent) = self
+                .load_toml(path)
+                .ok()
+                .and_then(|toml| Some(toml.get("inherits")?.as_str()?.to_owned()))
+            else {
+                break;
+            };
+            name = parent;
+        }
+        paths
+    }
+
     pub fn read_names(path: &Path) -> Vec<String> {
         std::fs::read_dir(path)
             .map(|entries| {
@@ -1,5 +1,7 @@
 use crate::{graphics::Rect, View, ViewId};
+use serde::{Deserialize, Serialize};
 use slotmap::HopSlotMap;
+use std::path::PathBuf;
 
 // the dimensions are recomputed on window resize/tree change.
 //
@@ -8,7 +10,8 @@ pub struct Tree {
     root: ViewId,
     // (container, index inside the container)
     pub focus: ViewId,
-    // fullscreen: bool,
+    /// The width of the focused view when it is shown alone, centered in the area.
+    zoom: Option<u16>,
     area: Rect,
 
     nodes: HopSlotMap<ViewId, Node>,
@@ -45,13 +48,34 @@ pub fn view(view: View) -> Self {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Layout {
     Horizontal,
     Vertical,
     // could explore stacked/tabbed
 }
 
+/// A serializable description of the arrangement of the views in a [Tree].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LayoutNode {
+    /// A view of the document at `path`, or of a scratch buffer.
+    View {
+        path: Option<PathBuf>,
+        #[serde(default)]
+        focused: bool,
+    },
+    Split {
+        layout: Layout,
+        children: Vec<LayoutNode>,
+        /// The share of the space taken by each child, as in [Tree::set_split_weights]. Layouts
+        /// saved without them are split equally.
+        #[serde(default)]
+        weights: Vec<f64>,
+    },
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Direction {
     Up,
@@ -64,6 +88,8 @@ pub enum Direction {
 pub struct Container {
     layout: Layout,
     children: Vec<ViewId>,
+    /// The share of the container's space taken by each child, relative to the other children.
+    weights: Vec<f64>,
     area: Rect,
 }
 
@@ -72,11 +98,52 @@ pub fn new(layout: Layout) -> Self {
         Self {
             layout,
             children: Vec::new(),
+            weights: Vec::new(),
             area: Rect::default(),
         }
     }
+
+    fn insert(&mut self, pos: usize, child: ViewId) {
+        self.children.insert(pos, child);
+        self.weights.insert(pos, 1.0);
+    }
+
+    fn remove(&mut self, pos: usize) -> ViewId {
+        self.weights.remove(pos);
+        self.children.remove(pos)
+    }
+
+    /// Splits `size` between the children by their weights. The last child takes the space left
+    /// by rounding.
+    fn child_sizes(&self, size: u16) -> impl Iterator<Item = u16> + '_ {
+        let total: f64 = self.weights.iter().sum();
+        let mut left = size;
+        self.weights.iter().enumerate().map(move |(i, weight)| {
+            let child_size = if i == self.weights.len() - 1 {
+                left
+            } else {
+                ((size as f64 * weight / total) as u16).min(left)
+            };
+            left -= child_size;
+            child_size
+        })
+    }
 }
 
+/// The border between two neighbouring children of a split: the column separating them in a
+/// vertical split or the statusline row of the upper one in a horizontal split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitBorder {
+    container: ViewId,
+    /// The index of the child before the border.
+    index: usize,
+}
+
+/// The smallest width a view can be resized to.
+const MIN_SPLIT_WIDTH: u16 = 8;
+/// The smallest height a view can be resized to, which fits one line and the statusline.
+const MIN_SPLIT_HEIGHT: u16 = 2;
+
 impl Default for Container {
     fn default() -> Self {
         Self::new(Layout::Vertical)
@@ -96,7 +163,7 @@ pub fn new(area: Rect) -> Self {
         Self {
             root,
             focus: root,
-            // fullscreen: false,
+            zoom: None,
             area,
             nodes,
             stack: Vec::new(),
@@ -131,7 +198,7 @@ pub fn insert(&mut self, view: View) -> ViewId {
             pos + 1
         };
 
-        container.children.insert(pos, node);
+        container.insert(pos, node);
         // focus the new node
         self.focus = node;
 
@@ -168,7 +235,7 @@ pub fn split(&mut self, view: View, layout: Layout) -> ViewId {
                     .unwrap();
                 pos + 1
             };
-            container.children.insert(pos, node);
+            container.insert(pos, node);
             self.nodes[node].parent = parent;
         } else {
             let mut split = Node::container(layout);
@@ -182,8 +249,8 @@ pub fn split(&mut self, view: View, layout: Layout) -> ViewId {
                 } => container,
                 _ => unreachable!(),
             };
-            container.children.push(focus);
-            container.children.push(node);
+            container.insert(0, focus);
+            container.insert(1, node);
             self.nodes[focus].parent = split;
             self.nodes[node].parent = split;
 
@@ -243,7 +310,7 @@ fn remove_or_replace(&mut self, child: ViewId, replacement: Option<ViewId>) {
             container.children[pos] = new;
             self.nodes[new].parent = parent;
         } else {
-            container.children.remove(pos);
+            container.remove(pos);
         }
     }
 
@@ -262,13 +329,56 @@ pub fn remove(&mut self, index: ViewId) {
         if parent_container.children.len() == 1 && !parent_is_root {
             // Lets merge the only child back to its grandparent so that Views
             // are equally spaced.
-            let sibling = parent_container.children.pop().unwrap();
+            let sibling = parent_container.remove(0);
             self.remove_or_replace(parent, Some(sibling));
         }
 
         self.recalculate()
     }
 
+    /// Describes the arrangement of the views, using `path` to find the path of the document
+    /// shown in a view.
+    pub fn layout_node(&self, path: impl Fn(&View) -> Option<PathBuf>) -> LayoutNode {
+        self.layout_node_at(self.root, &path)
+    }
+
+    fn layout_node_at(&self, id: ViewId, path: &impl Fn(&View) -> Option<PathBuf>) -> LayoutNode {
+        match &self.nodes[id].content {
+            Content::View(view) => LayoutNode::View {
+                path: path(view),
+                focused: id == self.focus,
+            },
+            // The root container may hold a single child.
+            Content::Container(container) if container.children.len() == 1 => {
+                self.layout_node_at(container.children[0], path)
+            }
+            Content::Container(container) => LayoutNode::Split {
+                layout: container.layout,
+                children: container
+                    .children
+                    .iter()
+                    .map(|&child| self.layout_node_at(child, path))
+                    .collect(),
+                weights: container.weights.clone(),
+            },
+        }
+    }
+
+    /// Sets the share of the space taken by each child of the split holding `view`, relative to
+    /// the other children. Weights which don't fit the split are ignored.
+    pub fn set_split_weights(&mut self, view: ViewId, weights: &[f64]) {
+        let parent = self.nodes[view].parent;
+        let container = self.container_mut(parent);
+        if weights.len() == container.weights.len()
+            && weights
+                .iter()
+                .all(|weight| weight.is_finite() && *weight > 0.0)
+        {
+            container.weights = weights.to_vec();
+            self.recalculate();
+        }
+    }
+
     pub fn views(&self) -> impl Iterator<Item = (&View, bool)> {
         let focus = self.focus;
         self.nodes.iter().filter_map(move |(key, node)| match node {
@@ -280,6 +390,12 @@ pub fn views(&self) -> impl Iterator<Item = (&View, bool)> {
         })
     }
 
+    /// The views shown on screen: all of them or, when zoomed, only the focused one.
+    pub fn visible_views(&self) -> impl Iterator<Item = (&View, bool)> {
+        let zoomed = self.zoom.is_some();
+        self.views().filter(move |(_, focused)| !zoomed || *focused)
+    }
+
     pub fn views_mut(&mut self) -> impl Iterator<Item = (&mut View, bool)> {
         let focus = self.focus;
         self.nodes
@@ -352,6 +468,17 @@ pub fn resize(&mut self, area: Rect) -> bool {
         false
     }
 
+    pub fn zoom(&self) -> Option<u16> {
+        self.zoom
+    }
+
+    /// Shows only the focused view, centered in the area with the given width, or all views
+    /// again for `None`.
+    pub fn set_zoom(&mut self, width: Option<u16>) {
+        self.zoom = width;
+        self.recalculate();
+    }
+
     pub fn recalculate(&mut self) {
         if self.is_empty() {
             // There are no more views, so the tree should focus itself again.
@@ -381,14 +508,14 @@ pub fn recalculate(&mut self) {
 
                     match container.layout {
                         Layout::Horizontal => {
-                            let len = container.children.len();
-
-                            let height = area.height / len as u16;
-
                             let mut child_y = area.y;
 
-                            for (i, child) in container.children.iter().enumerate() {
-                                let mut area = Rect::new(
+                            for (child, height) in container
+                                .children
+                                .iter()
+                                .zip(container.child_sizes(area.height))
+                            {
+                                let area = Rect::new(
                                     container.area.x,
                                     child_y,
                                     container.area.width,
@@ -396,12 +523,6 @@ pub fn recalculate(&mut self) {
                                 );
                                 child_y += height;
 
-                                // last child takes the remaining width because we can get uneven
-                                // space from rounding
-                                if i == len - 1 {
-                                    area.height = container.area.y + container.area.height - area.y;
-                                }
-
                                 self.stack.push((*child, area));
                             }
                         }
@@ -413,11 +534,15 @@ pub fn recalculate(&mut self) {
                             let total_gap = inner_gap * len_u16.saturating_sub(2);
 
                             let used_area = area.width.saturating_sub(total_gap);
-                            let width = used_area / len_u16;
 
                             let mut child_x = area.x;
 
-                            for (i, child) in container.children.iter().enumerate() {
+                            for (i, (child, width)) in container
+                                .children
+                                .iter()
+                                .zip(container.child_sizes(used_area))
+                                .enumerate()
+                            {
                                 let mut area = Rect::new(
                                     child_x,
                                     container.area.y,
@@ -439,6 +564,20 @@ pub fn recalculate(&mut self) {
                 }
             }
         }
+
+        // The other views keep their areas while hidden, so they don't need to be scrolled.
+        if let Some(width) = self.zoom {
+            let width = width.min(self.area.width);
+            let area = Rect::new(
+                self.area.x + (self.area.width - width) / 2,
+                self.area.y,
+                width,
+                self.area.height,
+            );
+            if let Content::View(view) = &mut self.nodes[self.focus].content {
+                view.area = area;
+            }
+        }
     }
 
     pub fn traverse(&self) -> Traverse<'_> {
@@ -669,6 +808,87 @@ pub fn swap_split_in_direction(&mut self, direction: Direction) -> Option<()> {
     pub fn area(&self) -> Rect {
         self.area
     }
+
+    fn node_area(&self, id: ViewId) -> Rect {
+        match &self.nodes[id].content {
+            Content::View(view) => view.area,
+            Content::Container(container) => container.area,
+        }
+    }
+
+    /// Returns the border between two splits at the screen coordinates.
+    pub fn split_border_at(&self, row: u16, column: u16) -> Option<SplitBorder> {
+        if self.zoom.is_some() {
+            return None;
+        }
+        self.nodes.iter().find_map(|(id, node)| {
+            let Content::Container(container) = &node.content else {
+                return None;
+            };
+            let (_, children) = container.children.split_last()?;
+            children.iter().enumerate().find_map(|(index, &child)| {
+                let area = self.node_area(child);
+                let is_border = match container.layout {
+                    Layout::Vertical => {
+                        column == area.right() && (area.top()..area.bottom()).contains(&row)
+                    }
+                    Layout::Horizontal => {
+                        row + 1 == area.bottom() && (area.left()..area.right()).contains(&column)
+                    }
+                };
+                is_border.then_some(SplitBorder {
+                    container: id,
+                    index,
+                })
+            })
+        })
+    }
+
+    /// Moves the border between two splits to the screen coordinates, keeping both of them at
+    /// least at their minimal size.
+    pub fn resize_split(&mut self, border: SplitBorder, row: u16, column: u16) {
+        let Some(Node {
+            content: Content::Container(container),
+            ..
+        }) = self.nodes.get(border.container)
+        else {
+            return;
+        };
+        let (Some(&before), Some(&after)) = (
+            container.children.get(border.index),
+            container.children.get(border.index + 1),
+        ) else {
+            return;
+        };
+        let (before, after) = (self.node_area(before), self.node_area(after));
+        let (start, pos, size, total, min) = match container.layout {
+            Layout::Vertical => (
+                before.x,
+                column,
+                column.saturating_sub(before.x),
+                before.width + after.width,
+                MIN_SPLIT_WIDTH,
+            ),
+            // The statusline of the upper view is moved to the row.
+            Layout::Horizontal => (
+                before.y,
+                row,
+                (row + 1).saturating_sub(before.y),
+                before.height + after.height,
+                MIN_SPLIT_HEIGHT,
+            ),
+        };
+        if total < min * 2 || start > pos {
+            return;
+        }
+        let size = size.clamp(min, total - min);
+
+        let container = self.container_mut(border.container);
+        let weight = container.weights[border.index] + container.weights[border.index + 1];
+        container.weights[border.index] = weight * size as f64 / total as f64;
+        container.weights[border.index + 1] = weight * (total - size) as f64 / total as f64;
+        self.recalculate();
+    }
 }
 
 #[derive(Debug)]
@@ -966,4 +1186,85 @@ fn vsplit_gap_rounding() {
                 .collect::<Vec<_>>()
         );
     }
+
+    #[test]
+    fn resize_split() {
+        let mut tree = Tree::new(Rect::new(0, 0, 81, 24));
+        let mut view = View::new(DocumentId::default(), GutterConfig::default());
+        view.area = Rect::new(0, 0, 81, 24);
+        let left = tree.insert(view);
+        let view = View::new(DocumentId::default(), GutterConfig::default());
+        let right = tree.split(view, Layout::Vertical);
+        let view = View::new(DocumentId::default(), GutterConfig::default());
+        let bottom = tree.split(view, Layout::Horizontal);
+        let widths = |tree: &Tree| (tree.get(left).area.width, tree.get(right).area.width);
+
+        assert_eq!(widths(&tree), (40, 40));
+        assert_eq!(tree.split_border_at(10, 39), None);
+        let border = tree.split_border_at(10, 40).unwrap();
+        tree.resize_split(border, 10, 30);
+        assert_eq!(widths(&tree), (30, 50));
+        assert_eq!(tree.get(bottom).area.width, 50);
+        // Views keep their minimal width.
+        tree.resize_split(border, 10, 78);
+        assert_eq!(widths(&tree), (72, 8));
+
+        // The statusline of the upper view is the border of a horizontal split.
+        assert_eq!(tree.get(right).area.height, 12);
+        let border = tree.split_border_at(11, 75).unwrap();
+        tree.resize_split(border, 5, 75);
+        assert_eq!(tree.get(right).area.height, 6);
+        assert_eq!(tree.get(bottom).area, Rect::new(73, 6, 8, 18));
+
+        // Removing a view gives its space to the others.
+        tree.remove(right);
+        assert_eq!(tree.get(bottom).area, Rect::new(73, 0, 8, 24));
+    }
+
+    #[test]
+    fn split_weights() {
+        let mut tree = Tree::new(Rect::new(0, 0, 81, 24));
+        let mut view = View::new(DocumentId::default(), GutterConfig::default());
+        view.area = Rect::new(0, 0, 81, 24);
+        let left = tree.insert(view);
+        let view = View::new(DocumentId::default(), GutterConfig::default());
+        let right = tree.split(view, Layout::Vertical);
+
+        tree.set_split_weights(left, &[1.0, 3.0]);
+        assert_eq!(tree.get(left).area.width, 20);
+        assert_eq!(tree.get(right).area.width, 60);
+        let LayoutNode::Split { weights, .. } = tree.layout_node(|_| None) else {
+            panic!("expected a split");
+        };
+        assert_eq!(weights, [1.0, 3.0]);
+
+        // Weights which don't fit the split are ignored.
+        tree.set_split_weights(left, &[1.0]);
+        tree.set_split_weights(left, &[1.0, 0.0]);
+        assert_eq!(tree.get(left).area.width, 20);
+    }
+
+    #[test]
+    fn zoom() {
+        let mut tree = Tree::new(Rect::new(0, 0, 180, 80));
+        let mut view = View::new(DocumentId::default(), GutterConfig::default());
+        view.area = Rect::new(0, 0, 180, 80);
+        let left = tree.insert(view);
+        let view = View::new(DocumentId::default(), GutterConfig::default());
+        let right = tree.split(view, Layout::Vertical);
+
+        tree.set_zoom(Some(100));
+        assert_eq!(tree.get(right).area, Rect::new(40, 0, 100, 80));
+        assert_eq!(tree.get(left).area, Rect::new(0, 0, 90, 80));
+        let visible: Vec<_> = tree.visible_views().map(|(view, _)| view.id).collect();
+        assert_eq!(visible, [right]);
+
+        tree.focus = left;
+        tree.resize(Rect::new(0, 0, 80, 80));
+        assert_eq!(tree.get(left).area, Rect::new(0, 0, 80, 80));
+
+        tree.set_zoom(None);
+        assert_eq!(tree.get(left).area, Rect::new(0, 0, 40, 80));
+        assert_eq!(tree.visible_views().count(), 2);
+    }
 }
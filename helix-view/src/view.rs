@@ -20,23 +20,51 @@
 use std::{
     collections::{HashMap, VecDeque},
     fmt,
+    path::PathBuf,
+    time::{Duration, Instant},
 };
 
 const JUMP_LIST_CAPACITY: usize = 30;
 
+/// The number of lines shown by each row of the minimap.
+pub const MINIMAP_LINES_PER_ROW: usize = 4;
+
 type Jump = (DocumentId, Selection);
 
+/// A smooth scroll of a view between two top lines of its document.
+#[derive(Debug, Clone, Copy)]
+struct ScrollAnimation {
+    from: usize,
+    to: usize,
+    start: Instant,
+    last_frame: Instant,
+}
+
+/// A jump into a file which isn't open, such as one of a previous session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedJump {
+    pub path: PathBuf,
+    pub selection: Selection,
+}
+
 #[derive(Debug, Clone)]
 pub struct JumpList {
     jumps: VecDeque<Jump>,
     current: usize,
+    /// Jumps into files which aren't open, from the oldest. They come before the other jumps and
+    /// their files are only opened once they're jumped back to, see [JumpList::take_saved].
+    saved: Vec<SavedJump>,
 }
 
 impl JumpList {
     pub fn new(initial: Jump) -> Self {
         let mut jumps = VecDeque::with_capacity(JUMP_LIST_CAPACITY);
         jumps.push_back(initial);
-        Self { jumps, current: 0 }
+        Self {
+            jumps,
+            current: 0,
+            saved: Vec::new(),
+        }
     }
 
     fn push_impl(&mut self, jump: Jump) -> usize {
@@ -45,9 +73,13 @@ fn push_impl(&mut self, jump: Jump) -> usize {
         // don't push duplicates
         if self.jumps.back() != Some(&jump) {
             // If the jumplist is full, drop the oldest item.
-            while self.jumps.len() >= JUMP_LIST_CAPACITY {
-                self.jumps.pop_front();
-                num_removed_from_front += 1;
+            while self.jumps.len() + self.saved.len() >= JUMP_LIST_CAPACITY {
+                if self.saved.is_empty() {
+                    self.jumps.pop_front();
+                    num_removed_from_front += 1;
+                } else {
+                    self.saved.remove(0);
+                }
             }
 
             self.jumps.push_back(jump);
@@ -92,6 +124,40 @@ pub fn backward(&mut self, view_id: ViewId, doc: &mut Document, count: usize) ->
         }
     }
 
+    /// Inserts older jumps, such as the ones of a previous session, before the existing ones.
+    /// The oldest are dropped if they don't fit.
+    pub fn prepend(&mut self, jumps: Vec<Jump>) {
+        let count = jumps
+            .len()
+            .min(JUMP_LIST_CAPACITY.saturating_sub(self.jumps.len() + self.saved.len()));
+        for jump in jumps.into_iter().rev().take(count) {
+            self.jumps.push_front(jump);
+        }
+        self.current += count;
+    }
+
+    /// Inserts jumps into files which aren't open before the existing ones. The oldest are dropped
+    /// if they don't fit.
+    pub fn prepend_saved(&mut self, mut jumps: Vec<SavedJump>) {
+        let count = jumps
+            .len()
+            .min(JUMP_LIST_CAPACITY.saturating_sub(self.jumps.len() + self.saved.len()));
+        jumps.drain(..jumps.len() - count);
+        self.saved.splice(0..0, jumps);
+    }
+
+    /// Takes the saved jumps which are needed to jump back `count` times, from the oldest. Their
+    /// files should be opened and the jumps [prepended](JumpList::prepend).
+    pub fn take_saved(&mut self, count: usize) -> Vec<SavedJump> {
+        let needed = count.saturating_sub(self.current).min(self.saved.len());
+        self.saved.split_off(self.saved.len() - needed)
+    }
+
+    /// The jumps into files which aren't open, from the oldest.
+    pub fn saved(&self) -> &[SavedJump] {
+        &self.saved
+    }
+
     pub fn remove(&mut self, doc_id: &DocumentId) {
         self.jumps.retain(|(other_id, _)| other_id != doc_id);
     }
@@ -154,6 +220,11 @@ pub struct View {
     // left to future work. For now we treat all views as focused and give them
     // each their own handler.
     pub diagnostics_handler: DiagnosticsHandler,
+    /// The document and top line rendered last, used to detect jumps to animate.
+    rendered_top_line: Option<(DocumentId, usize)>,
+    scroll_animation: Option<ScrollAnimation>,
+    /// Set once the terminal rendered the frames of an animation too slowly.
+    smooth_scroll_disabled: bool,
 }
 
 impl fmt::Debug for View {
@@ -179,6 +250,9 @@ pub fn new(doc: DocumentId, gutters: GutterConfig) -> Self {
             gutters,
             doc_revisions: HashMap::new(),
             diagnostics_handler: DiagnosticsHandler::new(),
+            rendered_top_line: None,
+            scroll_animation: None,
+            smooth_scroll_disabled: false,
         }
     }
 
@@ -190,7 +264,10 @@ pub fn add_to_history(&mut self, id: DocumentId) {
     }
 
     pub fn inner_area(&self, doc: &Document) -> Rect {
-        self.area.clip_left(self.gutter_offset(doc)).clip_bottom(1) // -1 for statusline
+        self.area
+            .clip_left(self.gutter_offset(doc))
+            .clip_right(self.minimap_width(doc) + self.scrollbar_width(doc))
+            .clip_bottom(1) // -1 for statusline
     }
 
     pub fn inner_height(&self) -> usize {
@@ -198,7 +275,191 @@ pub fn inner_height(&self) -> usize {
     }
 
     pub fn inner_width(&self, doc: &Document) -> u16 {
-        self.area.clip_left(self.gutter_offset(doc)).width
+        self.area
+            .clip_left(self.gutter_offset(doc))
+            .clip_right(self.minimap_width(doc) + self.scrollbar_width(doc))
+            .width
+    }
+
+    /// The width of the minimap, which is hidden unless the text keeps at least three times
+    /// its width.
+    fn minimap_width(&self, doc: &Document) -> u16 {
+        let width = doc.minimap_width();
+        let text_width = self
+            .area
+            .width
+            .saturating_sub(self.gutter_offset(doc) + self.scrollbar_width(doc));
+        if width.saturating_mul(4) <= text_width {
+            width
+        } else {
+            0
+        }
+    }
+
+    /// The columns on the right of the view which show the minimap.
+    pub fn minimap_area(&self, doc: &Document) -> Rect {
+        let width = self.minimap_width(doc);
+        Rect::new(
+            self.area.right() - self.scrollbar_width(doc) - width,
+            self.area.y,
+            width,
+            self.area.height.saturating_sub(1), // -1 for statusline
+        )
+    }
+
+    /// The first line shown in the minimap. The minimap scrolls so that the lines in the
+    /// viewport stay in the middle of it.
+    pub fn minimap_first_line(&self, doc: &Document) -> usize {
+        let rows = self.minimap_area(doc).height as usize;
+        let total_rows = doc.text().len_lines().div_ceil(MINIMAP_LINES_PER_ROW);
+        let viewport_row = doc
+            .text()
+            .char_to_line(doc.view_offset(self.id).anchor.min(doc.text().len_chars()))
+            / MINIMAP_LINES_PER_ROW;
+        let viewport_rows = self.inner_height().div_ceil(MINIMAP_LINES_PER_ROW);
+        let first_row = viewport_row
+            .saturating_sub(rows.saturating_sub(viewport_rows) / 2)
+            .min(total_rows.saturating_sub(rows));
+        first_row * MINIMAP_LINES_PER_ROW
+    }
+
+    /// The first line shown by the minimap row at the screen coordinates, if they are in the
+    /// minimap.
+    pub fn minimap_line_at_screen_coords(
+        &self,
+        doc: &Document,
+        row: u16,
+        column: u16,
+    ) -> Option<usize> {
+        let area = self.minimap_area(doc);
+        if area.width == 0
+            || !(area.left()..area.right()).contains(&column)
+            || !(area.top()..area.bottom()).contains(&row)
+        {
+            return None;
+        }
+        let line =
+            self.minimap_first_line(doc) + (row - area.top()) as usize * MINIMAP_LINES_PER_ROW;
+        Some(line.min(doc.text().len_lines().saturating_sub(1)))
+    }
+
+    fn scrollbar_width(&self, doc: &Document) -> u16 {
+        if doc.config.load().scrollbar && self.area.width > 1 {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// The rightmost column of the view which shows the scrollbar.
+    pub fn scrollbar_area(&self, doc: &Document) -> Rect {
+        let width = self.scrollbar_width(doc);
+        Rect::new(
+            self.area.right() - width,
+            self.area.y,
+            width,
+            self.area.height.saturating_sub(1), // -1 for statusline
+        )
+    }
+
+    /// The scrollbar row showing the line. Each row of the scrollbar stands for the same share
+    /// of the document.
+    pub fn scrollbar_row(&self, doc: &Document, line: usize) -> u16 {
+        let height = self.scrollbar_area(doc).height as usize;
+        let len_lines = doc.text().len_lines().max(1);
+        (line.min(len_lines) * height / len_lines).min(height.saturating_sub(1)) as u16
+    }
+
+    /// The first line of the document shown by the scrollbar row at the screen row. Rows
+    /// outside of the scrollbar are clamped to it.
+    pub fn scrollbar_line_at_screen_row(&self, doc: &Document, row: u16) -> usize {
+        let area = self.scrollbar_area(doc);
+        let row = row.clamp(area.top(), area.bottom().saturating_sub(1)) - area.top();
+        let len_lines = doc.text().len_lines();
+        (row as usize * len_lines)
+            .div_ceil(area.height.max(1) as usize)
+            .min(len_lines.saturating_sub(1))
+    }
+
+    /// Whether the screen coordinates are on the scrollbar.
+    pub fn is_scrollbar_at_screen_coords(&self, doc: &Document, row: u16, column: u16) -> bool {
+        let area = self.scrollbar_area(doc);
+        area.width != 0
+            && (area.left()..area.right()).contains(&column)
+            && (area.top()..area.bottom()).contains(&row)
+    }
+
+    /// The number of document lines which fill the viewport, including the lines hidden by
+    /// folds.
+    pub fn viewport_doc_lines(&self, doc: &Document) -> usize {
+        let text = doc.text().slice(..);
+        let top = text.char_to_line(doc.view_offset(self.id).anchor.min(text.len_chars()));
+        let mut end = top + self.inner_height();
+        let mut hidden_until = top;
+        for fold in doc.folds(self.id) {
+            if fold.start >= end {
+                break;
+            }
+            let first_hidden = (fold.start + 1).max(hidden_until);
+            if fold.end >= first_hidden {
+                end += fold.end + 1 - first_hidden;
+                hidden_until = fold.end + 1;
+            }
+        }
+        end - top
+    }
+
+    /// Returns the top line to render while smoothly scrolling towards the top line of the
+    /// viewport, or `None` once it is reached. Jumps over more than a quarter of the viewport
+    /// are animated over `duration`. Animations are disabled for the view when its frames are
+    /// rendered too slowly to show them.
+    pub fn smooth_scroll_line(
+        &mut self,
+        doc: &Document,
+        duration: Duration,
+        now: Instant,
+    ) -> Option<usize> {
+        let text = doc.text().slice(..);
+        let target = text.char_to_line(doc.view_offset(self.id).anchor.min(text.len_chars()));
+        let last = match self.rendered_top_line {
+            Some((doc_id, line)) if doc_id == doc.id() => line,
+            _ => target,
+        };
+        let threshold = (self.inner_height() / 4).max(3);
+
+        let animation = match self.scroll_animation.take() {
+            Some(animation) if animation.to == target => Some(animation),
+            _ if !self.smooth_scroll_disabled && last.abs_diff(target) > threshold => {
+                Some(ScrollAnimation {
+                    from: last,
+                    to: target,
+                    start: now,
+                    last_frame: now,
+                })
+            }
+            _ => None,
+        };
+        let line = animation.and_then(|mut animation| {
+            let elapsed = now.saturating_duration_since(animation.start);
+            if elapsed >= duration {
+                return None;
+            }
+            if now.saturating_duration_since(animation.last_frame) > duration / 2 {
+                self.smooth_scroll_disabled = true;
+                return None;
+            }
+            animation.last_frame = now;
+            self.scroll_animation = Some(animation);
+
+            // Ease out so that the scroll slows down when reaching the viewport.
+            let progress = elapsed.as_secs_f64() / duration.as_secs_f64();
+            let eased = 1.0 - (1.0 - progress).powi(3);
+            let distance = animation.to as f64 - animation.from as f64;
+            Some((animation.from as f64 + distance * eased).round() as usize)
+        });
+
+        self.rendered_top_line = Some((doc.id(), line.unwrap_or(target)));
+        line
     }
 
     pub fn gutters(&self) -> &[GutterType] {
@@ -491,6 +752,11 @@ pub fn text_annotations<'a>(
             }
         }
 
+        let fold_style = theme.and_then(|t| t.find_highlight("ui.virtual.fold"));
+        text_annotations
+            .add_inline_annotations(doc.fold_markers(self.id), fold_style)
+            .add_folds(doc.folds(self.id));
+
         let width = self.inner_width(doc);
         let enable_cursor_line = self
             .diagnostics_handler
@@ -1092,4 +1358,162 @@ fn test_text_pos_at_screen_coords_graphemes() {
             Some(7)
         );
     }
+
+    #[test]
+    fn test_minimap_line_at_screen_coords() {
+        let mut view = View::new(DocumentId::default(), GutterConfig::default());
+        view.area = Rect::new(40, 40, 60, 40);
+        let text = "line\n".repeat(100);
+        let mut config = Config::default();
+        config.minimap.enable = true;
+        let mut doc = Document::from(
+            Rope::from_str(&text),
+            None,
+            Arc::new(ArcSwap::new(Arc::new(config))),
+            Arc::new(ArcSwap::from_pointee(syntax::Loader::default())),
+        );
+        doc.ensure_view_init(view.id);
+
+        let minimap = view.minimap_area(&doc);
+        assert_eq!(minimap, Rect::new(90, 40, 10, 39));
+        assert_eq!(view.inner_area(&doc).right(), 90);
+        assert_eq!(view.minimap_line_at_screen_coords(&doc, 40, 89), None);
+        assert_eq!(view.minimap_line_at_screen_coords(&doc, 40, 90), Some(0));
+        assert_eq!(view.minimap_line_at_screen_coords(&doc, 42, 95), Some(8));
+        // Rows past the end of the document select the last line.
+        assert_eq!(view.minimap_line_at_screen_coords(&doc, 78, 95), Some(100));
+
+        // The minimap is hidden when the view is too narrow.
+        view.area = Rect::new(40, 40, 40, 40);
+        assert_eq!(view.minimap_area(&doc).width, 0);
+        assert_eq!(view.minimap_line_at_screen_coords(&doc, 40, 79), None);
+    }
+
+    #[test]
+    fn test_scrollbar_rows() {
+        let mut view = View::new(DocumentId::default(), GutterConfig::default());
+        view.area = Rect::new(0, 0, 40, 11);
+        let text = "line\n".repeat(99);
+        let config = Config {
+            scrollbar: true,
+            ..Default::default()
+        };
+        let mut doc = Document::from(
+            Rope::from_str(&text),
+            None,
+            Arc::new(ArcSwap::new(Arc::new(config))),
+            Arc::new(ArcSwap::from_pointee(syntax::Loader::default())),
+        );
+        doc.ensure_view_init(view.id);
+
+        assert_eq!(view.scrollbar_area(&doc), Rect::new(39, 0, 1, 10));
+        assert_eq!(view.inner_area(&doc).right(), 39);
+        assert!(view.is_scrollbar_at_screen_coords(&doc, 0, 39));
+        assert!(!view.is_scrollbar_at_screen_coords(&doc, 10, 39));
+        assert!(!view.is_scrollbar_at_screen_coords(&doc, 0, 38));
+
+        assert_eq!(view.scrollbar_row(&doc, 0), 0);
+        assert_eq!(view.scrollbar_row(&doc, 9), 0);
+        assert_eq!(view.scrollbar_row(&doc, 10), 1);
+        assert_eq!(view.scrollbar_row(&doc, 99), 9);
+        assert_eq!(view.scrollbar_line_at_screen_row(&doc, 0), 0);
+        assert_eq!(view.scrollbar_line_at_screen_row(&doc, 5), 50);
+        // Rows below the scrollbar are clamped to it while dragging.
+        assert_eq!(view.scrollbar_line_at_screen_row(&doc, 20), 90);
+    }
+
+    #[test]
+    fn test_smooth_scroll_line() {
+        let mut view = View::new(DocumentId::default(), GutterConfig::default());
+        view.area = Rect::new(0, 0, 40, 21);
+        let text = "line\n".repeat(200);
+        let mut doc = Document::from(
+            Rope::from_str(&text),
+            None,
+            Arc::new(ArcSwap::new(Arc::new(Config::default()))),
+            Arc::new(ArcSwap::from_pointee(syntax::Loader::default())),
+        );
+        doc.ensure_view_init(view.id);
+        let duration = Duration::from_millis(100);
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let view_id = view.id;
+        let scroll_to = |doc: &mut Document, line| {
+            let anchor = doc.text().line_to_char(line);
+            doc.set_view_offset(
+                view_id,
+                ViewPosition {
+                    anchor,
+                    ..ViewPosition::default()
+                },
+            );
+        };
+
+        assert_eq!(view.smooth_scroll_line(&doc, duration, at(0)), None);
+        // Scrolling a few lines isn't animated.
+        scroll_to(&mut doc, 3);
+        assert_eq!(view.smooth_scroll_line(&doc, duration, at(0)), None);
+
+        scroll_to(&mut doc, 103);
+        assert_eq!(view.smooth_scroll_line(&doc, duration, at(0)), Some(3));
+        assert_eq!(view.smooth_scroll_line(&doc, duration, at(20)), Some(52));
+        assert_eq!(view.smooth_scroll_line(&doc, duration, at(40)), Some(81));
+        assert_eq!(view.smooth_scroll_line(&doc, duration, at(100)), None);
+
+        // A frame taking longer than half of the animation disables the animations.
+        scroll_to(&mut doc, 3);
+        assert_eq!(view.smooth_scroll_line(&doc, duration, at(100)), Some(103));
+        assert_eq!(view.smooth_scroll_line(&doc, duration, at(160)), None);
+        scroll_to(&mut doc, 103);
+        assert_eq!(view.smooth_scroll_line(&doc, duration, at(200)), None);
+    }
+
+    #[test]
+    fn jumplist_prepend() {
+        let doc = DocumentId::default();
+        let jump = |pos| (doc, Selection::point(pos));
+        let mut jumps = JumpList::new(jump(0));
+        jumps.prepend((2..40).map(jump).collect());
+        let positions: Vec<_> = jumps
+            .iter()
+            .map(|(_, selection)| selection.primary().head)
+            .collect();
+        // The newest of the prepended jumps are kept.
+        assert_eq!(positions.len(), JUMP_LIST_CAPACITY);
+        assert_eq!(positions[..2], [11, 12]);
+        assert_eq!(positions[JUMP_LIST_CAPACITY - 2..], [39, 0]);
+        // The current jump stays the same.
+        assert_eq!(jumps.current, JUMP_LIST_CAPACITY - 1);
+    }
+
+    #[test]
+    fn jumplist_saved() {
+        let doc = DocumentId::default();
+        let jump = |pos| (doc, Selection::point(pos));
+        let saved = |pos| SavedJump {
+            path: PathBuf::from(format!("{pos}")),
+            selection: Selection::point(pos),
+        };
+        let mut jumps = JumpList::new(jump(0));
+        jumps.prepend_saved((1..40).map(saved).collect());
+        // The newest of the saved jumps are kept.
+        assert_eq!(jumps.saved().len(), JUMP_LIST_CAPACITY - 1);
+        assert_eq!(jumps.saved()[0], saved(11));
+
+        // Jumping back within the other jumps doesn't need the saved ones.
+        jumps.push(jump(40));
+        assert!(jumps.take_saved(1).is_empty());
+        let taken = jumps.take_saved(3);
+        assert_eq!(taken, [saved(38), saved(39)]);
+        jumps.prepend(vec![jump(38), jump(39)]);
+        assert_eq!(jumps.current, 3);
+        assert_eq!(jumps.saved().len(), JUMP_LIST_CAPACITY - 3);
+
+        // New jumps drop the oldest saved jumps first.
+        jumps.push(jump(41));
+        jumps.push(jump(42));
+        assert_eq!(jumps.iter().count(), 5);
+        assert_eq!(jumps.saved().len(), JUMP_LIST_CAPACITY - 5);
+        assert_eq!(jumps.saved()[0], saved(13));
+    }
 }